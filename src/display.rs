@@ -1,7 +1,16 @@
-use crate::onnx_rustime::backend::helper::find_top_5_peak_classes;
-use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
-use crate::onnx_rustime::ops::utils::tensor_proto_to_ndarray;
-use crate::onnx_rustime::shared::{Model, IMAGENET_CLASSES, MNIST_CLASSES, MODEL_NAME, VERBOSE};
+use crate::onnx_rustime::backend::evaluation::EvaluationReport;
+use crate::onnx_rustime::backend::format::FormatOptions;
+use crate::onnx_rustime::backend::helper::{find_top_5_peak_classes, OnnxError};
+use crate::onnx_rustime::backend::tensor::Tensor;
+use crate::onnx_rustime::shared::{
+    Model, NonFiniteCheck, RunOptions, IMAGENET_CLASSES, MNIST_CLASSES,
+};
+use crate::onnx_rustime::testing::{
+    CaseStatus, ConformanceReport, DeterminismReport, GoldenResult, GoldenStatus,
+};
+use crate::onnx_rustime::transforms::{
+    ModelSizeReport, OperatorStatus, OptLevel, OptimizationReport, OpsReport,
+};
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Input, Select};
 use std::path::Path;
@@ -16,14 +25,26 @@ const RUST_COLOR: &[u8] = &[209, 114, 119];
 /// 2. Ask the user to select a network.
 /// 3. Ask if the user wants to save the output data.
 /// 4. Ask for the path to save the output data.
-/// 5. Ask if the user wants to run in verbose mode.
+/// 5. Ask if the user wants to run in verbose mode, and to print the tensors in full.
+/// 6. Ask whether to check the node outputs for NaN/Inf and to use the reference kernels.
+/// 7. Ask for the graph optimization level.
 ///
 /// Returns a tuple containing:
 /// - model_path: Path to the selected ONNX model.
 /// - input_path: Path to the input test data for the selected model.
 /// - ground_truth_output_path: Path to the expected output test data for the selected model.
 /// - save_path: Optional path where the user wants to save the output data.
-pub fn menu() -> (String, String, Option<String>, Option<String>) {
+/// - opt_level: Optimization level applied to the model before running it.
+/// - run_options: Options of the run (verbosity, tensor printing, checks, kernels and label set
+///   of the model).
+pub fn menu() -> (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    OptLevel,
+    RunOptions,
+) {
     display_menu();
 
     let options = vec![
@@ -178,6 +199,8 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
         None
     };
 
+    let mut run_options = RunOptions::default();
+
     if options[selection] != "Pre-process and serialize an image" {
         // Ask if the user wants to run in verbose mode
         let verbose_selection = match Select::with_theme(&ColorfulTheme::default())
@@ -196,18 +219,92 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             _ => false,
         };
 
+        run_options.verbose = verbose_selection;
+
+        // Ask whether the tensors should be printed in full, summarized by default
+        let full_selection = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Print tensors in full?")
+            .items(&["No", "Yes", "Back"])
+            .default(0)
+            .interact()
+            .unwrap()
         {
-            let mut v = VERBOSE.lock().unwrap();
-            *v = verbose_selection;
-        }
+            0 => false,
+            1 => true,
+            2 => {
+                clear_screen();
+                return menu();
+            }
+            _ => false,
+        };
+
+        run_options.tensor_format.full = full_selection;
+
+        // Ask whether the node outputs should be scanned for NaN/Inf, off by default for speed
+        let check_selection = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Check node outputs for NaN/Inf?")
+            .items(&["No", "Warn and continue", "Abort", "Back"])
+            .default(0)
+            .interact()
+            .unwrap()
+        {
+            0 => NonFiniteCheck::Off,
+            1 => NonFiniteCheck::Warn,
+            2 => NonFiniteCheck::Abort,
+            3 => {
+                clear_screen();
+                return menu();
+            }
+            _ => NonFiniteCheck::Off,
+        };
+
+        run_options.non_finite_check = check_selection;
+
+        // Ask whether the naive reference kernels should replace the optimized ones (slow)
+        let reference_selection = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Use the reference kernels? (slow, for debugging)")
+            .items(&["No", "Yes", "Back"])
+            .default(0)
+            .interact()
+            .unwrap()
+        {
+            0 => false,
+            1 => true,
+            2 => {
+                clear_screen();
+                return menu();
+            }
+            _ => false,
+        };
+
+        run_options.reference_kernels = reference_selection;
+    }
+
+    let mut opt_level = OptLevel::default();
+
+    if options[selection] != "Pre-process and serialize an image" {
+        // Ask for the optimization level, Basic being the default
+        opt_level = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Graph optimization level?")
+            .items(&["Basic", "Extended", "None", "Back"])
+            .default(0)
+            .interact()
+            .unwrap()
+        {
+            0 => OptLevel::Basic,
+            1 => OptLevel::Extended,
+            2 => OptLevel::None,
+            3 => {
+                clear_screen();
+                return menu();
+            }
+            _ => OptLevel::default(),
+        };
     }
 
     let (model_path, output_path) = match options[selection] {
         "AlexNet" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::AlexNet;
-            }
+            run_options.model = Model::AlexNet;
             (
                 "models/bvlcalexnet-12/bvlcalexnet-12.onnx",
                 if input_path == default_input_paths[selection] {
@@ -218,10 +315,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "CaffeNet" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::CaffeNet;
-            }
+            run_options.model = Model::CaffeNet;
             (
                 "models/caffenet-12/caffenet-12.onnx",
                 if input_path == default_input_paths[selection] {
@@ -232,10 +326,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "CNN-Mnist" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::Mnist;
-            }
+            run_options.model = Model::Mnist;
             (
                 "models/mnist-8/mnist-8.onnx",
                 if input_path == default_input_paths[selection] {
@@ -246,10 +337,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "ResNet-152" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::ResNet;
-            }
+            run_options.model = Model::ResNet;
             (
                 "models/resnet152-v2-7/resnet152-v2-7.onnx",
                 if input_path == default_input_paths[selection] {
@@ -260,10 +348,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "SqueezeNet" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::SqueezeNet;
-            }
+            run_options.model = Model::SqueezeNet;
             (
                 "models/squeezenet1.0-12/squeezenet1.0-12.onnx",
                 if input_path == default_input_paths[selection] {
@@ -274,10 +359,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "ZFNet" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::ZFNet;
-            }
+            run_options.model = Model::ZFNet;
             (
                 "models/zfnet512-12/zfnet512-12.onnx",
                 if input_path == default_input_paths[selection] {
@@ -288,10 +370,7 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
             )
         }
         "Pre-process and serialize an image" => {
-            {
-                let mut d = MODEL_NAME.lock().unwrap();
-                *d = Model::PreProcessing;
-            }
+            run_options.model = Model::PreProcessing;
             ("PREPROCESSING", None)
         }
         _ => {
@@ -302,7 +381,14 @@ pub fn menu() -> (String, String, Option<String>, Option<String>) {
 
     println!("{}", "\n🦀 LOADING...\n".green().bold());
 
-    (model_path.to_string(), input_path, output_path, save_path)
+    (
+        model_path.to_string(),
+        input_path,
+        output_path,
+        save_path,
+        opt_level,
+        run_options,
+    )
 }
 
 fn display_menu() {
@@ -364,19 +450,59 @@ fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
     }
 }
 
-pub fn display_outputs(predicted: &TensorProto, expected: Option<TensorProto>) {
-    let name_column_width = 35; // Fixed width
-    let model_name = {
-        let lock = MODEL_NAME.lock().unwrap();
-        lock.clone()
+/// A class of an output: its index, its name and its probability.
+type PeakClass = (usize, &'static str, f32);
+
+/// The five most probable classes of every batch of an output.
+fn top_5_classes(output: &Tensor, model: &Model) -> Result<Vec<Vec<PeakClass>>, OnnxError> {
+    let values = output.to_ndarray::<f32>()?;
+    let not_scores = || {
+        OnnxError::InvalidValue(format!(
+            "The output of shape {:?} doesn't hold class scores for the {:?} labels",
+            values.shape(),
+            model
+        ))
     };
+    if values.is_empty() {
+        return Err(not_scores());
+    }
+    let classes: &[&'static str] = match model {
+        Model::Mnist => &MNIST_CLASSES,
+        _ => &IMAGENET_CLASSES,
+    };
+
+    find_top_5_peak_classes(&values, model)
+        .ok_or_else(not_scores)?
+        .into_iter()
+        .map(|top_5| {
+            top_5
+                .into_iter()
+                .map(|(peak, value)| Ok((peak, *classes.get(peak).ok_or_else(not_scores)?, value)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Prints the predicted output, and the expected one if given, with their five most probable
+/// classes. Fails if an output doesn't hold `f32` scores for the labels of `options.model`.
+pub fn display_outputs(
+    predicted: &Tensor,
+    expected: Option<Tensor>,
+    options: &RunOptions,
+) -> Result<(), OnnxError> {
+    let name_column_width = 35; // Fixed width
+    let model_name = &options.model;
 
-    let predicted_output = tensor_proto_to_ndarray::<f32>(predicted).unwrap();
+    // The values, summarized unless `options.tensor_format.full`, after their statistics.
+    let format_options = FormatOptions {
+        stats: true,
+        ..options.tensor_format.clone()
+    };
 
     println!("{}", "Predicted Output:".bold().magenta());
-    println!("{:?}\n", predicted_output);
+    println!("{}\n", predicted.format(&format_options));
 
-    let predicted_top_5 = find_top_5_peak_classes(&predicted_output).unwrap();
+    let predicted_top_5 = top_5_classes(predicted, model_name)?;
     for (batch_index, top_5) in predicted_top_5.iter().enumerate() {
         println!("[Batch {}]\n", batch_index);
         println!(
@@ -392,11 +518,7 @@ pub fn display_outputs(predicted: &TensorProto, expected: Option<TensorProto>) {
             "-".repeat(name_column_width).bold().magenta(),
             "------------------".bold().magenta()
         );
-        for &(peak, value) in top_5.iter() {
-            let class_name = match model_name {
-                Model::Mnist => MNIST_CLASSES[peak],
-                _ => IMAGENET_CLASSES[peak],
-            };
+        for &(peak, class_name, value) in top_5.iter() {
             let truncated_class_name = truncate_with_ellipsis(class_name, name_column_width);
             let percentage = value * 100.0;
             println!(
@@ -412,10 +534,9 @@ pub fn display_outputs(predicted: &TensorProto, expected: Option<TensorProto>) {
     if let Some(expected_tensor) = expected {
         println!("{}", "\nExpected Output:".bold().blue());
 
-        let expected_output = tensor_proto_to_ndarray::<f32>(&expected_tensor).unwrap();
-        println!("{:?}\n", expected_output);
+        println!("{}\n", expected_tensor.format(&format_options));
 
-        let expected_top_5 = find_top_5_peak_classes(&expected_output).unwrap();
+        let expected_top_5 = top_5_classes(&expected_tensor, model_name)?;
         for (batch_index, top_5) in expected_top_5.iter().enumerate() {
             println!("[Batch {}]\n", batch_index);
             println!(
@@ -432,11 +553,7 @@ pub fn display_outputs(predicted: &TensorProto, expected: Option<TensorProto>) {
                 "------------------".bold().blue()
             );
 
-            for &(peak, value) in top_5.iter() {
-                let class_name = match model_name {
-                    Model::Mnist => MNIST_CLASSES[peak],
-                    _ => IMAGENET_CLASSES[peak],
-                };
+            for &(peak, class_name, value) in top_5.iter() {
                 let truncated_class_name = truncate_with_ellipsis(class_name, name_column_width);
                 let percentage = value * 100.0;
                 println!(
@@ -450,4 +567,398 @@ pub fn display_outputs(predicted: &TensorProto, expected: Option<TensorProto>) {
         }
     }
     println!("\n");
+    Ok(())
+}
+
+pub fn display_optimization_report(report: &OptimizationReport) {
+    let name_column_width = 35; // Fixed width
+
+    println!(
+        "{} {} ({} → {} nodes, {} iterations)\n",
+        "🔧 Optimization level:".bold().cyan(),
+        report.level,
+        report.nodes_before,
+        report.nodes_after,
+        report.iterations
+    );
+
+    if report.passes.is_empty() {
+        return;
+    }
+
+    println!(
+        "{:<width$} {:<10} {:<10} {:<14} {}",
+        "Pass".bold().cyan(),
+        "Removed".bold().cyan(),
+        "Added".bold().cyan(),
+        "Bytes saved".bold().cyan(),
+        "Time".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "----------".bold().cyan(),
+        "----------".bold().cyan(),
+        "--------------".bold().cyan(),
+        "------------------".bold().cyan()
+    );
+    for pass in &report.passes {
+        println!(
+            "{:<width$} {:<10} {:<10} {:<14} {:?}",
+            truncate_with_ellipsis(&pass.name, name_column_width),
+            pass.nodes_removed,
+            pass.nodes_added,
+            pass.bytes_saved,
+            pass.time,
+            width = name_column_width
+        );
+    }
+    println!();
+}
+
+/// Displays the coverage of the operations of a model, unsupported ones first, with the opsets
+/// and input types that differ from the ones handled by the kernels.
+pub fn display_ops_report(report: &OpsReport) {
+    let name_column_width = 35; // Fixed width
+    let opset = |version: Option<i64>| version.map_or("-".to_string(), |v| v.to_string());
+
+    println!(
+        "{:<width$} {:<8} {:<18} {:<14} {}",
+        "Operation".bold().cyan(),
+        "Nodes".bold().cyan(),
+        "Status".bold().cyan(),
+        "Opset (kernel)".bold().cyan(),
+        "Input types".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "--------".bold().cyan(),
+        "------------------".bold().cyan(),
+        "--------------".bold().cyan(),
+        "-".repeat(30).bold().cyan()
+    );
+    for operator in &report.operators {
+        let status = match operator.status {
+            OperatorStatus::Supported => format!("{:<18}", "supported").green(),
+            OperatorStatus::Unsupported => format!("{:<18}", "unsupported").red(),
+            OperatorStatus::OpsetMismatch => format!("{:<18}", "opset mismatch").yellow(),
+            OperatorStatus::UnsupportedTypes => format!("{:<18}", "unsupported types").yellow(),
+        };
+        let opsets = match operator.kernel_opsets {
+            Some((first, last)) => format!(
+                "{} ({}-{})",
+                opset(operator.requested_opset),
+                first,
+                last
+            ),
+            None => opset(operator.requested_opset),
+        };
+
+        let mut types: Vec<String> = operator
+            .input_types
+            .iter()
+            .map(|data_type| {
+                if operator.kernel_types.is_empty() || operator.kernel_types.contains(data_type) {
+                    data_type.clone()
+                } else {
+                    data_type.red().to_string()
+                }
+            })
+            .collect();
+        if operator.unknown_inputs > 0 {
+            types.push(format!("{} unknown", operator.unknown_inputs).dimmed().to_string());
+        }
+
+        println!(
+            "{:<width$} {:<8} {} {:<14} {}",
+            truncate_with_ellipsis(&operator.op_type, name_column_width),
+            operator.count,
+            status,
+            opsets,
+            types.join(", "),
+            width = name_column_width
+        );
+    }
+
+    let missing: Vec<_> = report
+        .operators
+        .iter()
+        .filter(|operator| operator.status != OperatorStatus::Supported)
+        .collect();
+    println!(
+        "\n{} {} of {} operations supported, {} nodes to cover\n",
+        "🧩 Operators:".bold().cyan(),
+        (report.operators.len() - missing.len()).to_string().green(),
+        report.operators.len(),
+        missing
+            .iter()
+            .map(|operator| operator.count)
+            .sum::<usize>()
+            .to_string()
+            .red()
+    );
+}
+
+pub fn display_model_size_report(report: &ModelSizeReport) {
+    let name_column_width = 35; // Fixed width
+    let megabytes = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+
+    println!(
+        "{} {:.2} MB ({} initializers, {:.2} MB)\n",
+        "📦 Model size:".bold().cyan(),
+        megabytes(report.model_bytes),
+        report.initializers.len(),
+        megabytes(report.initializer_bytes)
+    );
+
+    if report.initializers.is_empty() {
+        return;
+    }
+
+    println!(
+        "{:<width$} {:<10} {:<20} {:<14} {}",
+        "Initializer".bold().cyan(),
+        "Type".bold().cyan(),
+        "Shape".bold().cyan(),
+        "Bytes".bold().cyan(),
+        "Share".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "----------".bold().cyan(),
+        "--------------------".bold().cyan(),
+        "--------------".bold().cyan(),
+        "--------".bold().cyan()
+    );
+    for init in &report.initializers {
+        let share = if report.initializer_bytes > 0 {
+            init.bytes as f64 / report.initializer_bytes as f64 * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "{:<width$} {:<10} {:<20} {:<14} {:.2}%",
+            truncate_with_ellipsis(&init.name, name_column_width),
+            init.data_type,
+            format!("{:?}", init.shape),
+            init.bytes,
+            share,
+            width = name_column_width
+        );
+    }
+    println!();
+}
+
+pub fn display_conformance_report(report: &ConformanceReport) {
+    let name_column_width = 35; // Fixed width
+
+    println!(
+        "{:<width$} {:<10} {:<10} {}",
+        "Operation".bold().cyan(),
+        "Passed".bold().cyan(),
+        "Failed".bold().cyan(),
+        "Skipped".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "----------".bold().cyan(),
+        "----------".bold().cyan(),
+        "----------".bold().cyan()
+    );
+    for (op_type, [passed, failed, skipped]) in report.by_op_type() {
+        println!(
+            "{:<width$} {:<10} {:<10} {}",
+            truncate_with_ellipsis(&op_type, name_column_width),
+            passed,
+            failed,
+            skipped,
+            width = name_column_width
+        );
+    }
+
+    let failures: Vec<_> = report
+        .cases
+        .iter()
+        .filter(|case| case.status == CaseStatus::Failed)
+        .collect();
+    if !failures.is_empty() {
+        println!("\n{}", "❌ Failed cases:".bold().red());
+        for case in failures {
+            println!("{} - {}", case.name.bold(), case.message);
+        }
+    }
+
+    println!(
+        "\n{} {} passed, {} failed, {} skipped\n",
+        "🧪 Conformance:".bold().cyan(),
+        report.count(CaseStatus::Passed).to_string().green(),
+        report.count(CaseStatus::Failed).to_string().red(),
+        report.count(CaseStatus::Skipped).to_string().yellow()
+    );
+}
+
+/// Displays the outcome of the golden check of every model, with the first drifted layer of the
+/// failing ones.
+pub fn display_golden_results(results: &[GoldenResult]) {
+    let name_column_width = 35; // Fixed width
+
+    println!(
+        "{:<width$} {:<16} {}",
+        "Model".bold().cyan(),
+        "Status".bold().cyan(),
+        "Output".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "----------------".bold().cyan(),
+        "-".repeat(40).bold().cyan()
+    );
+    for result in results {
+        let status = match result.status {
+            GoldenStatus::Passed => "passed".green(),
+            GoldenStatus::Updated => "updated".yellow(),
+            GoldenStatus::Failed => "failed".red(),
+            GoldenStatus::MissingGolden => "missing golden".yellow(),
+            GoldenStatus::Error(_) => "error".red(),
+        };
+        let output = match (&result.status, &result.output) {
+            (GoldenStatus::Error(message), _) => message.clone(),
+            (_, Some(report)) => report.to_string(),
+            _ => String::new(),
+        };
+        println!(
+            "{:<width$} {:<16} {}",
+            truncate_with_ellipsis(&result.model, name_column_width),
+            status,
+            output,
+            width = name_column_width
+        );
+
+        if let Some(drift) = &result.drift {
+            println!(
+                "  {} layer {} '{}' ({}): mean {:.6e} -> {:.6e}, std {:.6e} -> {:.6e}",
+                "First drift at".bold().red(),
+                drift.index,
+                drift.actual.name,
+                drift.actual.op_type,
+                drift.golden.mean,
+                drift.actual.mean,
+                drift.golden.std,
+                drift.actual.std
+            );
+        }
+    }
+    println!();
+}
+
+/// Displays the accuracy of a classifier, overall and for every class, with the latency summary.
+pub fn display_evaluation_report(report: &EvaluationReport) {
+    let name_column_width = 35; // Fixed width
+
+    println!(
+        "{:<width$} {:<10} {:<10} {}",
+        "Class".bold().cyan(),
+        "Images".bold().cyan(),
+        "Top-1".bold().cyan(),
+        "Top-5".bold().cyan(),
+        width = name_column_width
+    );
+    println!(
+        "{} {} {} {}",
+        "-".repeat(name_column_width).bold().cyan(),
+        "----------".bold().cyan(),
+        "----------".bold().cyan(),
+        "----------".bold().cyan()
+    );
+    for (class, accuracy) in &report.per_class {
+        let name = IMAGENET_CLASSES
+            .get(*class)
+            .map_or(class.to_string(), |name| format!("{} ({})", class, name));
+        println!(
+            "{:<width$} {:<10} {:<10} {:.2}%",
+            truncate_with_ellipsis(&name, name_column_width),
+            accuracy.images,
+            format!(
+                "{:.2}%",
+                accuracy.top1_correct as f64 / accuracy.images as f64 * 100.0
+            ),
+            accuracy.top5_correct as f64 / accuracy.images as f64 * 100.0,
+            width = name_column_width
+        );
+    }
+
+    println!(
+        "\n{} {} images, top-1 {}, top-5 {}",
+        "🎯 Accuracy:".bold().cyan(),
+        report.images,
+        format!("{:.2}%", report.top1_accuracy() * 100.0).green(),
+        format!("{:.2}%", report.top5_accuracy() * 100.0).green()
+    );
+    println!(
+        "{} {} batches, {:.2?} per image, p50 {:.2?}, p95 {:.2?}, max {:.2?} per batch\n",
+        "⏱️  Latency:".bold().cyan(),
+        report.latency.batches,
+        report.latency.mean_per_image,
+        report.latency.p50,
+        report.latency.p95,
+        report.latency.max
+    );
+}
+
+/// Displays the output hashes of every run of a determinism check, and the divergences.
+pub fn display_determinism_report(report: &DeterminismReport) {
+    if !report.seeded_nodes.is_empty() {
+        println!(
+            "{} {}\n",
+            "🎲 Seeded random nodes:".bold().yellow(),
+            report.seeded_nodes.join(", ")
+        );
+    }
+
+    println!(
+        "{:<10} {:<6} {}",
+        "Threads".bold().cyan(),
+        "Run".bold().cyan(),
+        "Output hashes".bold().cyan()
+    );
+    println!(
+        "{} {} {}",
+        "----------".bold().cyan(),
+        "------".bold().cyan(),
+        "-".repeat(40).bold().cyan()
+    );
+    for run in &report.runs {
+        println!(
+            "{:<10} {:<6} {}",
+            run.threads,
+            run.run,
+            run.hashes.join(" ")
+        );
+    }
+
+    if report.is_deterministic() {
+        println!(
+            "\n{} {} runs produced identical outputs\n",
+            "✅ Deterministic:".bold().green(),
+            report.runs.len()
+        );
+    } else {
+        println!("\n{}", "❌ Divergences from the first run:".bold().red());
+        for divergence in &report.divergences {
+            println!(
+                "{} threads, run {}: output '{}' differs, max abs diff {}",
+                divergence.threads, divergence.run, divergence.output, divergence.max_abs_diff
+            );
+        }
+        println!();
+    }
 }
@@ -1,9 +1,12 @@
+#[cfg(feature = "cli")]
 pub mod display;
 pub mod onnx_rustime;
 
 pub use onnx_rustime::backend;
 pub use onnx_rustime::onnx_proto;
 pub use onnx_rustime::ops;
+pub use onnx_rustime::testing;
+pub use onnx_rustime::transforms;
 
 #[cfg(any(feature = "include_pyo3", feature = "include_neon"))]
 mod common {
@@ -11,50 +14,71 @@ mod common {
 
     use once_cell::sync::Lazy;
     use std::collections::HashMap;
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
 
     pub use display::display_outputs;
 
+    pub use onnx_rustime::backend::format::FormatOptions;
     pub use onnx_rustime::backend::parser::OnnxParser;
-    pub use onnx_rustime::backend::run::run;
-    pub use onnx_rustime::onnx_proto::onnx_ml_proto3::ModelProto;
-    pub use onnx_rustime::onnx_proto::onnx_ml_proto3::TensorProto;
-    pub use onnx_rustime::ops::utils::tensor_proto_to_ndarray;
+    pub use onnx_rustime::backend::session::Session;
+    pub use onnx_rustime::backend::tensor::Tensor;
 
     pub use onnx_rustime::shared::Model;
-    pub use onnx_rustime::shared::MODEL_NAME;
-    pub use onnx_rustime::shared::VERBOSE;
+    pub use onnx_rustime::shared::RunOptions;
 
     pub type ModelId = usize; // An alias for our model IDs.
     pub type DataId = usize;
+    pub type StoredData = (Tensor, Option<Model>); // A tensor and the labels of its model.
 
-    pub static MODELS: Lazy<Mutex<HashMap<ModelId, ModelProto>>> =
+    // Every output is stored with the label set of the session that computed it (loaded data
+    // has none).
+    pub static MODELS: Lazy<Mutex<HashMap<ModelId, Arc<Session>>>> =
         Lazy::new(|| Mutex::new(HashMap::new()));
-    pub static DATA: Lazy<Mutex<HashMap<DataId, TensorProto>>> =
+    pub static DATA: Lazy<Mutex<HashMap<DataId, StoredData>>> =
         Lazy::new(|| Mutex::new(HashMap::new()));
 
-    pub fn store_model(model: ModelProto) -> ModelId {
+    pub fn store_model(session: Session) -> ModelId {
         let mut models = MODELS.lock().unwrap();
         let id = models.len() + 1; // Generate a new unique ID.
-        models.insert(id, model);
+        models.insert(id, Arc::new(session));
         id
     }
 
-    pub fn get_model(id: ModelId) -> Option<ModelProto> {
+    pub fn get_model(id: ModelId) -> Option<Arc<Session>> {
         let models = MODELS.lock().unwrap();
-        models.get(&id).cloned() // Cloned because we can't return a reference directly.
+        models.get(&id).cloned() // The session is shared, not copied.
     }
 
-    pub fn store_data(tensor: TensorProto) -> DataId {
+    pub fn store_data(tensor: Tensor, labels: Option<Model>) -> DataId {
         let mut data = DATA.lock().unwrap();
         let id = data.len() + 1; // Generate a new unique ID.
-        data.insert(id, tensor);
+        data.insert(id, (tensor, labels));
         id
     }
 
-    pub fn get_data(id: DataId) -> Option<TensorProto> {
+    pub fn get_data(id: DataId) -> Option<Tensor> {
         let data = DATA.lock().unwrap();
-        data.get(&id).cloned() // Cloned because we can't return a reference directly.
+        data.get(&id).map(|(tensor, _)| tensor.clone())
+    }
+
+    /// The options to print the stored tensors with: summarized, after their statistics.
+    pub fn print_options() -> FormatOptions {
+        FormatOptions {
+            stats: true,
+            ..Default::default()
+        }
+    }
+
+    /// The options to display an output with: the label set of the model that computed it.
+    pub fn display_options(id: DataId) -> RunOptions {
+        let data = DATA.lock().unwrap();
+        match data.get(&id) {
+            Some((_, Some(labels))) => RunOptions {
+                model: labels.clone(),
+                ..Default::default()
+            },
+            _ => RunOptions::default(),
+        }
     }
 }
 
@@ -81,18 +105,17 @@ mod include_pyo3 {
             }
         };
 
+        match Session::builder()
+            .model_path(path)
+            .labels(model_enum)
+            .build()
         {
-            let mut d = MODEL_NAME.lock().unwrap();
-            *d = model_enum;
-        }
-
-        match OnnxParser::load_model(path.to_string()) {
-            Ok(model) => {
-                let id = store_model(model);
+            Ok(session) => {
+                let id = store_model(session);
                 Ok(id)
             }
             Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-                "{:?}",
+                "{}",
                 e
             ))),
         }
@@ -102,11 +125,11 @@ mod include_pyo3 {
     pub fn py_load_data(path: &str) -> PyResult<DataId> {
         match OnnxParser::load_data(path.to_string()) {
             Ok(tensor) => {
-                let id = store_data(tensor);
+                let id = store_data(tensor, None);
                 Ok(id)
             }
             Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-                "{:?}",
+                "{}",
                 e
             ))),
         }
@@ -114,14 +137,10 @@ mod include_pyo3 {
 
     #[pyfunction]
     pub fn py_print_data(data_id: DataId) -> PyResult<()> {
-        // Get the TensorProto from the DATA storage using the provided ID
+        // Get the Tensor from the DATA storage using the provided ID
         if let Some(tensor) = get_data(data_id) {
-            // Convert the TensorProto to an ndarray
-            let ndarray = tensor_proto_to_ndarray::<f32>(&tensor)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("{:?}", e)))?;
-
-            // Print the ndarray
-            println!("{:?}", ndarray);
+            // Print its statistics and its values, summarized
+            println!("{}", tensor.format(&print_options()));
 
             Ok(())
         } else {
@@ -133,23 +152,21 @@ mod include_pyo3 {
 
     #[pyfunction]
     pub fn py_run(model_id: ModelId, input_data_id: DataId, verbose: bool) -> PyResult<DataId> {
-        // Retrieve the ModelProto and TensorProto from storages
-        let model = get_model(model_id)
+        // Retrieve the Session and the Tensor from storages
+        let session = get_model(model_id)
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid model ID"))?;
 
         let input_tensor = get_data(input_data_id)
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid data ID"))?;
 
-        {
-            let mut v = VERBOSE.lock().unwrap();
-            *v = verbose;
-        }
+        // The verbosity is chosen at every run
+        let output_tensor = session
+            .with_verbose(verbose)
+            .run(input_tensor)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(e.to_string()))?;
 
-        // Call the original run function
-        let output_tensor = run(&model, input_tensor);
-
-        // Store the output TensorProto in the DATA storage and return its ID
-        Ok(store_data(output_tensor))
+        // Store the output Tensor in the DATA storage and return its ID
+        Ok(store_data(output_tensor, Some(session.options().model.clone())))
     }
 
     #[pyfunction]
@@ -157,12 +174,12 @@ mod include_pyo3 {
         predicted_data_id: DataId,
         expected_data_id: Option<DataId>,
     ) -> PyResult<()> {
-        // Retrieve the predicted TensorProto from storages
+        // Retrieve the predicted Tensor from storages
         let predicted_tensor = get_data(predicted_data_id).ok_or_else(|| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid predicted data ID")
         })?;
 
-        // Retrieve the expected TensorProto from storages, if available
+        // Retrieve the expected Tensor from storages, if available
         let expected_tensor = match expected_data_id {
             Some(id) => Some(get_data(id).ok_or_else(|| {
                 PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid expected data ID")
@@ -170,9 +187,12 @@ mod include_pyo3 {
             None => None,
         };
 
-        display_outputs(&predicted_tensor, expected_tensor);
-
-        Ok(())
+        display_outputs(
+            &predicted_tensor,
+            expected_tensor,
+            &display_options(predicted_data_id),
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(e.to_string()))
     }
 
     #[pymodule]
@@ -207,18 +227,17 @@ mod include_neon {
             _ => return cx.throw_error("Invalid model path"),
         };
 
+        match Session::builder()
+            .model_path(path)
+            .labels(model_enum)
+            .build()
         {
-            let mut d = MODEL_NAME.lock().unwrap();
-            *d = model_enum;
-        }
-
-        match OnnxParser::load_model(path) {
-            Ok(model) => {
-                let id = store_model(model);
+            Ok(session) => {
+                let id = store_model(session);
                 Ok(cx.number(id as f64))
             }
             Err(e) => {
-                let err_msg = format!("{:?}", e);
+                let err_msg = format!("{}", e);
                 cx.throw_error(err_msg)
             }
         }
@@ -229,11 +248,11 @@ mod include_neon {
 
         match OnnxParser::load_data(path) {
             Ok(tensor) => {
-                let id = store_data(tensor);
+                let id = store_data(tensor, None);
                 Ok(cx.number(id as f64))
             }
             Err(e) => {
-                let err_msg = format!("{:?}", e);
+                let err_msg = format!("{}", e);
                 cx.throw_error(err_msg)
             }
         }
@@ -243,15 +262,7 @@ mod include_neon {
         let data_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as DataId;
 
         if let Some(tensor) = get_data(data_id) {
-            let ndarray = match tensor_proto_to_ndarray::<f32>(&tensor) {
-                Ok(ndarray) => ndarray,
-                Err(e) => {
-                    let err_msg = format!("{:?}", e);
-                    return cx.throw_error(err_msg);
-                }
-            };
-
-            println!("{:?}", ndarray);
+            println!("{}", tensor.format(&print_options()));
 
             Ok(cx.undefined())
         } else {
@@ -265,8 +276,8 @@ mod include_neon {
         let input_data_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as DataId;
         let verbose = cx.argument::<JsBoolean>(2)?.value(&mut cx);
 
-        let model = match get_model(model_id) {
-            Some(model) => model,
+        let session = match get_model(model_id) {
+            Some(session) => session,
             None => {
                 let err_msg = "Invalid model ID".to_string();
                 return cx.throw_error(err_msg);
@@ -281,13 +292,12 @@ mod include_neon {
             }
         };
 
-        {
-            let mut v = VERBOSE.lock().unwrap();
-            *v = verbose;
-        }
-
-        let output_tensor = run(&model, input_tensor);
-        let data_id = store_data(output_tensor);
+        // The verbosity is chosen at every run
+        let output_tensor = match session.with_verbose(verbose).run(input_tensor) {
+            Ok(tensor) => tensor,
+            Err(e) => return cx.throw_error(e.to_string()),
+        };
+        let data_id = store_data(output_tensor, Some(session.options().model.clone()));
 
         Ok(cx.number(data_id as f64))
     }
@@ -312,7 +322,13 @@ mod include_neon {
             }
         };
 
-        display_outputs(&predicted_tensor, Some(expected_tensor));
+        if let Err(e) = display_outputs(
+            &predicted_tensor,
+            Some(expected_tensor),
+            &display_options(predicted_data_id),
+        ) {
+            return cx.throw_error(e.to_string());
+        }
 
         Ok(cx.undefined())
     }
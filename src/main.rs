@@ -1,20 +1,50 @@
-mod onnx_rustime;
+use onnx_rustime::backend::evaluation::{evaluate, EvaluationOptions};
+use onnx_rustime::backend::helper::OnnxError;
 use onnx_rustime::backend::parser::OnnxParser;
 use onnx_rustime::backend::pre_processing::serialize_image;
-use onnx_rustime::backend::run::run;
-use std::env;
-mod display;
-use display::{display_outputs, menu};
+use onnx_rustime::backend::session::Session;
+use onnx_rustime::shared::RunOptions;
+use onnx_rustime::testing::{
+    check_broadcasting, check_goldens, check_kernel, run_node_tests, verify_determinism,
+    CaseStatus, GoldenStatus, DIFFERENTIAL_OPS,
+};
+use onnx_rustime::transforms::{
+    model_size_report, ops_report, prune_unused_initializers, OptLevel,
+};
+use onnx_rustime_lib::display::{
+    display_conformance_report, display_determinism_report, display_evaluation_report,
+    display_golden_results, display_model_size_report, display_ops_report,
+    display_optimization_report, display_outputs, menu,
+};
+use onnx_rustime_lib::onnx_rustime;
+use std::path::Path;
+use std::{env, fs, process};
+
+/// Environment variable holding the path of the file to write the spans of the run to, in the
+/// folded stack format of flamegraphs (see `install_flame_subscriber`).
+#[cfg(feature = "flame")]
+const TRACE_FLAME_VAR: &str = "ONNX_RUSTIME_TRACE_FLAME";
 
 fn main() {
     env::set_var("RUST_BACKTRACE", "1");
 
-    let (model_path, input_path, output_path, save_path_opt) = menu();
+    #[cfg(feature = "flame")]
+    let _flame_guard = install_flame_subscriber();
+
+    // Model tools are run as subcommands, without the interactive menu
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        run_command(&args);
+        return;
+    }
+
+    let (model_path, input_path, output_path, save_path_opt, opt_level, run_options) = menu();
 
     if model_path == "PREPROCESSING" {
         if let Some(save_path) = save_path_opt {
             if let Err(err) = serialize_image(input_path, save_path) {
-                eprintln!("Failed to preprocess and serialize image: {:?}", err);
+                eprintln!("Failed to preprocess and serialize image: {}", err);
+                process::exit(1);
             }
             return;
         } else {
@@ -24,23 +54,362 @@ fn main() {
     }
 
     // If not preprocessing, proceed with model loading and inference
-    let model = OnnxParser::load_model(model_path).unwrap();
+    if let Err(err) = run_model(
+        model_path,
+        input_path,
+        output_path,
+        save_path_opt,
+        opt_level,
+        &run_options,
+    ) {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Records the spans of the runtime, at every level, to the file named by `TRACE_FLAME_VAR` if
+/// set. The file is complete once the returned guard is dropped, and can be rendered with
+/// `inferno-flamegraph < file > flamegraph.svg`.
+#[cfg(feature = "flame")]
+fn install_flame_subscriber() -> Option<tracing_flame::FlushGuard<std::io::BufWriter<fs::File>>> {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let path = env::var(TRACE_FLAME_VAR).ok()?;
+    let (layer, guard) = match tracing_flame::FlameLayer::with_file(&path) {
+        Ok(flame) => flame,
+        Err(err) => {
+            eprintln!("Failed to create the trace file '{}': {}", path, err);
+            return None;
+        }
+    };
+
+    let subscriber = tracing_subscriber::registry().with(layer.with_file_and_line(false));
+    if let Err(err) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Failed to install the trace subscriber: {}", err);
+        return None;
+    }
+    Some(guard)
+}
+
+/// Runs the model chosen in the menu on its input, and displays the output.
+fn run_model(
+    model_path: String,
+    input_path: String,
+    output_path: Option<String>,
+    save_path_opt: Option<String>,
+    opt_level: OptLevel,
+    options: &RunOptions,
+) -> Result<(), OnnxError> {
+    // The graph is optimized when the session is built
+    let session = Session::builder()
+        .model_path(model_path)
+        .opt_level(opt_level)
+        .run_options(options.clone())
+        .build()?;
+    display_optimization_report(session.optimization_report());
 
-    let input = OnnxParser::load_data(input_path).unwrap();
+    let input = OnnxParser::load_data(input_path)?;
 
-    let expected_output = if let Some(path) = output_path {
-        Some(OnnxParser::load_data(path).unwrap())
-    } else {
-        None
+    let expected_output = match output_path {
+        Some(path) => Some(OnnxParser::load_data(path)?),
+        None => None,
     };
 
     // Run the model
-    let predicted_output = run(&model, input);
+    let predicted_output = session.run(input)?;
 
     // If save_path_opt contains a path, save the data
     if let Some(save_path) = save_path_opt {
-        OnnxParser::save_data(&predicted_output, save_path).unwrap();
+        OnnxParser::save_data(&predicted_output, save_path)?;
+    }
+
+    display_outputs(&predicted_output, expected_output, session.options())
+}
+
+const USAGE: &str = "Usage:
+  onnx_rustime_project                              Run the interactive menu
+  onnx_rustime_project size-report <model>          Show the size of every initializer
+  onnx_rustime_project prune <model> <output>       Remove the unused initializers
+  onnx_rustime_project ops-report --model <model> [--json <report>]
+                                                    Check which operations of a model are supported
+  onnx_rustime_project evaluate <model> <images-dir> <ground-truth> [--batch-size <n>] [--limit <n>]
+                        [--misclassified <csv>]     Compute the top-1/top-5 accuracy of a classifier
+  onnx_rustime_project conformance <node-tests-dir> [--filter <name>] [--json <report>]
+                                                    Run the ONNX backend node tests
+  onnx_rustime_project check-broadcasting [--cases <n>] [--seed <seed>]
+                                                    Check broadcasting on random shapes
+  onnx_rustime_project check-kernels [--cases <n>] [--seed <seed>]
+                                                    Check the kernels against the reference ones
+  onnx_rustime_project golden [--models-dir <dir>] [--model <name>]
+                                                    Check the models against their golden outputs
+  onnx_rustime_project verify-determinism <model> <input> [--runs <n>] [--threads <n,n,...>]
+                                                    Check that the outputs are reproducible";
+
+fn run_command(args: &[String]) {
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+
+    let result = match args.as_slice() {
+        ["size-report", model_path] => OnnxParser::load_model(model_path.to_string())
+            .map(|model| display_model_size_report(&model_size_report(&model))),
+        ["prune", model_path, output_path] => {
+            OnnxParser::load_model(model_path.to_string()).and_then(|mut model| {
+                let before = model_size_report(&model);
+                prune_unused_initializers(model.mut_graph())?;
+                let after = model_size_report(&model);
+
+                println!(
+                    "Removed {} initializers ({} bytes)",
+                    before.initializers.len() - after.initializers.len(),
+                    before.initializer_bytes - after.initializer_bytes
+                );
+                OnnxParser::save_model(&model, output_path.to_string())
+            })
+        }
+        ["ops-report", options @ ..] => run_ops_report(options),
+        ["evaluate", model_path, images_dir, ground_truth, options @ ..] => {
+            run_evaluation(model_path, images_dir, ground_truth, options)
+        }
+        ["conformance", dir, options @ ..] => run_conformance(dir, options),
+        ["golden", options @ ..] => run_golden(options),
+        ["verify-determinism", model_path, input_path, options @ ..] => {
+            run_determinism_check(model_path, input_path, options)
+        }
+        ["check-broadcasting", options @ ..] => {
+            run_broadcasting_check(options);
+            Ok(())
+        }
+        ["check-kernels", options @ ..] => {
+            run_kernels_check(options);
+            Ok(())
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run_evaluation(
+    model_path: &str,
+    images_dir: &str,
+    ground_truth: &str,
+    options: &[&str],
+) -> Result<(), OnnxError> {
+    let mut evaluation_options = EvaluationOptions::default();
+    let mut csv_path = None;
+    for option in options.chunks(2) {
+        match option {
+            ["--batch-size", value] if value.parse::<usize>().is_ok_and(|n| n > 0) => {
+                evaluation_options.batch_size = value.parse().unwrap()
+            }
+            ["--limit", value] if value.parse::<usize>().is_ok() => {
+                evaluation_options.limit = Some(value.parse().unwrap())
+            }
+            ["--misclassified", path] => csv_path = Some(*path),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let session = Session::builder().model_path(model_path).build()?;
+    let report = evaluate(
+        &session,
+        Path::new(images_dir),
+        Path::new(ground_truth),
+        evaluation_options,
+    )?;
+    display_evaluation_report(&report);
+
+    if let Some(path) = csv_path {
+        fs::write(path, report.misclassified_csv()).map_err(|e| OnnxError::io(path, e))?;
+    }
+    Ok(())
+}
+
+fn run_conformance(dir: &str, options: &[&str]) -> Result<(), OnnxError> {
+    let mut filter = None;
+    let mut json_path = None;
+    for option in options.chunks(2) {
+        match option {
+            ["--filter", name] => filter = Some(*name),
+            ["--json", path] => json_path = Some(*path),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let report = run_node_tests(Path::new(dir), filter)?;
+    display_conformance_report(&report);
+
+    if let Some(path) = json_path {
+        fs::write(path, report.to_json()).map_err(|e| OnnxError::io(path, e))?;
+    }
+
+    if report.count(CaseStatus::Failed) > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_ops_report(options: &[&str]) -> Result<(), OnnxError> {
+    let mut model_path = None;
+    let mut json_path = None;
+    for option in options.chunks(2) {
+        match option {
+            ["--model", path] => model_path = Some(*path),
+            ["--json", path] => json_path = Some(*path),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+    let model_path = match model_path {
+        Some(path) => path,
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let report = ops_report(&OnnxParser::load_model(model_path.to_string())?);
+    display_ops_report(&report);
+
+    if let Some(path) = json_path {
+        fs::write(path, report.to_json()).map_err(|e| OnnxError::io(path, e))?;
+    }
+
+    if !report.is_fully_supported() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_broadcasting_check(options: &[&str]) {
+    let mut cases = 1000;
+    let mut seed = 0;
+    for option in options.chunks(2) {
+        match option {
+            ["--cases", value] if value.parse::<usize>().is_ok() => cases = value.parse().unwrap(),
+            ["--seed", value] if value.parse::<u64>().is_ok() => seed = value.parse().unwrap(),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    match check_broadcasting(cases, seed) {
+        Ok(()) => println!("Broadcasting: {} cases passed", cases),
+        Err(failure) => {
+            eprintln!("Broadcasting: {}", failure);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_kernels_check(options: &[&str]) {
+    let mut cases = 1000;
+    let mut seed = 0;
+    for option in options.chunks(2) {
+        match option {
+            ["--cases", value] if value.parse::<usize>().is_ok() => cases = value.parse().unwrap(),
+            ["--seed", value] if value.parse::<u64>().is_ok() => seed = value.parse().unwrap(),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let mut failed = false;
+    for op_type in DIFFERENTIAL_OPS {
+        match check_kernel(op_type, cases, seed) {
+            Ok(()) => println!("{}: {} cases passed", op_type, cases),
+            Err(failure) => {
+                eprintln!("{}", failure);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn run_golden(options: &[&str]) -> Result<(), OnnxError> {
+    let mut models_dir = "models";
+    let mut filter = None;
+    for option in options.chunks(2) {
+        match option {
+            ["--models-dir", dir] => models_dir = dir,
+            ["--model", name] => filter = Some(*name),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
     }
 
-    display_outputs(&predicted_output, expected_output);
+    let results = check_goldens(Path::new(models_dir), filter)?;
+    display_golden_results(&results);
+
+    if results.iter().any(|result| {
+        matches!(
+            result.status,
+            GoldenStatus::Failed | GoldenStatus::Error(_)
+        )
+    }) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_determinism_check(
+    model_path: &str,
+    input_path: &str,
+    options: &[&str],
+) -> Result<(), OnnxError> {
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut runs = 3;
+    let mut thread_counts = vec![1, 2, available];
+    thread_counts.sort();
+    thread_counts.dedup();
+    for option in options.chunks(2) {
+        match option {
+            ["--runs", value] if value.parse::<usize>().is_ok_and(|n| n > 0) => {
+                runs = value.parse().unwrap()
+            }
+            ["--threads", values]
+                if values
+                    .split(',')
+                    .all(|value| value.parse::<usize>().is_ok_and(|n| n > 0)) =>
+            {
+                thread_counts = values.split(',').map(|value| value.parse().unwrap()).collect()
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let model = OnnxParser::load_model(model_path.to_string())?;
+    let input = OnnxParser::load_tensor_proto(input_path.to_string())?;
+    let report = verify_determinism(&model, vec![input], runs, &thread_counts)?;
+    display_determinism_report(&report);
+
+    if !report.is_deterministic() {
+        process::exit(1);
+    }
+    Ok(())
 }
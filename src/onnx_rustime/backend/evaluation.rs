@@ -0,0 +1,275 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::backend::pre_processing::preprocess_image;
+use crate::onnx_rustime::backend::session::Session;
+use crate::onnx_rustime::backend::tensor::Tensor;
+use ndarray::{concatenate, ArrayD, Axis};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Options of `evaluate`.
+#[derive(Debug, Clone, Copy)]
+pub struct EvaluationOptions {
+    /// Number of images run together through the network.
+    pub batch_size: usize,
+    /// If present, only the first `limit` images of the ground truth are evaluated.
+    pub limit: Option<usize>,
+}
+
+impl Default for EvaluationOptions {
+    fn default() -> Self {
+        EvaluationOptions {
+            batch_size: 8,
+            limit: None,
+        }
+    }
+}
+
+/// Accuracy on the images of a single class.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassAccuracy {
+    pub images: usize,
+    pub top1_correct: usize,
+    pub top5_correct: usize,
+}
+
+/// An image whose top-1 prediction is wrong.
+#[derive(Debug, Clone)]
+pub struct Misclassification {
+    pub file: String,
+    pub expected: usize,
+    pub predicted: usize,
+}
+
+/// Summary of the time spent running the batches (preprocessing excluded).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LatencySummary {
+    pub batches: usize,
+    /// Average time per image.
+    pub mean_per_image: Duration,
+    /// Median time per batch.
+    pub p50: Duration,
+    /// 95th percentile of the time per batch.
+    pub p95: Duration,
+    /// Slowest batch.
+    pub max: Duration,
+}
+
+/// Outcome of an accuracy evaluation.
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationReport {
+    pub images: usize,
+    pub top1_correct: usize,
+    pub top5_correct: usize,
+    /// Accuracy of every class present in the evaluated images.
+    pub per_class: BTreeMap<usize, ClassAccuracy>,
+    /// The misclassified images, in evaluation order.
+    pub misclassified: Vec<Misclassification>,
+    pub latency: LatencySummary,
+}
+
+impl EvaluationReport {
+    pub fn top1_accuracy(&self) -> f64 {
+        self.top1_correct as f64 / self.images.max(1) as f64
+    }
+
+    pub fn top5_accuracy(&self) -> f64 {
+        self.top5_correct as f64 / self.images.max(1) as f64
+    }
+
+    /// The misclassified images as CSV, with a `file,expected,predicted` header.
+    pub fn misclassified_csv(&self) -> String {
+        let mut csv = String::from("file,expected,predicted\n");
+        for image in &self.misclassified {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                image.file, image.expected, image.predicted
+            ));
+        }
+        csv
+    }
+}
+
+/// `evaluate` - Computes the accuracy of an image classification model on a labelled dataset
+///
+/// The ground truth file has one `<file name> <class index>` pair per line, like the
+/// `val_map.txt` of the ImageNet validation set (`ILSVRC2012_val_00000001.JPEG 65`); comma
+/// separated pairs are accepted as well. Empty lines and lines starting with `#` are ignored.
+///
+/// The images are read lazily, in the order of the ground truth, preprocessed like the ImageNet
+/// inputs (see `preprocess_image`) and run in batches of `options.batch_size`: only one batch is
+/// in memory at a time. The predicted classes are the indices of the largest outputs of the first
+/// graph output.
+///
+/// # Arguments
+///
+/// * `session` - The session of the classification model.
+/// * `images_dir` - The directory containing the images.
+/// * `ground_truth` - The ground truth file.
+/// * `options` - The batch size and the maximum number of images.
+///
+/// # Returns
+///
+/// * `Result<EvaluationReport, OnnxError>` - The accuracies, the misclassified images and the
+///   latency summary, or an error (`OnnxError`) if the ground truth is malformed, an image can't
+///   be read or the model fails.
+///
+/// # Example
+///
+/// ```rust
+/// let options = EvaluationOptions { batch_size: 16, limit: Some(100) };
+/// let report = evaluate(&session, Path::new("val"), Path::new("val_map.txt"), options)?;
+/// println!("top-1 {:.2}%", report.top1_accuracy() * 100.0);
+/// ```
+pub fn evaluate(
+    session: &Session,
+    images_dir: &Path,
+    ground_truth: &Path,
+    options: EvaluationOptions,
+) -> Result<EvaluationReport, OnnxError> {
+    if options.batch_size == 0 {
+        return Err(OnnxError::InvalidValue(
+            "The batch size must be positive".to_string(),
+        ));
+    }
+
+    let file = File::open(ground_truth).map_err(|e| OnnxError::io(ground_truth, e))?;
+    let labels = BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| match line {
+            Ok(line) if line.trim().is_empty() || line.trim_start().starts_with('#') => None,
+            Ok(line) => Some(parse_label(&line, index + 1)),
+            Err(e) => Some(Err(OnnxError::InternalError(format!(
+                "Failed to read {}: {}",
+                ground_truth.display(),
+                e
+            )))),
+        })
+        .take(options.limit.unwrap_or(usize::MAX));
+
+    let mut report = EvaluationReport::default();
+    let mut latencies = Vec::new();
+    let mut batch: Vec<(String, usize)> = Vec::with_capacity(options.batch_size);
+    let mut images: Vec<ArrayD<f32>> = Vec::with_capacity(options.batch_size);
+
+    for label in labels {
+        let (file, class) = label?;
+        let path = images_dir.join(&file);
+        let image = preprocess_image(path.to_string_lossy().to_string())?;
+        images.push(image.into_ndarray::<f32>()?);
+        batch.push((file, class));
+
+        if batch.len() == options.batch_size {
+            latencies.push(evaluate_batch(session, &images, &batch, &mut report)?);
+            batch.clear();
+            images.clear();
+        }
+    }
+    if !batch.is_empty() {
+        latencies.push(evaluate_batch(session, &images, &batch, &mut report)?);
+    }
+
+    report.latency = latency_summary(latencies, report.images);
+    Ok(report)
+}
+
+/// Parses a `<file name> <class index>` line of the ground truth.
+fn parse_label(line: &str, line_number: usize) -> Result<(String, usize), OnnxError> {
+    let fields: Vec<&str> = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .collect();
+
+    match fields.as_slice() {
+        [file, class] => class
+            .parse()
+            .map(|class| (file.to_string(), class))
+            .map_err(|_| {
+                OnnxError::InvalidValue(format!(
+                    "Invalid class index '{}' at line {} of the ground truth",
+                    class, line_number
+                ))
+            }),
+        _ => Err(OnnxError::InvalidValue(format!(
+            "Expected '<file name> <class index>' at line {} of the ground truth, found '{}'",
+            line_number, line
+        ))),
+    }
+}
+
+/// Runs a batch of preprocessed images and adds the predictions to the report, returning the
+/// time spent running the model.
+fn evaluate_batch(
+    session: &Session,
+    images: &[ArrayD<f32>],
+    labels: &[(String, usize)],
+    report: &mut EvaluationReport,
+) -> Result<Duration, OnnxError> {
+    let views: Vec<_> = images.iter().map(|image| image.view()).collect();
+    let input = concatenate(Axis(0), &views)
+        .map_err(|e| OnnxError::ShapeError(format!("Failed to batch the images: {}", e)))?;
+    let input = Tensor::from_ndarray(input).with_name("data");
+
+    let start = Instant::now();
+    let outputs = session.run_with_inputs(vec![input])?;
+    let elapsed = start.elapsed();
+
+    let output = outputs.first().ok_or(OnnxError::MissingInput(
+        "The model has no output".to_string(),
+    ))?;
+    let scores = output.to_ndarray::<f32>()?;
+    let classes = scores.len() / labels.len();
+    let scores = scores.into_shape((labels.len(), classes)).map_err(|e| {
+        OnnxError::ShapeError(format!(
+            "Expected one row of scores per image, found {:?}: {}",
+            output.shape(),
+            e
+        ))
+    })?;
+
+    for ((file, expected), row) in labels.iter().zip(scores.outer_iter()) {
+        let mut ranking: Vec<usize> = (0..classes).collect();
+        ranking.sort_by(|&a, &b| row[b].total_cmp(&row[a]));
+
+        let top1 = ranking.first().is_some_and(|class| class == expected);
+        let top5 = ranking.iter().take(5).any(|class| class == expected);
+
+        let class = report.per_class.entry(*expected).or_default();
+        class.images += 1;
+        class.top1_correct += top1 as usize;
+        class.top5_correct += top5 as usize;
+        report.images += 1;
+        report.top1_correct += top1 as usize;
+        report.top5_correct += top5 as usize;
+
+        if !top1 {
+            report.misclassified.push(Misclassification {
+                file: file.clone(),
+                expected: *expected,
+                predicted: ranking.first().copied().unwrap_or_default(),
+            });
+        }
+    }
+
+    Ok(elapsed)
+}
+
+fn latency_summary(mut latencies: Vec<Duration>, images: usize) -> LatencySummary {
+    if latencies.is_empty() {
+        return LatencySummary::default();
+    }
+
+    latencies.sort();
+    let percentile = |p: f64| latencies[((latencies.len() - 1) as f64 * p).round() as usize];
+
+    LatencySummary {
+        batches: latencies.len(),
+        mean_per_image: latencies.iter().sum::<Duration>() / images as u32,
+        p50: percentile(0.5),
+        p95: percentile(0.95),
+        max: latencies[latencies.len() - 1],
+    }
+}
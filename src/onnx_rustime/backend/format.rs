@@ -0,0 +1,330 @@
+use crate::onnx_rustime::backend::tensor::{DType, Tensor, TensorData, TensorElement};
+use half::f16;
+use ndarray::{ArrayD, ArrayViewD, Axis};
+
+/// Options of `format_tensor`. The defaults follow numpy: 3 edge items, summaries above 1000
+/// values and lines of 75 characters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatOptions {
+    /// Number of decimals of the floating point values.
+    pub precision: usize,
+    /// Number of values shown at each end of a summarized axis.
+    pub edge_items: usize,
+    /// Number of values above which the tensor is summarized.
+    pub threshold: usize,
+    /// Maximum width of the lines of values, wrapped beyond it.
+    pub line_width: usize,
+    /// Precede the values with a line of statistics (see `tensor_stats`).
+    pub stats: bool,
+    /// Print all the values, even above `threshold`.
+    pub full: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            precision: 4,
+            edge_items: 3,
+            threshold: 1000,
+            line_width: 75,
+            stats: false,
+            full: false,
+        }
+    }
+}
+
+/// Element types that `format_tensor` can print.
+pub trait FormatElement: TensorElement {
+    /// The value as a number, for the statistics (`None` for strings).
+    fn to_f64(&self) -> Option<f64>;
+
+    /// The value with `precision` decimals, in scientific notation if `scientific`.
+    fn format(&self, precision: usize, scientific: bool) -> String;
+}
+
+macro_rules! impl_format_float {
+    ($t: ty) => {
+        impl FormatElement for $t {
+            fn to_f64(&self) -> Option<f64> {
+                Some(f64::from(*self))
+            }
+
+            fn format(&self, precision: usize, scientific: bool) -> String {
+                if scientific && self.is_finite() {
+                    // Rust writes `1.5e3`, numpy `1.5e+03`.
+                    let formatted = format!("{:.*e}", precision, self);
+                    let (mantissa, exponent) =
+                        formatted.split_once('e').unwrap_or((&formatted, "0"));
+                    format!("{}e{:+03}", mantissa, exponent.parse::<i32>().unwrap_or(0))
+                } else {
+                    format!("{:.*}", precision, self)
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_format_int {
+    ($t: ty) => {
+        impl FormatElement for $t {
+            fn to_f64(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+
+            fn format(&self, _precision: usize, _scientific: bool) -> String {
+                self.to_string()
+            }
+        }
+    };
+}
+
+impl_format_float!(f32);
+impl_format_float!(f16);
+impl_format_float!(f64);
+impl_format_int!(i8);
+impl_format_int!(u8);
+impl_format_int!(i32);
+impl_format_int!(i64);
+
+/// Booleans count as 0 and 1 in the statistics, and are printed like numpy.
+impl FormatElement for bool {
+    fn to_f64(&self) -> Option<f64> {
+        Some(u8::from(*self) as f64)
+    }
+
+    fn format(&self, _precision: usize, _scientific: bool) -> String {
+        if *self { "True" } else { "False" }.to_string()
+    }
+}
+
+impl FormatElement for String {
+    fn to_f64(&self) -> Option<f64> {
+        None
+    }
+
+    fn format(&self, _precision: usize, _scientific: bool) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// `format_tensor` - Formats the values of a tensor like numpy
+///
+/// The values are nested in brackets, one level per axis, and right-aligned to the same width.
+/// Above `options.threshold` values (unless `options.full`), only the first and the last
+/// `options.edge_items` of every axis are shown, around an ellipsis: the output only depends on
+/// the shape and on the shown values. Floats use `options.precision` decimals, in scientific
+/// notation when the shown values are too large or too small for it.
+///
+/// # Arguments
+///
+/// * `array` - The values.
+/// * `options` - The formatting options.
+///
+/// # Returns
+///
+/// * `String` - The formatted values, preceded by the statistics line if `options.stats`,
+///   without a trailing newline.
+///
+/// # Example
+///
+/// ```rust
+/// let array = ArrayD::from_shape_vec(vec![2, 3], vec![1.0f32, -2.5, 3.0, 4.0, 5.0, 6.0])?;
+/// // [[ 1.0000, -2.5000,  3.0000],
+/// //  [ 4.0000,  5.0000,  6.0000]]
+/// println!("{}", format_tensor(&array, &FormatOptions::default()));
+/// ```
+pub fn format_tensor<T: FormatElement>(array: &ArrayD<T>, options: &FormatOptions) -> String {
+    let summarize = !options.full && array.len() > options.threshold;
+
+    // The indices shown along every axis, `None` standing for the ellipsis.
+    let shown: Vec<Vec<Option<usize>>> = array
+        .shape()
+        .iter()
+        .map(|&len| {
+            if summarize && len > 2 * options.edge_items {
+                (0..options.edge_items)
+                    .map(Some)
+                    .chain([None])
+                    .chain((len - options.edge_items..len).map(Some))
+                    .collect()
+            } else {
+                (0..len).map(Some).collect()
+            }
+        })
+        .collect();
+
+    let values = shown_values(array.view(), &shown);
+    let scientific = needs_scientific(&values, options.precision);
+    let width = values
+        .iter()
+        .map(|value| value.format(options.precision, scientific).len())
+        .max()
+        .unwrap_or(0);
+    let format = |value: &T| format!("{:>1$}", value.format(options.precision, scientific), width);
+
+    let formatted = if array.ndim() == 0 {
+        array.iter().next().map(format).unwrap_or_default()
+    } else {
+        format_axis(array.view(), &shown, 0, options.line_width, &format)
+    };
+
+    if options.stats {
+        format!("{}\n{}", tensor_stats(array, options.precision), formatted)
+    } else {
+        formatted
+    }
+}
+
+/// The values shown by `format_tensor`.
+fn shown_values<'a, T>(array: ArrayViewD<'a, T>, shown: &[Vec<Option<usize>>]) -> Vec<&'a T> {
+    match shown.split_first() {
+        None => array.into_iter().collect(),
+        Some((indices, shown)) => indices
+            .iter()
+            .flatten()
+            .flat_map(|&index| shown_values(array.clone().index_axis_move(Axis(0), index), shown))
+            .collect(),
+    }
+}
+
+/// Whether the floats must be written in scientific notation: when the largest one has more
+/// than 8 integer digits, or when the smallest nonzero one would be rounded to zero. Integers
+/// ignore it.
+fn needs_scientific<T: FormatElement>(values: &[&T], precision: usize) -> bool {
+    let magnitudes: Vec<f64> = values
+        .iter()
+        .filter_map(|value| value.to_f64())
+        .filter(|value| value.is_finite() && *value != 0.0)
+        .map(f64::abs)
+        .collect();
+
+    let max = magnitudes.iter().copied().fold(0.0, f64::max);
+    let min = magnitudes.iter().copied().fold(f64::INFINITY, f64::min);
+    max >= 1e8 || min < 0.5 * 10f64.powi(-(precision as i32))
+}
+
+/// Formats an axis (and the ones after it) starting at column `indent`.
+fn format_axis<T>(
+    array: ArrayViewD<'_, T>,
+    shown: &[Vec<Option<usize>>],
+    indent: usize,
+    line_width: usize,
+    format: &impl Fn(&T) -> String,
+) -> String {
+    let items: Vec<String> = if array.ndim() == 1 {
+        shown[0]
+            .iter()
+            .map(|index| index.map_or("...".to_string(), |index| format(&array[index])))
+            .collect()
+    } else {
+        shown[0]
+            .iter()
+            .map(|index| match index {
+                Some(index) => format_axis(
+                    array.index_axis(Axis(0), *index),
+                    &shown[1..],
+                    indent + 1,
+                    line_width,
+                    format,
+                ),
+                None => "...".to_string(),
+            })
+            .collect()
+    };
+
+    let mut output = String::from("[");
+    if array.ndim() == 1 {
+        // The values are wrapped, continuation lines being aligned after the bracket.
+        let mut column = indent + 1;
+        for (i, item) in items.iter().enumerate() {
+            let item = if i + 1 < items.len() {
+                format!("{},", item)
+            } else {
+                item.clone()
+            };
+            if i > 0 {
+                if column + 1 + item.len() > line_width {
+                    output.push('\n');
+                    output.push_str(&" ".repeat(indent + 1));
+                    column = indent + 1;
+                } else {
+                    output.push(' ');
+                    column += 1;
+                }
+            }
+            output.push_str(&item);
+            column += item.len();
+        }
+    } else {
+        // Sub-arrays on their own lines, separated by one blank line per axis below them.
+        let separator = format!(
+            ",{}{}",
+            "\n".repeat(array.ndim() - 1),
+            " ".repeat(indent + 1)
+        );
+        output.push_str(&items.join(&separator));
+    }
+    output.push(']');
+    output
+}
+
+/// `tensor_stats` - Summarizes a tensor in one line
+///
+/// # Arguments
+///
+/// * `array` - The values.
+/// * `precision` - The number of decimals of the statistics.
+///
+/// # Returns
+///
+/// * `String` - The type and the shape, followed for numbers by the minimum, maximum, mean and
+///   (population) standard deviation of the values that aren't NaN, and the number of NaN values.
+///
+/// # Example
+///
+/// ```rust
+/// // float32[1, 10] min -4.7022 max 5.8316 mean 0.0123 std 3.1018 NaN 0
+/// println!("{}", tensor_stats(&output, 4));
+/// ```
+pub fn tensor_stats<T: FormatElement>(array: &ArrayD<T>, precision: usize) -> String {
+    let header = format!("{}{:?}", T::DTYPE, array.shape());
+    if T::DTYPE == DType::String {
+        return header;
+    }
+
+    let values: Vec<f64> = array.iter().filter_map(|value| value.to_f64()).collect();
+
+    let nan_count = values.iter().filter(|value| value.is_nan()).count();
+    let numbers: Vec<f64> = values.into_iter().filter(|value| !value.is_nan()).collect();
+    if numbers.is_empty() {
+        return format!("{} NaN {}", header, nan_count);
+    }
+
+    let count = numbers.len() as f64;
+    let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = numbers.iter().sum::<f64>() / count;
+    let std = (numbers.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count).sqrt();
+
+    format!(
+        "{} min {:.*} max {:.*} mean {:.*} std {:.*} NaN {}",
+        header, precision, min, precision, max, precision, mean, precision, std, nan_count
+    )
+}
+
+impl Tensor {
+    /// Formats the values of the tensor (see `format_tensor`).
+    pub fn format(&self, options: &FormatOptions) -> String {
+        match self.data() {
+            TensorData::Float(array) => format_tensor(array, options),
+            TensorData::Float16(array) => format_tensor(array, options),
+            TensorData::Double(array) => format_tensor(array, options),
+            TensorData::Int8(array) => format_tensor(array, options),
+            TensorData::UInt8(array) => format_tensor(array, options),
+            TensorData::Int32(array) => format_tensor(array, options),
+            TensorData::Int64(array) => format_tensor(array, options),
+            TensorData::Bool(array) => format_tensor(array, options),
+            TensorData::String(array) => format_tensor(array, options),
+        }
+    }
+}
@@ -1,56 +1,237 @@
 #![allow(dead_code)]
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
+use crate::onnx_rustime::ops::top_k::top_k_indices;
 use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
-use protobuf::{ProtobufEnum, RepeatedField};
+use protobuf::{ProtobufEnum, ProtobufError, RepeatedField};
 use std::collections::HashMap;
-use crate::onnx_rustime::shared::{MODEL_NAME, Model};
+use thiserror::Error;
+use crate::onnx_rustime::shared::Model;
 
 /// Represents the various types of errors that can occur within the ONNX runtime.
 ///
 /// This enum provides detailed error variants that capture specific failure points within the
 /// ONNX processing pipeline. Each variant provides a description, often encapsulating
-/// additional information about the root cause of the error.
-#[derive(Debug)]
+/// additional information about the root cause of the error. Errors raised by the standard
+/// library or by protobuf are kept as `source`, so that the whole chain can be inspected.
+#[derive(Debug, Error)]
 pub enum OnnxError {
     /// Indicates that a required attribute was not found.
     ///
     /// The contained `String` provides the name or identifier of the missing attribute.
+    #[error("Attribute not found: {0}")]
     AttributeNotFound(String),
 
     /// Represents generic internal errors that might occur during processing.
     ///
     /// The contained `String` provides a description or message detailing the nature of the internal error.
+    #[error("Internal error: {0}")]
     InternalError(String),
 
     /// Indicates an error that occurred during data type conversion.
     ///
     /// The contained `String` provides additional information about the conversion that failed.
+    #[error("Conversion error: {0}")]
     ConversionError(String),
 
     /// Represents an error where an operation or functionality is not supported.
     ///
     /// The contained `String` provides details about the unsupported operation.
+    #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
 
     /// Indicates a mismatch between expected and actual tensor shapes.
     ///
-    /// The contained `String` provides details about the shape mismatch, such as the expected vs. actual dimensions.
-    ShapeMismatch(String),
+    /// `detail` describes the mismatch, such as the expected vs. actual dimensions. Kernels raise it
+    /// with `OnnxError::shape_mismatch`, and the executor fills in the node and its inputs (see
+    /// `with_node_context`).
+    #[error("{}", shape_mismatch_message(.node_name, .op_type, .input_names, .input_shapes, .detail))]
+    ShapeMismatch {
+        node_name: String,
+        op_type: String,
+        input_names: Vec<String>,
+        input_shapes: Vec<Vec<i64>>,
+        detail: String,
+    },
 
     /// Represents an error where an expected input tensor or data is missing.
     ///
     /// The contained `String` provides details about the missing input.
+    #[error("Missing input: {0}")]
     MissingInput(String),
 
     /// Indicates an error due to invalid data or values.
     ///
     /// The contained `String` provides details about the nature of the invalid data.
+    #[error("Invalid value: {0}")]
     InvalidValue(String),
 
+    /// Indicates that an attribute of a node has an invalid value.
+    ///
+    /// `name` is the name of the attribute, and `detail` describes why its value is invalid.
+    #[error("Invalid attribute '{name}': {detail}")]
+    InvalidAttribute { name: String, detail: String },
+
     /// Indicates an error related to tensor shape computations.
     ///
     /// The contained `String` provides details about the shape computation error.
+    #[error("Shape error: {0}")]
     ShapeError(String),
+
+    /// Indicates that a file (model, tensor, image, external data, ...) couldn't be opened, read
+    /// or written.
+    #[error("Failed to access '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Indicates that a file isn't a valid protobuf message (e.g. a truncated or corrupt model).
+    #[error("Failed to decode '{path}': {source}")]
+    ProtobufDecode {
+        path: String,
+        #[source]
+        source: ProtobufError,
+    },
+
+    /// Indicates that a run was stopped before completion at the caller's request.
+    #[error("The run was cancelled")]
+    Cancelled,
+
+    /// Indicates that a node produced NaN or infinite values (see `NonFiniteCheck`).
+    ///
+    /// The fields identify the node and the index of the first non-finite element of its output.
+    #[error(
+        "Node {} produced a non-finite value at {:?}",
+        node_label(.node_name, .op_type),
+        .first_bad_index
+    )]
+    NonFiniteOutput {
+        node_name: String,
+        op_type: String,
+        first_bad_index: Vec<usize>,
+    },
+
+    /// Wraps an error raised by a node with the position of the node in the graph.
+    ///
+    /// `node_index` is 1-based, so that the error reads "node 37/214".
+    #[error(
+        "Node {}/{} {} failed:\n  {}",
+        .node_index,
+        .node_count,
+        node_label(.node_name, .op_type),
+        .source.to_string().replace('\n', "\n  ")
+    )]
+    NodeFailed {
+        node_index: usize,
+        node_count: usize,
+        node_name: String,
+        op_type: String,
+        #[source]
+        source: Box<OnnxError>,
+    },
+}
+
+impl OnnxError {
+    /// Creates an `OnnxError::ShapeMismatch` without node context.
+    pub fn shape_mismatch<S: Into<String>>(detail: S) -> Self {
+        OnnxError::ShapeMismatch {
+            node_name: String::new(),
+            op_type: String::new(),
+            input_names: Vec::new(),
+            input_shapes: Vec::new(),
+            detail: detail.into(),
+        }
+    }
+
+    /// Creates an `OnnxError::Io` on the file at `path`.
+    pub fn io<P: AsRef<std::path::Path>>(path: P, source: std::io::Error) -> Self {
+        OnnxError::Io {
+            path: path.as_ref().display().to_string(),
+            source,
+        }
+    }
+
+    /// Attaches the node that raised a shape error, and the shapes of its operands, to the error.
+    ///
+    /// The operands are given as the names of the node inputs they are bound to and their
+    /// dimensions, in order. `OnnxError::ShapeError`s become `OnnxError::ShapeMismatch`es. The
+    /// other errors, and the shape mismatches that already have a node, are returned unchanged.
+    pub fn with_node_context(self, node: &NodeProto, operands: &[(&str, &[i64])]) -> Self {
+        let detail = match self {
+            OnnxError::ShapeMismatch {
+                ref op_type,
+                detail,
+                ..
+            } if op_type.is_empty() => detail,
+            OnnxError::ShapeError(detail) => detail,
+            other => return other,
+        };
+
+        let (input_names, input_shapes) = operands
+            .iter()
+            .map(|(name, dims)| (name.to_string(), dims.to_vec()))
+            .unzip();
+
+        OnnxError::ShapeMismatch {
+            node_name: node.get_name().to_string(),
+            op_type: node.get_op_type().to_string(),
+            input_names,
+            input_shapes,
+            detail,
+        }
+    }
+
+    /// Wraps the error into an `OnnxError::NodeFailed`, with the 1-based position of the node.
+    pub fn at_node(self, node_index: usize, node_count: usize, node: &NodeProto) -> Self {
+        OnnxError::NodeFailed {
+            node_index,
+            node_count,
+            node_name: node.get_name().to_string(),
+            op_type: node.get_op_type().to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the innermost error, skipping the `OnnxError::NodeFailed` wrappers.
+    pub fn root_cause(&self) -> &OnnxError {
+        match self {
+            OnnxError::NodeFailed { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+}
+
+/// Formats a node as `'name' (OpType)`, or `(OpType)` for unnamed nodes.
+fn node_label(node_name: &str, op_type: &str) -> String {
+    if node_name.is_empty() {
+        format!("({})", op_type)
+    } else {
+        format!("'{}' ({})", node_name, op_type)
+    }
+}
+
+/// Formats a shape mismatch, with the shapes of the inputs of the node if known.
+fn shape_mismatch_message(
+    node_name: &str,
+    op_type: &str,
+    input_names: &[String],
+    input_shapes: &[Vec<i64>],
+    detail: &str,
+) -> String {
+    let mut message = if op_type.is_empty() {
+        format!("Shape mismatch: {}", detail)
+    } else {
+        format!(
+            "Shape mismatch in node {}: {}",
+            node_label(node_name, op_type),
+            detail
+        )
+    };
+    for (name, shape) in input_names.iter().zip(input_shapes) {
+        message += &format!("\n  input '{}': {:?}", name, shape);
+    }
+    message
 }
 
 macro_rules! set_optional {
@@ -320,6 +501,7 @@ pub fn make_attribute<S: Into<String>, U: Into<Vec<u8>>>(
 #[allow(dead_code)]
 pub enum TensorValue {
     Float(Vec<f32>),
+    Float16(Vec<half::f16>),
     UInt8(Vec<u8>),
     Int8(Vec<i8>),
     UInt16(Vec<u16>),
@@ -340,6 +522,10 @@ macro_rules! set_tensor_data {
               $proto.set_int32_data(vals.into_iter().map(|v| if v { 1 } else { 0 }).collect());
               $proto.set_data_type(TensorProto_DataType::BOOL as i32);
           }
+          TensorValue::Float16(vals) => {
+              $proto.set_int32_data(vals.into_iter().map(|v| v.to_bits() as i32).collect());
+              $proto.set_data_type(TensorProto_DataType::FLOAT16 as i32);
+          }
           $(TensorValue::$type(vals) => {
               $proto.$setter(vals.into_iter().map(Into::into).collect());
               $proto.set_data_type(TensorProto_DataType::$proto_type as i32);
@@ -369,6 +555,7 @@ pub fn make_tensor<S: Into<String>>(
         | Int64   INT64   set_int64_data
         | Double  DOUBLE  set_double_data
         // | Bool    BOOL    set_int32_data // no from -> special-cased
+        // | Float16 FLOAT16 set_int32_data // bit pattern -> special-cased
     );
     tensor_proto
 }
@@ -415,14 +602,20 @@ pub fn make_tensor_value_info<S: Into<String>>(
     value_info_proto
 }
 
-pub fn duplicate_input_tensor(input: &TensorProto, n: usize) -> TensorProto {
-    let input_nd_array = tensor_proto_to_ndarray::<f32>(input).unwrap();
+pub fn duplicate_input_tensor(input: &TensorProto, n: usize) -> Result<TensorProto, OnnxError> {
+    let input_nd_array = tensor_proto_to_ndarray::<f32>(input)?;
     let input_shape: Vec<usize> = input
         .get_dims()
         .to_vec()
         .iter()
         .map(|&x| x as usize)
         .collect();
+    if input_shape.len() != 4 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a 4D input to duplicate, but got shape {:?}",
+            input_shape
+        )));
+    }
 
     let vec_input = input_nd_array.into_raw_vec();
 
@@ -436,20 +629,15 @@ pub fn duplicate_input_tensor(input: &TensorProto, n: usize) -> TensorProto {
         ndarray::IxDyn(&[n, input_shape[1], input_shape[2], input_shape[3]]),
         duplicated_arrays,
     )
-    .unwrap();
+    .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
 
-    ndarray_to_tensor_proto::<f32>(result_array, input.get_name()).unwrap()
+    ndarray_to_tensor_proto::<f32>(result_array, input.get_name())
 }
 
 use ndarray::prelude::*;
 
-fn logits_to_prob(logits: ArrayView1<f32>) -> Array1<f32> {
-    let model_name = {
-        let lock = MODEL_NAME.lock().unwrap();
-        lock.clone()
-    };
-
-    match model_name {
+fn logits_to_prob(logits: ArrayView1<f32>, model: &Model) -> Array1<f32> {
+    match model {
         Model::ResNet => {
             let max = logits.fold(0. / 0., |m, &val| f32::max(m, val)); // NaN-safe max
             let exps = logits.mapv(|x| (x - max).exp());
@@ -468,7 +656,7 @@ fn logits_to_prob(logits: ArrayView1<f32>) -> Array1<f32> {
     }
 }
 
-pub fn find_top_5_peak_classes(output: &ArrayD<f32>) -> Option<Vec<Vec<(usize, f32)>>> {
+pub fn find_top_5_peak_classes(output: &ArrayD<f32>, model: &Model) -> Option<Vec<Vec<(usize, f32)>>> {
     // Calculate total number of elements and the batch size
     let total_elements = output.len();
     let first_dim = output.shape()[0];
@@ -481,12 +669,12 @@ pub fn find_top_5_peak_classes(output: &ArrayD<f32>) -> Option<Vec<Vec<(usize, f
     let mut top_5_peak_classes = Vec::with_capacity(first_dim);
 
     for batch in reshaped.outer_iter() {
-        let probabilities = logits_to_prob(batch.view());
+        let probabilities = logits_to_prob(batch.view(), model);
 
-        let mut indexed_values: Vec<(usize, f32)> = probabilities.iter().enumerate().map(|(i, &v)| (i, v)).collect();
-        indexed_values.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap()); // Sort in descending order
-
-        let top_5: Vec<(usize, f32)> = indexed_values.iter().take(5).cloned().collect();
+        let top_5: Vec<(usize, f32)> = top_k_indices(probabilities.view(), 5, true, f32::total_cmp)
+            .into_iter()
+            .map(|class| (class, probabilities[class]))
+            .collect();
         top_5_peak_classes.push(top_5);
     }
 
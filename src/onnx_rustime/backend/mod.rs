@@ -1,4 +1,11 @@
+#[cfg(feature = "image")]
+pub mod evaluation;
+pub mod format;
 pub mod helper;
 pub mod parser;
 pub mod run;
+pub mod session;
+pub(crate) mod style;
+pub mod tensor;
+#[cfg(feature = "image")]
 pub mod pre_processing;
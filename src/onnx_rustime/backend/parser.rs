@@ -1,79 +1,248 @@
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
 use crate::onnx_rustime::backend::helper::OnnxError;
-use protobuf::{CodedInputStream, Message};
+#[cfg(feature = "tracing")]
+use crate::onnx_rustime::backend::run::duration_ms;
+use crate::onnx_rustime::backend::tensor::Tensor;
+use half::f16;
+use protobuf::{CodedInputStream, Message, ProtobufError};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
 
 pub fn parse_raw_data_as_floats(raw_data: &[u8]) -> Vec<f32> {
     let mut doubles = Vec::with_capacity(raw_data.len() / 4);
 
-    for i in (0..raw_data.len()).step_by(4) {
-        let bytes = [
-            raw_data[i],
-            raw_data[i + 1],
-            raw_data[i + 2],
-            raw_data[i + 3],
-        ];
-        let double_value = f32::from_le_bytes(bytes);
+    // A truncated last element is dropped, and reported as a shape mismatch by the caller.
+    for bytes in raw_data.chunks_exact(4) {
+        let double_value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
         doubles.push(double_value);
     }
 
     doubles
 }
 
+pub fn parse_raw_data_as_floats16(raw_data: &[u8]) -> Vec<f16> {
+    let mut halves = Vec::with_capacity(raw_data.len() / 2);
+
+    for bytes in raw_data.chunks_exact(2) {
+        let half_value = f16::from_le_bytes([bytes[0], bytes[1]]);
+        halves.push(half_value);
+    }
+
+    halves
+}
+
+pub fn parse_raw_data_as_ints32(raw_data: &[u8]) -> Vec<i32> {
+    let mut ints32 = Vec::with_capacity(raw_data.len() / 4);
+
+    for bytes in raw_data.chunks_exact(4) {
+        let int32_value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        ints32.push(int32_value);
+    }
+
+    ints32
+}
+
 pub fn parse_raw_data_as_ints64(raw_data: &[u8]) -> Vec<i64> {
     let mut ints64 = Vec::with_capacity(raw_data.len() / 8);
 
-    for i in (0..raw_data.len()).step_by(8) {
-        let bytes = [
-            raw_data[i],
-            raw_data[i + 1],
-            raw_data[i + 2],
-            raw_data[i + 3],
-            raw_data[i + 4],
-            raw_data[i + 5],
-            raw_data[i + 6],
-            raw_data[i + 7],
-        ];
-        let int64_value = i64::from_le_bytes(bytes);
+    for bytes in raw_data.chunks_exact(8) {
+        let mut le_bytes = [0; 8];
+        le_bytes.copy_from_slice(bytes);
+        let int64_value = i64::from_le_bytes(le_bytes);
         ints64.push(int64_value);
     }
 
     ints64
 }
 
+pub fn parse_raw_data_as_doubles(raw_data: &[u8]) -> Vec<f64> {
+    let mut doubles = Vec::with_capacity(raw_data.len() / 8);
+
+    for bytes in raw_data.chunks_exact(8) {
+        let mut le_bytes = [0; 8];
+        le_bytes.copy_from_slice(bytes);
+        doubles.push(f64::from_le_bytes(le_bytes));
+    }
+
+    doubles
+}
+
 pub struct OnnxParser;
 
 impl OnnxParser {
     pub fn load_model(path: String) -> Result<ModelProto, OnnxError> {
-        let mut file = std::fs::File::open(path).map_err(|_| OnnxError::InternalError("Failed to open model file".to_string()))?;
+        let base_dir = Path::new(&path).parent().unwrap_or(Path::new(".")).to_path_buf();
+        Self::load_model_with_external_data(path, &base_dir)
+    }
+
+    /// Loads a model whose external data files are in `base_dir` rather than in the directory of
+    /// the model.
+    pub fn load_model_with_external_data(
+        path: String,
+        base_dir: &Path,
+    ) -> Result<ModelProto, OnnxError> {
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "parser.load_model",
+            path = path.as_str(),
+            nodes = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        )
+        .entered();
+
+        let mut file = std::fs::File::open(&path).map_err(|e| OnnxError::io(&path, e))?;
         let mut stream = CodedInputStream::new(&mut file);
 
         let mut model = ModelProto::new();
-        model.merge_from(&mut stream).map_err(|_| OnnxError::InternalError("Failed to merge model from stream".to_string()))?;
+        model.merge_from(&mut stream).map_err(|e| decode_error(&path, e))?;
+
+        // Weights stored in external files are loaded right away, so the rest of the runtime
+        // only deals with in-memory tensors.
+        for tensor in model.mut_graph().mut_initializer().iter_mut() {
+            if tensor.get_data_location() == TensorProto_DataLocation::EXTERNAL {
+                Self::resolve_external_data(tensor, base_dir)?;
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            span.record("nodes", model.get_graph().get_node().len());
+            span.record("duration_ms", duration_ms(start));
+        }
 
         Ok(model)
     }
 
-    pub fn load_data(path: String) -> Result<TensorProto, OnnxError> {
-        let mut file = std::fs::File::open(path).map_err(|_| OnnxError::InternalError("Failed to open data file".to_string()))?;
+    /// Reads the data of a tensor stored in an external file (`location`, `offset` and `length`
+    /// entries of `external_data`, the location being relative to `base_dir`) into `raw_data`.
+    fn resolve_external_data(tensor: &mut TensorProto, base_dir: &Path) -> Result<(), OnnxError> {
+        let entry = |key: &str| {
+            tensor
+                .get_external_data()
+                .iter()
+                .find(|entry| entry.get_key() == key)
+                .map(|entry| entry.get_value().to_string())
+        };
+        let parse = |value: Option<String>| {
+            value
+                .map(|v| v.parse::<u64>().map_err(|_| OnnxError::ConversionError(format!("Invalid external data entry '{}'", v))))
+                .transpose()
+        };
+
+        let location = entry("location").ok_or(OnnxError::MissingInput(format!(
+            "External data location of tensor '{}'",
+            tensor.get_name()
+        )))?;
+        let offset = parse(entry("offset"))?.unwrap_or(0);
+        let length = parse(entry("length"))?;
+
+        let path = base_dir.join(&location).to_string_lossy().to_string();
+        let mut file = std::fs::File::open(&path).map_err(|e| OnnxError::io(&path, e))?;
+        file.seek(SeekFrom::Start(offset)).map_err(|e| OnnxError::io(&path, e))?;
+
+        let mut raw_data = Vec::new();
+        let read = match length {
+            Some(length) => file.take(length).read_to_end(&mut raw_data),
+            None => file.read_to_end(&mut raw_data),
+        };
+        read.map_err(|e| OnnxError::io(&path, e))?;
+
+        tensor.set_raw_data(raw_data);
+        tensor.clear_external_data();
+        tensor.set_data_location(TensorProto_DataLocation::DEFAULT);
+
+        Ok(())
+    }
+
+    pub fn load_data(path: String) -> Result<Tensor, OnnxError> {
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "parser.load_data",
+            path = path.as_str(),
+            shape = tracing::field::Empty,
+            decode_ms = tracing::field::Empty,
+            convert_ms = tracing::field::Empty,
+        )
+        .entered();
+
+        let proto = Self::load_tensor_proto(path)?;
+
+        #[cfg(feature = "tracing")]
+        let (decode_ms, start) = (duration_ms(start), std::time::Instant::now());
+
+        let tensor = Tensor::from_proto(&proto)?;
+
+        #[cfg(feature = "tracing")]
+        {
+            span.record("shape", tracing::field::debug(tensor.shape()));
+            span.record("decode_ms", decode_ms);
+            span.record("convert_ms", duration_ms(start));
+        }
+
+        Ok(tensor)
+    }
+
+    /// Loads a serialized `TensorProto` without converting it, e.g. for the tests working on the
+    /// ONNX messages.
+    pub fn load_tensor_proto(path: String) -> Result<TensorProto, OnnxError> {
+        let mut file = std::fs::File::open(&path).map_err(|e| OnnxError::io(&path, e))?;
         let mut stream = CodedInputStream::new(&mut file);
 
         let mut tensor = TensorProto::new();
-        tensor.merge_from(&mut stream).map_err(|_| OnnxError::InternalError("Failed to merge tensor from stream".to_string()))?;
+        tensor.merge_from(&mut stream).map_err(|e| decode_error(&path, e))?;
 
         Ok(tensor)
     }
 
+    /// The version of the operator set of `domain` imported by a model, if it imports one. The
+    /// empty domain and `ai.onnx` both name the default operator set.
+    pub fn opset_version(model: &ModelProto, domain: &str) -> Option<i64> {
+        model
+            .get_opset_import()
+            .iter()
+            .find(|opset| {
+                opset.get_domain() == domain
+                    || (is_default_domain(opset.get_domain()) && is_default_domain(domain))
+            })
+            .map(|opset| opset.get_version())
+    }
+
     #[allow(dead_code)]
     pub fn save_model(model: &ModelProto, path: String) -> Result<(), OnnxError> {
-        let mut file = std::fs::File::create(path).map_err(|_| OnnxError::InternalError("Failed to create model file".to_string()))?;
-        model.write_to_writer(&mut file).map_err(|_| OnnxError::InternalError("Failed to write model to file".to_string()))?;
+        let mut file = std::fs::File::create(&path).map_err(|e| OnnxError::io(&path, e))?;
+        model.write_to_writer(&mut file).map_err(|e| decode_error(&path, e))?;
         Ok(())
     }
 
+    pub fn save_data(tensor: &Tensor, path: String) -> Result<(), OnnxError> {
+        Self::save_tensor_proto(&tensor.to_proto()?, path)
+    }
+
     #[allow(dead_code)]
-    pub fn save_data(tensor: &TensorProto, path: String) -> Result<(), OnnxError> {
-        let mut file = std::fs::File::create(path).map_err(|_| OnnxError::InternalError("Failed to create data file".to_string()))?;
-        tensor.write_to_writer(&mut file).map_err(|_| OnnxError::InternalError("Failed to write tensor to file".to_string()))?;
+    pub fn save_tensor_proto(tensor: &TensorProto, path: String) -> Result<(), OnnxError> {
+        let mut file = std::fs::File::create(&path).map_err(|e| OnnxError::io(&path, e))?;
+        tensor.write_to_writer(&mut file).map_err(|e| decode_error(&path, e))?;
         Ok(())
     }
 }
+
+/// Whether a domain is the default (`ai.onnx`) one.
+pub(crate) fn is_default_domain(domain: &str) -> bool {
+    domain.is_empty() || domain == "ai.onnx"
+}
+
+/// Converts a protobuf error on a file: I/O failures (e.g. a directory given as model) are
+/// reported as such, the rest as decoding errors.
+fn decode_error<P: AsRef<Path>>(path: P, source: ProtobufError) -> OnnxError {
+    match source {
+        ProtobufError::IoError(source) => OnnxError::io(path, source),
+        source => OnnxError::ProtobufDecode {
+            path: path.as_ref().display().to_string(),
+            source,
+        },
+    }
+}
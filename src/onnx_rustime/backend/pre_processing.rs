@@ -1,13 +1,14 @@
 extern crate image;
 extern crate ndarray;
 
-use colored::Colorize;
-
-use image::{imageops, GenericImageView};
+use image::{imageops, GenericImageView, ImageError};
 use ndarray::{prelude::*, Array3, ArrayD};
 
-use crate::onnx_rustime::backend::{helper::OnnxError, parser::OnnxParser};
-use crate::onnx_rustime::ops::utils::ndarray_to_tensor_proto;
+#[cfg(feature = "tracing")]
+use crate::onnx_rustime::backend::run::duration_ms;
+use crate::onnx_rustime::backend::{
+    helper::OnnxError, parser::OnnxParser, style::Colorize, tensor::Tensor,
+};
 
 const MIN_SIZE: u32 = 256;
 const CROP_SIZE: u32 = 224;
@@ -26,18 +27,35 @@ const SCALE_FACTOR: f32 = 255.0;
 ///
 /// # Returns
 ///
-/// * `ArrayD<f32>` - Returns the preprocessed image as an ArrayD with shape (1, 3, CROP_SIZE, CROP_SIZE).
+/// * `Result<Tensor, OnnxError>` - Returns the preprocessed image as a float tensor named `data` with shape (1, 3, CROP_SIZE, CROP_SIZE),
+///   or an OnnxError if the image can't be opened or decoded.
 ///
 /// # Example
 ///
 /// ```rust
-/// let image_array = preprocess_image("/path/to/image.jpg".to_string());
+/// let image = preprocess_image("/path/to/image.jpg".to_string())?;
 /// ```
-fn preprocess_image(path: String) -> ArrayD<f32> {
+pub fn preprocess_image(path: String) -> Result<Tensor, OnnxError> {
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "preprocessing.image",
+        path = path.as_str(),
+        duration_ms = tracing::field::Empty,
+    )
+    .entered();
+
     // Load the image
-    let mut img = image::open(path).unwrap();
+    let mut img = image::open(&path).map_err(|e| match e {
+        ImageError::IoError(source) => OnnxError::io(&path, source),
+        e => OnnxError::InvalidValue(format!("Failed to decode image '{}': {}", path, e)),
+    })?;
 
     let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return Err(OnnxError::InvalidValue(format!("Image '{}' is empty", path)));
+    }
 
     // Resize the image with a minimum size of MIN_SIZE while maintaining the aspect ratio
     let (nwidth, nheight) = if width > height {
@@ -68,16 +86,18 @@ fn preprocess_image(path: String) -> ArrayD<f32> {
         bs.push(raw_data[3 * i + 2]);
     }
 
+    let shape_error = |e: ndarray::ShapeError| OnnxError::ShapeError(e.to_string());
     let r_array: Array2<u8> =
-        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), rs).unwrap();
+        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), rs).map_err(shape_error)?;
     let g_array: Array2<u8> =
-        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), gs).unwrap();
+        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), gs).map_err(shape_error)?;
     let b_array: Array2<u8> =
-        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), bs).unwrap();
+        Array::from_shape_vec((CROP_SIZE as usize, CROP_SIZE as usize), bs).map_err(shape_error)?;
 
     // Stack them to make an Array3
     let mut arr: Array3<u8> =
-        ndarray::stack(Axis(2), &[r_array.view(), g_array.view(), b_array.view()]).unwrap();
+        ndarray::stack(Axis(2), &[r_array.view(), g_array.view(), b_array.view()])
+            .map_err(shape_error)?;
     // Transpose it from HWC to CHW layout
     arr.swap_axes(0, 2);
 
@@ -89,7 +109,7 @@ fn preprocess_image(path: String) -> ArrayD<f32> {
             MEAN[2] * SCALE_FACTOR,
         ],
     )
-    .unwrap();
+    .map_err(shape_error)?;
 
     let std = Array::from_shape_vec(
         (3, 1, 1),
@@ -99,7 +119,7 @@ fn preprocess_image(path: String) -> ArrayD<f32> {
             STD[2] * SCALE_FACTOR,
         ],
     )
-    .unwrap();
+    .map_err(shape_error)?;
 
     let mut arr_f: Array3<f32> = arr.mapv(|x| x as f32);
 
@@ -110,14 +130,17 @@ fn preprocess_image(path: String) -> ArrayD<f32> {
     let arr_f_batch: Array4<f32> = arr_f.insert_axis(Axis(0));
 
     // Convert Array4 to ArrayD
-    let arr_d: ArrayD<f32> = arr_f_batch.into_dimensionality().unwrap();
+    let arr_d: ArrayD<f32> = arr_f_batch.into_dyn();
 
-    arr_d
+    #[cfg(feature = "tracing")]
+    span.record("duration_ms", duration_ms(start));
+
+    Ok(Tensor::from_ndarray(arr_d).with_name("data"))
 }
 
 /// `serialize_image` - Preprocesses and serializes an image, saving it to a file.
 ///
-/// This function preprocesses an image and converts it to a tensor proto, which is then saved to a file.
+/// This function preprocesses an image and saves the resulting tensor to a file as a TensorProto.
 ///
 /// # Arguments
 ///
@@ -134,17 +157,13 @@ fn preprocess_image(path: String) -> ArrayD<f32> {
 /// let result = serialize_image("/input/image.jpg".to_string(), "/output/data.pb".to_string());
 /// ```
 pub fn serialize_image(input_path: String, output_path: String) -> Result<(), OnnxError> {
-    println!("{}", "🚀 Starting to preprocess the image...");
-
-    let img_ndarray = preprocess_image(input_path);
-
-    println!("{}", "✅ Image preprocessed. Converting to tensor proto...");
+    println!("🚀 Starting to preprocess the image...");
 
-    let img_tensorproto = ndarray_to_tensor_proto::<f32>(img_ndarray, "data")?;
+    let img_tensor = preprocess_image(input_path)?;
 
-    println!("{}", "✅ Tensor proto created. Saving data...");
+    println!("✅ Image preprocessed. Saving data...");
 
-    let result = OnnxParser::save_data(&img_tensorproto, output_path.clone());
+    let result = OnnxParser::save_data(&img_tensor, output_path.clone());
 
     match result {
         Ok(_) => println!(
@@ -1,12 +1,167 @@
+use crate::onnx_rustime::backend::format::FormatOptions;
 use crate::onnx_rustime::backend::helper::OnnxError;
-use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{ModelProto, NodeProto, TensorProto};
+use crate::onnx_rustime::backend::parser::OnnxParser;
+use crate::onnx_rustime::backend::style::Colorize;
+use crate::onnx_rustime::backend::tensor::Tensor;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, ModelProto, NodeProto, TensorProto,
+};
+use crate::onnx_rustime::ops::dropout::is_training_mode;
+use crate::onnx_rustime::ops::flatten::flattened_shape;
+use crate::onnx_rustime::ops::reference::run_reference_node;
+use crate::onnx_rustime::ops::reshape::constant_reshaped_shape;
 use crate::onnx_rustime::ops::*;
-use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::collections::HashMap;
-use std::time::Instant;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::onnx_rustime::shared::VERBOSE;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::TensorProto_DataType;
+use crate::onnx_rustime::ops::add::add_ndarray;
+use crate::onnx_rustime::ops::batch_normalization::batch_normalization_ndarray;
+use crate::onnx_rustime::ops::conv::conv_ndarray;
+use crate::onnx_rustime::ops::gemm::gemm_ndarray;
+use crate::onnx_rustime::ops::global_average_pool::global_average_pooling;
+use crate::onnx_rustime::ops::matmul::matmul_ndarray;
+use crate::onnx_rustime::ops::maxpool::maxpool_ndarray;
+use crate::onnx_rustime::ops::relu::relu_ndarray;
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use crate::onnx_rustime::shared::{CancellationToken, NonFiniteCheck, RunOptions};
+use half::f16;
+use ndarray::ArrayD;
+use protobuf::ProtobufEnum;
+
+/// A tensor computed by a run.
+///
+/// The FLOAT tensors computed by the kernels running on arrays (see `run_ndarray_node`) are kept
+/// as arrays, in standard layout like the decoded ones, so that the nodes reading them don't
+/// decode them again; the other tensors are kept as the `TensorProto` of their kernel. An array is
+/// only encoded when a kernel taking `TensorProto`s reads it, or when it is returned by the run.
+#[derive(Debug, Clone)]
+enum Value {
+    Array(Arc<ArrayD<f32>>),
+    Proto(Arc<TensorProto>),
+}
+
+impl Value {
+    fn from_array(array: ArrayD<f32>) -> Self {
+        let array = if array.is_standard_layout() {
+            array
+        } else {
+            array.as_standard_layout().into_owned()
+        };
+        Value::Array(Arc::new(array))
+    }
+
+    #[cfg(feature = "tracing")]
+    fn dims(&self) -> Vec<i64> {
+        match self {
+            Value::Array(array) => array.shape().iter().map(|&dim| dim as i64).collect(),
+            Value::Proto(tensor) => tensor.get_dims().to_vec(),
+        }
+    }
+
+    fn data_type(&self) -> i32 {
+        match self {
+            Value::Array(_) => TensorProto_DataType::FLOAT.value(),
+            Value::Proto(tensor) => tensor.get_data_type(),
+        }
+    }
+
+    /// The values of a FLOAT tensor, decoded if the tensor is a `TensorProto`.
+    fn array(&self) -> Option<Arc<ArrayD<f32>>> {
+        match self {
+            Value::Array(array) => Some(Arc::clone(array)),
+            Value::Proto(tensor) => float_array(tensor),
+        }
+    }
+
+    /// The tensor as a `TensorProto` named `name`, copied only if it is still shared.
+    fn into_proto(self, name: &str) -> Result<TensorProto, OnnxError> {
+        match self {
+            Value::Array(array) => {
+                let array = Arc::try_unwrap(array).unwrap_or_else(|shared| (*shared).clone());
+                ndarray_to_tensor_proto::<f32>(array, name)
+            }
+            Value::Proto(tensor) => {
+                let mut tensor = Arc::try_unwrap(tensor).unwrap_or_else(|shared| (*shared).clone());
+                tensor.set_name(name.to_string());
+                Ok(tensor)
+            }
+        }
+    }
+
+    /// The tensor with new dimensions, its values copied only if it is still shared.
+    fn reshaped(self, dims: Vec<i64>, name: &str) -> Result<Self, OnnxError> {
+        match self {
+            Value::Array(array) => {
+                let array = Arc::try_unwrap(array).unwrap_or_else(|shared| (*shared).clone());
+                let shape: Vec<usize> = dims.iter().map(|&dim| dim as usize).collect();
+                array
+                    .into_shape(shape)
+                    .map(|array| Value::Array(Arc::new(array)))
+                    .map_err(|e| OnnxError::ShapeError(e.to_string()))
+            }
+            Value::Proto(mut tensor) => {
+                let tensor_mut = Arc::make_mut(&mut tensor);
+                tensor_mut.set_dims(dims);
+                tensor_mut.set_name(name.to_string());
+                Ok(Value::Proto(tensor))
+            }
+        }
+    }
+}
+
+/// Decodes a FLOAT tensor, or returns `None` for the other element types (and invalid data, left
+/// to the kernels to report).
+fn float_array(tensor: &TensorProto) -> Option<Arc<ArrayD<f32>>> {
+    if tensor.get_data_type() != TensorProto_DataType::FLOAT.value() {
+        return None;
+    }
+    tensor_proto_to_ndarray::<f32>(tensor).ok().map(Arc::new)
+}
+
+/// Tensors computed by a run, by name.
+///
+/// They are shared rather than copied: a pass-through node stores the tensor of its input under
+/// the name of its output, so the name of a tensor is the key it is stored under, which may differ
+/// from its `TensorProto::get_name`.
+type TensorMap = HashMap<String, Value>;
+
+/// An initializer of a run, borrowed from the model (or from the inputs bound to it) for the whole
+/// run, with its values if it is a FLOAT tensor of the model, converted once by the plan.
+#[derive(Clone)]
+struct Initializer<'a> {
+    proto: &'a TensorProto,
+    array: Option<Arc<ArrayD<f32>>>,
+}
+
+impl Initializer<'_> {
+    fn array(&self) -> Option<Arc<ArrayD<f32>>> {
+        self.array.clone().or_else(|| float_array(self.proto))
+    }
+
+    fn value(&self) -> Value {
+        match &self.array {
+            Some(array) => Value::Array(Arc::clone(array)),
+            None => Value::Proto(Arc::new(self.proto.clone())),
+        }
+    }
+}
+
+/// Initializers by name.
+type InitializerMap<'a> = HashMap<&'a str, Initializer<'a>>;
+
+/// An operand of a node.
+#[derive(Clone, Copy)]
+struct Operand<'a> {
+    /// The name of the node input (see `TensorMap`).
+    name: &'a str,
+    tensor: &'a TensorProto,
+    initializer: bool,
+}
 
 /// Executes a given ONNX model using the provided input tensor and returns the output tensor.
 ///
@@ -17,11 +172,28 @@ use crate::onnx_rustime::shared::VERBOSE;
 ///
 /// * `model` - The ONNX model to be executed.
 /// * `input_tensor` - The input tensor for the model.
+/// * `options` - The options of the run (verbosity, NaN checks, threads, ...).
 ///
 /// # Returns
 ///
-/// * `TensorProto` - The output tensor after model execution.
-pub fn run(model: &ModelProto, input_tensor: TensorProto) -> TensorProto {
+/// * `Result<TensorProto, OnnxError>` - The output tensor after model execution, or the error of
+///   the first failing node.
+pub fn run(
+    model: &ModelProto,
+    input_tensor: TensorProto,
+    options: &RunOptions,
+) -> Result<TensorProto, OnnxError> {
+    let plan = ExecutionPlan::new(model, options.threads)?;
+    run_planned(model, &plan, input_tensor, options)
+}
+
+/// `run`, with the plan prepared once for all the runs of a `Session`.
+pub(crate) fn run_planned(
+    model: &ModelProto,
+    plan: &ExecutionPlan,
+    input_tensor: TensorProto,
+    options: &RunOptions,
+) -> Result<TensorProto, OnnxError> {
     // Capture the current time before running the model
     let start = Instant::now();
 
@@ -29,15 +201,19 @@ pub fn run(model: &ModelProto, input_tensor: TensorProto) -> TensorProto {
     let graph = model.get_graph();
 
     // Initialize a map to hold the tensors for each node's input.
-    let mut input_map: HashMap<String, TensorProto> = HashMap::new();
-    input_map.insert(graph.input[0].name.clone(), input_tensor);
+    let mut input_map = TensorMap::new();
+    let input_name = graph
+        .get_input()
+        .first()
+        .ok_or(OnnxError::MissingInput("The graph has no input".to_string()))?
+        .get_name();
+    input_map.insert(
+        input_name.to_string(),
+        Value::Proto(Arc::new(input_tensor)),
+    );
 
     // Map the initializers by their names for easy lookup.
-    let initializers_map: HashMap<String, TensorProto> = graph
-        .get_initializer()
-        .iter()
-        .map(|tensor_proto| (tensor_proto.name.clone(), tensor_proto.clone()))
-        .collect();
+    let initializers_map = initializer_map(graph, &[], &plan.initializers);
 
     // Initialize a progress bar
     let bar = ProgressBar::new(graph.get_node().len() as u64);
@@ -48,22 +224,457 @@ pub fn run(model: &ModelProto, input_tensor: TensorProto) -> TensorProto {
             .progress_chars("█▁"),
     );
 
-    // Iterate over each node in the graph.
-    for node in &graph.node {
-        // Gather the inputs for the current node.
-        let node_inputs: Vec<_> = node
-            .get_input()
-            .iter()
-            .filter_map(|name| input_map.get(name))
-            .collect();
+    let result =
+        plan.install(|| execute_graph(graph, plan, input_map, &initializers_map, &bar, options));
+    bar.finish();
+    let mut input_map = result?;
 
-        // Gather the initializers for the current node.
-        let node_initializers: Vec<_> = node
-            .get_input()
+    let duration = start.elapsed();
+    println!("\n\n{} ({:?})\n", "🦀 SUCCESSFULLY RUN NETWORK!".bold().magenta(), duration);
+
+    // Return the output tensor for the entire model.
+    let output_name = graph
+        .get_output()
+        .first()
+        .map(|output| output.get_name())
+        .unwrap_or_default();
+    take_tensor(&mut input_map, output_name).ok_or(OnnxError::MissingInput(format!(
+        "Output tensor '{}' was not computed",
+        output_name
+    )))?
+}
+
+/// Executes a given ONNX model on several inputs and returns all its outputs, without printing
+/// anything.
+///
+/// The inputs are bound, in order, to the graph inputs that are not initializers. As in the
+/// models `run` is meant for, only the first input flows through the network as data: the
+/// others are bound as initializers (weights), replacing the initializers with the same name.
+///
+/// # Arguments
+///
+/// * `model` - The ONNX model to be executed.
+/// * `inputs` - The input tensors, in the order of the graph inputs.
+/// * `options` - The options of the run.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - The output tensors, in the order of the graph
+///   outputs, or the first error raised while running the nodes.
+pub fn run_with_inputs(
+    model: &ModelProto,
+    inputs: Vec<TensorProto>,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let plan = ExecutionPlan::new(model, options.threads)?;
+    run_with_inputs_planned(model, &plan, inputs, options)
+}
+
+/// `run_with_inputs`, with the plan prepared once for all the runs of a `Session`.
+pub(crate) fn run_with_inputs_planned(
+    model: &ModelProto,
+    plan: &ExecutionPlan,
+    inputs: Vec<TensorProto>,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let graph = model.get_graph();
+    let (input_map, bound_initializers) = bind_inputs(graph, inputs)?;
+    let initializers_map = initializer_map(graph, &bound_initializers, &plan.initializers);
+
+    let mut input_map = plan.install(|| {
+        execute_graph(
+            graph,
+            plan,
+            input_map,
+            &initializers_map,
+            &ProgressBar::hidden(),
+            options,
+        )
+    })?;
+
+    graph
+        .get_output()
+        .iter()
+        .map(|output| {
+            take_tensor(&mut input_map, output.get_name())
+                .or_else(|| {
+                    initializers_map
+                        .get(output.get_name())
+                        .map(|initializer| Ok(initializer.proto.clone()))
+                })
+                .ok_or(OnnxError::MissingInput(format!(
+                    "Output tensor '{}' not found",
+                    output.get_name()
+                )))?
+        })
+        .collect()
+}
+
+/// Executes a given ONNX model like `run_with_inputs`, but returns every tensor computed while
+/// running it, by name: the inputs, the outputs and all the intermediate values.
+///
+/// # Arguments
+///
+/// * `model` - The ONNX model to be executed.
+/// * `inputs` - The input tensors, in the order of the graph inputs.
+/// * `options` - The options of the run.
+///
+/// # Returns
+///
+/// * `Result<HashMap<String, TensorProto>, OnnxError>` - The tensors by name (initializers
+///   excluded), or the first error raised while running the nodes.
+pub fn run_with_intermediates(
+    model: &ModelProto,
+    inputs: Vec<TensorProto>,
+    options: &RunOptions,
+) -> Result<HashMap<String, TensorProto>, OnnxError> {
+    let graph = model.get_graph();
+    let mut plan = ExecutionPlan::new(model, options.threads)?;
+    plan.keep_tensors = true;
+    let (input_map, bound_initializers) = bind_inputs(graph, inputs)?;
+    let initializers_map = initializer_map(graph, &bound_initializers, &plan.initializers);
+
+    let mut input_map = plan.install(|| {
+        execute_graph(
+            graph,
+            &plan,
+            input_map,
+            &initializers_map,
+            &ProgressBar::hidden(),
+            options,
+        )
+    })?;
+
+    let names: Vec<String> = input_map.keys().cloned().collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            take_tensor(&mut input_map, &name).map(|tensor| tensor.map(|tensor| (name, tensor)))
+        })
+        .collect()
+}
+
+/// What the runs of a graph share, prepared once (by a `Session`, or by every call of `run` and
+/// of its variants): the number of uses of every tensor, the FLOAT initializers decoded to arrays,
+/// the operator set of the model and the thread pool. It is never modified by a run, so that
+/// concurrent runs don't wait on each other; the state of a run is kept in a `RunContext` of its
+/// own.
+#[derive(Debug)]
+pub(crate) struct ExecutionPlan {
+    /// Number of node inputs and graph outputs reading every tensor, by name.
+    uses: HashMap<String, usize>,
+    /// The FLOAT initializers of the graph, by name, decoded once and shared by the nodes of
+    /// every run reading them (see `run_ndarray_node`).
+    initializers: HashMap<String, Arc<ArrayD<f32>>>,
+    /// Whether the tensors stay in the `TensorMap` once read by their last node, for
+    /// `run_with_intermediates`: the kernels running on arrays then never overwrite them.
+    keep_tensors: bool,
+    /// The version of the default operator set imported by the model, which selects the
+    /// semantics of the operations that changed (e.g. `Softmax` in opset 13).
+    opset: i64,
+    /// The pool running the kernels, or `None` for the global rayon pool.
+    pool: Option<ThreadPool>,
+}
+
+impl ExecutionPlan {
+    /// Counts the uses of the tensors of the graph of a model, decodes its FLOAT initializers,
+    /// and builds a pool of `threads` threads if any. Models without an import of the default
+    /// operator set are assumed to target the latest version.
+    pub(crate) fn new(model: &ModelProto, threads: Option<usize>) -> Result<Self, OnnxError> {
+        let uses = tensor_uses(model.get_graph());
+        let initializers = model
+            .get_graph()
+            .get_initializer()
             .iter()
-            .filter_map(|name| initializers_map.get(name))
+            .filter_map(|tensor| Some((tensor.get_name().to_string(), float_array(tensor)?)))
             .collect();
 
+        let pool = threads
+            .map(|threads| ThreadPoolBuilder::new().num_threads(threads).build())
+            .transpose()
+            .map_err(|e| {
+                OnnxError::InternalError(format!("Failed to create a thread pool: {}", e))
+            })?;
+
+        let opset = OnnxParser::opset_version(model, "").unwrap_or(LATEST_OPSET);
+
+        Ok(ExecutionPlan {
+            uses,
+            initializers,
+            keep_tensors: false,
+            opset,
+            pool,
+        })
+    }
+
+    /// The plan of a subgraph of the model (e.g. a branch of an `If`), prepared at every run of
+    /// the node owning it. The subgraph runs in the pool of the enclosing graph, and its
+    /// initializers are decoded by the nodes reading them.
+    fn subgraph(&self, graph: &GraphProto) -> Self {
+        ExecutionPlan {
+            uses: tensor_uses(graph),
+            initializers: HashMap::new(),
+            keep_tensors: false,
+            opset: self.opset,
+            pool: None,
+        }
+    }
+
+    /// Runs `f` in the pool of the plan, or in the global pool if it has none. Several runs can
+    /// share the pool at the same time.
+    fn install<T: Send>(
+        &self,
+        f: impl FnOnce() -> Result<T, OnnxError> + Send,
+    ) -> Result<T, OnnxError> {
+        // The spans of the run are recorded by the subscriber of the caller, under its current
+        // span, even from a thread of the pool.
+        #[cfg(feature = "tracing")]
+        let f = {
+            let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+            let span = tracing::Span::current();
+            move || tracing::dispatcher::with_default(&dispatch, || span.in_scope(f))
+        };
+
+        match &self.pool {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+}
+
+/// Counts the node inputs and graph outputs reading every tensor of a graph, by name (see
+/// `read_names`).
+fn tensor_uses(graph: &GraphProto) -> HashMap<String, usize> {
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    let names = graph
+        .get_node()
+        .iter()
+        .flat_map(read_names)
+        .chain(graph.get_output().iter().map(|output| output.get_name()));
+    for name in names {
+        *uses.entry(name.to_string()).or_insert(0) += 1;
+    }
+    uses
+}
+
+/// The names of the tensors a node reads: its inputs, and the ones read by the nodes and outputs
+/// of its subgraphs (e.g. the branches of an `If`), which can capture the tensors of the
+/// enclosing graph by name.
+fn read_names(node: &NodeProto) -> Vec<&str> {
+    let mut names: Vec<&str> = node.get_input().iter().map(String::as_str).collect();
+    for attribute in node.get_attribute() {
+        let graph = attribute.has_g().then(|| attribute.get_g());
+        for subgraph in graph.into_iter().chain(attribute.get_graphs()) {
+            names.extend(subgraph.get_node().iter().flat_map(read_names));
+            names.extend(subgraph.get_output().iter().map(|output| output.get_name()));
+        }
+    }
+    names
+}
+
+/// The state of one run, which isn't shared with the other runs of the same plan.
+struct RunContext<'a> {
+    /// The tensors computed so far.
+    tensors: TensorMap,
+    /// Number of uses of every tensor by the nodes still to run (and the graph outputs), so that
+    /// a tensor can be moved instead of shared when its last consumer is a forwarding node.
+    remaining_uses: HashMap<&'a str, usize>,
+    /// Whether the tensors read by their last node are kept (see `ExecutionPlan::keep_tensors`).
+    keep_tensors: bool,
+    /// Number of nodes and time spent, by operation type (see `RunOptions::profiling`).
+    profile: BTreeMap<&'a str, (usize, Duration)>,
+    /// Stops the run once cancelled (see `RunOptions::cancellation`).
+    cancellation: Option<&'a CancellationToken>,
+}
+
+impl<'a> RunContext<'a> {
+    fn new(plan: &'a ExecutionPlan, tensors: TensorMap, options: &'a RunOptions) -> Self {
+        RunContext {
+            tensors,
+            remaining_uses: plan
+                .uses
+                .iter()
+                .map(|(name, &uses)| (name.as_str(), uses))
+                .collect(),
+            keep_tensors: plan.keep_tensors,
+            profile: BTreeMap::new(),
+            cancellation: options.cancellation.as_ref(),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+}
+
+/// Maps the initializers of a graph by name, with their arrays decoded by the plan, followed by
+/// `bound` which replace the ones with the same name. The tensors are borrowed and the arrays
+/// shared, so that a run (or the runs of a `Session`) doesn't copy the weights of the model.
+fn initializer_map<'a>(
+    graph: &'a GraphProto,
+    bound: &'a [TensorProto],
+    arrays: &HashMap<String, Arc<ArrayD<f32>>>,
+) -> InitializerMap<'a> {
+    let initializers = graph.get_initializer().iter().map(|tensor| Initializer {
+        proto: tensor,
+        array: arrays.get(tensor.get_name()).cloned(),
+    });
+    let bound = bound.iter().map(|tensor| Initializer {
+        proto: tensor,
+        array: None,
+    });
+    initializers
+        .chain(bound)
+        .map(|initializer| (initializer.proto.get_name(), initializer))
+        .collect()
+}
+
+/// Binds the inputs to the graph inputs that are not initializers, returning the tensors flowing
+/// through the network and the ones bound as initializers. See `run_with_inputs`.
+fn bind_inputs(
+    graph: &GraphProto,
+    inputs: Vec<TensorProto>,
+) -> Result<(TensorMap, Vec<TensorProto>), OnnxError> {
+    let initializer_names: HashSet<&str> = graph
+        .get_initializer()
+        .iter()
+        .map(|tensor| tensor.get_name())
+        .collect();
+
+    let input_names: Vec<String> = graph
+        .get_input()
+        .iter()
+        .map(|input| input.get_name().to_string())
+        .filter(|name| !initializer_names.contains(name.as_str()))
+        .collect();
+    if inputs.len() > input_names.len() {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected at most {} inputs, got {}",
+            input_names.len(),
+            inputs.len()
+        )));
+    }
+
+    let mut input_map = TensorMap::new();
+    let mut bound_initializers = Vec::new();
+    for (index, (name, mut tensor)) in input_names.into_iter().zip(inputs).enumerate() {
+        tensor.set_name(name.clone());
+        if index == 0 {
+            input_map.insert(name, Value::Proto(Arc::new(tensor)));
+        } else {
+            bound_initializers.push(tensor);
+        }
+    }
+
+    Ok((input_map, bound_initializers))
+}
+
+/// Removes a tensor from the map and names it, copying it only if it is still shared with another
+/// name (see `Value::into_proto`).
+fn take_tensor(input_map: &mut TensorMap, name: &str) -> Option<Result<TensorProto, OnnxError>> {
+    input_map.remove(name).map(|value| value.into_proto(name))
+}
+
+/// Runs the nodes of a graph in order, starting from the given inputs, and returns every tensor
+/// computed along the way (inputs included).
+fn execute_graph<'a>(
+    graph: &'a GraphProto,
+    plan: &'a ExecutionPlan,
+    input_map: TensorMap,
+    initializers_map: &InitializerMap,
+    bar: &ProgressBar,
+    options: &'a RunOptions,
+) -> Result<TensorMap, OnnxError> {
+    let mut context = RunContext::new(plan, input_map, options);
+    let node_count = graph.get_node().len();
+
+    #[cfg(feature = "tracing")]
+    let run_start = Instant::now();
+    #[cfg(feature = "tracing")]
+    let run_span = tracing::info_span!(
+        "session.run",
+        model = graph.get_name(),
+        input_shapes = ?context.tensors.values().map(|value| value.dims()).collect::<Vec<_>>(),
+        duration_ms = tracing::field::Empty,
+    )
+    .entered();
+
+    // Iterate over each node in the graph.
+    for (node_index, node) in graph.get_node().iter().enumerate() {
+        if context.is_cancelled() {
+            return Err(OnnxError::Cancelled);
+        }
+
+        #[cfg(feature = "tracing")]
+        let node_start = Instant::now();
+        #[cfg(feature = "tracing")]
+        let node_span = tracing::debug_span!(
+            "node",
+            op_type = node.get_op_type(),
+            name = node.get_name(),
+            output_shape = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        )
+        .entered();
+
+        for name in read_names(node) {
+            if let Some(uses) = context.remaining_uses.get_mut(name) {
+                *uses -= 1;
+            }
+        }
+
+        // Pass-through and reshaping nodes reuse the tensor of their input, without converting
+        // it to an ndarray and back, unless a custom kernel replaces them.
+        let forwarded = if options.custom_ops.contains_key(node.get_op_type()) {
+            None
+        } else {
+            forwarded_input(node, &context.tensors, initializers_map)
+        };
+        if let Some((source, dims)) = forwarded {
+            let output_name = node.get_output()[0].clone();
+            let mut output = if context.remaining_uses.get(source.as_str()) == Some(&0) {
+                context.tensors.remove(&source)
+            } else {
+                None
+            }
+            .or_else(|| context.tensors.get(&source).cloned())
+            .or_else(|| {
+                initializers_map
+                    .get(source.as_str())
+                    .map(Initializer::value)
+            })
+            .ok_or(OnnxError::MissingInput(format!(
+                "Input '{}' of node '{}' not found",
+                source,
+                node.get_name()
+            )))?;
+
+            match dims {
+                // The values are copied only if the input is still used under another name.
+                Some(dims) => output = output.reshaped(dims, &output_name)?,
+                None => {
+                    if let Value::Proto(tensor) = &mut output {
+                        if let Some(tensor) = Arc::get_mut(tensor) {
+                            tensor.set_name(output_name.clone());
+                        }
+                    }
+                }
+            }
+
+            bar.println(format!(
+                "{} {} {}",
+                "🚀 Forwarding Node:".bold(),
+                node.get_op_type(),
+                node.get_name()
+            ));
+            #[cfg(feature = "tracing")]
+            record_node(&node_span, &output, node_start);
+            context.tensors.insert(output_name, output);
+            bar.inc(1);
+            continue;
+        }
+
         bar.println(format!(
             "{} {} {}",
             "🚀 Running Node:".bold(),
@@ -71,29 +682,735 @@ pub fn run(model: &ModelProto, input_tensor: TensorProto) -> TensorProto {
             node.get_name()
         ));
 
-        let output_tensor = if *VERBOSE.lock().unwrap() {
-            run_node_verbose(&bar, node, &node_inputs, &node_initializers)
-        } else {
-            run_node(node, &node_inputs, &node_initializers).expect("Failed to run node")
+        let start = Instant::now();
+        let outputs = match ndarray_operands(node, &mut context, initializers_map, options) {
+            Some(operands) => {
+                let shapes: Vec<(&str, Vec<i64>)> = node
+                    .get_input()
+                    .iter()
+                    .zip(&operands)
+                    .filter_map(|(name, operand)| {
+                        let dims = operand
+                            .as_ref()?
+                            .array
+                            .shape()
+                            .iter()
+                            .map(|&dim| dim as i64);
+                        Some((name.as_str(), dims.collect()))
+                    })
+                    .collect();
+                let output = run_ndarray_node(node, operands)
+                    .and_then(|output| {
+                        let name = node
+                            .get_output()
+                            .first()
+                            .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+                        Ok((name.clone(), Value::from_array(output)))
+                    })
+                    .map_err(|e| {
+                        let named: Vec<(&str, &[i64])> = shapes
+                            .iter()
+                            .map(|(name, dims)| (*name, dims.as_slice()))
+                            .collect();
+                        e.with_node_context(node, &named)
+                            .at_node(node_index + 1, node_count, node)
+                    })?;
+                // The operands are only logged in verbose mode, which runs the kernels taking
+                // `TensorProto`s.
+                if options.non_finite_check != NonFiniteCheck::Off {
+                    check_finite(bar, node, &output.1, &[], options)?;
+                }
+                vec![output]
+            }
+            None => {
+                // The kernels taking `TensorProto`s read the arrays computed by the graph encoded.
+                let encoded: HashMap<&str, TensorProto> = node
+                    .get_input()
+                    .iter()
+                    .filter_map(|name| match context.tensors.get(name) {
+                        Some(value @ Value::Array(_)) => Some(
+                            value
+                                .clone()
+                                .into_proto(name)
+                                .map(|tensor| (name.as_str(), tensor)),
+                        ),
+                        _ => None,
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                // Gather the operands of the current node, in the order of its inputs.
+                let operands: Vec<Operand> = node
+                    .get_input()
+                    .iter()
+                    .filter_map(|name| {
+                        let computed = match context.tensors.get(name) {
+                            Some(Value::Proto(tensor)) => Some(tensor.as_ref()),
+                            Some(Value::Array(_)) => encoded.get(name.as_str()),
+                            None => None,
+                        };
+                        computed
+                            .map(|tensor| Operand {
+                                name,
+                                tensor,
+                                initializer: false,
+                            })
+                            .or_else(|| {
+                                initializers_map
+                                    .get(name.as_str())
+                                    .map(|initializer| Operand {
+                                        name,
+                                        tensor: initializer.proto,
+                                        initializer: true,
+                                    })
+                            })
+                    })
+                    .collect();
+
+                let output_tensors = if node.get_op_type() == "If"
+                    && !options.custom_ops.contains_key(node.get_op_type())
+                {
+                    // The branches read the tensors of the graph, not only the operands of the
+                    // node.
+                    run_if(
+                        node,
+                        &operands,
+                        &context.tensors,
+                        initializers_map,
+                        plan,
+                        options,
+                    )
+                } else if options.verbose {
+                    run_node_verbose(bar, node, &operands, plan.opset, options)
+                } else {
+                    run_node(node, &operands, plan.opset, options)
+                }
+                .map_err(|e| {
+                    let named: Vec<(&str, &[i64])> = operands
+                        .iter()
+                        .map(|operand| (operand.name, operand.tensor.get_dims()))
+                        .collect();
+                    e.with_node_context(node, &named)
+                        .at_node(node_index + 1, node_count, node)
+                })?;
+
+                let outputs: Vec<(String, Value)> = output_tensors
+                    .into_iter()
+                    .map(|tensor| {
+                        (
+                            tensor.get_name().to_string(),
+                            Value::Proto(Arc::new(tensor)),
+                        )
+                    })
+                    .collect();
+                if options.non_finite_check != NonFiniteCheck::Off {
+                    for (_, output) in &outputs {
+                        check_finite(bar, node, output, &operands, options)?;
+                    }
+                }
+                outputs
+            }
         };
+        if options.profiling {
+            let entry = context.profile.entry(node.get_op_type()).or_default();
+            entry.0 += 1;
+            entry.1 += start.elapsed();
+        }
 
-        let output_name = output_tensor.get_name().to_string();
-        // Store the output tensor so it can be used as input for subsequent nodes.
-        input_map.insert(output_name, output_tensor);
+        #[cfg(feature = "tracing")]
+        if let Some((_, output)) = outputs.first() {
+            record_node(&node_span, output, node_start);
+        }
+
+        // Store the outputs so they can be used as inputs for subsequent nodes.
+        context.tensors.extend(outputs);
 
         // Increment the progress bar
         bar.inc(1);
     }
-    bar.finish();
 
-    let duration = start.elapsed();
-    println!("\n\n{} ({:?})\n", "🦀 SUCCESSFULLY RUN NETWORK!".bold().magenta(), duration);
+    if options.profiling {
+        log_profile(bar, context.profile);
+    }
 
-    // Return the output tensor for the entire model.
-    input_map
-        .get(&graph.get_output()[0].name)
-        .expect("Output tensor not found")
-        .clone()
+    #[cfg(feature = "tracing")]
+    run_span.record("duration_ms", duration_ms(run_start));
+
+    Ok(context.tensors)
+}
+
+/// Runs an `If` node: its `then_branch` subgraph if its BOOL condition is true, its `else_branch`
+/// otherwise, in the scope of the enclosing graph (see `run_subgraph`). The outputs of the branch
+/// are named after the outputs of the node: the two branches can give outputs of different shapes.
+fn run_if(
+    node: &NodeProto,
+    operands: &[Operand],
+    tensors: &TensorMap,
+    initializers_map: &InitializerMap,
+    plan: &ExecutionPlan,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let condition = operands
+        .first()
+        .ok_or(OnnxError::MissingInput("cond".to_string()))?;
+    let condition = tensor_proto_to_ndarray::<bool>(condition.tensor)?;
+    let condition = match condition.iter().next() {
+        Some(&value) if condition.len() == 1 => value,
+        _ => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The condition of an If must be a single value, got a tensor of shape {:?}",
+                condition.shape()
+            )))
+        }
+    };
+
+    let branch_name = if condition {
+        "then_branch"
+    } else {
+        "else_branch"
+    };
+    let branch = node
+        .get_attribute()
+        .iter()
+        .find(|attribute| attribute.get_name() == branch_name && attribute.has_g())
+        .map(|attribute| attribute.get_g())
+        .ok_or(OnnxError::AttributeNotFound(branch_name.to_string()))?;
+    let outputs = run_subgraph(branch, tensors, initializers_map, plan, options)?;
+    if outputs.len() != node.get_output().len() {
+        return Err(OnnxError::InvalidValue(format!(
+            "The {} of the If has {} outputs, the node {}",
+            branch_name,
+            outputs.len(),
+            node.get_output().len()
+        )));
+    }
+
+    Ok(outputs
+        .into_iter()
+        .zip(node.get_output())
+        .map(|(mut output, name)| {
+            output.set_name(name.clone());
+            output
+        })
+        .collect())
+}
+
+/// Runs a subgraph (e.g. a branch of an `If`) in the scope of the graph enclosing it, and returns
+/// its outputs in order. Its nodes read the tensors computed so far and the initializers of the
+/// enclosing graphs, unless the subgraph defines its own under the same names.
+fn run_subgraph(
+    graph: &GraphProto,
+    tensors: &TensorMap,
+    initializers_map: &InitializerMap,
+    plan: &ExecutionPlan,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let plan = plan.subgraph(graph);
+    let mut scope: InitializerMap = initializers_map.clone();
+    scope.extend(initializer_map(graph, &[], &plan.initializers));
+    // The time of the subgraph is profiled as the one of the node owning it.
+    let unprofiled;
+    let options = match options.profiling {
+        true => {
+            unprofiled = RunOptions {
+                profiling: false,
+                ..options.clone()
+            };
+            &unprofiled
+        }
+        false => options,
+    };
+
+    // The tensors of the enclosing graph are shared, not copied.
+    let mut tensors = execute_graph(
+        graph,
+        &plan,
+        tensors.clone(),
+        &scope,
+        &ProgressBar::hidden(),
+        options,
+    )?;
+
+    graph
+        .get_output()
+        .iter()
+        .map(|output| {
+            take_tensor(&mut tensors, output.get_name())
+                .or_else(|| {
+                    scope
+                        .get(output.get_name())
+                        .map(|initializer| Ok(initializer.proto.clone()))
+                })
+                .ok_or(OnnxError::MissingInput(format!(
+                    "Output tensor '{}' of the subgraph '{}' not found",
+                    output.get_name(),
+                    graph.get_name()
+                )))?
+        })
+        .collect()
+}
+
+/// Records the output shape and the duration of a node on its span.
+#[cfg(feature = "tracing")]
+fn record_node(span: &tracing::Span, output: &Value, start: Instant) {
+    if !span.is_disabled() {
+        span.record("output_shape", tracing::field::debug(output.dims()));
+        span.record("duration_ms", duration_ms(start));
+    }
+}
+
+/// Milliseconds elapsed since `start`, the unit of the durations recorded on the spans.
+#[cfg(feature = "tracing")]
+pub(crate) fn duration_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1e3
+}
+
+/// Logs the number of nodes and the time spent by operation type, slowest first.
+fn log_profile(bar: &ProgressBar, profile: BTreeMap<&str, (usize, Duration)>) {
+    let total: Duration = profile.values().map(|&(_, time)| time).sum();
+    let mut profile: Vec<_> = profile.into_iter().collect();
+    profile.sort_by(|(_, (_, a)), (_, (_, b))| b.cmp(a));
+
+    log(bar, format!("\n{}", "⏱️  Time by operation:".bold()));
+    for (op_type, (count, time)) in profile {
+        log(
+            bar,
+            format!(
+                "   {:<24} {:>5} nodes {:>12?} {:>6.1}%",
+                op_type,
+                count,
+                time,
+                100.0 * time.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON)
+            ),
+        );
+    }
+}
+
+/// Logs a message above the progress bar, or on stderr when the progress bar (and the node log)
+/// is hidden.
+fn log(bar: &ProgressBar, message: String) {
+    if bar.is_hidden() {
+        eprintln!("{}", message)
+    } else {
+        bar.println(message)
+    }
+}
+
+/// Scans the output of a node for NaN and infinite values, and logs a warning or returns
+/// `OnnxError::NonFiniteOutput` depending on `options.non_finite_check`. In verbose mode, the
+/// minimum and maximum of every operand of the offending node are logged as well.
+///
+/// Only FLOAT and FLOAT16 outputs are scanned.
+fn check_finite(
+    bar: &ProgressBar,
+    node: &NodeProto,
+    output: &Value,
+    operands: &[Operand],
+    options: &RunOptions,
+) -> Result<(), OnnxError> {
+    let values = match output {
+        Value::Array(array) => Some(Cow::Borrowed(array.as_ref())),
+        Value::Proto(tensor) => float_values(tensor).map(Cow::Owned),
+    };
+    let values = match values {
+        Some(values) => values,
+        None => return Ok(()),
+    };
+    let mut flat_index = match values.as_slice().and_then(first_non_finite) {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+
+    let mut first_bad_index = vec![0; values.ndim()];
+    for (index, &dim) in first_bad_index.iter_mut().zip(values.shape()).rev() {
+        *index = flat_index % dim;
+        flat_index /= dim;
+    }
+
+    // Warnings must be visible even when the progress bar (and the node log) is hidden.
+    log(
+        bar,
+        format!(
+            "{} {} {} produced {} at {:?}",
+            "⚠️  Non-finite output:".bold().yellow(),
+            node.get_op_type(),
+            node.get_name(),
+            values[first_bad_index.as_slice()],
+            first_bad_index
+        ),
+    );
+    if options.verbose {
+        for operand in operands {
+            if let Some(values) = float_values(operand.tensor) {
+                log(
+                    bar,
+                    format!(
+                        "   {} min {} max {}",
+                        operand.name,
+                        values.fold(f32::INFINITY, |min, &v| min.min(v)),
+                        values.fold(f32::NEG_INFINITY, |max, &v| max.max(v))
+                    ),
+                );
+            }
+        }
+    }
+
+    match options.non_finite_check {
+        NonFiniteCheck::Abort => Err(OnnxError::NonFiniteOutput {
+            node_name: node.get_name().to_string(),
+            op_type: node.get_op_type().to_string(),
+            first_bad_index,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Decodes a FLOAT or FLOAT16 tensor as f32, or returns `None` for the other element types.
+fn float_values(tensor: &TensorProto) -> Option<ArrayD<f32>> {
+    match TensorProto_DataType::from_i32(tensor.get_data_type())? {
+        TensorProto_DataType::FLOAT => tensor_proto_to_ndarray::<f32>(tensor).ok(),
+        TensorProto_DataType::FLOAT16 => tensor_proto_to_ndarray::<f16>(tensor)
+            .ok()
+            .map(|values| values.mapv(f32::from)),
+        _ => None,
+    }
+}
+
+/// Returns the index of the first NaN or infinite value.
+///
+/// The values are checked in chunks, with a branch-free test on the exponent bits that the
+/// compiler can vectorize; only the chunk containing a non-finite value is searched element by
+/// element.
+fn first_non_finite(values: &[f32]) -> Option<usize> {
+    const CHUNK: usize = 1024;
+    const EXPONENT: u32 = 0x7f80_0000;
+
+    values
+        .chunks(CHUNK)
+        .enumerate()
+        .find_map(|(chunk_index, chunk)| {
+            chunk
+                .iter()
+                .fold(false, |found, v| found | (v.to_bits() & EXPONENT == EXPONENT))
+                .then(|| chunk.iter().position(|v| !v.is_finite()))
+                .flatten()
+                .map(|index| chunk_index * CHUNK + index)
+        })
+}
+
+/// Latest version of the default (`ai.onnx`) operator set known to the executor.
+pub const LATEST_OPSET: i64 = 21;
+
+const FLOATS: &[TensorProto_DataType] = &[
+    TensorProto_DataType::FLOAT,
+    TensorProto_DataType::FLOAT16,
+];
+
+const QUANTIZED: &[TensorProto_DataType] =
+    &[TensorProto_DataType::UINT8, TensorProto_DataType::INT8];
+
+const INDEXED: &[TensorProto_DataType] = &[
+    TensorProto_DataType::FLOAT,
+    TensorProto_DataType::FLOAT16,
+    TensorProto_DataType::INT64,
+];
+
+/// What the kernel of an operation supports.
+#[derive(Debug, Clone, Copy)]
+pub struct OperationSupport {
+    pub op_type: &'static str,
+    /// First and last version of the default operator set whose semantics the kernel implements.
+    pub opsets: (i64, i64),
+    /// Element types accepted on the inputs (any type if empty).
+    pub data_types: &'static [TensorProto_DataType],
+}
+
+const fn support(
+    op_type: &'static str,
+    opsets: (i64, i64),
+    data_types: &'static [TensorProto_DataType],
+) -> OperationSupport {
+    OperationSupport {
+        op_type,
+        opsets,
+        data_types,
+    }
+}
+
+/// Operations the executor can run: the ones dispatched by `run_node`, plus `If` whose branches
+/// are run by `execute_graph`.
+pub const SUPPORTED_OPERATIONS: [OperationSupport; 120] = [
+    support("Abs", (6, LATEST_OPSET), INDEXED),
+    // Multidirectional broadcasting since opset 7.
+    support("Add", (7, LATEST_OPSET), FLOATS),
+    support("And", (7, LATEST_OPSET), &[TensorProto_DataType::BOOL]),
+    // Negative axes since opset 11, select_last_index since opset 12. The indices are INT64.
+    support("ArgMax", (1, LATEST_OPSET), INDEXED),
+    support("ArgMin", (1, LATEST_OPSET), INDEXED),
+    support("AveragePool", (1, LATEST_OPSET), FLOATS),
+    support("BatchNormalization", (7, LATEST_OPSET), FLOATS),
+    support(
+        "Cast",
+        (6, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::DOUBLE,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT8,
+            TensorProto_DataType::UINT8,
+            TensorProto_DataType::INT32,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    // The type of the output is the one of the second input.
+    support(
+        "CastLike",
+        (15, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::DOUBLE,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT8,
+            TensorProto_DataType::UINT8,
+            TensorProto_DataType::INT32,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    support("Ceil", (6, LATEST_OPSET), FLOATS),
+    support("Clip", (6, LATEST_OPSET), FLOATS),
+    support(
+        "Compress",
+        (9, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::DOUBLE,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::INT32,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    // INT64 inputs, e.g. the shapes computed by the graph, stay INT64.
+    support("Concat", (4, LATEST_OPSET), INDEXED),
+    // The value of any type; value_float(s) and value_int(s) since opset 12.
+    support("Constant", (1, LATEST_OPSET), &[]),
+    support("ConstantOfShape", (9, LATEST_OPSET), &[]),
+    support("Conv", (1, LATEST_OPSET), FLOATS),
+    support("ConvTranspose", (1, LATEST_OPSET), FLOATS),
+    // The axis is an INT32 or INT64 input.
+    support("CumSum", (11, LATEST_OPSET), INDEXED),
+    // The CRD mode since opset 11.
+    support("DepthToSpace", (1, LATEST_OPSET), FLOATS),
+    support(
+        "DequantizeLinear",
+        (10, LATEST_OPSET),
+        &[
+            TensorProto_DataType::UINT8,
+            TensorProto_DataType::INT8,
+            TensorProto_DataType::INT32,
+        ],
+    ),
+    support("Div", (7, LATEST_OPSET), INDEXED),
+    support(
+        "Dropout",
+        (1, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    support("DynamicQuantizeLinear", (11, LATEST_OPSET), FLOATS),
+    support("Einsum", (12, LATEST_OPSET), FLOATS),
+    support("Elu", (6, LATEST_OPSET), FLOATS),
+    // The comparisons are broadcast since opset 7.
+    support(
+        "Equal",
+        (7, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    support("Erf", (9, LATEST_OPSET), FLOATS),
+    support("Exp", (6, LATEST_OPSET), FLOATS),
+    support(
+        "Expand",
+        (8, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    // The input of any type, the output of the type of the input or of dtype.
+    support("EyeLike", (9, LATEST_OPSET), &[]),
+    support("Flatten", (1, LATEST_OPSET), FLOATS),
+    support("Floor", (6, LATEST_OPSET), FLOATS),
+    // The types of the data of the Gather and Scatter operations, the indices being INT32 or
+    // INT64.
+    support("Gather", (1, LATEST_OPSET), INDEXED),
+    support("GatherElements", (11, LATEST_OPSET), INDEXED),
+    // batch_dims since opset 12.
+    support("GatherND", (11, LATEST_OPSET), INDEXED),
+    support("Gelu", (20, LATEST_OPSET), FLOATS),
+    support("Gemm", (7, LATEST_OPSET), FLOATS),
+    support("GlobalAveragePool", (1, LATEST_OPSET), FLOATS),
+    support("GlobalLpPool", (1, LATEST_OPSET), FLOATS),
+    support("Greater", (7, LATEST_OPSET), INDEXED),
+    support("GreaterOrEqual", (12, LATEST_OPSET), INDEXED),
+    support("GRU", (7, LATEST_OPSET), FLOATS),
+    support("HardSigmoid", (6, LATEST_OPSET), FLOATS),
+    support("HardSwish", (14, LATEST_OPSET), FLOATS),
+    support("Hardmax", (1, LATEST_OPSET), FLOATS),
+    // Forwarded (see `forwarded_input`), the kernel being the fallback.
+    support("Identity", (1, LATEST_OPSET), &[]),
+    // The BOOL condition; the branches are subgraphs.
+    support("If", (1, LATEST_OPSET), &[TensorProto_DataType::BOOL]),
+    support("LayerNormalization", (17, LATEST_OPSET), FLOATS),
+    support("LeakyRelu", (6, LATEST_OPSET), FLOATS),
+    support("Less", (7, LATEST_OPSET), INDEXED),
+    support("LessOrEqual", (12, LATEST_OPSET), INDEXED),
+    support("Log", (6, LATEST_OPSET), FLOATS),
+    support("LogSoftmax", (1, LATEST_OPSET), FLOATS),
+    support("LpPool", (1, LATEST_OPSET), FLOATS),
+    support("LRN", (1, LATEST_OPSET), FLOATS),
+    support("LSTM", (7, LATEST_OPSET), FLOATS),
+    support("MatMul", (1, LATEST_OPSET), FLOATS),
+    support("MatMulInteger", (10, LATEST_OPSET), QUANTIZED),
+    // Broadcast since opset 8, on INT64 tensors since opset 12.
+    support("Max", (8, LATEST_OPSET), INDEXED),
+    support("MaxPool", (1, LATEST_OPSET), FLOATS),
+    support("Mean", (8, LATEST_OPSET), FLOATS),
+    support("Min", (8, LATEST_OPSET), INDEXED),
+    support("Mish", (18, LATEST_OPSET), FLOATS),
+    support("Mod", (10, LATEST_OPSET), INDEXED),
+    support("Mul", (7, LATEST_OPSET), INDEXED),
+    support("Neg", (6, LATEST_OPSET), INDEXED),
+    support("NonMaxSuppression", (11, LATEST_OPSET), FLOATS),
+    support("Not", (1, LATEST_OPSET), &[TensorProto_DataType::BOOL]),
+    // The indices and depth of any numeric type, the values of the type of the output.
+    support("OneHot", (9, LATEST_OPSET), &[]),
+    support("Or", (7, LATEST_OPSET), &[TensorProto_DataType::BOOL]),
+    // The pads are an attribute before opset 11, an input since; the axes since opset 18 and the
+    // wrap mode since opset 19.
+    support("Pad", (2, LATEST_OPSET), FLOATS),
+    // INT64 exponents since opset 12.
+    support("Pow", (7, LATEST_OPSET), INDEXED),
+    // Unidirectional broadcasting of the slope since opset 7.
+    support("PRelu", (7, LATEST_OPSET), FLOATS),
+    support("QLinearConv", (10, LATEST_OPSET), QUANTIZED),
+    support("QuantizeLinear", (10, LATEST_OPSET), FLOATS),
+    // The output of the type of dtype (FLOAT, DOUBLE or FLOAT16), the input of the Like variants of
+    // any type.
+    support("RandomNormal", (1, LATEST_OPSET), &[]),
+    support("RandomNormalLike", (1, LATEST_OPSET), &[]),
+    support("RandomUniform", (1, LATEST_OPSET), &[]),
+    support("RandomUniformLike", (1, LATEST_OPSET), &[]),
+    support("Reciprocal", (6, LATEST_OPSET), FLOATS),
+    // The axes of the reductions are an attribute before opset 18 (13 for ReduceSum), an input
+    // since.
+    support("ReduceL1", (1, LATEST_OPSET), FLOATS),
+    support("ReduceL2", (1, LATEST_OPSET), FLOATS),
+    support("ReduceLogSum", (1, LATEST_OPSET), FLOATS),
+    support("ReduceLogSumExp", (1, LATEST_OPSET), FLOATS),
+    support("ReduceMax", (1, LATEST_OPSET), FLOATS),
+    support("ReduceMean", (1, LATEST_OPSET), FLOATS),
+    support("ReduceMin", (1, LATEST_OPSET), FLOATS),
+    support("ReduceProd", (1, LATEST_OPSET), FLOATS),
+    support("ReduceSum", (1, LATEST_OPSET), FLOATS),
+    support("ReduceSumSquare", (1, LATEST_OPSET), FLOATS),
+    support("Relu", (6, LATEST_OPSET), FLOATS),
+    // The shape is an attribute before opset 5, an input since.
+    support(
+        "Reshape",
+        (1, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT64,
+        ],
+    ),
+    // The scales are the second input in opset 10, the scales or the sizes after the roi since.
+    support("Resize", (10, LATEST_OPSET), FLOATS),
+    support("RNN", (7, LATEST_OPSET), FLOATS),
+    // The coordinate_transformation_mode since opset 16, the output_half_pixel one before.
+    support("RoiAlign", (10, LATEST_OPSET), FLOATS),
+    support("Round", (11, LATEST_OPSET), FLOATS),
+    // The reduction add and mul since opset 16, min and max since opset 18.
+    support("ScatterElements", (11, LATEST_OPSET), INDEXED),
+    support("ScatterND", (11, LATEST_OPSET), INDEXED),
+    support("Selu", (6, LATEST_OPSET), FLOATS),
+    // Only the dimensions of the input are read. start and end since opset 15.
+    support("Shape", (1, LATEST_OPSET), &[]),
+    support("Sign", (9, LATEST_OPSET), INDEXED),
+    support("Size", (1, LATEST_OPSET), &[]),
+    // The indices are attributes before opset 10, inputs (with the steps) since.
+    support("Slice", (1, LATEST_OPSET), FLOATS),
+    support("Softmax", (1, LATEST_OPSET), FLOATS),
+    support("Softplus", (1, LATEST_OPSET), FLOATS),
+    support("Softsign", (1, LATEST_OPSET), FLOATS),
+    support("SpaceToDepth", (1, LATEST_OPSET), FLOATS),
+    // The sizes are an attribute before opset 13, an input since, and num_outputs since opset 18.
+    support("Split", (1, LATEST_OPSET), FLOATS),
+    support("Sqrt", (6, LATEST_OPSET), FLOATS),
+    // The axes of Squeeze and Unsqueeze are an attribute before opset 13, an input since. Only the
+    // dimensions change.
+    support("Squeeze", (1, LATEST_OPSET), &[]),
+    support("Sub", (7, LATEST_OPSET), INDEXED),
+    support("Sum", (8, LATEST_OPSET), INDEXED),
+    support(
+        "Tile",
+        (6, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    // k is an input since opset 10, largest and sorted since opset 11.
+    support("TopK", (1, LATEST_OPSET), INDEXED),
+    support("Transpose", (1, LATEST_OPSET), FLOATS),
+    support(
+        "Trilu",
+        (14, LATEST_OPSET),
+        &[
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::DOUBLE,
+            TensorProto_DataType::INT64,
+            TensorProto_DataType::INT32,
+            TensorProto_DataType::BOOL,
+        ],
+    ),
+    support("Unsqueeze", (1, LATEST_OPSET), &[]),
+    // Deprecated by Resize in opset 10. The scales are an attribute in opset 7, an input in
+    // opset 9.
+    support("Upsample", (7, 9), FLOATS),
+    // BOOL conditions, and branches of any of the other types.
+    support(
+        "Where",
+        (9, LATEST_OPSET),
+        &[
+            TensorProto_DataType::BOOL,
+            TensorProto_DataType::FLOAT,
+            TensorProto_DataType::FLOAT16,
+            TensorProto_DataType::INT64,
+        ],
+    ),
+    support("Xor", (7, LATEST_OPSET), &[TensorProto_DataType::BOOL]),
+];
+
+/// The support of an operation of the default domain, if the executor can run it.
+pub fn operation_support(op_type: &str) -> Option<&'static OperationSupport> {
+    SUPPORTED_OPERATIONS
+        .iter()
+        .find(|support| support.op_type == op_type)
 }
 
 /// Executes a specific node in the ONNX graph.
@@ -104,39 +1421,492 @@ pub fn run(model: &ModelProto, input_tensor: TensorProto) -> TensorProto {
 /// # Arguments
 ///
 /// * `node` - The node to be executed.
-/// * `inputs` - A list of input tensors for the node.
-/// * `initializers` - A list of initializer tensors for the node.
+/// * `operands` - The input and initializer tensors of the node, in the order of its inputs.
+/// * `opset` - The version of the default operator set imported by the model.
+/// * `options` - The options of the run (see `RunOptions::custom_ops` and
+///   `RunOptions::reference_kernels`).
 ///
 /// # Returns
 ///
-/// * `Result<TensorProto, OnnxError>` - The output tensor from the node's execution or an error.
+/// * `Result<Vec<TensorProto>, OnnxError>` - The output tensors from the node's execution, in the
+///   order of its outputs (the optional outputs the node doesn't request are left out), or an
+///   error.
 fn run_node(
     node: &NodeProto,
-    inputs: &Vec<&TensorProto>,
-    initializers: &Vec<&TensorProto>,
-) -> Result<TensorProto, OnnxError> {
-    match node.get_op_type() {
+    operands: &[Operand],
+    opset: i64,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    if let Some(kernel) = options.custom_ops.get(node.get_op_type()) {
+        return kernel(&ordered_operands(operands), node).map(|output| vec![output]);
+    }
+
+    // Add and the reference kernels take the tensors computed by the graph and the initializers
+    // apart.
+    let (inputs, initializers): (Vec<&TensorProto>, Vec<&TensorProto>) = (
+        operands
+            .iter()
+            .filter(|operand| !operand.initializer)
+            .map(|operand| operand.tensor)
+            .collect(),
+        operands
+            .iter()
+            .filter(|operand| operand.initializer)
+            .map(|operand| operand.tensor)
+            .collect(),
+    );
+    let (inputs, initializers) = (&inputs, &initializers);
+
+    if options.reference_kernels {
+        if let Some(result) = run_reference_node(node, inputs, initializers, opset) {
+            return result.map(|output| vec![output]);
+        }
+    }
+
+    // The data of the kernels taking a single tensor, and the parameters following it for Conv,
+    // ConvTranspose and BatchNormalization, whether they are computed by the graph or
+    // initializers.
+    let positional = positional_operands(node, operands);
+    let data = || {
+        positional.first().copied().flatten().ok_or_else(|| {
+            OnnxError::MissingInput(format!(
+                "Input '{}' of node '{}' is missing",
+                node.get_input().first().map_or("", String::as_str),
+                node.get_name()
+            ))
+        })
+    };
+    let parameters = || positional.iter().skip(1).flatten().copied().collect::<Vec<_>>();
+
+    let output = match node.get_op_type() {
+        // Operations with several outputs.
+        // The ratio was an attribute before opset 12, and training_mode is only an input.
+        "Dropout" => return dropout(&positional_operands(node, operands), node),
+        "DynamicQuantizeLinear" => return dynamic_quantize_linear(data()?, node),
+        // The optional inputs of the recurrent operations can be omitted before the last ones.
+        "GRU" => return gru(&positional_operands(node, operands), node),
+        "LayerNormalization" => return layer_normalization(&ordered_operands(operands), node),
+        "LSTM" => return lstm(&positional_operands(node, operands), node),
+        "MaxPool" => return maxpool(data()?, node),
+        "RNN" => return rnn(&positional_operands(node, operands), node),
+        "Split" => return split(&ordered_operands(operands), node),
+        "TopK" => return top_k(&ordered_operands(operands), node),
+
+        "Abs" => abs(data()?, node),
         "Add" => add(inputs, Some(initializers), node),
-        "BatchNormalization" => batch_normalization(inputs[0], initializers, node),
-        "Concat" => concat(inputs, node),
-        "Conv" => conv(inputs[0], initializers, node),
-        "Dropout" => dropout(inputs[0], Some(initializers), node),
-        "Exp" => exp(inputs[0], node),
-        "Flatten" => flatten(inputs[0], node),
-        "Gemm" => gemm(inputs, Some(initializers), node),
-        "GlobalAveragePool" => global_average_pool(inputs[0], node),
-        "LRN" => lrn(inputs[0], node),
-        "MatMul" => matmul(inputs, Some(initializers), node),
-        "MaxPool" => maxpool(inputs[0], node),
-        "ReduceSum" => reduce_sum(inputs[0], node),
-        "Relu" => relu(inputs[0], node),
-        "Reshape" => reshape(inputs.get(0).copied(), initializers, node),
-        "Softmax" => softmax(inputs[0], node),
-        _ => Err(OnnxError::InternalError(format!(
+        "And" => and(&ordered_operands(operands), node),
+        "ArgMax" => argmax(data()?, node),
+        "ArgMin" => argmin(data()?, node),
+        "AveragePool" => average_pool(data()?, node),
+        "BatchNormalization" => batch_normalization(data()?, &parameters(), node),
+        "Cast" => cast(data()?, node),
+        "CastLike" => cast_like(&ordered_operands(operands), node),
+        "Ceil" => ceil(data()?, node),
+        // The bounds are attributes before opset 11, optional inputs since.
+        "Clip" => clip(&positional_operands(node, operands), node),
+        "Compress" => compress(&ordered_operands(operands), node),
+        // Constants baked into the graph can be concatenated with the tensors it computes.
+        "Concat" => concat(&ordered_operands(operands), node),
+        "Constant" => constant(node),
+        "ConstantOfShape" => constant_of_shape(&ordered_operands(operands), node),
+        "Conv" => conv(data()?, &parameters(), node),
+        "ConvTranspose" => conv_transpose(data()?, &parameters(), node),
+        "CumSum" => cum_sum(&ordered_operands(operands), node),
+        "DepthToSpace" => depth_to_space(data()?, node),
+        "DequantizeLinear" => dequantize_linear(&ordered_operands(operands), node),
+        "Div" => div(&ordered_operands(operands), node),
+        "Einsum" => einsum(&ordered_operands(operands), node),
+        "Elu" => elu(data()?, node),
+        "Equal" => equal(&ordered_operands(operands), node),
+        "Erf" => erf(data()?, node),
+        "Exp" => exp(data()?, node),
+        "Expand" => expand(&ordered_operands(operands), node),
+        "EyeLike" => eye_like(data()?, node),
+        "Flatten" => flatten(data()?, node),
+        "Floor" => floor(data()?, node),
+        // The data is an initializer for the embeddings, computed by the graph for the shapes.
+        "Gather" => gather(&ordered_operands(operands), node),
+        "GatherElements" => gather_elements(&ordered_operands(operands), node),
+        "GatherND" => gather_nd(&ordered_operands(operands), node),
+        "Gelu" => gelu(data()?, node),
+        // C can be an input or an initializer, whatever B is.
+        "Gemm" => gemm(&ordered_operands(operands), None, node),
+        "GlobalAveragePool" => global_average_pool(data()?, node),
+        "GlobalLpPool" => global_lp_pool(data()?, node),
+        "Greater" => greater(&ordered_operands(operands), node),
+        "GreaterOrEqual" => greater_or_equal(&ordered_operands(operands), node),
+        "HardSigmoid" => hard_sigmoid(data()?, node),
+        "HardSwish" => hard_swish(data()?, node),
+        "Hardmax" => hardmax(data()?, node, opset),
+        // Only the nodes that can't be forwarded, e.g. whose input is missing.
+        "Identity" => identity(&ordered_operands(operands), node),
+        "LeakyRelu" => leaky_relu(data()?, node),
+        "Less" => less(&ordered_operands(operands), node),
+        "LessOrEqual" => less_or_equal(&ordered_operands(operands), node),
+        // Not re-exported, to leave `log` to the progress bar.
+        "Log" => math::log(data()?, node),
+        "LogSoftmax" => log_softmax(data()?, node, opset),
+        "LpPool" => lp_pool(data()?, node),
+        "LRN" => lrn(data()?, node),
+        "MatMul" => matmul(&ordered_operands(operands), None, node),
+        "MatMulInteger" => matmul_integer(&positional_operands(node, operands), node),
+        "Max" => max(&ordered_operands(operands), node),
+        "Mean" => mean(&ordered_operands(operands), node),
+        "Min" => min(&ordered_operands(operands), node),
+        "Mish" => mish(data()?, node),
+        "Mod" => modulo(&ordered_operands(operands), node),
+        "Mul" => mul(&ordered_operands(operands), node),
+        "Neg" => neg(data()?, node),
+        // The thresholds and the maximum number of boxes are optional inputs.
+        "NonMaxSuppression" => non_max_suppression(&positional_operands(node, operands), node),
+        "Not" => not(data()?, node),
+        "OneHot" => one_hot(&ordered_operands(operands), node),
+        "Or" => or(&ordered_operands(operands), node),
+        // The constant value can be omitted before the axes.
+        "Pad" => pad(&positional_operands(node, operands), node),
+        "Pow" => pow(&ordered_operands(operands), node),
+        // The slope is usually an initializer, but may be computed by the graph.
+        "PRelu" => prelu(&ordered_operands(operands), node),
+        "QLinearConv" => qlinear_conv(&positional_operands(node, operands), node),
+        "QuantizeLinear" => quantize_linear(&ordered_operands(operands), node),
+        "RandomNormal" => random_normal(node),
+        "RandomNormalLike" => random_normal_like(data()?, node),
+        "RandomUniform" => random_uniform(node),
+        "RandomUniformLike" => random_uniform_like(data()?, node),
+        "Reciprocal" => reciprocal(data()?, node),
+        // The axes are an attribute or, in the recent operator sets, an optional input.
+        "ReduceL1" => reduce_l1(&ordered_operands(operands), node),
+        "ReduceL2" => reduce_l2(&ordered_operands(operands), node),
+        "ReduceLogSum" => reduce_log_sum(&ordered_operands(operands), node),
+        "ReduceLogSumExp" => reduce_log_sum_exp(&ordered_operands(operands), node),
+        "ReduceMax" => reduce_max(&ordered_operands(operands), node),
+        "ReduceMean" => reduce_mean(&ordered_operands(operands), node),
+        "ReduceMin" => reduce_min(&ordered_operands(operands), node),
+        "ReduceProd" => reduce_prod(&ordered_operands(operands), node),
+        "ReduceSum" => reduce_sum(&ordered_operands(operands), node),
+        "ReduceSumSquare" => reduce_sum_square(&ordered_operands(operands), node),
+        "Relu" => relu(data()?, node),
+        "Reshape" => reshape(&ordered_operands(operands), node),
+        // The roi and the scales can be omitted before the sizes.
+        "Resize" => resize(&positional_operands(node, operands), node, opset),
+        "RoiAlign" => roi_align(&ordered_operands(operands), node, opset),
+        "Round" => round(data()?, node),
+        "ScatterElements" => scatter_elements(&ordered_operands(operands), node),
+        "ScatterND" => scatter_nd(&ordered_operands(operands), node),
+        "Selu" => selu(data()?, node),
+        // The dimensions of an initializer are as good as the ones of a computed tensor.
+        "Shape" => shape(&ordered_operands(operands), node),
+        "Sign" => sign(data()?, node),
+        "Size" => size(&ordered_operands(operands), node),
+        // The axes and steps are optional inputs: steps can be given without axes.
+        "Slice" => slice(&positional_operands(node, operands), node),
+        "Softmax" => softmax(data()?, node, opset),
+        "Softplus" => softplus(data()?, node),
+        "Softsign" => softsign(data()?, node),
+        "SpaceToDepth" => space_to_depth(data()?, node),
+        "Sqrt" => sqrt(data()?, node),
+        // The axes of Squeeze and Unsqueeze are an attribute before opset 13, an input since.
+        "Squeeze" => squeeze(&ordered_operands(operands), node),
+        "Sub" => sub(&ordered_operands(operands), node),
+        "Sum" => sum(&ordered_operands(operands), node),
+        "Tile" => tile(&ordered_operands(operands), node),
+        // The data can be a weight stored in the layout of another framework.
+        "Transpose" => transpose(&ordered_operands(operands), node),
+        // k is an optional input.
+        "Trilu" => trilu(&ordered_operands(operands), node),
+        "Unsqueeze" => unsqueeze(&ordered_operands(operands), node),
+        "Upsample" => upsample(&ordered_operands(operands), node, opset),
+        "Where" => where_op(&ordered_operands(operands), node),
+        "Xor" => xor(&ordered_operands(operands), node),
+        _ => Err(OnnxError::UnsupportedOperation(format!(
             "Operation '{}' not found!",
             node.get_op_type()
         ))),
+    };
+    output.map(|output| vec![output])
+}
+
+/// The operations `run_ndarray_node` runs on arrays.
+const NDARRAY_OPERATIONS: [&str; 8] = [
+    "Add",
+    "BatchNormalization",
+    "Conv",
+    "Gemm",
+    "GlobalAveragePool",
+    "MatMul",
+    "MaxPool",
+    "Relu",
+];
+
+/// An operand of a node running on arrays.
+struct ArrayOperand<'a> {
+    /// The name of the node input (see `TensorMap`).
+    name: &'a str,
+    array: Arc<ArrayD<f32>>,
+    initializer: bool,
+}
+
+/// Gathers the operands of a node running on arrays, at the position of its inputs (`None` for
+/// the optional inputs it omits), or returns `None` if the node runs on `TensorProto`s: if its
+/// operation isn't one of `NDARRAY_OPERATIONS`, in verbose mode, with the reference kernels or a
+/// custom kernel, for the indices output of a `MaxPool`, or if an operand isn't a FLOAT tensor.
+///
+/// The first operand of `Relu` and `BatchNormalization`, and the first computed one of `Add`, is
+/// overwritten by the kernel: it is removed from the tensors if no other node reads it, so that
+/// its values are only copied if they are still shared under another name.
+fn ndarray_operands<'a>(
+    node: &'a NodeProto,
+    context: &mut RunContext,
+    initializers_map: &InitializerMap,
+    options: &RunOptions,
+) -> Option<Vec<Option<ArrayOperand<'a>>>> {
+    let op_type = node.get_op_type();
+    if !NDARRAY_OPERATIONS.contains(&op_type)
+        || options.verbose
+        || options.reference_kernels
+        || options.custom_ops.contains_key(op_type)
+        || (op_type == "MaxPool"
+            && node
+                .get_output()
+                .get(1)
+                .is_some_and(|name| !name.is_empty()))
+    {
+        return None;
+    }
+
+    let operands = node
+        .get_input()
+        .iter()
+        .map(|name| {
+            if name.is_empty() {
+                return Some(None);
+            }
+            let operand = match context.tensors.get(name) {
+                Some(value) => ArrayOperand {
+                    name,
+                    array: value.array()?,
+                    initializer: false,
+                },
+                None => ArrayOperand {
+                    name,
+                    array: initializers_map.get(name.as_str())?.array()?,
+                    initializer: true,
+                },
+            };
+            Some(Some(operand))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let overwritten = match op_type {
+        "Relu" | "BatchNormalization" => operands.first(),
+        "Add" => operands
+            .iter()
+            .find(|operand| operand.as_ref().is_some_and(|operand| !operand.initializer)),
+        _ => None,
+    };
+    if let Some(Some(operand)) = overwritten {
+        let reads = node
+            .get_input()
+            .iter()
+            .filter(|name| *name == operand.name)
+            .count();
+        if !operand.initializer
+            && !context.keep_tensors
+            && reads == 1
+            && context.remaining_uses.get(operand.name) == Some(&0)
+        {
+            context.tensors.remove(operand.name);
+        }
+    }
+
+    Some(operands)
+}
+
+/// Runs a node on the arrays of its operands (see `ndarray_operands`), with the kernels of the
+/// `TensorProto` path, which decode their operands and encode their output around the same
+/// functions. The FLOAT tensors flowing between these nodes are thus neither decoded nor encoded,
+/// and the initializers are decoded once by the plan.
+///
+/// The operand a kernel overwrites is only copied if it is still shared.
+fn run_ndarray_node(
+    node: &NodeProto,
+    operands: Vec<Option<ArrayOperand>>,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let owned =
+        |array: Arc<ArrayD<f32>>| Arc::try_unwrap(array).unwrap_or_else(|shared| (*shared).clone());
+    let missing = |name: &str| OnnxError::MissingInput(name.to_string());
+
+    if node.get_op_type() == "Add" {
+        // The tensors computed by the graph first, then the initializers, as in `add`.
+        let (inputs, initializers): (Vec<_>, Vec<_>) = operands
+            .into_iter()
+            .flatten()
+            .partition(|operand| !operand.initializer);
+        let mut arrays = inputs
+            .into_iter()
+            .chain(initializers)
+            .map(|operand| operand.array);
+        let first = arrays
+            .next()
+            .ok_or(missing("No tensors provided for addition"))?;
+        let others: Vec<Arc<ArrayD<f32>>> = arrays.collect();
+        let others: Vec<&ArrayD<f32>> = others.iter().map(Arc::as_ref).collect();
+        return add_ndarray(owned(first), &others);
     }
+
+    let mut positional: Vec<Option<Arc<ArrayD<f32>>>> = operands
+        .into_iter()
+        .map(|operand| operand.map(|operand| operand.array))
+        .collect();
+    // The data is taken out of the operands, so that it can be overwritten if no node shares it.
+    let mut data = || {
+        positional
+            .first_mut()
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                OnnxError::MissingInput(format!(
+                    "Input '{}' of node '{}' is missing",
+                    node.get_input().first().map_or("", String::as_str),
+                    node.get_name()
+                ))
+            })
+    };
+
+    match node.get_op_type() {
+        "BatchNormalization" => {
+            let x = data()?;
+            let parameters: Vec<&ArrayD<f32>> = positional
+                .iter()
+                .skip(1)
+                .flatten()
+                .map(Arc::as_ref)
+                .collect();
+            batch_normalization_ndarray(owned(x), &parameters, node)
+        }
+        "Conv" => {
+            let x = data()?;
+            let mut parameters = positional.iter().skip(1).flatten();
+            let kernel = parameters.next().ok_or(missing("W"))?;
+            conv_ndarray(&x, kernel, parameters.next().map(Arc::as_ref), node)
+        }
+        "GlobalAveragePool" => global_average_pooling(&*data()?),
+        "MaxPool" => maxpool_ndarray(&*data()?, node).map(|(pooled, _)| pooled),
+        "Relu" => Ok(relu_ndarray(owned(data()?))),
+        op_type => {
+            // Gemm and MatMul take their operands in order, the bias of a fused MatMul last.
+            let ordered: Vec<&ArrayD<f32>> = positional.iter().flatten().map(Arc::as_ref).collect();
+            let a = ordered.first().ok_or(missing("A"))?;
+            let b = ordered.get(1).ok_or(missing("B"))?;
+            match op_type {
+                "Gemm" => gemm_ndarray(a, b, ordered.get(2).copied(), node),
+                _ => matmul_ndarray(a, b, ordered.get(2).copied(), node),
+            }
+        }
+    }
+}
+
+/// The tensors of the operands of a node, in the order of its inputs, for the kernels taking the
+/// inputs and the initializers together.
+fn ordered_operands<'a>(operands: &[Operand<'a>]) -> Vec<&'a TensorProto> {
+    operands.iter().map(|operand| operand.tensor).collect()
+}
+
+/// The tensors of the operands of a node at the position of its inputs, `None` for the optional
+/// inputs it omits, for the kernels whose optional inputs aren't only the last ones.
+fn positional_operands<'a>(
+    node: &NodeProto,
+    operands: &[Operand<'a>],
+) -> Vec<Option<&'a TensorProto>> {
+    node.get_input()
+        .iter()
+        .map(|name| {
+            operands
+                .iter()
+                .find(|operand| !name.is_empty() && operand.name == name)
+                .map(|operand| operand.tensor)
+        })
+        .collect()
+}
+
+/// Returns the name of the input whose tensor a node reuses, and the dimensions of its output if
+/// they change:
+///
+/// * unchanged for an `Identity`, a `Dropout` in inference mode without mask output, a `Cast`
+///   to the type the input already has, or a `CastLike` of an input of the type of its target;
+/// * with new dimensions for a `Reshape` (with a constant shape) or a `Flatten` of a FLOAT tensor
+///   computed by the graph, whose values stay the same.
+///
+/// The other nodes, and the reshapes whose shape is invalid, are left to their kernels.
+fn forwarded_input(
+    node: &NodeProto,
+    input_map: &TensorMap,
+    initializers_map: &InitializerMap,
+) -> Option<(String, Option<Vec<i64>>)> {
+    // The data type of a tensor, and its `TensorProto` unless it is an array.
+    let lookup = |name: &String| match input_map.get(name) {
+        Some(Value::Proto(tensor)) => Some((tensor.get_data_type(), Some(tensor.as_ref()))),
+        Some(value @ Value::Array(_)) => Some((value.data_type(), None)),
+        None => initializers_map
+            .get(name.as_str())
+            .map(|initializer| (initializer.proto.get_data_type(), Some(initializer.proto))),
+    };
+
+    let source = node.get_input().first()?;
+    let (data_type, _) = lookup(source)?;
+    if node.get_output().first().is_none_or(|output| output.is_empty()) {
+        return None;
+    }
+
+    let forwarded = match node.get_op_type() {
+        "Identity" => true,
+        "Dropout" => {
+            let mask_requested = node.get_output().get(1).is_some_and(|mask| !mask.is_empty());
+            let training_mode = node
+                .get_input()
+                .get(2)
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    lookup(name).is_some_and(|(_, tensor)| tensor.is_some_and(is_training_mode))
+                });
+            !mask_requested && training_mode != Some(true)
+        }
+        "Cast" => node
+            .get_attribute()
+            .iter()
+            .find(|attribute| attribute.get_name() == "to")
+            .is_some_and(|to| to.get_i() as i32 == data_type),
+        "CastLike" => node
+            .get_input()
+            .get(1)
+            .and_then(lookup)
+            .is_some_and(|(target_type, _)| target_type == data_type),
+        "Reshape" | "Flatten" => {
+            if data_type != TensorProto_DataType::FLOAT.value() {
+                return None;
+            }
+            let input_shape: Vec<usize> = match input_map.get(source)? {
+                Value::Array(array) => array.shape().to_vec(),
+                Value::Proto(tensor) => tensor.get_dims().iter().map(|&dim| dim as usize).collect(),
+            };
+            let output_shape = match node.get_op_type() {
+                "Reshape" => {
+                    let shape = node.get_input().get(1)?;
+                    constant_reshaped_shape(
+                        &input_shape,
+                        initializers_map.get(shape.as_str())?.proto,
+                        node,
+                    )
+                }
+                _ => flattened_shape(&input_shape, node),
+            }
+            .ok()?;
+            let dims = output_shape.into_iter().map(|dim| dim as i64).collect();
+            return Some((source.clone(), Some(dims)));
+        }
+        _ => false,
+    };
+
+    forwarded.then(|| (source.clone(), None))
 }
 
 fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
@@ -150,12 +1920,13 @@ fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
 fn run_node_verbose(
     bar: &ProgressBar,
     node: &NodeProto,
-    input_tensors: &Vec<&TensorProto>,
-    initializer_tensors: &Vec<&TensorProto>,
-) -> TensorProto {
+    operands: &[Operand],
+    opset: i64,
+    options: &RunOptions,
+) -> Result<Vec<TensorProto>, OnnxError> {
     let name_column_width = 35; // Fixed width
 
-    if !input_tensors.is_empty() || !initializer_tensors.is_empty() {
+    if !operands.is_empty() {
         bar.println(format!(
             "{:<16} {:<width$} {}",
             "Operand".red(),
@@ -170,37 +1941,53 @@ fn run_node_verbose(
             "------------------".red()
         ));
 
-        for input in input_tensors {
+        // The inputs computed by the graph first, then the initializers.
+        let inputs = operands.iter().filter(|operand| !operand.initializer);
+        let initializers = operands.iter().filter(|operand| operand.initializer);
+
+        for input in inputs {
             bar.println(format!(
                 "{:<15} {:<width$} {:?}",
                 "🟢 Input".bright_green(),
-                truncate_with_ellipsis(input.get_name(), name_column_width),
-                input.get_dims(),
+                truncate_with_ellipsis(input.name, name_column_width),
+                input.tensor.get_dims(),
                 width = name_column_width
             ));
         }
 
-        for initializer in initializer_tensors {
+        for initializer in initializers {
             bar.println(format!(
                 "{:<15} {:<width$} {:?}",
                 "🟡 Initializer".bright_yellow(),
-                truncate_with_ellipsis(initializer.get_name(), name_column_width),
-                initializer.get_dims(),
+                truncate_with_ellipsis(initializer.name, name_column_width),
+                initializer.tensor.get_dims(),
                 width = name_column_width
             ));
         }
     }
 
-    let output_tensor =
-        run_node(node, input_tensors, initializer_tensors).expect("Failed to run node");
+    let output_tensors = run_node(node, operands, opset, options)?;
 
-    bar.println(format!(
-        "{:<15} {:<width$} {:?}\n\n",
-        "🟣 Output".bright_purple(),
-        truncate_with_ellipsis(output_tensor.get_name(), name_column_width),
-        output_tensor.get_dims(),
-        width = name_column_width
-    ));
+    for output_tensor in &output_tensors {
+        bar.println(format!(
+            "{:<15} {:<width$} {:?}",
+            "🟣 Output".bright_purple(),
+            truncate_with_ellipsis(output_tensor.get_name(), name_column_width),
+            output_tensor.get_dims(),
+            width = name_column_width
+        ));
+
+        // The values of the output, summarized above the threshold of the options. The data types
+        // without a `Tensor` equivalent only get the line above.
+        if let Ok(tensor) = Tensor::from_proto(output_tensor) {
+            let format_options = FormatOptions {
+                stats: true,
+                ..options.tensor_format.clone()
+            };
+            bar.println(tensor.format(&format_options));
+        }
+    }
+    bar.println("\n");
 
-    output_tensor
+    Ok(output_tensors)
 }
@@ -0,0 +1,418 @@
+use crate::onnx_rustime::backend::format::FormatOptions;
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::backend::parser::OnnxParser;
+use crate::onnx_rustime::backend::run::{run_planned, run_with_inputs_planned, ExecutionPlan};
+use crate::onnx_rustime::backend::tensor::Tensor;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{ModelProto, TensorProto};
+use crate::onnx_rustime::shared::{CancellationToken, CustomOp, Model, NonFiniteCheck, RunOptions};
+use crate::onnx_rustime::transforms::{OptLevel, OptimizationReport, PassManager};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A model ready to be run: loaded, with its initializers overridden and optimized, along with
+/// the options of its runs.
+///
+/// Sessions are built by a `SessionBuilder` (see `Session::builder`), or by `Session::from_model`
+/// with the default options.
+///
+/// A session is `Send` and `Sync`: it can be shared between threads (e.g. in an `Arc`) and run
+/// by several of them at the same time. Its model, options and execution plan (including its
+/// thread pool) are prepared by `build` and never modified by the runs, which keep their state
+/// to themselves: concurrent runs share the thread pool, without waiting on a lock. Cloning a
+/// session doesn't copy the model.
+#[derive(Debug, Clone)]
+pub struct Session {
+    model: Arc<ModelProto>,
+    plan: Arc<ExecutionPlan>,
+    options: RunOptions,
+    optimization_report: OptimizationReport,
+}
+
+impl Session {
+    /// Starts the configuration of a session.
+    pub fn builder() -> SessionBuilder {
+        SessionBuilder::default()
+    }
+
+    /// Builds a session running a model with the default options (see `SessionBuilder`).
+    pub fn from_model(model: ModelProto) -> Result<Self, OnnxError> {
+        Session::builder().model(model).build()
+    }
+
+    /// The model run by the session, after the overrides and the optimizations.
+    pub fn model(&self) -> &ModelProto {
+        &self.model
+    }
+
+    /// The options of the runs of the session.
+    pub fn options(&self) -> &RunOptions {
+        &self.options
+    }
+
+    /// The statistics of the optimization of the model.
+    pub fn optimization_report(&self) -> &OptimizationReport {
+        &self.optimization_report
+    }
+
+    /// A copy of the session printing, or not, the details of its runs; the model and the
+    /// execution plan are shared with the session.
+    pub fn with_verbose(&self, verbose: bool) -> Session {
+        let mut session = self.clone();
+        session.options.verbose = verbose;
+        session
+    }
+
+    /// Runs the model on its first input, with a progress bar (see `backend::run::run`).
+    pub fn run(&self, input: Tensor) -> Result<Tensor, OnnxError> {
+        let output = run_planned(&self.model, &self.plan, input.into_proto()?, &self.options)?;
+        Tensor::from_proto(&output)
+    }
+
+    /// Runs the model on all its inputs, returning all its outputs (see
+    /// `backend::run::run_with_inputs`).
+    pub fn run_with_inputs(&self, inputs: Vec<Tensor>) -> Result<Vec<Tensor>, OnnxError> {
+        let inputs = inputs
+            .into_iter()
+            .map(Tensor::into_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        run_with_inputs_planned(&self.model, &self.plan, inputs, &self.options)?
+            .iter()
+            .map(Tensor::from_proto)
+            .collect()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Session {
+    /// `run_async` - Runs the model on all its inputs without blocking the async runtime
+    ///
+    /// The run is moved to the blocking threads of tokio (`spawn_blocking`), whose number is
+    /// bounded by the runtime: a pending run occupies one of them for its whole length, the
+    /// kernels themselves running in the rayon pool of the session (see `SessionBuilder::threads`).
+    /// The other tasks of the runtime are never blocked. Like `run_with_inputs`, nothing is
+    /// printed.
+    ///
+    /// Dropping the future cancels the run: it stops before its next node, the node being run
+    /// completing in the background first. Cancelling the token of the session (see
+    /// `SessionBuilder::cancellation_token`) cancels it too.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input tensors, in the order of the graph inputs (see `run_with_inputs`).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Tensor>, OnnxError>` - The outputs, in the order of the graph outputs,
+    ///   `OnnxError::Cancelled` if the token of the session or the runtime stopped the run, or the
+    ///   error of the first failing node.
+    ///
+    /// # Panics
+    ///
+    /// If the future is polled outside of a tokio runtime, or if a kernel panics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let session = Arc::new(Session::builder().model_path("models/mnist-8/mnist-8.onnx").build()?);
+    /// let (a, b) = tokio::join!(session.run_async(vec![first]), session.run_async(vec![second]));
+    /// ```
+    pub async fn run_async(&self, inputs: Vec<Tensor>) -> Result<Vec<Tensor>, OnnxError> {
+        let token = match &self.options.cancellation {
+            Some(token) => token.child_token(),
+            None => CancellationToken::new(),
+        };
+        // Cancels the run if the future is dropped before its end.
+        let _guard = CancelOnDrop(token.clone());
+
+        let model = Arc::clone(&self.model);
+        let plan = Arc::clone(&self.plan);
+        let options = RunOptions {
+            cancellation: Some(token),
+            ..self.options.clone()
+        };
+        let inputs = inputs
+            .into_iter()
+            .map(Tensor::into_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let outputs = tokio::task::spawn_blocking(move || {
+            run_with_inputs_planned(&model, &plan, inputs, &options)
+        })
+        .await
+        .map_err(|e| match e.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(_) => OnnxError::Cancelled,
+        })??;
+
+        outputs.iter().map(Tensor::from_proto).collect()
+    }
+}
+
+/// Cancels a token when dropped.
+#[cfg(feature = "tokio")]
+struct CancelOnDrop(CancellationToken);
+
+#[cfg(feature = "tokio")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Configuration of a `Session`.
+///
+/// The setters only record the options: they are all checked together by `build`, which fails if
+/// no model (or two models) were given, a file can't be read, the number of threads is zero, an
+/// operation type is registered twice, or an override doesn't match an initializer of the model.
+///
+/// Unless set, the model is optimized at the `Basic` level and run with the default `RunOptions`.
+///
+/// # Example
+///
+/// ```rust
+/// let session = Session::builder()
+///     .model_path("models/mnist-8/mnist-8.onnx")
+///     .opt_level(OptLevel::Extended)
+///     .threads(4)
+///     .labels(Model::Mnist)
+///     .enable_profiling()
+///     .build()?;
+/// let output = session.run(input)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SessionBuilder {
+    model_path: Option<PathBuf>,
+    model: Option<ModelProto>,
+    external_data_dir: Option<PathBuf>,
+    opt_level: OptLevel,
+    options: RunOptions,
+    custom_ops: Vec<(String, CustomOp)>,
+    initializer_overrides: Vec<TensorProto>,
+}
+
+impl SessionBuilder {
+    /// Loads the model from an ONNX file.
+    pub fn model_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.model_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Runs a model already in memory.
+    pub fn model(mut self, model: ModelProto) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Reads the external data files of the model (see `OnnxParser::load_model`) from a directory
+    /// other than the one of the model. Only valid with `model_path`.
+    pub fn external_data_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.external_data_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the graph optimization level (`Basic` by default).
+    pub fn opt_level(mut self, level: OptLevel) -> Self {
+        self.opt_level = level;
+        self
+    }
+
+    /// Runs the kernels in a pool of `threads` threads instead of the global one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.options.threads = Some(threads);
+        self
+    }
+
+    /// Logs the time spent in every operation type at the end of every run.
+    pub fn enable_profiling(mut self) -> Self {
+        self.options.profiling = true;
+        self
+    }
+
+    /// Logs the operands and the output of every node.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.options.verbose = verbose;
+        self
+    }
+
+    /// Sets the label set used to display the outputs.
+    pub fn labels(mut self, model: Model) -> Self {
+        self.options.model = model;
+        self
+    }
+
+    /// Scans the output of every node for NaN and infinite values.
+    pub fn non_finite_check(mut self, check: NonFiniteCheck) -> Self {
+        self.options.non_finite_check = check;
+        self
+    }
+
+    /// Runs the reference kernels instead of the optimized ones.
+    pub fn reference_kernels(mut self, enabled: bool) -> Self {
+        self.options.reference_kernels = enabled;
+        self
+    }
+
+    /// Stops the runs of the session with `OnnxError::Cancelled` once the token is cancelled.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.options.cancellation = Some(token);
+        self
+    }
+
+    /// Sets how the tensors are printed when displaying the outputs and in verbose mode.
+    pub fn tensor_format(mut self, format: FormatOptions) -> Self {
+        self.options.tensor_format = format;
+        self
+    }
+
+    /// Replaces all the run options set so far. The custom operations registered with
+    /// `register_op` are kept.
+    pub fn run_options(mut self, options: RunOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Registers the kernel of a custom operation, which takes precedence over the built-in kernel
+    /// of the same operation type if any. The nodes removed by the optimization (e.g. `Identity`,
+    /// or the constants folded into initializers) are never run: replacing their kernel requires
+    /// `OptLevel::None`.
+    pub fn register_op(mut self, op_type: &str, kernel: CustomOp) -> Self {
+        self.custom_ops.push((op_type.to_string(), kernel));
+        self
+    }
+
+    /// Replaces the value of an initializer of the model, matched by the name of the tensor. The
+    /// new value must have the same element type and shape.
+    pub fn override_initializer(mut self, tensor: TensorProto) -> Self {
+        self.initializer_overrides.push(tensor);
+        self
+    }
+
+    /// `build` - Checks the configuration and builds the session
+    ///
+    /// The model is loaded (with its external data), its initializers are overridden and it is
+    /// optimized, in this order; then the execution plan of its runs is prepared.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Session, OnnxError>` - The session, `OnnxError::InvalidValue` if the options
+    ///   conflict, or the error of the loading or of the optimization of the model.
+    pub fn build(self) -> Result<Session, OnnxError> {
+        self.validate()?;
+
+        let mut model = match (self.model, self.model_path) {
+            (Some(model), None) => model,
+            (None, Some(path)) => {
+                let path_name = path.to_string_lossy().to_string();
+                match &self.external_data_dir {
+                    Some(dir) => OnnxParser::load_model_with_external_data(path_name, dir)?,
+                    None => OnnxParser::load_model(path_name)?,
+                }
+            }
+            _ => unreachable!("checked by validate"),
+        };
+
+        for tensor in self.initializer_overrides {
+            override_initializer(&mut model, tensor)?;
+        }
+
+        let (model, optimization_report) = PassManager::new(self.opt_level).run(&model)?;
+
+        let mut options = self.options;
+        options.custom_ops.extend(self.custom_ops);
+        let plan = ExecutionPlan::new(&model, options.threads)?;
+
+        Ok(Session {
+            model: Arc::new(model),
+            plan: Arc::new(plan),
+            options,
+            optimization_report,
+        })
+    }
+
+    /// Checks the options that don't depend on the content of the model.
+    fn validate(&self) -> Result<(), OnnxError> {
+        let invalid = |message: String| Err(OnnxError::InvalidValue(message));
+
+        match (&self.model, &self.model_path) {
+            (None, None) => {
+                return invalid("No model given: set either model_path or model".to_string())
+            }
+            (Some(_), Some(path)) => {
+                return invalid(format!(
+                    "Both a model and a model path ('{}') were given",
+                    path.display()
+                ))
+            }
+            _ => {}
+        }
+
+        if let Some(dir) = &self.external_data_dir {
+            if self.model_path.is_none() {
+                return invalid(
+                    "An external data directory requires a model loaded from model_path"
+                        .to_string(),
+                );
+            }
+            if !dir.is_dir() {
+                return invalid(format!(
+                    "External data directory '{}' doesn't exist",
+                    dir.display()
+                ));
+            }
+        }
+
+        if self.options.threads == Some(0) {
+            return invalid("The number of threads must be positive".to_string());
+        }
+
+        for (index, (op_type, _)) in self.custom_ops.iter().enumerate() {
+            if op_type.is_empty() {
+                return invalid("Custom operations must have a type".to_string());
+            }
+            if self.custom_ops[..index]
+                .iter()
+                .any(|(other, _)| other == op_type)
+            {
+                return invalid(format!("Custom operation '{}' registered twice", op_type));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces the initializer with the name of `tensor`, checking that its type and shape match.
+fn override_initializer(model: &mut ModelProto, tensor: TensorProto) -> Result<(), OnnxError> {
+    let initializer = model
+        .mut_graph()
+        .mut_initializer()
+        .iter_mut()
+        .find(|initializer| initializer.get_name() == tensor.get_name())
+        .ok_or(OnnxError::InvalidValue(format!(
+            "Cannot override '{}': the model has no initializer with this name",
+            tensor.get_name()
+        )))?;
+
+    if initializer.get_data_type() != tensor.get_data_type()
+        || initializer.get_dims() != tensor.get_dims()
+    {
+        return Err(OnnxError::InvalidValue(format!(
+            "Cannot override '{}': expected a tensor of type {} and shape {:?}, found type {} and shape {:?}",
+            tensor.get_name(),
+            initializer.get_data_type(),
+            initializer.get_dims(),
+            tensor.get_data_type(),
+            tensor.get_dims()
+        )));
+    }
+
+    *initializer = tensor;
+    Ok(())
+}
+
+// Sessions and tensors are shared between threads and moved into tasks.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Session>();
+    assert_send_sync::<Tensor>();
+};
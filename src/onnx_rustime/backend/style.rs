@@ -0,0 +1,43 @@
+//! Styling of the messages printed by the runtime: the colors of `colored` with the `cli`
+//! feature, plain text without it (so that library consumers don't depend on `colored`).
+
+#[cfg(feature = "cli")]
+pub use colored::Colorize;
+
+/// The styles of `colored::Colorize` used by the runtime, leaving the text unchanged.
+#[cfg(not(feature = "cli"))]
+pub trait Colorize: Sized + Into<String> {
+    fn bold(self) -> String {
+        self.into()
+    }
+
+    fn red(self) -> String {
+        self.into()
+    }
+
+    fn yellow(self) -> String {
+        self.into()
+    }
+
+    fn magenta(self) -> String {
+        self.into()
+    }
+
+    fn bright_green(self) -> String {
+        self.into()
+    }
+
+    fn bright_yellow(self) -> String {
+        self.into()
+    }
+
+    fn bright_purple(self) -> String {
+        self.into()
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+impl Colorize for &str {}
+
+#[cfg(not(feature = "cli"))]
+impl Colorize for String {}
@@ -0,0 +1,756 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{TensorProto, TensorProto_DataType};
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use half::f16;
+use ndarray::ArrayD;
+use protobuf::ProtobufEnum;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of values shown by the `Display` and `Debug` implementations of `Tensor`.
+pub const PREVIEW_LEN: usize = 8;
+
+/// Element types of a `Tensor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DType {
+    Float,
+    Float16,
+    Double,
+    Int8,
+    UInt8,
+    Int32,
+    Int64,
+    Bool,
+    String,
+}
+
+impl DType {
+    /// The ONNX data type of the elements.
+    pub fn to_proto(self) -> TensorProto_DataType {
+        match self {
+            DType::Float => TensorProto_DataType::FLOAT,
+            DType::Float16 => TensorProto_DataType::FLOAT16,
+            DType::Double => TensorProto_DataType::DOUBLE,
+            DType::Int8 => TensorProto_DataType::INT8,
+            DType::UInt8 => TensorProto_DataType::UINT8,
+            DType::Int32 => TensorProto_DataType::INT32,
+            DType::Int64 => TensorProto_DataType::INT64,
+            DType::Bool => TensorProto_DataType::BOOL,
+            DType::String => TensorProto_DataType::STRING,
+        }
+    }
+}
+
+impl fmt::Display for DType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DType::Float => write!(f, "float32"),
+            DType::Float16 => write!(f, "float16"),
+            DType::Double => write!(f, "float64"),
+            DType::Int8 => write!(f, "int8"),
+            DType::UInt8 => write!(f, "uint8"),
+            DType::Int32 => write!(f, "int32"),
+            DType::Int64 => write!(f, "int64"),
+            DType::Bool => write!(f, "bool"),
+            DType::String => write!(f, "string"),
+        }
+    }
+}
+
+/// The values of a `Tensor`, as an array of their element type. The arrays are always in
+/// standard (row-major) layout.
+#[derive(Clone, PartialEq)]
+pub enum TensorData {
+    Float(ArrayD<f32>),
+    Float16(ArrayD<f16>),
+    Double(ArrayD<f64>),
+    Int8(ArrayD<i8>),
+    UInt8(ArrayD<u8>),
+    Int32(ArrayD<i32>),
+    Int64(ArrayD<i64>),
+    Bool(ArrayD<bool>),
+    String(ArrayD<String>),
+}
+
+/// Element types that can be stored in a `Tensor`.
+pub trait TensorElement: Clone + fmt::Debug + Sized {
+    const DTYPE: DType;
+
+    /// Wraps an array of this type.
+    fn wrap(array: ArrayD<Self>) -> TensorData;
+
+    /// The array, if the values have this type.
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>>;
+
+    /// The array, if the values have this type, taken out of the enum.
+    fn take(data: TensorData) -> Option<ArrayD<Self>>;
+
+    /// A copy of the values as an array of this type, converting them if this type is wider.
+    fn to_array(data: &TensorData) -> Option<ArrayD<Self>> {
+        Self::array(data).cloned()
+    }
+}
+
+/// FLOAT16 values can be read as `f32`: they are upcast, like `tensor_proto_to_ndarray::<f32>`
+/// does.
+impl TensorElement for f32 {
+    const DTYPE: DType = DType::Float;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Float(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Float(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Float(array) => Some(array),
+            TensorData::Float16(array) => Some(array.mapv(f32::from)),
+            _ => None,
+        }
+    }
+
+    fn to_array(data: &TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Float(array) => Some(array.clone()),
+            TensorData::Float16(array) => Some(array.mapv(f32::from)),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for f16 {
+    const DTYPE: DType = DType::Float16;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Float16(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Float16(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Float16(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for f64 {
+    const DTYPE: DType = DType::Double;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Double(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Double(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Double(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i8 {
+    const DTYPE: DType = DType::Int8;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Int8(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Int8(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Int8(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for u8 {
+    const DTYPE: DType = DType::UInt8;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::UInt8(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::UInt8(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::UInt8(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i32 {
+    const DTYPE: DType = DType::Int32;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Int32(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Int32(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Int32(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for i64 {
+    const DTYPE: DType = DType::Int64;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Int64(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Int64(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Int64(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for bool {
+    const DTYPE: DType = DType::Bool;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::Bool(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::Bool(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::Bool(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl TensorElement for String {
+    const DTYPE: DType = DType::String;
+
+    fn wrap(array: ArrayD<Self>) -> TensorData {
+        TensorData::String(array)
+    }
+
+    fn array(data: &TensorData) -> Option<&ArrayD<Self>> {
+        match data {
+            TensorData::String(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    fn take(data: TensorData) -> Option<ArrayD<Self>> {
+        match data {
+            TensorData::String(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+/// A named tensor: the type exchanged with the runtime (`Session::run`, `OnnxParser::load_data`,
+/// `preprocess_image`, ...).
+///
+/// It converts from and to `TensorProto` (`Tensor::from_proto` and `Tensor::to_proto`, or
+/// `TryFrom`) for the code working on the ONNX messages directly.
+///
+/// # Example
+///
+/// ```rust
+/// let tensor = Tensor::from_ndarray(ArrayD::<f32>::zeros(vec![1, 3, 224, 224])).with_name("data");
+/// assert_eq!(tensor.shape(), &[1, 3, 224, 224]);
+/// assert_eq!(tensor.dtype(), DType::Float);
+/// // float32[1, 3, 224, 224] 'data': [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, ... 150520 more]
+/// println!("{}", tensor);
+/// ```
+///
+/// With the `serde` feature, tensors (de)serialize as
+/// `{"name": "data", "dtype": "float32", "shape": [1, 3], "data": [0.5, 1.0, 2.0]}`, the values
+/// being in row-major order (see `Tensor::to_json`).
+#[derive(Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "JsonTensor", into = "JsonTensor")
+)]
+pub struct Tensor {
+    name: String,
+    data: TensorData,
+}
+
+impl Tensor {
+    /// Creates an unnamed tensor holding the values of an array.
+    pub fn from_ndarray<T: TensorElement>(array: ArrayD<T>) -> Self {
+        let array = if array.is_standard_layout() {
+            array
+        } else {
+            array.as_standard_layout().into_owned()
+        };
+
+        Tensor {
+            name: String::new(),
+            data: T::wrap(array),
+        }
+    }
+
+    /// Creates an unnamed tensor from its values.
+    pub fn from_data(data: TensorData) -> Self {
+        match data {
+            TensorData::Float(array) => Self::from_ndarray(array),
+            TensorData::Float16(array) => Self::from_ndarray(array),
+            TensorData::Double(array) => Self::from_ndarray(array),
+            TensorData::Int8(array) => Self::from_ndarray(array),
+            TensorData::UInt8(array) => Self::from_ndarray(array),
+            TensorData::Int32(array) => Self::from_ndarray(array),
+            TensorData::Int64(array) => Self::from_ndarray(array),
+            TensorData::Bool(array) => Self::from_ndarray(array),
+            TensorData::String(array) => Self::from_ndarray(array),
+        }
+    }
+
+    /// Renames the tensor.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data(&self) -> &TensorData {
+        &self.data
+    }
+
+    pub fn dtype(&self) -> DType {
+        match self.data {
+            TensorData::Float(_) => DType::Float,
+            TensorData::Float16(_) => DType::Float16,
+            TensorData::Double(_) => DType::Double,
+            TensorData::Int8(_) => DType::Int8,
+            TensorData::UInt8(_) => DType::UInt8,
+            TensorData::Int32(_) => DType::Int32,
+            TensorData::Int64(_) => DType::Int64,
+            TensorData::Bool(_) => DType::Bool,
+            TensorData::String(_) => DType::String,
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        match &self.data {
+            TensorData::Float(array) => array.shape(),
+            TensorData::Float16(array) => array.shape(),
+            TensorData::Double(array) => array.shape(),
+            TensorData::Int8(array) => array.shape(),
+            TensorData::UInt8(array) => array.shape(),
+            TensorData::Int32(array) => array.shape(),
+            TensorData::Int64(array) => array.shape(),
+            TensorData::Bool(array) => array.shape(),
+            TensorData::String(array) => array.shape(),
+        }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.shape().iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values in row-major order, or `None` if they don't have type `T`.
+    pub fn as_slice<T: TensorElement>(&self) -> Option<&[T]> {
+        T::array(&self.data).and_then(|array| array.as_slice())
+    }
+
+    /// A copy of the values as an array of type `T`. FLOAT16 tensors can be read as `f32`.
+    pub fn to_ndarray<T: TensorElement>(&self) -> Result<ArrayD<T>, OnnxError> {
+        T::to_array(&self.data).ok_or_else(|| self.type_error(T::DTYPE))
+    }
+
+    /// The values as an array of type `T`, without copying them if they already have this type.
+    pub fn into_ndarray<T: TensorElement>(self) -> Result<ArrayD<T>, OnnxError> {
+        let error = self.type_error(T::DTYPE);
+        T::take(self.data).ok_or(error)
+    }
+
+    fn type_error(&self, expected: DType) -> OnnxError {
+        OnnxError::ConversionError(format!(
+            "Tensor '{}' holds {} values, not {}",
+            self.name,
+            self.dtype(),
+            expected
+        ))
+    }
+
+    /// `from_proto` - Reads the values of a `TensorProto`
+    ///
+    /// # Arguments
+    ///
+    /// * `proto` - The tensor, of type FLOAT, FLOAT16, DOUBLE, INT8, UINT8, INT32, INT64, BOOL or
+    ///   STRING.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Tensor, OnnxError>` - The tensor with the same name, or an error if its type
+    ///   isn't supported or its data doesn't match its dimensions.
+    pub fn from_proto(proto: &TensorProto) -> Result<Self, OnnxError> {
+        let data_type = TensorProto_DataType::from_i32(proto.get_data_type());
+        let data = match data_type {
+            Some(TensorProto_DataType::FLOAT) => {
+                TensorData::Float(tensor_proto_to_ndarray::<f32>(proto)?)
+            }
+            Some(TensorProto_DataType::FLOAT16) => {
+                TensorData::Float16(tensor_proto_to_ndarray::<f16>(proto)?)
+            }
+            Some(TensorProto_DataType::DOUBLE) => {
+                TensorData::Double(tensor_proto_to_ndarray::<f64>(proto)?)
+            }
+            Some(TensorProto_DataType::INT8) => {
+                TensorData::Int8(tensor_proto_to_ndarray::<i8>(proto)?)
+            }
+            Some(TensorProto_DataType::UINT8) => {
+                TensorData::UInt8(tensor_proto_to_ndarray::<u8>(proto)?)
+            }
+            Some(TensorProto_DataType::INT32) => {
+                TensorData::Int32(tensor_proto_to_ndarray::<i32>(proto)?)
+            }
+            Some(TensorProto_DataType::INT64) => {
+                TensorData::Int64(tensor_proto_to_ndarray::<i64>(proto)?)
+            }
+            Some(TensorProto_DataType::BOOL) => {
+                TensorData::Bool(tensor_proto_to_ndarray::<bool>(proto)?)
+            }
+            Some(TensorProto_DataType::STRING) => {
+                TensorData::String(tensor_proto_to_ndarray::<String>(proto)?)
+            }
+            _ => {
+                return Err(OnnxError::ConversionError(format!(
+                    "Tensor '{}' has unsupported data type {}",
+                    proto.get_name(),
+                    data_type.map_or(proto.get_data_type().to_string(), |t| format!("{:?}", t))
+                )))
+            }
+        };
+
+        Ok(Tensor::from_data(data).with_name(proto.get_name()))
+    }
+
+    /// Converts the tensor to a `TensorProto` with the same name.
+    pub fn to_proto(&self) -> Result<TensorProto, OnnxError> {
+        self.clone().into_proto()
+    }
+
+    /// Converts the tensor to a `TensorProto` with the same name, without copying its values
+    /// first.
+    pub fn into_proto(self) -> Result<TensorProto, OnnxError> {
+        match self.data {
+            TensorData::Float(array) => ndarray_to_tensor_proto::<f32>(array, &self.name),
+            TensorData::Float16(array) => ndarray_to_tensor_proto::<f16>(array, &self.name),
+            TensorData::Double(array) => ndarray_to_tensor_proto::<f64>(array, &self.name),
+            TensorData::Int8(array) => ndarray_to_tensor_proto::<i8>(array, &self.name),
+            TensorData::UInt8(array) => ndarray_to_tensor_proto::<u8>(array, &self.name),
+            TensorData::Int32(array) => ndarray_to_tensor_proto::<i32>(array, &self.name),
+            TensorData::Int64(array) => ndarray_to_tensor_proto::<i64>(array, &self.name),
+            TensorData::Bool(array) => ndarray_to_tensor_proto::<bool>(array, &self.name),
+            TensorData::String(array) => ndarray_to_tensor_proto::<String>(array, &self.name),
+        }
+    }
+
+    /// The first `PREVIEW_LEN` values, followed by the number of the others.
+    fn preview(&self) -> String {
+        fn values<T: fmt::Debug>(array: &ArrayD<T>) -> Vec<String> {
+            array
+                .iter()
+                .take(PREVIEW_LEN)
+                .map(|v| format!("{:?}", v))
+                .collect()
+        }
+
+        let mut values = match &self.data {
+            TensorData::Float(array) => values(array),
+            TensorData::Float16(array) => values(array),
+            TensorData::Double(array) => values(array),
+            TensorData::Int8(array) => values(array),
+            TensorData::UInt8(array) => values(array),
+            TensorData::Int32(array) => values(array),
+            TensorData::Int64(array) => values(array),
+            TensorData::Bool(array) => values(array),
+            TensorData::String(array) => values(array),
+        };
+        if self.len() > PREVIEW_LEN {
+            values.push(format!("... {} more", self.len() - PREVIEW_LEN));
+        }
+        format!("[{}]", values.join(", "))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Tensor {
+    /// `to_json` - Serializes the tensor to JSON
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, OnnxError>` - The JSON object, or an error if the tensor holds NaN or
+    ///   infinite values, which JSON can't represent.
+    pub fn to_json(&self) -> Result<String, OnnxError> {
+        let finite = match &self.data {
+            TensorData::Double(array) => array.iter().all(|v| v.is_finite()),
+            data => f32::to_array(data).is_none_or(|array| array.iter().all(|v| v.is_finite())),
+        };
+        if !finite {
+            return Err(OnnxError::ConversionError(format!(
+                "Tensor '{}' holds non-finite values, which JSON can't represent",
+                self.name
+            )));
+        }
+        serde_json::to_string(self).map_err(|e| OnnxError::ConversionError(e.to_string()))
+    }
+
+    /// Deserializes a tensor from JSON (see `Tensor::to_json`).
+    pub fn from_json(json: &str) -> Result<Self, OnnxError> {
+        serde_json::from_str(json).map_err(|e| OnnxError::ConversionError(e.to_string()))
+    }
+}
+
+/// The JSON representation of a `Tensor`.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct JsonTensor {
+    #[serde(default)]
+    name: String,
+    dtype: String,
+    shape: Vec<usize>,
+    data: Vec<JsonValue>,
+}
+
+/// A value of a `JsonTensor`. Integers are accepted in float tensors.
+///
+/// The other numbers are read as `Double`: `Float` only writes the values of the float32 and
+/// float16 tensors with their shortest representation.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonValue {
+    Int(i64),
+    Double(f64),
+    Float(f32),
+    Bool(bool),
+    String(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<Tensor> for JsonTensor {
+    fn from(tensor: Tensor) -> Self {
+        let dtype = tensor.dtype().to_string();
+        let shape = tensor.shape().to_vec();
+        let data = match tensor.data {
+            TensorData::Float(array) => array.into_iter().map(JsonValue::Float).collect(),
+            TensorData::Float16(array) => array
+                .into_iter()
+                .map(|v| JsonValue::Float(v.to_f32()))
+                .collect(),
+            TensorData::Double(array) => array.into_iter().map(JsonValue::Double).collect(),
+            TensorData::Int8(array) => array
+                .into_iter()
+                .map(|v| JsonValue::Int(v as i64))
+                .collect(),
+            TensorData::UInt8(array) => array
+                .into_iter()
+                .map(|v| JsonValue::Int(v as i64))
+                .collect(),
+            TensorData::Int32(array) => array
+                .into_iter()
+                .map(|v| JsonValue::Int(v as i64))
+                .collect(),
+            TensorData::Int64(array) => array.into_iter().map(JsonValue::Int).collect(),
+            TensorData::Bool(array) => array.into_iter().map(JsonValue::Bool).collect(),
+            TensorData::String(array) => array.into_iter().map(JsonValue::String).collect(),
+        };
+
+        JsonTensor {
+            name: tensor.name,
+            dtype,
+            shape,
+            data,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<JsonTensor> for Tensor {
+    type Error = String;
+
+    fn try_from(json: JsonTensor) -> Result<Self, Self::Error> {
+        fn values<T>(
+            json: &JsonTensor,
+            value: impl Fn(&JsonValue) -> Option<T>,
+        ) -> Result<ArrayD<T>, String> {
+            let values = json
+                .data
+                .iter()
+                .enumerate()
+                .map(|(index, v)| {
+                    value(v).ok_or_else(|| {
+                        format!(
+                            "Value {} of tensor '{}' is not a valid {} value",
+                            index, json.name, json.dtype
+                        )
+                    })
+                })
+                .collect::<Result<Vec<T>, _>>()?;
+
+            ArrayD::from_shape_vec(json.shape.clone(), values).map_err(|_| {
+                format!(
+                    "Tensor '{}' has {} values, but shape {:?}",
+                    json.name,
+                    json.data.len(),
+                    json.shape
+                )
+            })
+        }
+
+        fn double(value: &JsonValue) -> Option<f64> {
+            match *value {
+                JsonValue::Double(v) => Some(v),
+                JsonValue::Float(v) => Some(v as f64),
+                JsonValue::Int(v) => Some(v as f64),
+                _ => None,
+            }
+        }
+
+        fn float(value: &JsonValue) -> Option<f32> {
+            double(value).map(|v| v as f32)
+        }
+
+        fn int(value: &JsonValue) -> Option<i64> {
+            match *value {
+                JsonValue::Int(v) => Some(v),
+                _ => None,
+            }
+        }
+
+        let data = match json.dtype.as_str() {
+            "float32" => TensorData::Float(values(&json, float)?),
+            "float16" => TensorData::Float16(values(&json, |v| float(v).map(f16::from_f32))?),
+            "float64" => TensorData::Double(values(&json, double)?),
+            "int8" => TensorData::Int8(values(&json, |v| {
+                int(v).and_then(|v| i8::try_from(v).ok())
+            })?),
+            "uint8" => TensorData::UInt8(values(&json, |v| {
+                int(v).and_then(|v| u8::try_from(v).ok())
+            })?),
+            "int32" => TensorData::Int32(values(&json, |v| {
+                int(v).and_then(|v| i32::try_from(v).ok())
+            })?),
+            "int64" => TensorData::Int64(values(&json, int)?),
+            "bool" => TensorData::Bool(values(&json, |v| match *v {
+                JsonValue::Bool(v) => Some(v),
+                _ => None,
+            })?),
+            "string" => TensorData::String(values(&json, |v| match v {
+                JsonValue::String(v) => Some(v.clone()),
+                _ => None,
+            })?),
+            dtype => {
+                return Err(format!(
+                    "Tensor '{}' has unsupported data type {}",
+                    json.name, dtype
+                ))
+            }
+        };
+
+        Ok(Tensor::from_data(data).with_name(&json.name))
+    }
+}
+
+impl TryFrom<&TensorProto> for Tensor {
+    type Error = OnnxError;
+
+    fn try_from(proto: &TensorProto) -> Result<Self, Self::Error> {
+        Tensor::from_proto(proto)
+    }
+}
+
+impl TryFrom<Tensor> for TensorProto {
+    type Error = OnnxError;
+
+    fn try_from(tensor: Tensor) -> Result<Self, Self::Error> {
+        tensor.into_proto()
+    }
+}
+
+impl fmt::Display for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:?}", self.dtype(), self.shape())?;
+        if !self.name.is_empty() {
+            write!(f, " '{}'", self.name)?;
+        }
+        write!(f, ": {}", self.preview())
+    }
+}
+
+impl fmt::Debug for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tensor")
+            .field("name", &self.name)
+            .field("dtype", &self.dtype())
+            .field("shape", &self.shape())
+            .field("values", &format_args!("{}", self.preview()))
+            .finish()
+    }
+}
@@ -1,5 +1,7 @@
 pub mod backend;
 pub mod onnx_proto;
 pub mod ops;
+pub mod testing;
+pub mod transforms;
 
 pub mod shared;
\ No newline at end of file
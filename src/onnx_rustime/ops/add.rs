@@ -1,7 +1,10 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
-use crate::onnx_rustime::ops::utils::{convert_to_output_tensor, tensor_proto_to_ndarray};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_map, broadcast_shapes, convert_to_output_tensor, tensor_proto_to_ndarray,
+};
 use ndarray::prelude::*;
+use ndarray::Zip;
 
 /// `add` - ONNX Node Implementation for Element-wise Addition
 ///
@@ -28,6 +31,7 @@ use ndarray::prelude::*;
 /// This function returns an error:
 /// * If it fails to convert any TensorProto to an ndarray.
 /// * If no tensors are provided for the addition operation.
+/// * If the shapes of the tensors can't be broadcast together.
 ///
 /// # Example
 ///
@@ -73,11 +77,29 @@ pub fn add(
         ));
     }
 
-    // Element-wise addition of the tensors
-    let result = merged_tensors
-        .iter()
-        .skip(1)
-        .fold(merged_tensors[0].clone(), |acc, x| acc + x);
+    let first = merged_tensors.remove(0);
+    let others: Vec<&ArrayD<f32>> = merged_tensors.iter().collect();
+    let result = add_ndarray(first, &others)?;
 
     convert_to_output_tensor(node, result)
 }
+
+/// `add` on decoded tensors. The sums are accumulated in `first` when it already has the shape of
+/// the result, which is the case of the residual connections.
+pub(crate) fn add_ndarray(
+    first: ArrayD<f32>,
+    others: &[&ArrayD<f32>],
+) -> Result<ArrayD<f32>, OnnxError> {
+    // Element-wise addition of the tensors, broadcast together like the operands of Sub, Mul and
+    // Div.
+    others.iter().try_fold(first, |mut acc, x| {
+        if broadcast_shapes(acc.shape(), x.shape())? == acc.shape() {
+            Zip::from(&mut acc)
+                .and_broadcast(*x)
+                .for_each(|a, &b| *a += b);
+            Ok(acc)
+        } else {
+            broadcast_map(&acc, x, |a, b| a + b)
+        }
+    })
+}
@@ -0,0 +1,154 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis};
+use std::cmp::Ordering;
+
+/// The index of the element of every lane along `axis` that `wins` over the others: the first of
+/// the tied ones, or the last one with `select_last_index`.
+fn arg_axis<T, F>(
+    data: &ArrayD<T>,
+    axis: usize,
+    keepdims: bool,
+    select_last_index: bool,
+    wins: F,
+) -> ArrayD<i64>
+where
+    T: Copy,
+    F: Fn(T, T) -> bool,
+{
+    let indices = data.map_axis(Axis(axis), |lane| {
+        let mut best = 0;
+        for (index, &value) in lane.iter().enumerate().skip(1) {
+            // A tie replaces the best element only when the last index is selected.
+            if wins(value, lane[best]) || (select_last_index && !wins(lane[best], value)) {
+                best = index;
+            }
+        }
+        best as i64
+    });
+    match keepdims {
+        true => indices.insert_axis(Axis(axis)),
+        false => indices,
+    }
+}
+
+/// Takes the ArgMax or ArgMin of the input of a node, `ordering` being the ordering of the winning
+/// element relative to the others.
+fn arg_reduce(
+    data: &TensorProto,
+    node: &NodeProto,
+    ordering: Ordering,
+) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(0))?;
+    let keepdims = get_int_attribute(&attributes, "keepdims", Some(1))? != 0;
+    let select_last_index = get_int_attribute(&attributes, "select_last_index", Some(0))? != 0;
+
+    let dims = data.get_dims();
+    let rank = dims.len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    if dims[axis] == 0 {
+        return Err(OnnxError::InvalidValue(format!(
+            "Cannot take the {} of the empty axis {}",
+            node.get_op_type(),
+            axis
+        )));
+    }
+
+    let indices = if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let data = tensor_proto_to_ndarray::<i64>(data)?;
+        arg_axis(&data, axis, keepdims, select_last_index, |a, b| {
+            a.cmp(&b) == ordering
+        })
+    } else {
+        let data = tensor_proto_to_ndarray::<f32>(data)?;
+        arg_axis(&data, axis, keepdims, select_last_index, |a, b| {
+            a.partial_cmp(&b) == Some(ordering)
+        })
+    };
+    ndarray_to_tensor_proto::<i64>(indices, output_name)
+}
+
+/// `argmax` - ONNX Node Implementation for ArgMax
+///
+/// The index of the maximum of every lane of the input along `axis`, e.g. the class of every row
+/// of scores. When the maximum occurs several times, the index of its first occurrence is taken,
+/// or of its last one with `select_last_index`.
+///
+/// The output is INT64 whatever the type of the input, so that it can be fed to the indices of a
+/// Gather.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to reduce, 0 by default. Negative values count from the end.
+/// - `keepdims` (int): 1 (default) to keep the reduced axis with a length of 1, 0 to remove it.
+/// - `select_last_index` (int): 1 to take the last occurrence of the maximum, 0 (default) the
+///   first one (since opset 12).
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor (FLOAT, FLOAT16 or INT64).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT64 tensor of the indices. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An `axis` out of range, or of length 0.
+///
+/// # Example
+///
+/// ```rust
+/// // The class of every row of [batch, classes] scores.
+/// let classes = argmax(&scores, &node)?;
+/// ```
+pub fn argmax(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    arg_reduce(input, node, Ordering::Greater)
+}
+
+/// `argmin` - ONNX Node Implementation for ArgMin
+///
+/// The index of the minimum of every lane of the input along `axis`. When the minimum occurs
+/// several times, the index of its first occurrence is taken, or of its last one with
+/// `select_last_index`.
+///
+/// The attributes, the output type and the errors are those of `argmax`.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor (FLOAT, FLOAT16 or INT64).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT64 tensor of the indices. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let nearest = argmin(&distances, &node)?;
+/// ```
+pub fn argmin(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    arg_reduce(input, node, Ordering::Less)
+}
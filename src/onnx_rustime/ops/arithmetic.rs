@@ -0,0 +1,312 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_map, extract_attributes, get_int_attribute, ndarray_to_tensor_proto,
+    tensor_proto_to_ndarray, TensorType,
+};
+use ndarray::ArrayD;
+
+/// Applies an element-wise binary operation to the two inputs of a node, broadcast together:
+/// `float` to FLOAT (and FLOAT16) inputs, `integer` to INT64 ones.
+fn binary(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    float: fn(f32, f32) -> f32,
+    integer: fn(i64, i64) -> i64,
+) -> Result<TensorProto, OnnxError> {
+    let [a, b] = match inputs {
+        [a, b] => [*a, *b],
+        _ => {
+            return Err(OnnxError::MissingInput(format!(
+                "{} expects the tensors A and B, got {} inputs",
+                node.get_op_type(),
+                inputs.len()
+            )))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let int64 = |tensor: &TensorProto| tensor.get_data_type() == TensorProto_DataType::INT64 as i32;
+    match (int64(a), int64(b)) {
+        (true, true) => {
+            let a = tensor_proto_to_ndarray::<i64>(a)?;
+            let b = tensor_proto_to_ndarray::<i64>(b)?;
+            if matches!(node.get_op_type(), "Div" | "Mod") && b.iter().any(|&b| b == 0) {
+                return Err(OnnxError::InvalidValue(format!(
+                    "Integer division by zero in a {}",
+                    node.get_op_type()
+                )));
+            }
+            let result = broadcast_map(&a, &b, |&a, &b| integer(a, b))?;
+            ndarray_to_tensor_proto::<i64>(result, output_name)
+        }
+        (false, false) => {
+            let a = tensor_proto_to_ndarray::<f32>(a)?;
+            let b = tensor_proto_to_ndarray::<f32>(b)?;
+            let result = broadcast_map(&a, &b, |&a, &b| float(a, b))?;
+            ndarray_to_tensor_proto::<f32>(result, output_name)
+        }
+        _ => Err(OnnxError::InvalidValue(format!(
+            "The inputs of a {} must have the same data type, got {} and {}",
+            node.get_op_type(),
+            a.get_data_type(),
+            b.get_data_type()
+        ))),
+    }
+}
+
+/// `sub` - ONNX Node Implementation for Sub
+///
+/// Subtracts `B` from `A` element by element. The inputs are broadcast together as in NumPy (see
+/// `broadcast_shapes`): the shapes are aligned from the last dimension on, the missing leading
+/// dimensions count as 1 and the dimensions of 1 stretch, e.g. [N, C, H, W] - [C, 1, 1].
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `A` and `B`, both FLOAT (or FLOAT16) or both INT64.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the difference, FLOAT or INT64. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or inputs of different types.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let centered = sub(&[&image, &mean], &node)?;
+/// ```
+pub fn sub(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    binary(inputs, node, |a, b| a - b, i64::wrapping_sub)
+}
+
+/// `mul` - ONNX Node Implementation for Mul
+///
+/// Multiplies `A` and `B` element by element. The broadcasting, the inputs and the errors are
+/// those of `sub`.
+///
+/// # Example
+///
+/// ```rust
+/// let scaled = mul(&[&features, &scale], &node)?;
+/// ```
+pub fn mul(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    binary(inputs, node, |a, b| a * b, i64::wrapping_mul)
+}
+
+/// `div` - ONNX Node Implementation for Div
+///
+/// Divides `A` by `B` element by element. The quotient of INT64 inputs is truncated toward zero,
+/// and a division by zero is an error. The broadcasting and the other errors are those of `sub`.
+///
+/// # Example
+///
+/// ```rust
+/// let normalized = div(&[&centered, &std], &node)?;
+/// ```
+pub fn div(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    binary(inputs, node, |a, b| a / b, i64::wrapping_div)
+}
+
+/// The remainder of `a / b` with the sign of `b`, like `%` in Python.
+fn floored_remainder<T>(a: T, b: T, remainder: fn(T, T) -> T) -> T
+where
+    T: Copy + Default + PartialOrd + std::ops::Add<Output = T>,
+{
+    let zero = T::default();
+    let r = remainder(a, b);
+    if r != zero && (r < zero) != (b < zero) {
+        r + b
+    } else {
+        r
+    }
+}
+
+/// `modulo` - ONNX Node Implementation for Mod
+///
+/// The remainder of the division of `A` by `B` element by element, e.g. to bucket positions. With
+/// `fmod` set, it is the remainder of the division truncated toward zero (`fmod` in C), with the
+/// sign of `A`; otherwise the one of the floored division (`%` in Python), with the sign of `B`:
+/// `-7 mod 3` is -1 with `fmod`, 2 without.
+///
+/// A division by zero gives NaN for FLOAT inputs and is an error for INT64 ones. The
+/// broadcasting and the other errors are those of `sub`.
+///
+/// # Attributes
+///
+/// - `fmod` (int): 1 for the sign of the dividend, 0 (default) for the sign of the divisor. The
+///   specification requires 1 for FLOAT inputs, but both are accepted.
+///
+/// # Example
+///
+/// ```rust
+/// let buckets = modulo(&[&positions, &bucket_count], &node)?;
+/// ```
+pub fn modulo(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    match get_int_attribute(&attributes, "fmod", Some(0))? != 0 {
+        true => binary(inputs, node, |a, b| a % b, i64::wrapping_rem),
+        false => binary(
+            inputs,
+            node,
+            |a, b| floored_remainder(a, b, |a, b| a % b),
+            |a, b| floored_remainder(a, b, i64::wrapping_rem),
+        ),
+    }
+}
+
+/// Combines the elements of any number of inputs, broadcast together, pair by pair from the
+/// first input on.
+fn fold_inputs<T: TensorType>(
+    inputs: &[&TensorProto],
+    f: impl Fn(&T::DataType, &T::DataType) -> T::DataType,
+) -> Result<ArrayD<T::DataType>, OnnxError> {
+    let mut inputs = inputs.iter();
+    let first = inputs
+        .next()
+        .ok_or(OnnxError::MissingInput("data_0".to_string()))?;
+    let mut result = tensor_proto_to_ndarray::<T>(first)?;
+    for input in inputs {
+        result = broadcast_map(&result, &tensor_proto_to_ndarray::<T>(input)?, &f)?;
+    }
+    Ok(result)
+}
+
+/// Checks that the inputs of a variadic operation all have the same kind of type, and returns
+/// the name of its output and whether the inputs are INT64.
+fn variadic_inputs<'a>(
+    inputs: &[&TensorProto],
+    node: &'a NodeProto,
+) -> Result<(&'a String, bool), OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let int64 = |tensor: &TensorProto| tensor.get_data_type() == TensorProto_DataType::INT64 as i32;
+    let first = inputs.first().ok_or_else(|| {
+        OnnxError::MissingInput(format!("{} expects at least one input", node.get_op_type()))
+    })?;
+    if let Some(other) = inputs.iter().find(|input| int64(input) != int64(first)) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The inputs of a {} must have the same data type, got {} and {}",
+            node.get_op_type(),
+            first.get_data_type(),
+            other.get_data_type()
+        )));
+    }
+    Ok((output_name, int64(first)))
+}
+
+/// Combines the FLOAT or INT64 inputs of a variadic operation with `float` or `integer`.
+fn variadic(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    float: fn(f32, f32) -> f32,
+    integer: fn(i64, i64) -> i64,
+) -> Result<TensorProto, OnnxError> {
+    let (output_name, int64) = variadic_inputs(inputs, node)?;
+    match int64 {
+        true => ndarray_to_tensor_proto::<i64>(
+            fold_inputs::<i64>(inputs, |&a, &b| integer(a, b))?,
+            output_name,
+        ),
+        false => ndarray_to_tensor_proto::<f32>(
+            fold_inputs::<f32>(inputs, |&a, &b| float(a, b))?,
+            output_name,
+        ),
+    }
+}
+
+/// `sum` - ONNX Node Implementation for Sum
+///
+/// The element-wise sum of any number of inputs, e.g. of the feature maps of the branches of a
+/// DenseNet block. The inputs are broadcast together as in NumPy (see `broadcast_shapes`), and a
+/// single input is copied as is.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors to sum, at least one, all FLOAT (or FLOAT16) or all INT64.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the sum, FLOAT or INT64. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * No inputs, or inputs of different types.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let merged = sum(&[&branch_1, &branch_2, &branch_3], &node)?;
+/// ```
+pub fn sum(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    variadic(inputs, node, |a, b| a + b, i64::wrapping_add)
+}
+
+/// `max` - ONNX Node Implementation for Max
+///
+/// The element-wise maximum of any number of inputs; NaN wins over any other value, like in
+/// `numpy.maximum`. The broadcasting, the inputs and the errors are those of `sum`.
+///
+/// # Example
+///
+/// ```rust
+/// let clipped_below = max(&[&x, &floor_value], &node)?;
+/// ```
+pub fn max(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let float = |a: f32, b: f32| if a.is_nan() || a > b { a } else { b };
+    variadic(inputs, node, float, i64::max)
+}
+
+/// `min` - ONNX Node Implementation for Min
+///
+/// The element-wise minimum of any number of inputs; NaN wins over any other value, like in
+/// `numpy.minimum`. The broadcasting, the inputs and the errors are those of `sum`.
+///
+/// # Example
+///
+/// ```rust
+/// let clipped_above = min(&[&x, &ceiling_value], &node)?;
+/// ```
+pub fn min(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let float = |a: f32, b: f32| if a.is_nan() || a < b { a } else { b };
+    variadic(inputs, node, float, i64::min)
+}
+
+/// `mean` - ONNX Node Implementation for Mean
+///
+/// The element-wise mean of any number of FLOAT inputs, e.g. of the predictions of an ensemble.
+/// The broadcasting and the errors are those of `sum`.
+///
+/// # Example
+///
+/// ```rust
+/// let ensemble = mean(&[&model_1, &model_2, &model_3], &node)?;
+/// ```
+pub fn mean(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (output_name, int64) = variadic_inputs(inputs, node)?;
+    if int64 {
+        return Err(OnnxError::UnsupportedOperation(
+            "Mean is not defined on INT64 tensors".to_string(),
+        ));
+    }
+    let count = inputs.len() as f32;
+    let total = fold_inputs::<f32>(inputs, |a, b| a + b)?;
+    ndarray_to_tensor_proto::<f32>(total.mapv(|x| x / count), output_name)
+}
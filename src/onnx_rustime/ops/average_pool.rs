@@ -0,0 +1,110 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, pool_geometry,
+    tensor_proto_to_ndarray, PoolAxis,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+
+/// `average_pool` - ONNX Node Implementation for Average Pooling Operation
+///
+/// Averages the values of the input tensor over windows sliding along its spatial axes (all the
+/// axes after the batch and the channels), each window giving one output value.
+///
+/// The averages are separable: the input is averaged along one spatial axis at a time, each pass
+/// dividing by the number of taps of the window along its axis.
+///
+/// # Attributes
+///
+/// - `kernel_shape` (ints): The size of the window along each spatial axis, required.
+/// - `strides`, `dilations` (ints): The steps between the windows and between their taps, 1
+///   by default.
+/// - `pads` (ints): The padding at the beginning and at the end of each spatial axis (the
+///   beginnings first), 0 by default. Ignored with `auto_pad`.
+/// - `auto_pad` (string): `NOTSET` (explicit `pads`), `SAME_UPPER`, `SAME_LOWER` or `VALID`.
+/// - `ceil_mode` (int): Round the number of windows up instead of down, the extra window being
+///   clipped to the padded input. 0 by default.
+/// - `count_include_pad` (int): Whether the padded positions count in the divisor of a window
+///   overlapping the padding (they are always zero in the sum). With 0 (the default), the border
+///   windows are the average of their input values only.
+///
+/// The output spatial shape follows `pool_geometry`.
+///
+/// # Arguments
+///
+/// * `inputs` - A reference to the `[N, C, D1, ..., Dn]` tensor to be average pooled.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor after average pooling. In case of
+///   an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, or a missing `kernel_shape`.
+/// * An input without spatial axes, or attributes not matching its spatial axes.
+/// * A window that doesn't fit in the padded input.
+///
+/// # Example
+///
+/// ```rust
+/// let result_tensor = average_pool(&input_tensor, &node);
+/// ```
+pub fn average_pool(inputs: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let count_include_pad = get_int_attribute(&attributes, "count_include_pad", Some(0))? != 0;
+
+    let input = tensor_proto_to_ndarray::<f32>(inputs)?;
+    if input.ndim() < 3 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input with spatial axes, got shape {:?}",
+            input.shape()
+        )));
+    }
+
+    let geometry = pool_geometry(&attributes, &input.shape()[2..])?;
+    let result = geometry
+        .iter()
+        .enumerate()
+        .fold(input, |array, (axis, geometry)| {
+            average_along_axis(&array, Axis(axis + 2), geometry, count_include_pad)
+        });
+
+    convert_to_output_tensor(node, result)
+}
+
+/// Averages the windows of one spatial axis, in parallel across the windows.
+fn average_along_axis(
+    input: &ArrayD<f32>,
+    axis: Axis,
+    geometry: &PoolAxis,
+    count_include_pad: bool,
+) -> ArrayD<f32> {
+    let input_len = input.len_of(axis);
+    let mut shape = input.shape().to_vec();
+    shape[axis.index()] = geometry.output_len;
+
+    let mut output = ArrayD::zeros(shape);
+    output
+        .axis_iter_mut(axis)
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(window, mut sum)| {
+            let mut count = 0;
+            for position in geometry.taps(window) {
+                if (0..input_len as isize).contains(&position) {
+                    sum += &input.index_axis(axis, position as usize);
+                    count += 1;
+                } else if count_include_pad && geometry.in_padded_input(position, input_len) {
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                sum /= count as f32;
+            }
+        });
+    output
+}
@@ -1,11 +1,10 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, get_float_attribute, stack_along_batch_dimension,
-    tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, get_float_attribute, tensor_proto_to_ndarray,
 };
+use ndarray::parallel::prelude::*;
 use ndarray::prelude::*;
-use rayon::prelude::*;
 
 /// `batch_normalization` - ONNX Node Implementation for Batch Normalization
 ///
@@ -16,6 +15,16 @@ use rayon::prelude::*;
 /// * Inference mode (default): Uses the provided estimated statistics ('input_mean' and 'input_var').
 /// * Training mode: Uses the running statistics.
 ///
+/// The statistics and the parameters are per channel (axis 1), broadcast over the batch and the
+/// trailing axes, so the input can have any rank of at least 2 (`[N, C]`, `[N, C, L]`,
+/// `[N, C, H, W]`, `[N, C, D, H, W]`...).
+///
+/// # Attributes
+///
+/// - `epsilon` (float): Added to the variance to avoid dividing by zero, 1e-5 by default.
+/// - `momentum` (float): The factor of the running statistics, 0.9 by default. Only used to
+///   update them in training mode, so it doesn't change the output.
+///
 /// # Arguments
 ///
 /// * `input` - A reference to the input tensor 'X'.
@@ -27,6 +36,13 @@ use rayon::prelude::*;
 /// * `Result<TensorProto, OnnxError>` - Returns the batch-normalized output as a `TensorProto` or
 ///   an error (`OnnxError`) if the operation fails at any stage.
 ///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes.
+/// * An input of rank lower than 2, or missing parameter tensors.
+/// * Parameter tensors whose length isn't the number of channels.
+///
 /// # Example
 ///
 /// ```rust
@@ -37,53 +53,70 @@ pub fn batch_normalization(
     initializers: &Vec<&TensorProto>,
     node: &NodeProto,
 ) -> Result<TensorProto, OnnxError> {
-    // Extract node attributes.
-    let attributes = extract_attributes(node.get_attribute())?;
-    let epsilon = get_float_attribute(&attributes, "epsilon", Some(1e-05))?;
-
     // Convert TensorProtos to ndarrays.
     let x = tensor_proto_to_ndarray::<f32>(input)?;
-    let scale = tensor_proto_to_ndarray::<f32>(initializers[0])?;
-    let bias = tensor_proto_to_ndarray::<f32>(initializers[1])?;
-    let mean = tensor_proto_to_ndarray::<f32>(initializers[2])?;
-    let var = tensor_proto_to_ndarray::<f32>(initializers[3])?;
+    let parameters = initializers
+        .iter()
+        .map(|parameter| tensor_proto_to_ndarray::<f32>(parameter))
+        .collect::<Result<Vec<_>, OnnxError>>()?;
+    let parameters: Vec<&ArrayD<f32>> = parameters.iter().collect();
 
-    let batch_size = x.shape()[0];
+    let result = batch_normalization_ndarray(x, &parameters, node)?;
+    convert_to_output_tensor(node, result)
+}
 
-    // Reshape tensors for broadcasting.
-    let broadcasted_mean = mean
-        .into_shape((x.shape()[1], 1, 1))
-        .map_err(|_| OnnxError::ShapeMismatch("Failed to broadcast mean".into()))?;
+/// `batch_normalization` on the decoded input, normalized in place, and parameter tensors.
+pub(crate) fn batch_normalization_ndarray(
+    mut x: ArrayD<f32>,
+    parameters: &[&ArrayD<f32>],
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
+    // Extract node attributes.
+    let attributes = extract_attributes(node.get_attribute())?;
+    let epsilon = get_float_attribute(&attributes, "epsilon", Some(1e-05))?;
 
-    let broadcasted_var = var
-        .into_shape((x.shape()[1], 1, 1))
-        .map_err(|_| OnnxError::ShapeMismatch("Failed to broadcast variance".into()))?;
+    if parameters.len() < 4 {
+        return Err(OnnxError::MissingInput(format!(
+            "BatchNormalization requires scale, B, input_mean and input_var, got {} tensors",
+            parameters.len()
+        )));
+    }
 
-    let broadcasted_scale = scale
-        .into_shape((x.shape()[1], 1, 1))
-        .map_err(|_| OnnxError::ShapeMismatch("Failed to broadcast scale".into()))?;
+    if x.ndim() < 2 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input of shape [N, C, ...], got {:?}",
+            x.shape()
+        )));
+    }
+    let channels = x.shape()[1];
 
-    let broadcasted_bias = bias
-        .into_shape((x.shape()[1], 1, 1))
-        .map_err(|_| OnnxError::ShapeMismatch("Failed to broadcast bias".into()))?;
+    // The per-channel tensors, flattened.
+    let parameter = |index: usize, name: &str| -> Result<Vec<f32>, OnnxError> {
+        let parameter = parameters[index];
+        if parameter.len() != channels {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Expected one value per channel ({}) in {}, got shape {:?}",
+                channels,
+                name,
+                parameter.shape()
+            )));
+        }
+        Ok(parameter.iter().copied().collect())
+    };
+    let scale = parameter(0, "scale")?;
+    let bias = parameter(1, "B")?;
+    let mean = parameter(2, "input_mean")?;
+    let var = parameter(3, "input_var")?;
 
-    // Process batches.
-    let result_list: Vec<_> = (0..batch_size)
+    // Normalize every channel with the formula: out = (x - mean) / sqrt(var + epsilon) * scale + bias
+    x.axis_iter_mut(Axis(1))
         .into_par_iter()
-        .map(|i| {
-            let batch_data = x.index_axis(Axis(0), i);
+        .enumerate()
+        .for_each(|(c, mut channel)| {
+            let factor = scale[c] / (var[c] + epsilon).sqrt();
+            let shift = bias[c] - mean[c] * factor;
+            channel.mapv_inplace(|v| v * factor + shift);
+        });
 
-            // Normalize using the formula: normalized = (x - mean) / sqrt(var + epsilon)
-            let normalized =
-                (&batch_data - &broadcasted_mean) / (&broadcasted_var + epsilon).mapv(|v| v.sqrt());
-
-            // Apply scale and bias: out = normalized * scale + bias
-            normalized * &broadcasted_scale + &broadcasted_bias
-        })
-        .collect();
-
-    // Concatenate results along the batch dimension.
-    let result = stack_along_batch_dimension(result_list)?;
-
-    convert_to_output_tensor(node, result)
+    Ok(x)
 }
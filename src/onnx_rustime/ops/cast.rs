@@ -0,0 +1,207 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use half::f16;
+use ndarray::ArrayD;
+use protobuf::ProtobufEnum;
+
+/// The elements of the input of a Cast, widened without loss to the largest type of their kind.
+enum Elements {
+    Floats(ArrayD<f64>),
+    Integers(ArrayD<i64>),
+    Booleans(ArrayD<bool>),
+}
+
+impl Elements {
+    fn decode(input: &TensorProto) -> Result<Self, OnnxError> {
+        let data_type = input.get_data_type();
+        Ok(match TensorProto_DataType::from_i32(data_type) {
+            Some(TensorProto_DataType::FLOAT) | Some(TensorProto_DataType::FLOAT16) => {
+                Elements::Floats(tensor_proto_to_ndarray::<f32>(input)?.mapv(f64::from))
+            }
+            Some(TensorProto_DataType::DOUBLE) => {
+                Elements::Floats(tensor_proto_to_ndarray::<f64>(input)?)
+            }
+            Some(TensorProto_DataType::INT8) => {
+                Elements::Integers(tensor_proto_to_ndarray::<i8>(input)?.mapv(i64::from))
+            }
+            Some(TensorProto_DataType::UINT8) => {
+                Elements::Integers(tensor_proto_to_ndarray::<u8>(input)?.mapv(i64::from))
+            }
+            Some(TensorProto_DataType::INT32) => {
+                Elements::Integers(tensor_proto_to_ndarray::<i32>(input)?.mapv(i64::from))
+            }
+            Some(TensorProto_DataType::INT64) => {
+                Elements::Integers(tensor_proto_to_ndarray::<i64>(input)?)
+            }
+            Some(TensorProto_DataType::BOOL) => {
+                Elements::Booleans(tensor_proto_to_ndarray::<bool>(input)?)
+            }
+            _ => {
+                return Err(OnnxError::UnsupportedOperation(format!(
+                    "Cast from data type {} is not supported",
+                    data_type
+                )))
+            }
+        })
+    }
+
+    /// The elements converted with the function for their kind.
+    fn convert<T>(
+        &self,
+        from_float: impl Fn(f64) -> T,
+        from_integer: impl Fn(i64) -> T,
+        from_boolean: impl Fn(bool) -> T,
+    ) -> ArrayD<T> {
+        match self {
+            Elements::Floats(array) => array.mapv(from_float),
+            Elements::Integers(array) => array.mapv(from_integer),
+            Elements::Booleans(array) => array.mapv(from_boolean),
+        }
+    }
+}
+
+/// `cast` - ONNX Node Implementation for the Cast Operation
+///
+/// Converts the elements of the input tensor to the element type given by the `to` attribute,
+/// among FLOAT, DOUBLE, FLOAT16, INT8, UINT8, INT32, INT64 and BOOL, with the semantics of a
+/// NumPy `astype`:
+///
+/// * floating point values are rounded to the nearest value of a narrower floating point type;
+/// * integers are rounded to the nearest floating point value (INT64 values beyond 2^24 can't all
+///   be represented as FLOAT);
+/// * floating point values are truncated toward zero to integers, saturating at the bounds of the
+///   integer type (NaN gives 0);
+/// * integers are wrapped to a narrower integer type;
+/// * BOOL values are 0 and 1, and every value other than 0 (NaN included) is true.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the tensor whose elements will be converted.
+/// * `node` - A reference to the ONNX NodeProto containing the `to` attribute.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the converted tensor, or an error (`OnnxError`)
+///   if the source or target type is not supported.
+///
+/// # Errors
+///
+/// Potential errors include:
+/// * Missing `to` attribute.
+/// * Conversion from `TensorProto` to ndarray not succeeding.
+/// * A source or target element type other than the ones listed above.
+///
+/// # Example
+///
+/// ```rust
+/// let casted_tensor = cast(&input_tensor, &node);
+/// ```
+pub fn cast(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let to = get_int_attribute(&attributes, "to", None)? as i32;
+
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    cast_to(input, to, output_name)
+}
+
+/// `cast_like` - ONNX Node Implementation for the CastLike Operation
+///
+/// Converts the elements of the input tensor to the element type of the `target_type` tensor,
+/// with the semantics of `cast`, e.g. a FLOAT constant cast to the FLOAT16 activations of a mixed
+/// precision graph. The values of `target_type` are ignored; when it already has the type of the
+/// input, the output has the values of the input.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input` whose elements will be converted, and the tensor
+///   `target_type` whose element type is the one of the output.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the converted tensor, or an error (`OnnxError`)
+///   if the source or target type is not supported.
+///
+/// # Errors
+///
+/// Potential errors include:
+/// * Missing inputs.
+/// * A source or target element type other than the ones of `cast`.
+///
+/// # Example
+///
+/// ```rust
+/// let half_bias = cast_like(&[&bias, &half_activations], &node)?;
+/// ```
+pub fn cast_like(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let target_type = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("target_type".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    cast_to(input, target_type.get_data_type(), output_name)
+}
+
+/// Converts the elements of `input` to the data type `to`, and encodes them as the output `name`.
+fn cast_to(input: &TensorProto, to: i32, output_name: &str) -> Result<TensorProto, OnnxError> {
+    let elements = Elements::decode(input)?;
+
+    // The `as` conversions from floats to integers truncate toward zero and saturate.
+    match TensorProto_DataType::from_i32(to) {
+        Some(TensorProto_DataType::FLOAT) => ndarray_to_tensor_proto::<f32>(
+            elements.convert(|x| x as f32, |x| x as f32, |x| u8::from(x).into()),
+            output_name,
+        ),
+        Some(TensorProto_DataType::DOUBLE) => ndarray_to_tensor_proto::<f64>(
+            elements.convert(|x| x, |x| x as f64, |x| u8::from(x).into()),
+            output_name,
+        ),
+        Some(TensorProto_DataType::FLOAT16) => ndarray_to_tensor_proto::<f16>(
+            elements.convert(
+                f16::from_f64,
+                |x| f16::from_f64(x as f64),
+                |x| f16::from(u8::from(x)),
+            ),
+            output_name,
+        ),
+        Some(TensorProto_DataType::INT8) => ndarray_to_tensor_proto::<i8>(
+            elements.convert(|x| x as i8, |x| x as i8, i8::from),
+            output_name,
+        ),
+        Some(TensorProto_DataType::UINT8) => ndarray_to_tensor_proto::<u8>(
+            elements.convert(|x| x as u8, |x| x as u8, u8::from),
+            output_name,
+        ),
+        Some(TensorProto_DataType::INT32) => ndarray_to_tensor_proto::<i32>(
+            elements.convert(|x| x as i32, |x| x as i32, i32::from),
+            output_name,
+        ),
+        Some(TensorProto_DataType::INT64) => ndarray_to_tensor_proto::<i64>(
+            elements.convert(|x| x as i64, |x| x, i64::from),
+            output_name,
+        ),
+        Some(TensorProto_DataType::BOOL) => ndarray_to_tensor_proto::<bool>(
+            elements.convert(|x| x != 0.0, |x| x != 0, |x| x),
+            output_name,
+        ),
+        _ => Err(OnnxError::UnsupportedOperation(format!(
+            "Cast to data type {} is not supported",
+            to
+        ))),
+    }
+}
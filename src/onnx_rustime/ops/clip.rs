@@ -0,0 +1,84 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, tensor_proto_to_ndarray,
+};
+
+/// `clip` - ONNX Node Implementation for Clipping (Clip)
+///
+/// Limits every value of the input to the interval `[min, max]`, e.g. to `[0, 6]` for the ReLU6 of
+/// MobileNet. When `min` is greater than `max`, every value becomes `max`; NaN values stay NaN.
+///
+/// The bounds moved between operator sets: before opset 11 they are the `min` and `max`
+/// attributes, since opset 11 the optional `min` and `max` inputs, scalar tensors given by an
+/// initializer or computed by the graph. Both forms are accepted, an attribute taking the place of
+/// the input. A bound given by neither is unbounded (-inf for `min`, +inf for `max`).
+///
+/// # Attributes
+///
+/// - `min` (float): The lower bound, in the graphs before opset 11.
+/// - `max` (float): The upper bound, in the graphs before opset 11.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input` and the optional scalar `min` and `max`, by position among the
+///   node inputs (`None` for an omitted optional input).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the clipped tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input`.
+/// * A `min` or `max` input that isn't a single value.
+///
+/// # Example
+///
+/// ```rust
+/// let relu6 = clip(&[Some(&input_tensor), Some(&zero), Some(&six)], &node)?;
+/// ```
+pub fn clip(inputs: &[Option<&TensorProto>], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let x = input(0).ok_or(OnnxError::MissingInput("input".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let bound = |name: &str, index: usize, unbounded: f32| -> Result<f32, OnnxError> {
+        if let Some(value) = attributes.get(name).and_then(|attr| attr.as_float()) {
+            return Ok(value);
+        }
+        match input(index) {
+            Some(tensor) => {
+                let values = tensor_proto_to_ndarray::<f32>(tensor)?;
+                match values.iter().next() {
+                    Some(&value) if values.len() == 1 => Ok(value),
+                    _ => Err(OnnxError::InvalidValue(format!(
+                        "The {} of Clip must be a single value, got a tensor of shape {:?}",
+                        name,
+                        values.shape()
+                    ))),
+                }
+            }
+            None => Ok(unbounded),
+        }
+    };
+    let min = bound("min", 1, f32::NEG_INFINITY)?;
+    let max = bound("max", 2, f32::INFINITY)?;
+
+    let x = tensor_proto_to_ndarray::<f32>(x)?;
+    convert_to_output_tensor(
+        node,
+        x.mapv(|value| {
+            // Applied in this order, a min greater than max gives max.
+            let value = if value < min { min } else { value };
+            if value > max {
+                max
+            } else {
+                value
+            }
+        }),
+    )
+}
@@ -0,0 +1,172 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_map, ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use std::cmp::Ordering;
+
+/// Compares the elements of `a` and `b`, broadcast together, and encodes as the BOOL output `name`
+/// whether their ordering is one of `orderings`.
+fn compare_as<T>(
+    a: &TensorProto,
+    b: &TensorProto,
+    orderings: &[Ordering],
+    name: &str,
+) -> Result<TensorProto, OnnxError>
+where
+    T: TensorType,
+    T::DataType: PartialOrd,
+{
+    let a = tensor_proto_to_ndarray::<T>(a)?;
+    let b = tensor_proto_to_ndarray::<T>(b)?;
+    // NaN is unordered: all the comparisons with it are false.
+    let result = broadcast_map(&a, &b, |a, b| {
+        a.partial_cmp(b)
+            .is_some_and(|ordering| orderings.contains(&ordering))
+    })?;
+    ndarray_to_tensor_proto::<bool>(result, name)
+}
+
+/// Compares the two inputs of a node, the result being true where the ordering of the element of
+/// `A` relative to the one of `B` is one of `orderings`.
+fn compare(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    orderings: &[Ordering],
+) -> Result<TensorProto, OnnxError> {
+    let [a, b] = match inputs {
+        [a, b] => [*a, *b],
+        _ => {
+            return Err(OnnxError::MissingInput(format!(
+                "{} expects the tensors A and B, got {} inputs",
+                node.get_op_type(),
+                inputs.len()
+            )))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let data_type = a.get_data_type();
+    let float = |data_type: i32| {
+        data_type == TensorProto_DataType::FLOAT as i32
+            || data_type == TensorProto_DataType::FLOAT16 as i32
+    };
+    if data_type != b.get_data_type() && !(float(data_type) && float(b.get_data_type())) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The inputs of a {} must have the same data type, got {} and {}",
+            node.get_op_type(),
+            data_type,
+            b.get_data_type()
+        )));
+    }
+
+    match data_type {
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            compare_as::<i64>(a, b, orderings, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            compare_as::<bool>(a, b, orderings, output_name)
+        }
+        _ => compare_as::<f32>(a, b, orderings, output_name),
+    }
+}
+
+/// `equal` - ONNX Node Implementation for Equal
+///
+/// Compares the two inputs element by element, the BOOL output being true where they are equal.
+/// The inputs are broadcast together as in NumPy (see `broadcast_shapes`), e.g. to compare a
+/// tensor with a scalar.
+///
+/// The output feeds the condition of a Where, a Not or a Cast.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `A` and `B`, of the same type (FLOAT, FLOAT16, INT64 or BOOL).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the BOOL tensor of the comparisons. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or inputs of different types.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let padding_mask = equal(&[&token_ids, &padding_id], &node)?;
+/// ```
+pub fn equal(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    compare(inputs, node, &[Ordering::Equal])
+}
+
+/// `greater` - ONNX Node Implementation for Greater
+///
+/// Compares the two inputs element by element, the BOOL output being true where `A` is greater
+/// than `B`. Comparisons with NaN are false.
+///
+/// The broadcasting, the inputs and the errors are those of `equal`, but for BOOL inputs, which
+/// are only accepted by Equal.
+///
+/// # Example
+///
+/// ```rust
+/// let confident = greater(&[&scores, &threshold], &node)?;
+/// ```
+pub fn greater(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    compare(inputs, node, &[Ordering::Greater])
+}
+
+/// `greater_or_equal` - ONNX Node Implementation for GreaterOrEqual
+///
+/// Compares the two inputs element by element, the BOOL output being true where `A` is greater
+/// than or equal to `B`. The broadcasting, the inputs and the errors are those of `greater`.
+///
+/// # Example
+///
+/// ```rust
+/// let reached = greater_or_equal(&[&positions, &length], &node)?;
+/// ```
+pub fn greater_or_equal(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    compare(inputs, node, &[Ordering::Greater, Ordering::Equal])
+}
+
+/// `less` - ONNX Node Implementation for Less
+///
+/// Compares the two inputs element by element, the BOOL output being true where `A` is less than
+/// `B`. The broadcasting, the inputs and the errors are those of `greater`.
+///
+/// # Example
+///
+/// ```rust
+/// let in_range = less(&[&indices, &length], &node)?;
+/// ```
+pub fn less(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    compare(inputs, node, &[Ordering::Less])
+}
+
+/// `less_or_equal` - ONNX Node Implementation for LessOrEqual
+///
+/// Compares the two inputs element by element, the BOOL output being true where `A` is less than
+/// or equal to `B`. The broadcasting, the inputs and the errors are those of `greater`.
+///
+/// # Example
+///
+/// ```rust
+/// let causal_mask = less_or_equal(&[&key_positions, &query_positions], &node)?;
+/// ```
+pub fn less_or_equal(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    compare(inputs, node, &[Ordering::Less, Ordering::Equal])
+}
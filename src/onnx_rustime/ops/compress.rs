@@ -0,0 +1,138 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use half::f16;
+use ndarray::{Axis, IxDyn};
+
+/// Selects the slices of `input` at `indices` along `axis`, or its flattened elements at
+/// `indices` without axis, and encodes them as the output `name`.
+fn compress_as<T: TensorType>(
+    input: &TensorProto,
+    indices: &[usize],
+    axis: Option<usize>,
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<T>(input)?;
+    let selected = match axis {
+        Some(axis) => x.select(Axis(axis), indices),
+        None => {
+            let len = x.len();
+            let flattened = x
+                .into_shape(IxDyn(&[len]))
+                .map_err(|e| OnnxError::ShapeError(format!("Failed to flatten: {}", e)))?;
+            flattened.select(Axis(0), indices)
+        }
+    };
+    ndarray_to_tensor_proto::<T>(selected, name)
+}
+
+/// `compress` - ONNX Node Implementation for Compress
+///
+/// Selects the slices of the input along `axis` where the BOOL `condition` is true, like
+/// `np.compress`, e.g. the detections kept by a comparison with a threshold. Without `axis` the
+/// input is flattened first and the elements are selected. The condition can be shorter than the
+/// axis: the slices past its end are dropped.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis of the slices, negative values counting from the end. The input is
+///   flattened without it.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input` (FLOAT, FLOAT16, DOUBLE, INT64, INT32 or BOOL) and the 1-D
+///   BOOL tensor `condition`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the selected slices (or elements), of the type of
+///   `input`. The axis is empty when no condition is true. In case of an unsuccessful operation, it
+///   returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or a `condition` that isn't 1-D or is longer than the axis.
+/// * An `axis` out of range, or an input of an unsupported type.
+///
+/// # Example
+///
+/// ```rust
+/// // The rows of the boxes whose score passed a Greater.
+/// let kept_boxes = compress(&[&boxes, &confident], &node)?;
+/// ```
+pub fn compress(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let condition = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("condition".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let rank = input.get_dims().len() as i64;
+    let axis = match attributes.get("axis").and_then(|attr| attr.as_int()) {
+        Some(axis) if axis < -rank || axis >= rank => {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )))
+        }
+        Some(axis) => Some(if axis < 0 { axis + rank } else { axis } as usize),
+        None => None,
+    };
+    let len = match axis {
+        Some(axis) => input.get_dims()[axis],
+        None => input.get_dims().iter().product(),
+    } as usize;
+
+    let condition = tensor_proto_to_ndarray::<bool>(condition)?;
+    if condition.ndim() != 1 || condition.len() > len {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The condition of a Compress must be 1-D and not longer than the {} slices of the \
+             input, got shape {:?}",
+            len,
+            condition.shape()
+        )));
+    }
+    let indices: Vec<usize> = condition
+        .iter()
+        .enumerate()
+        .filter(|(_, &selected)| selected)
+        .map(|(index, _)| index)
+        .collect();
+
+    match input.get_data_type() {
+        t if t == TensorProto_DataType::FLOAT as i32 => {
+            compress_as::<f32>(input, &indices, axis, output_name)
+        }
+        t if t == TensorProto_DataType::FLOAT16 as i32 => {
+            compress_as::<f16>(input, &indices, axis, output_name)
+        }
+        t if t == TensorProto_DataType::DOUBLE as i32 => {
+            compress_as::<f64>(input, &indices, axis, output_name)
+        }
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            compress_as::<i64>(input, &indices, axis, output_name)
+        }
+        t if t == TensorProto_DataType::INT32 as i32 => {
+            compress_as::<i32>(input, &indices, axis, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            compress_as::<bool>(input, &indices, axis, output_name)
+        }
+        t => Err(OnnxError::UnsupportedOperation(format!(
+            "Compress doesn't support the data type {}",
+            t
+        ))),
+    }
+}
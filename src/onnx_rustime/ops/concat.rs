@@ -1,7 +1,10 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
-use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, get_int_attribute, ndarray_to_tensor_proto,
+    tensor_proto_to_ndarray,
 };
 use ndarray::*;
 
@@ -12,65 +15,115 @@ use ndarray::*;
 ///
 /// # Arguments
 ///
-/// * `inputs` - A reference to a vector containing the input tensors to concatenate.
+/// * `inputs` - The tensors to concatenate, any number of them, in the order of the node inputs:
+///   tensors computed by the graph and initializers alike. They are FLOAT (or FLOAT16, upcast), or
+///   all INT64.
 /// * `node` - A reference to the ONNX NodeProto that describes the node in the ONNX computation graph.
 ///
+/// # Attributes
+///
+/// * `axis` - The axis to concatenate along. A negative value counts from the back, in the range
+///   `[-r, r-1]` where `r` is the rank of the inputs.
+///
 /// # Returns
 ///
 /// * `Result<TensorProto, OnnxError>` - Returns the concatenated output as a `TensorProto` or
 ///   an error (`OnnxError`) if the operation fails at any stage.
 ///
+/// # Errors
+///
+/// Possible errors include:
+/// * No inputs, or an `axis` out of range.
+/// * An input of another rank than the first one, or whose dimensions other than `axis` differ
+///   from the ones of the first input (the error names the input).
+///
 /// # Example
 ///
 /// ```rust
 /// let concatenated_result = concat(&input_tensors, &node);
 /// ```
-pub fn concat(inputs: &Vec<&TensorProto>, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+pub fn concat(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
     // Extract node attributes.
     let attributes = extract_attributes(node.get_attribute())?;
-    let axis = get_int_attribute(&attributes, "axis", None)? as usize;
+    let axis = get_int_attribute(&attributes, "axis", None)?;
+
+    let names: Vec<&str> = inputs.iter().map(|tp| tp.get_name()).collect();
 
-    let inputs_nd_array: Vec<_> = inputs
+    // INT64 inputs are concatenated as such, e.g. the dimensions of a shape computed by the graph.
+    let int64 = inputs
+        .first()
+        .is_some_and(|tp| tp.get_data_type() == TensorProto_DataType::INT64 as i32);
+    if int64 {
+        let output_name = node
+            .get_output()
+            .first()
+            .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+        let inputs_nd_array = inputs
+            .iter()
+            .map(|tp| tensor_proto_to_ndarray::<i64>(tp))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = concat_tensors(inputs_nd_array, &names, axis)?;
+        return ndarray_to_tensor_proto::<i64>(result, output_name);
+    }
+
+    let inputs_nd_array = inputs
         .iter()
-        .map(|tp| tensor_proto_to_ndarray::<f32>(tp).unwrap())
-        .collect();
+        .map(|tp| tensor_proto_to_ndarray::<f32>(tp))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let result = concat_tensors(inputs_nd_array, axis)?;
+    let result = concat_tensors(inputs_nd_array, &names, axis)?;
 
     convert_to_output_tensor(node, result)
 }
 
+/// Concatenates `tensors` along `axis` (negative values counting from the back), after checking
+/// that their other dimensions match the ones of the first tensor. `names` identify the tensors
+/// in the errors.
 fn concat_tensors<T, D>(
     tensors: Vec<ArrayBase<OwnedRepr<T>, D>>,
-    axis: usize,
+    names: &[&str],
+    axis: i64,
 ) -> Result<ArrayBase<OwnedRepr<T>, D>, OnnxError>
 where
     T: Clone,
     D: Dimension + RemoveAxis,
 {
-    // Ensure all tensors have the same dimensionality
-    let first_dim = tensors[0].ndim();
+    let first = tensors.first().ok_or(OnnxError::MissingInput(
+        "Concat needs at least one input".to_string(),
+    ))?;
 
-    if tensors.iter().any(|tensor| tensor.ndim() != first_dim) {
-        return Err(OnnxError::ShapeError(
-            "All tensors must have the same number of dimensions.".to_string(),
-        ));
+    // Ensure the specified axis is valid
+    let rank = first.ndim() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for tensors of rank {}",
+            axis, rank
+        )));
     }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
 
-    // Ensure the specified axis is valid
-    if axis >= first_dim {
-        return Err(OnnxError::ShapeError(
-            "Specified axis is out of bounds for the given tensors.".to_string(),
-        ));
+    // Ensure all tensors have the rank of the first one, and its dimensions besides the axis.
+    for (index, tensor) in tensors.iter().enumerate().skip(1) {
+        let matches = tensor.ndim() == first.ndim()
+            && (0..first.ndim())
+                .all(|dim| dim == axis || tensor.shape()[dim] == first.shape()[dim]);
+        if !matches {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Can't concatenate input {} ('{}') of shape {:?} with input 0 ('{}') of shape {:?} \
+                 along axis {}: all the other dimensions must match",
+                index,
+                names.get(index).unwrap_or(&""),
+                tensor.shape(),
+                names.first().unwrap_or(&""),
+                first.shape(),
+                axis
+            )));
+        }
     }
 
     // Convert to ArrayView for concatenate function
     let views: Vec<_> = tensors.iter().map(|tensor| tensor.view()).collect();
 
     // Concatenate along the specified axis
-    let concatenated_output = concatenate(Axis(axis), &views).map_err(|_| {
-        OnnxError::ShapeError("Failed to concatenate tensors along specified axis.".to_string())
-    })?;
-
-    Ok(concatenated_output)
+    concatenate(Axis(axis), &views).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
 }
@@ -0,0 +1,191 @@
+use crate::onnx_rustime::backend::helper::{make_tensor, OnnxError, TensorValue};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use half::f16;
+use ndarray::{ArrayD, IxDyn};
+
+/// The name of the (single) output of a node.
+fn output_name(node: &NodeProto) -> Result<&str, OnnxError> {
+    node.get_output()
+        .first()
+        .map(|name| name.as_str())
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))
+}
+
+/// `constant` - ONNX Node Implementation for Constant
+///
+/// The value held by the node, e.g. the shapes and indices of the graphs exported without folding
+/// their constants into initializers. The value is a tensor of any type in the `value` attribute,
+/// or, since opset 12, a FLOAT or INT64 scalar or vector in one of the `value_float`,
+/// `value_floats`, `value_int` and `value_ints` attributes.
+///
+/// # Attributes
+///
+/// - `value` (tensor): The tensor of the node.
+/// - `value_float`, `value_int` (float, int): A FLOAT or INT64 scalar, since opset 12.
+/// - `value_floats`, `value_ints` (floats, ints): A FLOAT or INT64 vector, since opset 12.
+///
+/// # Arguments
+///
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the value of the node. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A node without one of the attributes above, e.g. with a `sparse_value` or a string value,
+///   which are not supported.
+///
+/// # Example
+///
+/// ```rust
+/// let shape = constant(&node)?;
+/// ```
+pub fn constant(node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let output_name = output_name(node)?;
+    let attributes = extract_attributes(node.get_attribute())?;
+
+    if let Some(value) = attributes.get("value").and_then(|attr| attr.as_tensor()) {
+        let mut value = value.clone();
+        value.set_name(output_name.to_string());
+        return Ok(value);
+    }
+    let (dims, values) = if let Some(value) = attributes.get("value_float") {
+        (
+            vec![],
+            value
+                .as_float()
+                .map(|value| TensorValue::Float(vec![value])),
+        )
+    } else if let Some(values) = attributes.get("value_floats") {
+        let values = values.as_floats().cloned().unwrap_or_default();
+        (vec![values.len() as i64], Some(TensorValue::Float(values)))
+    } else if let Some(value) = attributes.get("value_int") {
+        (
+            vec![],
+            value.as_int().map(|value| TensorValue::Int64(vec![value])),
+        )
+    } else if let Some(values) = attributes.get("value_ints") {
+        let values = values.as_ints().cloned().unwrap_or_default();
+        (vec![values.len() as i64], Some(TensorValue::Int64(values)))
+    } else {
+        (vec![], None)
+    };
+
+    match values {
+        Some(values) => Ok(make_tensor(Some(output_name), dims, values)),
+        None => Err(OnnxError::UnsupportedOperation(format!(
+            "The Constant {} has no value, value_float(s) or value_int(s) attribute",
+            output_name
+        ))),
+    }
+}
+
+/// A tensor of the given shape filled with the single value of `value`.
+fn filled<T: TensorType>(
+    value: &TensorProto,
+    shape: &[usize],
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let value = tensor_proto_to_ndarray::<T>(value)?;
+    match value.iter().next() {
+        Some(element) if value.len() == 1 => {
+            ndarray_to_tensor_proto::<T>(ArrayD::from_elem(IxDyn(shape), element.clone()), name)
+        }
+        _ => Err(OnnxError::InvalidAttribute {
+            name: "value".to_string(),
+            detail: format!(
+                "expected a single value, got a tensor of shape {:?}",
+                value.shape()
+            ),
+        }),
+    }
+}
+
+/// `constant_of_shape` - ONNX Node Implementation for ConstantOfShape
+///
+/// A tensor of the shape given by its input, every element of which is the value of the node,
+/// e.g. the masks and position ids built by the exported transformers from a computed shape. An
+/// empty shape gives a scalar.
+///
+/// # Attributes
+///
+/// - `value` (tensor): A single-element tensor, whose value and type are the ones of the output.
+///   A FLOAT 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The INT64 tensor `input`, the 1-D shape of the output.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the filled tensor, of the type of `value` (FLOAT,
+///   DOUBLE, FLOAT16, INT64, INT32, INT8, UINT8 or BOOL). In case of an unsuccessful operation, it
+///   returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input`, or a shape with a negative dimension.
+/// * A `value` that isn't a single element, or of an unsupported type.
+///
+/// # Example
+///
+/// ```rust
+/// // An INT64 tensor of ones, with a `value` attribute of [1].
+/// let ones = constant_of_shape(&[&shape], &node)?;
+/// ```
+pub fn constant_of_shape(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let shape = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let output_name = output_name(node)?;
+
+    // An empty shape (a scalar output) has no data to convert.
+    let shape: Vec<i64> = match shape.get_dims().iter().product::<i64>() {
+        0 => vec![],
+        _ => tensor_proto_to_ndarray::<i64>(shape)?
+            .iter()
+            .copied()
+            .collect(),
+    };
+    if let Some(&dim) = shape.iter().find(|&&dim| dim < 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The shape {:?} of a ConstantOfShape has the negative dimension {}",
+            shape, dim
+        )));
+    }
+    let shape: Vec<usize> = shape.iter().map(|&dim| dim as usize).collect();
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let value = match attributes.get("value").and_then(|attr| attr.as_tensor()) {
+        Some(value) => value,
+        None => return ndarray_to_tensor_proto::<f32>(ArrayD::zeros(IxDyn(&shape)), output_name),
+    };
+    match value.get_data_type() {
+        t if t == TensorProto_DataType::FLOAT as i32 => filled::<f32>(value, &shape, output_name),
+        t if t == TensorProto_DataType::DOUBLE as i32 => filled::<f64>(value, &shape, output_name),
+        t if t == TensorProto_DataType::FLOAT16 as i32 => filled::<f16>(value, &shape, output_name),
+        t if t == TensorProto_DataType::INT64 as i32 => filled::<i64>(value, &shape, output_name),
+        t if t == TensorProto_DataType::INT32 as i32 => filled::<i32>(value, &shape, output_name),
+        t if t == TensorProto_DataType::INT8 as i32 => filled::<i8>(value, &shape, output_name),
+        t if t == TensorProto_DataType::UINT8 as i32 => filled::<u8>(value, &shape, output_name),
+        t if t == TensorProto_DataType::BOOL as i32 => filled::<bool>(value, &shape, output_name),
+        t => Err(OnnxError::UnsupportedOperation(format!(
+            "ConstantOfShape doesn't support values of data type {}",
+            t
+        ))),
+    }
+}
@@ -2,22 +2,19 @@ use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
 use crate::onnx_rustime::ops::utils::{
     convert_to_output_tensor, extract_attributes, get_int_attribute, get_ints_attribute,
-    get_string_attribute, pad_matrix_3d, stack_along_batch_dimension, tensor_proto_to_ndarray,
+    get_string_attribute, stack_along_batch_dimension, tensor_proto_to_ndarray,
 };
 use ndarray::prelude::*;
-use num_traits::Float;
+use ndarray::{RawData, Slice};
+use num_traits::{Float, Zero};
 use rayon::prelude::*;
+use std::collections::HashMap;
 
 pub type DataRepresentation<F> = Array3<F>;
 
-/// Padding (specific way of adding zeros to the input matrix) kind used in the convolution.
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum Padding {
-    /// Output has the same shape as input.
-    Same,
-    /// Padding is only used to make input fit the kernel.
-    Valid,
-}
+/// Number of spatial axes of the convolution: 1D and 2D convolutions are lifted to 3D, the
+/// missing leading spatial axes having size 1.
+pub(crate) const SPATIAL_AXES: usize = 3;
 
 /// Rust implementation of a convolutional layer.
 /// The weight matrix shall have dimension (in that order)
@@ -27,8 +24,10 @@ struct ConvolutionLayer<F: Float> {
     /// Weight matrix of the kernel
     kernel: Array4<F>,
     bias: Option<Array1<F>>,
-    stride: usize,
-    padding: Padding,
+    /// Strides along the height and the width.
+    strides: [usize; 2],
+    /// Spacing between the kernel taps along the height and the width (1 for a dense kernel).
+    dilations: [usize; 2],
 }
 
 impl<F: 'static + Float + std::ops::AddAssign> ConvolutionLayer<F> {
@@ -39,92 +38,58 @@ impl<F: 'static + Float + std::ops::AddAssign> ConvolutionLayer<F> {
     pub fn new(
         weights: Array4<F>,
         bias_array: Option<Array1<F>>,
-        stride: usize,
-        padding: Padding,
+        strides: [usize; 2],
+        dilations: [usize; 2],
     ) -> ConvolutionLayer<F> {
-        assert!(stride > 0, "Stride of 0 passed");
+        assert!(strides[0] > 0 && strides[1] > 0, "Stride of 0 passed");
+        assert!(dilations[0] > 0 && dilations[1] > 0, "Dilation of 0 passed");
         ConvolutionLayer {
             kernel: weights,
             bias: bias_array,
-            stride,
-            padding,
+            strides,
+            dilations,
         }
     }
 
     /// Analog to conv2d.
-    pub fn convolve(&self, image: &DataRepresentation<F>) -> DataRepresentation<F> {
+    pub fn convolve<'a>(&'a self, image: ArrayView3<'a, F>) -> DataRepresentation<F> {
         conv2d(
             &self.kernel,
             self.bias.as_ref(),
             image,
-            self.padding,
-            self.stride,
+            self.strides,
+            self.dilations,
         )
     }
 }
 
-fn get_padding_size(
-    input_h: usize,
-    input_w: usize,
-    stride: usize,
-    kernel_h: usize,
-    kernel_w: usize,
-) -> (usize, usize, usize, usize, usize, usize) {
-    let pad_along_height: usize;
-    let pad_along_width: usize;
-    let idx_0: usize = 0;
-
-    if input_h % stride == idx_0 {
-        pad_along_height = (kernel_h - stride).max(idx_0);
-    } else {
-        pad_along_height = (kernel_h - (input_h % stride)).max(idx_0);
-    };
-    if input_w % stride == idx_0 {
-        pad_along_width = (kernel_w - stride).max(idx_0);
-    } else {
-        pad_along_width = (kernel_w - (input_w % stride)).max(idx_0);
-    };
-
-    let pad_top = pad_along_height / 2;
-    let pad_bottom = pad_along_height - pad_top;
-    let pad_left = pad_along_width / 2;
-    let pad_right = pad_along_width - pad_left;
-
-    (
-        pad_along_height,
-        pad_along_width,
-        pad_bottom,
-        pad_top,
-        pad_right,
-        pad_left,
-    )
-}
-
 fn im2col_ref<'a, T, F: 'a + Float>(
     im_arr: T,
     ker_height: usize,
     ker_width: usize,
-    im_height: usize,
-    im_width: usize,
-    im_channel: usize,
-    stride: usize,
+    strides: [usize; 2],
+    dilations: [usize; 2],
 ) -> Array2<F>
 where
     // Args:
     //   im_arr: image matrix to be translated into columns, (C,H,W)
     //   ker_height: filter height (hh)
     //   ker_width: filter width (ww)
-    //   im_height: image height
-    //   im_width: image width
+    //   strides: steps (sh, sw) between the patches
+    //   dilations: spacing (dh, dw) between the pixels of a patch
     //
     // Returns:
     //   col: (new_h*new_w,hh*ww*C) matrix, each column is a cube that will convolve with a filter
-    //         new_h = (H-hh) // stride + 1, new_w = (W-ww) // stride + 1
+    //         new_h = (H-(dh*(hh-1)+1)) // sh + 1, new_w = (W-(dw*(ww-1)+1)) // sw + 1
     T: AsArray<'a, F, Ix3>,
 {
     let im2d_arr: ArrayView3<F> = im_arr.into();
-    let new_h = (im_height - ker_height) / stride + 1;
-    let new_w = (im_width - ker_width) / stride + 1;
+    let (im_channel, im_height, im_width) = im2d_arr.dim();
+    // Extent of the dilated kernel.
+    let window_h = dilations[0] * (ker_height - 1) + 1;
+    let window_w = dilations[1] * (ker_width - 1) + 1;
+    let new_h = (im_height - window_h) / strides[0] + 1;
+    let new_w = (im_width - window_w) / strides[1] + 1;
     let mut cols_img: Array2<F> =
         Array::zeros((new_h * new_w, im_channel * ker_height * ker_width));
     let mut cont = 0_usize;
@@ -132,12 +97,13 @@ where
         for j in 1..new_w + 1 {
             let patch = im2d_arr.slice(s![
                 ..,
-                (i - 1) * stride..((i - 1) * stride + ker_height),
-                (j - 1) * stride..((j - 1) * stride + ker_width),
+                (i - 1) * strides[0]..((i - 1) * strides[0] + window_h);dilations[0],
+                (j - 1) * strides[1]..((j - 1) * strides[1] + window_w);dilations[1],
             ]);
-            let patchrow_unwrap: Array1<F> = Array::from_iter(patch.map(|a| *a));
-
-            cols_img.row_mut(cont).assign(&patchrow_unwrap);
+            // Copied in the logical order of the patch, without a temporary row.
+            for (col, &pixel) in cols_img.row_mut(cont).iter_mut().zip(patch.iter()) {
+                *col = pixel;
+            }
             cont += 1;
         }
     }
@@ -146,17 +112,18 @@ where
 
 /// Performs a convolution on the given image data using this layers initializers.
 /// We always convolve on flattened images and expect the input array in im2col
-/// style format.
+/// style format. The image is expected to be already padded.
 ///
 /// Input:
 /// -----------------------------------------------
 /// - kernel_weights: weights of shape (F, C, HH, WW)
 /// - im2d: Input data of shape (C, H, W)
 /// -----------------------------------------------
-/// - 'stride': The number of pixels between adjacent receptive fields in the
-///     horizontal and vertical directions, must be int
-/// - 'pad': "Same" or "Valid"
-
+/// - 'strides': The number of pixels (sh, sw) between adjacent receptive fields in the
+///   vertical and horizontal directions
+/// - 'dilations': The number of pixels (dh, dw) between the taps of the kernel, the
+///   kernel covering dh * (HH - 1) + 1 rows and dw * (WW - 1) + 1 columns
+///
 /// Returns:
 /// -----------------------------------------------
 /// - out: Output data, of shape (F, H', W')
@@ -164,8 +131,8 @@ fn conv2d<'a, T, V, F: 'static + Float + std::ops::AddAssign>(
     kernel_weights: T,
     bias: Option<&Array1<F>>,
     im2d: V,
-    padding: Padding,
-    stride: usize,
+    strides: [usize; 2],
+    dilations: [usize; 2],
 ) -> DataRepresentation<F>
 where
     // This trait bound ensures that kernel and im2d can be passed as owned array or view.
@@ -176,88 +143,35 @@ where
     // Initialisations
     let im2d_arr: ArrayView3<F> = im2d.into();
     let kernel_weights_arr: ArrayView4<F> = kernel_weights.into();
-    let im_col: Array2<F>; // output of fn: im2col_ref()
-    let new_im_height: usize;
-    let new_im_width: usize;
     let weight_shape = kernel_weights_arr.shape();
-    let num_filters = weight_shape[0] as usize;
-    let num_channels_out = weight_shape[1] as usize;
-    let kernel_height = weight_shape[2] as usize;
-    let kernel_width = weight_shape[3] as usize;
-
-    // Dimensions: C, H, W
-    let im_channel = im2d_arr.len_of(Axis(0));
+    let num_filters = weight_shape[0];
+    let num_channels_out = weight_shape[1];
+    let kernel_height = weight_shape[2];
+    let kernel_width = weight_shape[3];
+    // Extent of the dilated kernel.
+    let window_height = dilations[0] * (kernel_height - 1) + 1;
+    let window_width = dilations[1] * (kernel_width - 1) + 1;
+
+    // Dimensions: H, W
     let im_height = im2d_arr.len_of(Axis(1));
     let im_width = im2d_arr.len_of(Axis(2));
 
-    // Calculate output shapes H', W' for two types of Padding
-    if padding == Padding::Same {
-        // H' = H / stride
-        // W' = W / stride
-
-        let h_float = im_height as f32;
-        let w_float = im_width as f32;
-        let stride_float = stride as f32;
-
-        let new_im_height_float = (h_float / stride_float).ceil();
-        let new_im_width_float = (w_float / stride_float).ceil();
-
-        new_im_height = new_im_height_float as usize;
-        new_im_width = new_im_width_float as usize;
-    } else {
-        // H' =  ((H - HH) / stride ) + 1
-        // W' =  ((W - WW) / stride ) + 1
-        new_im_height = ((im_height - kernel_height) / stride) + 1;
-        new_im_width = ((im_width - kernel_width) / stride) + 1;
-    };
+    // H' =  ((H - (dh * (HH - 1) + 1)) / sh ) + 1
+    // W' =  ((W - (dw * (WW - 1) + 1)) / sw ) + 1
+    let new_im_height = ((im_height - window_height) / strides[0]) + 1;
+    let new_im_width = ((im_width - window_width) / strides[1]) + 1;
 
     // weights.reshape(F, HH*WW*C)
     let filter_col = kernel_weights_arr
         .into_shape((num_filters, kernel_height * kernel_width * num_channels_out))
         .unwrap();
 
-    // fn:im2col() for different Paddings
-    if padding == Padding::Same {
-        let (pad_num_h, pad_num_w, pad_top, pad_bottom, pad_left, pad_right) =
-            get_padding_size(im_height, im_width, stride, kernel_height, kernel_width);
-        let mut im2d_arr_pad: Array3<F> = Array::zeros((
-            num_channels_out,
-            im_height + pad_num_h,
-            im_width + pad_num_w,
-        ));
-        let pad_bottom_int = (im_height + pad_num_h) - pad_bottom;
-        let pad_right_int = (im_width + pad_num_w) - pad_right;
-
-        im2d_arr_pad
-            .slice_mut(s![.., pad_top..pad_bottom_int, pad_left..pad_right_int])
-            .assign(&im2d_arr);
-
-        let im_height_pad = im2d_arr_pad.len_of(Axis(1));
-        let im_width_pad = im2d_arr_pad.len_of(Axis(2));
-
-        im_col = im2col_ref(
-            im2d_arr_pad.view(),
-            kernel_height,
-            kernel_width,
-            im_height_pad,
-            im_width_pad,
-            im_channel,
-            stride,
-        );
-    } else {
-        im_col = im2col_ref(
-            im2d_arr,
-            kernel_height,
-            kernel_width,
-            im_height,
-            im_width,
-            im_channel,
-            stride,
-        );
-    };
+    let im_col = im2col_ref(im2d_arr, kernel_height, kernel_width, strides, dilations);
     let filter_transpose = filter_col.t();
 
-    let mul = im_col.dot(&filter_transpose);
+    // The product may be in column-major order (e.g. with a single-column `im_col`), while
+    // `into_shape` keeps the memory order.
+    let mul = im_col.dot(&filter_transpose).as_standard_layout().into_owned();
     let output = mul
         .into_shape((new_im_height, new_im_width, num_filters))
         .unwrap()
@@ -291,60 +205,122 @@ where
     }
 }
 
-/// Determines the padding type and processes the input based on the `auto_pad` attribute.
+/// Reads a spatial attribute of the node (one positive value per spatial axis), `default` along
+/// every axis when missing.
+pub(crate) fn get_spatial_attribute(
+    attributes: &HashMap<String, Attribute<String>>,
+    name: &str,
+    rank: usize,
+    default: i64,
+) -> Result<Vec<usize>, OnnxError> {
+    let values = get_ints_attribute(attributes, name, Some(vec![default; rank]))?;
+    if values.len() != rank || values.iter().any(|&value| value <= 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected {} positive {}, got {:?}",
+            rank, name, values
+        )));
+    }
+    Ok(values.into_iter().map(|value| value as usize).collect())
+}
+
+/// Determines the padding of every spatial axis from the `auto_pad` attribute.
 ///
-/// This function processes the input tensor to determine the padding type and prepares the
-/// input data for the convolution operation. If the `auto_pad` attribute is set to "NOT_SET",
-/// the input tensor will be explicitly padded as per the provided padding values. Otherwise,
-/// the input tensor remains unchanged, but the function will indicate the type of padding
-/// (either `Same` or `Valid`) that should be applied during the convolution operation.
+/// With `NOTSET` (the default), the padding is read from the `pads` attribute, in the ONNX
+/// order: the beginnings of all the axes, then their ends. `VALID` means no padding, while
+/// `SAME_UPPER` and `SAME_LOWER` pad the input so that the output has `ceil(input / stride)`
+/// pixels, the extra pixel of an odd padding going at the end and at the beginning
+/// respectively.
 ///
-/// # initializers:
-/// * `auto_pad`: The value of the `auto_pad` attribute from the ONNX node.
+/// # Arguments
 /// * `attributes`: Extracted attributes from the ONNX node.
-/// * `input_nd_array`: The input ndarray.
-/// * `batch_size`: The size of the batch dimension from the input ndarray.
+/// * `input_shape`: The spatial shape of the input.
+/// * `kernel_shape`, `strides`, `dilations`: The spatial parameters of the convolution.
 ///
 /// # Returns:
-/// * A tuple containing:
-///   - `Padding`: The type of padding (`Same` or `Valid`).
-///   - `Vec<Array3<f32>>`: A vector of processed inputs, which may be the same as the original
-///     inputs or explicitly padded depending on the `auto_pad` value.
-///
-/// # Panics:
-/// * If an unsupported value for `auto_pad` is provided.
-///
-fn determine_padding_and_input(
-    auto_pad: &str,
-    attributes: &std::collections::HashMap<String, Attribute<String>>,
-    input_nd_array: &ArrayD<f32>,
-    batch_size: usize,
-) -> (Padding, Vec<Array3<f32>>) {
-    match auto_pad {
-        "NOT_SET" => {
-            let pads = get_ints_attribute(attributes, "pads", Some(vec![0, 0, 0, 0])).unwrap();
-            let padded_input: Vec<_> = (0..batch_size)
-                .map(|i| {
-                    let single_input = input_nd_array.slice(s![i, .., .., ..]).to_owned();
-                    pad_matrix_3d(&single_input, &pads).unwrap()
-                })
-                .collect();
-            (Padding::Valid, padded_input)
-        }
-        _ => {
-            let inputs: Vec<_> = (0..batch_size)
-                .map(|i| input_nd_array.slice(s![i, .., .., ..]).to_owned())
-                .collect();
-            (
-                match auto_pad {
-                    "SAME_UPPER" | "SAME_LOWER" => Padding::Same,
-                    "VALID" => Padding::Valid,
-                    _ => panic!("Invalid auto_pad value"),
-                },
-                inputs,
-            )
+/// * The padding `(begin, end)` of every spatial axis, or an `OnnxError::InvalidValue` for an
+///   unknown `auto_pad` or invalid `pads`.
+pub(crate) fn determine_padding(
+    attributes: &HashMap<String, Attribute<String>>,
+    input_shape: &[usize],
+    kernel_shape: &[usize],
+    strides: &[usize],
+    dilations: &[usize],
+) -> Result<Vec<(usize, usize)>, OnnxError> {
+    let rank = input_shape.len();
+    let auto_pad = get_string_attribute(attributes, "auto_pad", Some("NOTSET".to_string()))?;
+
+    match auto_pad.as_str() {
+        "NOTSET" | "NOT_SET" => {
+            let pads = get_ints_attribute(attributes, "pads", Some(vec![0; 2 * rank]))?;
+            if pads.len() != 2 * rank || pads.iter().any(|&pad| pad < 0) {
+                return Err(OnnxError::InvalidValue(format!(
+                    "Expected {} non-negative pads, got {:?}",
+                    2 * rank,
+                    pads
+                )));
+            }
+            Ok((0..rank)
+                .map(|axis| (pads[axis] as usize, pads[axis + rank] as usize))
+                .collect())
         }
+        "VALID" => Ok(vec![(0, 0); rank]),
+        "SAME_UPPER" | "SAME_LOWER" => Ok((0..rank)
+            .map(|axis| {
+                let output = input_shape[axis].div_ceil(strides[axis]);
+                let window = dilations[axis] * (kernel_shape[axis] - 1) + 1;
+                let total =
+                    ((output - 1) * strides[axis] + window).saturating_sub(input_shape[axis]);
+                let begin = if auto_pad == "SAME_UPPER" {
+                    total / 2
+                } else {
+                    total - total / 2
+                };
+                (begin, total - begin)
+            })
+            .collect()),
+        _ => Err(OnnxError::InvalidValue(format!(
+            "Invalid auto_pad value {}",
+            auto_pad
+        ))),
+    }
+}
+
+/// Pads the spatial axes (all but the first two) of an `[N, C, ...]` array with zeros.
+pub(crate) fn pad_spatial<T: Clone + Zero>(
+    input: &ArrayD<T>,
+    pads: &[(usize, usize)],
+) -> ArrayD<T> {
+    let mut shape = input.shape().to_vec();
+    for (axis, (begin, end)) in pads.iter().enumerate() {
+        shape[axis + 2] += begin + end;
     }
+
+    let mut padded = ArrayD::zeros(shape);
+    padded
+        .slice_each_axis_mut(|axis| match axis.axis.index() {
+            index if index < 2 => Slice::from(..),
+            index => {
+                let begin = pads[index - 2].0;
+                Slice::from(begin..begin + input.shape()[index])
+            }
+        })
+        .assign(input);
+    padded
+}
+
+/// Inserts axes of size 1 after the first two axes of an `[N, C, ...]` array, up to
+/// `SPATIAL_AXES` spatial axes.
+pub(crate) fn lift_to_3d<S: RawData>(
+    array: ArrayBase<S, IxDyn>,
+) -> Result<ArrayBase<S, Ix5>, OnnxError> {
+    let mut shape = array.shape().to_vec();
+    let missing = (SPATIAL_AXES + 2).saturating_sub(shape.len());
+    shape.splice(2..2, vec![1; missing]);
+
+    array
+        .into_shape(shape)
+        .and_then(|array| array.into_dimensionality::<Ix5>())
+        .map_err(|_| OnnxError::ShapeError("Failed to lift the convolution to 3D".to_string()))
 }
 
 /// `conv` - ONNX Node Implementation for Convolution
@@ -354,6 +330,22 @@ fn determine_padding_and_input(
 /// and then performs matrix multiplication with the filter. This optimizes convolution by
 /// reusing computed values, making it more efficient.
 ///
+/// The convolution can have 1, 2 or 3 spatial axes (`[N, C, L]`, `[N, C, H, W]` or
+/// `[N, C, D, H, W]` inputs), the number of spatial axes being the length of the `kernel_shape`
+/// attribute, or the rank of the kernel minus 2 without it. 1D and 2D convolutions are run as 3D
+/// ones with a depth of 1: a 3D convolution is the sum, over the depth taps of the kernel, of 2D
+/// convolutions of the matching depth slices of the input.
+///
+/// With `group` > 1, the input channels and the kernels are split into `group` groups, each
+/// group of kernels convolving its own group of channels (a depthwise convolution when `group` is
+/// the number of input channels), and the outputs of the groups are concatenated along the
+/// channel axis.
+///
+/// `strides`, `dilations` and `pads` have one value per spatial axis (two for `pads`, the
+/// beginnings of all the axes then their ends), and default to 1, 1 and 0. Dilated kernels cover
+/// `dilation * (kernel - 1) + 1` pixels along each axis, both when computing the output shape
+/// and the automatic (`SAME_*`) padding.
+///
 /// Additionally, the convolution operation is parallelized across batches. If the input has a
 /// batch dimension of size N, N distinct convolutions will run concurrently, one for each input
 /// in the batch.
@@ -363,7 +355,7 @@ fn determine_padding_and_input(
 /// * `inputs` - A reference to the input tensor containing the data to be convolved.
 /// * `initializers` - A reference to the vector containing the filter tensors for the convolution.
 /// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes,
-///   such as padding type and strides.
+///   such as padding type, strides and dilations.
 ///
 /// # Returns
 ///
@@ -373,7 +365,9 @@ fn determine_padding_and_input(
 /// # Errors
 ///
 /// This function may error if:
-/// * The input tensor's shape isn't 4-dimensional.
+/// * The convolution doesn't have 1 to 3 spatial axes, or the input, the kernel and the
+///   `kernel_shape` attribute disagree on them.
+/// * The channels of the input or the kernels can't be split into `group` groups.
 /// * Attribute extraction fails or provides invalid values.
 /// * There's a shape mismatch during the operation.
 ///
@@ -387,113 +381,245 @@ pub fn conv(
     initializers: &Vec<&TensorProto>,
     node: &NodeProto,
 ) -> Result<TensorProto, OnnxError> {
+    // Convert the input, the kernel and the bias TensorProtos to ndarrays.
+    let input_nd_array = tensor_proto_to_ndarray::<f32>(inputs)?;
+    let kernel = initializers
+        .first()
+        .ok_or(OnnxError::MissingInput("W".to_string()))?;
+    let kernel = tensor_proto_to_ndarray::<f32>(kernel)?;
+    let bias = initializers
+        .get(1)
+        .map(|bias| tensor_proto_to_ndarray::<f32>(bias))
+        .transpose()?;
+
+    let result = conv_ndarray(&input_nd_array, &kernel, bias.as_ref(), node)?;
+
+    // Convert the result to an output tensor and return.
+    convert_to_output_tensor(node, result)
+}
+
+/// `conv` on the decoded input, kernel and bias, which are only borrowed: the executor keeps the
+/// kernels of a model as arrays for all its runs.
+pub(crate) fn conv_ndarray(
+    input_nd_array: &ArrayD<f32>,
+    kernel: &ArrayD<f32>,
+    bias: Option<&ArrayD<f32>>,
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
     // Extract the attributes from the node.
     let attributes = extract_attributes(node.get_attribute())?;
 
-    // Convert the input TensorProto to a ndarray.
-    let input_nd_array = tensor_proto_to_ndarray::<f32>(inputs)?;
-    let batch_size = input_nd_array.shape()[0];
-
-    // Check if the input tensor has the expected 4D shape.
-    if input_nd_array.ndim() != 4 {
-        return Err(OnnxError::ShapeError("Expected a 4D tensor".to_string()));
+    // The number of spatial axes follows the kernel_shape attribute, or the kernel without it.
+    let kernel_shape = get_ints_attribute(
+        &attributes,
+        "kernel_shape",
+        Some(
+            kernel
+                .shape()
+                .iter()
+                .skip(2)
+                .map(|&len| len as i64)
+                .collect(),
+        ),
+    )?;
+    let rank = kernel_shape.len();
+    if !(1..=SPATIAL_AXES).contains(&rank) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected 1 to {} spatial axes, got a kernel_shape of {:?}",
+            SPATIAL_AXES, kernel_shape
+        )));
     }
 
-    // Get the auto_pad attribute and create the kernel array.
-    let auto_pad = get_string_attribute(&attributes, "auto_pad", Some("NOT_SET".to_string()))?;
-    let kernel = tensor_proto_to_ndarray::<f32>(initializers[0])?
-        .into_shape((
-            initializers[0].get_dims()[0] as usize,
-            initializers[0].get_dims()[1] as usize,
-            initializers[0].get_dims()[2] as usize,
-            initializers[0].get_dims()[3] as usize,
-        ))
-        .map_err(|_| OnnxError::ShapeError("Failed to create kernel matrix".to_string()))?;
-
-    let bias_option = initializers
-        .get(1)
-        .map(|bias| tensor_proto_to_ndarray::<f32>(bias).unwrap())
-        .and_then(|array| array.into_dimensionality::<Ix1>().ok());
-
-    // Determine the padding and optionally pre-pad the input.
-    let (padding_mode, input) =
-        determine_padding_and_input(&auto_pad, &attributes, &input_nd_array, batch_size);
+    // Check if the input tensor and the kernel have the expected shapes.
+    if input_nd_array.ndim() != rank + 2 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected a {}D tensor for {} spatial axes, got shape {:?}",
+            rank + 2,
+            rank,
+            input_nd_array.shape()
+        )));
+    }
+    if kernel.ndim() != rank + 2
+        || kernel.shape()[2..]
+            .iter()
+            .zip(&kernel_shape)
+            .any(|(&len, &expected)| len as i64 != expected)
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a {}D kernel of spatial shape {:?}, but got shape {:?}",
+            rank + 2,
+            kernel_shape,
+            kernel.shape()
+        )));
+    }
 
-    let strides = get_ints_attribute(&attributes, "strides", None)?;
-    let stride = strides
-        .get(0)
-        .ok_or(OnnxError::InternalError(
-            "Failed to fetch stride attribute".to_string(),
-        ))?
-        .clone() as usize;
+    let bias_option = bias.and_then(|array| array.view().into_dimensionality::<Ix1>().ok());
+
+    // One stride and one dilation (spacing between the taps of the kernel) per spatial axis.
+    let strides = get_spatial_attribute(&attributes, "strides", rank, 1)?;
+    let dilations = get_spatial_attribute(&attributes, "dilations", rank, 1)?;
+    let kernel_spatial = &kernel.shape()[2..];
+
+    // Pad the input explicitly, whatever the auto_pad.
+    let pads = determine_padding(
+        &attributes,
+        &input_nd_array.shape()[2..],
+        kernel_spatial,
+        &strides,
+        &dilations,
+    )?;
+    let padded = pad_spatial(input_nd_array, &pads);
+
+    // The dilated kernel must fit in the padded input.
+    let window: Vec<usize> = (0..rank)
+        .map(|axis| dilations[axis] * (kernel_spatial[axis] - 1) + 1)
+        .collect();
+    if window
+        .iter()
+        .zip(&padded.shape()[2..])
+        .any(|(window, len)| window > len)
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The kernel of shape {:?} with dilations {:?} covers {:?} pixels, more than the \
+             padded input of {:?}",
+            kernel.shape(),
+            dilations,
+            window,
+            &padded.shape()[2..]
+        )));
+    }
 
     let group: i64 = get_int_attribute(&attributes, "group", Some(1))?; // default value 1
+    if group <= 0 {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected a positive group, got {}",
+            group
+        )));
+    }
 
-    let channels = input[0].shape()[0] as i64; // Assuming [channels, height, width]
-    let channels_per_group = channels / group;
-
-    if channels % group != 0 {
-        return Err(OnnxError::ShapeError(
-            "Number of channels is not divisible by group.".to_string(),
-        ));
+    // Check the kernel against the input channels and the bias, instead of letting ndarray panic.
+    if input_nd_array.shape()[1] as i64 != kernel.shape()[1] as i64 * group {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Input has {} channels, but the kernel expects {} ({} per group, group {})",
+            input_nd_array.shape()[1],
+            kernel.shape()[1] as i64 * group,
+            kernel.shape()[1],
+            group
+        )));
+    }
+    if kernel.shape()[0] as i64 % group != 0 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The kernel has {} output channels, which can't be split into {} groups",
+            kernel.shape()[0],
+            group
+        )));
+    }
+    if let Some(bias) = &bias_option {
+        if bias.len() != kernel.shape()[0] {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Bias has {} elements, but the kernel has {} output channels",
+                bias.len(),
+                kernel.shape()[0]
+            )));
+        }
     }
 
-    let kernels_per_group = kernel.shape()[0] as i64 / group;
+    // Output shape, without the lifted axes.
+    let mut output_shape = vec![input_nd_array.shape()[0], kernel.shape()[0]];
+    output_shape.extend(
+        (0..rank).map(|axis| (padded.shape()[axis + 2] - window[axis]) / strides[axis] + 1),
+    );
+
+    // Lift the convolution to 3 spatial axes.
+    let lifted = |values: &[usize]| {
+        let mut lifted = vec![1; SPATIAL_AXES - rank];
+        lifted.extend_from_slice(values);
+        lifted
+    };
+    let (strides, dilations) = (lifted(&strides), lifted(&dilations));
+    let input = lift_to_3d(padded)?;
+    let kernel = lift_to_3d(kernel.view())?;
+
+    let group = group as usize;
+    let (batch_size, channels, depth, _, _) = input.dim();
+    let (filters, _, kernel_depth, _, _) = kernel.dim();
+    let channels_per_group = channels / group;
+    let kernels_per_group = filters / group;
+    let output_depth = (depth - (dilations[0] * (kernel_depth - 1) + 1)) / strides[0] + 1;
+
+    // One 2D layer per group and depth tap of the kernel, the first one adding the bias.
+    let layers: Vec<Vec<_>> = (0..group)
+        .map(|g| {
+            let group_kernel = kernel.slice(s![
+                g * kernels_per_group..(g + 1) * kernels_per_group,
+                ..,
+                ..,
+                ..,
+                ..
+            ]);
+            let group_bias = bias_option.as_ref().map(|bias| {
+                bias.slice(s![g * kernels_per_group..(g + 1) * kernels_per_group])
+                    .to_owned()
+            });
+
+            (0..kernel_depth)
+                .map(|kd| {
+                    ConvolutionLayer::new(
+                        group_kernel.index_axis(Axis(2), kd).to_owned(),
+                        group_bias.clone().filter(|_| kd == 0),
+                        [strides[1], strides[2]],
+                        [dilations[1], dilations[2]],
+                    )
+                })
+                .collect()
+        })
+        .collect();
 
     // Parallelize the convolution operation for each input in the batch.
     let result_list: Vec<_> = (0..batch_size)
         .into_par_iter()
         .map(|i| {
-            let current_input = &input[i];
-            let group_results: Vec<_> = (0..group)
-                .into_iter()
-                .map(|g| {
-                    let group_input = current_input
-                        .slice(s![
-                            (g * channels_per_group) as usize
-                                ..((g + 1) * channels_per_group) as usize,
-                            ..,
-                            ..
-                        ])
-                        .to_owned();
-
-                    let group_kernel = kernel
-                        .slice(s![
-                            (g * kernels_per_group) as usize
-                                ..((g + 1) * kernels_per_group) as usize,
-                            ..,
-                            ..,
-                            ..
-                        ])
-                        .to_owned();
-
-                    let group_bias = if let Some(ref bias) = bias_option {
-                        let bias_per_group = bias.shape()[0] as i64 / group;
-
-                        Some(
-                            bias.slice(s![
-                                (g * bias_per_group) as usize..((g + 1) * bias_per_group) as usize,
-                            ])
-                            .to_owned(),
-                        )
-                    } else {
-                        None
-                    };
-
-                    ConvolutionLayer::new(group_kernel, group_bias, stride, padding_mode)
-                        .convolve(&group_input)
+            let depth_results: Vec<_> = (0..output_depth)
+                .map(|od| {
+                    let group_results: Vec<_> = layers
+                        .iter()
+                        .enumerate()
+                        .map(|(g, group_layers)| {
+                            let group_input = input.slice(s![
+                                i,
+                                g * channels_per_group..(g + 1) * channels_per_group,
+                                ..,
+                                ..,
+                                ..
+                            ]);
+
+                            // Sum of the 2D convolutions of the depth taps.
+                            group_layers
+                                .iter()
+                                .enumerate()
+                                .map(|(kd, layer)| {
+                                    let d = od * strides[0] + kd * dilations[0];
+                                    layer.convolve(group_input.index_axis(Axis(1), d))
+                                })
+                                .reduce(|sum, output| sum + output)
+                                .unwrap()
+                        })
+                        .collect();
+
+                    // Convert each result into an ArrayView
+                    let views: Vec<_> = group_results.iter().map(|arr| arr.view()).collect();
+
+                    ndarray::concatenate(Axis(0), &views[..]).unwrap()
                 })
                 .collect();
 
-            // Convert each result into an ArrayView
-            let views: Vec<_> = group_results.iter().map(|arr| arr.view()).collect();
+            let views: Vec<_> = depth_results.iter().map(|arr| arr.view()).collect();
 
-            ndarray::concatenate(Axis(0), &views[..]).unwrap()
+            ndarray::stack(Axis(1), &views[..]).unwrap()
         })
         .collect();
 
-    let result = stack_along_batch_dimension(result_list)?;
-
-    // Convert the result to an output tensor and return.
-    convert_to_output_tensor(node, result)
+    stack_along_batch_dimension(result_list)?
+        .into_shape(output_shape)
+        .map_err(|_| OnnxError::ShapeError("Failed to reshape the convolution output".to_string()))
 }
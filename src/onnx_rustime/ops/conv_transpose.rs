@@ -0,0 +1,366 @@
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, get_ints_attribute,
+    get_string_attribute, stack_along_batch_dimension, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Geometry of a transposed convolution along one spatial axis.
+#[derive(Debug, Clone, Copy)]
+struct AxisGeometry {
+    stride: usize,
+    dilation: usize,
+    /// Output pixels cropped at the beginning of the axis.
+    pad_begin: usize,
+    output_len: usize,
+}
+
+/// Reads a pair of values (one per spatial axis) of at least `min`.
+fn get_pair_attribute(
+    attributes: &HashMap<String, Attribute<String>>,
+    name: &str,
+    default: i64,
+    min: i64,
+) -> Result<[usize; 2], OnnxError> {
+    match get_ints_attribute(attributes, name, Some(vec![default, default]))?.as_slice() {
+        &[h, w] if h >= min && w >= min => Ok([h as usize, w as usize]),
+        values => Err(OnnxError::InvalidValue(format!(
+            "Expected two {} of at least {}, got {:?}",
+            name, min, values
+        ))),
+    }
+}
+
+/// Splits the padding of an axis between its beginning and its end, like `auto_pad`. The extra
+/// pixel of an odd padding goes to the end with `SAME_UPPER`, to the beginning otherwise.
+fn split_padding(total: usize, auto_pad: &str) -> (usize, usize) {
+    if auto_pad == "SAME_UPPER" {
+        (total / 2, total - total / 2)
+    } else {
+        (total - total / 2, total / 2)
+    }
+}
+
+/// The input positions `i` of the axis written by a kernel tap, i.e. with
+/// `0 <= i * stride + offset < output_len`, and the output position of the first one.
+fn tap_range(
+    input_len: usize,
+    geometry: &AxisGeometry,
+    offset: isize,
+) -> Option<(Range<usize>, usize)> {
+    let stride = geometry.stride as isize;
+    let first = if offset < 0 {
+        (-offset + stride - 1) / stride
+    } else {
+        0
+    };
+    let end =
+        ((geometry.output_len as isize - offset + stride - 1) / stride).min(input_len as isize);
+
+    (first < end).then(|| {
+        (
+            first as usize..end as usize,
+            (first * stride + offset) as usize,
+        )
+    })
+}
+
+/// Transposed convolution of one `[C, H, W]` input with a `[C, M / group, kH, kW]` kernel.
+///
+/// Every input pixel adds its value times the kernel to the output, the kernels of neighbouring
+/// pixels being `stride` apart: each kernel tap adds a strided slice of the output at once.
+fn conv_transpose2d(
+    input: ArrayView3<f32>,
+    kernel: &Array4<f32>,
+    bias: Option<&Array1<f32>>,
+    group: usize,
+    geometry: [AxisGeometry; 2],
+) -> Array3<f32> {
+    let (channels, input_h, input_w) = input.dim();
+    let (_, filters_per_group, kernel_h, kernel_w) = kernel.dim();
+    let channels_per_group = channels / group;
+    let [rows, columns] = geometry;
+
+    let mut output = Array3::zeros((
+        filters_per_group * group,
+        rows.output_len,
+        columns.output_len,
+    ));
+    if let Some(bias) = bias {
+        for (mut filter, &value) in output.outer_iter_mut().zip(bias) {
+            filter.fill(value);
+        }
+    }
+
+    for c in 0..channels {
+        let g = c / channels_per_group;
+        for m in 0..filters_per_group {
+            let mut filter = output.index_axis_mut(Axis(0), g * filters_per_group + m);
+            for ky in 0..kernel_h {
+                let row_offset = (ky * rows.dilation) as isize - rows.pad_begin as isize;
+                let Some((input_rows, y)) = tap_range(input_h, &rows, row_offset) else {
+                    continue;
+                };
+                for kx in 0..kernel_w {
+                    let weight = kernel[[c, m, ky, kx]];
+                    let column_offset =
+                        (kx * columns.dilation) as isize - columns.pad_begin as isize;
+                    let Some((input_columns, x)) = tap_range(input_w, &columns, column_offset)
+                    else {
+                        continue;
+                    };
+
+                    let y_end = y + (input_rows.len() - 1) * rows.stride + 1;
+                    let x_end = x + (input_columns.len() - 1) * columns.stride + 1;
+                    filter
+                        .slice_mut(s![y..y_end;rows.stride, x..x_end;columns.stride])
+                        .scaled_add(
+                            weight,
+                            &input.slice(s![c, input_rows.clone(), input_columns]),
+                        );
+                }
+            }
+        }
+    }
+    output
+}
+
+/// `conv_transpose` - ONNX Node Implementation for Transposed Convolution
+///
+/// The gradient of `Conv` with respect to its input, used to upsample feature maps (e.g. in the
+/// decoders of segmentation models): every input pixel scatters its value times the kernel into
+/// the output, the kernels of neighbouring pixels being `strides` apart and overlapping when the
+/// kernel is larger than the stride.
+///
+/// Along each spatial axis, the full output covers
+/// `stride * (input - 1) + output_padding + dilation * (kernel - 1) + 1` pixels, cropped by the
+/// padding at both ends.
+///
+/// # Attributes
+///
+/// - `strides` (ints): Upsampling factor along the height and the width, 1 by default.
+/// - `pads` (ints): Pixels cropped from the full output, in the order top, left, bottom, right.
+///   They can be asymmetric. Ignored when `output_shape` is given or with `auto_pad`.
+/// - `output_padding` (ints): Pixels added at the end of the full output, to pick one of the
+///   output sizes that a strided `Conv` maps to the same input size. 0 by default.
+/// - `output_shape` (ints): Explicit height and width of the output (optionally preceded by the
+///   batch size and the channels). It wins over `pads`: the padding is computed from it, split
+///   between the two ends like with `auto_pad`. A larger shape than the full output adds
+///   zeros (plus bias) at the end.
+/// - `auto_pad` (string): `NOTSET` (explicit `pads`), `VALID` (no padding), or `SAME_UPPER` and
+///   `SAME_LOWER` for an output of `input * stride` pixels. An odd padding puts the extra pixel at
+///   the end with `SAME_UPPER` and at the beginning otherwise, as in the ONNX definition
+///   (onnxruntime does the opposite).
+/// - `dilations` (ints): Spacing between the kernel taps, 1 by default.
+/// - `group` (int): The input channels and the kernels are split into `group` groups, each group
+///   of kernels only reading its group of channels. 1 by default.
+/// - `kernel_shape` (ints): Optional, checked against the kernel.
+///
+/// The convolution is parallelized across the batch.
+///
+/// # Arguments
+///
+/// * `inputs` - The `[N, C, H, W]` input tensor.
+/// * `initializers` - The `[C, M / group, kH, kW]` kernel and the optional bias of `M` elements.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - The `[N, M, H_out, W_out]` output, or an error
+///   (`OnnxError`) if the operation fails.
+///
+/// # Errors
+///
+/// This function may error if:
+/// * The input or the kernel isn't 4-dimensional, or the kernel is missing.
+/// * The channels of the input or the bias don't match the kernel, or the channels can't be split
+///   into `group` groups.
+/// * An attribute has an invalid value, or the padding leaves an empty output.
+///
+/// # Example
+///
+/// ```rust
+/// // A 2x upsampling: [1, 64, 16, 16] -> [1, 32, 32, 32] with a 4x4 kernel, strides 2 and pads 1.
+/// let upsampled = conv_transpose(&input_tensor, &kernel_and_bias, &node)?;
+/// ```
+pub fn conv_transpose(
+    inputs: &TensorProto,
+    initializers: &Vec<&TensorProto>,
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+
+    let input = tensor_proto_to_ndarray::<f32>(inputs)?
+        .into_dimensionality::<Ix4>()
+        .map_err(|_| OnnxError::ShapeError("Expected a 4D tensor".to_string()))?;
+
+    let kernel = initializers
+        .first()
+        .ok_or(OnnxError::MissingInput("ConvTranspose kernel".to_string()))?;
+    let kernel = tensor_proto_to_ndarray::<f32>(kernel)?
+        .into_dimensionality::<Ix4>()
+        .map_err(|_| {
+            OnnxError::shape_mismatch(format!(
+                "Expected a 4D kernel, but got shape {:?}",
+                kernel.get_dims()
+            ))
+        })?;
+    let bias = initializers
+        .get(1)
+        .map(|bias| tensor_proto_to_ndarray::<f32>(bias))
+        .transpose()?;
+
+    let group = get_int_attribute(&attributes, "group", Some(1))?;
+    if group <= 0 {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected a positive group, got {}",
+            group
+        )));
+    }
+    let group = group as usize;
+
+    let (batch_size, channels, input_h, input_w) = input.dim();
+    let (kernel_channels, filters_per_group, kernel_h, kernel_w) = kernel.dim();
+    if channels != kernel_channels {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Input has {} channels, but the kernel of shape {:?} expects {}",
+            channels,
+            kernel.shape(),
+            kernel_channels
+        )));
+    }
+    if channels % group != 0 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The input has {} channels, which can't be split into {} groups",
+            channels, group
+        )));
+    }
+    let filters = filters_per_group * group;
+    if let Some(bias) = &bias {
+        if bias.shape() != [filters] {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Bias of shape {:?}, but the kernel has {} output channels",
+                bias.shape(),
+                filters
+            )));
+        }
+    }
+
+    let kernel_shape = get_ints_attribute(
+        &attributes,
+        "kernel_shape",
+        Some(vec![kernel_h as i64, kernel_w as i64]),
+    )?;
+    if kernel_shape != [kernel_h as i64, kernel_w as i64] {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The kernel_shape attribute {:?} doesn't match the kernel of shape {:?}",
+            kernel_shape,
+            kernel.shape()
+        )));
+    }
+
+    let strides = get_pair_attribute(&attributes, "strides", 1, 1)?;
+    let dilations = get_pair_attribute(&attributes, "dilations", 1, 1)?;
+    let output_padding = get_pair_attribute(&attributes, "output_padding", 0, 0)?;
+    let auto_pad = get_string_attribute(&attributes, "auto_pad", Some("NOTSET".to_string()))?;
+    let pads = get_ints_attribute(&attributes, "pads", Some(vec![0; 4]))?;
+    let output_shape = get_ints_attribute(&attributes, "output_shape", Some(vec![]))?;
+
+    if !matches!(
+        auto_pad.as_str(),
+        "NOTSET" | "NOT_SET" | "VALID" | "SAME_UPPER" | "SAME_LOWER"
+    ) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Invalid auto_pad value {}",
+            auto_pad
+        )));
+    }
+    if pads.len() != 4 || pads.iter().any(|&pad| pad < 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected four non-negative pads, got {:?}",
+            pads
+        )));
+    }
+    let output_shape = match output_shape.as_slice() {
+        [] => None,
+        [.., h, w] if output_shape.len() <= 4 && *h > 0 && *w > 0 => {
+            Some([*h as usize, *w as usize])
+        }
+        values => {
+            return Err(OnnxError::InvalidValue(format!(
+                "Expected a positive output_shape of the spatial axes, got {:?}",
+                values
+            )))
+        }
+    };
+
+    let input_sizes = [input_h, input_w];
+    let kernel_sizes = [kernel_h, kernel_w];
+    let mut geometry = [AxisGeometry {
+        stride: 1,
+        dilation: 1,
+        pad_begin: 0,
+        output_len: 0,
+    }; 2];
+    for axis in 0..2 {
+        let full = strides[axis] * (input_sizes[axis] - 1)
+            + output_padding[axis]
+            + dilations[axis] * (kernel_sizes[axis] - 1)
+            + 1;
+
+        let (pad_begin, output_len) = match (output_shape, auto_pad.as_str()) {
+            (Some(shape), _) => {
+                let (pad_begin, _) = split_padding(full.saturating_sub(shape[axis]), &auto_pad);
+                (pad_begin, shape[axis])
+            }
+            (None, "SAME_UPPER" | "SAME_LOWER") => {
+                let output_len = input_sizes[axis] * strides[axis];
+                let (pad_begin, _) = split_padding(full.saturating_sub(output_len), &auto_pad);
+                (pad_begin, output_len)
+            }
+            (None, "VALID") => (0, full),
+            (None, _) => {
+                let (pad_begin, pad_end) = (pads[axis] as usize, pads[axis + 2] as usize);
+                if pad_begin + pad_end >= full {
+                    return Err(OnnxError::shape_mismatch(format!(
+                        "Pads {:?} crop the whole output of {} pixels along axis {}",
+                        pads,
+                        full,
+                        axis + 2
+                    )));
+                }
+                (pad_begin, full - pad_begin - pad_end)
+            }
+        };
+
+        geometry[axis] = AxisGeometry {
+            stride: strides[axis],
+            dilation: dilations[axis],
+            pad_begin,
+            output_len,
+        };
+    }
+
+    // Parallelize the transposed convolution across the batch.
+    let bias = bias.map(|bias| bias.into_shape(filters).unwrap());
+    let result_list: Vec<_> = (0..batch_size)
+        .into_par_iter()
+        .map(|n| {
+            conv_transpose2d(
+                input.index_axis(Axis(0), n),
+                &kernel,
+                bias.as_ref(),
+                group,
+                geometry,
+            )
+        })
+        .collect();
+
+    let result = stack_along_batch_dimension(result_list)?;
+
+    convert_to_output_tensor(node, result)
+}
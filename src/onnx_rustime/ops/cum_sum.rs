@@ -0,0 +1,118 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::gather::gather_indices;
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis};
+use std::ops::Add;
+
+/// The cumulative sums of every lane of `data` along `axis`, from the last element on if
+/// `reverse`, excluding the element itself if `exclusive`.
+fn cumulative_sums<T>(data: &ArrayD<T>, axis: usize, exclusive: bool, reverse: bool) -> ArrayD<T>
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    let mut sums = data.clone();
+    for mut lane in sums.lanes_mut(Axis(axis)) {
+        let mut total = T::default();
+        let mut accumulate = |element: &mut T| {
+            let value = *element;
+            if exclusive {
+                *element = total;
+            }
+            total = total + value;
+            if !exclusive {
+                *element = total;
+            }
+        };
+        match reverse {
+            true => lane.iter_mut().rev().for_each(&mut accumulate),
+            false => lane.iter_mut().for_each(&mut accumulate),
+        }
+    }
+    sums
+}
+
+/// `cum_sum` - ONNX Node Implementation for CumSum
+///
+/// The cumulative sums of the input along `axis`, e.g. the positions of the tokens of a sequence
+/// from its attention mask: the element `i` of every lane is the sum of the elements `0..=i`.
+///
+/// # Attributes
+///
+/// - `exclusive` (int): 1 to exclude the element itself from its sum (`0..i`), the first sum
+///   being 0. 0 by default.
+/// - `reverse` (int): 1 to sum from the last element on (`i..` with `exclusive`, `i + 1..`
+///   otherwise). 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `x` (FLOAT, FLOAT16 or INT64) and `axis`, a single INT32 or INT64
+///   value. Negative axes count from the end.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the sums, of the shape and of the type of `x`
+///   (FLOAT for a FLOAT16 `x`). In case of an unsuccessful operation, it returns an error
+///   (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `x` or `axis`, or an `axis` that isn't a single value.
+/// * An axis out of range.
+///
+/// # Example
+///
+/// ```rust
+/// let positions = cum_sum(&[&attention_mask, &axis], &node)?;
+/// ```
+pub fn cum_sum(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("x".to_string()))?;
+    let axis = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("axis".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let exclusive = get_int_attribute(&attributes, "exclusive", Some(0))? != 0;
+    let reverse = get_int_attribute(&attributes, "reverse", Some(0))? != 0;
+
+    let axis = gather_indices(axis)?;
+    let axis = match axis.iter().next() {
+        Some(&value) if axis.len() == 1 => value,
+        _ => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The axis of a CumSum must be a single value, got a tensor of shape {:?}",
+                axis.shape()
+            )))
+        }
+    };
+    let rank = x.get_dims().len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+
+    if x.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let x = tensor_proto_to_ndarray::<i64>(x)?;
+        let sums = cumulative_sums(&x, axis, exclusive, reverse);
+        ndarray_to_tensor_proto::<i64>(sums, output_name)
+    } else {
+        let x = tensor_proto_to_ndarray::<f32>(x)?;
+        let sums = cumulative_sums(&x, axis, exclusive, reverse);
+        ndarray_to_tensor_proto::<f32>(sums, output_name)
+    }
+}
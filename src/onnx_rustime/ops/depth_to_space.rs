@@ -0,0 +1,180 @@
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, get_string_attribute,
+    tensor_proto_to_ndarray,
+};
+use ndarray::{Array4, ArrayD, Ix4};
+use std::collections::HashMap;
+
+/// Reads the input of a DepthToSpace or SpaceToDepth node as an NCHW tensor, and its positive
+/// `blocksize`.
+fn input_and_blocksize(
+    op_type: &str,
+    input: &TensorProto,
+    attributes: &HashMap<String, Attribute<String>>,
+) -> Result<(Array4<f32>, usize), OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    let shape = x.shape().to_vec();
+    let x = x.into_dimensionality::<Ix4>().map_err(|_| {
+        OnnxError::shape_mismatch(format!(
+            "{} expects an input of shape [N, C, H, W], got {:?}",
+            op_type, shape
+        ))
+    })?;
+    match get_int_attribute(attributes, "blocksize", None)? {
+        blocksize if blocksize > 0 => Ok((x, blocksize as usize)),
+        blocksize => Err(OnnxError::InvalidAttribute {
+            name: "blocksize".to_string(),
+            detail: format!("expected a positive size, got {}", blocksize),
+        }),
+    }
+}
+
+/// `x` viewed as a tensor of the 6 dimensions of `split`, with its axes permuted by `perm` and
+/// merged back into the 4 dimensions of `output`.
+fn rearranged(
+    x: Array4<f32>,
+    split: [usize; 6],
+    perm: [usize; 6],
+    output: [usize; 4],
+) -> Result<ArrayD<f32>, OnnxError> {
+    let reshape_error = |e: ndarray::ShapeError| {
+        OnnxError::ShapeError(format!("Failed to rearrange the blocks: {}", e))
+    };
+    let blocks = x.into_shape(split.to_vec()).map_err(reshape_error)?;
+    blocks
+        .permuted_axes(perm.to_vec())
+        .as_standard_layout()
+        .into_owned()
+        .into_shape(output.to_vec())
+        .map_err(reshape_error)
+}
+
+/// `depth_to_space` - ONNX Node Implementation for DepthToSpace
+///
+/// Moves the channels of the input into `blocksize` by `blocksize` blocks of pixels: an NCHW
+/// tensor becomes `[N, C / blocksize², H * blocksize, W * blocksize]`, e.g. the pixel shuffle
+/// upsampling the output of a super-resolution model.
+///
+/// In the `DCR` mode (depth-column-row, the default) the channels are read as
+/// `[blocksize, blocksize, C / blocksize²]`, the offset within the block first; in the `CRD` mode
+/// (column-row-depth, the export of PyTorch's `PixelShuffle`) as
+/// `[C / blocksize², blocksize, blocksize]`, the output channel first.
+///
+/// # Attributes
+///
+/// - `blocksize` (int): The size of the blocks.
+/// - `mode` (string): "DCR" (default) or "CRD", since opset 11.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the NCHW input tensor, whose channels are a multiple of
+///   `blocksize²`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the rearranged tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An input that isn't 4-D, or whose channels aren't a multiple of `blocksize²`.
+/// * A missing or non-positive `blocksize`, or an unknown `mode`.
+///
+/// # Example
+///
+/// ```rust
+/// // The [1, 12, 240, 320] output of a super-resolution model, with a blocksize of 2.
+/// let image = depth_to_space(&features, &node)?; // [1, 3, 480, 640]
+/// ```
+pub fn depth_to_space(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let (x, blocksize) = input_and_blocksize("DepthToSpace", input, &attributes)?;
+    let crd = match get_string_attribute(&attributes, "mode", Some("DCR".to_string()))?.as_str() {
+        "DCR" => false,
+        "CRD" => true,
+        other => {
+            return Err(OnnxError::InvalidAttribute {
+                name: "mode".to_string(),
+                detail: format!("expected \"DCR\" or \"CRD\", got \"{}\"", other),
+            })
+        }
+    };
+
+    let (n, c, h, w) = x.dim();
+    let b = blocksize;
+    if c % (b * b) != 0 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The {} channels of the input of DepthToSpace aren't a multiple of the {} of a \
+             blocksize of {}",
+            c,
+            b * b,
+            b
+        )));
+    }
+    let depth = c / (b * b);
+    let output = [n, depth, h * b, w * b];
+    let y = match crd {
+        false => rearranged(x, [n, b, b, depth, h, w], [0, 3, 4, 1, 5, 2], output)?,
+        true => rearranged(x, [n, depth, b, b, h, w], [0, 1, 4, 2, 5, 3], output)?,
+    };
+    convert_to_output_tensor(node, y)
+}
+
+/// `space_to_depth` - ONNX Node Implementation for SpaceToDepth
+///
+/// The inverse of the `DCR` mode of DepthToSpace: moves every `blocksize` by `blocksize` block of
+/// pixels of the input into the channels, the offset within the block first. An NCHW tensor
+/// becomes `[N, C * blocksize², H / blocksize, W / blocksize]`, e.g. the passthrough layer of
+/// YOLOv2.
+///
+/// # Attributes
+///
+/// - `blocksize` (int): The size of the blocks.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the NCHW input tensor, whose height and width are multiples of
+///   `blocksize`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the rearranged tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An input that isn't 4-D, or whose height or width isn't a multiple of `blocksize`.
+/// * A missing or non-positive `blocksize`.
+///
+/// # Example
+///
+/// ```rust
+/// let stacked = space_to_depth(&image, &node)?; // [1, 3, 640, 640] -> [1, 12, 320, 320]
+/// ```
+pub fn space_to_depth(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let (x, blocksize) = input_and_blocksize("SpaceToDepth", input, &attributes)?;
+
+    let (n, c, h, w) = x.dim();
+    let b = blocksize;
+    if h % b != 0 || w % b != 0 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The height {} and width {} of the input of SpaceToDepth aren't multiples of the \
+             blocksize {}",
+            h, w, b
+        )));
+    }
+    let y = rearranged(
+        x,
+        [n, c, h / b, b, w / b, b],
+        [0, 3, 5, 1, 2, 4],
+        [n, c * b * b, h / b, w / b],
+    )?;
+    convert_to_output_tensor(node, y)
+}
@@ -0,0 +1,103 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::matmul_integer::widen;
+use crate::onnx_rustime::ops::quantize_linear::per_axis;
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+use ndarray::Zip;
+
+/// `dequantize_linear` - ONNX Node Implementation for Linear Dequantization (DequantizeLinear)
+///
+/// The inverse of `quantize_linear`: turns a quantized tensor back into the real values it
+/// stands for.
+///
+/// `y = (x - x_zero_point) * x_scale`
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis of the per-axis dequantization, 1 by default. Negative values count
+///   from the end. Ignored when `x_scale` has a single value.
+/// - `block_size` (int): Blocked quantization isn't supported, only its default of 0.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `x`, `x_scale` and the optional `x_zero_point`, in the order of the
+///   node inputs. `x` is a UINT8, INT8 or INT32 tensor (e.g. a bias quantized for QLinearConv,
+///   whose zero point is 0), and `x_zero_point` has its type. `x_scale` has a single value
+///   (per-tensor quantization) or is a 1-D tensor of one value per index of `axis` (per-axis
+///   quantization), and `x_zero_point` the same shape.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the FLOAT tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A missing `x` or `x_scale`, or an `x` of another type than UINT8, INT8 or INT32.
+/// * A scale or zero point that is neither a single value nor a vector along `axis`.
+/// * A non-zero `block_size`.
+///
+/// # Example
+///
+/// ```rust
+/// let dequantized = dequantize_linear(&[&x, &x_scale, &x_zero_point], &node)?;
+/// ```
+pub fn dequantize_linear(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(1))?;
+    let block_size = get_int_attribute(&attributes, "block_size", Some(0))?;
+    if block_size != 0 {
+        return Err(OnnxError::UnsupportedOperation(format!(
+            "Blocked quantization isn't supported, got a block_size of {}",
+            block_size
+        )));
+    }
+
+    if inputs.len() < 2 {
+        return Err(OnnxError::MissingInput(
+            "DequantizeLinear requires the x and x_scale inputs".to_string(),
+        ));
+    }
+    let x = quantized_values(inputs[0], "x")?;
+    let scale = per_axis(
+        tensor_proto_to_ndarray::<f32>(inputs[1])?,
+        x.shape(),
+        axis,
+        "x_scale",
+    )?;
+    let zero_point = match inputs.get(2) {
+        Some(zero_point) => per_axis(
+            quantized_values(zero_point, "x_zero_point")?,
+            x.shape(),
+            axis,
+            "x_zero_point",
+        )?,
+        None => arr0(0).into_dyn(),
+    };
+
+    let y = Zip::from(&x)
+        .and_broadcast(&scale)
+        .and_broadcast(&zero_point)
+        .map_collect(|&x, &scale, &zero_point| (x - zero_point) as f32 * scale);
+
+    convert_to_output_tensor(node, y)
+}
+
+/// The values of a UINT8, INT8 or INT32 tensor, as i32.
+fn quantized_values(tensor: &TensorProto, name: &str) -> Result<ArrayD<i32>, OnnxError> {
+    if tensor.get_data_type() == TensorProto_DataType::INT32 as i32 {
+        tensor_proto_to_ndarray::<i32>(tensor)
+    } else {
+        widen(tensor, name)
+    }
+}
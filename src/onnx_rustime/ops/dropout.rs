@@ -1,7 +1,8 @@
-use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::backend::helper::{make_tensor, OnnxError, TensorValue};
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, get_float_attribute, get_int_attribute,
+    tensor_proto_to_ndarray,
 };
 use ndarray::prelude::*;
 use rand::{Rng, SeedableRng};
@@ -9,92 +10,112 @@ use rand::{Rng, SeedableRng};
 /// `dropout` - ONNX Node Implementation for Dropout
 ///
 /// Executes the dropout operation, which randomly nullifies a portion of the input units during
-/// training to mitigate overfitting. This operation might consider an optional ratio indicating
-/// the proportion of input units to drop. During inference (with training mode turned off), the
-/// function omits dropout and scales the outputs using the retained ratio.
+/// training to mitigate overfitting, scaling the kept ones by `1 / (1 - ratio)`. During inference
+/// (with training mode turned off, the default) the input is passed through unchanged.
+///
+/// The optional `mask` output holds the kept units: all of them in inference mode.
+///
+/// # Attributes
+///
+/// - `ratio` (float): The proportion of dropped units, in the graphs before opset 12.
+/// - `seed` (int): The seed of the random mask, random by default.
 ///
 /// # Arguments
 ///
-/// * `input` - A reference to the tensor to undergo dropout.
-/// * `initializers` - Optional initializers:
-///     * The first parameter (if provided) outlines the dropout ratio (defaults to 0.5 if not given).
-///     * The second parameter (if given) indicates the model's mode (1 for training, 0 for inference).
+/// * `inputs` - The tensor `data` and the optional scalar `ratio` (0.5 by default, or the `ratio`
+///   attribute before opset 12) and `training_mode` (BOOL, false by default), by position among
+///   the node inputs (`None` for an omitted optional input). They can be initializers or computed
+///   by the graph.
 /// * `node` - A reference to the ONNX NodeProto with node-specific data and attributes, such as the random seed.
 ///
 /// # Returns
 ///
-/// * `Result<TensorProto, OnnxError>` - Returns the tensor after applying dropout, or
-///   an error (`OnnxError`) if any stage of the operation encounters an issue.
+/// * `Result<Vec<TensorProto>, OnnxError>` - Returns the tensor after applying dropout, followed by
+///   the BOOL mask if the node has a second output, or an error (`OnnxError`) if any stage of the
+///   operation encounters an issue.
 ///
 /// # Errors
 ///
 /// Potential errors include:
-/// * Conversion from `TensorProto` to ndarray not succeeding.
+/// * Missing `data`, or conversion from `TensorProto` to ndarray not succeeding.
 /// * Issues or invalid values during attribute extraction.
 ///
 /// # Example
 ///
 /// ```rust
-/// let dropout_output = dropout(&input_tensor, Some(&params_tensors), &node);
+/// let outputs = dropout(&[Some(&input_tensor), Some(&ratio), Some(&training_mode)], &node)?;
+/// let (output, mask) = (&outputs[0], outputs.get(1));
 /// ```
 pub fn dropout(
-    input: &TensorProto,
-    initializers: Option<&Vec<&TensorProto>>,
+    inputs: &[Option<&TensorProto>],
     node: &NodeProto,
-) -> Result<TensorProto, OnnxError> {
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let data = input(0).ok_or(OnnxError::MissingInput("data".to_string()))?;
+
     // Convert TensorProto to ndarray
-    let input_nd_array = tensor_proto_to_ndarray::<f32>(input).map_err(|_| {
+    let input_nd_array = tensor_proto_to_ndarray::<f32>(data).map_err(|_| {
         OnnxError::ConversionError("Failed to convert TensorProto to ndarray".into())
     })?;
 
-    let (ratio, training_mode) = match initializers {
-        Some(tensor_protos) => {
-            let ratio = tensor_protos
-                .get(0)
-                .and_then(|tp| tp.get_float_data().get(0))
-                .cloned()
-                .unwrap_or(0.5);
-            let training_mode = tensor_protos
-                .get(1)
-                .and_then(|tp| tp.get_int64_data().get(0))
-                .cloned()
-                .unwrap_or(0);
-
-            (ratio, training_mode)
-        }
-        None => (0.5, 0),
+    let attributes = extract_attributes(node.get_attribute())?;
+    let ratio = match input(1) {
+        Some(ratio) => tensor_proto_to_ndarray::<f32>(ratio)?
+            .iter()
+            .next()
+            .copied()
+            .unwrap_or(0.5),
+        None => get_float_attribute(&attributes, "ratio", Some(0.5))?,
     };
+    let training_mode = input(2).is_some_and(is_training_mode);
 
-    if training_mode == 0 {
-        let result = convert_to_output_tensor(node, input_nd_array.clone());
-        return result;
-    }
+    let (result, mask) = if training_mode {
+        let seed = get_int_attribute(&attributes, "seed", Some(rand::thread_rng().gen()))?;
 
-    let attributes = extract_attributes(node.get_attribute())?;
-    let seed = get_int_attribute(&attributes, "seed", Some(rand::thread_rng().gen()))?;
-
-    // Compute the scale
-    let scale = 1. / (1. - ratio);
+        // Compute the scale
+        let scale = 1. / (1. - ratio);
 
-    let shape = input_nd_array.shape();
-    let feature_shape: Vec<_> = shape[1..].to_vec();
+        let shape = input_nd_array.shape();
+        let feature_shape: Vec<_> = shape[1..].to_vec();
 
-    // Initialize the RNG with the provided seed
-    let mut rng = rand::rngs::StdRng::seed_from_u64(seed as u64);
+        // Initialize the RNG with the provided seed
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed as u64);
 
-    // Generate the random mask for a single batch element
-    let mask_len = feature_shape.iter().product::<usize>();
-    let single_mask: ArrayD<bool> =
-        Array::from_iter(std::iter::repeat_with(|| rng.gen::<f32>() >= ratio).take(mask_len))
-            .into_shape(feature_shape)
-            .unwrap();
+        // Generate the random mask for a single batch element
+        let mask_len = feature_shape.iter().product::<usize>();
+        let single_mask: ArrayD<bool> =
+            Array::from_iter(std::iter::repeat_with(|| rng.gen::<f32>() >= ratio).take(mask_len))
+                .into_shape(feature_shape)
+                .unwrap();
 
-    // Convert single mask to the same shape as input, but repeating it for each batch element
-    let mask = single_mask.broadcast(shape.to_vec()).unwrap();
+        // Convert single mask to the same shape as input, but repeating it for each batch element
+        let mask = single_mask.broadcast(shape.to_vec()).unwrap().to_owned();
 
-    // Element-wise multiply the scaled input tensor by the mask
-    let result = input_nd_array.mapv(|x| x * scale) * mask.mapv(|x| if x { 1.0 } else { 0.0 });
+        // Element-wise multiply the scaled input tensor by the mask
+        let result = input_nd_array.mapv(|x| x * scale) * mask.mapv(|x| if x { 1.0 } else { 0.0 });
+        (result, mask)
+    } else {
+        let mask = ArrayD::from_elem(input_nd_array.raw_dim(), true);
+        (input_nd_array, mask)
+    };
 
     // Convert the results back to TensorProto
-    convert_to_output_tensor(node, result)
+    let mut outputs = vec![convert_to_output_tensor(node, result)?];
+    if let Some(name) = node.get_output().get(1).filter(|name| !name.is_empty()) {
+        let dims = mask.shape().iter().map(|&dim| dim as i64).collect();
+        let values = mask.iter().copied().collect();
+        outputs.push(make_tensor(
+            Some(name.as_str()),
+            dims,
+            TensorValue::Bool(values),
+        ));
+    }
+    Ok(outputs)
+}
+
+/// Reads the `training_mode` input of a Dropout node, stored either as a BOOL or as an integer.
+pub fn is_training_mode(tensor: &TensorProto) -> bool {
+    tensor.get_int32_data().iter().any(|&x| x != 0)
+        || tensor.get_int64_data().iter().any(|&x| x != 0)
+        || tensor.get_raw_data().iter().any(|&x| x != 0)
 }
@@ -0,0 +1,86 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::quantize_linear::{quantize, quantized_range, quantized_tensor};
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use ndarray::prelude::*;
+
+/// `dynamic_quantize_linear` - ONNX Node Implementation for Dynamic Quantization
+/// (DynamicQuantizeLinear)
+///
+/// Quantizes a float tensor to UINT8 with a scale and a zero point computed from the range of
+/// its values, as inserted by the dynamic quantization of onnxruntime before the integer
+/// operations (e.g. `MatMulInteger`):
+///
+/// ```text
+/// x_min = min(0, min(x))
+/// x_max = max(0, max(x))
+/// y_scale = (x_max - x_min) / 255
+/// y_zero_point = saturate(round(-x_min / y_scale))
+/// y = saturate(round(x / y_scale) + y_zero_point)
+/// ```
+///
+/// The range always includes 0, so that 0 is exactly representable: an all-positive input has
+/// a zero point of 0, an all-negative one of 255. `round` rounds halves to even. An input of
+/// zeros only (or an empty input) has a scale of 0, and is quantized to zeros with a zero point
+/// of 0.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the FLOAT tensor to be quantized.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the UINT8 tensor `y`, the FLOAT scalar
+///   `y_scale` and the UINT8 scalar `y_zero_point`, in the order of the node outputs (the outputs
+///   the node leaves unnamed are left out). In case of an unsuccessful operation, it returns an
+///   error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Unsuccessful conversion of the input, or a missing output name.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = dynamic_quantize_linear(&input_tensor, &node)?;
+/// ```
+pub fn dynamic_quantize_linear(
+    input: &TensorProto,
+    node: &NodeProto,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    let data_type = TensorProto_DataType::UINT8 as i32;
+    let range = quantized_range(data_type)?;
+
+    let x_min = x.iter().fold(0.0f32, |min, &value| min.min(value));
+    let x_max = x.iter().fold(0.0f32, |max, &value| max.max(value));
+    let scale = (x_max - x_min) / (range.1 - range.0) as f32;
+    // With a scale of 0, the NaN of 0 / 0 is quantized to 0.
+    let zero_point = quantize(-x_min / scale, range.0, range);
+    let y = x.mapv(|value| quantize(value / scale, zero_point, range));
+
+    let name = |index: usize| node.get_output().get(index).filter(|name| !name.is_empty());
+    let mut outputs = vec![quantized_tensor(
+        y,
+        data_type,
+        name(0).ok_or(OnnxError::InternalError("Output name missing".to_string()))?,
+    )?];
+    if let Some(name) = name(1) {
+        outputs.push(ndarray_to_tensor_proto::<f32>(
+            arr0(scale).into_dyn(),
+            name,
+        )?);
+    }
+    if let Some(name) = name(2) {
+        outputs.push(quantized_tensor(
+            arr0(zero_point).into_dyn(),
+            data_type,
+            name,
+        )?);
+    }
+    Ok(outputs)
+}
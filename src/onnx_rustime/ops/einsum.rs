@@ -0,0 +1,308 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::matmul::batched_matmul;
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_string_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis, IxDyn};
+
+/// An operand of the equation: a tensor and the label of each of its axes.
+struct Term {
+    data: ArrayD<f32>,
+    labels: Vec<char>,
+}
+
+/// The labels of the inputs and of the output of an equation, e.g. `[ij, jk]` and `ik` for
+/// `"ij,jk->ik"`. Without `->` the output is made of the labels appearing once, in alphabetical
+/// order, as in NumPy.
+fn parse_equation(equation: &str) -> Result<(Vec<Vec<char>>, Vec<char>), OnnxError> {
+    let invalid = |detail: String| OnnxError::InvalidAttribute {
+        name: "equation".to_string(),
+        detail: format!("{} in \"{}\"", detail, equation),
+    };
+    if equation.contains('.') {
+        return Err(OnnxError::UnsupportedOperation(format!(
+            "The ellipsis of the Einsum equation \"{}\" is not supported",
+            equation
+        )));
+    }
+
+    let compact: String = equation.chars().filter(|c| !c.is_whitespace()).collect();
+    let (lhs, rhs) = match compact.split_once("->") {
+        Some((lhs, rhs)) => (lhs, Some(rhs)),
+        None => (compact.as_str(), None),
+    };
+    let inputs: Vec<Vec<char>> = lhs.split(',').map(|term| term.chars().collect()).collect();
+    if let Some(label) = lhs
+        .chars()
+        .chain(rhs.unwrap_or("").chars())
+        .find(|&label| label != ',' && !label.is_ascii_alphabetic())
+    {
+        return Err(invalid(format!("unexpected character '{}'", label)));
+    }
+    let all_labels = || inputs.iter().flatten();
+
+    let output: Vec<char> = match rhs {
+        Some(rhs) => rhs.chars().collect(),
+        None => {
+            let mut once: Vec<char> = all_labels()
+                .filter(|&label| all_labels().filter(|&other| other == label).count() == 1)
+                .copied()
+                .collect();
+            once.sort_unstable();
+            once
+        }
+    };
+    for (i, label) in output.iter().enumerate() {
+        if output[..i].contains(label) {
+            return Err(invalid(format!("the output label '{}' is repeated", label)));
+        }
+        if !all_labels().any(|other| other == label) {
+            return Err(invalid(format!(
+                "the output label '{}' is not an input label",
+                label
+            )));
+        }
+    }
+    Ok((inputs, output))
+}
+
+/// The term with one axis per distinct label: the axes sharing a label are replaced by their
+/// diagonal, e.g. `ii` becomes `i`.
+fn diagonal(term: Term) -> Result<Term, OnnxError> {
+    let mut distinct: Vec<char> = Vec::new();
+    for &label in &term.labels {
+        if !distinct.contains(&label) {
+            distinct.push(label);
+        }
+    }
+    if distinct.len() == term.labels.len() {
+        return Ok(term);
+    }
+
+    // The axis of the diagonal of each axis of the term.
+    let positions: Vec<usize> = term
+        .labels
+        .iter()
+        .map(|label| {
+            distinct
+                .iter()
+                .position(|other| other == label)
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut shape = vec![0; distinct.len()];
+    for (&position, &dim) in positions.iter().zip(term.data.shape()) {
+        if shape[position] != 0 && shape[position] != dim {
+            return Err(OnnxError::shape_mismatch(format!(
+                "The axes labelled '{}' of a tensor of shape {:?} have different dimensions",
+                distinct[position],
+                term.data.shape()
+            )));
+        }
+        shape[position] = dim;
+    }
+
+    let data = ArrayD::from_shape_fn(IxDyn(&shape), |index| {
+        let source: Vec<usize> = positions.iter().map(|&position| index[position]).collect();
+        term.data[IxDyn(&source)]
+    });
+    Ok(Term {
+        data,
+        labels: distinct,
+    })
+}
+
+/// The term with the axes whose label isn't in `kept` summed out.
+fn reduce(term: Term, kept: &[char]) -> Term {
+    let Term {
+        mut data,
+        mut labels,
+    } = term;
+    for axis in (0..labels.len()).rev() {
+        if !kept.contains(&labels[axis]) {
+            data = data.sum_axis(Axis(axis));
+            labels.remove(axis);
+        }
+    }
+    Term { data, labels }
+}
+
+/// The term with its axes permuted to the order of `labels`, as a standard layout array.
+fn permuted(term: &Term, labels: &[char]) -> ArrayD<f32> {
+    let axes: Vec<usize> = labels
+        .iter()
+        .map(|label| {
+            term.labels
+                .iter()
+                .position(|other| other == label)
+                .unwrap_or(0)
+        })
+        .collect();
+    term.data
+        .view()
+        .permuted_axes(axes)
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// The product of two terms, keeping the labels of `kept`: the shared labels that aren't kept are
+/// contracted by a batched matrix multiplication, the shared ones that are kept are its batch.
+fn contract(a: Term, b: Term, kept: &[char]) -> Result<Term, OnnxError> {
+    let shared = |label: &&char| b.labels.contains(label);
+    let batch: Vec<char> = a
+        .labels
+        .iter()
+        .filter(shared)
+        .filter(|label| kept.contains(label))
+        .copied()
+        .collect();
+    let contracted: Vec<char> = a
+        .labels
+        .iter()
+        .filter(shared)
+        .filter(|label| !kept.contains(label))
+        .copied()
+        .collect();
+    let left: Vec<char> = a
+        .labels
+        .iter()
+        .filter(|label| !shared(label))
+        .copied()
+        .collect();
+    let right: Vec<char> = b
+        .labels
+        .iter()
+        .filter(|label| !a.labels.contains(label))
+        .copied()
+        .collect();
+
+    let dims = |term: &Term, labels: &[char]| -> Vec<usize> {
+        labels
+            .iter()
+            .map(|label| {
+                term.data.shape()[term.labels.iter().position(|l| l == label).unwrap_or(0)]
+            })
+            .collect()
+    };
+    let len = |dims: &[usize]| dims.iter().product::<usize>();
+    let (batch_dims, left_dims) = (dims(&a, &batch), dims(&a, &left));
+    let (contracted_dims, right_dims) = (dims(&a, &contracted), dims(&b, &right));
+    if batch_dims != dims(&b, &batch) || contracted_dims != dims(&b, &contracted) {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The operands of shapes {:?} and {:?} have different dimensions for the same label",
+            a.data.shape(),
+            b.data.shape()
+        )));
+    }
+
+    let reshape = |data: ArrayD<f32>, shape: Vec<usize>| {
+        data.into_shape(shape)
+            .map_err(|e| OnnxError::ShapeError(format!("Failed to reshape an operand: {}", e)))
+    };
+    let a_matrices = reshape(
+        permuted(&a, &[batch.as_slice(), &left, &contracted].concat()),
+        vec![len(&batch_dims), len(&left_dims), len(&contracted_dims)],
+    )?;
+    let b_matrices = reshape(
+        permuted(&b, &[batch.as_slice(), &contracted, &right].concat()),
+        vec![len(&batch_dims), len(&contracted_dims), len(&right_dims)],
+    )?;
+    let product = batched_matmul(&a_matrices, &b_matrices)?;
+
+    Ok(Term {
+        data: reshape(product, [batch_dims, left_dims, right_dims].concat())?,
+        labels: [batch, left, right].concat(),
+    })
+}
+
+/// `einsum` - ONNX Node Implementation for Einsum
+///
+/// Sums of products of the elements of the inputs, described by an equation in the Einstein
+/// notation, e.g. `"bhqd,bhkd->bhqk"` for the attention scores of a transformer. Each input is
+/// labelled by one letter per axis; the labels of the output are kept, and the others are summed
+/// over. A label repeated in an input takes its diagonal, e.g. `"ii->"` is a trace.
+///
+/// The inputs are multiplied from left to right. The labels used by a single input are summed
+/// first, and each product is a batched matrix multiplication whose batch is made of the shared
+/// labels still needed, and whose inner dimension is made of the other shared labels.
+///
+/// # Attributes
+///
+/// - `equation` (string): The equation, e.g. `"ij,jk->ik"`. Without `->` the output is made of
+///   the labels appearing once, in alphabetical order.
+///
+/// # Arguments
+///
+/// * `inputs` - The FLOAT tensors, one per term of the equation.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the resulting tensor, with one axis per output
+///   label. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An equation with an ellipsis (`...`), which is not supported.
+/// * A malformed equation, or one whose terms don't match the inputs.
+/// * Different dimensions for the same label.
+///
+/// # Example
+///
+/// ```rust
+/// // The attention scores, with the equation "bhqd,bhkd->bhqk".
+/// let scores = einsum(&[&queries, &keys], &node)?;
+/// ```
+pub fn einsum(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let equation = get_string_attribute(&attributes, "equation", None)?;
+    let (input_labels, output) = parse_equation(&equation)?;
+
+    if input_labels.len() != inputs.len() {
+        return Err(OnnxError::InvalidValue(format!(
+            "The Einsum equation \"{}\" has {} terms for {} inputs",
+            equation,
+            input_labels.len(),
+            inputs.len()
+        )));
+    }
+    let mut terms = Vec::with_capacity(inputs.len());
+    for (input, labels) in inputs.iter().zip(input_labels) {
+        let data = tensor_proto_to_ndarray::<f32>(input)?;
+        if data.ndim() != labels.len() {
+            return Err(OnnxError::shape_mismatch(format!(
+                "The term \"{}\" of the equation \"{}\" doesn't match an input of shape {:?}",
+                labels.iter().collect::<String>(),
+                equation,
+                data.shape()
+            )));
+        }
+        terms.push(diagonal(Term { data, labels })?);
+    }
+
+    // The labels of the terms after each one.
+    let later: Vec<Vec<char>> = (0..terms.len())
+        .map(|i| {
+            terms[i + 1..]
+                .iter()
+                .flat_map(|term| term.labels.clone())
+                .collect()
+        })
+        .collect();
+    let mut result: Option<Term> = None;
+    for (term, later) in terms.into_iter().zip(later) {
+        // The labels needed by the output or by another term.
+        let mut kept = [output.as_slice(), &later].concat();
+        kept.extend(result.iter().flat_map(|result| result.labels.clone()));
+        let term = reduce(term, &kept);
+        result = Some(match result {
+            Some(result) => contract(result, term, &[output.as_slice(), &later].concat())?,
+            None => term,
+        });
+    }
+
+    let result = result.ok_or(OnnxError::MissingInput("Inputs".to_string()))?;
+    convert_to_output_tensor(node, permuted(&result, &output))
+}
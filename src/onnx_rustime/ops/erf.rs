@@ -0,0 +1,72 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{convert_to_output_tensor, tensor_proto_to_ndarray};
+
+/// `erf` - ONNX Node Implementation for the Error Function (Erf)
+///
+/// `erf(x) = 2 / sqrt(pi) * integral of exp(-t^2) from 0 to x`, as used by the BERT-style models
+/// computing GELU before opset 20 (`0.5 * x * (1 + erf(x / sqrt(2)))`).
+///
+/// The values are computed in f64, with an error below 1e-7 (see `erf_value`).
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor of the error function of every value,
+///   of the shape of the input. In case of an unsuccessful operation, it returns an error
+///   (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let output = erf(&input_tensor, &node)?;
+/// ```
+pub fn erf(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| erf_value(x as f64) as f32))
+}
+
+/// The error function of `x`.
+///
+/// Near 0, where `erf(x)` is small and must keep its relative precision, it is the sum of its
+/// Maclaurin series. Elsewhere it is `1 - erfc(|x|)` (with the sign of `x`), `erfc` being the
+/// Chebyshev fit of Numerical Recipes, whose relative error is below 1.2e-7.
+pub(crate) fn erf_value(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x.abs() < 0.5 {
+        // x - x^3 / 3 + x^5 / 10 - x^7 / 42 + ... : 12 terms are exact to f64 below 0.5.
+        let mut sum = 0.0;
+        let mut power = x;
+        for n in 0..12 {
+            sum += power / (2 * n + 1) as f64;
+            power *= -x * x / (n + 1) as f64;
+        }
+        return sum * std::f64::consts::FRAC_2_SQRT_PI;
+    }
+
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |sum, coefficient| sum * t + coefficient);
+    let erfc = t * (-z * z + polynomial).exp();
+    (1.0 - erfc).copysign(x)
+}
@@ -0,0 +1,107 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_shapes, ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use ndarray::IxDyn;
+
+/// Broadcasts the elements of `input` to `output_shape` and encodes them as the output `name`.
+fn expand_as<T: TensorType>(
+    input: &TensorProto,
+    output_shape: &[usize],
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let data = tensor_proto_to_ndarray::<T>(input)?;
+    let expanded = data
+        .broadcast(IxDyn(output_shape))
+        .ok_or_else(|| {
+            OnnxError::shape_mismatch(format!(
+                "Cannot broadcast a tensor of shape {:?} to {:?}",
+                data.shape(),
+                output_shape
+            ))
+        })?
+        .to_owned();
+    ndarray_to_tensor_proto::<T>(expanded, name)
+}
+
+/// `expand` - ONNX Node Implementation for Expand
+///
+/// Broadcasts the input to the shape given by the INT64 tensor `shape`, e.g. to stretch an
+/// attention mask of shape [batch, 1, 1, sequence] over the heads and the queries. The broadcast
+/// follows the NumPy rules in both directions: the shapes are aligned from the last dimension on,
+/// the missing leading dimensions count as 1, and a dimension of 1 (in the input or in `shape`)
+/// stretches to the other one. The output shape can thus be larger than `shape`, as in
+/// `np.ones(shape) * input`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `input` (FLOAT, FLOAT16, INT64 or BOOL) and `shape`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the expanded tensor, of the type of `input` (FLOAT
+///   for a FLOAT16 `input`). In case of an unsuccessful operation, it returns an error
+///   (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input` or `shape`, or a `shape` with negative dimensions.
+/// * Dimensions other than 1 of the input and of `shape` that don't match.
+///
+/// # Example
+///
+/// ```rust
+/// // [3, 1] expanded to [3, 4].
+/// let expanded = expand(&[&column, &shape], &node)?;
+/// ```
+pub fn expand(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let shape = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("shape".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    // An empty `shape` (a scalar target) has no data to convert.
+    let shape: Vec<i64> = match shape.get_dims().iter().product::<i64>() {
+        0 => vec![],
+        _ => tensor_proto_to_ndarray::<i64>(shape)?
+            .iter()
+            .copied()
+            .collect(),
+    };
+    if let Some(&dim) = shape.iter().find(|&&dim| dim < 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The shape {:?} of an Expand has the negative dimension {}",
+            shape, dim
+        )));
+    }
+    let shape: Vec<usize> = shape.iter().map(|&dim| dim as usize).collect();
+    let input_shape: Vec<usize> = input.get_dims().iter().map(|&dim| dim as usize).collect();
+    let output_shape = broadcast_shapes(&input_shape, &shape).map_err(|_| {
+        OnnxError::shape_mismatch(format!(
+            "Cannot expand a tensor of shape {:?} to the shape {:?}: the dimensions other than 1 \
+             must match, from the last one on",
+            input_shape, shape
+        ))
+    })?;
+
+    match input.get_data_type() {
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            expand_as::<i64>(input, &output_shape, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            expand_as::<bool>(input, &output_shape, output_name)
+        }
+        _ => expand_as::<f32>(input, &output_shape, output_name),
+    }
+}
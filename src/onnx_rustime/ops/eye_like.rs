@@ -0,0 +1,102 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, TensorType,
+};
+use ndarray::Array2;
+
+/// A matrix of the given shape with `one` on the diagonal `k` and `zero` elsewhere.
+fn eye_as<T: TensorType>(
+    shape: (usize, usize),
+    k: i64,
+    zero: T::DataType,
+    one: T::DataType,
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let eye = Array2::from_shape_fn(shape, |(row, column)| {
+        match column as i64 - row as i64 == k {
+            true => one.clone(),
+            false => zero.clone(),
+        }
+    });
+    ndarray_to_tensor_proto::<T>(eye.into_dyn(), name)
+}
+
+/// `eye_like` - ONNX Node Implementation for EyeLike
+///
+/// A matrix of the shape of the 2-D input, with ones on a diagonal and zeros elsewhere, e.g. an
+/// identity or a mask built by the graph. The diagonal `k` is made of the elements whose column
+/// minus row is `k`: 0 is the main diagonal, positive values are above it and negative values
+/// below it, whatever the shape of the matrix. The values of the input are ignored.
+///
+/// # Attributes
+///
+/// - `k` (int): The diagonal of ones, 0 (the main one) by default.
+/// - `dtype` (int): The data type of the output, the one of the input by default.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the 2-D input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the matrix, FLOAT, DOUBLE, INT64, INT32, INT8,
+///   UINT8 or BOOL. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An input that isn't 2-D.
+/// * An unsupported data type.
+///
+/// # Example
+///
+/// ```rust
+/// // The [3, 5] matrix with ones above the main diagonal, for k = 1.
+/// let shifted = eye_like(&input_3x5, &node)?;
+/// ```
+pub fn eye_like(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let shape = match input.get_dims() {
+        &[rows, columns] => (rows as usize, columns as usize),
+        dims => {
+            return Err(OnnxError::shape_mismatch(format!(
+                "EyeLike expects a 2-D input, got shape {:?}",
+                dims
+            )))
+        }
+    };
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let k = get_int_attribute(&attributes, "k", Some(0))?;
+    let dtype = match get_int_attribute(&attributes, "dtype", Some(0))? as i32 {
+        0 => input.get_data_type(),
+        dtype => dtype,
+    };
+
+    match dtype {
+        t if t == TensorProto_DataType::FLOAT as i32 => {
+            eye_as::<f32>(shape, k, 0.0, 1.0, output_name)
+        }
+        t if t == TensorProto_DataType::DOUBLE as i32 => {
+            eye_as::<f64>(shape, k, 0.0, 1.0, output_name)
+        }
+        t if t == TensorProto_DataType::INT64 as i32 => eye_as::<i64>(shape, k, 0, 1, output_name),
+        t if t == TensorProto_DataType::INT32 as i32 => eye_as::<i32>(shape, k, 0, 1, output_name),
+        t if t == TensorProto_DataType::INT8 as i32 => eye_as::<i8>(shape, k, 0, 1, output_name),
+        t if t == TensorProto_DataType::UINT8 as i32 => eye_as::<u8>(shape, k, 0, 1, output_name),
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            eye_as::<bool>(shape, k, false, true, output_name)
+        }
+        t => Err(OnnxError::UnsupportedOperation(format!(
+            "EyeLike doesn't support the data type {}",
+            t
+        ))),
+    }
+}
@@ -35,16 +35,28 @@ use crate::onnx_rustime::ops::utils::{convert_to_output_tensor, tensor_proto_to_
 /// let flattened_tensor = flatten(&input_tensor, &node);
 /// ```
 pub fn flatten(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
-    // Extract dimensions from the input tensor.
-    let input_shape = input.get_dims();
-    let input_first = input_shape[0] as usize;
-
     // Convert the input TensorProto to an ndarray.
     let input_nd_array = tensor_proto_to_ndarray::<f32>(input).map_err(|_| {
         OnnxError::ConversionError("Failed to convert TensorProto to ndarray".into())
     })?;
 
-    let mut output_shape: Vec<usize> = Vec::new();
+    let output_shape = flattened_shape(input_nd_array.shape(), node)?;
+
+    // Reshape the input ndarray to the output shape.
+    let result = input_nd_array
+        .into_shape(output_shape)
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+
+    // Convert the reshaped ndarray back to TensorProto and return.
+    convert_to_output_tensor(node, result)
+}
+
+/// The shape of the output of a Flatten node: the dimensions of the input before the `axis`
+/// attribute, and the ones after it, are multiplied together.
+pub fn flattened_shape(input_shape: &[usize], node: &NodeProto) -> Result<Vec<usize>, OnnxError> {
+    let input_first = *input_shape.first().ok_or(OnnxError::shape_mismatch(
+        "Cannot flatten a scalar".to_string(),
+    ))?;
 
     // Extract the 'axis' attribute from the node.
     let axis_attribute = node
@@ -55,34 +67,23 @@ pub fn flatten(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, Onn
     // Determine the axis value; default is 1 if not provided.
     let axis = axis_attribute.map_or(1, |attr| attr.get_i() as usize);
 
-    // Compute the total number of elements from the axis to the last dimension.
-    let total_elements = input_shape.clone()[1..].iter().product::<i64>() as usize;
-
     // Determine the shape of the output tensor based on the axis.
     if axis <= 1 {
-        output_shape = vec![input_first, total_elements];
+        // Compute the total number of elements from the axis to the last dimension.
+        let total_elements = input_shape[1..].iter().product();
+        Ok(vec![input_first, total_elements])
     } else {
         let mut outer_dim = 1;
         let mut inner_dim = 1;
 
         for (index, &dim) in input_shape.iter().enumerate() {
             if index < axis {
-                outer_dim *= dim as usize;
+                outer_dim *= dim;
             } else {
-                inner_dim *= dim as usize;
+                inner_dim *= dim;
             }
         }
 
-        output_shape.push(outer_dim);
-        output_shape.push(inner_dim);
+        Ok(vec![outer_dim, inner_dim])
     }
-
-    // Reshape the input ndarray to the output shape.
-    let result = input_nd_array.into_shape(output_shape).unwrap();
-
-    // For debugging: print the shape of the result.
-    println!("shape {:?}", result.shape());
-
-    // Convert the reshaped ndarray back to TensorProto and return.
-    convert_to_output_tensor(node, result)
 }
@@ -0,0 +1,364 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis};
+
+/// Reads the indices of a Gather or a Scatter, INT32 or INT64.
+pub(crate) fn gather_indices(indices: &TensorProto) -> Result<ArrayD<i64>, OnnxError> {
+    if indices.get_data_type() == TensorProto_DataType::INT32 as i32 {
+        Ok(tensor_proto_to_ndarray::<i32>(indices)?.mapv(i64::from))
+    } else {
+        tensor_proto_to_ndarray::<i64>(indices)
+    }
+}
+
+/// The position of `index` along an axis of length `length`, negative indices counting from the
+/// end of the axis.
+pub(crate) fn index_position(index: i64, length: usize, axis: usize) -> Result<usize, OnnxError> {
+    let position = if index < 0 {
+        index + length as i64
+    } else {
+        index
+    };
+    usize::try_from(position)
+        .ok()
+        .filter(|&position| position < length)
+        .ok_or_else(|| {
+            OnnxError::InvalidValue(format!(
+                "Index {} is out of range for axis {} of length {}",
+                index, axis, length
+            ))
+        })
+}
+
+/// Takes the entries of `data` along `axis` at `indices`: the axis is replaced by the dimensions
+/// of the indices, so that a scalar index removes it.
+fn gather_axis<T: Clone>(
+    data: &ArrayD<T>,
+    indices: &ArrayD<i64>,
+    axis: usize,
+) -> Result<ArrayD<T>, OnnxError> {
+    let length = data.len_of(Axis(axis));
+    let positions = indices
+        .iter()
+        .map(|&index| index_position(index, length, axis))
+        .collect::<Result<Vec<usize>, OnnxError>>()?;
+
+    let mut shape = data.shape()[..axis].to_vec();
+    shape.extend_from_slice(indices.shape());
+    shape.extend_from_slice(&data.shape()[axis + 1..]);
+    let gathered = data.select(Axis(axis), &positions);
+    ArrayD::from_shape_vec(shape, gathered.iter().cloned().collect())
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+}
+
+/// `gather` - ONNX Node Implementation for Gather
+///
+/// Takes the entries of `data` along `axis` at the positions given by `indices`, of any rank: the
+/// output has the shape `data.shape[..axis] + indices.shape + data.shape[axis + 1..]`. Gathering
+/// the rows of an embedding matrix with a [batch, sequence] tensor of token ids gives a
+/// [batch, sequence, embedding] tensor, and a scalar index removes the axis (e.g. the batch size
+/// taken from the output of a `Shape`).
+///
+/// Negative indices count from the end of the axis.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to gather along, 0 by default. Negative values count from the end.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `data` (FLOAT, FLOAT16 or INT64) and `indices` (INT32 or INT64), in
+///   the order of the node inputs. Either can be an initializer.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the gathered tensor, of the element type of
+///   `data` (FLOAT for a FLOAT16 `data`). In case of an unsuccessful operation, it returns an
+///   error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data` or `indices`.
+/// * An `axis` out of range, or an index out of the range of the axis.
+///
+/// # Example
+///
+/// ```rust
+/// let embeddings = gather(&[&embedding_matrix, &token_ids], &node)?;
+/// ```
+pub fn gather(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (data, indices) = match inputs {
+        [data, indices, ..] => (*data, gather_indices(indices)?),
+        _ => {
+            return Err(OnnxError::MissingInput(
+                "Gather requires the data and indices inputs".to_string(),
+            ))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(0))?;
+    let rank = data.get_dims().len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+
+    if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let data = tensor_proto_to_ndarray::<i64>(data)?;
+        ndarray_to_tensor_proto::<i64>(gather_axis(&data, &indices, axis)?, output_name)
+    } else {
+        let data = tensor_proto_to_ndarray::<f32>(data)?;
+        ndarray_to_tensor_proto::<f32>(gather_axis(&data, &indices, axis)?, output_name)
+    }
+}
+
+/// Takes, for every index, the entry of `data` at its position with the coordinate along `axis`
+/// replaced by the index.
+fn gather_elements_axis<T: Clone>(
+    data: &ArrayD<T>,
+    indices: &ArrayD<i64>,
+    axis: usize,
+) -> Result<ArrayD<T>, OnnxError> {
+    if indices.ndim() != data.ndim()
+        || (0..data.ndim()).any(|d| d != axis && indices.shape()[d] > data.shape()[d])
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The indices of shape {:?} of a GatherElements must have the rank of the data of \
+             shape {:?}, and no longer dimensions but along axis {}",
+            indices.shape(),
+            data.shape(),
+            axis
+        )));
+    }
+
+    let length = data.len_of(Axis(axis));
+    let values = indices
+        .indexed_iter()
+        .map(|(mut position, &index)| {
+            position[axis] = index_position(index, length, axis)?;
+            Ok(data[&position].clone())
+        })
+        .collect::<Result<Vec<T>, OnnxError>>()?;
+    ArrayD::from_shape_vec(indices.raw_dim(), values)
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+}
+
+/// `gather_elements` - ONNX Node Implementation for GatherElements
+///
+/// Takes one entry of `data` for every index, at the position of the index with its coordinate
+/// along `axis` replaced by the index (`torch.gather`). For 2D tensors and `axis = 1`,
+/// `output[i][j] = data[i][indices[i][j]]`. The output has the shape of the indices, which have
+/// the rank of the data and dimensions no longer than its own but along `axis`.
+///
+/// Negative indices count from the end of the axis.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to gather along, 0 by default. Negative values count from the end.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `data` (FLOAT, FLOAT16 or INT64) and `indices` (INT32 or INT64), in
+///   the order of the node inputs. Either can be an initializer.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the gathered tensor, of the shape of the indices
+///   and the element type of `data` (FLOAT for a FLOAT16 `data`). In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data` or `indices`.
+/// * An `axis` out of range, or an index out of the range of the axis.
+/// * Indices of another rank than the data, or longer along an axis other than `axis`.
+///
+/// # Example
+///
+/// ```rust
+/// let picked = gather_elements(&[&scores, &top_indices], &node)?;
+/// ```
+pub fn gather_elements(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let (data, indices) = match inputs {
+        [data, indices, ..] => (*data, gather_indices(indices)?),
+        _ => {
+            return Err(OnnxError::MissingInput(
+                "GatherElements requires the data and indices inputs".to_string(),
+            ))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(0))?;
+    let rank = data.get_dims().len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+
+    if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let data = tensor_proto_to_ndarray::<i64>(data)?;
+        ndarray_to_tensor_proto::<i64>(gather_elements_axis(&data, &indices, axis)?, output_name)
+    } else {
+        let data = tensor_proto_to_ndarray::<f32>(data)?;
+        ndarray_to_tensor_proto::<f32>(gather_elements_axis(&data, &indices, axis)?, output_name)
+    }
+}
+
+/// Takes the slices of `data` addressed by the last dimension of `indices`, within each of the
+/// first `batch_dims` dimensions they share.
+fn gather_nd_slices<T: Clone>(
+    data: &ArrayD<T>,
+    indices: &ArrayD<i64>,
+    batch_dims: usize,
+) -> Result<ArrayD<T>, OnnxError> {
+    let (r, q) = (data.ndim(), indices.ndim());
+    if q == 0 || batch_dims >= r.min(q) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The batch_dims {} of a GatherND must be less than the ranks {} of the data and {} of \
+             the indices",
+            batch_dims, r, q
+        )));
+    }
+    let k = indices.shape()[q - 1];
+    if data.shape()[..batch_dims] != indices.shape()[..batch_dims] || k < 1 || k > r - batch_dims {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Cannot index the data of shape {:?} with indices of shape {:?} and batch_dims {}",
+            data.shape(),
+            indices.shape(),
+            batch_dims
+        )));
+    }
+
+    let mut shape = indices.shape()[..q - 1].to_vec();
+    shape.extend_from_slice(&data.shape()[batch_dims + k..]);
+
+    // The batch dimensions are flattened into one, and so are the dimensions of the index tuples.
+    let batch: usize = data.shape()[..batch_dims].iter().product();
+    let tuples: usize = indices.shape()[batch_dims..q - 1].iter().product();
+    let mut data_shape = vec![batch];
+    data_shape.extend_from_slice(&data.shape()[batch_dims..]);
+    let data = data
+        .view()
+        .into_shape(data_shape)
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+    let indices = indices
+        .view()
+        .into_shape(vec![batch, tuples, k])
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+
+    let mut values = Vec::new();
+    for (b, batch_indices) in indices.outer_iter().enumerate() {
+        for tuple in batch_indices.outer_iter() {
+            let mut entry = data.index_axis(Axis(0), b);
+            for (t, &index) in tuple.iter().enumerate() {
+                let position = index_position(index, entry.len_of(Axis(0)), batch_dims + t)?;
+                entry = entry.index_axis_move(Axis(0), position);
+            }
+            values.extend(entry.iter().cloned());
+        }
+    }
+
+    ArrayD::from_shape_vec(shape, values).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+}
+
+/// `gather_nd` - ONNX Node Implementation for GatherND
+///
+/// Takes the slices of `data` addressed by the last dimension of `indices`: each tuple of `k`
+/// indices selects the entry of the first `k` axes of the data (after the batch ones), of the
+/// shape of the remaining axes. The output has the shape
+/// `indices.shape[..q - 1] + data.shape[batch_dims + k..]`.
+///
+/// With `batch_dims = b`, the first `b` dimensions of the data and the indices are the same, and
+/// the indices of every batch address the data of the same batch only, e.g. the boxes kept for
+/// each image of a detection model.
+///
+/// Negative indices count from the end of their axis.
+///
+/// # Attributes
+///
+/// - `batch_dims` (int): The number of batch dimensions, 0 by default (since opset 12).
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `data` (FLOAT, FLOAT16 or INT64) and `indices` (INT32 or INT64), in
+///   the order of the node inputs. Either can be an initializer.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the gathered tensor, of the element type of
+///   `data` (FLOAT for a FLOAT16 `data`). In case of an unsuccessful operation, it returns an
+///   error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data` or `indices`.
+/// * A `batch_dims` not less than the ranks of the data and the indices, batch dimensions that
+///   differ, or index tuples longer than the axes of the data after the batch ones.
+/// * An index out of the range of its axis.
+///
+/// # Example
+///
+/// ```rust
+/// let boxes = gather_nd(&[&all_boxes, &kept_indices], &node)?;
+/// ```
+pub fn gather_nd(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (data, indices) = match inputs {
+        [data, indices, ..] => (*data, gather_indices(indices)?),
+        _ => {
+            return Err(OnnxError::MissingInput(
+                "GatherND requires the data and indices inputs".to_string(),
+            ))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let batch_dims = get_int_attribute(&attributes, "batch_dims", Some(0))?;
+    let batch_dims = usize::try_from(batch_dims).map_err(|_| {
+        OnnxError::InvalidValue(format!(
+            "The batch_dims of a GatherND can't be negative, got {}",
+            batch_dims
+        ))
+    })?;
+
+    if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let data = tensor_proto_to_ndarray::<i64>(data)?;
+        ndarray_to_tensor_proto::<i64>(gather_nd_slices(&data, &indices, batch_dims)?, output_name)
+    } else {
+        let data = tensor_proto_to_ndarray::<f32>(data)?;
+        ndarray_to_tensor_proto::<f32>(gather_nd_slices(&data, &indices, batch_dims)?, output_name)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::erf::erf_value;
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_string_attribute, tensor_proto_to_ndarray,
+};
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI};
+
+/// `gelu` - ONNX Node Implementation for the Gaussian Error Linear Unit Activation (Gelu)
+///
+/// The input weighted by the probability of a standard normal variable being below it, the
+/// activation of the transformers:
+///
+/// `y = 0.5 * x * (1 + erf(x / sqrt(2)))`
+///
+/// or, with `approximate = "tanh"`,
+///
+/// `y = 0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3)))`
+///
+/// The exact form computes the same values as the `Erf` node the exports before opset 20 build
+/// it with.
+///
+/// # Attributes
+///
+/// - `approximate` (string): `"none"` (the default) for the exact form, or `"tanh"`.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * An `approximate` other than `"none"` and `"tanh"`.
+///
+/// # Example
+///
+/// ```rust
+/// let activated = gelu(&input_tensor, &node)?;
+/// ```
+pub fn gelu(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let approximate = get_string_attribute(&attributes, "approximate", Some("none".to_string()))?;
+    let gate: fn(f64) -> f64 = match approximate.as_str() {
+        "none" => |x| 0.5 * (1.0 + erf_value(x * FRAC_1_SQRT_2)),
+        "tanh" => |x| {
+            // sqrt(2 / pi)
+            let scale = FRAC_2_SQRT_PI * FRAC_1_SQRT_2;
+            0.5 * (1.0 + (scale * (x + 0.044715 * x.powi(3))).tanh())
+        },
+        approximate => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The approximate attribute of Gelu must be \"none\" or \"tanh\", got \"{}\"",
+                approximate
+            )))
+        }
+    };
+
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| (x as f64 * gate(x as f64)) as f32))
+}
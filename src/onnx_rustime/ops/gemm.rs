@@ -21,18 +21,19 @@ pub enum OperationMode {
 /// - \( A' \) is the transpose of \( A \) if `transA` is true, otherwise it's \( A \).
 /// - \( B' \) is the transpose of \( B \) if `transB` is true, otherwise it's \( B \).
 /// - `alpha` and `beta` are scalar multipliers.
-/// - For plain matrix multiplication (if node type is "MatMul"):
-///     * `alpha` defaults to 1 (no scaling).
-///     * `beta` defaults to 0 (effectively disregarding \( C \)).
+/// - `alpha` and `beta` default to 1. For plain matrix multiplication (if node type is
+///   "MatMul") there is no \( C \).
 ///
-/// This operation supports unidirectional broadcasting where tensor \( C \) should be
-/// unidirectionally broadcastable to the shape of \( A \times B \).
+/// For GEMM, \( A' \) and \( B' \) are matrices of shapes `(M, K)` and `(K, N)`, and the
+/// optional \( C \) (since opset 11) is unidirectionally broadcast to the `(M, N)` result: it can
+/// be a scalar, a vector of `N` values (e.g. the bias of a linear layer), or a matrix of shape
+/// `(1, N)`, `(M, 1)` or `(M, N)`.
 ///
 /// # Arguments
 ///
 /// * `inputs`: A vector containing references to the input tensors `A`, `B`,
 ///   and optionally `C` if the operation type is GEMM.
-/// * `initializers`: An optional vector of initializers, currently unused.
+/// * `initializers`: An optional vector of initializers, appended to the inputs.
 /// * `node`: A reference to the ONNX `NodeProto` that might have node-specific attributes
 ///   like `alpha`, `beta`, `transA`, and `transB`. The node type determines whether the operation
 ///   is GEMM or plain matrix multiplication (MatMul).
@@ -53,59 +54,103 @@ pub fn gemm(
     initializers: Option<&Vec<&TensorProto>>,
     node: &NodeProto,
 ) -> Result<TensorProto, OnnxError> {
+    // Merge inputs and initializers.
+    let mut merged_tensors: Vec<&TensorProto> = inputs.clone();
+    if let Some(params) = initializers {
+        merged_tensors.extend(params);
+    }
+
+    // Convert TensorProtos to ndarray using the merged list.
+    let a = tensor_proto_to_ndarray::<f32>(get_tensor(&merged_tensors, 0, "A")?)?;
+    let b = tensor_proto_to_ndarray::<f32>(get_tensor(&merged_tensors, 1, "B")?)?;
+    // C is usually an initializer (e.g. the bias of a linear layer), so it's looked up in the
+    // merged list.
+    let c = merged_tensors
+        .get(2)
+        .map(|c| tensor_proto_to_ndarray::<f32>(c))
+        .transpose()?;
+
+    let result = gemm_ndarray(&a, &b, c.as_ref(), node)?;
+    convert_to_output_tensor(node, result)
+}
+
+/// `gemm` on the decoded `A`, `B` and `C`, which are only borrowed: the executor keeps the
+/// weights of a model as arrays for all its runs.
+pub(crate) fn gemm_ndarray(
+    a: &ArrayD<f32>,
+    b: &ArrayD<f32>,
+    c: Option<&ArrayD<f32>>,
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
     // Extract operation attributes and decide the operation mode.
     let attributes = extract_attributes(node.get_attribute())?;
     let mode = determine_mode(&node.op_type)?;
 
     // Fetch operation attributes.
     let alfa: f32 = get_float_attribute(&attributes, "alpha", Some(1.0))?;
-    let beta: f32 = get_float_attribute(&attributes, "beta", Some(0.0))?;
+    let beta: f32 = get_float_attribute(&attributes, "beta", Some(1.0))?;
     let trans_a: i64 = get_int_attribute(&attributes, "transA", Some(0))?;
     let trans_b: i64 = get_int_attribute(&attributes, "transB", Some(0))?;
 
-    // Merge inputs and initializers.
-    let mut merged_tensors: Vec<&TensorProto> = inputs.clone();
-    if let Some(params) = initializers {
-        merged_tensors.extend(params);
+    // GEMM only multiplies matrices.
+    if let OperationMode::Gemm = mode {
+        if a.ndim() != 2 || b.ndim() != 2 {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Gemm expects matrices, got A of shape {:?} and B of shape {:?}",
+                a.shape(),
+                b.shape()
+            )));
+        }
     }
 
-    // Convert TensorProtos to ndarray using the merged list.
-    let mut a = tensor_proto_to_ndarray::<f32>(get_tensor(&merged_tensors, 0, "A")?)?;
-    let mut b = tensor_proto_to_ndarray::<f32>(get_tensor(&merged_tensors, 1, "B")?)?;
-
     // Transpose matrices based on attributes
-    if trans_a == 1 {
-        a = a.t().to_owned();
-    }
-    if trans_b == 1 {
-        b = b.t().to_owned();
+    let a = transposed(a, trans_a != 0);
+    let b = transposed(b, trans_b != 0);
+
+    // Check that the matrices can be multiplied, instead of letting `dot` panic.
+    if a.ndim() == 0 || b.ndim() != 2 || a.shape()[a.ndim() - 1] != b.shape()[0] {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Can't multiply A of shape {:?} by B of shape {:?} (after transA={}, transB={})",
+            a.shape(),
+            b.shape(),
+            trans_a,
+            trans_b
+        )));
     }
 
     // Perform the matrix multiplication.
-    let mut result = matrix_multiply(&a, &b).ok_or(OnnxError::InternalError(
+    let mut result = matrix_multiply(&a.view(), &b.view()).ok_or(OnnxError::InternalError(
         "Failed to multiply matrices".to_string(),
     ))?;
     result.mapv_inplace(|x| x * alfa);
 
     // Handle the case for GEMM operation where there's an optional C matrix.
     if let OperationMode::Gemm = mode {
-        if let Some(c_tensor_proto) = inputs.get(2) {
-            let mut c_array = tensor_proto_to_ndarray::<f32>(c_tensor_proto)?;
-            c_array.mapv_inplace(|x| x * beta);
-
-            // Ensure both matrices are broadcast-compatible.
-            if result.shape() != c_array.shape() {
-                return Err(OnnxError::ShapeMismatch(format!(
-                    "Expected shape {:?}, but got {:?}",
+        if let Some(c) = c {
+            let c_array = c.mapv(|x| x * beta);
+
+            // Ensure C is unidirectionally broadcastable to the result.
+            let c_broadcast = c_array.broadcast(result.shape()).ok_or_else(|| {
+                OnnxError::shape_mismatch(format!(
+                    "Expected shape broadcastable to {:?}, but got {:?}",
                     result.shape(),
                     c_array.shape()
-                )));
-            }
-            result += &c_array;
+                ))
+            })?;
+            result += &c_broadcast;
         }
     }
 
-    convert_to_output_tensor(node, result)
+    Ok(result)
+}
+
+/// The transpose of a matrix, or a view of the matrix itself.
+fn transposed(matrix: &ArrayD<f32>, transpose: bool) -> CowArray<'_, f32, IxDyn> {
+    if transpose {
+        matrix.t().to_owned().into()
+    } else {
+        matrix.view().into()
+    }
 }
 
 /// Determines the operation mode based on the operation type.
@@ -132,12 +177,8 @@ fn get_tensor<'a>(
         .ok_or(OnnxError::MissingInput(name.to_string()))
 }
 
-fn matrix_multiply(a: &ArrayD<f32>, b: &ArrayD<f32>) -> Option<ArrayD<f32>> {
-    let b_matrix = b
-        .view()
-        .into_dimensionality::<ndarray::Ix2>()
-        .unwrap()
-        .to_owned();
+fn matrix_multiply(a: &ArrayViewD<f32>, b: &ArrayViewD<f32>) -> Option<ArrayD<f32>> {
+    let b_matrix = b.view().into_dimensionality::<ndarray::Ix2>().unwrap();
 
     match a.shape()[0] {
         1 => matrix_multiply_single(a, &b_matrix),
@@ -147,8 +188,8 @@ fn matrix_multiply(a: &ArrayD<f32>, b: &ArrayD<f32>) -> Option<ArrayD<f32>> {
 
 /// Handle the batched matrix multiplication.
 fn matrix_multiply_batched(
-    a: &ArrayD<f32>,
-    b_matrix: &ndarray::Array2<f32>,
+    a: &ArrayViewD<f32>,
+    b_matrix: &ndarray::ArrayView2<f32>,
 ) -> Option<ArrayD<f32>> {
     let shape = a.shape();
     let batch_size = shape[0];
@@ -169,8 +210,11 @@ fn matrix_multiply_batched(
 }
 
 /// Handle a single matrix multiplication.
-fn matrix_multiply_single(a: &ArrayD<f32>, b_matrix: &ndarray::Array2<f32>) -> Option<ArrayD<f32>> {
-    let a_2d = a.to_owned().into_dimensionality::<ndarray::Ix2>().unwrap();
+fn matrix_multiply_single(
+    a: &ArrayViewD<f32>,
+    b_matrix: &ndarray::ArrayView2<f32>,
+) -> Option<ArrayD<f32>> {
+    let a_2d = a.view().into_dimensionality::<ndarray::Ix2>().unwrap();
     let result = a_2d.dot(b_matrix);
     Some(result.into_dyn())
 }
@@ -1,21 +1,22 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, stack_along_batch_dimension,
-    tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, tensor_proto_to_ndarray,
 };
+use ndarray::parallel::prelude::*;
 use ndarray::prelude::*;
-use rayon::prelude::*;
 
 /// `global_average_pool` - ONNX Node Implementation for Global Average Pooling Operation
 ///
 /// Computes the average value for each channel across all spatial dimensions, which
 /// effectively condenses the spatial dimensions into a single averaged value. The resultant
-/// tensor shape is `[batch_size, channels, 1, 1]`.
+/// tensor shape is `[batch_size, channels, 1, ..., 1]`, with as many spatial axes as the input:
+/// `[N, C, 1]` for a 1-D signal, `[N, C, 1, 1, 1]` for a volume. The values are summed in double
+/// precision, so the averages of large spatial extents don't lose the small values.
 ///
 /// # Arguments
 ///
-/// * `inputs` - A reference to the tensor set for global average pooling.
+/// * `inputs` - A reference to the `[N, C, D1, ..., Dn]` tensor to be pooled.
 /// * `node` - A reference to the ONNX NodeProto containing node-specific data and
 ///   potential attributes.
 ///
@@ -30,8 +31,14 @@ use rayon::prelude::*;
 /// Possible errors include:
 /// * Failed extraction of node attributes.
 /// * Unsuccessful conversion from `TensorProto` to ndarray.
-/// * Global average pooling operation not being successful.
+/// * An input without spatial axes.
 ///
+/// # Example
+///
+/// ```rust
+/// // The [N, C, 1, 1, 1] features of the [N, C, D, H, W] activations of a 3D CNN.
+/// let result_tensor = global_average_pool(&input_tensor, &node);
+/// ```
 pub fn global_average_pool(
     inputs: &TensorProto,
     node: &NodeProto,
@@ -52,8 +59,8 @@ pub fn global_average_pool(
 /// Performs global average pooling on the given tensor.
 ///
 /// This helper function computes the global average for each channel in the
-/// input tensor. It expects the input tensor shape to be `[batch_size, channels, ..., ...]`.
-/// The output tensor will have a shape of `[batch_size, channels, 1, 1]`.
+/// input tensor. It expects the input tensor shape to be `[batch_size, channels, ...]`, with
+/// any number of spatial axes, which are all 1 in the output.
 ///
 /// # Arguments
 /// - `input_tensor`: The input tensor for which the global average pooling is computed.
@@ -62,32 +69,39 @@ pub fn global_average_pool(
 /// - A `Result` containing the tensor after performing global average pooling or
 ///   an error of type `OnnxError` if the pooling operation fails.
 ///
-fn global_average_pooling(input_tensor: &ArrayD<f32>) -> Result<ArrayD<f32>, OnnxError> {
-    // Get the batch size and number of channels from the input tensor shape.
-    let batch_size = input_tensor.shape()[0];
-    let channels = input_tensor.shape()[1];
+pub(crate) fn global_average_pooling(input_tensor: &ArrayD<f32>) -> Result<ArrayD<f32>, OnnxError> {
+    if input_tensor.ndim() < 3 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input with spatial axes, got shape {:?}",
+            input_tensor.shape()
+        )));
+    }
 
-    // Perform global average pooling for each batch and channel.
-    let pooled_results: Vec<_> = (0..batch_size)
-        .into_par_iter()
-        .map(|b| {
-            let mut channel_averages = vec![0.0; channels];
-            for c in 0..channels {
-                let channel_data = input_tensor
-                    .index_axis(Axis(0), b)
-                    .index_axis(Axis(0), c)
-                    .to_owned();
+    // One row per channel, holding all its spatial values.
+    let (batch_size, channels) = (input_tensor.shape()[0], input_tensor.shape()[1]);
+    let input = input_tensor.as_standard_layout();
+    let rows = input
+        .view()
+        .into_shape((
+            batch_size * channels,
+            input.len() / (batch_size * channels).max(1),
+        ))
+        .map_err(|_| OnnxError::ShapeError("Failed to flatten the channels".to_string()))?;
 
-                // Calculate the average value for the current channel.
-                let average_value = channel_data.mean().unwrap();
-                channel_averages[c] = average_value;
-            }
-            // Convert channel averages into a tensor of shape [channels, 1, 1].
-            ArrayD::from_shape_vec(IxDyn(&[channels, 1, 1]), channel_averages)
-                .expect("Failed to create tensor from averages")
-        })
-        .collect();
+    // The sums are accumulated as f64: a f32 sum of millions of values absorbs the small ones.
+    let mut averages = Array1::<f32>::zeros(batch_size * channels);
+    averages
+        .axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .zip(rows.axis_iter(Axis(0)))
+        .for_each(|(mut average, row)| {
+            let sum: f64 = row.iter().map(|&value| value as f64).sum();
+            average.fill((sum / row.len() as f64) as f32);
+        });
 
-    // Stack the results along the batch dimension to produce the final output tensor.
-    stack_along_batch_dimension(pooled_results)
+    let mut output_shape = vec![batch_size, channels];
+    output_shape.resize(input.ndim(), 1);
+    averages
+        .into_shape(output_shape)
+        .map_err(|e| OnnxError::ShapeError(format!("Failed to create output tensor: {}", e)))
 }
@@ -0,0 +1,81 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::lp_pool::lp_order;
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, tensor_proto_to_ndarray,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+
+/// `global_lp_pool` - ONNX Node Implementation for Global Lp Pooling Operation
+///
+/// Computes the Lp norm `(sum |x| ^ p) ^ (1 / p)` of every channel across all its spatial axes,
+/// like an `LpPool` whose window covers the whole input. The resultant tensor shape is
+/// `[batch_size, channels, 1, ..., 1]`, with as many spatial axes as the input.
+///
+/// # Attributes
+///
+/// - `p` (int): The order of the norm, 2 by default. Also accepted as a float.
+///
+/// # Arguments
+///
+/// * `inputs` - A reference to the `[N, C, D1, ..., Dn]` tensor to be pooled.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor after global Lp pooling. In case of
+///   an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, or a `p` that isn't positive.
+/// * An input without spatial axes.
+///
+/// # Example
+///
+/// ```rust
+/// let result_tensor = global_lp_pool(&input_tensor, &node);
+/// ```
+pub fn global_lp_pool(inputs: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let p = lp_order(&attributes)?;
+
+    let input = tensor_proto_to_ndarray::<f32>(inputs)?;
+    if input.ndim() < 3 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input with spatial axes, got shape {:?}",
+            input.shape()
+        )));
+    }
+
+    // One row per channel, holding all its spatial values.
+    let (batch_size, channels) = (input.shape()[0], input.shape()[1]);
+    let input = input.as_standard_layout();
+    let rows = input
+        .view()
+        .into_shape((
+            batch_size * channels,
+            input.len() / (batch_size * channels).max(1),
+        ))
+        .map_err(|_| OnnxError::ShapeError("Failed to flatten the channels".to_string()))?;
+
+    let mut norms = Array1::<f32>::zeros(batch_size * channels);
+    norms
+        .axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .zip(rows.axis_iter(Axis(0)))
+        .for_each(|(mut norm, row)| {
+            let sum: f32 = row.iter().map(|value| value.abs().powf(p)).sum();
+            norm.fill(sum.powf(1.0 / p));
+        });
+
+    let mut output_shape = vec![batch_size, channels];
+    output_shape.resize(input.ndim(), 1);
+    let result = norms
+        .into_shape(output_shape)
+        .map_err(|e| OnnxError::ShapeError(format!("Failed to create output tensor: {}", e)))?;
+
+    convert_to_output_tensor(node, result)
+}
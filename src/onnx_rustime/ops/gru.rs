@@ -0,0 +1,110 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::recurrent::Recurrence;
+use crate::onnx_rustime::ops::utils::{extract_attributes, get_int_attribute};
+use ndarray::prelude::*;
+
+/// `gru` - ONNX Node Implementation for GRU
+///
+/// A Gated Recurrent Unit layer, run over the sequences of the batch in one or both directions.
+/// With `f` and `g` its activation functions (Sigmoid and Tanh by default), each time step
+/// computes the gates from the input `Xt` and the previous hidden state `Ht-1`:
+///
+/// ```text
+/// zt = f(Xt*(Wz^T) + Ht-1*(Rz^T) + Wbz + Rbz)
+/// rt = f(Xt*(Wr^T) + Ht-1*(Rr^T) + Wbr + Rbr)
+/// ht = g(Xt*(Wh^T) + (rt (.) Ht-1)*(Rh^T) + Rbh + Wbh)    # linear_before_reset = 0
+/// ht = g(Xt*(Wh^T) + (rt (.) (Ht-1*(Rh^T) + Rbh)) + Wbh)  # linear_before_reset = 1
+/// Ht = (1 - zt) (.) ht + zt (.) Ht-1
+/// ```
+///
+/// The weights of the gates are stacked in the order `z`, `r`, `h`, and the ones of the reverse
+/// direction of a bidirectional GRU follow the ones of the forward direction. The sequence
+/// lengths are handled as in `lstm`.
+///
+/// # Attributes
+///
+/// - `hidden_size` (int): The size of the hidden state, the last dimension of `R` by default.
+/// - `direction` (string): `forward` (the default), `reverse` or `bidirectional`.
+/// - `activations` (strings): The functions `f` and `g` of every direction.
+/// - `activation_alpha`, `activation_beta` (floats): The parameters of the activation functions
+///   having them (e.g. HardSigmoid), in order.
+/// - `clip` (float): The bound of the inputs of the activations, unbounded by default.
+/// - `linear_before_reset` (int): 1 to apply the reset gate after the product by `Rh` (as
+///   PyTorch and the exports of its GRU do), 0 by default.
+/// - `layout` (int): 1 for `X`, `Y` and the states with the batch first, 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X` (`[seq_length, batch_size, input_size]`), `W`
+///   (`[num_directions, 3 * hidden_size, input_size]`), `R` (`[num_directions, 3 *
+///   hidden_size, hidden_size]`) and the optional `B` (`[num_directions, 6 * hidden_size]`, the
+///   biases of `W` then the ones of `R`), `sequence_lens` (INT32 `[batch_size]`) and `initial_h`
+///   (`[num_directions, batch_size, hidden_size]`), by position among the node inputs (`None`
+///   for an omitted optional input). The omitted ones are zero, and the sequences span all of
+///   `X`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the outputs the node requests among `Y`
+///   (`[seq_length, num_directions, batch_size, hidden_size]`) and `Y_h` (the final hidden
+///   state, `[num_directions, batch_size, hidden_size]`). In case of an unsuccessful operation,
+///   it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X`, `W` or `R`.
+/// * Inputs whose shapes don't match the sizes of the node, or sequence lengths out of range.
+/// * An unknown `direction` or activation function, or a wrong number of activations.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = gru(&[Some(&x), Some(&w), Some(&r), Some(&b)], &node)?;
+/// let (y, y_h) = (&outputs[0], &outputs[1]);
+/// ```
+pub fn gru(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let recurrence = Recurrence::new("GRU", inputs, &attributes, 3, &["Sigmoid", "Tanh"])?;
+    let linear_before_reset = get_int_attribute(&attributes, "linear_before_reset", Some(0))? != 0;
+
+    let hidden = recurrence.hidden_size;
+    let gate = |gates: &Array2<f32>, index: usize| {
+        gates
+            .slice(s![.., index * hidden..(index + 1) * hidden])
+            .to_owned()
+    };
+
+    recurrence.run(
+        node,
+        vec![recurrence.initial_h.clone()],
+        |direction, inputs, states| {
+            let previous = &states[0];
+            let recurrent = recurrence.projected_state(direction, previous);
+            // The activations f and g of the direction.
+            let f = |x: f32| recurrence.activate(direction, 0, x);
+            let g = |x: f32| recurrence.activate(direction, 1, x);
+
+            let update = (gate(&inputs, 0) + gate(&recurrent, 0)).mapv(f);
+            let reset = (gate(&inputs, 1) + gate(&recurrent, 1)).mapv(f);
+            let reset_hidden = match linear_before_reset {
+                true => &reset * &gate(&recurrent, 2),
+                false => gate(
+                    &recurrence.projected_state(direction, &(&reset * previous)),
+                    2,
+                ),
+            };
+            let candidate = (gate(&inputs, 2) + reset_hidden).mapv(g);
+            let hidden_state = Array2::from_shape_fn(previous.raw_dim(), |(batch, j)| {
+                let z = update[[batch, j]];
+                (1.0 - z) * candidate[[batch, j]] + z * previous[[batch, j]]
+            });
+            vec![hidden_state]
+        },
+    )
+}
@@ -0,0 +1,76 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_float_attribute, tensor_proto_to_ndarray,
+};
+
+/// `hard_sigmoid` - ONNX Node Implementation for the Hard Sigmoid Activation (HardSigmoid)
+///
+/// A piecewise linear approximation of the sigmoid, as exported from MobileNetV3:
+///
+/// `y = max(0, min(1, alpha * x + beta))`
+///
+/// The output is 0 up to `x = -beta / alpha`, and 1 from `x = (1 - beta) / alpha`.
+///
+/// # Attributes
+///
+/// - `alpha` (float): The slope, 0.2 by default.
+/// - `beta` (float): The offset, 0.5 by default.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = hard_sigmoid(&input_tensor, &node)?;
+/// ```
+pub fn hard_sigmoid(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let alpha = get_float_attribute(&attributes, "alpha", Some(0.2))?;
+    let beta = get_float_attribute(&attributes, "beta", Some(0.5))?;
+
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| hard_sigmoid_value(x, alpha, beta)))
+}
+
+/// `hard_swish` - ONNX Node Implementation for the Hard Swish Activation (HardSwish)
+///
+/// The input gated by its hard sigmoid, with the fixed `alpha = 1/6` and `beta = 0.5` of the
+/// specification (the operation has no attributes):
+///
+/// `y = x * max(0, min(1, x / 6 + 0.5))`
+///
+/// The output is 0 up to `x = -3`, and `x` from `x = 3`.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = hard_swish(&input_tensor, &node)?;
+/// ```
+pub fn hard_swish(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| x * hard_sigmoid_value(x, 1.0 / 6.0, 0.5)))
+}
+
+/// `alpha * x + beta`, clamped to [0, 1].
+fn hard_sigmoid_value(x: f32, alpha: f32, beta: f32) -> f32 {
+    (alpha * x + beta).clamp(0.0, 1.0)
+}
@@ -0,0 +1,58 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
+use crate::onnx_rustime::ops::softmax::normalize_along_axis;
+
+/// `hardmax` - ONNX Node Implementation for the Hardmax Operation
+///
+/// The hard counterpart of `softmax`: every group of values normalized together gets a 1 at the
+/// position of its maximum and 0 everywhere else. When the maximum occurs several times, only its
+/// first occurrence is set to 1.
+///
+/// # Arguments
+///
+/// * `input` - Reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto, housing the attributes of the operation.
+/// * `opset` - The version of the default operator set imported by the model, which selects the
+///   semantics of `axis`.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor of 0 and 1, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Attributes
+///
+/// * `axis` - The axis of the operation, with the semantics of `softmax`: before opset 13 it
+///   defaults to `1` and the input is coerced to 2D around it (a single 1 per row of the
+///   flattened trailing dimensions), since opset 13 it defaults to `-1` and there is a 1 per lane
+///   along it. Negative values count from the end.
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, or an `axis` out of the range of the input.
+///
+/// # Example
+///
+/// ```rust
+/// let one_hot = hardmax(&input, &node, opset)?;
+/// ```
+pub fn hardmax(
+    input: &TensorProto,
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    normalize_along_axis(input, node, opset, |mut lane| {
+        // The first maximum wins: later values must be strictly greater to replace it.
+        let mut first_max: Option<(usize, f32)> = None;
+        for (index, &value) in lane.iter().enumerate() {
+            if first_max.is_none_or(|(_, max)| value > max) {
+                first_max = Some((index, value));
+            }
+        }
+        lane.fill(0.0);
+        if let Some((index, _)) = first_max {
+            lane[index] = 1.0;
+        }
+    })
+}
@@ -0,0 +1,43 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+
+/// `identity` - ONNX Node Implementation for Identity
+///
+/// The input, under the name of the output. The executor forwards the Identity nodes instead of
+/// running this kernel (as the Dropout nodes in inference mode and the Cast nodes to the type of
+/// their input): their output shares the tensor of their input, which is never copied. The kernel
+/// is the fallback of the nodes it can't forward, and copies the input.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input`, of any type.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the copy of the input. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input`.
+///
+/// # Example
+///
+/// ```rust
+/// let output = identity(&[&input], &node)?;
+/// ```
+pub fn identity(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let mut output = (*input).clone();
+    output.set_name(output_name.clone());
+    Ok(output)
+}
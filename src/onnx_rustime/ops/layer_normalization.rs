@@ -0,0 +1,148 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_float_attribute, get_int_attribute,
+    ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+
+/// `layer_normalization` - ONNX Node Implementation for Layer Normalization
+///
+/// Normalizes the input over its last axes, from `axis` to the end: every group of values
+/// sharing the same indices before `axis` is shifted by its mean and divided by its standard
+/// deviation, then scaled and shifted by `Scale` and `B`:
+///
+/// `Y = (X - mean) / sqrt(variance + epsilon) * Scale + B`
+///
+/// # Attributes
+///
+/// - `axis` (int): The first normalized axis, -1 by default (the last one). Negative values
+///   count from the end.
+/// - `epsilon` (float): Added to the variance to avoid dividing by zero, 1e-5 by default.
+/// - `stash_type` (int): The type of the statistics. Only 1 (FLOAT, the default) is supported.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X`, `Scale` and the optional `B`, in the order of the node inputs.
+///   `Scale` and `B` are broadcast to the normalized shape (`X.shape[axis..]`).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the normalized tensor, followed by the
+///   optional `Mean` and `InvStdDev` outputs the node requests, of shape `X.shape[..axis]`
+///   followed by ones. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, or an unsupported `stash_type`.
+/// * A missing `Scale`, or an `axis` out of the range of the input.
+/// * A `Scale` or `B` that can't be broadcast to the normalized shape.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = layer_normalization(&[&input, &scale, &bias], &node)?;
+/// ```
+pub fn layer_normalization(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(-1))?;
+    let epsilon = get_float_attribute(&attributes, "epsilon", Some(1e-5))?;
+    let stash_type = get_int_attribute(&attributes, "stash_type", Some(1))?;
+    if stash_type != 1 {
+        return Err(OnnxError::InvalidValue(format!(
+            "Only a stash_type of 1 (FLOAT) is supported, got {}",
+            stash_type
+        )));
+    }
+
+    if inputs.len() < 2 {
+        return Err(OnnxError::MissingInput(
+            "LayerNormalization requires the X and Scale inputs".to_string(),
+        ));
+    }
+    let x = tensor_proto_to_ndarray::<f32>(inputs[0])?;
+    let rank = x.ndim() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+
+    // Scale and B, flattened over the normalized shape.
+    let normalized_shape = &x.shape()[axis..];
+    let broadcast_parameter = |tensor: &TensorProto, name: &str| -> Result<Vec<f32>, OnnxError> {
+        let parameter = tensor_proto_to_ndarray::<f32>(tensor)?;
+        let broadcast = parameter.broadcast(normalized_shape).ok_or_else(|| {
+            OnnxError::shape_mismatch(format!(
+                "Can't broadcast the {} of shape {:?} to the normalized shape {:?}",
+                name,
+                parameter.shape(),
+                normalized_shape
+            ))
+        })?;
+        Ok(broadcast.iter().copied().collect())
+    };
+    let scale = broadcast_parameter(inputs[1], "Scale")?;
+    let bias = match inputs.get(2) {
+        Some(bias) => broadcast_parameter(bias, "B")?,
+        None => vec![0.0; scale.len()],
+    };
+
+    let group_len = normalized_shape.iter().product::<usize>();
+    let groups = x.shape()[..axis].iter().product::<usize>();
+    let mut output = x
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((groups, group_len))
+        .map_err(|_| OnnxError::ShapeError("Failed to group the normalized axes".to_string()))?;
+
+    let mut mean = Array1::<f32>::zeros(groups);
+    let mut inv_std_dev = Array1::<f32>::zeros(groups);
+    output
+        .axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .zip(mean.axis_iter_mut(Axis(0)))
+        .zip(inv_std_dev.axis_iter_mut(Axis(0)))
+        .for_each(|((mut group, mut mean), mut inv_std_dev)| {
+            let group_mean = group.sum() / group_len as f32;
+            let variance = group
+                .iter()
+                .map(|value| (value - group_mean).powi(2))
+                .sum::<f32>()
+                / group_len as f32;
+            let inverse = 1.0 / (variance + epsilon).sqrt();
+
+            for ((value, scale), bias) in group.iter_mut().zip(&scale).zip(&bias) {
+                *value = (*value - group_mean) * inverse * scale + bias;
+            }
+            mean.fill(group_mean);
+            inv_std_dev.fill(inverse);
+        });
+
+    let reshape =
+        |error| OnnxError::ShapeError(format!("Failed to create output tensor: {}", error));
+    let mut statistics_shape = x.shape()[..axis].to_vec();
+    statistics_shape.resize(x.ndim(), 1);
+
+    let mut outputs = vec![convert_to_output_tensor(
+        node,
+        output.into_shape(x.shape()).map_err(reshape)?,
+    )?];
+    for (index, statistic) in [(1, mean), (2, inv_std_dev)] {
+        if let Some(name) = node.get_output().get(index).filter(|name| !name.is_empty()) {
+            let statistic = statistic
+                .into_shape(statistics_shape.clone())
+                .map_err(reshape)?;
+            outputs.push(ndarray_to_tensor_proto::<f32>(statistic, name)?);
+        }
+    }
+    Ok(outputs)
+}
@@ -0,0 +1,55 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
+use crate::onnx_rustime::ops::softmax::normalize_along_axis;
+
+/// `log_softmax` - ONNX Node Implementation for Log-Softmax Normalization (LogSoftmax)
+///
+/// The logarithm of the softmax of the input, as exported at the end of classifiers trained
+/// with a negative log-likelihood loss. It is computed directly as
+///
+/// `LogSoftmax(x) = x - max(x) - log(sum(exp(x - max(x))))`
+///
+/// rather than as `log(softmax(x))`, whose smallest probabilities underflow to 0 (and their
+/// logarithm to -inf): large negative logits keep a finite value.
+///
+/// # Arguments
+///
+/// * `input` - Reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto, housing the attributes of the operation.
+/// * `opset` - The version of the default operator set imported by the model, which selects the
+///   semantics of `axis`.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor of log-probabilities, of the shape of
+///   the input. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Attributes
+///
+/// * `axis` - The axis of the normalization, with the semantics of `softmax`: before opset 13 it
+///   defaults to `1` and the input is coerced to 2D around it, since opset 13 it defaults to `-1`
+///   and only the values along it are normalized together. Negative values count from the end.
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, or an `axis` out of the range of the input.
+///
+/// # Example
+///
+/// ```rust
+/// let log_probabilities = log_softmax(&logits, &node, opset)?;
+/// ```
+pub fn log_softmax(
+    input: &TensorProto,
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    normalize_along_axis(input, node, opset, |mut lane| {
+        let max = lane.fold(f32::NEG_INFINITY, |max, &value| max.max(value));
+        let log_sum = lane
+            .fold(0.0f32, |sum, &value| sum + (value - max).exp())
+            .ln();
+        lane.mapv_inplace(|value| value - max - log_sum);
+    })
+}
@@ -0,0 +1,133 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_map, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::ArrayD;
+
+/// Reads an input of a logical operation, which must be BOOL.
+fn bool_input(tensor: &TensorProto, node: &NodeProto) -> Result<ArrayD<bool>, OnnxError> {
+    if tensor.get_data_type() != TensorProto_DataType::BOOL as i32 {
+        return Err(OnnxError::InvalidValue(format!(
+            "The inputs of {} must be BOOL, got the data type {}",
+            node.get_op_type(),
+            tensor.get_data_type()
+        )));
+    }
+    tensor_proto_to_ndarray::<bool>(tensor)
+}
+
+/// Combines the two BOOL inputs of a node, broadcast together, with `f`.
+fn logical(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    f: fn(bool, bool) -> bool,
+) -> Result<TensorProto, OnnxError> {
+    let [a, b] = match inputs {
+        [a, b] => [*a, *b],
+        _ => {
+            return Err(OnnxError::MissingInput(format!(
+                "{} expects the tensors A and B, got {} inputs",
+                node.get_op_type(),
+                inputs.len()
+            )))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let a = bool_input(a, node)?;
+    let b = bool_input(b, node)?;
+    let result = broadcast_map(&a, &b, |&a, &b| f(a, b))?;
+    ndarray_to_tensor_proto::<bool>(result, output_name)
+}
+
+/// `and` - ONNX Node Implementation for And
+///
+/// The logical AND of the two BOOL inputs, element by element. The inputs are broadcast together
+/// as in NumPy (see `broadcast_shapes`), e.g. a [1, S] padding mask with a [B, 1] one.
+///
+/// # Arguments
+///
+/// * `inputs` - The BOOL tensors `A` and `B`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the BOOL result. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or inputs that aren't BOOL.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let mask = and(&[&padding_mask, &causal_mask], &node)?;
+/// ```
+pub fn and(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    logical(inputs, node, |a, b| a && b)
+}
+
+/// `or` - ONNX Node Implementation for Or
+///
+/// The logical OR of the two BOOL inputs, element by element. The broadcasting, the inputs and
+/// the errors are those of `and`.
+///
+/// # Example
+///
+/// ```rust
+/// let masked = or(&[&padding_mask, &future_mask], &node)?;
+/// ```
+pub fn or(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    logical(inputs, node, |a, b| a || b)
+}
+
+/// `xor` - ONNX Node Implementation for Xor
+///
+/// The logical XOR of the two BOOL inputs, element by element. The broadcasting, the inputs and
+/// the errors are those of `and`.
+///
+/// # Example
+///
+/// ```rust
+/// let changed = xor(&[&previous_mask, &mask], &node)?;
+/// ```
+pub fn xor(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    logical(inputs, node, |a, b| a != b)
+}
+
+/// `not` - ONNX Node Implementation for Not
+///
+/// The logical negation of the BOOL input, element by element.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the BOOL input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the BOOL result, or an error (`OnnxError`) if the
+///   input isn't BOOL.
+///
+/// # Example
+///
+/// ```rust
+/// let padding = not(&attention_mask, &node)?;
+/// ```
+pub fn not(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let result = bool_input(input, node)?.mapv(|x| !x);
+    ndarray_to_tensor_proto::<bool>(result, output_name)
+}
@@ -0,0 +1,117 @@
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, pool_geometry, tensor_proto_to_ndarray, PoolAxis,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+use std::collections::HashMap;
+
+/// `lp_pool` - ONNX Node Implementation for Lp Pooling Operation
+///
+/// Computes the Lp norm of the windows sliding along the spatial axes of the input tensor (all
+/// the axes after the batch and the channels): every output is `(sum |x| ^ p) ^ (1 / p)` over
+/// the values of its window, the padded positions counting as zeros. With `p` 1 it is the sum of
+/// the absolute values, with `p` 2 the Euclidean norm.
+///
+/// The sums are separable: the powers are summed along one spatial axis at a time.
+///
+/// # Attributes
+///
+/// - `p` (int): The order of the norm, 2 by default. Also accepted as a float, as emitted by some
+///   exporters (it was a float until opset 2).
+/// - `kernel_shape` (ints): The size of the window along each spatial axis, required.
+/// - `strides`, `dilations` (ints): The steps between the windows and between their taps, 1
+///   by default.
+/// - `pads` (ints): The padding at the beginning and at the end of each spatial axis (the
+///   beginnings first), 0 by default. Ignored with `auto_pad`.
+/// - `auto_pad` (string): `NOTSET` (explicit `pads`), `SAME_UPPER`, `SAME_LOWER` or `VALID`.
+/// - `ceil_mode` (int): Round the number of windows up instead of down, 0 by default.
+///
+/// The output spatial shape follows `pool_geometry`.
+///
+/// # Arguments
+///
+/// * `inputs` - A reference to the `[N, C, D1, ..., Dn]` tensor to be pooled.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor after Lp pooling. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Failure in extracting node attributes, a missing `kernel_shape` or a `p` that isn't positive.
+/// * An input without spatial axes, or attributes not matching its spatial axes.
+/// * A window that doesn't fit in the padded input.
+///
+/// # Example
+///
+/// ```rust
+/// let result_tensor = lp_pool(&input_tensor, &node);
+/// ```
+pub fn lp_pool(inputs: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let p = lp_order(&attributes)?;
+
+    let input = tensor_proto_to_ndarray::<f32>(inputs)?;
+    if input.ndim() < 3 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input with spatial axes, got shape {:?}",
+            input.shape()
+        )));
+    }
+
+    let geometry = pool_geometry(&attributes, &input.shape()[2..])?;
+    let mut result = geometry.iter().enumerate().fold(
+        input.mapv(|value| value.abs().powf(p)),
+        |array, (axis, geometry)| sum_along_axis(&array, Axis(axis + 2), geometry),
+    );
+    result.par_mapv_inplace(|sum| sum.powf(1.0 / p));
+
+    convert_to_output_tensor(node, result)
+}
+
+/// Reads the `p` attribute of `LpPool` and `GlobalLpPool`, an int or a float, 2 by default.
+pub(crate) fn lp_order(attributes: &HashMap<String, Attribute<String>>) -> Result<f32, OnnxError> {
+    let p = match attributes.get("p") {
+        Some(Attribute::Int(p)) => *p as f32,
+        Some(Attribute::Float(p)) => *p,
+        Some(_) => {
+            return Err(OnnxError::InvalidValue(
+                "Expected an int or a float p".to_string(),
+            ))
+        }
+        None => 2.0,
+    };
+    if !(p.is_finite() && p > 0.0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected a positive p, got {}",
+            p
+        )));
+    }
+    Ok(p)
+}
+
+/// Sums the windows of one spatial axis, in parallel across the windows.
+fn sum_along_axis(input: &ArrayD<f32>, axis: Axis, geometry: &PoolAxis) -> ArrayD<f32> {
+    let input_len = input.len_of(axis);
+    let mut shape = input.shape().to_vec();
+    shape[axis.index()] = geometry.output_len;
+
+    let mut output = ArrayD::zeros(shape);
+    output
+        .axis_iter_mut(axis)
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(window, mut sum)| {
+            for position in geometry.taps(window) {
+                if (0..input_len as isize).contains(&position) {
+                    sum += &input.index_axis(axis, position as usize);
+                }
+            }
+        });
+    output
+}
@@ -31,7 +31,8 @@ use rayon::prelude::*;
 /// The square sum for a region is:
 ///
 /// `square_sum[n, c, d1, ..., dk] = sum(X[n, i, d1, ..., dk] ^ 2)`,
-/// where the values of i are within the defined local region.
+/// where the values of i are within the defined local region. The region is truncated at the
+/// first and the last channels, but the sum is still divided by `size` below.
 ///
 /// The output tensor Y is calculated as:
 ///
@@ -50,9 +51,9 @@ use rayon::prelude::*;
 /// # Errors
 ///
 /// Possible errors include:
-/// * Failed extraction of node attributes.
+/// * Failed extraction of node attributes, or a `size` of 0.
 /// * Unsuccessful conversion from `TensorProto` to ndarray.
-/// * LRN operation not being successful.
+/// * An input without channels.
 ///
 /// # Example
 ///
@@ -65,14 +66,30 @@ pub fn lrn(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxErr
     let alpha: f32 = get_float_attribute(&attributes, "alpha", Some(0.0001))?;
     let beta: f32 = get_float_attribute(&attributes, "beta", Some(0.75))?;
     let bias: f32 = get_float_attribute(&attributes, "bias", Some(1.0))?;
-    let size: usize = get_int_attribute(&attributes, "size", None)? as usize;
+    let size = get_int_attribute(&attributes, "size", None)?;
+    if size < 1 {
+        return Err(OnnxError::InvalidValue(format!(
+            "The size of LRN must be at least 1, got {}",
+            size
+        )));
+    }
+    let size = size as usize;
 
     // Convert TensorProto to ndarray.
     let x = tensor_proto_to_ndarray::<f32>(input)?;
-    let shape = x.dim();
-    let c = shape[1]; // Number of channels
+    if x.ndim() < 2 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input of shape [N, C, ...], got {:?}",
+            x.shape()
+        )));
+    }
+    let c = x.shape()[1]; // Number of channels
+    let squares = x.mapv(|v| v.powi(2));
 
-    let mut square_sum = Array::zeros(shape);
+    // The channels of the region of a channel, below and above it (both included).
+    let (below, above) = ((size - 1) / 2, size / 2);
+
+    let mut square_sum = Array::zeros(x.raw_dim());
 
     // Split square_sum into n slices along the batch dimension and iterate in parallel.
     square_sum
@@ -80,18 +97,14 @@ pub fn lrn(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxErr
         .into_par_iter()
         .enumerate()
         .for_each(|(b, mut batch_slice)| {
+            let batch_squares = squares.index_axis(Axis(0), b);
             for idx in 0..c {
-                let start = usize::max(
-                    0,
-                    idx.saturating_sub(((size - 1) as f32 / 2.0).floor() as usize),
-                );
-                let end = usize::min(c, idx + ((size - 1) as f32 / 2.0).ceil() as usize);
+                let start = idx.saturating_sub(below);
+                let end = usize::min(c, idx + above + 1);
 
+                let mut channel_sum = batch_slice.index_axis_mut(Axis(0), idx);
                 for j in start..end {
-                    let slice = x.slice(s![b, j, .., ..]);
-                    batch_slice
-                        .slice_mut(s![idx, .., ..])
-                        .zip_mut_with(&slice.mapv(|v| v.powi(2)), |a, &b| *a += b);
+                    channel_sum += &batch_squares.index_axis(Axis(0), j);
                 }
             }
         });
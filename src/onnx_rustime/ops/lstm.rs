@@ -0,0 +1,126 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::recurrent::Recurrence;
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+
+/// `lstm` - ONNX Node Implementation for LSTM
+///
+/// A Long Short-Term Memory layer, run over the sequences of the batch in one or both
+/// directions. With `f`, `g` and `h` its activation functions (Sigmoid, Tanh and Tanh by
+/// default), each time step computes the gates from the input `Xt` and the previous states
+/// `Ht-1` and `Ct-1`:
+///
+/// ```text
+/// it = f(Xt*(Wi^T) + Ht-1*(Ri^T) + Pi (.) Ct-1 + Wbi + Rbi)
+/// ft = f(Xt*(Wf^T) + Ht-1*(Rf^T) + Pf (.) Ct-1 + Wbf + Rbf)
+/// ct = g(Xt*(Wc^T) + Ht-1*(Rc^T) + Wbc + Rbc)
+/// Ct = ft (.) Ct-1 + it (.) ct
+/// ot = f(Xt*(Wo^T) + Ht-1*(Ro^T) + Po (.) Ct + Wbo + Rbo)
+/// Ht = ot (.) h(Ct)
+/// ```
+///
+/// The weights of the gates are stacked in the order `i`, `o`, `f`, `c`, and the ones of the
+/// reverse direction of a bidirectional LSTM follow the ones of the forward direction. Each
+/// sequence stops at its length in `sequence_lens`: the reverse direction starts from its last
+/// element, its outputs past the end are zero, and its final states are the ones of its last
+/// step.
+///
+/// # Attributes
+///
+/// - `hidden_size` (int): The size of the hidden state, the last dimension of `R` by default.
+/// - `direction` (string): `forward` (the default), `reverse` or `bidirectional`.
+/// - `activations` (strings): The functions `f`, `g` and `h` of every direction.
+/// - `activation_alpha`, `activation_beta` (floats): The parameters of the activation functions
+///   having them (e.g. HardSigmoid), in order.
+/// - `clip` (float): The bound of the inputs of the activations, unbounded by default.
+/// - `input_forget` (int): 1 to couple the input and the forget gates (`ft = 1 - it`), 0 by
+///   default.
+/// - `layout` (int): 1 for `X`, `Y` and the states with the batch first, 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X` (`[seq_length, batch_size, input_size]`), `W`
+///   (`[num_directions, 4 * hidden_size, input_size]`), `R` (`[num_directions, 4 *
+///   hidden_size, hidden_size]`) and the optional `B` (`[num_directions, 8 * hidden_size]`, the
+///   biases of `W` then the ones of `R`), `sequence_lens` (INT32 `[batch_size]`), `initial_h`
+///   and `initial_c` (`[num_directions, batch_size, hidden_size]`) and `P` (the peepholes
+///   `[num_directions, 3 * hidden_size]`, in the order `i`, `o`, `f`), by position among the
+///   node inputs (`None` for an omitted optional input). The omitted ones are zero, and the
+///   sequences span all of `X`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the outputs the node requests among `Y`
+///   (`[seq_length, num_directions, batch_size, hidden_size]`), `Y_h` and `Y_c` (the final
+///   states, `[num_directions, batch_size, hidden_size]`). In case of an unsuccessful operation,
+///   it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X`, `W` or `R`.
+/// * Inputs whose shapes don't match the sizes of the node, or sequence lengths out of range.
+/// * An unknown `direction` or activation function, or a wrong number of activations.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = lstm(&[Some(&x), Some(&w), Some(&r), Some(&b)], &node)?;
+/// let (y, y_h) = (&outputs[0], &outputs[1]);
+/// ```
+pub fn lstm(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let recurrence = Recurrence::new("LSTM", inputs, &attributes, 4, &["Sigmoid", "Tanh", "Tanh"])?;
+    let input_forget = get_int_attribute(&attributes, "input_forget", Some(0))? != 0;
+    let initial_c = recurrence.state(inputs, 6, "initial_c")?;
+
+    let (directions, hidden) = (recurrence.directions(), recurrence.hidden_size);
+    let peepholes = match inputs.get(7).copied().flatten() {
+        Some(p) => {
+            let p = tensor_proto_to_ndarray::<f32>(p)?;
+            if p.shape() != [directions, 3 * hidden] {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "The P input of LSTM must be of shape {:?}, got {:?}",
+                    [directions, 3 * hidden],
+                    p.shape()
+                )));
+            }
+            p.into_dimensionality::<Ix2>()
+                .map_err(|e| OnnxError::ShapeError(e.to_string()))?
+        }
+        None => Array2::zeros((directions, 3 * hidden)),
+    };
+
+    let initial_states = vec![recurrence.initial_h.clone(), initial_c];
+    recurrence.run(node, initial_states, |direction, inputs, states| {
+        let (previous_hidden, previous_cell) = (&states[0], &states[1]);
+        let gates = inputs + recurrence.projected_state(direction, previous_hidden);
+        let p = peepholes.row(direction);
+        // The activations f, g and h of the direction.
+        let activate = |index: usize, x: f32| recurrence.activate(direction, index, x);
+
+        let cell = Array2::from_shape_fn(previous_cell.raw_dim(), |(batch, j)| {
+            let gate = |index: usize| gates[[batch, index * hidden + j]];
+            let previous = previous_cell[[batch, j]];
+            let input = activate(0, gate(0) + p[j] * previous);
+            let forget = match input_forget {
+                true => 1.0 - input,
+                false => activate(0, gate(2) + p[2 * hidden + j] * previous),
+            };
+            forget * previous + input * activate(1, gate(3))
+        });
+        let hidden_state = Array2::from_shape_fn(cell.raw_dim(), |(batch, j)| {
+            let cell = cell[[batch, j]];
+            let output = activate(0, gates[[batch, hidden + j]] + p[hidden + j] * cell);
+            output * recurrence.activation(direction, 2).apply(cell)
+        });
+        vec![hidden_state, cell]
+    })
+}
@@ -0,0 +1,277 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_map, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+
+/// Applies an element-wise function to the input of a node: `float` to a FLOAT (or FLOAT16)
+/// input, `integer` to an INT64 one, if the operation is defined on integers.
+///
+/// The functions follow IEEE 754 where the operation is undefined: e.g. `Log(0)` is -inf and
+/// `Sqrt(-1)` is NaN.
+fn unary(
+    input: &TensorProto,
+    node: &NodeProto,
+    float: fn(f32) -> f32,
+    integer: Option<fn(i64) -> i64>,
+) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    if input.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let integer = integer.ok_or_else(|| {
+            OnnxError::UnsupportedOperation(format!(
+                "{} is not defined on INT64 tensors",
+                node.get_op_type()
+            ))
+        })?;
+        let x = tensor_proto_to_ndarray::<i64>(input)?;
+        return ndarray_to_tensor_proto::<i64>(x.mapv(integer), output_name);
+    }
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    ndarray_to_tensor_proto::<f32>(x.mapv(float), output_name)
+}
+
+/// `exp` - ONNX Node Implementation for Exponential Operation
+///
+/// Evaluates the exponential of each element within the input tensor.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the tensor consisting of values set to be exponentiated.
+/// * `node` - A reference to the ONNX NodeProto which might have node-specific data required
+///   during the subsequent conversion back to TensorProto.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tensor with computed exponentials, or
+///   raises an error (`OnnxError`) if any phase of the operation experiences an issue.
+///
+/// # Errors
+///
+/// Potential errors include:
+/// * Conversion from `TensorProto` to ndarray not succeeding.
+///
+/// # Example
+///
+/// ```rust
+/// let exponential_output = exp(&input_tensor, &node);
+/// ```
+pub fn exp(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::exp, None)
+}
+
+/// `log` - ONNX Node Implementation for Log
+///
+/// The natural logarithm of every element of the input: -inf for 0, NaN for the negative values.
+/// The arguments, the output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let log_probabilities = log(&probabilities, &node)?;
+/// ```
+pub fn log(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::ln, None)
+}
+
+/// `sqrt` - ONNX Node Implementation for Sqrt
+///
+/// The square root of every element of the input: NaN for the negative values. The arguments,
+/// the output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let std = sqrt(&variance, &node)?;
+/// ```
+pub fn sqrt(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::sqrt, None)
+}
+
+/// `reciprocal` - ONNX Node Implementation for Reciprocal
+///
+/// `1 / x` for every element of the input: infinite (with the sign of the zero) for 0. The
+/// arguments, the output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let inverse_std = reciprocal(&std, &node)?;
+/// ```
+pub fn reciprocal(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::recip, None)
+}
+
+/// `neg` - ONNX Node Implementation for Neg
+///
+/// The opposite of every element of the input, FLOAT or INT64 (wrapping around for the minimum
+/// INT64 value). The arguments are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let negated = neg(&input_tensor, &node)?;
+/// ```
+pub fn neg(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, |x| -x, Some(i64::wrapping_neg))
+}
+
+/// `abs` - ONNX Node Implementation for Abs
+///
+/// The absolute value of every element of the input, FLOAT or INT64 (wrapping around for the
+/// minimum INT64 value). The arguments are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let magnitudes = abs(&input_tensor, &node)?;
+/// ```
+pub fn abs(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::abs, Some(i64::wrapping_abs))
+}
+
+/// `sign` - ONNX Node Implementation for Sign
+///
+/// -1, 0 or 1 for every negative, zero or positive element of the input, FLOAT or INT64. NaN
+/// stays NaN. The arguments are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let signs = sign(&input_tensor, &node)?;
+/// ```
+pub fn sign(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    // `f32::signum` is 1 for +0 and -1 for -0.
+    let float = |x: f32| if x == 0.0 { 0.0 } else { x.signum() };
+    unary(input, node, float, Some(i64::signum))
+}
+
+/// `pow` - ONNX Node Implementation for Pow
+///
+/// Raises every element of `X` to the power of the matching element of `Y`, the two inputs being
+/// broadcast together as in NumPy (see `broadcast_shapes`), e.g. `x ^ 3` in the tanh
+/// approximation of GELU.
+///
+/// The output has the type of `X`. The exponents can be INT64 (since opset 12): integer
+/// exponents of a FLOAT base are exact repeated multiplications, and an INT64 base raised to a
+/// non-negative INT64 exponent wraps around on overflow. Otherwise the power is computed in
+/// double precision (and truncated toward zero for an INT64 base).
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X` and `Y`, each FLOAT (or FLOAT16) or INT64.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the powers, of the type of `X`. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let cubed = pow(&[&x, &three], &node)?;
+/// ```
+pub fn pow(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let [x, y] = match inputs {
+        [x, y] => [*x, *y],
+        _ => {
+            return Err(OnnxError::MissingInput(format!(
+                "Pow expects the tensors X and Y, got {} inputs",
+                inputs.len()
+            )))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let int64 = |tensor: &TensorProto| tensor.get_data_type() == TensorProto_DataType::INT64 as i32;
+    match (int64(x), int64(y)) {
+        (false, false) => {
+            let x = tensor_proto_to_ndarray::<f32>(x)?;
+            let y = tensor_proto_to_ndarray::<f32>(y)?;
+            let result = broadcast_map(&x, &y, |x, &y| x.powf(y))?;
+            ndarray_to_tensor_proto::<f32>(result, output_name)
+        }
+        (false, true) => {
+            let x = tensor_proto_to_ndarray::<f32>(x)?;
+            let y = tensor_proto_to_ndarray::<i64>(y)?;
+            let result = broadcast_map(&x, &y, |&x, &y| match i32::try_from(y) {
+                Ok(y) => x.powi(y),
+                Err(_) => (x as f64).powf(y as f64) as f32,
+            })?;
+            ndarray_to_tensor_proto::<f32>(result, output_name)
+        }
+        (true, false) => {
+            let x = tensor_proto_to_ndarray::<i64>(x)?;
+            let y = tensor_proto_to_ndarray::<f32>(y)?;
+            let result = broadcast_map(&x, &y, |&x, &y| (x as f64).powf(y as f64) as i64)?;
+            ndarray_to_tensor_proto::<i64>(result, output_name)
+        }
+        (true, true) => {
+            let x = tensor_proto_to_ndarray::<i64>(x)?;
+            let y = tensor_proto_to_ndarray::<i64>(y)?;
+            let result = broadcast_map(&x, &y, |&x, &y| match u32::try_from(y) {
+                Ok(y) => x.wrapping_pow(y),
+                Err(_) => (x as f64).powf(y as f64) as i64,
+            })?;
+            ndarray_to_tensor_proto::<i64>(result, output_name)
+        }
+    }
+}
+
+/// `floor` - ONNX Node Implementation for Floor
+///
+/// The largest integer less than or equal to every element of the input. The arguments, the
+/// output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let cells = floor(&coordinates, &node)?;
+/// ```
+pub fn floor(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::floor, None)
+}
+
+/// `ceil` - ONNX Node Implementation for Ceil
+///
+/// The smallest integer greater than or equal to every element of the input. The arguments, the
+/// output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let output_lengths = ceil(&scaled_lengths, &node)?;
+/// ```
+pub fn ceil(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::ceil, None)
+}
+
+/// `round` - ONNX Node Implementation for Round
+///
+/// The nearest integer to every element of the input, the halves being rounded to the even one
+/// like in QuantizeLinear (0.5 to 0, 1.5 and 2.5 to 2, -2.5 to -2), not away from zero like
+/// `f32::round`. The arguments, the output and the errors are those of `exp`.
+///
+/// # Example
+///
+/// ```rust
+/// let rounded = round(&input_tensor, &node)?;
+/// ```
+pub fn round(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    unary(input, node, f32::round_ties_even, None)
+}
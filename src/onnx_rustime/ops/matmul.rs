@@ -1,6 +1,12 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
-use crate::onnx_rustime::ops::gemm::gemm;
+use crate::onnx_rustime::ops::utils::{
+    broadcast_shapes, convert_to_output_tensor, extract_attributes, get_int_attribute,
+    tensor_proto_to_ndarray,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+use ndarray::LinalgScalar;
 
 /// `matmul` - ONNX Node Implementation for Matrix Multiplication (MatMul) Operation
 ///
@@ -13,16 +19,25 @@ use crate::onnx_rustime::ops::gemm::gemm;
 /// of the two input tensors. Refer to numpy's documentation for further clarity:
 /// [numpy.matmul](https://docs.scipy.org/doc/numpy-1.13.0/reference/generated/numpy.matmul.html)
 ///
-/// Internally, this function leverages the `gemm` (General Matrix Multiply) operation,
-/// which is a more general case for matrix multiplication. `gemm` handles possible biases,
-/// scalar multipliers, and transposition, whereas `matmul` provides a simpler interface for
-/// straightforward matrix multiplication.
+/// * Inputs of rank 2 or more are stacks of matrices in their last two dimensions. The
+///   dimensions before them (the batch dimensions) are broadcast against each other, aligned on
+///   the right, so `[1, 3, 4] x [7, 4, 5]` gives `[7, 3, 5]` and `[2, 3, 4] x [4, 5]` gives
+///   `[2, 3, 5]`.
+/// * A 1-D `A` is a row vector and a 1-D `B` a column vector; the dimension they add is removed
+///   from the output, so `[4] x [4, 5]` gives `[5]`, and `[4] x [4]` a scalar.
+///
+/// The matrix products of the batch are computed in parallel.
+///
+/// A MatMul fused with the Add of a bias by the optimizer (see `fuse_matmul_add`) has a nonzero
+/// `fused_bias` attribute and the bias as third input, added to the last dimension of the
+/// product.
 ///
 /// # Arguments
 ///
-/// * `inputs` - A vector reference containing the two tensors to be multiplied.
+/// * `inputs` - A vector reference containing the two tensors to be multiplied, and the bias of
+///   a fused MatMul.
 /// * `initializers` - An optional vector reference containing additional tensor initializers,
-///   if any. In the current context, these initializers are directly forwarded to the `gemm` function.
+///   appended to the inputs (B is usually a weight).
 /// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
 ///
 /// # Returns
@@ -32,27 +47,160 @@ use crate::onnx_rustime::ops::gemm::gemm;
 ///
 /// # Errors
 ///
-/// Possible errors include any that might arise from the underlying `gemm` function, such as:
-/// * Failed extraction of node attributes.
-/// * Dimension mismatches during matrix multiplication.
-/// * Other multiplication-specific errors.
+/// Possible errors include:
+/// * Missing or scalar inputs.
+/// * Mismatching inner dimensions, or batch dimensions that can't be broadcast together.
 ///
 /// # Example
 ///
 /// ```rust
 /// let result_tensor = matmul(&input_tensors, Some(&initializers), &node);
 /// ```
-///
-/// # Note
-///
-/// While `matmul` provides a simple interface tailored for matrix multiplication, the
-/// underlying mechanics are powered by the more robust `gemm` function. This design ensures
-/// optimization and support for more advanced features without complicating the basic matrix
-/// multiplication use case.
 pub fn matmul(
     inputs: &Vec<&TensorProto>,
     initializers: Option<&Vec<&TensorProto>>,
     node: &NodeProto,
 ) -> Result<TensorProto, OnnxError> {
-    gemm(inputs, initializers, node)
+    let mut operands = inputs.iter().chain(initializers.into_iter().flatten());
+    let a = operands
+        .next()
+        .ok_or(OnnxError::MissingInput("A".to_string()))?;
+    let b = operands
+        .next()
+        .ok_or(OnnxError::MissingInput("B".to_string()))?;
+
+    let bias = operands
+        .next()
+        .map(|bias| tensor_proto_to_ndarray::<f32>(bias))
+        .transpose()?;
+    let result = matmul_ndarray(
+        &tensor_proto_to_ndarray::<f32>(a)?,
+        &tensor_proto_to_ndarray::<f32>(b)?,
+        bias.as_ref(),
+        node,
+    )?;
+
+    convert_to_output_tensor(node, result)
+}
+
+/// `matmul` on the decoded `A`, `B` and bias, which are only borrowed: the executor keeps the
+/// weights of a model as arrays for all its runs.
+pub(crate) fn matmul_ndarray(
+    a: &ArrayD<f32>,
+    b: &ArrayD<f32>,
+    bias: Option<&ArrayD<f32>>,
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
+    add_fused_bias(batched_matmul(a, b)?, bias, node)
+}
+
+/// Adds the bias of a fused MatMul (a node with a nonzero `fused_bias` attribute) to the last
+/// dimension of its product. The product of the other nodes is returned as is.
+pub(crate) fn add_fused_bias(
+    mut product: ArrayD<f32>,
+    bias: Option<&ArrayD<f32>>,
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    if get_int_attribute(&attributes, "fused_bias", Some(0))? == 0 {
+        return Ok(product);
+    }
+
+    let bias = bias.ok_or(OnnxError::MissingInput("bias".to_string()))?;
+    if bias.ndim() != 1 || product.shape().last() != Some(&bias.len()) {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Can't add a bias of shape {:?} to a product of shape {:?}",
+            bias.shape(),
+            product.shape()
+        )));
+    }
+    product += bias;
+
+    Ok(product)
+}
+
+/// Multiplies two tensors with the semantics of numpy.matmul (see `matmul`), for any element type
+/// (`matmul_integer` multiplies i32 tensors).
+pub(crate) fn batched_matmul<T: LinalgScalar + Send + Sync>(
+    a: &ArrayD<T>,
+    b: &ArrayD<T>,
+) -> Result<ArrayD<T>, OnnxError> {
+    let mismatch = || {
+        OnnxError::shape_mismatch(format!(
+            "Can't multiply A of shape {:?} by B of shape {:?}",
+            a.shape(),
+            b.shape()
+        ))
+    };
+    if a.ndim() == 0 || b.ndim() == 0 {
+        return Err(mismatch());
+    }
+
+    // Promote the vectors to matrices.
+    let (a_vector, b_vector) = (a.ndim() == 1, b.ndim() == 1);
+    let a = if a_vector {
+        a.view().insert_axis(Axis(0))
+    } else {
+        a.view()
+    };
+    let b = if b_vector {
+        b.view().insert_axis(Axis(1))
+    } else {
+        b.view()
+    };
+
+    let (rows, inner) = (a.shape()[a.ndim() - 2], a.shape()[a.ndim() - 1]);
+    let columns = b.shape()[b.ndim() - 1];
+    if b.shape()[b.ndim() - 2] != inner {
+        return Err(mismatch());
+    }
+    let batch = broadcast_shapes(&a.shape()[..a.ndim() - 2], &b.shape()[..b.ndim() - 2])
+        .map_err(|_| mismatch())?;
+
+    let batch_len = batch.iter().product::<usize>();
+    let mut result = Array3::<T>::zeros((batch_len, rows, columns));
+    result
+        .outer_iter_mut()
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(index, mut product)| {
+            // The batch index, from the last dimension.
+            let mut batch_index = vec![0; batch.len()];
+            let mut rest = index;
+            for (i, &dim) in batch.iter().enumerate().rev() {
+                batch_index[i] = rest % dim;
+                rest /= dim;
+            }
+            product.assign(&matrix_at(&a, &batch_index).dot(&matrix_at(&b, &batch_index)));
+        });
+
+    // Remove the dimensions added to the vectors.
+    let mut shape = batch;
+    if !a_vector {
+        shape.push(rows);
+    }
+    if !b_vector {
+        shape.push(columns);
+    }
+    result
+        .into_shape(shape)
+        .map_err(|e| OnnxError::ShapeError(format!("Failed to create output tensor: {}", e)))
+}
+
+/// The matrix of a stack at a broadcast batch index: the batch dimensions of size 1 are
+/// repeated, and the leading dimensions the stack doesn't have are ignored.
+fn matrix_at<'a, T>(stack: &ArrayViewD<'a, T>, batch_index: &[usize]) -> ArrayView2<'a, T> {
+    let batch_rank = stack.ndim() - 2;
+    let offset = batch_index.len() - batch_rank;
+    let mut matrix = stack.clone();
+    for axis in 0..batch_rank {
+        let index = match stack.shape()[axis] {
+            1 => 0,
+            _ => batch_index[offset + axis],
+        };
+        matrix = matrix.index_axis_move(Axis(0), index);
+    }
+    matrix
+        .into_dimensionality()
+        .expect("The last two dimensions of a stack are a matrix")
 }
@@ -0,0 +1,121 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::matmul::batched_matmul;
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use ndarray::prelude::*;
+
+/// `matmul_integer` - ONNX Node Implementation for Integer Matrix Multiplication (MatMulInteger)
+///
+/// Multiplies two quantized tensors with the semantics of `matmul` (batch dimensions broadcast,
+/// 1-D operands as vectors), after subtracting their zero points:
+///
+/// `Y = (A - a_zero_point) * (B - b_zero_point)`
+///
+/// `A` and `B` are UINT8 or INT8 tensors, widened to INT32 before the subtraction, so the
+/// differences and the accumulated products can't overflow their 8 bits. The output is INT32.
+///
+/// * A zero point with a single element applies to the whole tensor.
+/// * A 1-D `a_zero_point` of M elements applies to the rows of `A` (of shape `[..., M, K]`), and a
+///   1-D `b_zero_point` of N elements to the columns of `B` (of shape `[..., K, N]`).
+/// * Other shapes are broadcast to the shape of their operand, e.g. `[..., M, 1]` for
+///   `a_zero_point`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `A`, `B` and the optional `a_zero_point` and `b_zero_point`, by
+///   position among the node inputs (`None` for an omitted optional input). A zero point has the
+///   element type of its operand.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT32 product. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `A` or `B`, or operands that aren't UINT8 or INT8.
+/// * A zero point of another element type than its operand, or that can't be broadcast to it.
+/// * The shape errors of `matmul`.
+///
+/// # Example
+///
+/// ```rust
+/// let result_tensor = matmul_integer(&[Some(&a), Some(&b), None, Some(&b_zero_point)], &node);
+/// ```
+pub fn matmul_integer(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let a = input(0).ok_or(OnnxError::MissingInput("A".to_string()))?;
+    let b = input(1).ok_or(OnnxError::MissingInput("B".to_string()))?;
+
+    let a = subtract_zero_point(a, input(2), "A", Axis(1))?;
+    let b = subtract_zero_point(b, input(3), "B", Axis(0))?;
+    let result = batched_matmul(&a, &b)?;
+
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    ndarray_to_tensor_proto::<i32>(result, output_name)
+}
+
+/// Widens a quantized operand to i32 and subtracts its zero point, if any. A 1-D zero point of
+/// several elements gets the dimension `vector_axis`, to apply to the rows of `A` (`Axis(1)`) or
+/// the columns of `B` (`Axis(0)`).
+fn subtract_zero_point(
+    operand: &TensorProto,
+    zero_point: Option<&TensorProto>,
+    name: &str,
+    vector_axis: Axis,
+) -> Result<ArrayD<i32>, OnnxError> {
+    let values = widen(operand, name)?;
+    let zero_point = match zero_point {
+        Some(zero_point) if zero_point.get_data_type() != operand.get_data_type() => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The zero point of {} must have its element type",
+                name
+            )));
+        }
+        Some(zero_point) => widen(zero_point, name)?,
+        None => return Ok(values),
+    };
+
+    if zero_point.len() == 1 {
+        let zero_point = zero_point.iter().next().copied().unwrap_or(0);
+        return Ok(values.mapv(|value| value - zero_point));
+    }
+    let zero_point = match zero_point.ndim() {
+        1 => zero_point.insert_axis(vector_axis),
+        _ => zero_point,
+    };
+    let broadcast = zero_point.broadcast(values.shape()).ok_or_else(|| {
+        OnnxError::shape_mismatch(format!(
+            "Can't broadcast the zero point of shape {:?} to {} of shape {:?}",
+            zero_point.shape(),
+            name,
+            values.shape()
+        ))
+    })?;
+    Ok(&values - &broadcast)
+}
+
+/// The values of a UINT8 or INT8 tensor, as i32.
+pub(crate) fn widen(tensor: &TensorProto, name: &str) -> Result<ArrayD<i32>, OnnxError> {
+    let data_type = tensor.get_data_type();
+    if data_type == TensorProto_DataType::UINT8 as i32 {
+        Ok(tensor_proto_to_ndarray::<u8>(tensor)?.mapv(i32::from))
+    } else if data_type == TensorProto_DataType::INT8 as i32 {
+        Ok(tensor_proto_to_ndarray::<i8>(tensor)?.mapv(i32::from))
+    } else {
+        Err(OnnxError::UnsupportedOperation(format!(
+            "Expected a UINT8 or INT8 tensor for {}, got data type {}",
+            name, data_type
+        )))
+    }
+}
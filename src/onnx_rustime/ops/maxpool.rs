@@ -1,11 +1,11 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, get_int_attribute, get_ints_attribute,
-    pad_matrix_2d, tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, get_int_attribute, ndarray_to_tensor_proto,
+    pool_geometry, tensor_proto_to_ndarray, PoolAxis,
 };
+use ndarray::parallel::prelude::*;
 use ndarray::prelude::*;
-use rayon::prelude::*;
 
 /// `maxpool` - ONNX Node Implementation for Maximum Pooling Operation
 ///
@@ -16,9 +16,16 @@ use rayon::prelude::*;
 /// is commonly used in Convolutional Neural Networks (CNNs) to reduce spatial dimensions and introduce
 /// spatial invariances.
 ///
-/// The calculation of the output spatial shape differs based on whether explicit padding (defined by `pads`)
-/// or auto padding (defined by the now DEPRECATED `auto_pad` attribute) is employed. The operation's behavior
-/// can also be influenced by the `ceil_mode` attribute.
+/// The output spatial shape follows `pool_geometry`: the windows are defined by `kernel_shape`,
+/// `strides`, `dilations` and `pads` (or `auto_pad`), along any number of spatial axes. With
+/// `ceil_mode`, the number of windows is rounded up, the last windows being clipped to the input;
+/// padded elements never win.
+///
+/// When the node has a second output (`Indices`, e.g. for a following `MaxUnpool`), it receives
+/// the position of every maximum in the flattened input, as INT64. With `storage_order` 0 (the
+/// default) the positions are in row-major order, with 1 the spatial axes are in column-major
+/// order (the batch and channel offsets staying the same). Ties go to the first position in
+/// row-major order.
 ///
 /// Detailed computation equations and descriptions can be found in the official documentation:
 /// [MaxPool Official ONNX Docs](https://github.com/onnx/onnx/blob/master/docs/Operators.md#MaxPool).
@@ -30,127 +37,174 @@ use rayon::prelude::*;
 ///
 /// # Returns
 ///
-/// * `Result<TensorProto, OnnxError>` - Outputs the tensor after max pooling. In case of
-///   an unsuccessful operation, it returns an error (`OnnxError`).
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the tensor after max pooling, followed by the
+///   indices if the node requests them. In case of an unsuccessful operation, it returns an error
+///   (`OnnxError`).
 ///
 /// # Errors
 ///
 /// Possible errors include, but are not limited to:
-/// * Failure in extracting node attributes.
+/// * Failure in extracting node attributes, or a missing `kernel_shape`.
 /// * Mismatch in tensor dimensions for pooling.
 /// * Inconsistencies in kernel and stride shapes.
 ///
 /// # Example
 ///
 /// ```rust
-/// let result_tensor = maxpool(&input_tensor, &node);
+/// let outputs = maxpool(&input_tensor, &node)?;
+/// let (pooled, indices) = (&outputs[0], outputs.get(1));
 /// ```
-///
-/// # Note
-///
-/// The internal behavior of the function (such as calculations for output shape) is
-/// largely determined by various attributes like `kernel_shape`, `pads`, and `strides`.
-/// Additional attributes like `ceil_mode` and `storage_order` are extracted but not currently
-/// utilized. While `auto_pad` is mentioned in the official documentation, it is marked as
-/// DEPRECATED, and its usage should be avoided in modern implementations.
-pub fn maxpool(inputs: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
-    let attributes = extract_attributes(node.get_attribute())?;
-
-    // 2D-maxpool kernel and stride are always 2D
-    let kernel_shape = get_ints_attribute(&attributes, "kernel_shape", Some(vec![1, 1]))?;
-    let pads = get_ints_attribute(&attributes, "pads", Some(vec![0, 0, 0, 0]))?;
-    let strides = get_ints_attribute(&attributes, "strides", Some(vec![1, 1]))?;
+pub fn maxpool(inputs: &TensorProto, node: &NodeProto) -> Result<Vec<TensorProto>, OnnxError> {
+    let inputs_nd_array = tensor_proto_to_ndarray::<f32>(inputs)?;
+    let (result, indices) = maxpool_ndarray(&inputs_nd_array, node)?;
 
-    // TODO: ceil_mode storage_order and dilations are not used
-    let _ceil_mode = get_int_attribute(&attributes, "ceil_mode", Some(0))?;
-    let _storage_order = get_int_attribute(&attributes, "storage_order", Some(0))?;
-    let _dilations = get_ints_attribute(&attributes, "dilations", Some(vec![1, 1]))?;
+    let mut outputs = vec![convert_to_output_tensor(node, result)?];
+    if let Some(name) = node.get_output().get(1).filter(|name| !name.is_empty()) {
+        outputs.push(ndarray_to_tensor_proto::<i64>(indices, name)?);
+    }
+    Ok(outputs)
+}
 
-    let inputs_nd_array = tensor_proto_to_ndarray::<f32>(inputs)?;
+/// `maxpool` on a decoded input. Returns the maxima and their indices.
+pub(crate) fn maxpool_ndarray(
+    inputs_nd_array: &ArrayD<f32>,
+    node: &NodeProto,
+) -> Result<(ArrayD<f32>, ArrayD<i64>), OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let storage_order = get_int_attribute(&attributes, "storage_order", Some(0))?;
+    if storage_order != 0 && storage_order != 1 {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected a storage_order of 0 or 1, got {}",
+            storage_order
+        )));
+    }
 
-    let result = pool(&inputs_nd_array, &kernel_shape, &pads, &strides)?;
+    if inputs_nd_array.ndim() < 3 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected an input with spatial axes, got shape {:?}",
+            inputs_nd_array.shape()
+        )));
+    }
 
-    convert_to_output_tensor(node, result)
+    let geometry = pool_geometry(&attributes, &inputs_nd_array.shape()[2..])?;
+    pool(inputs_nd_array, &geometry, storage_order == 1)
 }
 
+/// Max pooling of an `[N, C, D1, ..., Dn]` input, in parallel across the batch and the
+/// channels. Returns the maxima and their positions in the flattened input.
 fn pool(
     input_matrix: &ArrayD<f32>,
-    kernel_shape: &Vec<i64>,
-    pads: &Vec<i64>,
-    strides: &Vec<i64>,
-) -> Result<ArrayD<f32>, OnnxError> {
-    // Choose the indices of dimensions to extract
-    let batch_size = input_matrix.shape()[0];
-    let channels = input_matrix.shape()[1];
-
-    // Extract kernel shape and strides
-    let kernel_height = kernel_shape[0] as usize;
-    let kernel_width = kernel_shape[1] as usize;
-    let stride_height = strides[0] as usize;
-    let stride_width = strides[1] as usize;
-
-    let mut pooled_results = Vec::new();
-
-    for b in 0..batch_size {
-        for c in 0..channels {
-            // Extract the height-width input matrix
-            let h_w_matrix = input_matrix
-                .index_axis(Axis(0), b)
-                .index_axis(Axis(0), c)
-                .to_owned()
-                .into_shape((input_matrix.shape()[2], input_matrix.shape()[3]))
-                .unwrap();
-
-            // Apply padding to the height-width input matrix
-            let padded_matrix = pad_matrix_2d(&h_w_matrix, &pads)?;
-
-            // Extract the dimensions of the padded matrix
-            let (padded_rows, padded_cols) = padded_matrix.dim();
-
-            // Calculate output dimensions
-            let output_rows = (padded_rows - kernel_height) / stride_height + 1;
-            let output_cols = (padded_cols - kernel_width) / stride_width + 1;
-
-            // Parallelize the pooling operation
-            let pooled_matrix = (0..output_rows)
-                .into_par_iter()
-                .map(|i| {
-                    let mut row = Vec::with_capacity(output_cols);
-
-                    for j in 0..output_cols {
-                        let start_row = i * stride_height;
-                        let start_col = j * stride_width;
-                        let end_row = start_row + kernel_height;
-                        let end_col = start_col + kernel_width;
-
-                        // Extract the corresponding patch from the padded matrix
-                        let patch = padded_matrix.slice(s![start_row..end_row, start_col..end_col]);
-
-                        // Calculate the max value within the patch
-                        let max_value = patch.fold(std::f32::NEG_INFINITY, |acc, &x| acc.max(x));
-
-                        row.push(max_value);
-                    }
-
-                    row
+    geometry: &[PoolAxis],
+    column_major: bool,
+) -> Result<(ArrayD<f32>, ArrayD<i64>), OnnxError> {
+    let spatial_shape = &input_matrix.shape()[2..];
+    let spatial_len: usize = spatial_shape.iter().product();
+
+    // The input positions read by every window, along every spatial axis.
+    let windows: Vec<Vec<Vec<usize>>> = geometry
+        .iter()
+        .zip(spatial_shape)
+        .map(|(axis, &len)| {
+            (0..axis.output_len)
+                .map(|window| {
+                    axis.taps(window)
+                        .filter(|&position| (0..len as isize).contains(&position))
+                        .map(|position| position as usize)
+                        .collect()
                 })
-                .collect::<Vec<_>>();
-
-            pooled_results.push(pooled_matrix);
+                .collect()
+        })
+        .collect();
+
+    // Offset of a step along every spatial axis in the flattened indices.
+    let mut index_strides = vec![1; spatial_shape.len()];
+    if column_major {
+        for axis in 1..spatial_shape.len() {
+            index_strides[axis] = index_strides[axis - 1] * spatial_shape[axis - 1];
+        }
+    } else {
+        for axis in (0..spatial_shape.len().saturating_sub(1)).rev() {
+            index_strides[axis] = index_strides[axis + 1] * spatial_shape[axis + 1];
         }
     }
 
-    // Convert the collected rows into an ArrayD
-    let pooled_tensor = ArrayD::from_shape_vec(
-        IxDyn(&[
-            batch_size,
-            channels,
-            pooled_results[0].len(),
-            pooled_results[0][0].len(),
-        ]),
-        pooled_results.into_iter().flatten().flatten().collect(),
-    )
-    .map_err(|_| OnnxError::ShapeError("Failed to create output tensor".to_string()));
+    let (batch_size, channels) = (input_matrix.shape()[0], input_matrix.shape()[1]);
+    let mut output_shape = vec![batch_size * channels];
+    output_shape.extend(geometry.iter().map(|axis| axis.output_len));
+    let mut maxima = ArrayD::from_elem(output_shape.clone(), f32::NEG_INFINITY);
+    let mut indices = ArrayD::<i64>::zeros(output_shape);
+
+    let lanes = input_matrix
+        .view()
+        .into_shape(
+            std::iter::once(batch_size * channels)
+                .chain(spatial_shape.iter().copied())
+                .collect::<Vec<_>>(),
+        )
+        .map_err(|_| OnnxError::ShapeError("Failed to flatten the channels".to_string()))?;
+
+    // Parallelize the pooling operation
+    maxima
+        .outer_iter_mut()
+        .into_par_iter()
+        .zip(indices.outer_iter_mut())
+        .zip(lanes.outer_iter())
+        .enumerate()
+        .for_each(|(lane, ((mut maxima, mut indices), input))| {
+            let offset = (lane * spatial_len) as i64;
+            // Reused by every window of the lane.
+            let mut axis_windows: Vec<&[usize]> = Vec::with_capacity(windows.len());
+            let mut tap = vec![0; windows.len()];
+            let mut position = vec![0; windows.len()];
+            for (output, max) in maxima.indexed_iter_mut() {
+                axis_windows.clear();
+                axis_windows.extend(
+                    windows
+                        .iter()
+                        .enumerate()
+                        .map(|(axis, windows)| windows[output[axis]].as_slice()),
+                );
+
+                // Visit the positions of the window in row-major order.
+                tap.fill(0);
+                let mut first = true;
+                'window: loop {
+                    if axis_windows.iter().any(|window| window.is_empty()) {
+                        break;
+                    }
+                    for (axis, window) in axis_windows.iter().enumerate() {
+                        position[axis] = window[tap[axis]];
+                    }
+                    let value = input[position.as_slice()];
+                    if first || value > *max {
+                        first = false;
+                        *max = value;
+                        indices[output.slice()] = offset
+                            + position
+                                .iter()
+                                .zip(&index_strides)
+                                .map(|(&position, &stride)| (position * stride) as i64)
+                                .sum::<i64>();
+                    }
 
-    pooled_tensor
+                    for axis in (0..tap.len()).rev() {
+                        tap[axis] += 1;
+                        if tap[axis] < axis_windows[axis].len() {
+                            continue 'window;
+                        }
+                        tap[axis] = 0;
+                    }
+                    break;
+                }
+            }
+        });
+
+    let mut result_shape = vec![batch_size, channels];
+    result_shape.extend(geometry.iter().map(|axis| axis.output_len));
+    let reshape =
+        |error| OnnxError::ShapeError(format!("Failed to create output tensor: {}", error));
+    Ok((
+        maxima.into_shape(result_shape.clone()).map_err(reshape)?,
+        indices.into_shape(result_shape).map_err(reshape)?,
+    ))
 }
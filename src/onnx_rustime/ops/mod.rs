@@ -1,35 +1,140 @@
 pub mod add;
+pub mod argmax;
+pub mod arithmetic;
+pub mod average_pool;
 pub mod batch_normalization;
+pub mod cast;
+pub mod clip;
+pub mod compare;
+pub mod compress;
 pub mod concat;
+pub mod constant;
 pub mod conv;
+pub mod conv_transpose;
+pub mod cum_sum;
+pub mod depth_to_space;
+pub mod dequantize_linear;
 pub mod dropout;
-pub mod exp;
+pub mod dynamic_quantize_linear;
+pub mod einsum;
+pub mod erf;
+pub mod expand;
+pub mod eye_like;
 pub mod flatten;
+pub mod gather;
+pub mod gelu;
 pub mod gemm;
 pub mod global_average_pool;
+pub mod global_lp_pool;
+pub mod gru;
+pub mod hard_sigmoid;
+pub mod hardmax;
+pub mod identity;
+pub mod layer_normalization;
+pub mod log_softmax;
+pub mod logical;
+pub mod lp_pool;
 pub mod lrn;
+pub mod lstm;
+pub mod math;
 pub mod matmul;
+pub mod matmul_integer;
 pub mod maxpool;
-pub mod reduce_sum;
+pub mod non_max_suppression;
+pub mod one_hot;
+pub mod pad;
+pub mod qlinear_conv;
+pub mod quantize_linear;
+pub mod random;
+pub mod recurrent;
+pub mod reduce;
+pub mod reference;
 pub mod relu;
 pub mod reshape;
+pub mod resize;
+pub mod rnn;
+pub mod roi_align;
+pub mod scatter;
+pub mod shape;
+pub mod slice;
 pub mod softmax;
+pub mod softplus;
+pub mod split;
+pub mod squeeze;
+pub mod tile;
+pub mod top_k;
+pub mod transpose;
+pub mod trilu;
 pub mod utils;
+pub mod where_op;
 
 // Re-export functions
 pub use add::add;
+pub use argmax::{argmax, argmin};
+pub use arithmetic::{div, max, mean, min, modulo, mul, sub, sum};
+pub use average_pool::average_pool;
 pub use batch_normalization::batch_normalization;
+pub use cast::{cast, cast_like};
+pub use clip::clip;
+pub use compare::{equal, greater, greater_or_equal, less, less_or_equal};
+pub use compress::compress;
 pub use concat::concat;
+pub use constant::{constant, constant_of_shape};
 pub use conv::conv;
+pub use conv_transpose::conv_transpose;
+pub use cum_sum::cum_sum;
+pub use depth_to_space::{depth_to_space, space_to_depth};
+pub use dequantize_linear::dequantize_linear;
 pub use dropout::dropout;
-pub use exp::exp;
+pub use dynamic_quantize_linear::dynamic_quantize_linear;
+pub use einsum::einsum;
+pub use erf::erf;
+pub use expand::expand;
+pub use eye_like::eye_like;
 pub use flatten::flatten;
+pub use gather::{gather, gather_elements, gather_nd};
+pub use gelu::gelu;
 pub use gemm::gemm;
 pub use global_average_pool::global_average_pool;
+pub use global_lp_pool::global_lp_pool;
+pub use gru::gru;
+pub use hard_sigmoid::{hard_sigmoid, hard_swish};
+pub use hardmax::hardmax;
+pub use identity::identity;
+pub use layer_normalization::layer_normalization;
+pub use log_softmax::log_softmax;
+pub use logical::{and, not, or, xor};
+pub use lp_pool::lp_pool;
 pub use lrn::lrn;
+pub use lstm::lstm;
+pub use math::{abs, ceil, exp, floor, neg, pow, reciprocal, round, sign, sqrt};
 pub use matmul::matmul;
+pub use matmul_integer::matmul_integer;
 pub use maxpool::maxpool;
-pub use reduce_sum::reduce_sum;
-pub use relu::relu;
+pub use non_max_suppression::non_max_suppression;
+pub use one_hot::one_hot;
+pub use pad::pad;
+pub use qlinear_conv::qlinear_conv;
+pub use quantize_linear::quantize_linear;
+pub use random::{random_normal, random_normal_like, random_uniform, random_uniform_like};
+pub use reduce::{
+    reduce_l1, reduce_l2, reduce_log_sum, reduce_log_sum_exp, reduce_max, reduce_mean, reduce_min,
+    reduce_prod, reduce_sum, reduce_sum_square,
+};
+pub use relu::{elu, leaky_relu, prelu, relu, selu};
 pub use reshape::reshape;
+pub use resize::{resize, upsample};
+pub use rnn::rnn;
+pub use roi_align::roi_align;
+pub use scatter::{scatter_elements, scatter_nd};
+pub use shape::{shape, size};
+pub use slice::slice;
 pub use softmax::softmax;
+pub use softplus::{mish, softplus, softsign};
+pub use split::split;
+pub use squeeze::{squeeze, unsqueeze};
+pub use tile::tile;
+pub use top_k::top_k;
+pub use transpose::transpose;
+pub use trilu::trilu;
+pub use where_op::where_op;
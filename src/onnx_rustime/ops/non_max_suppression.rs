@@ -0,0 +1,205 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+    TensorType,
+};
+use ndarray::{Array2, ArrayD, ArrayView1, Axis, Ix3};
+
+/// The value of an optional single-value input, `None` if it is omitted.
+fn single_value<T: TensorType>(
+    input: Option<&TensorProto>,
+    name: &str,
+) -> Result<Option<T::DataType>, OnnxError> {
+    let input = match input {
+        Some(input) => tensor_proto_to_ndarray::<T>(input)?,
+        None => return Ok(None),
+    };
+    match input.iter().next() {
+        Some(value) if input.len() == 1 => Ok(Some(value.clone())),
+        _ => Err(OnnxError::InvalidValue(format!(
+            "The {} of NonMaxSuppression must be a single value, got a tensor of shape {:?}",
+            name,
+            input.shape()
+        ))),
+    }
+}
+
+/// The corners `[y_min, x_min, y_max, x_max]` of a box given as `[y1, x1, y2, x2]` (any two
+/// opposite corners), or as `[x_center, y_center, width, height]` with `center_point_box`.
+fn corners(coordinates: ArrayView1<'_, f32>, center_point_box: bool) -> [f32; 4] {
+    let c = |i: usize| coordinates[i];
+    match center_point_box {
+        false => [
+            c(0).min(c(2)),
+            c(1).min(c(3)),
+            c(0).max(c(2)),
+            c(1).max(c(3)),
+        ],
+        true => [
+            c(1) - c(3) / 2.0,
+            c(0) - c(2) / 2.0,
+            c(1) + c(3) / 2.0,
+            c(0) + c(2) / 2.0,
+        ],
+    }
+}
+
+/// The intersection over union of two boxes, 0 if either of them is empty.
+fn intersection_over_union(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let area = |c: &[f32; 4]| (c[2] - c[0]) * (c[3] - c[1]);
+    let (area_a, area_b) = (area(a), area(b));
+    if area_a <= 0.0 || area_b <= 0.0 {
+        return 0.0;
+    }
+    let height = a[2].min(b[2]) - a[0].max(b[0]);
+    let width = a[3].min(b[3]) - a[1].max(b[1]);
+    if height <= 0.0 || width <= 0.0 {
+        return 0.0;
+    }
+    let intersection = height * width;
+    intersection / (area_a + area_b - intersection)
+}
+
+/// `non_max_suppression` - ONNX Node Implementation for NonMaxSuppression
+///
+/// Selects the boxes of every class of every batch, e.g. the detections of SSD or YOLO, dropping
+/// the ones overlapping a better one. The boxes scoring above `score_threshold` are taken from the
+/// best score on (of equal scores, the smaller index first), and a box is selected unless its
+/// intersection over union with a box already selected for the class is above `iou_threshold`.
+/// At most `max_output_boxes_per_class` boxes are selected per class.
+///
+/// # Attributes
+///
+/// - `center_point_box` (int): 0 (default) for boxes given as two opposite corners
+///   `[y1, x1, y2, x2]`, as in TensorFlow; 1 for boxes given as `[x_center, y_center, width,
+///   height]`, as in PyTorch.
+///
+/// # Arguments
+///
+/// * `inputs` - The FLOAT tensors `boxes` (`[num_batches, num_boxes, 4]`) and `scores`
+///   (`[num_batches, num_classes, num_boxes]`), and the optional single values
+///   `max_output_boxes_per_class` (INT64, 0 by default: no box is selected), `iou_threshold`
+///   (FLOAT in `[0, 1]`, 0 by default) and `score_threshold` (FLOAT, no threshold by default), by
+///   position among the node inputs (`None` for an omitted optional input).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT64 tensor `selected_indices`
+///   (`[num_selected, 3]`) of the `[batch, class, box]` indices of the selected boxes, by batch,
+///   then by class, then from the best score on. In case of an unsuccessful operation, it
+///   returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `boxes` or `scores`, or shapes that don't match.
+/// * Thresholds or a maximum number of boxes that aren't single values, or an `iou_threshold`
+///   out of `[0, 1]`.
+/// * A `center_point_box` other than 0 or 1.
+///
+/// # Example
+///
+/// ```rust
+/// let selected = non_max_suppression(
+///     &[Some(&boxes), Some(&scores), Some(&max_boxes), Some(&iou), Some(&score)],
+///     &node,
+/// )?;
+/// ```
+pub fn non_max_suppression(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let boxes = input(0).ok_or(OnnxError::MissingInput("boxes".to_string()))?;
+    let scores = input(1).ok_or(OnnxError::MissingInput("scores".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let center_point_box = match get_int_attribute(&attributes, "center_point_box", Some(0))? {
+        0 => false,
+        1 => true,
+        other => {
+            return Err(OnnxError::InvalidAttribute {
+                name: "center_point_box".to_string(),
+                detail: format!("expected 0 or 1, got {}", other),
+            })
+        }
+    };
+    let max_boxes = single_value::<i64>(input(2), "max_output_boxes_per_class")?.unwrap_or(0);
+    let iou_threshold = single_value::<f32>(input(3), "iou_threshold")?.unwrap_or(0.0);
+    let score_threshold = single_value::<f32>(input(4), "score_threshold")?;
+    if !(0.0..=1.0).contains(&iou_threshold) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The iou_threshold of NonMaxSuppression must be between 0 and 1, got {}",
+            iou_threshold
+        )));
+    }
+
+    let boxes = tensor_proto_to_ndarray::<f32>(boxes)?;
+    let scores = tensor_proto_to_ndarray::<f32>(scores)?;
+    let (box_shape, score_shape) = (boxes.shape(), scores.shape());
+    if box_shape.len() != 3
+        || box_shape[2] != 4
+        || score_shape.len() != 3
+        || score_shape[0] != box_shape[0]
+        || score_shape[2] != box_shape[1]
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "NonMaxSuppression expects boxes of shape [num_batches, num_boxes, 4] and scores of \
+             shape [num_batches, num_classes, num_boxes], got {:?} and {:?}",
+            box_shape, score_shape
+        )));
+    }
+
+    let three_dimensional = |array: ArrayD<f32>| {
+        array.into_dimensionality::<Ix3>().map_err(|e| {
+            OnnxError::ShapeError(format!("Failed to read the boxes and scores: {}", e))
+        })
+    };
+    let (boxes, scores) = (three_dimensional(boxes)?, three_dimensional(scores)?);
+
+    let mut selected_indices: Vec<i64> = Vec::new();
+    for (batch, (boxes, scores)) in boxes
+        .axis_iter(Axis(0))
+        .zip(scores.axis_iter(Axis(0)))
+        .enumerate()
+    {
+        let corners: Vec<[f32; 4]> = boxes
+            .outer_iter()
+            .map(|coordinates| corners(coordinates, center_point_box))
+            .collect();
+
+        for (class, scores) in scores.outer_iter().enumerate() {
+            let mut candidates: Vec<usize> = (0..scores.len())
+                .filter(|&i| score_threshold.is_none_or(|threshold| scores[i] > threshold))
+                .collect();
+            // The sort is stable: the ties keep the order of their indices.
+            candidates.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+
+            let mut selected: Vec<usize> = Vec::new();
+            for candidate in candidates {
+                if selected.len() as i64 >= max_boxes {
+                    break;
+                }
+                if selected.iter().all(|&other| {
+                    intersection_over_union(&corners[candidate], &corners[other]) <= iou_threshold
+                }) {
+                    selected.push(candidate);
+                }
+            }
+            for index in selected {
+                selected_indices.extend([batch as i64, class as i64, index as i64]);
+            }
+        }
+    }
+
+    let selected_indices =
+        Array2::from_shape_vec((selected_indices.len() / 3, 3), selected_indices)
+            .map_err(|e| OnnxError::ShapeError(format!("Failed to build the indices: {}", e)))?;
+    ndarray_to_tensor_proto::<i64>(selected_indices.into_dyn(), output_name)
+}
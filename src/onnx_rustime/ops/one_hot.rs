@@ -0,0 +1,141 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::gather::gather_indices;
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+    TensorType,
+};
+use ndarray::{ArrayD, Dimension, IxDyn};
+
+/// The values of a numeric tensor as integers, the FLOAT ones being truncated toward zero.
+fn integers(tensor: &TensorProto) -> Result<ArrayD<i64>, OnnxError> {
+    if tensor.get_data_type() == TensorProto_DataType::FLOAT as i32 {
+        Ok(tensor_proto_to_ndarray::<f32>(tensor)?.mapv(|value| value as i64))
+    } else {
+        gather_indices(tensor)
+    }
+}
+
+/// The one-hot encoding of `indices` along `axis` of the output, with the `[off, on]` values of
+/// `values`.
+fn one_hot_as<T: TensorType>(
+    indices: &ArrayD<i64>,
+    depth: usize,
+    axis: usize,
+    values: &TensorProto,
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let values = tensor_proto_to_ndarray::<T>(values)?;
+    let (off, on) = match values.as_slice() {
+        Some([off, on]) => (off.clone(), on.clone()),
+        _ => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The values of a OneHot must be the two values [off, on], got a tensor of shape \
+                 {:?}",
+                values.shape()
+            )))
+        }
+    };
+
+    let mut shape = indices.shape().to_vec();
+    shape.insert(axis, depth);
+    let output = ArrayD::from_shape_fn(IxDyn(&shape), |position| {
+        // The index at the position without the one-hot axis.
+        let mut index_position = position.slice().to_vec();
+        let class = index_position.remove(axis) as i64;
+        let index = indices[IxDyn(&index_position)];
+        match index == class || index + depth as i64 == class {
+            true => on.clone(),
+            false => off.clone(),
+        }
+    });
+    ndarray_to_tensor_proto::<T>(output, name)
+}
+
+/// `one_hot` - ONNX Node Implementation for OneHot
+///
+/// Encodes every index of `indices` as a vector of `depth` values along a new `axis`, the `on`
+/// value at the index and the `off` value elsewhere, e.g. the labels of a classifier or the
+/// tokens of a sequence. Negative indices count from the end, `-1` being `depth - 1`; the indices
+/// out of `[-depth, depth - 1]` are encoded with the `off` value only.
+///
+/// # Attributes
+///
+/// - `axis` (int): The position of the new axis in the output, -1 (the last one) by default.
+///   Negative values count from the end of the output.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `indices` and `depth` (a single value), FLOAT (truncated), INT32 or
+///   INT64, and the tensor `values`, the two values `[off, on]` (FLOAT, INT32 or INT64).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the encoded tensor, of the type of `values` and of
+///   the shape of `indices` with `depth` inserted at `axis`. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, a `depth` that isn't a single positive value, or `values` that aren't two
+///   values.
+/// * An `axis` out of range.
+///
+/// # Example
+///
+/// ```rust
+/// // The labels [1, 0, 2] as the rows of a 3x3 identity.
+/// let one_hot_labels = one_hot(&[&labels, &three, &off_on], &node)?;
+/// ```
+pub fn one_hot(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let indices = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("indices".to_string()))?;
+    let depth = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("depth".to_string()))?;
+    let values = inputs
+        .get(2)
+        .ok_or(OnnxError::MissingInput("values".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let depth = integers(depth)?;
+    let depth = match depth.iter().next() {
+        Some(&value) if depth.len() == 1 && value > 0 => value as usize,
+        _ => {
+            return Err(OnnxError::InvalidValue(format!(
+                "The depth of a OneHot must be a single positive value, got {}",
+                depth
+            )))
+        }
+    };
+    let indices = integers(indices)?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(-1))?;
+    let rank = indices.ndim() as i64 + 1;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for an output of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+
+    match values.get_data_type() {
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            one_hot_as::<i64>(&indices, depth, axis, values, output_name)
+        }
+        t if t == TensorProto_DataType::INT32 as i32 => {
+            one_hot_as::<i32>(&indices, depth, axis, values, output_name)
+        }
+        _ => one_hot_as::<f32>(&indices, depth, axis, values, output_name),
+    }
+}
@@ -0,0 +1,242 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_float_attribute, get_ints_attribute,
+    get_string_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis, IxDyn, Slice};
+
+/// How a Pad fills the elements outside of the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PadMode {
+    /// With the constant value.
+    Constant,
+    /// With the mirror of the input, without repeating the edge: `[1, 2, 3]` is padded as
+    /// `... 3, 2, [1, 2, 3], 2, 1 ...`.
+    Reflect,
+    /// With the value at the edge of the input.
+    Edge,
+    /// With the values at the other end of the input, as if it were periodic.
+    Wrap,
+}
+
+impl PadMode {
+    /// Reads the `mode` attribute of a Pad node, "constant" by default.
+    fn from_node(node: &NodeProto) -> Result<Self, OnnxError> {
+        let attributes = extract_attributes(node.get_attribute())?;
+        match get_string_attribute(&attributes, "mode", Some("constant".to_string()))?.as_str() {
+            "constant" => Ok(PadMode::Constant),
+            "reflect" => Ok(PadMode::Reflect),
+            "edge" => Ok(PadMode::Edge),
+            "wrap" => Ok(PadMode::Wrap),
+            other => Err(OnnxError::InvalidAttribute {
+                name: "mode".to_string(),
+                detail: format!(
+                    "expected \"constant\", \"reflect\", \"edge\" or \"wrap\", got \"{}\"",
+                    other
+                ),
+            }),
+        }
+    }
+
+    /// The index of the input element at `position` along an axis of length `length`, `position`
+    /// being relative to the start of the input (negative before it). `None` for the constant
+    /// value.
+    fn source(self, position: i64, length: usize) -> Option<usize> {
+        let length = length as i64;
+        if (0..length).contains(&position) {
+            return Some(position as usize);
+        }
+        let source = match self {
+            PadMode::Constant => return None,
+            PadMode::Edge => position.clamp(0, length - 1),
+            // The mirrored input repeats every 2 * (length - 1) elements.
+            PadMode::Reflect if length == 1 => 0,
+            PadMode::Reflect => {
+                let period = 2 * (length - 1);
+                let position = position.rem_euclid(period);
+                if position < length {
+                    position
+                } else {
+                    period - position
+                }
+            }
+            PadMode::Wrap => position.rem_euclid(length),
+        };
+        Some(source as usize)
+    }
+}
+
+/// Reads the INT64 `pads` or `axes` input of a Pad, `None` if the node omits it.
+fn int64_values(input: Option<&TensorProto>) -> Result<Option<Vec<i64>>, OnnxError> {
+    input
+        .map(|tensor| Ok(tensor_proto_to_ndarray::<i64>(tensor)?.into_raw_vec()))
+        .transpose()
+}
+
+/// `pad` - ONNX Node Implementation for Pad
+///
+/// Adds `pads[i]` elements before and `pads[i + n]` elements after the input along each of the `n`
+/// padded axes, filled according to `mode`:
+///
+/// * `constant`: with `constant_value` (0 by default);
+/// * `reflect`: with the mirror of the input, without repeating its edge;
+/// * `edge`: with the value at the edge of the input;
+/// * `wrap`: with the values at the other end of the input (since opset 19).
+///
+/// Negative pads remove elements instead: the input is cropped first, and the remaining pads are
+/// filled from the cropped input.
+///
+/// The pads moved between operator sets: before opset 11 they are the `pads` attribute (and the
+/// constant the `value` attribute), since opset 11 the `pads` input, with the optional
+/// `constant_value` and, since opset 18, `axes` inputs.
+///
+/// # Attributes
+///
+/// - `mode` (string): "constant" (default), "reflect", "edge" or "wrap".
+/// - `pads` (ints): The pads of every axis, before opset 11.
+/// - `value` (float): The constant value, before opset 11. 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 11, the INT64 tensor `pads`, the optional scalar
+///   `constant_value` and the optional INT64 tensor `axes` (all the axes by default, negative
+///   values counting from the end), by position among the node inputs (`None` for an omitted
+///   optional input).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the padded tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data` or `pads`, or a `constant_value` that isn't a single value.
+/// * Pads of another length than twice the number of padded axes, or cropping more than an axis.
+/// * Repeated axes, or axes out of range.
+/// * Padding an empty axis in a mode other than `constant`.
+/// * An `OnnxError::InvalidAttribute` for an unknown `mode`.
+///
+/// # Example
+///
+/// ```rust
+/// // Reflect-pads the time axis of a [batch, channels, time] signal by 2 on both sides.
+/// let padded = pad(&[Some(&signal), Some(&pads), None, Some(&time_axis)], &node)?;
+/// ```
+pub fn pad(inputs: &[Option<&TensorProto>], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let data = input(0).ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let data = tensor_proto_to_ndarray::<f32>(data)?;
+    let rank = data.ndim();
+    let mode = PadMode::from_node(node)?;
+
+    let (pads, value, axes) = if inputs.len() > 1 {
+        let pads = int64_values(input(1))?.ok_or(OnnxError::MissingInput("pads".to_string()))?;
+        let value = match input(2) {
+            Some(tensor) => {
+                let values = tensor_proto_to_ndarray::<f32>(tensor)?;
+                match values.iter().next() {
+                    Some(&value) if values.len() == 1 => value,
+                    _ => {
+                        return Err(OnnxError::InvalidValue(format!(
+                            "The constant_value of Pad must be a single value, got a tensor of \
+                             shape {:?}",
+                            values.shape()
+                        )))
+                    }
+                }
+            }
+            None => 0.0,
+        };
+        (pads, value, int64_values(input(3))?)
+    } else {
+        let attributes = extract_attributes(node.get_attribute())?;
+        (
+            get_ints_attribute(&attributes, "pads", None)?,
+            get_float_attribute(&attributes, "value", Some(0.0))?,
+            None,
+        )
+    };
+
+    let axes = axes.unwrap_or_else(|| (0..rank as i64).collect());
+    if pads.len() != 2 * axes.len() {
+        return Err(OnnxError::InvalidValue(format!(
+            "The pads {:?} of a Pad must have 2 values for each of the {} padded axes",
+            pads,
+            axes.len()
+        )));
+    }
+    // The pads before and after every axis of the data.
+    let mut begins = vec![0; rank];
+    let mut ends = vec![0; rank];
+    let mut seen = vec![false; rank];
+    for (i, &axis) in axes.iter().enumerate() {
+        if axis < -(rank as i64) || axis >= rank as i64 {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )));
+        }
+        let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+        if std::mem::replace(&mut seen[axis], true) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is repeated in the axes {:?} of a Pad",
+                axis, axes
+            )));
+        }
+        begins[axis] = pads[i];
+        ends[axis] = pads[i + axes.len()];
+    }
+
+    // The negative pads crop the data first.
+    let mut cropped = data.view();
+    for axis in 0..rank {
+        let length = cropped.len_of(Axis(axis)) as i64;
+        let (first, last) = (-begins[axis].min(0), length + ends[axis].min(0));
+        if first > last {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Cannot crop {} and {} elements from axis {} of length {}",
+                -begins[axis].min(0),
+                -ends[axis].min(0),
+                axis,
+                length
+            )));
+        }
+        cropped.slice_axis_inplace(Axis(axis), Slice::from(first as isize..last as isize));
+        begins[axis] = begins[axis].max(0);
+        ends[axis] = ends[axis].max(0);
+    }
+
+    // The element of the cropped data each position of the output takes along every axis, `None`
+    // for the constant value.
+    let sources = (0..rank)
+        .map(|axis| {
+            let length = cropped.len_of(Axis(axis));
+            let padded = length + (begins[axis] + ends[axis]) as usize;
+            if length == 0 && padded > 0 && mode != PadMode::Constant {
+                return Err(OnnxError::InvalidValue(format!(
+                    "Cannot pad the empty axis {} in the {:?} mode",
+                    axis, mode
+                )));
+            }
+            Ok((0..padded as i64)
+                .map(|position| mode.source(position - begins[axis], length))
+                .collect::<Vec<Option<usize>>>())
+        })
+        .collect::<Result<Vec<_>, OnnxError>>()?;
+
+    let shape: Vec<usize> = sources.iter().map(|source| source.len()).collect();
+    let mut index = vec![0; rank];
+    let padded = ArrayD::from_shape_fn(IxDyn(&shape), |position| {
+        for (axis, sources) in sources.iter().enumerate() {
+            match sources[position[axis]] {
+                Some(source) => index[axis] = source,
+                None => return value,
+            }
+        }
+        cropped[index.as_slice()]
+    });
+    convert_to_output_tensor(node, padded)
+}
@@ -0,0 +1,328 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::conv::{
+    determine_padding, get_spatial_attribute, lift_to_3d, pad_spatial, SPATIAL_AXES,
+};
+use crate::onnx_rustime::ops::matmul_integer::widen;
+use crate::onnx_rustime::ops::quantize_linear::{quantize, quantized_range, quantized_tensor};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, get_ints_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::parallel::prelude::*;
+use ndarray::prelude::*;
+
+/// `qlinear_conv` - ONNX Node Implementation for Quantized Convolution (QLinearConv)
+///
+/// Convolves a quantized input with quantized kernels, as in the int8 models of the zoo. Every
+/// tensor `t` stands for the real values `(t - t_zero_point) * t_scale`; the output is the
+/// convolution of the real values, quantized with `y_scale` and `y_zero_point`:
+///
+/// ```text
+/// accumulated = sum((x - x_zero_point) * (w - w_zero_point)) + B
+/// y = saturate(round(accumulated * x_scale * w_scale / y_scale) + y_zero_point)
+/// ```
+///
+/// The inputs are never dequantized: the differences to the zero points are widened to i32, and
+/// the convolution accumulates their products in i32, like `conv` with an im2col matrix per image
+/// and group. Only the requantization of each accumulated value goes through f32, rounding
+/// halves to even and saturating to the range of the output type.
+///
+/// The attributes are those of `conv`: 1 to 3 spatial axes, `kernel_shape`, `strides`,
+/// `dilations`, `pads`, `auto_pad` and `group`. The padding holds `x_zero_point`, the real zero.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `x`, `x_scale`, `x_zero_point`, `w`, `w_scale`, `w_zero_point`,
+///   `y_scale`, `y_zero_point` and the optional `B`, by position among the node inputs (`None`
+///   for an omitted optional input).
+///   * `x` and `w` are UINT8 or INT8 tensors, their zero points of the same type.
+///   * `w_scale` and `w_zero_point` have a single value, or one per output channel (per-channel
+///     quantization of the kernels); the other scales and zero points a single value.
+///   * `B` is an INT32 vector of one value per output channel, quantized with the scale
+///     `x_scale * w_scale` and a zero point of 0.
+///   * The output has the type of `y_zero_point`, UINT8 or INT8.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the quantized convolution. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A missing input, or inputs of unsupported types.
+/// * Scales or zero points with an unexpected number of values.
+/// * The shape and attribute errors of `conv`.
+///
+/// # Example
+///
+/// ```rust
+/// let inputs = [Some(&x), Some(&x_scale), Some(&x_zero_point), Some(&w), Some(&w_scale),
+///               Some(&w_zero_point), Some(&y_scale), Some(&y_zero_point), None];
+/// let output = qlinear_conv(&inputs, &node)?;
+/// ```
+pub fn qlinear_conv(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let input = |index: usize, name: &str| {
+        inputs
+            .get(index)
+            .copied()
+            .flatten()
+            .ok_or_else(|| OnnxError::MissingInput(name.to_string()))
+    };
+
+    // The differences to the zero points.
+    let x_zero_point = single_value(widen(input(2, "x_zero_point")?, "x_zero_point")?)?;
+    let x = widen(input(0, "x")?, "x")?.mapv(|value| value - x_zero_point);
+    let mut w = widen(input(3, "w")?, "w")?;
+    let filters = w.shape().first().copied().unwrap_or(0);
+    let w_zero_point = per_channel(
+        widen(input(5, "w_zero_point")?, "w_zero_point")?,
+        filters,
+        "w_zero_point",
+    )?;
+    for (mut kernel, zero_point) in w.outer_iter_mut().zip(&w_zero_point) {
+        kernel.mapv_inplace(|value| value - zero_point);
+    }
+
+    // The scale of each output channel, from the accumulated values to the output.
+    let x_scale = single_value(tensor_proto_to_ndarray::<f32>(input(1, "x_scale")?)?)?;
+    let w_scale = per_channel(
+        tensor_proto_to_ndarray::<f32>(input(4, "w_scale")?)?,
+        filters,
+        "w_scale",
+    )?;
+    let y_scale = single_value(tensor_proto_to_ndarray::<f32>(input(6, "y_scale")?)?)?;
+    let multipliers: Vec<f32> = w_scale
+        .iter()
+        .map(|w_scale| x_scale * w_scale / y_scale)
+        .collect();
+
+    let y_zero_point = input(7, "y_zero_point")?;
+    let y_type = y_zero_point.get_data_type();
+    let y_zero_point = single_value(widen(y_zero_point, "y_zero_point")?)?;
+    let range = quantized_range(y_type)?;
+
+    let bias = match inputs.get(8).copied().flatten() {
+        Some(bias) => {
+            let bias = tensor_proto_to_ndarray::<i32>(bias)?;
+            if bias.ndim() != 1 || bias.len() != filters {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "Bias has shape {:?}, but the kernel has {} output channels",
+                    bias.shape(),
+                    filters
+                )));
+            }
+            bias.into_raw_vec()
+        }
+        None => vec![0; filters],
+    };
+
+    // The number of spatial axes follows the kernel_shape attribute, or the kernel without it.
+    let kernel_shape = get_ints_attribute(
+        &attributes,
+        "kernel_shape",
+        Some(w.shape().iter().skip(2).map(|&len| len as i64).collect()),
+    )?;
+    let rank = kernel_shape.len();
+    if !(1..=SPATIAL_AXES).contains(&rank) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Expected 1 to {} spatial axes, got a kernel_shape of {:?}",
+            SPATIAL_AXES, kernel_shape
+        )));
+    }
+    if x.ndim() != rank + 2 {
+        return Err(OnnxError::ShapeError(format!(
+            "Expected a {}D tensor for {} spatial axes, got shape {:?}",
+            rank + 2,
+            rank,
+            x.shape()
+        )));
+    }
+    if w.ndim() != rank + 2
+        || w.shape()[2..]
+            .iter()
+            .zip(&kernel_shape)
+            .any(|(&len, &expected)| len as i64 != expected)
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a {}D kernel of spatial shape {:?}, but got shape {:?}",
+            rank + 2,
+            kernel_shape,
+            w.shape()
+        )));
+    }
+
+    let group = get_int_attribute(&attributes, "group", Some(1))?;
+    if group <= 0
+        || x.shape()[1] as i64 != w.shape()[1] as i64 * group
+        || filters as i64 % group != 0
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Can't split the {} input channels and the kernel of shape {:?} into {} groups",
+            x.shape()[1],
+            w.shape(),
+            group
+        )));
+    }
+    let group = group as usize;
+
+    // Pad the input explicitly, whatever the auto_pad.
+    let strides = get_spatial_attribute(&attributes, "strides", rank, 1)?;
+    let dilations = get_spatial_attribute(&attributes, "dilations", rank, 1)?;
+    let pads = determine_padding(
+        &attributes,
+        &x.shape()[2..],
+        &w.shape()[2..],
+        &strides,
+        &dilations,
+    )?;
+    let padded = pad_spatial(&x, &pads);
+
+    // The dilated kernel must fit in the padded input.
+    let window: Vec<usize> = (0..rank)
+        .map(|axis| dilations[axis] * (w.shape()[axis + 2] - 1) + 1)
+        .collect();
+    if window
+        .iter()
+        .zip(&padded.shape()[2..])
+        .any(|(window, len)| window > len)
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The kernel of shape {:?} with dilations {:?} covers {:?} pixels, more than the \
+             padded input of {:?}",
+            w.shape(),
+            dilations,
+            window,
+            &padded.shape()[2..]
+        )));
+    }
+    let mut output_shape = vec![x.shape()[0], filters];
+    output_shape.extend(
+        (0..rank).map(|axis| (padded.shape()[axis + 2] - window[axis]) / strides[axis] + 1),
+    );
+
+    // Lift the convolution to 3 spatial axes.
+    let lifted = |values: &[usize]| {
+        let mut lifted = vec![1; SPATIAL_AXES - rank];
+        lifted.extend_from_slice(values);
+        lifted
+    };
+    let (strides, dilations) = (lifted(&strides), lifted(&dilations));
+    let output_spatial = lifted(&output_shape[2..]);
+    let x = lift_to_3d(padded)?;
+    let w = lift_to_3d(w)?;
+
+    let (_, channels_per_group, kernel_d, kernel_h, kernel_w) = w.dim();
+    let kernels_per_group = filters / group;
+    let (output_d, output_h, output_w) = (output_spatial[0], output_spatial[1], output_spatial[2]);
+    let taps = channels_per_group * kernel_d * kernel_h * kernel_w;
+
+    // The kernels of each group, one per row.
+    let kernels: Vec<Array2<i32>> = (0..group)
+        .map(|g| {
+            w.slice(s![
+                g * kernels_per_group..(g + 1) * kernels_per_group,
+                ..,
+                ..,
+                ..,
+                ..
+            ])
+            .to_owned()
+            .into_shape((kernels_per_group, taps))
+            .expect("The kernels of a group are contiguous")
+        })
+        .collect();
+
+    // Convolve the images of the batch in parallel.
+    let mut result = Array5::<i32>::zeros((output_shape[0], filters, output_d, output_h, output_w));
+    result
+        .outer_iter_mut()
+        .into_par_iter()
+        .zip(x.outer_iter().into_par_iter())
+        .for_each(|(mut output, image)| {
+            for (g, kernels) in kernels.iter().enumerate() {
+                // im2col: one row per tap of the kernels, one column per output pixel.
+                let mut columns = Array2::<i32>::zeros((taps, output_d * output_h * output_w));
+                let mut rows = columns.outer_iter_mut();
+                for c in 0..channels_per_group {
+                    let channel = image.index_axis(Axis(0), g * channels_per_group + c);
+                    for kd in 0..kernel_d {
+                        for kh in 0..kernel_h {
+                            for kw in 0..kernel_w {
+                                let (d, h, w) =
+                                    (kd * dilations[0], kh * dilations[1], kw * dilations[2]);
+                                let pixels = channel.slice(s![
+                                    d..d + (output_d - 1) * strides[0] + 1;strides[0],
+                                    h..h + (output_h - 1) * strides[1] + 1;strides[1],
+                                    w..w + (output_w - 1) * strides[2] + 1;strides[2],
+                                ]);
+                                rows.next()
+                                    .expect("One row per tap")
+                                    .into_shape((output_d, output_h, output_w))
+                                    .expect("A row of the columns is contiguous")
+                                    .assign(&pixels);
+                            }
+                        }
+                    }
+                }
+
+                let accumulated = kernels.dot(&columns);
+                for (k, accumulated) in accumulated.outer_iter().enumerate() {
+                    let m = g * kernels_per_group + k;
+                    let (bias, multiplier) = (bias[m], multipliers[m]);
+                    let quantized = accumulated.mapv(|value| {
+                        quantize((value + bias) as f32 * multiplier, y_zero_point, range)
+                    });
+                    output.index_axis_mut(Axis(0), m).assign(
+                        &quantized
+                            .into_shape((output_d, output_h, output_w))
+                            .unwrap(),
+                    );
+                }
+            }
+        });
+
+    let result = result.into_shape(output_shape).map_err(|_| {
+        OnnxError::ShapeError("Failed to reshape the convolution output".to_string())
+    })?;
+
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    quantized_tensor(result, y_type, output_name)
+}
+
+/// The value of a scale or zero point quantizing a whole tensor.
+fn single_value<T: Copy>(values: ArrayD<T>) -> Result<T, OnnxError> {
+    match values.len() {
+        1 => Ok(values.iter().copied().next().unwrap()),
+        len => Err(OnnxError::InvalidValue(format!(
+            "Expected a single scale or zero point, got {} values",
+            len
+        ))),
+    }
+}
+
+/// The values of a scale or zero point quantizing a tensor per output channel, or as a whole.
+fn per_channel<T: Copy>(
+    values: ArrayD<T>,
+    channels: usize,
+    name: &str,
+) -> Result<Vec<T>, OnnxError> {
+    match values.len() {
+        1 => Ok(vec![single_value(values)?; channels]),
+        len if len == channels && values.ndim() == 1 => Ok(values.into_raw_vec()),
+        _ => Err(OnnxError::shape_mismatch(format!(
+            "Expected a single value or one per output channel ({}) in {}, got shape {:?}",
+            channels,
+            name,
+            values.shape()
+        ))),
+    }
+}
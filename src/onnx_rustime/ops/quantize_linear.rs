@@ -0,0 +1,192 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::matmul_integer::widen;
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+use ndarray::Zip;
+
+/// `quantize_linear` - ONNX Node Implementation for Linear Quantization (QuantizeLinear)
+///
+/// Quantizes a float tensor to UINT8 or INT8 with a scale and a zero point:
+///
+/// `y = saturate(round(x / y_scale) + y_zero_point)`
+///
+/// where `round` rounds halves to even and `saturate` clamps to the range of the output type
+/// (`[0, 255]` or `[-128, 127]`).
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis of the per-axis quantization, 1 by default. Negative values count
+///   from the end. Ignored when `y_scale` has a single value.
+/// - `output_dtype` (int): The output type without `y_zero_point`: UINT8 (the default) or INT8.
+/// - `block_size` (int): Blocked quantization isn't supported, only its default of 0.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `x`, `y_scale` and the optional `y_zero_point`, in the order of the
+///   node inputs. `y_scale` has a single value (per-tensor quantization) or is a 1-D tensor of one
+///   value per index of `axis` (per-axis quantization); `y_zero_point` has the same shape, and
+///   its type is the output type.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the quantized tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A missing `x` or `y_scale`, or an output type other than UINT8 or INT8.
+/// * A scale or zero point that is neither a single value nor a vector along `axis`.
+/// * A non-zero `block_size`.
+///
+/// # Example
+///
+/// ```rust
+/// let quantized = quantize_linear(&[&x, &y_scale, &y_zero_point], &node)?;
+/// ```
+pub fn quantize_linear(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(1))?;
+    let block_size = get_int_attribute(&attributes, "block_size", Some(0))?;
+    if block_size != 0 {
+        return Err(OnnxError::UnsupportedOperation(format!(
+            "Blocked quantization isn't supported, got a block_size of {}",
+            block_size
+        )));
+    }
+
+    if inputs.len() < 2 {
+        return Err(OnnxError::MissingInput(
+            "QuantizeLinear requires the x and y_scale inputs".to_string(),
+        ));
+    }
+    let x = tensor_proto_to_ndarray::<f32>(inputs[0])?;
+    let scale = per_axis(
+        tensor_proto_to_ndarray::<f32>(inputs[1])?,
+        x.shape(),
+        axis,
+        "y_scale",
+    )?;
+
+    // The zero point gives the output type, UINT8 by default.
+    let (zero_point, data_type) = match inputs.get(2) {
+        Some(zero_point) => (
+            per_axis(
+                widen(zero_point, "y_zero_point")?,
+                x.shape(),
+                axis,
+                "y_zero_point",
+            )?,
+            zero_point.get_data_type(),
+        ),
+        None => {
+            let output_dtype = get_int_attribute(&attributes, "output_dtype", Some(0))?;
+            let data_type = match output_dtype {
+                0 => TensorProto_DataType::UINT8 as i32,
+                output_dtype => output_dtype as i32,
+            };
+            (arr0(0).into_dyn(), data_type)
+        }
+    };
+    let range = quantized_range(data_type)?;
+
+    let y = Zip::from(&x)
+        .and_broadcast(&scale)
+        .and_broadcast(&zero_point)
+        .map_collect(|&x, &scale, &zero_point| quantize(x / scale, zero_point, range));
+
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    quantized_tensor(y, data_type, output_name)
+}
+
+/// The range of the values of a quantized type, UINT8 or INT8.
+pub(crate) fn quantized_range(data_type: i32) -> Result<(i32, i32), OnnxError> {
+    if data_type == TensorProto_DataType::UINT8 as i32 {
+        Ok((u8::MIN as i32, u8::MAX as i32))
+    } else if data_type == TensorProto_DataType::INT8 as i32 {
+        Ok((i8::MIN as i32, i8::MAX as i32))
+    } else {
+        Err(OnnxError::UnsupportedOperation(format!(
+            "Expected a UINT8 or INT8 quantized type, got data type {}",
+            data_type
+        )))
+    }
+}
+
+/// Quantizes a value already divided by its scale: rounds it half to even, adds the zero point
+/// and saturates it to `range`.
+pub(crate) fn quantize(value: f32, zero_point: i32, (low, high): (i32, i32)) -> i32 {
+    // The cast saturates the values out of the range of i32 (and turns NaN into 0).
+    (value.round_ties_even() as i32)
+        .saturating_add(zero_point)
+        .clamp(low, high)
+}
+
+/// A tensor of quantized values of the given type, UINT8 or INT8, which they must fit in.
+pub(crate) fn quantized_tensor(
+    values: ArrayD<i32>,
+    data_type: i32,
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    if data_type == TensorProto_DataType::UINT8 as i32 {
+        ndarray_to_tensor_proto::<u8>(values.mapv(|value| value as u8), name)
+    } else {
+        quantized_range(data_type)?;
+        ndarray_to_tensor_proto::<i8>(values.mapv(|value| value as i8), name)
+    }
+}
+
+/// Reshapes a scale or a zero point to broadcast it to an input of the given shape: a single
+/// value applies to the whole input, a 1-D tensor to the indices of `axis`.
+pub(crate) fn per_axis<T>(
+    values: ArrayD<T>,
+    shape: &[usize],
+    axis: i64,
+    name: &str,
+) -> Result<ArrayD<T>, OnnxError> {
+    if values.len() == 1 {
+        return values
+            .into_shape(IxDyn(&[]))
+            .map_err(|e| OnnxError::ShapeError(e.to_string()));
+    }
+
+    let rank = shape.len() as i64;
+    if values.ndim() != 1 || axis < -rank || axis >= rank {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a single value or a vector along axis {} of the input of shape {:?} in {}, \
+             got shape {:?}",
+            axis,
+            shape,
+            name,
+            values.shape()
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    if values.len() != shape[axis] {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected one value per index of axis {} ({}) in {}, got {}",
+            axis,
+            shape[axis],
+            name,
+            values.len()
+        )));
+    }
+
+    let mut broadcast_shape = vec![1; shape.len()];
+    broadcast_shape[axis] = shape[axis];
+    values
+        .into_shape(broadcast_shape)
+        .map_err(|e| OnnxError::ShapeError(e.to_string()))
+}
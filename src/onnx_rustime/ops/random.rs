@@ -0,0 +1,219 @@
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_float_attribute, get_int_attribute, get_ints_attribute,
+    ndarray_to_tensor_proto,
+};
+use half::f16;
+use ndarray::{ArrayD, IxDyn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// The generator of a node, seeded by its `seed` attribute, or by a random seed without it.
+fn generator(attributes: &HashMap<String, Attribute<String>>) -> Result<StdRng, OnnxError> {
+    let seed = get_float_attribute(attributes, "seed", Some(rand::thread_rng().gen()))?;
+    Ok(StdRng::seed_from_u64(seed.to_bits() as u64))
+}
+
+/// The shape and the data type of the output of a random node: the `shape` and `dtype` (FLOAT by
+/// default) attributes, or the shape and the type of `input` for the Like variants, `dtype`
+/// overriding the type.
+fn output_shape_and_type(
+    op_type: &str,
+    input: Option<&TensorProto>,
+    attributes: &HashMap<String, Attribute<String>>,
+) -> Result<(Vec<usize>, i32), OnnxError> {
+    let (shape, default_type) = match input {
+        Some(input) => (input.get_dims().to_vec(), input.get_data_type()),
+        None => (
+            get_ints_attribute(attributes, "shape", None)?,
+            TensorProto_DataType::FLOAT as i32,
+        ),
+    };
+    if let Some(&dim) = shape.iter().find(|&&dim| dim < 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The shape {:?} of a {} has the negative dimension {}",
+            shape, op_type, dim
+        )));
+    }
+    let dtype = match get_int_attribute(attributes, "dtype", Some(0))? as i32 {
+        0 => default_type,
+        dtype => dtype,
+    };
+    Ok((shape.iter().map(|&dim| dim as usize).collect(), dtype))
+}
+
+/// A tensor of the given shape and data type filled with the values of `sample`, computed in
+/// double precision.
+fn sampled(
+    op_type: &str,
+    shape: &[usize],
+    dtype: i32,
+    name: &str,
+    sample: impl FnMut() -> f64,
+) -> Result<TensorProto, OnnxError> {
+    let len = shape.iter().product();
+    let values = ArrayD::from_shape_vec(
+        IxDyn(shape),
+        std::iter::repeat_with(sample).take(len).collect(),
+    )
+    .map_err(|e| OnnxError::ShapeError(format!("Failed to shape the samples: {}", e)))?;
+
+    match dtype {
+        t if t == TensorProto_DataType::FLOAT as i32 => {
+            ndarray_to_tensor_proto::<f32>(values.mapv(|value| value as f32), name)
+        }
+        t if t == TensorProto_DataType::DOUBLE as i32 => {
+            ndarray_to_tensor_proto::<f64>(values, name)
+        }
+        t if t == TensorProto_DataType::FLOAT16 as i32 => {
+            ndarray_to_tensor_proto::<f16>(values.mapv(f16::from_f64), name)
+        }
+        t => Err(OnnxError::UnsupportedOperation(format!(
+            "{} doesn't support the data type {}",
+            op_type, t
+        ))),
+    }
+}
+
+/// Samples a normal distribution for a RandomNormal or RandomNormalLike node, with the
+/// Box-Muller transform of two uniform samples.
+fn normal(
+    op_type: &str,
+    input: Option<&TensorProto>,
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let attributes = extract_attributes(node.get_attribute())?;
+    let (shape, dtype) = output_shape_and_type(op_type, input, &attributes)?;
+    let mean = get_float_attribute(&attributes, "mean", Some(0.0))? as f64;
+    let scale = get_float_attribute(&attributes, "scale", Some(1.0))? as f64;
+
+    let mut rng = generator(&attributes)?;
+    sampled(op_type, &shape, dtype, output_name, || {
+        // 1 - u is in (0, 1], whose logarithm is finite.
+        let (u, v) = (1.0 - rng.gen::<f64>(), rng.gen::<f64>());
+        mean + scale * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+    })
+}
+
+/// Samples a uniform distribution for a RandomUniform or RandomUniformLike node.
+fn uniform(
+    op_type: &str,
+    input: Option<&TensorProto>,
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let attributes = extract_attributes(node.get_attribute())?;
+    let (shape, dtype) = output_shape_and_type(op_type, input, &attributes)?;
+    let low = get_float_attribute(&attributes, "low", Some(0.0))? as f64;
+    let high = get_float_attribute(&attributes, "high", Some(1.0))? as f64;
+
+    let mut rng = generator(&attributes)?;
+    sampled(op_type, &shape, dtype, output_name, || {
+        low + (high - low) * rng.gen::<f64>()
+    })
+}
+
+/// `random_normal` - ONNX Node Implementation for RandomNormal
+///
+/// A tensor of samples of the normal distribution of the given `mean` and `scale` (its standard
+/// deviation), e.g. the noise of a generative model or of a variational autoencoder.
+///
+/// The samples are reproducible for a given `seed`: two runs of a node with the same seed give
+/// the same tensor, while a node without seed gives different tensors at every run. The values
+/// of a seed are the ones of this implementation, not the ones of other runtimes.
+///
+/// # Attributes
+///
+/// - `shape` (ints): The shape of the output.
+/// - `mean` (float): The mean of the distribution, 0.0 by default.
+/// - `scale` (float): The standard deviation of the distribution, 1.0 by default.
+/// - `seed` (float): The seed of the generator, random by default.
+/// - `dtype` (int): The data type of the output, FLOAT (default), DOUBLE or FLOAT16.
+///
+/// # Arguments
+///
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the samples. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A missing `shape`, or one with a negative dimension.
+/// * An unsupported `dtype`.
+///
+/// # Example
+///
+/// ```rust
+/// // The latent noise of a generator, with a `shape` attribute of [1, 100].
+/// let noise = random_normal(&node)?;
+/// ```
+pub fn random_normal(node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    normal("RandomNormal", None, node)
+}
+
+/// `random_normal_like` - ONNX Node Implementation for RandomNormalLike
+///
+/// The samples of `random_normal`, of the shape of the input and of its type (unless `dtype` is
+/// given) instead of the `shape` attribute. The values of the input are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// let noisy = random_normal_like(&latents, &node)?;
+/// ```
+pub fn random_normal_like(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    normal("RandomNormalLike", Some(input), node)
+}
+
+/// `random_uniform` - ONNX Node Implementation for RandomUniform
+///
+/// A tensor of samples of the uniform distribution over `[low, high)`, e.g. the random masks of a
+/// dropout kept at inference. The attributes `shape`, `seed` and `dtype`, and the errors, are
+/// those of `random_normal`.
+///
+/// # Attributes
+///
+/// - `low` (float): The lower bound of the distribution, 0.0 by default.
+/// - `high` (float): The upper bound of the distribution, 1.0 by default.
+///
+/// # Example
+///
+/// ```rust
+/// let mask = random_uniform(&node)?;
+/// ```
+pub fn random_uniform(node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    uniform("RandomUniform", None, node)
+}
+
+/// `random_uniform_like` - ONNX Node Implementation for RandomUniformLike
+///
+/// The samples of `random_uniform`, of the shape of the input and of its type (unless `dtype` is
+/// given) instead of the `shape` attribute. The values of the input are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// let mask = random_uniform_like(&activations, &node)?;
+/// ```
+pub fn random_uniform_like(
+    input: &TensorProto,
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    uniform("RandomUniformLike", Some(input), node)
+}
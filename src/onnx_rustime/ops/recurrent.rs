@@ -0,0 +1,485 @@
+//! The parts shared by the recurrent operators (LSTM, GRU and RNN): the inputs `X`, `W`, `R`,
+//! `B`, `sequence_lens` and `initial_h`, the attributes, the activation functions, the loop over
+//! the time steps, and the outputs.
+//!
+//! The loop runs one direction at a time. At each step, every sequence of the batch reads the
+//! input of its own time step: the reverse direction starts from the last element of each
+//! sequence (not from the last time step of `X`), and the sequences shorter than `X` stop once
+//! they are over, their outputs staying zero and their final states the ones of their last
+//! element.
+
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::gather::gather_indices;
+use crate::onnx_rustime::ops::utils::{
+    get_float_attribute, get_floats_attribute, get_int_attribute, get_string_attribute,
+    get_strings_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+use std::collections::HashMap;
+
+/// An activation function of a recurrent operator, with its `alpha` and `beta`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Activation {
+    Relu,
+    Tanh,
+    Sigmoid,
+    Affine(f32, f32),
+    LeakyRelu(f32),
+    ThresholdedRelu(f32),
+    ScaledTanh(f32, f32),
+    HardSigmoid(f32, f32),
+    Elu(f32),
+    Softsign,
+    Softplus,
+}
+
+impl Activation {
+    /// The activation named `name` (case insensitive), taking its `alpha` and `beta` from the
+    /// front of the given lists when it has them.
+    fn from_name(
+        name: &str,
+        alphas: &mut impl Iterator<Item = f32>,
+        betas: &mut impl Iterator<Item = f32>,
+    ) -> Result<Self, OnnxError> {
+        let mut alpha = |default: f32| alphas.next().unwrap_or(default);
+        Ok(match name.to_ascii_lowercase().as_str() {
+            "relu" => Activation::Relu,
+            "tanh" => Activation::Tanh,
+            "sigmoid" => Activation::Sigmoid,
+            "affine" => Activation::Affine(alpha(1.0), betas.next().unwrap_or(0.0)),
+            "leakyrelu" => Activation::LeakyRelu(alpha(0.01)),
+            "thresholdedrelu" => Activation::ThresholdedRelu(alpha(1.0)),
+            "scaledtanh" => Activation::ScaledTanh(alpha(1.0), betas.next().unwrap_or(1.0)),
+            "hardsigmoid" => Activation::HardSigmoid(alpha(0.2), betas.next().unwrap_or(0.5)),
+            "elu" => Activation::Elu(alpha(1.0)),
+            "softsign" => Activation::Softsign,
+            "softplus" => Activation::Softplus,
+            _ => {
+                return Err(OnnxError::UnsupportedOperation(format!(
+                    "The activation function {} of a recurrent operator is not supported",
+                    name
+                )))
+            }
+        })
+    }
+
+    pub(crate) fn apply(self, x: f32) -> f32 {
+        match self {
+            Activation::Relu => x.max(0.0),
+            Activation::Tanh => x.tanh(),
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Affine(alpha, beta) => alpha * x + beta,
+            Activation::LeakyRelu(alpha) => {
+                if x < 0.0 {
+                    alpha * x
+                } else {
+                    x
+                }
+            }
+            Activation::ThresholdedRelu(alpha) => {
+                if x > alpha {
+                    x
+                } else {
+                    0.0
+                }
+            }
+            Activation::ScaledTanh(alpha, beta) => alpha * (beta * x).tanh(),
+            Activation::HardSigmoid(alpha, beta) => (alpha * x + beta).clamp(0.0, 1.0),
+            Activation::Elu(alpha) => {
+                if x < 0.0 {
+                    alpha * (x.exp() - 1.0)
+                } else {
+                    x
+                }
+            }
+            Activation::Softsign => x / (1.0 + x.abs()),
+            Activation::Softplus => (1.0 + x.exp()).ln(),
+        }
+    }
+}
+
+/// The inputs and the attributes of a recurrent node, with `X` and the initial states in the
+/// default layout (`[seq_length, batch_size, ...]` and `[num_directions, batch_size, ...]`).
+pub(crate) struct Recurrence {
+    /// `[seq_length, batch_size, input_size]`.
+    x: Array3<f32>,
+    /// `[num_directions, gates * hidden_size, input_size]`.
+    w: Array3<f32>,
+    /// `[num_directions, gates * hidden_size, hidden_size]`.
+    r: Array3<f32>,
+    /// `[num_directions, 2 * gates * hidden_size]`: the biases of `W` then the ones of `R`.
+    b: Array2<f32>,
+    /// The length of every sequence of the batch.
+    sequence_lens: Vec<usize>,
+    /// `[num_directions, batch_size, hidden_size]`.
+    pub(crate) initial_h: Array3<f32>,
+    pub(crate) hidden_size: usize,
+    /// Whether each direction runs from the end of the sequences.
+    reverse: Vec<bool>,
+    /// The activation functions of each direction.
+    activations: Vec<Vec<Activation>>,
+    clip: Option<f32>,
+    /// 1 for the batch-first layout of opset 14 (`X` of shape `[batch_size, seq_length, ...]`).
+    layout: i64,
+}
+
+impl Recurrence {
+    /// Reads the inputs `X`, `W`, `R`, `B`, `sequence_lens` and `initial_h` (by position, `None`
+    /// for an omitted optional input) of a node with `gates` gates, and its attributes
+    /// `direction`, `hidden_size`, `activations`, `activation_alpha`, `activation_beta`, `clip`
+    /// and `layout`. The activations of a direction are `default_activations` by default.
+    pub(crate) fn new(
+        op_type: &str,
+        inputs: &[Option<&TensorProto>],
+        attributes: &HashMap<String, Attribute<String>>,
+        gates: usize,
+        default_activations: &[&str],
+    ) -> Result<Self, OnnxError> {
+        let input = |index: usize| inputs.get(index).copied().flatten();
+        let required = |index: usize, name: &str| {
+            input(index).ok_or(OnnxError::MissingInput(name.to_string()))
+        };
+
+        let direction = get_string_attribute(attributes, "direction", Some("forward".into()))?;
+        let reverse = match direction.as_str() {
+            "forward" => vec![false],
+            "reverse" => vec![true],
+            "bidirectional" => vec![false, true],
+            _ => {
+                return Err(OnnxError::InvalidAttribute {
+                    name: "direction".to_string(),
+                    detail: format!(
+                        "expected \"forward\", \"reverse\" or \"bidirectional\", got \"{}\"",
+                        direction
+                    ),
+                })
+            }
+        };
+        let directions = reverse.len();
+        let layout = get_int_attribute(attributes, "layout", Some(0))?;
+        let clip = match attributes.contains_key("clip") {
+            true => Some(get_float_attribute(attributes, "clip", None)?),
+            false => None,
+        };
+
+        let x = three_dimensional(required(0, "X")?, "X", layout)?;
+        let w = three_dimensional(required(1, "W")?, "W", 0)?;
+        let r = three_dimensional(required(2, "R")?, "R", 0)?;
+        let (seq_length, batch_size, input_size) = x.dim();
+        let hidden_size = match attributes.contains_key("hidden_size") {
+            true => get_int_attribute(attributes, "hidden_size", None)? as usize,
+            false => r.shape()[2],
+        };
+
+        let mismatch = |name: &str, shape: &[usize], expected: &[usize]| {
+            OnnxError::shape_mismatch(format!(
+                "The {} input of {} must be of shape {:?} ({} direction(s), a hidden size of {} \
+                 and inputs of size {}), got {:?}",
+                name, op_type, expected, directions, hidden_size, input_size, shape
+            ))
+        };
+        let expected_w = [directions, gates * hidden_size, input_size];
+        if w.shape() != expected_w {
+            return Err(mismatch("W", w.shape(), &expected_w));
+        }
+        let expected_r = [directions, gates * hidden_size, hidden_size];
+        if r.shape() != expected_r {
+            return Err(mismatch("R", r.shape(), &expected_r));
+        }
+
+        let b = match input(3) {
+            Some(b) => {
+                let b = tensor_proto_to_ndarray::<f32>(b)?;
+                let expected = [directions, 2 * gates * hidden_size];
+                if b.shape() != expected {
+                    return Err(mismatch("B", b.shape(), &expected));
+                }
+                b.into_dimensionality::<Ix2>()
+                    .map_err(|e| OnnxError::ShapeError(e.to_string()))?
+            }
+            None => Array2::zeros((directions, 2 * gates * hidden_size)),
+        };
+
+        let sequence_lens = match input(4) {
+            Some(lens) => {
+                let lens = gather_indices(lens)?;
+                if lens.shape() != [batch_size] {
+                    return Err(mismatch("sequence_lens", lens.shape(), &[batch_size]));
+                }
+                lens.iter()
+                    .map(|&len| match usize::try_from(len) {
+                        Ok(len) if len <= seq_length => Ok(len),
+                        _ => Err(OnnxError::InvalidValue(format!(
+                            "The sequence length {} is out of the range 0..={} of the input",
+                            len, seq_length
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            None => vec![seq_length; batch_size],
+        };
+
+        let state_shape = [directions, batch_size, hidden_size];
+        let initial_h = match input(5) {
+            Some(initial_h) => {
+                let initial_h = three_dimensional(initial_h, "initial_h", layout)?;
+                if initial_h.shape() != state_shape {
+                    return Err(mismatch("initial_h", initial_h.shape(), &state_shape));
+                }
+                initial_h
+            }
+            None => Array3::zeros(state_shape),
+        };
+
+        let activations = activations(attributes, default_activations, directions)?;
+
+        Ok(Recurrence {
+            x,
+            w,
+            r,
+            b,
+            sequence_lens,
+            initial_h,
+            hidden_size,
+            reverse,
+            activations,
+            clip,
+            layout,
+        })
+    }
+
+    pub(crate) fn directions(&self) -> usize {
+        self.reverse.len()
+    }
+
+    /// Another initial state of the node (`initial_c` of an LSTM) at the position `index` of the
+    /// inputs, zero if it's omitted.
+    pub(crate) fn state(
+        &self,
+        inputs: &[Option<&TensorProto>],
+        index: usize,
+        name: &str,
+    ) -> Result<Array3<f32>, OnnxError> {
+        let expected = self.initial_h.raw_dim();
+        match inputs.get(index).copied().flatten() {
+            Some(state) => {
+                let state = three_dimensional(state, name, self.layout)?;
+                if state.raw_dim() != expected {
+                    return Err(OnnxError::shape_mismatch(format!(
+                        "The {} must be of shape {:?}, got {:?}",
+                        name,
+                        expected.slice(),
+                        state.shape()
+                    )));
+                }
+                Ok(state)
+            }
+            None => Ok(Array3::zeros(expected)),
+        }
+    }
+
+    /// Runs every direction over the sequences of the batch, and returns the outputs the node
+    /// requests: `Y`, then the final states.
+    ///
+    /// The states start from `initial_states` (`[num_directions, batch_size, hidden_size]`, the
+    /// hidden state first). At each step, `step` computes the next states of the whole batch
+    /// from the direction, the products of the inputs of the step by `W` plus their biases
+    /// (`[batch_size, gates * hidden_size]`, zero for the sequences that are over) and the
+    /// current states (`[batch_size, hidden_size]`). Only the rows of the sequences still running
+    /// are kept, and their hidden states are written to `Y`.
+    pub(crate) fn run<F>(
+        &self,
+        node: &NodeProto,
+        mut states: Vec<Array3<f32>>,
+        mut step: F,
+    ) -> Result<Vec<TensorProto>, OnnxError>
+    where
+        F: FnMut(usize, Array2<f32>, &[Array2<f32>]) -> Vec<Array2<f32>>,
+    {
+        let (seq_length, batch_size, _) = self.x.dim();
+        let directions = self.reverse.len();
+        let mut y = Array4::<f32>::zeros((seq_length, directions, batch_size, self.hidden_size));
+
+        for direction in 0..directions {
+            let projected = self.projected_inputs(direction);
+            let mut current: Vec<Array2<f32>> = states
+                .iter()
+                .map(|state| state.index_axis(Axis(0), direction).to_owned())
+                .collect();
+            for index in 0..seq_length {
+                let time_steps = self.time_steps(direction, index);
+                let next = step(direction, step_inputs(&projected, &time_steps), &current);
+                for (batch, time) in time_steps.iter().enumerate() {
+                    let Some(time) = *time else {
+                        continue;
+                    };
+                    for (state, next) in current.iter_mut().zip(&next) {
+                        state.row_mut(batch).assign(&next.row(batch));
+                    }
+                    y.slice_mut(s![time, direction, batch, ..])
+                        .assign(&next[0].row(batch));
+                }
+            }
+            for (state, current) in states.iter_mut().zip(current) {
+                state.index_axis_mut(Axis(0), direction).assign(&current);
+            }
+        }
+
+        self.outputs(node, y, states)
+    }
+
+    /// The products of the inputs of every time step by the weights `W` of a direction, plus
+    /// their biases: `[seq_length, batch_size, gates * hidden_size]`.
+    fn projected_inputs(&self, direction: usize) -> Array3<f32> {
+        let (seq_length, batch_size, input_size) = self.x.dim();
+        let w = self.w.index_axis(Axis(0), direction);
+        let bias = self.b.slice(s![direction, ..w.nrows()]);
+        let rows = self
+            .x
+            .view()
+            .into_shape((seq_length * batch_size, input_size))
+            .expect("X is in the standard layout");
+        (rows.dot(&w.t()) + bias)
+            .into_shape((seq_length, batch_size, w.nrows()))
+            .expect("The product is in the standard layout")
+    }
+
+    /// The products of the hidden states of a direction by its weights `R`, plus their biases:
+    /// `[batch_size, gates * hidden_size]`.
+    pub(crate) fn projected_state(&self, direction: usize, hidden: &Array2<f32>) -> Array2<f32> {
+        let r = self.r.index_axis(Axis(0), direction);
+        hidden.dot(&r.t()) + self.b.slice(s![direction, r.nrows()..])
+    }
+
+    /// The time step of every sequence of the batch at the step `step` of a direction, `None`
+    /// once the sequence is over.
+    fn time_steps(&self, direction: usize, step: usize) -> Vec<Option<usize>> {
+        self.sequence_lens
+            .iter()
+            .map(|&len| match (step < len, self.reverse[direction]) {
+                (false, _) => None,
+                (true, false) => Some(step),
+                (true, true) => Some(len - 1 - step),
+            })
+            .collect()
+    }
+
+    /// The activation function `index` of a direction.
+    pub(crate) fn activation(&self, direction: usize, index: usize) -> Activation {
+        self.activations[direction][index]
+    }
+
+    /// The activation function `index` of a direction applied to a gate input, clipped to
+    /// `[-clip, clip]` if the node has a `clip`.
+    pub(crate) fn activate(&self, direction: usize, index: usize, x: f32) -> f32 {
+        let x = match self.clip {
+            Some(clip) => x.clamp(-clip, clip),
+            None => x,
+        };
+        self.activation(direction, index).apply(x)
+    }
+
+    /// The outputs the node requests: `Y` (`[seq_length, num_directions, batch_size,
+    /// hidden_size]`) then the final states (`[num_directions, batch_size, hidden_size]`), in
+    /// the layout of the node.
+    fn outputs(
+        &self,
+        node: &NodeProto,
+        y: Array4<f32>,
+        states: Vec<Array3<f32>>,
+    ) -> Result<Vec<TensorProto>, OnnxError> {
+        let batch_first = self.layout != 0;
+        let y = match batch_first {
+            true => y.permuted_axes([2, 0, 1, 3]),
+            false => y,
+        };
+        let states = states.into_iter().map(|state| match batch_first {
+            true => state.permuted_axes([1, 0, 2]).into_dyn(),
+            false => state.into_dyn(),
+        });
+
+        let mut outputs = Vec::new();
+        for (output, name) in std::iter::once(y.into_dyn())
+            .chain(states)
+            .zip(node.get_output())
+        {
+            if !name.is_empty() {
+                let output = output.as_standard_layout().into_owned();
+                outputs.push(ndarray_to_tensor_proto::<f32>(output, name)?);
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+/// The rows of the projected inputs of the given time steps, zero for the sequences that are
+/// over.
+fn step_inputs(projected: &Array3<f32>, time_steps: &[Option<usize>]) -> Array2<f32> {
+    let width = projected.shape()[2];
+    Array2::from_shape_fn((time_steps.len(), width), |(batch, column)| {
+        time_steps[batch].map_or(0.0, |time| projected[[time, batch, column]])
+    })
+}
+
+/// A 3D FLOAT input, with its first two axes swapped in the batch-first layout.
+fn three_dimensional(
+    tensor: &TensorProto,
+    name: &str,
+    layout: i64,
+) -> Result<Array3<f32>, OnnxError> {
+    let array = tensor_proto_to_ndarray::<f32>(tensor)?;
+    let shape = array.shape().to_vec();
+    let array = array.into_dimensionality::<Ix3>().map_err(|_| {
+        OnnxError::shape_mismatch(format!(
+            "The {} must be 3D, got the shape {:?}",
+            name, shape
+        ))
+    })?;
+    Ok(match layout {
+        0 => array,
+        _ => array
+            .permuted_axes([1, 0, 2])
+            .as_standard_layout()
+            .into_owned(),
+    })
+}
+
+/// The activation functions of every direction, from the `activations`, `activation_alpha` and
+/// `activation_beta` attributes. The alphas and the betas are taken in order by the functions
+/// having them.
+fn activations(
+    attributes: &HashMap<String, Attribute<String>>,
+    defaults: &[&str],
+    directions: usize,
+) -> Result<Vec<Vec<Activation>>, OnnxError> {
+    let names = match attributes.contains_key("activations") {
+        true => get_strings_attribute(attributes, "activations", None)?,
+        false => (0..directions)
+            .flat_map(|_| defaults.iter().map(|name| name.to_string()))
+            .collect(),
+    };
+    if names.len() != defaults.len() * directions {
+        return Err(OnnxError::InvalidAttribute {
+            name: "activations".to_string(),
+            detail: format!(
+                "expected {} functions for {} direction(s), got {:?}",
+                defaults.len() * directions,
+                directions,
+                names
+            ),
+        });
+    }
+
+    let alphas = get_floats_attribute(attributes, "activation_alpha", Some(vec![]))?;
+    let betas = get_floats_attribute(attributes, "activation_beta", Some(vec![]))?;
+    let (mut alphas, mut betas) = (alphas.into_iter(), betas.into_iter());
+    let activations = names
+        .iter()
+        .map(|name| Activation::from_name(name, &mut alphas, &mut betas))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(activations
+        .chunks(defaults.len())
+        .map(|functions| functions.to_vec())
+        .collect())
+}
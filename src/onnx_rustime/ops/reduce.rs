@@ -0,0 +1,408 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, ArrayView1, IxDyn};
+
+/// The axes of a reduction: its second input since opset 18 (13 for ReduceSum), its `axes`
+/// attribute before. Empty if the node has neither, or an empty input.
+fn axes(inputs: &[&TensorProto], node: &NodeProto) -> Result<Vec<i64>, OnnxError> {
+    if let Some(axes) = inputs.get(1) {
+        if axes.get_dims().iter().product::<i64>() == 0 {
+            return Ok(vec![]);
+        }
+        return Ok(tensor_proto_to_ndarray::<i64>(axes)?.into_raw_vec());
+    }
+    let attributes = extract_attributes(node.get_attribute())?;
+    Ok(attributes
+        .get("axes")
+        .and_then(|attr| attr.as_ints().cloned())
+        .unwrap_or_default())
+}
+
+/// Turns the negative axes into positive ones, for a tensor of the given rank.
+fn normalized_axes(axes: &[i64], rank: usize) -> Result<Vec<usize>, OnnxError> {
+    let rank = rank as i64;
+    let mut normalized = Vec::with_capacity(axes.len());
+    for &axis in axes {
+        if axis < -rank || axis >= rank {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )));
+        }
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        if normalized.contains(&axis) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is repeated in {:?}",
+                axis, axes
+            )));
+        }
+        normalized.push(axis);
+    }
+    Ok(normalized)
+}
+
+/// Reduces the input of a Reduce node along its axes, every group of reduced elements giving the
+/// value of `reduction`.
+fn reduce<F>(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    reduction: F,
+) -> Result<TensorProto, OnnxError>
+where
+    F: Fn(ArrayView1<f32>) -> f32,
+{
+    let data = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let data = tensor_proto_to_ndarray::<f32>(data)?;
+    let rank = data.ndim();
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let keepdims = get_int_attribute(&attributes, "keepdims", Some(1))? != 0;
+    let noop = get_int_attribute(&attributes, "noop_with_empty_axes", Some(0))? != 0;
+
+    let axes = normalized_axes(&axes(inputs, node)?, rank)?;
+    let axes = match axes.is_empty() {
+        true if noop => return convert_to_output_tensor(node, data),
+        true => (0..rank).collect(),
+        false => axes,
+    };
+
+    // The kept axes are moved first and the reduced ones last, so that every row of the matrix
+    // they are flattened into is a group of reduced elements.
+    let kept: Vec<usize> = (0..rank).filter(|axis| !axes.contains(axis)).collect();
+    let groups: usize = kept.iter().map(|&axis| data.shape()[axis]).product();
+    let group: usize = axes.iter().map(|&axis| data.shape()[axis]).product();
+    let order: Vec<usize> = kept.iter().chain(&axes).copied().collect();
+    let rows = data
+        .view()
+        .permuted_axes(order)
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((groups, group))
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+    let values: Vec<f32> = rows.outer_iter().map(reduction).collect();
+
+    let shape: Vec<usize> = match keepdims {
+        true => (0..rank)
+            .map(|axis| match axes.contains(&axis) {
+                true => 1,
+                false => data.shape()[axis],
+            })
+            .collect(),
+        false => kept.iter().map(|&axis| data.shape()[axis]).collect(),
+    };
+    let reduced = ArrayD::from_shape_vec(IxDyn(&shape), values)
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+    convert_to_output_tensor(node, reduced)
+}
+
+/// `reduce_sum` - ONNX Node Implementation for ReduceSum
+///
+/// Reduces the input along the given axes to the sum of their elements, `y = sum(x)`, like
+/// NumPy's `sum` with `keepdims` set by default.
+///
+/// The ten reductions of this module share their attributes and inputs: `axes` is an attribute
+/// before opset 18 (before opset 13 for ReduceSum), an optional INT64 input since. Negative axes
+/// count from the end, in the range `[-r, r-1]` where `r` is the rank of the input. Without
+/// axes, or with empty ones, all the axes are reduced, unless `noop_with_empty_axes` is set: the
+/// input is then returned unchanged.
+///
+/// # Attributes
+///
+/// - `keepdims` (int): 1 (default) to keep the reduced axes with a length of 1, 0 to remove them.
+/// - `noop_with_empty_axes` (int): 1 to return the input when the axes are empty, 0 (default) to
+///   reduce all the axes.
+/// - `axes` (ints): The axes to reduce, before the axes became an input.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, in the recent operator sets, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`.
+/// * An axis out of range or repeated.
+///
+/// # Example
+///
+/// ```rust
+/// let sums = reduce_sum(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn reduce_sum(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.sum())
+}
+
+/// `reduce_mean` - ONNX Node Implementation for ReduceMean
+///
+/// Reduces the input along the given axes to the mean of their elements:
+///
+/// `y = sum(x) / n`, over the `n` reduced elements (NaN when there are none).
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let means = reduce_mean(&[&features, &spatial_axes], &node)?;
+/// ```
+pub fn reduce_mean(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.sum() / x.len() as f32)
+}
+
+/// `reduce_max` - ONNX Node Implementation for ReduceMax
+///
+/// Reduces the input along the given axes to the maximum of their elements:
+///
+/// `y = max(x)`, -inf when no element is reduced.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let maxima = reduce_max(&[&scores], &node)?;
+/// ```
+pub fn reduce_max(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| {
+        x.fold(f32::NEG_INFINITY, |max, &x| max.max(x))
+    })
+}
+
+/// `reduce_min` - ONNX Node Implementation for ReduceMin
+///
+/// Reduces the input along the given axes to the minimum of their elements:
+///
+/// `y = min(x)`, +inf when no element is reduced.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let minima = reduce_min(&[&scores], &node)?;
+/// ```
+pub fn reduce_min(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| {
+        x.fold(f32::INFINITY, |min, &x| min.min(x))
+    })
+}
+
+/// `reduce_prod` - ONNX Node Implementation for ReduceProd
+///
+/// Reduces the input along the given axes to the product of their elements:
+///
+/// `y = prod(x)`, 1 when no element is reduced.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let products = reduce_prod(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn reduce_prod(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.product())
+}
+
+/// `reduce_l1` - ONNX Node Implementation for ReduceL1
+///
+/// Reduces the input along the given axes to the L1 norm of their elements:
+///
+/// `y = sum(|x|)`.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let norms = reduce_l1(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn reduce_l1(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.fold(0.0, |sum, &x| sum + x.abs()))
+}
+
+/// `reduce_l2` - ONNX Node Implementation for ReduceL2
+///
+/// Reduces the input along the given axes to the L2 norm of their elements:
+///
+/// `y = sqrt(sum(x^2))`, e.g. to normalize embeddings.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let norms = reduce_l2(&[&embeddings, &last_axis], &node)?;
+/// ```
+pub fn reduce_l2(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.dot(&x).sqrt())
+}
+
+/// `reduce_sum_square` - ONNX Node Implementation for ReduceSumSquare
+///
+/// Reduces the input along the given axes to the sum of the squares of their elements:
+///
+/// `y = sum(x^2)`.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let energies = reduce_sum_square(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn reduce_sum_square(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.dot(&x))
+}
+
+/// `reduce_log_sum` - ONNX Node Implementation for ReduceLogSum
+///
+/// Reduces the input along the given axes to the logarithm of the sum of their elements:
+///
+/// `y = log(sum(x))`.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let log_sums = reduce_log_sum(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn reduce_log_sum(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| x.sum().ln())
+}
+
+/// `reduce_log_sum_exp` - ONNX Node Implementation for ReduceLogSumExp
+///
+/// Reduces the input along the given axes to the logarithm of the sum of the exponentials of their
+/// elements:
+///
+/// `y = log(sum(exp(x)))`
+///
+/// It is computed as `max(x) + log(sum(exp(x - max(x))))`, which is the same value but never
+/// exponentiates a positive number: large inputs don't overflow to infinity.
+///
+/// The attributes, the axes and the errors are those of `reduce_sum`.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 18, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the reduced tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let log_partitions = reduce_log_sum_exp(&[&logits, &last_axis], &node)?;
+/// ```
+pub fn reduce_log_sum_exp(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    reduce(inputs, node, |x| {
+        let max = x.fold(f32::NEG_INFINITY, |max, &x| max.max(x));
+        // Infinite for no elements, or for infinite ones: nothing to subtract.
+        if max.is_infinite() {
+            return x.mapv(f32::exp).sum().ln();
+        }
+        max + x.mapv(|x| (x - max).exp()).sum().ln()
+    })
+}
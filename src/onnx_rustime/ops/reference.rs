@@ -0,0 +1,506 @@
+//! Naive reference implementations of the heavy operations.
+//!
+//! Each function is a direct transcription of the ONNX definition: plain loops, no im2col, no
+//! parallelism, accumulation in f64. They are slow, but easy to check by reading, and are the
+//! ground truth the optimized kernels are fuzzed against (see `testing::differential`). Setting
+//! `RunOptions::reference_kernels` makes the executor run them instead of the optimized kernels,
+//! to find out whether a wrong output comes from a kernel.
+
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::matmul::add_fused_bias;
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_int_attribute, get_ints_attribute,
+    get_string_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::prelude::*;
+use std::collections::HashMap;
+
+/// Parameters of a 2D convolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvParams {
+    /// Strides along the height and the width.
+    pub strides: [usize; 2],
+    /// Padding in the ONNX order: top, left, bottom, right.
+    pub pads: [usize; 4],
+    /// Dilations along the height and the width.
+    pub dilations: [usize; 2],
+    pub group: usize,
+}
+
+/// Parameters of a 2D pooling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolParams {
+    /// Height and width of the window.
+    pub kernel_shape: [usize; 2],
+    pub strides: [usize; 2],
+    /// Padding in the ONNX order: top, left, bottom, right.
+    pub pads: [usize; 4],
+}
+
+/// Runs a node with its reference implementation, if the operation has one.
+///
+/// The inputs are split between `inputs` and `initializers` like for the optimized kernels (see
+/// `run_node`), and `opset` is the version of the default operator set imported by the model.
+pub fn run_reference_node(
+    node: &NodeProto,
+    inputs: &[&TensorProto],
+    initializers: &[&TensorProto],
+    opset: i64,
+) -> Option<Result<TensorProto, OnnxError>> {
+    let result = match node.get_op_type() {
+        "Conv" => conv_node(inputs, initializers, node),
+        "GlobalAveragePool" => first_input(inputs)
+            .and_then(tensor_proto_to_ndarray::<f32>)
+            .and_then(|input| global_average_pool(&input)),
+        "MatMul" => matmul_node(inputs, initializers, node),
+        "MaxPool" => max_pool_node(inputs, node),
+        "Softmax" => softmax_node(inputs, node, opset),
+        _ => return None,
+    };
+    Some(result.and_then(|output| convert_to_output_tensor(node, output)))
+}
+
+/// 2D convolution of an `[N, C, H, W]` input with an `[M, C / group, kH, kW]` kernel.
+pub fn conv2d(
+    input: &Array4<f32>,
+    kernel: &Array4<f32>,
+    bias: Option<&Array1<f32>>,
+    params: &ConvParams,
+) -> Result<Array4<f32>, OnnxError> {
+    let (batch, channels, height, width) = input.dim();
+    let (filters, group_channels, kernel_h, kernel_w) = kernel.dim();
+    let group = params.group;
+
+    if group == 0 || channels != group_channels * group || filters % group != 0 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Can't convolve an input of shape {:?} with a kernel of shape {:?} in {} groups",
+            input.shape(),
+            kernel.shape(),
+            group
+        )));
+    }
+    if bias.is_some_and(|bias| bias.len() != filters) {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a bias of {} elements",
+            filters
+        )));
+    }
+
+    let (strides, dilations, pads) = (params.strides, params.dilations, params.pads);
+    let out_h = output_size(height, kernel_h, strides[0], dilations[0], pads[0], pads[2])?;
+    let out_w = output_size(width, kernel_w, strides[1], dilations[1], pads[1], pads[3])?;
+    let row = |y, ky| input_index(y, ky, strides[0], dilations[0], pads[0], height);
+    let column = |x, kx| input_index(x, kx, strides[1], dilations[1], pads[1], width);
+    let filters_per_group = filters / group;
+
+    let mut output = Array4::zeros((batch, filters, out_h, out_w));
+    for ((n, m, y, x), value) in output.indexed_iter_mut() {
+        let g = m / filters_per_group;
+        let mut sum = bias.map_or(0.0, |bias| bias[m] as f64);
+        for c in 0..group_channels {
+            for ky in 0..kernel_h {
+                for kx in 0..kernel_w {
+                    if let (Some(row), Some(column)) = (row(y, ky), column(x, kx)) {
+                        sum += input[[n, g * group_channels + c, row, column]] as f64
+                            * kernel[[m, c, ky, kx]] as f64;
+                    }
+                }
+            }
+        }
+        *value = sum as f32;
+    }
+    Ok(output)
+}
+
+/// 2D max pooling of an `[N, C, H, W]` input. Padded positions never win.
+pub fn max_pool2d(input: &Array4<f32>, params: &PoolParams) -> Result<Array4<f32>, OnnxError> {
+    let (batch, channels, height, width) = input.dim();
+    let [kernel_h, kernel_w] = params.kernel_shape;
+    let (strides, pads) = (params.strides, params.pads);
+    let out_h = output_size(height, kernel_h, strides[0], 1, pads[0], pads[2])?;
+    let out_w = output_size(width, kernel_w, strides[1], 1, pads[1], pads[3])?;
+    let row = |y, ky| input_index(y, ky, strides[0], 1, pads[0], height);
+    let column = |x, kx| input_index(x, kx, strides[1], 1, pads[1], width);
+
+    let mut output = Array4::zeros((batch, channels, out_h, out_w));
+    for ((n, c, y, x), value) in output.indexed_iter_mut() {
+        let mut max = f32::NEG_INFINITY;
+        for ky in 0..kernel_h {
+            for kx in 0..kernel_w {
+                if let (Some(row), Some(column)) = (row(y, ky), column(x, kx)) {
+                    max = max.max(input[[n, c, row, column]]);
+                }
+            }
+        }
+        *value = max;
+    }
+    Ok(output)
+}
+
+/// Average of every channel of an `[N, C, ...]` input, keeping the spatial dimensions as 1s.
+pub fn global_average_pool(input: &ArrayD<f32>) -> Result<ArrayD<f32>, OnnxError> {
+    if input.ndim() < 2 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected an [N, C, ...] input, got shape {:?}",
+            input.shape()
+        )));
+    }
+    let mut shape = input.shape().to_vec();
+    shape[2..].iter_mut().for_each(|dim| *dim = 1);
+
+    let (batch, channels) = (shape[0], shape[1]);
+    let mut output = Vec::with_capacity(batch * channels);
+    for n in 0..batch {
+        for c in 0..channels {
+            let channel = input.slice_each_axis(|axis| match axis.axis.index() {
+                0 => (n..n + 1).into(),
+                1 => (c..c + 1).into(),
+                _ => (..).into(),
+            });
+            let sum: f64 = channel.iter().map(|&v| v as f64).sum();
+            output.push((sum / channel.len() as f64) as f32);
+        }
+    }
+    ArrayD::from_shape_vec(shape, output).map_err(|e| OnnxError::ShapeError(e.to_string()))
+}
+
+/// Matrix product with numpy semantics: the last two dimensions are multiplied, the leading
+/// (batch) ones are broadcast.
+pub fn matmul(a: &ArrayD<f32>, b: &ArrayD<f32>) -> Result<ArrayD<f32>, OnnxError> {
+    let mismatch = || {
+        OnnxError::shape_mismatch(format!(
+            "Can't multiply A of shape {:?} by B of shape {:?}",
+            a.shape(),
+            b.shape()
+        ))
+    };
+    if a.ndim() < 2 || b.ndim() < 2 || a.shape()[a.ndim() - 1] != b.shape()[b.ndim() - 2] {
+        return Err(mismatch());
+    }
+
+    let (rows, inner) = (a.shape()[a.ndim() - 2], a.shape()[a.ndim() - 1]);
+    let columns = b.shape()[b.ndim() - 1];
+    let (batch_a, batch_b) = (&a.shape()[..a.ndim() - 2], &b.shape()[..b.ndim() - 2]);
+
+    // Broadcast batch shape, aligned on the right.
+    let rank = batch_a.len().max(batch_b.len());
+    let dim =
+        |shape: &[usize], i: usize| (i + shape.len()).checked_sub(rank).map_or(1, |i| shape[i]);
+    let mut batch = Vec::with_capacity(rank);
+    for i in 0..rank {
+        let (x, y) = (dim(batch_a, i), dim(batch_b, i));
+        if x != y && x != 1 && y != 1 {
+            return Err(mismatch());
+        }
+        batch.push(x.max(y));
+    }
+
+    let mut shape = batch.clone();
+    shape.extend([rows, columns]);
+    Ok(ArrayD::from_shape_fn(shape, |index| {
+        let index = index.slice();
+        // The index of an operand: batch dimensions of size 1 are repeated.
+        let operand = |shape: &[usize], last: [usize; 2]| -> Vec<usize> {
+            let batch_rank = shape.len() - 2;
+            let mut operand: Vec<usize> = (0..batch_rank)
+                .map(|i| {
+                    if shape[i] == 1 {
+                        0
+                    } else {
+                        index[rank - batch_rank + i]
+                    }
+                })
+                .collect();
+            operand.extend(last);
+            operand
+        };
+        let (row, column) = (index[rank], index[rank + 1]);
+        (0..inner)
+            .map(|k| {
+                a[operand(a.shape(), [row, k]).as_slice()] as f64
+                    * b[operand(b.shape(), [k, column]).as_slice()] as f64
+            })
+            .sum::<f64>() as f32
+    }))
+}
+
+/// Softmax with the semantics of opset 1 to 12: the input is coerced to 2D, flattening the
+/// dimensions before and from `axis`, and every row is normalized.
+pub fn softmax(input: &ArrayD<f32>, axis: usize) -> Result<ArrayD<f32>, OnnxError> {
+    if axis > input.ndim() {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis,
+            input.ndim()
+        )));
+    }
+    let rows: usize = input.shape()[..axis].iter().product();
+    let columns: usize = input.shape()[axis..].iter().product();
+    let flat: Vec<f64> = input.iter().map(|&v| v as f64).collect();
+
+    let mut output = Vec::with_capacity(flat.len());
+    for row in flat.chunks(columns.max(1)).take(rows) {
+        let max = row.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = row.iter().map(|v| (v - max).exp()).sum();
+        output.extend(row.iter().map(|v| ((v - max).exp() / sum) as f32));
+    }
+    ArrayD::from_shape_vec(input.shape(), output).map_err(|e| OnnxError::ShapeError(e.to_string()))
+}
+
+/// Size of the output along a spatial dimension.
+fn output_size(
+    size: usize,
+    kernel: usize,
+    stride: usize,
+    dilation: usize,
+    pad_begin: usize,
+    pad_end: usize,
+) -> Result<usize, OnnxError> {
+    let window = dilation * (kernel.max(1) - 1) + 1;
+    let padded = size + pad_begin + pad_end;
+    if stride == 0 || dilation == 0 || kernel == 0 || padded < window {
+        return Err(OnnxError::shape_mismatch(format!(
+            "A window of {} (dilation {}) with stride {} doesn't fit in {} padded elements",
+            kernel, dilation, stride, padded
+        )));
+    }
+    Ok((padded - window) / stride + 1)
+}
+
+/// Position in the input read by the output `output` at the kernel offset `offset`, or `None`
+/// if it falls in the padding.
+fn input_index(
+    output: usize,
+    offset: usize,
+    stride: usize,
+    dilation: usize,
+    pad_begin: usize,
+    size: usize,
+) -> Option<usize> {
+    (output * stride + offset * dilation)
+        .checked_sub(pad_begin)
+        .filter(|&index| index < size)
+}
+
+fn first_input<'a>(inputs: &[&'a TensorProto]) -> Result<&'a TensorProto, OnnxError> {
+    inputs
+        .first()
+        .copied()
+        .ok_or(OnnxError::MissingInput("X".to_string()))
+}
+
+/// Strides, pads (top, left, bottom, right) and dilations of a 2D window.
+type SpatialParams = ([usize; 2], [usize; 4], [usize; 2]);
+
+/// Spatial attributes shared by Conv and MaxPool: strides, pads (resolved from `auto_pad`) and
+/// dilations.
+fn spatial_params(
+    attributes: &HashMap<String, Attribute<String>>,
+    spatial_shape: [usize; 2],
+    kernel_shape: [usize; 2],
+) -> Result<SpatialParams, OnnxError> {
+    let pair = |name: &str| -> Result<[usize; 2], OnnxError> {
+        match get_ints_attribute(attributes, name, Some(vec![1, 1]))?.as_slice() {
+            &[a, b] if a > 0 && b > 0 => Ok([a as usize, b as usize]),
+            values => Err(OnnxError::InvalidValue(format!(
+                "Expected two positive {}, got {:?}",
+                name, values
+            ))),
+        }
+    };
+    let strides = pair("strides")?;
+    let dilations = pair("dilations")?;
+
+    let auto_pad = get_string_attribute(attributes, "auto_pad", Some("NOTSET".to_string()))?;
+    let pads = match auto_pad.as_str() {
+        "NOTSET" | "NOT_SET" => {
+            match get_ints_attribute(attributes, "pads", Some(vec![0; 4]))?.as_slice() {
+                &[top, left, bottom, right] if top.min(left).min(bottom).min(right) >= 0 => {
+                    [top as usize, left as usize, bottom as usize, right as usize]
+                }
+                values => {
+                    return Err(OnnxError::InvalidValue(format!(
+                        "Expected four non-negative pads, got {:?}",
+                        values
+                    )))
+                }
+            }
+        }
+        "VALID" => [0; 4],
+        "SAME_UPPER" | "SAME_LOWER" => {
+            // The output has ceil(size / stride) elements, the extra padding goes at the end for
+            // SAME_UPPER and at the beginning for SAME_LOWER.
+            let total = |i: usize| {
+                let output = spatial_shape[i].div_ceil(strides[i]);
+                ((output - 1) * strides[i] + dilations[i] * (kernel_shape[i] - 1) + 1)
+                    .saturating_sub(spatial_shape[i])
+            };
+            let (total_h, total_w) = (total(0), total(1));
+            if auto_pad == "SAME_UPPER" {
+                [
+                    total_h / 2,
+                    total_w / 2,
+                    total_h - total_h / 2,
+                    total_w - total_w / 2,
+                ]
+            } else {
+                [
+                    total_h - total_h / 2,
+                    total_w - total_w / 2,
+                    total_h / 2,
+                    total_w / 2,
+                ]
+            }
+        }
+        other => {
+            return Err(OnnxError::InvalidValue(format!(
+                "Invalid auto_pad value '{}'",
+                other
+            )))
+        }
+    };
+
+    Ok((strides, pads, dilations))
+}
+
+fn to_4d(array: ArrayD<f32>, name: &str) -> Result<Array4<f32>, OnnxError> {
+    let shape = array.shape().to_vec();
+    array.into_dimensionality::<Ix4>().map_err(|_| {
+        OnnxError::shape_mismatch(format!("Expected a 4D {}, got shape {:?}", name, shape))
+    })
+}
+
+fn conv_node(
+    inputs: &[&TensorProto],
+    initializers: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let input = to_4d(
+        tensor_proto_to_ndarray::<f32>(first_input(inputs)?)?,
+        "input",
+    )?;
+    let kernel = initializers
+        .first()
+        .ok_or(OnnxError::MissingInput("W".to_string()))?;
+    let kernel = to_4d(tensor_proto_to_ndarray::<f32>(kernel)?, "kernel")?;
+    let bias = match initializers.get(1) {
+        Some(bias) => Some(
+            tensor_proto_to_ndarray::<f32>(bias)?
+                .into_dimensionality::<Ix1>()
+                .map_err(|_| OnnxError::shape_mismatch("Expected a 1D bias".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let (_, _, height, width) = input.dim();
+    let (_, _, kernel_h, kernel_w) = kernel.dim();
+    let (strides, pads, dilations) =
+        spatial_params(&attributes, [height, width], [kernel_h, kernel_w])?;
+    let params = ConvParams {
+        strides,
+        pads,
+        dilations,
+        group: get_int_attribute(&attributes, "group", Some(1))?.max(0) as usize,
+    };
+
+    Ok(conv2d(&input, &kernel, bias.as_ref(), &params)?.into_dyn())
+}
+
+fn max_pool_node(inputs: &[&TensorProto], node: &NodeProto) -> Result<ArrayD<f32>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let input = to_4d(
+        tensor_proto_to_ndarray::<f32>(first_input(inputs)?)?,
+        "input",
+    )?;
+
+    let kernel_shape = match get_ints_attribute(&attributes, "kernel_shape", None)?.as_slice() {
+        &[h, w] if h > 0 && w > 0 => [h as usize, w as usize],
+        values => {
+            return Err(OnnxError::InvalidValue(format!(
+                "Expected a 2D kernel_shape, got {:?}",
+                values
+            )))
+        }
+    };
+    let (_, _, height, width) = input.dim();
+    let (strides, pads, dilations) = spatial_params(&attributes, [height, width], kernel_shape)?;
+    if dilations != [1, 1] || get_int_attribute(&attributes, "ceil_mode", Some(0))? != 0 {
+        return Err(OnnxError::UnsupportedOperation(
+            "The reference MaxPool supports neither dilations nor ceil_mode".to_string(),
+        ));
+    }
+
+    let params = PoolParams {
+        kernel_shape,
+        strides,
+        pads,
+    };
+    Ok(max_pool2d(&input, &params)?.into_dyn())
+}
+
+fn matmul_node(
+    inputs: &[&TensorProto],
+    initializers: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<ArrayD<f32>, OnnxError> {
+    // Like the optimized kernel, B may be an input or an initializer.
+    let mut operands = inputs.iter().chain(initializers);
+    let a = operands
+        .next()
+        .ok_or(OnnxError::MissingInput("A".to_string()))?;
+    let b = operands
+        .next()
+        .ok_or(OnnxError::MissingInput("B".to_string()))?;
+    let product = matmul(
+        &tensor_proto_to_ndarray::<f32>(a)?,
+        &tensor_proto_to_ndarray::<f32>(b)?,
+    )?;
+    // The bias of a MatMul fused by the optimizer is not part of the specification.
+    let bias = operands
+        .next()
+        .map(|bias| tensor_proto_to_ndarray::<f32>(bias))
+        .transpose()?;
+    add_fused_bias(product, bias.as_ref(), node)
+}
+
+fn softmax_node(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    opset: i64,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let input = tensor_proto_to_ndarray::<f32>(first_input(inputs)?)?;
+    let default_axis = if opset < 13 { 1 } else { -1 };
+    let axis = get_int_attribute(&attributes, "axis", Some(default_axis))?;
+    let axis = if axis < 0 {
+        axis + input.ndim() as i64
+    } else {
+        axis
+    };
+    if axis < 0 || (opset >= 13 && axis >= input.ndim() as i64) {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis out of range for a tensor of rank {}",
+            input.ndim()
+        )));
+    }
+    if opset < 13 {
+        return softmax(&input, axis as usize);
+    }
+
+    // Since opset 13, only `axis` is normalized: it is moved last, so that the rows of the 2D
+    // coercion are its lanes, and moved back.
+    let last = input.ndim() - 1;
+    let mut order: Vec<usize> = (0..input.ndim()).collect();
+    order.swap(axis as usize, last);
+    let moved = input
+        .permuted_axes(order.clone())
+        .as_standard_layout()
+        .into_owned();
+    let output = softmax(&moved, last)?;
+    Ok(output
+        .permuted_axes(order)
+        .as_standard_layout()
+        .into_owned())
+}
@@ -1,7 +1,9 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
-use crate::onnx_rustime::ops::utils::tensor_proto_to_ndarray;
-use ndarray::ArrayD;
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_float_attribute, tensor_proto_to_ndarray, unidirectional_broadcast,
+};
+use ndarray::{ArrayD, Zip};
 
 use super::utils::convert_to_output_tensor;
 
@@ -38,13 +40,165 @@ pub fn relu(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxEr
         OnnxError::ConversionError("Failed to convert TensorProto to ndarray".into())
     })?;
 
-    let relu_values: Vec<f32> = input_nd_array
-        .iter()
-        .map(|&x| if x > 0.0 { x } else { 0.0 })
-        .collect();
+    convert_to_output_tensor(node, relu_ndarray(input_nd_array))
+}
+
+/// `relu` on a decoded tensor, whose values are replaced in place.
+pub(crate) fn relu_ndarray(mut values: ArrayD<f32>) -> ArrayD<f32> {
+    values.mapv_inplace(|x| if x > 0.0 { x } else { 0.0 });
+    values
+}
+
+/// `leaky_relu` - ONNX Node Implementation for the Leaky ReLU Activation (LeakyRelu)
+///
+/// Like ReLU, but the negative values are scaled by `alpha` instead of being set to 0:
+/// `y = x` for `x >= 0`, `y = alpha * x` otherwise.
+///
+/// # Attributes
+///
+/// - `alpha` (float): The slope of the negative values, 0.01 by default.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = leaky_relu(&input_tensor, &node)?;
+/// ```
+pub fn leaky_relu(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let alpha = get_float_attribute(&attributes, "alpha", Some(0.01))?;
+
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| if x < 0.0 { alpha * x } else { x }))
+}
+
+/// `elu` - ONNX Node Implementation for the Exponential Linear Unit Activation (Elu)
+///
+/// `y = x` for `x >= 0`, `y = alpha * (exp(x) - 1)` otherwise: the negative values saturate
+/// smoothly to `-alpha`.
+///
+/// # Attributes
+///
+/// - `alpha` (float): The value the negative values saturate to (negated), 1.0 by default.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = elu(&input_tensor, &node)?;
+/// ```
+pub fn elu(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let alpha = get_float_attribute(&attributes, "alpha", Some(1.0))?;
 
-    let result = ArrayD::from_shape_vec(input_nd_array.raw_dim(), relu_values)
-        .map_err(|_| OnnxError::ShapeMismatch("Failed to reshape!".into()))?;
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(
+        node,
+        x.mapv(|x| if x < 0.0 { alpha * x.exp_m1() } else { x }),
+    )
+}
+
+/// `selu` - ONNX Node Implementation for the Scaled Exponential Linear Unit Activation (Selu)
+///
+/// An Elu scaled by `gamma`: `y = gamma * x` for `x > 0`, `y = gamma * alpha * (exp(x) - 1)`
+/// otherwise. With the default constants, the activations of a network keep a mean of 0 and a
+/// variance of 1 (self-normalizing networks).
+///
+/// # Attributes
+///
+/// - `alpha` (float): 1.67326319217681884765625 by default.
+/// - `gamma` (float): 1.05070102214813232421875 by default.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = selu(&input_tensor, &node)?;
+/// ```
+pub fn selu(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let alpha = get_float_attribute(&attributes, "alpha", Some(1.6732632))?;
+    let gamma = get_float_attribute(&attributes, "gamma", Some(1.050701))?;
+
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(
+        node,
+        x.mapv(|x| {
+            if x > 0.0 {
+                gamma * x
+            } else {
+                gamma * alpha * x.exp_m1()
+            }
+        }),
+    )
+}
+
+/// `prelu` - ONNX Node Implementation for the Parametric ReLU Activation (PRelu)
+///
+/// A LeakyRelu whose slope is a learned tensor rather than an attribute: `y = x` for `x >= 0`,
+/// `y = slope * x` otherwise, the slope being unidirectionally broadcast to `X` (commonly one
+/// value per channel, of shape `[C, 1, 1]` against `[N, C, H, W]`).
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X` and `slope`, in the order of the node inputs. The slope is
+///   usually an initializer (a weight of the model), but may be computed by the graph.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of `X`. In case
+///   of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A missing `X` or `slope`, or a slope that can't be broadcast to `X`.
+///
+/// # Example
+///
+/// ```rust
+/// let activated = prelu(&[&input_tensor, &slope], &node)?;
+/// ```
+pub fn prelu(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    if inputs.len() < 2 {
+        return Err(OnnxError::MissingInput(
+            "PRelu requires the X and slope inputs".to_string(),
+        ));
+    }
+    let x = tensor_proto_to_ndarray::<f32>(inputs[0])?;
+    let slope = tensor_proto_to_ndarray::<f32>(inputs[1])?;
+    unidirectional_broadcast(x.shape(), slope.shape())?;
 
-    convert_to_output_tensor(node, result)
+    let y = Zip::from(&x)
+        .and_broadcast(&slope)
+        .map_collect(|&x, &slope| if x < 0.0 { slope * x } else { x });
+    convert_to_output_tensor(node, y)
 }
@@ -1,92 +1,96 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
+    extract_attributes, get_int_attribute, get_ints_attribute, tensor_proto_to_ndarray,
 };
-use ndarray::prelude::*;
 
-fn reshape_single_tensor(
-    input: ArrayViewD<f32>,
-    shape: &Vec<isize>,
+/// The shape of the output of a Reshape node.
+///
+/// At most one dimension of `shape` can be -1, inferred from the number of values of the input;
+/// a 0 copies the dimension of the input unless `allow_zero` is set, in which case it is an
+/// explicit 0 (and can't be combined with a -1).
+pub fn reshaped_shape(
+    input_shape: &[usize],
+    shape: &[isize],
     allow_zero: i64,
-) -> Result<Array<f32, IxDyn>, OnnxError> {
-    let mut inferred_dim = None;
-    let mut target_shape = shape.clone();
+) -> Result<Vec<usize>, OnnxError> {
+    if shape.iter().filter(|&&dim| dim == -1).count() > 1 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "At most one dimension of the shape of a Reshape can be -1, got {:?}",
+            shape
+        )));
+    }
+    if let Some(dim) = shape.iter().find(|&&dim| dim < -1) {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Invalid dimension {} in the shape {:?} of a Reshape",
+            dim, shape
+        )));
+    }
+    if allow_zero != 0 && shape.contains(&0) && shape.contains(&-1) {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The shape {:?} of a Reshape with allowzero can't contain both 0 and -1",
+            shape
+        )));
+    }
 
-    for (i, dim) in target_shape.iter_mut().enumerate() {
-        if *dim == -1 {
-            if inferred_dim.is_some() {
-                return Err(OnnxError::ShapeMismatch(
-                    "More than one inferred dimension!".into(),
-                ));
-            }
-            inferred_dim = Some(i);
-        } else if *dim == 0 {
-            if allow_zero == 0 {
-                *dim = input.shape()[i] as isize;
-            }
+    let mut target_shape = shape
+        .iter()
+        .enumerate()
+        .map(|(i, &dim)| match dim {
+            0 if allow_zero == 0 => input_shape.get(i).map(|&dim| dim as isize).ok_or_else(|| {
+                OnnxError::shape_mismatch(format!(
+                    "Cannot copy dimension {} of an input of shape {:?}",
+                    i, input_shape
+                ))
+            }),
+            _ => Ok(dim),
+        })
+        .collect::<Result<Vec<isize>, OnnxError>>()?;
+
+    let input_len = input_shape.iter().product::<usize>() as isize;
+    if let Some(idx) = target_shape.iter().position(|&dim| dim == -1) {
+        let product_of_dims: isize = target_shape.iter().filter(|&&dim| dim != -1).product();
+        if product_of_dims == 0 || input_len % product_of_dims != 0 {
+            return Err(OnnxError::shape_mismatch(format!(
+                "Cannot infer the -1 of {:?}: the {} values of an input of shape {:?} can't be \
+                 split into dimensions of {} values",
+                shape, input_len, input_shape, product_of_dims
+            )));
         }
+        target_shape[idx] = input_len / product_of_dims;
     }
 
-    if let Some(idx) = inferred_dim {
-        let product_of_dims: isize = target_shape.iter().filter(|&&dim| dim != -1).product();
-        println!("idx: {:?}", idx);
-        target_shape[idx] = (input.len() as isize) / product_of_dims;
+    if target_shape.iter().product::<isize>() != input_len {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Cannot reshape an input of shape {:?} ({} values) to {:?}",
+            input_shape, input_len, shape
+        )));
     }
 
-    Ok(input
-        .into_shape(target_shape.iter().map(|&x| x as usize).collect::<Vec<_>>())
-        .unwrap()
-        .to_owned())
+    Ok(target_shape.iter().map(|&x| x as usize).collect())
 }
 
-fn reshape_with_batches(
-    input: &TensorProto,
-    parameter: &TensorProto,
-    node: &NodeProto,
-) -> Result<TensorProto, OnnxError> {
-    // Extract node attributes.
-    let attributes = extract_attributes(node.get_attribute())?;
-    let allow_zero: i64 = get_int_attribute(&attributes, "allow_zero", Some(0))?;
-
-    // Retrieve the data tensor either from `inputs` or from `initializers`.
-    let input_nd_array = tensor_proto_to_ndarray::<f32>(&input)?;
-
-    // Determine the shape tensor.
-    let shape_tensor = parameter;
-
-    let mut target_shape: Vec<isize> = tensor_proto_to_ndarray::<i64>(shape_tensor)?
-        .into_raw_vec()
+/// The target shape stored in the shape tensor of a Reshape node.
+fn target_shape(shape_tensor: &TensorProto) -> Result<Vec<isize>, OnnxError> {
+    Ok(tensor_proto_to_ndarray::<i64>(shape_tensor)?
         .iter()
         .map(|&x| x as isize)
-        .collect();
-
-    target_shape[0] *= input_nd_array.shape()[0] as isize;
-
-    // Reshape each batch
-    let reshaped_batches =
-        reshape_single_tensor(input_nd_array.view(), &target_shape, allow_zero).unwrap();
-
-    convert_to_output_tensor(node, reshaped_batches)
+        .collect())
 }
 
-fn reshape_without_batches(
-    initializers: &Vec<&TensorProto>,
-    node: &NodeProto,
-) -> Result<TensorProto, OnnxError> {
-    // Extract node attributes.
+/// Reads the `allowzero` attribute of a Reshape node, 0 by default.
+fn allow_zero(node: &NodeProto) -> Result<i64, OnnxError> {
     let attributes = extract_attributes(node.get_attribute())?;
-    let allow_zero: i64 = get_int_attribute(&attributes, "allow_zero", Some(0))?;
-
-    let input_nd_array = tensor_proto_to_ndarray::<f32>(initializers[0])?;
-    let target_shape: Vec<isize> = tensor_proto_to_ndarray::<i64>(initializers[1])?
-        .into_raw_vec()
-        .iter()
-        .map(|&x| x as isize)
-        .collect();
+    get_int_attribute(&attributes, "allowzero", Some(0))
+}
 
-    let reshaped = reshape_single_tensor(input_nd_array.view(), &target_shape, allow_zero)?;
-    convert_to_output_tensor(node, reshaped)
+/// The shape of the output of a Reshape node whose target shape is the tensor `shape_tensor`.
+pub fn constant_reshaped_shape(
+    input_shape: &[usize],
+    shape_tensor: &TensorProto,
+    node: &NodeProto,
+) -> Result<Vec<usize>, OnnxError> {
+    reshaped_shape(input_shape, &target_shape(shape_tensor)?, allow_zero(node)?)
 }
 
 /// `reshape` - ONNX Node Implementation for Tensor Reshaping
@@ -96,23 +100,24 @@ fn reshape_without_batches(
 ///
 /// # Arguments
 ///
-/// * `inputs` - An optional reference to the input tensor. If provided, this tensor will be reshaped.
-/// * `initializers` - Contains tensors essential for the reshaping process. If `inputs` is `None`,
-///   the first tensor in `initializers` will be considered the data tensor. The subsequent tensor in
-///   `initializers` delineates the desired output shape.
+/// * `inputs` - The tensor `data`, of any type, and, since opset 5, the INT64 tensor `shape`.
+///   Before opset 5 the target shape is the `shape` attribute of the node.
 /// * `node` - A reference to the ONNX NodeProto containing node-specific attributes and directives
 ///   for the reshaping operation.
 ///
 /// # Returns
 ///
-/// * `Result<TensorProto, OnnxError>` - Outputs the reshaped tensor. In the event of an issue
+/// * `Result<TensorProto, OnnxError>` - Outputs the reshaped tensor, with the values and the type
+///   of `data`. In the event of an issue
 ///   during the reshaping process, an error (`OnnxError`) will be returned.
 ///
 /// # Errors
 ///
 /// Potential issues that can arise:
+/// * Missing `data`, or a missing target shape.
+/// * More than one -1 in the target shape, or a number of values that the other dimensions
+///   don't divide.
 /// * The reshaped tensor's dimensions do not match the defined shape.
-/// * Encountering incompatible attributes during the reshaping.
 ///
 /// # Notes
 ///
@@ -126,14 +131,38 @@ fn reshape_without_batches(
 ///
 /// Note that specifying a shape that includes both a 0 and a -1 value is invalid when the `allowzero`
 /// attribute is activated.
-pub fn reshape(
-    inputs: Option<&TensorProto>,
-    initializers: &Vec<&TensorProto>,
-    node: &NodeProto,
-) -> Result<TensorProto, OnnxError> {
-    if initializers.len() == 2 {
-        reshape_without_batches(initializers, node)
-    } else {
-        reshape_with_batches(inputs.unwrap(), initializers[0], node)
-    }
+///
+/// # Example
+///
+/// ```rust
+/// // Flattens every element of the batch.
+/// let flattened = reshape(&[&input_tensor, &shape_0_minus_1], &node)?;
+/// ```
+pub fn reshape(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let data = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let input_shape: Vec<usize> = data.get_dims().iter().map(|&dim| dim as usize).collect();
+
+    let target_shape = match inputs.get(1) {
+        Some(shape) => target_shape(shape)?,
+        None => {
+            let attributes = extract_attributes(node.get_attribute())?;
+            get_ints_attribute(&attributes, "shape", None)?
+                .into_iter()
+                .map(|dim| dim as isize)
+                .collect()
+        }
+    };
+    let output_shape = reshaped_shape(&input_shape, &target_shape, allow_zero(node)?)?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    // The values are stored in row-major order, which a reshape doesn't change.
+    let mut reshaped = (*data).clone();
+    reshaped.set_dims(output_shape.into_iter().map(|dim| dim as i64).collect());
+    reshaped.set_name(output_name.clone());
+    Ok(reshaped)
 }
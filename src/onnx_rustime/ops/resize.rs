@@ -0,0 +1,484 @@
+use crate::onnx_rustime::backend::helper::{Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_floats_attribute, get_int_attribute,
+    get_string_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis, IxDyn};
+use std::collections::HashMap;
+
+/// How a Resize maps a coordinate of the output to a coordinate of the input, along an axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CoordinateTransformation {
+    /// The centers of the pixels are scaled: `(x + 0.5) / scale - 0.5`.
+    HalfPixel,
+    /// Like `HalfPixel`, but 0 for an output of length 1.
+    PytorchHalfPixel,
+    /// The first and last pixels of the input and the output are aligned:
+    /// `x * (input - 1) / (output - 1)`.
+    AlignCorners,
+    /// `x / scale`.
+    Asymmetric,
+}
+
+impl CoordinateTransformation {
+    fn from_name(name: &str) -> Result<Self, OnnxError> {
+        match name {
+            "half_pixel" => Ok(CoordinateTransformation::HalfPixel),
+            "pytorch_half_pixel" => Ok(CoordinateTransformation::PytorchHalfPixel),
+            "align_corners" => Ok(CoordinateTransformation::AlignCorners),
+            "asymmetric" => Ok(CoordinateTransformation::Asymmetric),
+            other => Err(OnnxError::InvalidAttribute {
+                name: "coordinate_transformation_mode".to_string(),
+                detail: format!(
+                    "expected \"half_pixel\", \"pytorch_half_pixel\", \"align_corners\" or \
+                     \"asymmetric\", got \"{}\"",
+                    other
+                ),
+            }),
+        }
+    }
+
+    /// The coordinate of the input of length `input` matching the coordinate `x` of the output of
+    /// length `output`.
+    fn original(self, x: usize, scale: f32, input: usize, output: usize) -> f32 {
+        let x = x as f32;
+        match self {
+            CoordinateTransformation::PytorchHalfPixel if output <= 1 => 0.0,
+            CoordinateTransformation::HalfPixel | CoordinateTransformation::PytorchHalfPixel => {
+                (x + 0.5) / scale - 0.5
+            }
+            CoordinateTransformation::AlignCorners if output <= 1 => 0.0,
+            CoordinateTransformation::AlignCorners => {
+                x * (input as f32 - 1.0) / (output as f32 - 1.0)
+            }
+            CoordinateTransformation::Asymmetric => x / scale,
+        }
+    }
+}
+
+/// How the `nearest` mode of a Resize rounds a coordinate of the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NearestMode {
+    RoundPreferFloor,
+    RoundPreferCeil,
+    Floor,
+    Ceil,
+    /// The rounding of opset 10: down when upsampling, up when downsampling.
+    Simple,
+}
+
+impl NearestMode {
+    fn from_name(name: &str) -> Result<Self, OnnxError> {
+        match name {
+            "round_prefer_floor" => Ok(NearestMode::RoundPreferFloor),
+            "round_prefer_ceil" => Ok(NearestMode::RoundPreferCeil),
+            "floor" => Ok(NearestMode::Floor),
+            "ceil" => Ok(NearestMode::Ceil),
+            other => Err(OnnxError::InvalidAttribute {
+                name: "nearest_mode".to_string(),
+                detail: format!(
+                    "expected \"round_prefer_floor\", \"round_prefer_ceil\", \"floor\" or \
+                     \"ceil\", got \"{}\"",
+                    other
+                ),
+            }),
+        }
+    }
+
+    fn round(self, x: f32, scale: f32) -> f32 {
+        let halfway = x - x.floor() == 0.5;
+        match self {
+            NearestMode::RoundPreferFloor if halfway => x.floor(),
+            NearestMode::RoundPreferCeil if halfway => x.ceil(),
+            NearestMode::RoundPreferFloor | NearestMode::RoundPreferCeil => x.round(),
+            NearestMode::Floor => x.floor(),
+            NearestMode::Ceil => x.ceil(),
+            NearestMode::Simple if scale < 1.0 => x.ceil(),
+            NearestMode::Simple => x.floor(),
+        }
+    }
+}
+
+/// Reads a string attribute of a Resize node.
+fn string_attribute(
+    attributes: &HashMap<String, Attribute<String>>,
+    name: &str,
+    default: &str,
+) -> Result<String, OnnxError> {
+    get_string_attribute(attributes, name, Some(default.to_string()))
+}
+
+/// Resizes `x` along `axis` to `output` elements, with the nearest neighbors (`nearest` set) or a
+/// linear interpolation of the two closest elements.
+fn resize_axis(
+    x: ArrayD<f32>,
+    axis: usize,
+    output: usize,
+    scale: f32,
+    transformation: CoordinateTransformation,
+    nearest: Option<NearestMode>,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let input = x.len_of(Axis(axis));
+    let last = input as f32 - 1.0;
+    let originals = (0..output).map(|o| {
+        transformation
+            .original(o, scale, input, output)
+            .clamp(0.0, last)
+    });
+
+    match nearest {
+        Some(mode) => {
+            let indices: Vec<usize> = originals
+                .map(|original| mode.round(original, scale).clamp(0.0, last) as usize)
+                .collect();
+            Ok(x.select(Axis(axis), &indices))
+        }
+        None => {
+            let (mut lower, mut upper, mut weights) = (vec![], vec![], vec![]);
+            for original in originals {
+                let floor = original.floor();
+                lower.push(floor as usize);
+                upper.push((floor as usize + 1).min(input - 1));
+                weights.push(original - floor);
+            }
+            let mut shape = vec![1; x.ndim()];
+            shape[axis] = output;
+            let weights = ArrayD::from_shape_vec(IxDyn(&shape), weights)
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+            let (lower, upper) = (x.select(Axis(axis), &lower), x.select(Axis(axis), &upper));
+            Ok(lower * (1.0 - &weights) + upper * &weights)
+        }
+    }
+}
+
+/// `resize` - ONNX Node Implementation for Resize
+///
+/// Resizes the input, commonly the spatial axes of an NCHW tensor, to the lengths given by
+/// `sizes` or to the input lengths multiplied by `scales` (rounded down). Every element of the
+/// output takes the value of the input at the matching coordinate, per `mode`:
+///
+/// * `nearest` (default): the element closest to it, rounded per `nearest_mode`;
+/// * `linear`: the linear interpolation of the two elements around it along every resized axis
+///   (bilinear for two axes), the coordinate being clamped to the input.
+///
+/// The coordinates are matched per `coordinate_transformation_mode`. Before opset 11, the scales
+/// are the second input, the coordinates are `asymmetric`, and the nearest element is rounded
+/// down when upsampling, up when downsampling.
+///
+/// # Attributes
+///
+/// - `mode` (string): "nearest" (default) or "linear".
+/// - `coordinate_transformation_mode` (string): "half_pixel" (default), "pytorch_half_pixel",
+///   "align_corners" or "asymmetric", since opset 11.
+/// - `nearest_mode` (string): "round_prefer_floor" (default), "round_prefer_ceil", "floor" or
+///   "ceil", since opset 11.
+/// - `axes` (ints): The axes of `scales` or `sizes`, all of them by default (since opset 18).
+/// - `keep_aspect_ratio_policy` (string): With `sizes`, "stretch" (default) to resize every axis
+///   to its size, "not_larger" or "not_smaller" to scale the axes by the same factor, so that the
+///   output fits in or covers the sizes (since opset 18).
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `X` and, by position among the node inputs (`None` for an omitted
+///   optional input): since opset 11, the ignored `roi`, the FLOAT tensor `scales` and the INT64
+///   tensor `sizes`, only one of them being given (or not empty); before opset 11, the FLOAT
+///   tensor `scales`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+/// * `opset` - The version of the default operator set imported by the model, which selects the
+///   inputs and the defaults of the coordinates.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the resized tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X`, both or neither of `scales` and `sizes`, or one that doesn't have a value for
+///   every resized axis.
+/// * Scales that aren't positive.
+/// * An `OnnxError::InvalidAttribute` for an unknown mode, and an
+///   `OnnxError::UnsupportedOperation` for the `cubic` mode and antialiasing.
+///
+/// # Example
+///
+/// ```rust
+/// // Doubles the height and the width of an NCHW feature map.
+/// let upsampled = resize(&[Some(&features), None, Some(&scales_1_1_2_2)], &node, 13)?;
+/// ```
+pub fn resize(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| {
+        inputs
+            .get(index)
+            .copied()
+            .flatten()
+            // An empty tensor stands for an omitted input.
+            .filter(|tensor| tensor.get_dims().iter().product::<i64>() > 0)
+    };
+    let x = input(0).ok_or(OnnxError::MissingInput("X".to_string()))?;
+    let x = tensor_proto_to_ndarray::<f32>(x)?;
+    let rank = x.ndim();
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let nearest = match string_attribute(&attributes, "mode", "nearest")?.as_str() {
+        "nearest" if opset < 11 => Some(NearestMode::Simple),
+        "nearest" => Some(NearestMode::from_name(&string_attribute(
+            &attributes,
+            "nearest_mode",
+            "round_prefer_floor",
+        )?)?),
+        "linear" => None,
+        "cubic" => {
+            return Err(OnnxError::UnsupportedOperation(
+                "The cubic mode of Resize is not supported".to_string(),
+            ))
+        }
+        other => {
+            return Err(OnnxError::InvalidAttribute {
+                name: "mode".to_string(),
+                detail: format!("expected \"nearest\" or \"linear\", got \"{}\"", other),
+            })
+        }
+    };
+    let transformation = if opset < 11 {
+        CoordinateTransformation::Asymmetric
+    } else {
+        CoordinateTransformation::from_name(&string_attribute(
+            &attributes,
+            "coordinate_transformation_mode",
+            "half_pixel",
+        )?)?
+    };
+    if get_int_attribute(&attributes, "antialias", Some(0))? != 0 {
+        return Err(OnnxError::UnsupportedOperation(
+            "The antialiasing of Resize is not supported".to_string(),
+        ));
+    }
+
+    let axes = resized_axes(&attributes, rank)?;
+    let (scales, sizes) = if opset < 11 {
+        (input(1), None)
+    } else {
+        (input(2), input(3))
+    };
+
+    // The length and the scale of every axis of the output.
+    let mut lengths = x.shape().to_vec();
+    let mut scales_of_axes = vec![1.0f32; rank];
+    match (scales, sizes) {
+        (Some(scales), None) => {
+            let scales = tensor_proto_to_ndarray::<f32>(scales)?.into_raw_vec();
+            if scales.len() != axes.len() || scales.iter().any(|&scale| scale <= 0.0) {
+                return Err(OnnxError::InvalidValue(format!(
+                    "The scales {:?} of a Resize must be {} positive values",
+                    scales,
+                    axes.len()
+                )));
+            }
+            for (&axis, &scale) in axes.iter().zip(&scales) {
+                lengths[axis] = (x.shape()[axis] as f32 * scale).floor() as usize;
+                scales_of_axes[axis] = scale;
+            }
+        }
+        (None, Some(sizes)) => {
+            let sizes = tensor_proto_to_ndarray::<i64>(sizes)?.into_raw_vec();
+            if sizes.len() != axes.len() || sizes.iter().any(|&size| size < 0) {
+                return Err(OnnxError::InvalidValue(format!(
+                    "The sizes {:?} of a Resize must be {} values",
+                    sizes,
+                    axes.len()
+                )));
+            }
+            let ratios = axes
+                .iter()
+                .zip(&sizes)
+                .map(|(&axis, &size)| size as f32 / x.shape()[axis] as f32);
+            let policy = string_attribute(&attributes, "keep_aspect_ratio_policy", "stretch")?;
+            let common = match policy.as_str() {
+                "stretch" => None,
+                "not_larger" => ratios.clone().reduce(f32::min),
+                "not_smaller" => ratios.clone().reduce(f32::max),
+                other => {
+                    return Err(OnnxError::InvalidAttribute {
+                        name: "keep_aspect_ratio_policy".to_string(),
+                        detail: format!(
+                            "expected \"stretch\", \"not_larger\" or \"not_smaller\", got \"{}\"",
+                            other
+                        ),
+                    })
+                }
+            };
+            for ((&axis, &size), ratio) in axes.iter().zip(&sizes).zip(ratios) {
+                let scale = common.unwrap_or(ratio);
+                lengths[axis] = match common {
+                    Some(scale) => (x.shape()[axis] as f32 * scale).round() as usize,
+                    None => size as usize,
+                };
+                scales_of_axes[axis] = scale;
+            }
+        }
+        (Some(_), Some(_)) => {
+            return Err(OnnxError::InvalidValue(
+                "Only one of the scales and the sizes of a Resize can be given".to_string(),
+            ))
+        }
+        (None, None) => {
+            return Err(OnnxError::MissingInput(
+                "Resize requires the scales or the sizes input".to_string(),
+            ))
+        }
+    }
+
+    let resized = resize_axes(x, &lengths, &scales_of_axes, transformation, nearest)?;
+    convert_to_output_tensor(node, resized)
+}
+
+/// Resizes every axis of `x` to its length in `lengths`, by its scale in `scales`.
+fn resize_axes(
+    x: ArrayD<f32>,
+    lengths: &[usize],
+    scales: &[f32],
+    transformation: CoordinateTransformation,
+    nearest: Option<NearestMode>,
+) -> Result<ArrayD<f32>, OnnxError> {
+    let mut resized = x;
+    for (axis, (&length, &scale)) in lengths.iter().zip(scales).enumerate() {
+        if length == resized.len_of(Axis(axis)) && scale == 1.0 {
+            continue;
+        }
+        if resized.len_of(Axis(axis)) == 0 {
+            return Err(OnnxError::InvalidValue(format!(
+                "Cannot resize the empty axis {} to {} elements",
+                axis, length
+            )));
+        }
+        resized = resize_axis(resized, axis, length, scale, transformation, nearest)?;
+    }
+    Ok(resized)
+}
+
+/// `upsample` - ONNX Node Implementation for Upsample
+///
+/// The predecessor of Resize, deprecated in opset 10 but still found in older models: multiplies
+/// the length of every axis of the input by its scale (rounded down), like a Resize of opset 10.
+/// The coordinates are `asymmetric`, and in the `nearest` mode the element of the input is
+/// rounded down.
+///
+/// # Attributes
+///
+/// - `mode` (string): "nearest" (default) or "linear".
+/// - `scales` (floats): The scale of every axis, in opset 7.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `X` and, since opset 9, the FLOAT tensor `scales`, in the order of the
+///   node inputs.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+/// * `opset` - The version of the default operator set imported by the model, which selects where
+///   the scales come from.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the upsampled tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X` or `scales`.
+/// * Scales that aren't a value of at least 1 for every axis.
+/// * An `OnnxError::InvalidAttribute` for an unknown `mode`.
+///
+/// # Example
+///
+/// ```rust
+/// // Doubles the height and the width of an NCHW feature map, as in the tiny-yolov3 models.
+/// let upsampled = upsample(&[&features, &scales_1_1_2_2], &node, 9)?;
+/// ```
+pub fn upsample(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    let x = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("X".to_string()))?;
+    let x = tensor_proto_to_ndarray::<f32>(x)?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let nearest = match string_attribute(&attributes, "mode", "nearest")?.as_str() {
+        "nearest" => Some(NearestMode::Floor),
+        "linear" => None,
+        other => {
+            return Err(OnnxError::InvalidAttribute {
+                name: "mode".to_string(),
+                detail: format!("expected \"nearest\" or \"linear\", got \"{}\"", other),
+            })
+        }
+    };
+    let scales = if opset < 9 {
+        get_floats_attribute(&attributes, "scales", None)?
+    } else {
+        let scales = inputs
+            .get(1)
+            .ok_or(OnnxError::MissingInput("scales".to_string()))?;
+        tensor_proto_to_ndarray::<f32>(scales)?.into_raw_vec()
+    };
+    if scales.len() != x.ndim() || scales.iter().any(|&scale| scale < 1.0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The scales {:?} of an Upsample must be {} values of at least 1",
+            scales,
+            x.ndim()
+        )));
+    }
+
+    let lengths: Vec<usize> = x
+        .shape()
+        .iter()
+        .zip(&scales)
+        .map(|(&length, &scale)| (length as f32 * scale).floor() as usize)
+        .collect();
+    let resized = resize_axes(
+        x,
+        &lengths,
+        &scales,
+        CoordinateTransformation::Asymmetric,
+        nearest,
+    )?;
+    convert_to_output_tensor(node, resized)
+}
+
+/// The axes resized by a Resize: its `axes` attribute, normalized, or all the axes of the input.
+fn resized_axes(
+    attributes: &HashMap<String, Attribute<String>>,
+    rank: usize,
+) -> Result<Vec<usize>, OnnxError> {
+    let axes = match attributes.get("axes").and_then(|attr| attr.as_ints()) {
+        Some(axes) => axes,
+        None => return Ok((0..rank).collect()),
+    };
+    let mut normalized = Vec::with_capacity(axes.len());
+    for &axis in axes {
+        if axis < -(rank as i64) || axis >= rank as i64 {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )));
+        }
+        let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+        if normalized.contains(&axis) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is repeated in the axes {:?} of a Resize",
+                axis, axes
+            )));
+        }
+        normalized.push(axis);
+    }
+    Ok(normalized)
+}
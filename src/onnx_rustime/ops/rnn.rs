@@ -0,0 +1,75 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::recurrent::Recurrence;
+use crate::onnx_rustime::ops::utils::extract_attributes;
+
+/// `rnn` - ONNX Node Implementation for RNN
+///
+/// A vanilla recurrent layer, run over the sequences of the batch in one or both directions.
+/// With `f` its activation function (Tanh by default), each time step computes the hidden state
+/// from the input `Xt` and the previous hidden state `Ht-1`:
+///
+/// ```text
+/// Ht = f(Xt*(Wi^T) + Ht-1*(Ri^T) + Wbi + Rbi)
+/// ```
+///
+/// The weights of the reverse direction of a bidirectional RNN follow the ones of the forward
+/// direction. The sequence lengths are handled as in `lstm`.
+///
+/// # Attributes
+///
+/// - `hidden_size` (int): The size of the hidden state, the last dimension of `R` by default.
+/// - `direction` (string): `forward` (the default), `reverse` or `bidirectional`.
+/// - `activations` (strings): The function `f` of every direction, e.g. Tanh or Relu.
+/// - `activation_alpha`, `activation_beta` (floats): The parameters of the activation functions
+///   having them (e.g. LeakyRelu), in order.
+/// - `clip` (float): The bound of the inputs of the activations, unbounded by default.
+/// - `layout` (int): 1 for `X`, `Y` and the states with the batch first, 0 by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `X` (`[seq_length, batch_size, input_size]`), `W`
+///   (`[num_directions, hidden_size, input_size]`), `R` (`[num_directions, hidden_size,
+///   hidden_size]`) and the optional `B` (`[num_directions, 2 * hidden_size]`, the biases of `W`
+///   then the ones of `R`), `sequence_lens` (INT32 `[batch_size]`) and `initial_h`
+///   (`[num_directions, batch_size, hidden_size]`), by position among the node inputs (`None`
+///   for an omitted optional input). The omitted ones are zero, and the sequences span all of
+///   `X`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the outputs the node requests among `Y`
+///   (`[seq_length, num_directions, batch_size, hidden_size]`) and `Y_h` (the final hidden
+///   state, `[num_directions, batch_size, hidden_size]`). In case of an unsuccessful operation,
+///   it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X`, `W` or `R`.
+/// * Inputs whose shapes don't match the sizes of the node, or sequence lengths out of range.
+/// * An unknown `direction` or activation function, or a wrong number of activations.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = rnn(&[Some(&x), Some(&w), Some(&r), None, Some(&sequence_lens)], &node)?;
+/// let (y, y_h) = (&outputs[0], &outputs[1]);
+/// ```
+pub fn rnn(
+    inputs: &[Option<&TensorProto>],
+    node: &NodeProto,
+) -> Result<Vec<TensorProto>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let recurrence = Recurrence::new("RNN", inputs, &attributes, 1, &["Tanh"])?;
+
+    recurrence.run(
+        node,
+        vec![recurrence.initial_h.clone()],
+        |direction, inputs, states| {
+            let gates = inputs + recurrence.projected_state(direction, &states[0]);
+            vec![gates.mapv(|x| recurrence.activate(direction, 0, x))]
+        },
+    )
+}
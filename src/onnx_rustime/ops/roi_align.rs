@@ -0,0 +1,264 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_float_attribute, get_int_attribute,
+    get_string_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{Array4, ArrayView2, Axis, Ix4};
+
+/// A sampling point of a bin: the four pixels around it and their bilinear weights.
+struct Sample {
+    pixels: [(usize, usize); 4],
+    weights: [f32; 4],
+}
+
+impl Sample {
+    /// The sampling point `(y, x)` of a feature map of the given size. A point more than a pixel
+    /// outside the feature map has zero weights; one less than a pixel outside is moved to its
+    /// border.
+    fn new(y: f32, x: f32, height: usize, width: usize) -> Self {
+        let outside = y < -1.0 || y > height as f32 || x < -1.0 || x > width as f32;
+        if outside || height == 0 || width == 0 {
+            return Sample {
+                pixels: [(0, 0); 4],
+                weights: [0.0; 4],
+            };
+        }
+        // The pixels before and after a coordinate along an axis, and its distance from the first.
+        let around = |v: f32, len: usize| {
+            let low = v.max(0.0) as usize;
+            match low >= len - 1 {
+                true => (len - 1, len - 1, 0.0),
+                false => (low, low + 1, v.max(0.0) - low as f32),
+            }
+        };
+        let (y_low, y_high, ly) = around(y, height);
+        let (x_low, x_high, lx) = around(x, width);
+        let (hy, hx) = (1.0 - ly, 1.0 - lx);
+        Sample {
+            pixels: [
+                (y_low, x_low),
+                (y_low, x_high),
+                (y_high, x_low),
+                (y_high, x_high),
+            ],
+            weights: [hy * hx, hy * lx, ly * hx, ly * lx],
+        }
+    }
+
+    /// The weighted values of the four pixels around the point.
+    fn terms(&self, channel: &ArrayView2<'_, f32>) -> [f32; 4] {
+        std::array::from_fn(|i| self.weights[i] * channel[self.pixels[i]])
+    }
+}
+
+/// `roi_align` - ONNX Node Implementation for RoiAlign
+///
+/// Pools the regions of interest of the feature maps of a batch into fixed-size maps, e.g. the
+/// proposals of the second stage of a Faster or Mask R-CNN. Every region is split into
+/// `output_height` by `output_width` bins, and every bin takes the average (or the maximum) of a
+/// grid of points, interpolated bilinearly from the four pixels around each of them.
+///
+/// The regions are scaled by `spatial_scale`. With `coordinate_transformation_mode` set to
+/// `half_pixel` (the default since opset 16, as `aligned=True` in torchvision) they are shifted by
+/// half a pixel; with `output_half_pixel` (the behavior of opset 10, as `aligned=False`) they
+/// aren't, and are at least one pixel wide and high.
+///
+/// # Attributes
+///
+/// - `mode` (string): "avg" (default) or "max". The maximum of a point is the one of its four
+///   weighted pixels, as in onnxruntime.
+/// - `output_height`, `output_width` (int): The size of the pooled maps, 1 by default.
+/// - `sampling_ratio` (int): The number of points along each axis of a bin, or 0 (default) for
+///   `ceil(roi_height / output_height)` and `ceil(roi_width / output_width)`.
+/// - `spatial_scale` (float): The scale of the regions to the feature maps, 1.0 by default.
+/// - `coordinate_transformation_mode` (string): "half_pixel" or "output_half_pixel", since opset
+///   16.
+///
+/// # Arguments
+///
+/// * `inputs` - The FLOAT tensors `X` (`[N, C, H, W]`) and `rois` (`[num_rois, 4]`, the corners
+///   `[x1, y1, x2, y2]` of the regions), and the INT64 tensor `batch_indices` (`[num_rois]`, the
+///   image of each region).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+/// * `opset` - The version of the default operator set imported by the model, which selects the
+///   default `coordinate_transformation_mode`.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the pooled maps, of shape
+///   `[num_rois, C, output_height, output_width]`. In case of an unsuccessful operation, it
+///   returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or shapes that don't match.
+/// * A batch index out of range, or an output size that isn't positive.
+/// * An unknown `mode` or `coordinate_transformation_mode`.
+///
+/// # Example
+///
+/// ```rust
+/// // The 7x7 maps of the proposals of a Faster R-CNN, on a feature map of stride 16.
+/// let pooled = roi_align(&[&features, &proposals, &batch_indices], &node, 16)?;
+/// ```
+pub fn roi_align(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    let x = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("X".to_string()))?;
+    let rois = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("rois".to_string()))?;
+    let batch_indices = inputs
+        .get(2)
+        .ok_or(OnnxError::MissingInput("batch_indices".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let max_mode =
+        match get_string_attribute(&attributes, "mode", Some("avg".to_string()))?.as_str() {
+            "avg" => false,
+            "max" => true,
+            other => {
+                return Err(OnnxError::InvalidAttribute {
+                    name: "mode".to_string(),
+                    detail: format!("expected \"avg\" or \"max\", got \"{}\"", other),
+                })
+            }
+        };
+    let default_transformation = if opset < 16 {
+        "output_half_pixel"
+    } else {
+        "half_pixel"
+    };
+    let half_pixel = match get_string_attribute(
+        &attributes,
+        "coordinate_transformation_mode",
+        Some(default_transformation.to_string()),
+    )?
+    .as_str()
+    {
+        "half_pixel" => true,
+        "output_half_pixel" => false,
+        other => {
+            return Err(OnnxError::InvalidAttribute {
+                name: "coordinate_transformation_mode".to_string(),
+                detail: format!(
+                    "expected \"half_pixel\" or \"output_half_pixel\", got \"{}\"",
+                    other
+                ),
+            })
+        }
+    };
+    let output_size = |name: &str| match get_int_attribute(&attributes, name, Some(1))? {
+        size if size > 0 => Ok(size as usize),
+        size => Err(OnnxError::InvalidAttribute {
+            name: name.to_string(),
+            detail: format!("expected a positive size, got {}", size),
+        }),
+    };
+    let (output_height, output_width) =
+        (output_size("output_height")?, output_size("output_width")?);
+    let sampling_ratio = get_int_attribute(&attributes, "sampling_ratio", Some(0))?;
+    let spatial_scale = get_float_attribute(&attributes, "spatial_scale", Some(1.0))?;
+
+    let x = tensor_proto_to_ndarray::<f32>(x)?;
+    let x_shape = x.shape().to_vec();
+    let x = x.into_dimensionality::<Ix4>().map_err(|_| {
+        OnnxError::shape_mismatch(format!(
+            "RoiAlign expects an input of shape [N, C, H, W], got {:?}",
+            x_shape
+        ))
+    })?;
+    let rois = tensor_proto_to_ndarray::<f32>(rois)?;
+    let batch_indices = tensor_proto_to_ndarray::<i64>(batch_indices)?;
+    let num_rois = batch_indices.len();
+    if rois.shape() != [num_rois, 4] || batch_indices.ndim() != 1 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "RoiAlign expects rois of shape [num_rois, 4] and batch_indices of shape [num_rois], \
+             got {:?} and {:?}",
+            rois.shape(),
+            batch_indices.shape()
+        )));
+    }
+
+    let (batch_size, channels, height, width) = x.dim();
+    let mut output = Array4::<f32>::zeros((num_rois, channels, output_height, output_width));
+    for (roi, (&batch, mut pooled)) in batch_indices
+        .iter()
+        .zip(output.outer_iter_mut())
+        .enumerate()
+    {
+        if batch < 0 || batch as usize >= batch_size {
+            return Err(OnnxError::InvalidValue(format!(
+                "The batch index {} of region {} is out of range for a batch of {}",
+                batch, roi, batch_size
+            )));
+        }
+
+        let offset = if half_pixel { 0.5 } else { 0.0 };
+        let corner = |i: usize| rois[[roi, i]] * spatial_scale - offset;
+        let (start_x, start_y) = (corner(0), corner(1));
+        let (mut roi_width, mut roi_height) = (corner(2) - start_x, corner(3) - start_y);
+        if !half_pixel {
+            roi_width = roi_width.max(1.0);
+            roi_height = roi_height.max(1.0);
+        }
+        let bin_height = roi_height / output_height as f32;
+        let bin_width = roi_width / output_width as f32;
+        let grid_height = match sampling_ratio {
+            ratio if ratio > 0 => ratio as usize,
+            _ => bin_height.ceil() as usize,
+        };
+        let grid_width = match sampling_ratio {
+            ratio if ratio > 0 => ratio as usize,
+            _ => bin_width.ceil() as usize,
+        };
+        let count = (grid_height * grid_width).max(1) as f32;
+
+        // The sampling points of every bin, shared by the channels.
+        let bins: Vec<Vec<Sample>> = (0..output_height * output_width)
+            .map(|bin| {
+                let (bin_y, bin_x) = (bin / output_width, bin % output_width);
+                (0..grid_height * grid_width)
+                    .map(|point| {
+                        let (iy, ix) = (point / grid_width, point % grid_width);
+                        let y = start_y
+                            + bin_y as f32 * bin_height
+                            + (iy as f32 + 0.5) * bin_height / grid_height as f32;
+                        let x = start_x
+                            + bin_x as f32 * bin_width
+                            + (ix as f32 + 0.5) * bin_width / grid_width as f32;
+                        Sample::new(y, x, height, width)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let image = x.index_axis(Axis(0), batch as usize);
+        for (channel, mut maps) in image.outer_iter().zip(pooled.outer_iter_mut()) {
+            for (value, samples) in maps.iter_mut().zip(&bins) {
+                *value = match max_mode {
+                    false => {
+                        samples
+                            .iter()
+                            .flat_map(|sample| sample.terms(&channel))
+                            .sum::<f32>()
+                            / count
+                    }
+                    true => samples
+                        .iter()
+                        .map(|sample| sample.terms(&channel).into_iter().fold(f32::MIN, f32::max))
+                        .reduce(f32::max)
+                        .unwrap_or(0.0),
+                };
+            }
+        }
+    }
+
+    convert_to_output_tensor(node, output.into_dyn())
+}
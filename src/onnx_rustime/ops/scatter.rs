@@ -0,0 +1,298 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::gather::{gather_indices, index_position};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, get_string_attribute, ndarray_to_tensor_proto,
+    tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, Axis, Zip};
+use std::ops::{Add, Mul};
+
+/// How a Scatter combines an update with the value it is written over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reduction {
+    /// The update replaces the value: of several updates of the same position, the last one wins.
+    None,
+    Add,
+    Mul,
+    Min,
+    Max,
+}
+
+impl Reduction {
+    /// Reads the `reduction` attribute of a Scatter node, "none" by default.
+    fn from_node(node: &NodeProto) -> Result<Self, OnnxError> {
+        let attributes = extract_attributes(node.get_attribute())?;
+        match get_string_attribute(&attributes, "reduction", Some("none".to_string()))?.as_str() {
+            "none" => Ok(Reduction::None),
+            "add" => Ok(Reduction::Add),
+            "mul" => Ok(Reduction::Mul),
+            "min" => Ok(Reduction::Min),
+            "max" => Ok(Reduction::Max),
+            other => Err(OnnxError::InvalidAttribute {
+                name: "reduction".to_string(),
+                detail: format!(
+                    "expected \"none\", \"add\", \"mul\", \"min\" or \"max\", got \"{}\"",
+                    other
+                ),
+            }),
+        }
+    }
+
+    fn apply<T>(self, value: &mut T, update: T)
+    where
+        T: Copy + PartialOrd + Add<Output = T> + Mul<Output = T>,
+    {
+        *value = match self {
+            Reduction::None => update,
+            Reduction::Add => *value + update,
+            Reduction::Mul => *value * update,
+            Reduction::Min if update < *value => update,
+            Reduction::Max if update > *value => update,
+            Reduction::Min | Reduction::Max => *value,
+        };
+    }
+}
+
+/// The tensors `data`, `indices` and `updates` of a Scatter node.
+fn scatter_operands<'a>(
+    inputs: &[&'a TensorProto],
+    op_type: &str,
+) -> Result<(&'a TensorProto, ArrayD<i64>, &'a TensorProto), OnnxError> {
+    match inputs {
+        [data, indices, updates, ..] => Ok((*data, gather_indices(indices)?, *updates)),
+        _ => Err(OnnxError::MissingInput(format!(
+            "{} requires the data, indices and updates inputs",
+            op_type
+        ))),
+    }
+}
+
+/// Writes every update at the position of its index, with the coordinate along `axis` replaced by
+/// the index.
+fn scatter_elements_axis<T>(
+    data: &mut ArrayD<T>,
+    indices: &ArrayD<i64>,
+    updates: &ArrayD<T>,
+    axis: usize,
+    reduction: Reduction,
+) -> Result<(), OnnxError>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Mul<Output = T>,
+{
+    if indices.shape() != updates.shape()
+        || indices.ndim() != data.ndim()
+        || (0..data.ndim()).any(|d| d != axis && indices.shape()[d] > data.shape()[d])
+    {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The indices of shape {:?} and the updates of shape {:?} of a ScatterElements must \
+             have the same shape, of the rank of the data of shape {:?} and no longer \
+             dimensions but along axis {}",
+            indices.shape(),
+            updates.shape(),
+            data.shape(),
+            axis
+        )));
+    }
+
+    let length = data.len_of(Axis(axis));
+    for ((mut position, &index), &update) in indices.indexed_iter().zip(updates.iter()) {
+        position[axis] = index_position(index, length, axis)?;
+        reduction.apply(&mut data[&position], update);
+    }
+    Ok(())
+}
+
+/// `scatter_elements` - ONNX Node Implementation for ScatterElements
+///
+/// The counterpart of GatherElements: a copy of `data` where every update is written at the
+/// position of its index, with the coordinate along `axis` replaced by the index. For 2D tensors
+/// and `axis = 1`, `output[i][indices[i][j]] = updates[i][j]`.
+///
+/// The updates are applied in the order of the indices, so that of several updates of the same
+/// position the last one wins, unless `reduction` combines them with the value of the data.
+///
+/// Negative indices count from the end of the axis.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to scatter along, 0 by default. Negative values count from the end.
+/// - `reduction` (string): "none" (default) to replace the values, "add" or "mul" (since opset 16),
+///   "min" or "max" (since opset 18) to combine them with the updates.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `data` (FLOAT, FLOAT16 or INT64), `indices` (INT32 or INT64) and
+///   `updates` (of the type of `data` and the shape of `indices`), in the order of the node
+///   inputs.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the updated copy of `data` (FLOAT for a FLOAT16
+///   `data`). In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`, `indices` or `updates`.
+/// * An `axis` out of range, or an index out of the range of the axis.
+/// * Indices and updates of different shapes, of another rank than the data, or longer along an
+///   axis other than `axis`.
+/// * An `OnnxError::InvalidAttribute` for an unknown `reduction`.
+///
+/// # Example
+///
+/// ```rust
+/// let updated = scatter_elements(&[&data, &indices, &updates], &node)?;
+/// ```
+pub fn scatter_elements(
+    inputs: &[&TensorProto],
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let (data, indices, updates) = scatter_operands(inputs, "ScatterElements")?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(0))?;
+    let rank = data.get_dims().len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    let reduction = Reduction::from_node(node)?;
+
+    if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let mut data = tensor_proto_to_ndarray::<i64>(data)?;
+        let updates = tensor_proto_to_ndarray::<i64>(updates)?;
+        scatter_elements_axis(&mut data, &indices, &updates, axis, reduction)?;
+        ndarray_to_tensor_proto::<i64>(data, output_name)
+    } else {
+        let mut data = tensor_proto_to_ndarray::<f32>(data)?;
+        let updates = tensor_proto_to_ndarray::<f32>(updates)?;
+        scatter_elements_axis(&mut data, &indices, &updates, axis, reduction)?;
+        ndarray_to_tensor_proto::<f32>(data, output_name)
+    }
+}
+
+/// Writes every update slice at the slice of `data` addressed by its tuple of indices.
+fn scatter_nd_slices<T>(
+    data: &mut ArrayD<T>,
+    indices: &ArrayD<i64>,
+    updates: &ArrayD<T>,
+    reduction: Reduction,
+) -> Result<(), OnnxError>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Mul<Output = T>,
+{
+    let (r, q) = (data.ndim(), indices.ndim());
+    let k = indices.shape().last().copied().unwrap_or(0);
+    let mut updates_shape = indices.shape()[..q.saturating_sub(1)].to_vec();
+    updates_shape.extend_from_slice(&data.shape()[k.min(r)..]);
+    if q == 0 || k < 1 || k > r || updates.shape() != updates_shape.as_slice() {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Cannot scatter updates of shape {:?} into data of shape {:?} with indices of shape \
+             {:?}",
+            updates.shape(),
+            data.shape(),
+            indices.shape()
+        )));
+    }
+
+    // The dimensions of the index tuples are flattened into one.
+    let tuples: usize = indices.shape()[..q - 1].iter().product();
+    let indices = indices
+        .view()
+        .into_shape(vec![tuples, k])
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+    let mut slices_shape = vec![tuples];
+    slices_shape.extend_from_slice(&data.shape()[k..]);
+    let updates = updates
+        .view()
+        .into_shape(slices_shape)
+        .map_err(|e| OnnxError::shape_mismatch(e.to_string()))?;
+
+    for (tuple, update) in indices.outer_iter().zip(updates.outer_iter()) {
+        let mut slice = data.view_mut();
+        for (axis, &index) in tuple.iter().enumerate() {
+            let position = index_position(index, slice.len_of(Axis(0)), axis)?;
+            slice = slice.index_axis_move(Axis(0), position);
+        }
+        Zip::from(slice)
+            .and(update)
+            .for_each(|value, &update| reduction.apply(value, update));
+    }
+    Ok(())
+}
+
+/// `scatter_nd` - ONNX Node Implementation for ScatterND
+///
+/// The counterpart of GatherND: a copy of `data` where every tuple of `k` indices, in the last
+/// dimension of `indices`, addresses the slice of the first `k` axes of the data that the matching
+/// slice of `updates` is written to. The updates have the shape
+/// `indices.shape[..q - 1] + data.shape[k..]`, e.g. the detections written into the rows of a
+/// fixed-size buffer.
+///
+/// The updates are applied in the order of the indices, so that of several updates of the same
+/// slice the last one wins, unless `reduction` combines them with the value of the data.
+///
+/// Negative indices count from the end of their axis.
+///
+/// # Attributes
+///
+/// - `reduction` (string): "none" (default) to replace the values, "add" or "mul" (since opset 16),
+///   "min" or "max" (since opset 18) to combine them with the updates.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `data` (FLOAT, FLOAT16 or INT64), `indices` (INT32 or INT64) and
+///   `updates` (of the type of `data`), in the order of the node inputs.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the updated copy of `data` (FLOAT for a FLOAT16
+///   `data`). In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`, `indices` or `updates`.
+/// * Index tuples longer than the rank of the data, or updates of another shape than
+///   `indices.shape[..q - 1] + data.shape[k..]`.
+/// * An index out of the range of its axis.
+/// * An `OnnxError::InvalidAttribute` for an unknown `reduction`.
+///
+/// # Example
+///
+/// ```rust
+/// let buffer = scatter_nd(&[&empty_buffer, &rows, &detections], &node)?;
+/// ```
+pub fn scatter_nd(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (data, indices, updates) = scatter_operands(inputs, "ScatterND")?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let reduction = Reduction::from_node(node)?;
+
+    if data.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let mut data = tensor_proto_to_ndarray::<i64>(data)?;
+        let updates = tensor_proto_to_ndarray::<i64>(updates)?;
+        scatter_nd_slices(&mut data, &indices, &updates, reduction)?;
+        ndarray_to_tensor_proto::<i64>(data, output_name)
+    } else {
+        let mut data = tensor_proto_to_ndarray::<f32>(data)?;
+        let updates = tensor_proto_to_ndarray::<f32>(updates)?;
+        scatter_nd_slices(&mut data, &indices, &updates, reduction)?;
+        ndarray_to_tensor_proto::<f32>(data, output_name)
+    }
+}
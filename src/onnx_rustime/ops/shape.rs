@@ -0,0 +1,90 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto,
+};
+use ndarray::{arr0, Array1};
+
+/// The name of the output of a node, and the tensor of its first input.
+fn operand_and_output<'a>(
+    inputs: &[&'a TensorProto],
+    node: &'a NodeProto,
+) -> Result<(&'a TensorProto, &'a String), OnnxError> {
+    let data = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    Ok((data, output_name))
+}
+
+/// `shape` - ONNX Node Implementation for Shape
+///
+/// The dimensions of the input, as a 1D INT64 tensor, e.g. to compute the target shape of a
+/// Reshape from the one of a tensor of the graph. Only the dimensions of the input are read,
+/// whatever its element type.
+///
+/// Since opset 15, `start` and `end` select the dimensions `start..end`, like a Python slice:
+/// negative values count from the end, and the values out of range are clamped.
+///
+/// # Attributes
+///
+/// - `start` (int): The first dimension, 0 by default.
+/// - `end` (int): The dimension after the last one, the rank of the input by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT64 tensor of the dimensions. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// // The spatial dimensions of an NCHW tensor.
+/// let spatial_shape = shape(&[&features], &node_with_start_2)?;
+/// ```
+pub fn shape(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (data, output_name) = operand_and_output(inputs, node)?;
+    let dims = data.get_dims();
+    let rank = dims.len() as i64;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let clamped = |dim: i64| if dim < 0 { dim + rank } else { dim }.clamp(0, rank) as usize;
+    let start = clamped(get_int_attribute(&attributes, "start", Some(0))?);
+    let end = clamped(get_int_attribute(&attributes, "end", Some(rank))?);
+
+    let selected = dims[start..end.max(start)].to_vec();
+    ndarray_to_tensor_proto::<i64>(Array1::from(selected).into_dyn(), output_name)
+}
+
+/// `size` - ONNX Node Implementation for Size
+///
+/// The number of elements of the input, the product of its dimensions, as an INT64 scalar.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the INT64 scalar. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let count = size(&[&input_tensor], &node)?;
+/// ```
+pub fn size(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let (data, output_name) = operand_and_output(inputs, node)?;
+    let count: i64 = data.get_dims().iter().product();
+    ndarray_to_tensor_proto::<i64>(arr0(count).into_dyn(), output_name)
+}
@@ -0,0 +1,148 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, get_ints_attribute, tensor_proto_to_ndarray,
+};
+use ndarray::{Axis, Slice};
+
+/// The range of the elements of an axis of length `length` taken by a Slice, as the bounds of an
+/// ndarray `Slice` (whose negative step walks the range from its end).
+///
+/// The negative `start` and `end` count from the end of the axis, and both are clamped to the
+/// axis: to `[0, length]` for a positive step. For a negative one `start` is clamped to
+/// `[0, length - 1]` and `end` to `[-1, length - 1]`, so that an `end` of `i64::MIN` (or any
+/// other far negative value) reverses the axis up to its first element, and a far negative
+/// `start` still takes the first element (unlike NumPy, which takes nothing).
+fn slice_range(start: i64, end: i64, step: i64, length: usize) -> Slice {
+    let length = length as i64;
+    let from_end = |index: i64| if index < 0 { index + length } else { index };
+    let (start, end) = (from_end(start), from_end(end));
+
+    let (first, last) = if step > 0 {
+        (start.clamp(0, length), end.clamp(0, length))
+    } else {
+        // Walking backwards from `start` down to `end` (excluded) takes the elements of
+        // `end + 1..start + 1`, from the last one.
+        // `start` stays -1 on an empty axis, so that nothing is taken.
+        let (start, end) = (start.max(0).min(length - 1), end.clamp(-1, length - 1));
+        (end + 1, start + 1)
+    };
+    let last = last.max(first);
+    Slice::new(first as isize, Some(last as isize), step as isize)
+}
+
+/// Reads an INT64 input of a Slice, `None` if the node omits it.
+fn indices(input: Option<&TensorProto>) -> Result<Option<Vec<i64>>, OnnxError> {
+    input
+        .map(|tensor| Ok(tensor_proto_to_ndarray::<i64>(tensor)?.into_raw_vec()))
+        .transpose()
+}
+
+/// `slice` - ONNX Node Implementation for Slice
+///
+/// Takes the elements of the input from `starts[i]` to `ends[i]` (excluded), every `steps[i]`,
+/// along each axis `axes[i]`, like `x[start:end:step]` in NumPy. The other axes are taken whole.
+///
+/// A negative `start` or `end` counts from the end of the axis, and both are clamped to the axis,
+/// so that an `end` past its length (commonly `INT64_MAX`) means "to the end". A negative step
+/// walks the axis backwards from `start`, e.g. `starts = [-1]`, `ends = [INT64_MIN]`,
+/// `steps = [-1]` reverses it.
+///
+/// The indices moved between operator sets: before opset 10 they are the `starts`, `ends` and
+/// `axes` attributes (without steps), since opset 10 the inputs of the same names plus `steps`.
+///
+/// # Attributes
+///
+/// - `starts` (ints): The first index along each axis, before opset 10.
+/// - `ends` (ints): The index past the last one along each axis, before opset 10.
+/// - `axes` (ints): The axes to slice, before opset 10. `[0, 1, ...]` by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 10, the INT64 tensors `starts`, `ends` and the
+///   optional `axes` and `steps`, by position among the node inputs (`None` for an omitted
+///   optional input).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the slice of the input. In case of an
+///   unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`, `starts` or `ends`.
+/// * `starts`, `ends`, `axes` and `steps` of different lengths.
+/// * Repeated axes, or axes out of range.
+/// * A step of 0.
+///
+/// # Example
+///
+/// ```rust
+/// // The last element of every row.
+/// let last = slice(&[Some(&data), Some(&minus_one), Some(&int64_max), Some(&one)], &node)?;
+/// ```
+pub fn slice(inputs: &[Option<&TensorProto>], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = |index: usize| inputs.get(index).copied().flatten();
+    let data = input(0).ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let data = tensor_proto_to_ndarray::<f32>(data)?;
+    let rank = data.ndim() as i64;
+
+    let (starts, ends, axes, steps) = if inputs.len() > 1 {
+        (
+            indices(input(1))?.ok_or(OnnxError::MissingInput("starts".to_string()))?,
+            indices(input(2))?.ok_or(OnnxError::MissingInput("ends".to_string()))?,
+            indices(input(3))?,
+            indices(input(4))?,
+        )
+    } else {
+        let attributes = extract_attributes(node.get_attribute())?;
+        (
+            get_ints_attribute(&attributes, "starts", None)?,
+            get_ints_attribute(&attributes, "ends", None)?,
+            attributes
+                .get("axes")
+                .and_then(|attr| attr.as_ints())
+                .cloned(),
+            None,
+        )
+    };
+    let axes = axes.unwrap_or_else(|| (0..starts.len() as i64).collect());
+    let steps = steps.unwrap_or_else(|| vec![1; starts.len()]);
+    if ends.len() != starts.len() || axes.len() != starts.len() || steps.len() != starts.len() {
+        return Err(OnnxError::shape_mismatch(format!(
+            "The starts {:?}, ends {:?}, axes {:?} and steps {:?} of a Slice must have the same \
+             length",
+            starts, ends, axes, steps
+        )));
+    }
+
+    let mut sliced = data.view();
+    let mut seen = vec![false; data.ndim()];
+    for (((&start, &end), &axis), &step) in starts.iter().zip(&ends).zip(&axes).zip(&steps) {
+        if axis < -rank || axis >= rank {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )));
+        }
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        if std::mem::replace(&mut seen[axis], true) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is repeated in the axes {:?} of a Slice",
+                axis, axes
+            )));
+        }
+        if step == 0 {
+            return Err(OnnxError::InvalidValue(format!(
+                "The step of axis {} of a Slice can't be 0",
+                axis
+            )));
+        }
+        let range = slice_range(start, end, step, sliced.len_of(Axis(axis)));
+        sliced.slice_axis_inplace(Axis(axis), range);
+    }
+
+    convert_to_output_tensor(node, sliced.to_owned())
+}
@@ -1,11 +1,10 @@
 use crate::onnx_rustime::backend::helper::OnnxError;
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::*;
-use crate::onnx_rustime::ops::exp::exp;
-use crate::onnx_rustime::ops::reduce_sum::reduce_sum;
 use crate::onnx_rustime::ops::utils::{
-    convert_to_output_tensor, stack_along_batch_dimension, tensor_proto_to_ndarray,
+    convert_to_output_tensor, extract_attributes, get_int_attribute, tensor_proto_to_ndarray,
 };
 use ndarray::prelude::*;
+use ndarray::Zip;
 
 /// `softmax` - ONNX Node Implementation for Softmax Normalization
 ///
@@ -15,10 +14,15 @@ use ndarray::prelude::*;
 ///
 /// Softmax(input, axis) = Exp(input) / ReduceSum(Exp(input), axis=axis, keepdims=1)
 ///
+/// The maximum of every normalized group is subtracted before the exponential, which leaves the
+/// result unchanged but keeps large logits from overflowing to infinity.
+///
 /// # Arguments
 ///
 /// * `input` - Reference to the input tensor.
 /// * `node` - A reference to the ONNX NodeProto, housing attributes specific to the Softmax operation.
+/// * `opset` - The version of the default operator set imported by the model, which selects the
+///   semantics of `axis`.
 ///
 /// # Returns
 ///
@@ -27,36 +31,70 @@ use ndarray::prelude::*;
 ///
 /// # Attributes
 ///
-/// * `axis` - Specifies the dimension along which the Softmax computation should be performed. It defaults
-///   to `-1`. A negative value denotes that the counting of dimensions should be from the back. Valid range
-///   is given by `[-r, r-1]`, where `r` denotes the rank of the input tensor.
+/// * `axis` - Specifies the dimension along which the Softmax computation should be performed. A negative
+///   value denotes that the counting of dimensions should be from the back. Valid range is given by
+///   `[-r, r-1]`, where `r` denotes the rank of the input tensor.
+///   - Before opset 13, it defaults to `1`, and the input is coerced to 2D: the dimensions before `axis`
+///     are flattened into the rows, the ones from `axis` into the columns, and every row is normalized.
+///   - Since opset 13, it defaults to `-1`, and only the values along `axis` are normalized together.
 ///
 /// # Notes
 ///
 /// The resultant tensor post-Softmax computation retains the original shape. The values within this tensor represent
 /// the Softmax values of the corresponding input tensor elements.
-pub fn softmax(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
-    let exp_nd_array = tensor_proto_to_ndarray::<f32>(&exp(input, node)?).map_err(|_| {
-        OnnxError::ConversionError("Failed to convert TensorProto to ndarray".into())
-    })?;
-
-    let reduce_nd_array = tensor_proto_to_ndarray::<f32>(&reduce_sum(&exp(input, node)?, node)?)
-        .map_err(|_| {
-            OnnxError::ConversionError("Failed to convert TensorProto to ndarray".into())
-        })?;
-
-    let batch_size = exp_nd_array.shape()[0];
-    let mut result_list = Vec::with_capacity(batch_size);
+pub fn softmax(
+    input: &TensorProto,
+    node: &NodeProto,
+    opset: i64,
+) -> Result<TensorProto, OnnxError> {
+    normalize_along_axis(input, node, opset, |mut lane| {
+        let max = lane.fold(f32::NEG_INFINITY, |max, &value| max.max(value));
+        lane.mapv_inplace(|value| (value - max).exp());
+        let sum = lane.sum();
+        lane.mapv_inplace(|value| value / sum);
+    })
+}
 
-    for b in 0..batch_size {
-        let batch_exp = exp_nd_array.index_axis(Axis(0), b);
+/// Applies `normalize` to the groups of values of the input normalized together by the Softmax
+/// family (`Softmax`, `LogSoftmax`): the rows of the input coerced to 2D around `axis` before
+/// opset 13, the lanes along `axis` since. The groups are normalized in parallel.
+pub(crate) fn normalize_along_axis(
+    input: &TensorProto,
+    node: &NodeProto,
+    opset: i64,
+    normalize: impl Fn(ArrayViewMut1<f32>) + Sync + Send,
+) -> Result<TensorProto, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let coerced = opset < 13;
+    let axis = get_int_attribute(&attributes, "axis", Some(if coerced { 1 } else { -1 }))?;
 
-        let batch_sum = reduce_nd_array.index_axis(Axis(0), b);
-        let batch_result = batch_exp.to_owned() / batch_sum.to_owned();
-        result_list.push(batch_result);
+    let input = tensor_proto_to_ndarray::<f32>(input)?;
+    let rank = input.ndim() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
     }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    let shape = input.shape().to_vec();
 
-    let result = stack_along_batch_dimension(result_list)?;
+    let reshape = |error| OnnxError::ShapeError(format!("Failed to coerce the input: {}", error));
+    let output = if coerced {
+        let rows = shape[..axis].iter().product::<usize>();
+        let columns = shape[axis..].iter().product::<usize>();
+        let mut output = input
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((rows, columns))
+            .map_err(reshape)?;
+        Zip::from(output.lanes_mut(Axis(1))).par_for_each(normalize);
+        output.into_shape(shape).map_err(reshape)?
+    } else {
+        let mut output = input;
+        Zip::from(output.lanes_mut(Axis(axis))).par_for_each(normalize);
+        output
+    };
 
-    convert_to_output_tensor(node, result)
+    convert_to_output_tensor(node, output)
 }
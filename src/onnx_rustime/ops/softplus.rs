@@ -0,0 +1,89 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{convert_to_output_tensor, tensor_proto_to_ndarray};
+
+/// `softplus` - ONNX Node Implementation for the Softplus Activation
+///
+/// A smooth approximation of ReLU:
+///
+/// `y = log(1 + exp(x))`
+///
+/// It is computed as `max(x, 0) + log1p(exp(-|x|))`, which is the same value but never
+/// exponentiates a positive number: large inputs give `x` instead of overflowing to infinity.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = softplus(&input_tensor, &node)?;
+/// ```
+pub fn softplus(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(softplus_value))
+}
+
+/// `softsign` - ONNX Node Implementation for the Softsign Activation
+///
+/// `y = x / (1 + |x|)`, which tends to -1 and 1 more slowly than `tanh`.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = softsign(&input_tensor, &node)?;
+/// ```
+pub fn softsign(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| x / (1.0 + x.abs())))
+}
+
+/// `mish` - ONNX Node Implementation for the Mish Activation
+///
+/// The self-gated activation of YOLOv4:
+///
+/// `y = x * tanh(softplus(x))`
+///
+/// with the softplus of `softplus`, so that large inputs give `x`.
+///
+/// # Arguments
+///
+/// * `input` - A reference to the input tensor.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the activated tensor, of the shape of the input.
+///   In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Example
+///
+/// ```rust
+/// let activated = mish(&input_tensor, &node)?;
+/// ```
+pub fn mish(input: &TensorProto, node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    convert_to_output_tensor(node, x.mapv(|x| x * softplus_value(x).tanh()))
+}
+
+/// `log(1 + exp(x))`, without overflowing for large `x`.
+fn softplus_value(x: f32) -> f32 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
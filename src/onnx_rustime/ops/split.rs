@@ -0,0 +1,131 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::{Axis, Slice};
+
+/// `split` - ONNX Node Implementation for Split
+///
+/// Splits the input along `axis` into as many tensors as the node has outputs. The sizes of the
+/// parts are, in order of precedence:
+///
+/// * the `split` input (since opset 13) or the `split` attribute (before opset 13), which must
+///   sum to the length of the axis;
+/// * `ceil(length / num_outputs)` for the `num_outputs` attribute (since opset 18), the last part
+///   being smaller if the length isn't divisible;
+/// * an equal split among the outputs otherwise, the length being divisible by their number.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to split along, 0 by default. Negative values count from the end.
+/// - `split` (ints): The sizes of the parts, before opset 13.
+/// - `num_outputs` (int): The number of parts, since opset 18.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input` and, since opset 13, the optional INT64 tensor `split`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the parts, in the order of the node
+///   outputs. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input`, or an `axis` out of range.
+/// * Sizes that don't sum to the length of the axis, or a number of parts other than the number
+///   of outputs.
+/// * A length not divisible by the number of outputs, without sizes or `num_outputs`.
+///
+/// # Example
+///
+/// ```rust
+/// let parts = split(&[&input_tensor, &sizes], &node)?;
+/// ```
+pub fn split(inputs: &[&TensorProto], node: &NodeProto) -> Result<Vec<TensorProto>, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let input = tensor_proto_to_ndarray::<f32>(input)?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let axis = get_int_attribute(&attributes, "axis", Some(0))?;
+    let rank = input.ndim() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    let length = input.shape()[axis];
+
+    let outputs = node.get_output();
+    let sizes = match inputs.get(1) {
+        Some(split) => Some(tensor_proto_to_ndarray::<i64>(split)?.into_raw_vec()),
+        None => attributes
+            .get("split")
+            .and_then(|attr| attr.as_ints())
+            .cloned(),
+    };
+    let sizes: Vec<usize> = match (sizes, attributes.get("num_outputs")) {
+        (Some(sizes), _) => {
+            if sizes.iter().any(|&size| size < 0) || sizes.iter().sum::<i64>() != length as i64 {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "The sizes {:?} of the parts don't sum to the length {} of axis {}",
+                    sizes, length, axis
+                )));
+            }
+            sizes.into_iter().map(|size| size as usize).collect()
+        }
+        (None, Some(_)) => {
+            let requested = get_int_attribute(&attributes, "num_outputs", None)?;
+            let num_outputs = usize::try_from(requested).unwrap_or(0);
+            // Every part but the last one has the rounded up size.
+            let chunk = length.div_ceil(num_outputs.max(1));
+            if num_outputs == 0 || chunk * (num_outputs - 1) > length {
+                return Err(OnnxError::InvalidValue(format!(
+                    "Cannot split the length {} of axis {} into {} parts",
+                    length, axis, requested
+                )));
+            }
+            (0..num_outputs)
+                .map(|part| chunk.min(length - part * chunk))
+                .collect()
+        }
+        (None, None) => {
+            if outputs.is_empty() || length % outputs.len() != 0 {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "Cannot split the length {} of axis {} equally into {} outputs",
+                    length,
+                    axis,
+                    outputs.len()
+                )));
+            }
+            vec![length / outputs.len(); outputs.len()]
+        }
+    };
+    if sizes.len() != outputs.len() {
+        return Err(OnnxError::InvalidValue(format!(
+            "Split into {} parts, but the node has {} outputs",
+            sizes.len(),
+            outputs.len()
+        )));
+    }
+
+    let mut start = 0;
+    sizes
+        .iter()
+        .zip(outputs)
+        .map(|(&size, name)| {
+            let part = input
+                .slice_axis(Axis(axis), Slice::from(start..start + size))
+                .to_owned();
+            start += size;
+            ndarray_to_tensor_proto::<f32>(part, name)
+        })
+        .collect()
+}
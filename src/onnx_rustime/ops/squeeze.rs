@@ -0,0 +1,163 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{extract_attributes, tensor_proto_to_ndarray};
+
+/// `squeeze` - ONNX Node Implementation for Squeeze
+///
+/// Removes dimensions of size 1 from the shape of the input: the ones listed by `axes`, or all of
+/// them without `axes`. The values are unchanged, whatever their element type.
+///
+/// `axes` is an attribute before opset 13, an optional INT64 input since. Negative axes count from
+/// the end, in the range `[-r, r-1]` where `r` is the rank of the input.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 13, the optional tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the squeezed tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`.
+/// * An axis out of range, repeated, or whose dimension isn't 1.
+///
+/// # Example
+///
+/// ```rust
+/// let squeezed = squeeze(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn squeeze(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let data = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let input_shape = data.get_dims();
+
+    let output_shape = match axes(inputs, node)? {
+        None => input_shape
+            .iter()
+            .copied()
+            .filter(|&dim| dim != 1)
+            .collect(),
+        Some(axes) => {
+            let axes = normalized_axes(&axes, input_shape.len())?;
+            if let Some(&axis) = axes.iter().find(|&&axis| input_shape[axis] != 1) {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "Cannot squeeze axis {} of size {} of an input of shape {:?}",
+                    axis, input_shape[axis], input_shape
+                )));
+            }
+            input_shape
+                .iter()
+                .enumerate()
+                .filter(|(axis, _)| !axes.contains(axis))
+                .map(|(_, &dim)| dim)
+                .collect()
+        }
+    };
+
+    with_shape(data, output_shape, node)
+}
+
+/// `unsqueeze` - ONNX Node Implementation for Unsqueeze
+///
+/// Inserts dimensions of size 1 in the shape of the input, at the positions listed by `axes` in
+/// the shape of the output. The values are unchanged, whatever their element type.
+///
+/// `axes` is an attribute before opset 13, an INT64 input since. Negative axes count from the
+/// end, in the range `[-r, r-1]` where `r` is the rank of the output.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data` and, since opset 13, the tensor `axes`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the unsqueezed tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data` or `axes`.
+/// * An axis out of range or repeated.
+///
+/// # Example
+///
+/// ```rust
+/// let unsqueezed = unsqueeze(&[&input_tensor, &axes], &node)?;
+/// ```
+pub fn unsqueeze(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let data = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let axes = axes(inputs, node)?.ok_or(OnnxError::MissingInput("axes".to_string()))?;
+
+    let output_rank = data.get_dims().len() + axes.len();
+    let axes = normalized_axes(&axes, output_rank)?;
+    let mut input_dims = data.get_dims().iter();
+    let output_shape = (0..output_rank)
+        .map(|axis| match axes.contains(&axis) {
+            true => 1,
+            false => *input_dims.next().unwrap_or(&1),
+        })
+        .collect();
+
+    with_shape(data, output_shape, node)
+}
+
+/// The axes of a Squeeze or Unsqueeze node: its second input since opset 13, its `axes`
+/// attribute before. `None` if the node has neither.
+fn axes(inputs: &[&TensorProto], node: &NodeProto) -> Result<Option<Vec<i64>>, OnnxError> {
+    if let Some(axes) = inputs.get(1) {
+        return Ok(Some(tensor_proto_to_ndarray::<i64>(axes)?.into_raw_vec()));
+    }
+    let attributes = extract_attributes(node.get_attribute())?;
+    Ok(attributes
+        .get("axes")
+        .and_then(|attr| attr.as_ints().cloned()))
+}
+
+/// Turns the negative axes into positive ones, for a tensor of the given rank.
+fn normalized_axes(axes: &[i64], rank: usize) -> Result<Vec<usize>, OnnxError> {
+    let rank = rank as i64;
+    let mut normalized = Vec::with_capacity(axes.len());
+    for &axis in axes {
+        if axis < -rank || axis >= rank {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is out of range for a tensor of rank {}",
+                axis, rank
+            )));
+        }
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        if normalized.contains(&axis) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Axis {} is repeated in {:?}",
+                axis, axes
+            )));
+        }
+        normalized.push(axis);
+    }
+    Ok(normalized)
+}
+
+/// A copy of `data` with new dimensions, named after the output of the node.
+fn with_shape(
+    data: &TensorProto,
+    shape: Vec<i64>,
+    node: &NodeProto,
+) -> Result<TensorProto, OnnxError> {
+    let name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+    let mut output = data.clone();
+    output.set_name(name.clone());
+    output.set_dims(shape);
+    Ok(output)
+}
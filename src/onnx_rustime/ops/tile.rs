@@ -0,0 +1,103 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use ndarray::{ArrayD, Dimension, IxDyn};
+
+/// Repeats the elements of `input` to `output_shape` (a multiple of its shape) and encodes them
+/// as the output `name`.
+fn tile_as<T: TensorType>(
+    input: &TensorProto,
+    output_shape: &[usize],
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let data = tensor_proto_to_ndarray::<T>(input)?;
+    let tiled = ArrayD::from_shape_fn(IxDyn(output_shape), |index| {
+        let source: Vec<usize> = index
+            .slice()
+            .iter()
+            .zip(data.shape())
+            .map(|(&position, &dim)| position % dim)
+            .collect();
+        data[IxDyn(&source)].clone()
+    });
+    ndarray_to_tensor_proto::<T>(tiled, name)
+}
+
+/// `tile` - ONNX Node Implementation for Tile
+///
+/// Repeats the input `repeats[i]` times along every axis `i`, like `np.tile`: a [2, 3] matrix
+/// tiled by [2, 2] gives a [4, 6] matrix of 4 copies. A repeat of 1 leaves the axis as is, and a
+/// repeat of 0 gives an empty axis.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `input` (FLOAT, FLOAT16, INT64 or BOOL) and `repeats`, an INT64 tensor
+///   with one value per axis of `input`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the tiled tensor, of the type of `input` (FLOAT
+///   for a FLOAT16 `input`). In case of an unsuccessful operation, it returns an error
+///   (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input` or `repeats`.
+/// * A `repeats` whose length isn't the rank of `input`, or with negative values.
+///
+/// # Example
+///
+/// ```rust
+/// let tiled = tile(&[&input_tensor, &repeats], &node)?;
+/// ```
+pub fn tile(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let repeats = inputs
+        .get(1)
+        .ok_or(OnnxError::MissingInput("repeats".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let dims = input.get_dims();
+    let repeats: Vec<i64> = tensor_proto_to_ndarray::<i64>(repeats)?
+        .iter()
+        .copied()
+        .collect();
+    if repeats.len() != dims.len() {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected one repeat per axis of a tensor of shape {:?}, got {:?}",
+            dims, repeats
+        )));
+    }
+    if let Some(&repeat) = repeats.iter().find(|&&repeat| repeat < 0) {
+        return Err(OnnxError::InvalidValue(format!(
+            "The repeats {:?} of a Tile have the negative value {}",
+            repeats, repeat
+        )));
+    }
+    let output_shape: Vec<usize> = dims
+        .iter()
+        .zip(&repeats)
+        .map(|(&dim, &repeat)| (dim * repeat) as usize)
+        .collect();
+
+    match input.get_data_type() {
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            tile_as::<i64>(input, &output_shape, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            tile_as::<bool>(input, &output_shape, output_name)
+        }
+        _ => tile_as::<f32>(input, &output_shape, output_name),
+    }
+}
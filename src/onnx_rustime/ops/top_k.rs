@@ -0,0 +1,169 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+};
+use ndarray::{ArrayD, ArrayView1, Axis, IxDyn, Zip};
+use std::cmp::Ordering;
+
+/// The indices of the `k` largest (or smallest) elements of `lane`, from the largest (or the
+/// smallest) on. Of equal elements, the one with the smaller index comes first.
+pub(crate) fn top_k_indices<T, F>(
+    lane: ArrayView1<T>,
+    k: usize,
+    largest: bool,
+    compare: F,
+) -> Vec<usize>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mut order: Vec<usize> = (0..lane.len()).collect();
+    // The sort is stable: the ties keep the order of their indices.
+    match largest {
+        true => order.sort_by(|&a, &b| compare(&lane[b], &lane[a])),
+        false => order.sort_by(|&a, &b| compare(&lane[a], &lane[b])),
+    }
+    order.truncate(k);
+    order
+}
+
+/// The `k` largest (or smallest) elements of every lane of `data` along `axis`, and their indices.
+fn top_k_axis<T, F>(
+    data: &ArrayD<T>,
+    axis: usize,
+    k: usize,
+    largest: bool,
+    compare: F,
+) -> (ArrayD<T>, ArrayD<i64>)
+where
+    T: Copy + Default,
+    F: Fn(&T, &T) -> Ordering + Copy,
+{
+    let mut shape = data.shape().to_vec();
+    shape[axis] = k;
+    let mut values = ArrayD::<T>::default(IxDyn(&shape));
+    let mut indices = ArrayD::<i64>::zeros(IxDyn(&shape));
+    Zip::from(data.lanes(Axis(axis)))
+        .and(values.lanes_mut(Axis(axis)))
+        .and(indices.lanes_mut(Axis(axis)))
+        .for_each(|lane, mut values, mut indices| {
+            for (i, index) in top_k_indices(lane, k, largest, compare)
+                .into_iter()
+                .enumerate()
+            {
+                values[i] = lane[index];
+                indices[i] = index as i64;
+            }
+        });
+    (values, indices)
+}
+
+/// `top_k` - ONNX Node Implementation for TopK
+///
+/// The `k` largest (or smallest) elements of every lane of the input along `axis`, e.g. the best
+/// scoring classes or detections, and their indices along the axis. The elements are returned
+/// from the largest (or the smallest) on, whatever `sorted`; of equal elements, the one with the
+/// smaller index comes first.
+///
+/// `k` is an attribute in opset 1, a single-value INT64 input since opset 10.
+///
+/// # Attributes
+///
+/// - `axis` (int): The axis to take the elements along, -1 (the last one) by default. Negative
+///   values count from the end.
+/// - `largest` (int): 1 (default) for the largest elements, 0 for the smallest (since opset 11).
+/// - `sorted` (int): Whether the elements must be sorted (since opset 11). They always are.
+/// - `k` (int): The number of elements, in opset 1.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `X` (FLOAT, FLOAT16 or INT64) and, since opset 10, the tensor `K`.
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<Vec<TensorProto>, OnnxError>` - Outputs the tensor `Values`, of the type of `X`
+///   (FLOAT for a FLOAT16 `X`), followed by the INT64 tensor `Indices`. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `X` or `K`, or a `K` that isn't a single value.
+/// * An `axis` out of range, or a negative `k` or one larger than the length of the axis.
+///
+/// # Example
+///
+/// ```rust
+/// let outputs = top_k(&[&scores, &k], &node)?;
+/// let (values, indices) = (&outputs[0], &outputs[1]);
+/// ```
+pub fn top_k(inputs: &[&TensorProto], node: &NodeProto) -> Result<Vec<TensorProto>, OnnxError> {
+    let x = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("X".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let attributes = extract_attributes(node.get_attribute())?;
+    let k = match inputs.get(1) {
+        Some(k) => {
+            let k = tensor_proto_to_ndarray::<i64>(k)?;
+            match k.iter().next() {
+                Some(&value) if k.len() == 1 => value,
+                _ => {
+                    return Err(OnnxError::InvalidValue(format!(
+                        "The K of a TopK must be a single value, got a tensor of shape {:?}",
+                        k.shape()
+                    )))
+                }
+            }
+        }
+        None => get_int_attribute(&attributes, "k", None)?,
+    };
+    let axis = get_int_attribute(&attributes, "axis", Some(-1))?;
+    let largest = get_int_attribute(&attributes, "largest", Some(1))? != 0;
+
+    let dims = x.get_dims();
+    let rank = dims.len() as i64;
+    if axis < -rank || axis >= rank {
+        return Err(OnnxError::InvalidValue(format!(
+            "Axis {} is out of range for a tensor of rank {}",
+            axis, rank
+        )));
+    }
+    let axis = if axis < 0 { axis + rank } else { axis } as usize;
+    if k < 0 || k > dims[axis] {
+        return Err(OnnxError::InvalidValue(format!(
+            "The k {} of a TopK must be between 0 and the length {} of axis {}",
+            k, dims[axis], axis
+        )));
+    }
+    let k = k as usize;
+
+    let (values, indices) = if x.get_data_type() == TensorProto_DataType::INT64 as i32 {
+        let x = tensor_proto_to_ndarray::<i64>(x)?;
+        let (values, indices) = top_k_axis(&x, axis, k, largest, i64::cmp);
+        (
+            ndarray_to_tensor_proto::<i64>(values, output_name)?,
+            indices,
+        )
+    } else {
+        let x = tensor_proto_to_ndarray::<f32>(x)?;
+        let (values, indices) = top_k_axis(&x, axis, k, largest, f32::total_cmp);
+        (
+            ndarray_to_tensor_proto::<f32>(values, output_name)?,
+            indices,
+        )
+    };
+
+    let mut outputs = vec![values];
+    if let Some(name) = node.get_output().get(1).filter(|name| !name.is_empty()) {
+        outputs.push(ndarray_to_tensor_proto::<i64>(indices, name)?);
+    }
+    Ok(outputs)
+}
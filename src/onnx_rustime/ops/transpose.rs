@@ -0,0 +1,76 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{
+    convert_to_output_tensor, extract_attributes, tensor_proto_to_ndarray,
+};
+
+/// `transpose` - ONNX Node Implementation for Transpose
+///
+/// Permutes the axes of the input, of any rank: the axis `i` of the output is the axis `perm[i]`
+/// of the input, e.g. `perm = [0, 2, 3, 1]` turns an NCHW tensor into an NHWC one, and
+/// `perm = [0, 3, 1, 2]` turns it back.
+///
+/// # Attributes
+///
+/// - `perm` (ints): A permutation of the axes of the input. Reverses them by default.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `data`, computed by the graph or an initializer (e.g. a weight stored
+///   in the layout of another framework).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the transposed tensor. In case of an unsuccessful
+///   operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `data`.
+/// * An `OnnxError::InvalidAttribute` if `perm` isn't a permutation of the axes of the input (of
+///   another length, with repeated or out of range axes).
+///
+/// # Example
+///
+/// ```rust
+/// let nhwc = transpose(&[&nchw_tensor], &node)?;
+/// ```
+pub fn transpose(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("data".to_string()))?;
+    let x = tensor_proto_to_ndarray::<f32>(input)?;
+    let perm = permutation(node, x.ndim())?;
+
+    // The values are copied in the order of the permuted axes when converted back.
+    convert_to_output_tensor(node, x.permuted_axes(perm))
+}
+
+/// Reads the `perm` attribute of a Transpose node for an input of the given rank, checking that it
+/// is a permutation of its axes.
+fn permutation(node: &NodeProto, rank: usize) -> Result<Vec<usize>, OnnxError> {
+    let attributes = extract_attributes(node.get_attribute())?;
+    let perm = match attributes.get("perm").and_then(|attr| attr.as_ints()) {
+        Some(perm) => perm.clone(),
+        None => return Ok((0..rank).rev().collect()),
+    };
+
+    let mut seen = vec![false; rank];
+    let valid = perm.len() == rank
+        && perm.iter().all(|&axis| {
+            let in_range = axis >= 0 && (axis as usize) < rank;
+            in_range && !std::mem::replace(&mut seen[axis as usize], true)
+        });
+    if !valid {
+        return Err(OnnxError::InvalidAttribute {
+            name: "perm".to_string(),
+            detail: format!(
+                "{:?} is not a permutation of the {} axes of the input",
+                perm, rank
+            ),
+        });
+    }
+    Ok(perm.into_iter().map(|axis| axis as usize).collect())
+}
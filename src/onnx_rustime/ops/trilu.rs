@@ -0,0 +1,129 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    extract_attributes, get_int_attribute, ndarray_to_tensor_proto, tensor_proto_to_ndarray,
+    TensorType,
+};
+use half::f16;
+
+/// Zeroes the elements of `input` out of the triangle, in every matrix of its last two axes, and
+/// encodes the result as the output `name`.
+fn trilu_as<T: TensorType>(
+    input: &TensorProto,
+    k: i64,
+    upper: bool,
+    name: &str,
+) -> Result<TensorProto, OnnxError>
+where
+    T::DataType: Default,
+{
+    let mut x = tensor_proto_to_ndarray::<T>(input)?;
+    let rank = x.ndim();
+    if rank < 2 {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Trilu expects an input of rank 2 or more, got shape {:?}",
+            x.shape()
+        )));
+    }
+    for (position, value) in x.indexed_iter_mut() {
+        let diagonal = position[rank - 1] as i64 - position[rank - 2] as i64;
+        let kept = match upper {
+            true => diagonal >= k,
+            false => diagonal <= k,
+        };
+        if !kept {
+            *value = T::DataType::default();
+        }
+    }
+    ndarray_to_tensor_proto::<T>(x, name)
+}
+
+/// `trilu` - ONNX Node Implementation for Trilu
+///
+/// The upper or lower triangular part of the matrices of the last two axes of the input, the
+/// other elements being zero, e.g. the causal mask of the self-attention of a decoder. The
+/// diagonal `k` is made of the elements whose column minus row is `k`: the upper part keeps the
+/// elements on and above it, the lower part the ones on and below it. Beyond the corners of the
+/// matrix, `k` keeps all the elements or none.
+///
+/// # Attributes
+///
+/// - `upper` (int): 1 (default) for the upper triangular part, 0 for the lower one.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensor `input` (FLOAT, FLOAT16, DOUBLE, INT64, INT32 or BOOL) of rank 2 or
+///   more, and the optional INT64 scalar `k` (0, the main diagonal, by default).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the triangular part, of the shape and type of
+///   `input`. In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing `input`, or one of rank lower than 2.
+/// * A `k` that isn't a single value, or an input of an unsupported type.
+///
+/// # Example
+///
+/// ```rust
+/// // The causal mask of a decoder: the BOOL lower triangle of a [sequence, sequence] matrix.
+/// let causal_mask = trilu(&[&ones], &node)?;
+/// ```
+pub fn trilu(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let input = inputs
+        .first()
+        .ok_or(OnnxError::MissingInput("input".to_string()))?;
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    let k = match inputs.get(1) {
+        Some(k) => {
+            let k = tensor_proto_to_ndarray::<i64>(k)?;
+            match k.iter().next() {
+                Some(&value) if k.len() == 1 => value,
+                _ => {
+                    return Err(OnnxError::InvalidValue(format!(
+                        "The k of a Trilu must be a single value, got a tensor of shape {:?}",
+                        k.shape()
+                    )))
+                }
+            }
+        }
+        None => 0,
+    };
+    let attributes = extract_attributes(node.get_attribute())?;
+    let upper = get_int_attribute(&attributes, "upper", Some(1))? != 0;
+
+    match input.get_data_type() {
+        t if t == TensorProto_DataType::FLOAT as i32 => {
+            trilu_as::<f32>(input, k, upper, output_name)
+        }
+        t if t == TensorProto_DataType::FLOAT16 as i32 => {
+            trilu_as::<f16>(input, k, upper, output_name)
+        }
+        t if t == TensorProto_DataType::DOUBLE as i32 => {
+            trilu_as::<f64>(input, k, upper, output_name)
+        }
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            trilu_as::<i64>(input, k, upper, output_name)
+        }
+        t if t == TensorProto_DataType::INT32 as i32 => {
+            trilu_as::<i32>(input, k, upper, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            trilu_as::<bool>(input, k, upper, output_name)
+        }
+        t => Err(OnnxError::UnsupportedOperation(format!(
+            "Trilu doesn't support the data type {}",
+            t
+        ))),
+    }
+}
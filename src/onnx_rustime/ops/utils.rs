@@ -8,7 +8,7 @@ The utilities encompass a broad range of operations, including tensor data extra
 
 Key Features:
 - TensorType Trait: Defines a common interface for various tensor data types. It facilitates both data extraction from tensors and conversion of arrays into tensor data.
-- Data Extraction: Comprehensive implementations are provided for extracting tensor data for various primitive types (f32, i32, i64, String). These methods handle both direct and raw data formats.
+- Data Extraction: Comprehensive implementations are provided for extracting tensor data for various primitive types (f32, f64, f16, u8, i8, i32, i64, bool, String). These methods handle both direct and raw data formats.
 - Tensor Conversion: Utility functions are provided for converting between NDArrays and TensorProtos. These are essential for interfacing between ONNX and computational backends.
 - Attribute Handling: A set of utilities to extract and categorize attributes from ONNX nodes. This provides a structured way to access attributes by their names and types.
 - Matrix Padding: Functions to pad 2D and 3D matrices, a common operation in neural network layers.
@@ -26,10 +26,14 @@ This utility file is open for enhancements. If there are additional ONNX operati
 */
 
 use crate::onnx_rustime::backend::helper::{make_tensor, Attribute, OnnxError, TensorValue};
-use crate::onnx_rustime::backend::parser::{parse_raw_data_as_floats, parse_raw_data_as_ints64};
+use crate::onnx_rustime::backend::parser::{
+    parse_raw_data_as_doubles, parse_raw_data_as_floats, parse_raw_data_as_floats16,
+    parse_raw_data_as_ints32, parse_raw_data_as_ints64,
+};
 use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
     AttributeProto, AttributeProto_AttributeType, GraphProto, NodeProto, TensorProto,
 };
+use half::f16;
 use ndarray::*;
 use std::collections::HashMap;
 
@@ -39,7 +43,7 @@ use std::collections::HashMap;
 /// converting an array to tensor data.
 pub trait TensorType {
     /// Represents the specific type of data the tensor holds.
-    type DataType;
+    type DataType: Clone;
 
     /// Extracts data from a given tensor and checks it against an expected length.
     ///
@@ -79,14 +83,20 @@ impl TensorType for f32 {
         // Extract shape from the tensor.
         let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
 
+        // FLOAT16 tensors are upcast, so that every f32 kernel can run on half precision models.
+        if tensor.get_data_type() == DATA_TYPE_FLOAT16 {
+            let data = f16::extract_data(tensor, expected_len)?.mapv(f32::from);
+            return Ok(data);
+        }
+
         // Check if float_data is present and matches the expected length.
         if !tensor.float_data.is_empty() && tensor.float_data.len() == expected_len {
             ArrayD::from_shape_vec(shape, tensor.float_data.clone())
-                .map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else if !tensor.raw_data.is_empty() {
             // Parse raw data as floats.
             let data = parse_raw_data_as_floats(&tensor.raw_data);
-            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else {
             Err(OnnxError::InvalidValue(
                 "No valid data found for FLOAT type".to_string(),
@@ -99,6 +109,71 @@ impl TensorType for f32 {
     }
 }
 
+/// Implementation of `TensorType` for `f64` data type.
+impl TensorType for f64 {
+    type DataType = f64;
+
+    fn extract_data(
+        tensor: &TensorProto,
+        expected_len: usize,
+    ) -> Result<ArrayD<Self::DataType>, OnnxError> {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+
+        if !tensor.double_data.is_empty() && tensor.double_data.len() == expected_len {
+            ArrayD::from_shape_vec(shape, tensor.double_data.clone())
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            let data = parse_raw_data_as_doubles(&tensor.raw_data);
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else {
+            Err(OnnxError::InvalidValue(
+                "No valid data found for DOUBLE type".to_string(),
+            ))
+        }
+    }
+
+    fn to_tensor_data(array: ArrayD<Self::DataType>) -> TensorValue {
+        TensorValue::Double(array.into_dyn().into_raw_vec())
+    }
+}
+
+/// Implementation of `TensorType` for `f16` data type.
+///
+/// ONNX stores FLOAT16 values either as their bit pattern inside `int32_data`
+/// or as little-endian pairs of bytes inside `raw_data`.
+impl TensorType for f16 {
+    type DataType = f16;
+
+    fn extract_data(
+        tensor: &TensorProto,
+        expected_len: usize,
+    ) -> Result<ArrayD<Self::DataType>, OnnxError> {
+        // Extract shape from the tensor.
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+
+        if !tensor.int32_data.is_empty() && tensor.int32_data.len() == expected_len {
+            let data = tensor
+                .int32_data
+                .iter()
+                .map(|&bits| f16::from_bits(bits as u16))
+                .collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            // Parse raw data as half precision floats.
+            let data = parse_raw_data_as_floats16(&tensor.raw_data);
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else {
+            Err(OnnxError::InvalidValue(
+                "No valid data found for FLOAT16 type".to_string(),
+            ))
+        }
+    }
+
+    fn to_tensor_data(array: ArrayD<Self::DataType>) -> TensorValue {
+        TensorValue::Float16(array.into_dyn().into_raw_vec())
+    }
+}
+
 /// Implementation of `TensorType` for `i32` data type.
 impl TensorType for i32 {
     type DataType = i32;
@@ -111,7 +186,11 @@ impl TensorType for i32 {
 
         if !tensor.int32_data.is_empty() && tensor.int32_data.len() == expected_len {
             ArrayD::from_shape_vec(shape, tensor.int32_data.clone())
-                .map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            // Parse raw data as ints.
+            let data = parse_raw_data_as_ints32(&tensor.raw_data);
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else {
             Err(OnnxError::InvalidValue(
                 "No valid data found for INT32 type".to_string(),
@@ -124,6 +203,68 @@ impl TensorType for i32 {
     }
 }
 
+/// Implementation of `TensorType` for `u8` data type.
+///
+/// ONNX stores UINT8 values either widened inside `int32_data` or as single bytes inside
+/// `raw_data`.
+impl TensorType for u8 {
+    type DataType = u8;
+
+    fn extract_data(
+        tensor: &TensorProto,
+        expected_len: usize,
+    ) -> Result<ArrayD<Self::DataType>, OnnxError> {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+
+        if !tensor.int32_data.is_empty() && tensor.int32_data.len() == expected_len {
+            let data = tensor.int32_data.iter().map(|&value| value as u8).collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            ArrayD::from_shape_vec(shape, tensor.raw_data.clone())
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else {
+            Err(OnnxError::InvalidValue(
+                "No valid data found for UINT8 type".to_string(),
+            ))
+        }
+    }
+
+    fn to_tensor_data(array: ArrayD<Self::DataType>) -> TensorValue {
+        TensorValue::UInt8(array.into_dyn().into_raw_vec())
+    }
+}
+
+/// Implementation of `TensorType` for `i8` data type.
+///
+/// ONNX stores INT8 values either widened inside `int32_data` or as single bytes inside
+/// `raw_data`.
+impl TensorType for i8 {
+    type DataType = i8;
+
+    fn extract_data(
+        tensor: &TensorProto,
+        expected_len: usize,
+    ) -> Result<ArrayD<Self::DataType>, OnnxError> {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+
+        if !tensor.int32_data.is_empty() && tensor.int32_data.len() == expected_len {
+            let data = tensor.int32_data.iter().map(|&value| value as i8).collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            let data = tensor.raw_data.iter().map(|&byte| byte as i8).collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else {
+            Err(OnnxError::InvalidValue(
+                "No valid data found for INT8 type".to_string(),
+            ))
+        }
+    }
+
+    fn to_tensor_data(array: ArrayD<Self::DataType>) -> TensorValue {
+        TensorValue::Int8(array.into_dyn().into_raw_vec())
+    }
+}
+
 /// Implementation of `TensorType` for `i64` data type.
 impl TensorType for i64 {
     type DataType = i64;
@@ -137,11 +278,11 @@ impl TensorType for i64 {
 
         if !tensor.int64_data.is_empty() && tensor.int64_data.len() == expected_len {
             ArrayD::from_shape_vec(shape, tensor.int64_data.clone())
-                .map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else if !tensor.raw_data.is_empty() {
             // Parse raw data as floats.
             let data = parse_raw_data_as_ints64(&tensor.raw_data);
-            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else {
             Err(OnnxError::InvalidValue(
                 "No valid data found for INT64 type".to_string(),
@@ -154,6 +295,37 @@ impl TensorType for i64 {
     }
 }
 
+/// Implementation of `TensorType` for `bool` data type.
+///
+/// ONNX stores BOOL values either widened inside `int32_data` or as single bytes inside
+/// `raw_data`.
+impl TensorType for bool {
+    type DataType = bool;
+
+    fn extract_data(
+        tensor: &TensorProto,
+        expected_len: usize,
+    ) -> Result<ArrayD<Self::DataType>, OnnxError> {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+
+        if !tensor.int32_data.is_empty() && tensor.int32_data.len() == expected_len {
+            let data = tensor.int32_data.iter().map(|&value| value != 0).collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else if !tensor.raw_data.is_empty() {
+            let data = tensor.raw_data.iter().map(|&byte| byte != 0).collect();
+            ArrayD::from_shape_vec(shape, data).map_err(|e| OnnxError::shape_mismatch(e.to_string()))
+        } else {
+            Err(OnnxError::InvalidValue(
+                "No valid data found for BOOL type".to_string(),
+            ))
+        }
+    }
+
+    fn to_tensor_data(array: ArrayD<Self::DataType>) -> TensorValue {
+        TensorValue::Bool(array.into_dyn().into_raw_vec())
+    }
+}
+
 /// Implementation of `TensorType` for `String` data type.
 impl TensorType for String {
     type DataType = String;
@@ -173,7 +345,7 @@ impl TensorType for String {
                 .map(|s| String::from_utf8_lossy(s).to_string())
                 .collect::<Vec<_>>();
             ArrayD::from_shape_vec(shape, string_data)
-                .map_err(|e| OnnxError::ShapeMismatch(e.to_string()))
+                .map_err(|e| OnnxError::shape_mismatch(e.to_string()))
         } else {
             Err(OnnxError::InvalidValue(
                 "No valid data found for STRING type".to_string(),
@@ -207,6 +379,14 @@ pub fn ndarray_to_tensor_proto<T: TensorType>(
         .map(|x| *x as i64)
         .collect::<Vec<i64>>();
 
+    // The data is stored in row-major order: arrays in another memory layout (e.g. produced by
+    // broadcasting operations) are copied first.
+    let result = if result.is_standard_layout() {
+        result
+    } else {
+        result.as_standard_layout().into_owned()
+    };
+
     // Convert NDArray data to tensor data.
     let tensor_data = T::to_tensor_data(result);
 
@@ -238,10 +418,14 @@ pub fn convert_to_output_tensor(
 // Constants representing different data types in TensorProto.
 // They are mapped to the TensorProto data field.
 const DATA_TYPE_FLOAT: i32 = 1;
-const DATA_TYPE_INT32: i32 = 5;
-const DATA_TYPE_STRING: i32 = 6;
+const DATA_TYPE_UINT8: i32 = 2;
+const DATA_TYPE_INT8: i32 = 3;
+const DATA_TYPE_INT32: i32 = 6;
+const DATA_TYPE_STRING: i32 = 8;
 const DATA_TYPE_INT64: i32 = 7;
-const DATA_TYPE_RAW: i32 = 9;
+const DATA_TYPE_BOOL: i32 = 9;
+pub const DATA_TYPE_FLOAT16: i32 = 10;
+const DATA_TYPE_DOUBLE: i32 = 11;
 
 /// Converts a TensorProto to an NDArray.
 ///
@@ -258,13 +442,24 @@ pub fn tensor_proto_to_ndarray<T: TensorType>(
     // Calculate the expected length based on the dimensions of the tensor.
     let expected_len: usize = tensor.get_dims().iter().map(|&dim| dim as usize).product();
 
+    // Tensors without elements (e.g. a Tile by 0) carry no data at all.
+    if expected_len == 0 {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+        return ArrayD::from_shape_vec(shape, Vec::new())
+            .map_err(|e| OnnxError::shape_mismatch(e.to_string()));
+    }
+
     // Match on the data type of the tensor and extract the data accordingly.
     match Some(tensor.get_data_type()) {
         Some(DATA_TYPE_FLOAT) => T::extract_data(tensor, expected_len),
+        Some(DATA_TYPE_UINT8) => T::extract_data(tensor, expected_len),
+        Some(DATA_TYPE_INT8) => T::extract_data(tensor, expected_len),
         Some(DATA_TYPE_INT32) => T::extract_data(tensor, expected_len),
         Some(DATA_TYPE_STRING) => T::extract_data(tensor, expected_len),
         Some(DATA_TYPE_INT64) => T::extract_data(tensor, expected_len),
-        Some(DATA_TYPE_RAW) => T::extract_data(tensor, expected_len),
+        Some(DATA_TYPE_BOOL) => T::extract_data(tensor, expected_len),
+        Some(DATA_TYPE_FLOAT16) => T::extract_data(tensor, expected_len),
+        Some(DATA_TYPE_DOUBLE) => T::extract_data(tensor, expected_len),
         _ => Err(OnnxError::UnsupportedOperation(format!(
             "Unsupported data type: {}",
             tensor.get_data_type()
@@ -288,7 +483,7 @@ fn extract_raw_data(tensor: &TensorProto, expected_len: usize) -> Result<Vec<f32
         if data.len() == expected_len {
             Ok(data)
         } else {
-            Err(OnnxError::ShapeMismatch(format!(
+            Err(OnnxError::shape_mismatch(format!(
                 "Data length mismatch in RAW data: expected {} but got {}",
                 expected_len,
                 data.len()
@@ -609,7 +804,9 @@ pub fn get_graphs_attribute(
 /// # Arguments
 ///
 /// * `matrix` - A reference to the input 2D matrix.
-/// * `pads` - A reference to a vector containing padding values in the order [top, bottom, left, right].
+/// * `pads` - A reference to a vector containing padding values in the order [top, left, bottom, right].
+/// * `value` - The value of the padded elements (e.g. zero for convolutions, negative infinity for
+///   max pooling).
 ///
 /// # Returns
 ///
@@ -621,9 +818,13 @@ pub fn get_graphs_attribute(
 /// use ndarray::array;
 /// let matrix = array![[1.0, 2.0], [3.0, 4.0]];
 /// let pads = vec![1, 1, 1, 1];
-/// let padded_matrix = pad_matrix_2d(&matrix, &pads).unwrap();
+/// let padded_matrix = pad_matrix_2d(&matrix, &pads, 0.0).unwrap();
 /// ```
-pub fn pad_matrix_2d(matrix: &Array2<f32>, pads: &Vec<i64>) -> Result<Array2<f32>, OnnxError> {
+pub fn pad_matrix_2d(
+    matrix: &Array2<f32>,
+    pads: &[i64],
+    value: f32,
+) -> Result<Array2<f32>, OnnxError> {
     // Extract padding values
     let top = pads[0] as usize;
     let left = pads[1] as usize;
@@ -641,8 +842,8 @@ pub fn pad_matrix_2d(matrix: &Array2<f32>, pads: &Vec<i64>) -> Result<Array2<f32
         matrix.shape()[1] + left + right,
     );
 
-    // Create a new matrix filled with zeros for padding
-    let mut padded_matrix = Array2::zeros(padded_shape);
+    // Create a new matrix filled with the padding value
+    let mut padded_matrix = Array2::from_elem(padded_shape, value);
 
     // Slice the padded matrix and assign the input matrix values to it
     padded_matrix
@@ -656,7 +857,7 @@ pub fn pad_matrix_2d(matrix: &Array2<f32>, pads: &Vec<i64>) -> Result<Array2<f32
     Ok(padded_matrix)
 }
 
-pub fn pad_matrix_3d(matrix: &Array3<f32>, pads: &Vec<i64>) -> Result<Array3<f32>, OnnxError> {
+pub fn pad_matrix_3d(matrix: &Array3<f32>, pads: &[i64]) -> Result<Array3<f32>, OnnxError> {
     let c = matrix.shape()[0];
 
     // Vector to store the actual padded slices (owned data)
@@ -667,7 +868,7 @@ pub fn pad_matrix_3d(matrix: &Array3<f32>, pads: &Vec<i64>) -> Result<Array3<f32
         let slice = matrix.index_axis(ndarray::Axis(0), depth).to_owned();
 
         // Pad the slice using the existing function
-        let padded = pad_matrix_2d(&slice, pads)?;
+        let padded = pad_matrix_2d(&slice, pads, 0.0)?;
 
         // Push the padded slice into the vector
         padded_data.push(padded);
@@ -749,3 +950,258 @@ where
 
     Ok(reshaped_output)
 }
+
+/// Computes the shape two tensors are broadcast to, following the multidirectional (Numpy-style)
+/// broadcasting rules of ONNX: the shapes are aligned on their last dimension, and each pair of
+/// dimensions must either be equal or contain a 1.
+///
+/// # Arguments
+///
+/// * `a` - The shape of the first tensor.
+/// * `b` - The shape of the second tensor.
+///
+/// # Returns
+///
+/// * `Result<Vec<usize>, OnnxError>` - The broadcast shape, or an error
+///   (`OnnxError::ShapeMismatch`) if the shapes are not compatible.
+///
+/// # Example
+///
+/// ```rust
+/// let shape = broadcast_shapes(&[2, 1, 4], &[3, 1])?;
+/// assert_eq!(shape, vec![2, 3, 4]);
+/// ```
+pub fn broadcast_shapes(a: &[usize], b: &[usize]) -> Result<Vec<usize>, OnnxError> {
+    let rank = a.len().max(b.len());
+    let dim = |shape: &[usize], i: usize| {
+        (i + shape.len())
+            .checked_sub(rank)
+            .map_or(1, |index| shape[index])
+    };
+
+    (0..rank)
+        .map(|i| match (dim(a, i), dim(b, i)) {
+            (x, y) if x == y || y == 1 => Ok(x),
+            (1, y) => Ok(y),
+            _ => Err(OnnxError::shape_mismatch(format!(
+                "Shapes {:?} and {:?} can't be broadcast together",
+                a, b
+            ))),
+        })
+        .collect()
+}
+
+/// Computes the shape any number of tensors are broadcast to (e.g. the condition and the two
+/// branches of a Where), by broadcasting the shapes together one after the other with
+/// `broadcast_shapes`. No shapes give a scalar.
+///
+/// # Example
+///
+/// ```rust
+/// let shape = broadcast_all_shapes(&[&[], &[3, 1], &[2, 1, 4]])?;
+/// assert_eq!(shape, vec![2, 3, 4]);
+/// ```
+pub fn broadcast_all_shapes(shapes: &[&[usize]]) -> Result<Vec<usize>, OnnxError> {
+    shapes.iter().try_fold(Vec::new(), |shape, next| {
+        broadcast_shapes(&shape, next).map_err(|_| {
+            OnnxError::shape_mismatch(format!(
+                "Shapes {:?} can't be broadcast together",
+                shapes
+            ))
+        })
+    })
+}
+
+/// Applies `f` to every pair of elements of `a` and `b` broadcast together (see
+/// `broadcast_shapes`), e.g. to compare two tensors element by element.
+///
+/// # Example
+///
+/// ```rust
+/// let greater = broadcast_map(&scores, &threshold, |score, threshold| score > threshold)?;
+/// ```
+pub fn broadcast_map<A, B, C, F>(
+    a: &ArrayD<A>,
+    b: &ArrayD<B>,
+    f: F,
+) -> Result<ArrayD<C>, OnnxError>
+where
+    F: Fn(&A, &B) -> C,
+{
+    let shape = IxDyn(&broadcast_shapes(a.shape(), b.shape())?);
+    // The shapes broadcast together, so both views exist.
+    match (a.broadcast(shape.clone()), b.broadcast(shape)) {
+        (Some(a), Some(b)) => Ok(Zip::from(a).and(b).map_collect(f)),
+        _ => Err(OnnxError::shape_mismatch(format!(
+            "Shapes {:?} and {:?} can't be broadcast together",
+            a.shape(),
+            b.shape()
+        ))),
+    }
+}
+
+/// Checks that a tensor of shape `shape` can be unidirectionally broadcast to `target` (e.g. the
+/// slope of PRelu to its input): the shapes must broadcast together (see `broadcast_shapes`) to
+/// `target` itself, so that only `shape` is stretched.
+///
+/// # Example
+///
+/// ```rust
+/// unidirectional_broadcast(&[2, 3, 4, 4], &[3, 1, 1])?;
+/// assert!(unidirectional_broadcast(&[3, 1], &[3, 4]).is_err());
+/// ```
+pub fn unidirectional_broadcast(target: &[usize], shape: &[usize]) -> Result<(), OnnxError> {
+    if broadcast_shapes(target, shape)? != target {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Expected a shape broadcastable to {:?}, got {:?}",
+            target, shape
+        )));
+    }
+    Ok(())
+}
+
+/// Geometry of a pooling window along one spatial axis (see `pool_geometry`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolAxis {
+    pub kernel: usize,
+    pub stride: usize,
+    /// Spacing between the taps of the window.
+    pub dilation: usize,
+    /// Padding at the beginning and at the end of the axis.
+    pub pad_begin: usize,
+    pub pad_end: usize,
+    pub output_len: usize,
+}
+
+impl PoolAxis {
+    /// Positions of the taps of the window of the output `output`, relative to the unpadded
+    /// input: negative in the padding at the beginning, beyond the input in the one at the end.
+    pub fn taps(&self, output: usize) -> impl Iterator<Item = isize> {
+        let start = (output * self.stride) as isize - self.pad_begin as isize;
+        let dilation = self.dilation as isize;
+        (0..self.kernel as isize).map(move |tap| start + tap * dilation)
+    }
+
+    /// Whether a position returned by `taps` lies in the padded input.
+    pub fn in_padded_input(&self, position: isize, input_len: usize) -> bool {
+        position >= -(self.pad_begin as isize) && position < (input_len + self.pad_end) as isize
+    }
+}
+
+/// Computes the pooling windows along every spatial axis of the input.
+///
+/// The attributes are the ones shared by the pooling operations: `kernel_shape` (required), and
+/// `strides`, `dilations`, `pads`, `auto_pad` and `ceil_mode`. With explicit `pads` (`auto_pad`
+/// `NOTSET`), an axis has `(input + pads - dilation * (kernel - 1) - 1) / stride + 1` outputs,
+/// the division rounding up with `ceil_mode`, in which case a window starting in the padding at
+/// the end is dropped. `SAME_UPPER` and `SAME_LOWER` pad the input for `ceil(input / stride)`
+/// outputs, the extra pixel of an odd padding going at the end and at the beginning
+/// respectively, and `VALID` doesn't pad it.
+///
+/// # Arguments
+///
+/// * `attributes` - The attributes of the pooling node.
+/// * `input_shape` - The spatial shape of the input.
+///
+/// # Returns
+///
+/// * `Result<Vec<PoolAxis>, OnnxError>` - The geometry of every spatial axis, or an error
+///   (`OnnxError`) if an attribute is missing or invalid, or if the window doesn't fit in the
+///   padded input.
+///
+/// # Example
+///
+/// ```rust
+/// // kernel_shape [3, 3], pads [1, 1, 1, 1]: 5x5 inputs give 5x5 outputs.
+/// let geometry = pool_geometry(&attributes, &input.shape()[2..])?;
+/// assert_eq!(geometry[0].output_len, 5);
+/// ```
+pub fn pool_geometry(
+    attributes: &HashMap<String, Attribute<String>>,
+    input_shape: &[usize],
+) -> Result<Vec<PoolAxis>, OnnxError> {
+    let rank = input_shape.len();
+    let kernel_shape = get_ints_attribute(attributes, "kernel_shape", None)?;
+    let strides = get_ints_attribute(attributes, "strides", Some(vec![1; rank]))?;
+    let dilations = get_ints_attribute(attributes, "dilations", Some(vec![1; rank]))?;
+    let pads = get_ints_attribute(attributes, "pads", Some(vec![0; 2 * rank]))?;
+    let auto_pad = get_string_attribute(attributes, "auto_pad", Some("NOTSET".to_string()))?;
+    let ceil_mode = get_int_attribute(attributes, "ceil_mode", Some(0))? != 0;
+
+    for (name, values, len, min) in [
+        ("kernel_shape", &kernel_shape, rank, 1),
+        ("strides", &strides, rank, 1),
+        ("dilations", &dilations, rank, 1),
+        ("pads", &pads, 2 * rank, 0),
+    ] {
+        if values.len() != len || values.iter().any(|&value| value < min) {
+            return Err(OnnxError::InvalidValue(format!(
+                "Expected {} {} of at least {} for an input of spatial shape {:?}, got {:?}",
+                len, name, min, input_shape, values
+            )));
+        }
+    }
+
+    (0..rank)
+        .map(|axis| {
+            let input = input_shape[axis];
+            let (kernel, stride, dilation) = (
+                kernel_shape[axis] as usize,
+                strides[axis] as usize,
+                dilations[axis] as usize,
+            );
+            let window = dilation * (kernel - 1) + 1;
+
+            let (pad_begin, pad_end) = match auto_pad.as_str() {
+                "NOTSET" | "NOT_SET" => (pads[axis] as usize, pads[axis + rank] as usize),
+                "VALID" => (0, 0),
+                "SAME_UPPER" | "SAME_LOWER" => {
+                    let output = input.div_ceil(stride);
+                    let total = ((output - 1) * stride + window).saturating_sub(input);
+                    if auto_pad == "SAME_UPPER" {
+                        (total / 2, total - total / 2)
+                    } else {
+                        (total - total / 2, total / 2)
+                    }
+                }
+                _ => {
+                    return Err(OnnxError::InvalidValue(format!(
+                        "Invalid auto_pad value {}",
+                        auto_pad
+                    )))
+                }
+            };
+
+            let padded = input + pad_begin + pad_end;
+            if padded < window {
+                return Err(OnnxError::shape_mismatch(format!(
+                    "The window of {} pixels doesn't fit in the padded input of {} pixels along \
+                     axis {}",
+                    window,
+                    padded,
+                    axis + 2
+                )));
+            }
+
+            let explicit_pads = matches!(auto_pad.as_str(), "NOTSET" | "NOT_SET");
+            let mut output_len = if ceil_mode && explicit_pads {
+                (padded - window).div_ceil(stride) + 1
+            } else {
+                (padded - window) / stride + 1
+            };
+            // The last window must start in the input or in the padding at the beginning.
+            if (output_len - 1) * stride >= input + pad_begin {
+                output_len -= 1;
+            }
+
+            Ok(PoolAxis {
+                kernel,
+                stride,
+                dilation,
+                pad_begin,
+                pad_end,
+                output_len,
+            })
+        })
+        .collect()
+}
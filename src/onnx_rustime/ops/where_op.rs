@@ -0,0 +1,103 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::utils::{
+    broadcast_all_shapes, ndarray_to_tensor_proto, tensor_proto_to_ndarray, TensorType,
+};
+use ndarray::{ArrayD, IxDyn, Zip};
+
+/// Selects the elements of `x` where `condition` is true and the ones of `y` elsewhere, the three
+/// tensors being broadcast to `shape`, and encodes them as the output `name`.
+fn select_as<T: TensorType>(
+    condition: &ArrayD<bool>,
+    x: &TensorProto,
+    y: &TensorProto,
+    shape: &[usize],
+    name: &str,
+) -> Result<TensorProto, OnnxError> {
+    let x = tensor_proto_to_ndarray::<T>(x)?;
+    let y = tensor_proto_to_ndarray::<T>(y)?;
+    // The shapes were checked by `broadcast_all_shapes`.
+    let broadcast_error = || OnnxError::shape_mismatch(format!("Cannot broadcast to {:?}", shape));
+    let selected = Zip::from(
+        condition
+            .broadcast(IxDyn(shape))
+            .ok_or_else(broadcast_error)?,
+    )
+    .and(x.broadcast(IxDyn(shape)).ok_or_else(broadcast_error)?)
+    .and(y.broadcast(IxDyn(shape)).ok_or_else(broadcast_error)?)
+    .map_collect(|&condition, x, y| match condition {
+        true => x.clone(),
+        false => y.clone(),
+    });
+    ndarray_to_tensor_proto::<T>(selected, name)
+}
+
+/// `where_op` - ONNX Node Implementation for Where
+///
+/// Selects, element by element, the value of `X` where the BOOL tensor `condition` is true and
+/// the one of `Y` elsewhere, e.g. to mask the scores of an attention with a large negative value.
+/// The three inputs are broadcast together, as in NumPy (see `broadcast_all_shapes`): a scalar
+/// condition selects a whole branch.
+///
+/// # Arguments
+///
+/// * `inputs` - The tensors `condition` (BOOL), `X` and `Y` (both FLOAT, FLOAT16, INT64 or BOOL).
+/// * `node` - A reference to the ONNX NodeProto containing node-specific data and attributes.
+///
+/// # Returns
+///
+/// * `Result<TensorProto, OnnxError>` - Outputs the selected tensor, of the type of `X` (FLOAT for
+///   a FLOAT16 `X`). In case of an unsuccessful operation, it returns an error (`OnnxError`).
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * Missing inputs, or a `condition` that isn't BOOL.
+/// * Shapes that can't be broadcast together.
+///
+/// # Example
+///
+/// ```rust
+/// let masked_scores = where_op(&[&mask, &scores, &minus_infinity], &node)?;
+/// ```
+pub fn where_op(inputs: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let [condition, x, y] = match inputs {
+        [condition, x, y] => [*condition, *x, *y],
+        _ => {
+            return Err(OnnxError::MissingInput(format!(
+                "Where expects the condition, X and Y, got {} inputs",
+                inputs.len()
+            )))
+        }
+    };
+    let output_name = node
+        .get_output()
+        .first()
+        .ok_or(OnnxError::InternalError("Output name missing".to_string()))?;
+
+    if condition.get_data_type() != TensorProto_DataType::BOOL as i32 {
+        return Err(OnnxError::InvalidValue(format!(
+            "The condition of a Where must be BOOL, got the data type {}",
+            condition.get_data_type()
+        )));
+    }
+    let condition = tensor_proto_to_ndarray::<bool>(condition)?;
+
+    let dims = |tensor: &TensorProto| -> Vec<usize> {
+        tensor.get_dims().iter().map(|&dim| dim as usize).collect()
+    };
+    let (x_shape, y_shape) = (dims(x), dims(y));
+    let shape = broadcast_all_shapes(&[condition.shape(), &x_shape, &y_shape])?;
+
+    match x.get_data_type() {
+        t if t == TensorProto_DataType::INT64 as i32 => {
+            select_as::<i64>(&condition, x, y, &shape, output_name)
+        }
+        t if t == TensorProto_DataType::BOOL as i32 => {
+            select_as::<bool>(&condition, x, y, &shape, output_name)
+        }
+        _ => select_as::<f32>(&condition, x, y, &shape, output_name),
+    }
+}
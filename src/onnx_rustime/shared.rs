@@ -1,11 +1,22 @@
-use lazy_static::lazy_static;
-use std::sync::Mutex;
+use crate::onnx_rustime::backend::format::FormatOptions;
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{NodeProto, TensorProto};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-lazy_static! {
-    pub static ref VERBOSE: Mutex<bool> = Mutex::new(true);
+/// What to do when a node produces NaN or infinite values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NonFiniteCheck {
+    /// Node outputs are not scanned.
+    Off,
+    /// A warning is logged and the execution continues.
+    Warn,
+    /// The execution stops with `OnnxError::NonFiniteOutput`.
+    Abort,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Model {
     AlexNet,
     CaffeNet,
@@ -16,8 +27,94 @@ pub enum Model {
     PreProcessing,
 }
 
-lazy_static! {
-    pub static ref MODEL_NAME: Mutex<Model> = Mutex::new(Model::AlexNet);
+/// Kernel of a custom operation (see `RunOptions::custom_ops`). It receives the operands of the
+/// node in the order of its inputs (missing optional inputs are skipped), and the node itself for
+/// its attributes. An operand forwarded by a pass-through node (e.g. an `Identity`) is shared with
+/// its source, and may still carry the name of the source: the names of the operands are the
+/// inputs of the node.
+pub type CustomOp = fn(&[&TensorProto], &NodeProto) -> Result<TensorProto, OnnxError>;
+
+/// A flag asking the runs holding it to stop (see `RunOptions::cancellation`). Clones share the
+/// flag, so that a run can be cancelled from another thread.
+///
+/// A run checks the flag before every node: the node being run when the flag is set completes,
+/// then the run stops with `OnnxError::Cancelled`.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    parent: Option<Box<CancellationToken>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that is cancelled by `cancel`, or when this one is cancelled.
+    pub fn child_token(&self) -> Self {
+        CancellationToken {
+            cancelled: Arc::default(),
+            parent: Some(Box::new(self.clone())),
+        }
+    }
+
+    /// Cancels the runs holding the token (or one of its children).
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
+/// Options of a run of a model, passed to `run` (and to the other entry points of
+/// `backend::run`) and to the display functions. Nothing is process-wide, so that runs with
+/// different options can happen at the same time.
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    /// Log the operands and the output of every node.
+    pub verbose: bool,
+    /// The model being run, which selects the labels of the classes, and whether its outputs are
+    /// logits to be normalized, when displaying them.
+    pub model: Model,
+    /// Scan the output of every node for NaN and infinite values.
+    pub non_finite_check: NonFiniteCheck,
+    /// Run the naive reference implementations (see `ops::reference`) instead of the optimized
+    /// kernels, for the operations having one.
+    pub reference_kernels: bool,
+    /// Log the time spent in every operation type at the end of the run.
+    pub profiling: bool,
+    /// Number of threads running the kernels, or `None` for the global rayon pool (one thread
+    /// per core).
+    pub threads: Option<usize>,
+    /// Kernels of custom operations, by operation type. They take precedence over the built-in
+    /// kernels of the operations with the same type.
+    pub custom_ops: HashMap<String, CustomOp>,
+    /// Stop the run with `OnnxError::Cancelled` once the token is cancelled.
+    pub cancellation: Option<CancellationToken>,
+    /// How the tensors are printed: the outputs, and the output of every node when `verbose`.
+    pub tensor_format: FormatOptions,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            verbose: false,
+            model: Model::AlexNet,
+            non_finite_check: NonFiniteCheck::Off,
+            reference_kernels: false,
+            profiling: false,
+            threads: None,
+            custom_ops: HashMap::new(),
+            cancellation: None,
+            tensor_format: FormatOptions::default(),
+        }
+    }
 }
 
 pub const MNIST_CLASSES: [&str; 10] = [
@@ -0,0 +1,261 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use half::f16;
+use ndarray::{ArrayD, Dimension};
+use std::fmt;
+
+/// How two elements are compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    /// `|actual - expected| <= atol + rtol * |expected|`, the rule used by `numpy.allclose`.
+    AbsRel { atol: f64, rtol: f64 },
+    /// The elements are at most this many representable values apart. Useful to check that a
+    /// kernel change only affects the last bits of the results.
+    Ulps(u64),
+}
+
+/// Options of `tensors_close`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CloseOptions {
+    pub comparison: Comparison,
+    /// Whether two NaN elements are considered equal. A NaN compared with a number is always a
+    /// violation.
+    pub nan_equal: bool,
+}
+
+impl CloseOptions {
+    /// Absolute/relative comparison, with NaN never equal to NaN.
+    pub fn abs_rel(atol: f64, rtol: f64) -> Self {
+        CloseOptions {
+            comparison: Comparison::AbsRel { atol, rtol },
+            nan_equal: false,
+        }
+    }
+
+    /// ULP-distance comparison, with NaN never equal to NaN.
+    pub fn ulps(max_ulps: u64) -> Self {
+        CloseOptions {
+            comparison: Comparison::Ulps(max_ulps),
+            nan_equal: false,
+        }
+    }
+
+    /// Makes two NaN elements compare equal.
+    pub fn with_nan_equal(mut self, nan_equal: bool) -> Self {
+        self.nan_equal = nan_equal;
+        self
+    }
+}
+
+/// Floating point types `tensors_close` can compare.
+pub trait CloseElement: Copy + fmt::Debug {
+    fn to_f64(self) -> f64;
+
+    /// Number of representable values between `self` and `other` (0 if they are equal).
+    fn ulp_distance(self, other: Self) -> u64;
+}
+
+/// Maps the bits of a float to integers ordered as the floats they represent, so that
+/// consecutive floats map to consecutive integers (`-0.0` and `0.0` both map to 0).
+macro_rules! impl_close_element {
+    ($t: ty, $signed: ty) => {
+        impl CloseElement for $t {
+            fn to_f64(self) -> f64 {
+                self.into()
+            }
+
+            fn ulp_distance(self, other: Self) -> u64 {
+                let ordered = |value: $t| {
+                    let bits = value.to_bits() as $signed;
+                    if bits < 0 {
+                        <$signed>::MIN - bits
+                    } else {
+                        bits
+                    }
+                };
+                (ordered(self) as i128 - ordered(other) as i128).unsigned_abs() as u64
+            }
+        }
+    };
+}
+
+impl_close_element!(f32, i32);
+impl_close_element!(f64, i64);
+impl_close_element!(f16, i16);
+
+/// Outcome of a tensor comparison.
+#[derive(Debug, Clone)]
+pub struct CloseReport {
+    pub options: CloseOptions,
+    /// Number of compared elements.
+    pub elements: usize,
+    /// Number of elements outside the tolerance.
+    pub violations: usize,
+    /// Largest absolute difference between two finite numbers.
+    pub max_abs_diff: f64,
+    /// Largest difference relative to the expected value (infinite if the expected value is 0).
+    pub max_rel_diff: f64,
+    /// Largest distance in ULPs between two finite numbers.
+    pub max_ulp_diff: u64,
+    /// Index of the worst element: the violation with the largest absolute difference (NaN
+    /// mismatches first), or the element with the largest difference if there are no
+    /// violations. `None` for empty tensors.
+    pub worst_index: Option<Vec<usize>>,
+    /// Actual and expected values at `worst_index`.
+    pub worst_values: Option<(f64, f64)>,
+}
+
+impl CloseReport {
+    /// Whether every element is within the tolerance.
+    pub fn is_close(&self) -> bool {
+        self.violations == 0
+    }
+}
+
+impl fmt::Display for CloseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.options.comparison {
+            Comparison::AbsRel { atol, rtol } => write!(
+                f,
+                "{} of {} elements differ (atol {}, rtol {})",
+                self.violations, self.elements, atol, rtol
+            )?,
+            Comparison::Ulps(max_ulps) => write!(
+                f,
+                "{} of {} elements differ (max {} ulps)",
+                self.violations, self.elements, max_ulps
+            )?,
+        }
+        write!(
+            f,
+            ", max abs diff {}, max rel diff {}, max ulp diff {}",
+            self.max_abs_diff, self.max_rel_diff, self.max_ulp_diff
+        )?;
+        if let (Some(index), Some((actual, expected))) = (&self.worst_index, self.worst_values) {
+            write!(
+                f,
+                ", worst at {:?}: expected {}, got {}",
+                index, expected, actual
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// `tensors_close` - Compares two tensors element by element
+///
+/// Infinities are close only to the same infinity, and NaN only to NaN when
+/// `options.nan_equal` is set.
+///
+/// # Arguments
+///
+/// * `actual` - The computed tensor.
+/// * `expected` - The reference tensor.
+/// * `options` - The comparison rule and the NaN handling (see `CloseOptions`).
+///
+/// # Returns
+///
+/// * `Result<CloseReport, OnnxError>` - The statistics of the differences, or an error
+///   (`OnnxError::ShapeMismatch`) if the two tensors have different shapes.
+///
+/// # Example
+///
+/// ```rust
+/// let report = tensors_close(&output, &expected, CloseOptions::abs_rel(1e-5, 1e-4))?;
+/// if !report.is_close() {
+///     println!("{}", report);
+/// }
+/// ```
+pub fn tensors_close<T: CloseElement>(
+    actual: &ArrayD<T>,
+    expected: &ArrayD<T>,
+    options: CloseOptions,
+) -> Result<CloseReport, OnnxError> {
+    if actual.shape() != expected.shape() {
+        return Err(OnnxError::shape_mismatch(format!(
+            "Can't compare a tensor of shape {:?} with one of shape {:?}",
+            actual.shape(),
+            expected.shape()
+        )));
+    }
+
+    let mut report = CloseReport {
+        options,
+        elements: expected.len(),
+        violations: 0,
+        max_abs_diff: 0.0,
+        max_rel_diff: 0.0,
+        max_ulp_diff: 0,
+        worst_index: None,
+        worst_values: None,
+    };
+    // (is a violation, absolute difference) of the worst element so far.
+    let mut worst: Option<(bool, f64)> = None;
+
+    for ((index, &a), &e) in actual.indexed_iter().zip(expected.iter()) {
+        let (a64, e64) = (a.to_f64(), e.to_f64());
+
+        let (close, abs_diff) = if a64.is_nan() || e64.is_nan() {
+            (
+                options.nan_equal && a64.is_nan() && e64.is_nan(),
+                f64::INFINITY,
+            )
+        } else if a64 == e64 {
+            (true, 0.0)
+        } else if a64.is_infinite() || e64.is_infinite() {
+            (false, f64::INFINITY)
+        } else {
+            let abs_diff = (a64 - e64).abs();
+            let ulps = a.ulp_distance(e);
+            report.max_abs_diff = report.max_abs_diff.max(abs_diff);
+            report.max_rel_diff = report.max_rel_diff.max(abs_diff / e64.abs());
+            report.max_ulp_diff = report.max_ulp_diff.max(ulps);
+
+            let close = match options.comparison {
+                Comparison::AbsRel { atol, rtol } => abs_diff <= atol + rtol * e64.abs(),
+                Comparison::Ulps(max_ulps) => ulps <= max_ulps,
+            };
+            (close, abs_diff)
+        };
+
+        if !close {
+            report.violations += 1;
+        }
+        // NaN mismatches are infinitely wrong, but matching NaNs are not wrong at all.
+        let abs_diff = if close && abs_diff.is_infinite() {
+            0.0
+        } else {
+            abs_diff
+        };
+        if worst.is_none_or(|(worst_violation, worst_diff)| {
+            (!close, abs_diff) > (worst_violation, worst_diff)
+        }) {
+            worst = Some((!close, abs_diff));
+            report.worst_index = Some(index.as_array_view().to_vec());
+            report.worst_values = Some((a64, e64));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Panics with a detailed message if two tensors have different shapes or are not close
+/// according to `|actual - expected| <= atol + rtol * |expected|`.
+///
+/// # Example
+///
+/// ```rust
+/// assert_tensors_close(&output, &expected, 1e-6, 1e-5);
+/// ```
+#[track_caller]
+pub fn assert_tensors_close<T: CloseElement>(
+    actual: &ArrayD<T>,
+    expected: &ArrayD<T>,
+    atol: f64,
+    rtol: f64,
+) {
+    match tensors_close(actual, expected, CloseOptions::abs_rel(atol, rtol)) {
+        Ok(report) if report.is_close() => {}
+        Ok(report) => panic!("Tensors are not close: {}", report),
+        Err(e) => panic!("Tensors are not close: {:?}", e),
+    }
+}
@@ -0,0 +1,436 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::backend::parser::OnnxParser;
+use crate::onnx_rustime::backend::run::{operation_support, run_with_inputs};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{TensorProto, TensorProto_DataType};
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::testing::catch_panic;
+use crate::onnx_rustime::testing::compare::{tensors_close, CloseOptions};
+use half::f16;
+use ndarray::ArrayD;
+use protobuf::ProtobufEnum;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Comparison options for the outputs of a given data type (`TensorProto_DataType` value).
+///
+/// Floating point outputs use the tolerances of the ONNX backend tests (looser for FLOAT16),
+/// integer and boolean outputs must match exactly. NaN outputs are expected where the reference
+/// has NaN.
+pub fn tolerance(data_type: i32) -> CloseOptions {
+    let options = match TensorProto_DataType::from_i32(data_type) {
+        Some(TensorProto_DataType::FLOAT) | Some(TensorProto_DataType::DOUBLE) => {
+            CloseOptions::abs_rel(1e-7, 1e-3)
+        }
+        Some(TensorProto_DataType::FLOAT16) | Some(TensorProto_DataType::BFLOAT16) => {
+            CloseOptions::abs_rel(1e-3, 1e-2)
+        }
+        _ => CloseOptions::abs_rel(0.0, 0.0),
+    };
+    options.with_nan_equal(true)
+}
+
+/// Outcome of a test case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseStatus {
+    Passed,
+    Failed,
+    /// The case uses an operation (or a variant of it) the runtime doesn't support.
+    Skipped,
+}
+
+impl fmt::Display for CaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaseStatus::Passed => write!(f, "passed"),
+            CaseStatus::Failed => write!(f, "failed"),
+            CaseStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Result of a single test case (a directory with a `model.onnx` and its test data sets).
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub name: String,
+    /// Operation types of the model, in order of first appearance.
+    pub op_types: Vec<String>,
+    pub status: CaseStatus,
+    /// Why the case failed or was skipped, empty for passed cases.
+    pub message: String,
+}
+
+/// Results of a conformance run.
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    pub cases: Vec<CaseResult>,
+}
+
+impl ConformanceReport {
+    /// Number of cases with the given outcome.
+    pub fn count(&self, status: CaseStatus) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.status == status)
+            .count()
+    }
+
+    /// Number of passed, failed and skipped cases for every operation type, sorted by name.
+    ///
+    /// A case is counted for every operation it contains.
+    pub fn by_op_type(&self) -> Vec<(String, [usize; 3])> {
+        let mut counts: Vec<(String, [usize; 3])> = Vec::new();
+        for case in &self.cases {
+            let column = match case.status {
+                CaseStatus::Passed => 0,
+                CaseStatus::Failed => 1,
+                CaseStatus::Skipped => 2,
+            };
+            for op_type in &case.op_types {
+                match counts.iter_mut().find(|(name, _)| name == op_type) {
+                    Some((_, count)) => count[column] += 1,
+                    None => {
+                        let mut count = [0; 3];
+                        count[column] = 1;
+                        counts.push((op_type.clone(), count));
+                    }
+                }
+            }
+        }
+        counts.sort_by(|a, b| a.0.cmp(&b.0));
+        counts
+    }
+
+    /// Serializes the report as JSON, with the totals and the result of every case.
+    pub fn to_json(&self) -> String {
+        let cases: Vec<String> = self
+            .cases
+            .iter()
+            .map(|case| {
+                let op_types: Vec<String> =
+                    case.op_types.iter().map(|op| json_string(op)).collect();
+                format!(
+                    "    {{\"name\": {}, \"op_types\": [{}], \"status\": \"{}\", \"message\": {}}}",
+                    json_string(&case.name),
+                    op_types.join(", "),
+                    case.status,
+                    json_string(&case.message)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"passed\": {},\n  \"failed\": {},\n  \"skipped\": {},\n  \"cases\": [\n{}\n  ]\n}}\n",
+            self.count(CaseStatus::Passed),
+            self.count(CaseStatus::Failed),
+            self.count(CaseStatus::Skipped),
+            cases.join(",\n")
+        )
+    }
+}
+
+/// `run_node_tests` - Runs the ONNX backend node tests
+///
+/// The ONNX repository ships a test case for every operation and variant, in
+/// `onnx/backend/test/data/node`: each case is a directory with a `model.onnx` and one or more
+/// `test_data_set_N` directories holding `input_K.pb` and the expected `output_K.pb`.
+///
+/// Every case whose operations are all supported is run through `run_with_inputs`, and its
+/// outputs are compared with the expected ones by `tensors_close` (see `tolerance`). Cases with unsupported
+/// operations, or failing with `OnnxError::UnsupportedOperation`, are skipped. Panics raised by
+/// the kernels are caught and reported as failures.
+///
+/// # Arguments
+///
+/// * `dir` - The directory containing the test cases.
+/// * `filter` - If present, only the cases whose name contains it are run.
+///
+/// # Returns
+///
+/// * `Result<ConformanceReport, OnnxError>` - The results of the cases, sorted by name, or an
+///   error (`OnnxError`) if `dir` can't be read.
+///
+/// # Example
+///
+/// ```rust
+/// let report = run_node_tests(Path::new("onnx/backend/test/data/node"), Some("test_relu"))?;
+/// println!("{} passed", report.count(CaseStatus::Passed));
+/// ```
+pub fn run_node_tests(dir: &Path, filter: Option<&str>) -> Result<ConformanceReport, OnnxError> {
+    let entries = fs::read_dir(dir).map_err(|e| OnnxError::io(dir, e))?;
+
+    let mut case_dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join("model.onnx").is_file())
+        .filter(|path| filter.is_none_or(|filter| case_name(path).contains(filter)))
+        .collect();
+    case_dirs.sort();
+
+    let cases = case_dirs.iter().map(|path| run_node_test(path)).collect();
+
+    Ok(ConformanceReport { cases })
+}
+
+/// Runs a single test case directory. See `run_node_tests`.
+pub fn run_node_test(case_dir: &Path) -> CaseResult {
+    let mut result = CaseResult {
+        name: case_name(case_dir),
+        op_types: Vec::new(),
+        status: CaseStatus::Failed,
+        message: String::new(),
+    };
+
+    let model_path = case_dir.join("model.onnx");
+    let model = match OnnxParser::load_model(model_path.to_string_lossy().to_string()) {
+        Ok(model) => model,
+        Err(e) => {
+            result.message = format!("Failed to load the model: {}", e);
+            return result;
+        }
+    };
+
+    let mut unsupported = Vec::new();
+    for node in model.get_graph().get_node() {
+        let op_type = match node.get_domain() {
+            "" | "ai.onnx" => node.get_op_type().to_string(),
+            domain => format!("{}.{}", domain, node.get_op_type()),
+        };
+        if operation_support(&op_type).is_none() && !unsupported.contains(&op_type) {
+            unsupported.push(op_type.clone());
+        }
+        if !result.op_types.contains(&op_type) {
+            result.op_types.push(op_type);
+        }
+    }
+    if !unsupported.is_empty() {
+        result.status = CaseStatus::Skipped;
+        result.message = format!("Unsupported operations: {}", unsupported.join(", "));
+        return result;
+    }
+
+    let mut data_sets: Vec<PathBuf> = fs::read_dir(case_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir() && case_name(path).starts_with("test_data_set_"))
+                .collect()
+        })
+        .unwrap_or_default();
+    data_sets.sort();
+    if data_sets.is_empty() {
+        result.message = "No test data set found".to_string();
+        return result;
+    }
+
+    for data_set in &data_sets {
+        let (inputs, expected) = match (
+            load_tensors(data_set, "input"),
+            load_tensors(data_set, "output"),
+        ) {
+            (Ok(inputs), Ok(expected)) => (inputs, expected),
+            (Err(e), _) | (_, Err(e)) => {
+                result.message = format!("{}: {}", case_name(data_set), e);
+                return result;
+            }
+        };
+
+        let outcome = catch_panic(|| run_with_inputs(&model, inputs, &RunOptions::default()));
+        let outputs = match outcome {
+            Ok(Ok(outputs)) => outputs,
+            Ok(Err(e)) if matches!(e.root_cause(), OnnxError::UnsupportedOperation(_)) => {
+                result.status = CaseStatus::Skipped;
+                result.message = e.root_cause().to_string();
+                return result;
+            }
+            Ok(Err(e)) => {
+                result.message = format!("{}: {}", case_name(data_set), e);
+                return result;
+            }
+            Err(message) => {
+                result.message = format!("{}: panicked: {}", case_name(data_set), message);
+                return result;
+            }
+        };
+
+        if outputs.len() != expected.len() {
+            result.message = format!(
+                "{}: expected {} outputs, got {}",
+                case_name(data_set),
+                expected.len(),
+                outputs.len()
+            );
+            return result;
+        }
+        for (index, (actual, expected)) in outputs.iter().zip(&expected).enumerate() {
+            if let Err(message) = compare_tensors(actual, expected) {
+                result.message = format!("{}: output {}: {}", case_name(data_set), index, message);
+                return result;
+            }
+        }
+    }
+
+    result.status = CaseStatus::Passed;
+    result
+}
+
+fn case_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Loads `<prefix>_0.pb`, `<prefix>_1.pb`, ... until the first missing index.
+fn load_tensors(dir: &Path, prefix: &str) -> Result<Vec<TensorProto>, OnnxError> {
+    let mut tensors = Vec::new();
+    loop {
+        let path = dir.join(format!("{}_{}.pb", prefix, tensors.len()));
+        if !path.is_file() {
+            return Ok(tensors);
+        }
+        tensors.push(OnnxParser::load_tensor_proto(path.to_string_lossy().to_string())?);
+    }
+}
+
+/// Compares an output with the expected tensor, describing the first difference found.
+fn compare_tensors(actual: &TensorProto, expected: &TensorProto) -> Result<(), String> {
+    if actual.get_data_type() != expected.get_data_type() {
+        return Err(format!(
+            "expected type {}, got {}",
+            data_type_name(expected.get_data_type()),
+            data_type_name(actual.get_data_type())
+        ));
+    }
+    if actual.get_dims() != expected.get_dims() {
+        return Err(format!(
+            "expected shape {:?}, got {:?}",
+            expected.get_dims(),
+            actual.get_dims()
+        ));
+    }
+
+    if expected.get_data_type() == TensorProto_DataType::STRING.value() {
+        return match actual.get_string_data() == expected.get_string_data() {
+            true => Ok(()),
+            false => Err("the strings differ".to_string()),
+        };
+    }
+
+    let shape: Vec<usize> = expected
+        .get_dims()
+        .iter()
+        .map(|&dim| dim as usize)
+        .collect();
+    let to_array =
+        |values: Vec<f64>| ArrayD::from_shape_vec(shape.clone(), values).map_err(|e| e.to_string());
+    let actual_values = tensor_values(actual)
+        .and_then(to_array)
+        .map_err(|e| format!("output: {}", e))?;
+    let expected_values = tensor_values(expected)
+        .and_then(to_array)
+        .map_err(|e| format!("expected output: {}", e))?;
+
+    let options = tolerance(expected.get_data_type());
+    match tensors_close(&actual_values, &expected_values, options) {
+        Ok(report) if report.is_close() => Ok(()),
+        Ok(report) => Err(report.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn data_type_name(data_type: i32) -> String {
+    TensorProto_DataType::from_i32(data_type)
+        .map(|data_type| format!("{:?}", data_type))
+        .unwrap_or_else(|| data_type.to_string())
+}
+
+/// Decodes the elements of a numeric or boolean tensor, stored either in `raw_data` or in the
+/// typed field of its data type.
+pub(crate) fn tensor_values(tensor: &TensorProto) -> Result<Vec<f64>, String> {
+    let raw = tensor.get_raw_data();
+    let from_raw = !raw.is_empty();
+
+    macro_rules! le_values {
+        ($t: ty) => {
+            raw.chunks_exact(std::mem::size_of::<$t>())
+                .map(|bytes| <$t>::from_le_bytes(bytes.try_into().unwrap()) as f64)
+                .collect()
+        };
+    }
+
+    let values: Vec<f64> = match TensorProto_DataType::from_i32(tensor.get_data_type()) {
+        Some(TensorProto_DataType::FLOAT) if from_raw => le_values!(f32),
+        Some(TensorProto_DataType::FLOAT) => {
+            tensor.get_float_data().iter().map(|&v| v as f64).collect()
+        }
+        Some(TensorProto_DataType::DOUBLE) if from_raw => le_values!(f64),
+        Some(TensorProto_DataType::DOUBLE) => tensor.get_double_data().to_vec(),
+        Some(TensorProto_DataType::FLOAT16) if from_raw => raw
+            .chunks_exact(2)
+            .map(|bytes| f16::from_le_bytes([bytes[0], bytes[1]]).to_f64())
+            .collect(),
+        Some(TensorProto_DataType::FLOAT16) => tensor
+            .get_int32_data()
+            .iter()
+            .map(|&bits| f16::from_bits(bits as u16).to_f64())
+            .collect(),
+        Some(TensorProto_DataType::INT8) if from_raw => le_values!(i8),
+        Some(TensorProto_DataType::UINT8) | Some(TensorProto_DataType::BOOL) if from_raw => {
+            le_values!(u8)
+        }
+        Some(TensorProto_DataType::INT16) if from_raw => le_values!(i16),
+        Some(TensorProto_DataType::UINT16) if from_raw => le_values!(u16),
+        Some(TensorProto_DataType::INT32) if from_raw => le_values!(i32),
+        Some(TensorProto_DataType::INT8)
+        | Some(TensorProto_DataType::UINT8)
+        | Some(TensorProto_DataType::BOOL)
+        | Some(TensorProto_DataType::INT16)
+        | Some(TensorProto_DataType::UINT16)
+        | Some(TensorProto_DataType::INT32) => {
+            tensor.get_int32_data().iter().map(|&v| v as f64).collect()
+        }
+        Some(TensorProto_DataType::INT64) if from_raw => le_values!(i64),
+        Some(TensorProto_DataType::INT64) => {
+            tensor.get_int64_data().iter().map(|&v| v as f64).collect()
+        }
+        Some(TensorProto_DataType::UINT32) if from_raw => le_values!(u32),
+        Some(TensorProto_DataType::UINT64) if from_raw => le_values!(u64),
+        Some(TensorProto_DataType::UINT32) | Some(TensorProto_DataType::UINT64) => {
+            tensor.get_uint64_data().iter().map(|&v| v as f64).collect()
+        }
+        _ => {
+            return Err(format!(
+                "can't compare tensors of type {}",
+                data_type_name(tensor.get_data_type())
+            ))
+        }
+    };
+
+    let expected_len = tensor.get_dims().iter().product::<i64>() as usize;
+    if values.len() != expected_len {
+        return Err(format!(
+            "{} elements stored for shape {:?}",
+            values.len(),
+            tensor.get_dims()
+        ));
+    }
+
+    Ok(values)
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
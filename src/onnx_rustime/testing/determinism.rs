@@ -0,0 +1,205 @@
+use crate::onnx_rustime::backend::helper::{make_attribute, Attribute, OnnxError};
+use crate::onnx_rustime::backend::run::run_with_inputs;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{ModelProto, NodeProto, TensorProto};
+use crate::onnx_rustime::ops::dropout::is_training_mode;
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::testing::conformance::tensor_values;
+use crate::onnx_rustime::testing::fnv1a;
+use protobuf::Message;
+
+/// Operations drawing random numbers, whatever their inputs. `Dropout` is random only in training
+/// mode.
+pub const RANDOM_OPS: [&str; 6] = [
+    "Bernoulli",
+    "Multinomial",
+    "RandomNormal",
+    "RandomNormalLike",
+    "RandomUniform",
+    "RandomUniformLike",
+];
+
+/// Seed given to the random nodes without one.
+pub const DETERMINISM_SEED: i64 = 0;
+
+/// Hashes of the outputs of one run.
+#[derive(Debug, Clone)]
+pub struct DeterminismRun {
+    /// Number of threads of the pool the run was executed in.
+    pub threads: usize,
+    /// 1-based index of the run among the ones with the same number of threads.
+    pub run: usize,
+    /// Hash of the serialized bytes of every output, in the order of the graph outputs.
+    pub hashes: Vec<String>,
+}
+
+/// An output differing from the one of the first run.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub threads: usize,
+    pub run: usize,
+    pub output: String,
+    /// Largest absolute difference from the first run (NaN if the shapes differ).
+    pub max_abs_diff: f64,
+}
+
+/// Outcome of `verify_determinism`.
+#[derive(Debug, Clone, Default)]
+pub struct DeterminismReport {
+    pub output_names: Vec<String>,
+    pub runs: Vec<DeterminismRun>,
+    pub divergences: Vec<Divergence>,
+    /// The random nodes that had no seed, and were given `DETERMINISM_SEED`, as `name (OpType)`.
+    pub seeded_nodes: Vec<String>,
+}
+
+impl DeterminismReport {
+    /// Whether every run produced the same bytes as the first one.
+    pub fn is_deterministic(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// `verify_determinism` - Checks that a model gives bit-identical outputs across runs and thread
+/// counts
+///
+/// The model is run `runs` times in a rayon pool of each of the given sizes, and the serialized
+/// bytes of every output are hashed. Any output differing from the one of the first run is
+/// reported with its largest numeric difference.
+///
+/// Random nodes (`RANDOM_OPS`, and `Dropout` unless its `training_mode` is a false initializer)
+/// without a `seed` attribute are given `DETERMINISM_SEED`, so that only unintended
+/// nondeterminism is reported. They are listed in the report.
+///
+/// # Arguments
+///
+/// * `model` - The model to be checked.
+/// * `inputs` - The input tensors, in the order of the graph inputs (see `run_with_inputs`).
+/// * `runs` - Number of runs for every thread count.
+/// * `thread_counts` - Sizes of the thread pools.
+///
+/// # Returns
+///
+/// * `Result<DeterminismReport, OnnxError>` - The hashes of every run and the divergences, or
+///   the first error raised while running the model.
+///
+/// # Example
+///
+/// ```rust
+/// let report = verify_determinism(&model, vec![input], 3, &[1, 4])?;
+/// assert!(report.is_deterministic());
+/// ```
+pub fn verify_determinism(
+    model: &ModelProto,
+    inputs: Vec<TensorProto>,
+    runs: usize,
+    thread_counts: &[usize],
+) -> Result<DeterminismReport, OnnxError> {
+    let mut model = model.clone();
+    let mut report = DeterminismReport {
+        seeded_nodes: seed_random_nodes(&mut model),
+        output_names: model
+            .get_graph()
+            .get_output()
+            .iter()
+            .map(|output| output.get_name().to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut reference: Option<Vec<TensorProto>> = None;
+    for &threads in thread_counts {
+        let options = RunOptions {
+            threads: Some(threads),
+            ..Default::default()
+        };
+
+        for run in 1..=runs {
+            let outputs = run_with_inputs(&model, inputs.clone(), &options)?;
+            let hashes: Vec<String> = outputs.iter().map(tensor_hash).collect();
+
+            if let Some(expected) = &reference {
+                for ((output, expected), name) in
+                    outputs.iter().zip(expected).zip(&report.output_names)
+                {
+                    if tensor_hash(output) != tensor_hash(expected) {
+                        report.divergences.push(Divergence {
+                            threads,
+                            run,
+                            output: name.clone(),
+                            max_abs_diff: max_abs_diff(output, expected),
+                        });
+                    }
+                }
+            } else {
+                reference = Some(outputs);
+            }
+
+            report.runs.push(DeterminismRun {
+                threads,
+                run,
+                hashes,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Gives `DETERMINISM_SEED` to the random nodes without a seed, returning their names.
+fn seed_random_nodes(model: &mut ModelProto) -> Vec<String> {
+    let graph = model.mut_graph();
+    let initializers = graph.get_initializer().to_vec();
+    let is_random = |node: &NodeProto| match node.get_op_type() {
+        "Dropout" => node
+            .get_input()
+            .get(2)
+            .filter(|name| !name.is_empty())
+            .is_some_and(|name| {
+                initializers
+                    .iter()
+                    .find(|init| init.get_name() == name)
+                    .is_none_or(is_training_mode)
+            }),
+        op_type => RANDOM_OPS.contains(&op_type),
+    };
+
+    let mut seeded = Vec::new();
+    for node in graph.mut_node().iter_mut() {
+        if !is_random(node) || node.get_attribute().iter().any(|a| a.get_name() == "seed") {
+            continue;
+        }
+
+        // The seed of Dropout is an int, the one of the other random operations a float.
+        let seed = if node.get_op_type() == "Dropout" {
+            make_attribute("seed", Attribute::<String>::Int(DETERMINISM_SEED))
+        } else {
+            make_attribute("seed", Attribute::<String>::Float(DETERMINISM_SEED as f32))
+        };
+        node.mut_attribute().push(seed);
+        seeded.push(format!("{} ({})", node.get_name(), node.get_op_type()));
+    }
+    seeded
+}
+
+fn tensor_hash(tensor: &TensorProto) -> String {
+    format!(
+        "{:016x}",
+        fnv1a(&tensor.write_to_bytes().unwrap_or_default())
+    )
+}
+
+fn max_abs_diff(actual: &TensorProto, expected: &TensorProto) -> f64 {
+    match (tensor_values(actual), tensor_values(expected)) {
+        (Ok(actual_values), Ok(expected_values))
+            if actual.get_dims() == expected.get_dims()
+                && actual_values.len() == expected_values.len() =>
+        {
+            actual_values
+                .iter()
+                .zip(&expected_values)
+                .map(|(a, e)| (a - e).abs())
+                .fold(0.0, f64::max)
+        }
+        _ => f64::NAN,
+    }
+}
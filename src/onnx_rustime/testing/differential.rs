@@ -0,0 +1,303 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::ops::reference::{
+    conv2d, global_average_pool, matmul, max_pool2d, softmax, ConvParams, PoolParams,
+};
+use crate::onnx_rustime::testing::generators::random_tensor;
+use crate::onnx_rustime::testing::op_test::OpTest;
+use ndarray::{Array1, ArrayD, Ix1, Ix4};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// `(atol, rtol)` between the optimized kernels and the reference implementations. The inputs
+/// are in `[-10, 10]`, so the sums of the largest convolutions reach a few thousands, and their
+/// float rounding error a few 1e-4.
+pub const KERNEL_TOLERANCE: (f64, f64) = (1e-3, 1e-4);
+
+/// Largest batch, channel count (per group) and spatial dimension of the generated inputs.
+pub const MAX_BATCH: usize = 2;
+pub const MAX_CHANNELS: usize = 3;
+pub const MAX_SPATIAL: usize = 9;
+
+/// Largest kernel side, stride, padding and group count of the generated convolutions and
+/// poolings.
+pub const MAX_KERNEL: usize = 4;
+pub const MAX_STRIDE: usize = 3;
+pub const MAX_PAD: usize = 2;
+pub const MAX_GROUP: usize = 3;
+
+/// Largest dilation of the generated convolutions.
+pub const MAX_DILATION: usize = 3;
+
+/// Operations checked by `check_kernels`.
+pub const DIFFERENTIAL_OPS: [&str; 5] =
+    ["Conv", "GlobalAveragePool", "MatMul", "MaxPool", "Softmax"];
+
+/// A generated test case: the parameters of the node, and the seed its input values are drawn
+/// from.
+#[derive(Debug, Clone)]
+pub struct KernelCase {
+    pub op_type: &'static str,
+    /// Shapes of the inputs, in order.
+    pub shapes: Vec<Vec<usize>>,
+    /// Integer attributes of the node.
+    pub attributes: Vec<(&'static str, Vec<i64>)>,
+    /// Seed of the generator of the input values (see `random_tensor`).
+    pub seed: u64,
+}
+
+impl fmt::Display for KernelCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} with inputs of shapes {:?}",
+            self.op_type, self.shapes
+        )?;
+        for (name, values) in &self.attributes {
+            write!(f, ", {} {:?}", name, values)?;
+        }
+        write!(f, " (input seed {})", self.seed)
+    }
+}
+
+impl KernelCase {
+    /// The input values, drawn in order from `seed`.
+    pub fn inputs(&self) -> Vec<ArrayD<f32>> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.shapes
+            .iter()
+            .map(|shape| random_tensor(&mut rng, shape))
+            .collect()
+    }
+
+    fn attribute(&self, name: &str) -> Option<&[i64]> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == name)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// The expected output, computed by the reference implementation.
+    pub fn reference_output(&self) -> Result<ArrayD<f32>, OnnxError> {
+        let inputs = self.inputs();
+        let pair = |name: &str| {
+            self.attribute(name)
+                .map_or([1, 1], |values| [values[0] as usize, values[1] as usize])
+        };
+        let pads = self.attribute("pads").map_or([0; 4], |pads| {
+            [pads[0], pads[1], pads[2], pads[3]].map(|pad| pad as usize)
+        });
+        let to_4d = |array: &ArrayD<f32>| {
+            array
+                .clone()
+                .into_dimensionality::<Ix4>()
+                .map_err(|e| OnnxError::ShapeError(e.to_string()))
+        };
+
+        match self.op_type {
+            "Conv" => {
+                let bias: Option<Array1<f32>> = match inputs.get(2) {
+                    Some(bias) => Some(
+                        bias.clone()
+                            .into_dimensionality::<Ix1>()
+                            .map_err(|e| OnnxError::ShapeError(e.to_string()))?,
+                    ),
+                    None => None,
+                };
+                let params = ConvParams {
+                    strides: pair("strides"),
+                    pads,
+                    dilations: pair("dilations"),
+                    group: self.attribute("group").map_or(1, |group| group[0] as usize),
+                };
+                conv2d(
+                    &to_4d(&inputs[0])?,
+                    &to_4d(&inputs[1])?,
+                    bias.as_ref(),
+                    &params,
+                )
+                .map(|output| output.into_dyn())
+            }
+            "GlobalAveragePool" => global_average_pool(&inputs[0]),
+            "MatMul" => matmul(&inputs[0], &inputs[1]),
+            "MaxPool" => {
+                let params = PoolParams {
+                    kernel_shape: pair("kernel_shape"),
+                    strides: pair("strides"),
+                    pads,
+                };
+                max_pool2d(&to_4d(&inputs[0])?, &params).map(|output| output.into_dyn())
+            }
+            "Softmax" => softmax(&inputs[0], 1),
+            op_type => Err(OnnxError::UnsupportedOperation(op_type.to_string())),
+        }
+    }
+
+    /// Runs the case through the executor, and compares its output with the reference one.
+    pub fn check(&self) -> Result<(), String> {
+        let expected = self
+            .reference_output()
+            .map_err(|e| format!("{}: the reference failed: {}", self, e))?;
+
+        let mut test = OpTest::new(self.op_type)
+            .expect(expected)
+            .tolerance(KERNEL_TOLERANCE.0, KERNEL_TOLERANCE.1);
+        for input in self.inputs() {
+            test = test.input(input);
+        }
+        for (name, values) in &self.attributes {
+            test = match name {
+                &"group" => test.attribute(name, values[0]),
+                _ => test.attribute(name, values.clone()),
+            };
+        }
+        test.run()
+            .map_err(|message| format!("{}: {}", self, message))
+    }
+}
+
+/// Generates a random test case of one of the `DIFFERENTIAL_OPS`.
+///
+/// The parameters stay within what the kernels support: MatMul of (stacks of) matrices, 4D
+/// GlobalAveragePool and Softmax along the default axis. The convolutions are 2D, like the
+/// reference one.
+pub fn random_case<R: Rng>(rng: &mut R, op_type: &'static str) -> KernelCase {
+    let batch = rng.gen_range(1..=MAX_BATCH);
+    let mut case = KernelCase {
+        op_type,
+        shapes: Vec::new(),
+        attributes: Vec::new(),
+        seed: rng.gen(),
+    };
+
+    match op_type {
+        "Conv" => {
+            let group = rng.gen_range(1..=MAX_GROUP);
+            let channels = rng.gen_range(1..=MAX_CHANNELS);
+            let filters = group * rng.gen_range(1..=MAX_CHANNELS);
+            let kernel = [rng.gen_range(1..=MAX_KERNEL), rng.gen_range(1..=MAX_KERNEL)];
+            let strides = [
+                rng.gen_range(1..=MAX_STRIDE) as i64,
+                rng.gen_range(1..=MAX_STRIDE) as i64,
+            ];
+            let dilations = [
+                rng.gen_range(1..=MAX_DILATION),
+                rng.gen_range(1..=MAX_DILATION),
+            ];
+            let pads: Vec<usize> = (0..4).map(|_| rng.gen_range(0..=MAX_PAD)).collect();
+            // The dilated kernel must fit in the padded input.
+            let spatial = |i: usize, rng: &mut R| {
+                let window = dilations[i] * (kernel[i] - 1) + 1;
+                let min = window.saturating_sub(pads[i] + pads[i + 2]).max(1);
+                rng.gen_range(min..=MAX_SPATIAL.max(min))
+            };
+            let (height, width) = (spatial(0, rng), spatial(1, rng));
+
+            case.shapes = vec![
+                vec![batch, group * channels, height, width],
+                vec![filters, channels, kernel[0], kernel[1]],
+            ];
+            if rng.gen_bool(0.5) {
+                case.shapes.push(vec![filters]);
+            }
+            case.attributes = vec![
+                ("kernel_shape", kernel.iter().map(|&k| k as i64).collect()),
+                ("strides", strides.to_vec()),
+                ("pads", pads.iter().map(|&pad| pad as i64).collect()),
+                ("dilations", dilations.iter().map(|&d| d as i64).collect()),
+                ("group", vec![group as i64]),
+            ];
+        }
+        "MaxPool" => {
+            let kernel = [rng.gen_range(1..=MAX_KERNEL), rng.gen_range(1..=MAX_KERNEL)];
+            // ONNX requires the padding to be smaller than the window.
+            let pads: Vec<usize> = (0..4)
+                .map(|i| rng.gen_range(0..kernel[i % 2].min(MAX_PAD + 1)))
+                .collect();
+            let height = rng.gen_range(kernel[0]..=MAX_SPATIAL);
+            let width = rng.gen_range(kernel[1]..=MAX_SPATIAL);
+
+            case.shapes = vec![vec![batch, rng.gen_range(1..=MAX_CHANNELS), height, width]];
+            case.attributes = vec![
+                ("kernel_shape", kernel.iter().map(|&k| k as i64).collect()),
+                (
+                    "strides",
+                    (0..2)
+                        .map(|_| rng.gen_range(1..=MAX_STRIDE) as i64)
+                        .collect(),
+                ),
+                ("pads", pads.iter().map(|&pad| pad as i64).collect()),
+            ];
+        }
+        "MatMul" => {
+            let [rows, inner, columns] = [(); 3].map(|_| rng.gen_range(1..=2 * MAX_SPATIAL));
+            // Stacks of matrices whose batch dimensions broadcast against each other: each
+            // operand may lack the leading ones or have some of size 1.
+            let batch: Vec<usize> = (0..rng.gen_range(0..=2))
+                .map(|_| rng.gen_range(1..=MAX_CHANNELS))
+                .collect();
+            let mut stack = |matrix: [usize; 2]| {
+                let mut shape: Vec<usize> = batch[rng.gen_range(0..=batch.len())..]
+                    .iter()
+                    .map(|&dim| if rng.gen_bool(0.3) { 1 } else { dim })
+                    .collect();
+                shape.extend(matrix);
+                shape
+            };
+            case.shapes = vec![stack([rows, inner]), stack([inner, columns])];
+        }
+        "GlobalAveragePool" => {
+            // One to three spatial axes.
+            let rank = rng.gen_range(3..=5);
+            let mut shape = vec![batch, rng.gen_range(1..=MAX_CHANNELS)];
+            shape.extend((2..rank).map(|_| rng.gen_range(1..=MAX_SPATIAL)));
+            case.shapes = vec![shape];
+        }
+        "Softmax" => {
+            let rank = rng.gen_range(2..=4);
+            let mut shape = vec![batch];
+            shape.extend((1..rank).map(|_| rng.gen_range(1..=MAX_SPATIAL)));
+            case.shapes = vec![shape];
+        }
+        _ => {}
+    }
+
+    case
+}
+
+/// `check_kernel` - Fuzzes the kernel of an operation against its reference implementation
+///
+/// Generates `cases` random cases of the operation (see `random_case`), runs each one through
+/// the executor and checks that its output matches the one of the reference implementation (see
+/// `ops::reference`) within `KERNEL_TOLERANCE`.
+///
+/// # Arguments
+///
+/// * `op_type` - One of the `DIFFERENTIAL_OPS`.
+/// * `cases` - The number of random cases.
+/// * `seed` - The seed of the random generator: the same seed generates the same cases.
+///
+/// # Returns
+///
+/// * `Result<(), String>` - Nothing, or the description of the first failing case with all its
+///   parameters, enough to rebuild it as a fixed regression test (e.g. with `op_test!`).
+///
+/// # Example
+///
+/// ```rust
+/// check_kernel("Conv", 1000, 42)?;
+/// ```
+pub fn check_kernel(op_type: &'static str, cases: usize, seed: u64) -> Result<(), String> {
+    for case in 0..cases {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(case as u64));
+        random_case(&mut rng, op_type).check()?;
+    }
+    Ok(())
+}
+
+/// Runs `check_kernel` on every operation of `DIFFERENTIAL_OPS`.
+pub fn check_kernels(cases: usize, seed: u64) -> Result<(), String> {
+    DIFFERENTIAL_OPS
+        .iter()
+        .try_for_each(|&op_type| check_kernel(op_type, cases, seed))
+}
@@ -0,0 +1,61 @@
+use ndarray::ArrayD;
+use rand::Rng;
+
+/// Largest rank of the generated shapes.
+pub const MAX_RANK: usize = 4;
+
+/// Largest dimension of the generated shapes.
+pub const MAX_DIM: usize = 4;
+
+/// Generates a shape with rank in `0..=max_rank` and dimensions in `1..=max_dim`.
+pub fn random_shape<R: Rng>(rng: &mut R, max_rank: usize, max_dim: usize) -> Vec<usize> {
+    let rank = rng.gen_range(0..=max_rank);
+    (0..rank).map(|_| rng.gen_range(1..=max_dim)).collect()
+}
+
+/// Generates two shapes that can be broadcast together.
+///
+/// Both shapes are derived from a common output shape, dropping some leading dimensions and
+/// replacing others with 1, so that every broadcasting case (different ranks, 1 against a
+/// dimension, scalars) shows up.
+pub fn broadcastable_shapes<R: Rng>(rng: &mut R) -> (Vec<usize>, Vec<usize>) {
+    let output = random_shape(rng, MAX_RANK, MAX_DIM);
+    let a = derived_shape(rng, &output);
+    let b = derived_shape(rng, &output);
+    (a, b)
+}
+
+/// Generates two shapes that can't be broadcast together: two aligned dimensions differ and are
+/// both greater than 1.
+pub fn incompatible_shapes<R: Rng>(rng: &mut R) -> (Vec<usize>, Vec<usize>) {
+    loop {
+        let (mut a, mut b) = broadcastable_shapes(rng);
+        let common_rank = a.len().min(b.len());
+        if common_rank == 0 {
+            continue;
+        }
+
+        // Dimensions are aligned starting from the last one.
+        let k = rng.gen_range(0..common_rank);
+        let (index_a, index_b) = (a.len() - 1 - k, b.len() - 1 - k);
+        let x = rng.gen_range(2..=MAX_DIM);
+        let y = (x - 2 + rng.gen_range(1..MAX_DIM - 1)) % (MAX_DIM - 1) + 2;
+        a[index_a] = x;
+        b[index_b] = y;
+
+        return (a, b);
+    }
+}
+
+/// Generates a tensor of the given shape with values uniformly distributed in `[-10, 10)`.
+pub fn random_tensor<R: Rng>(rng: &mut R, shape: &[usize]) -> ArrayD<f32> {
+    ArrayD::from_shape_simple_fn(shape, || rng.gen_range(-10.0..10.0))
+}
+
+fn derived_shape<R: Rng>(rng: &mut R, output: &[usize]) -> Vec<usize> {
+    let dropped = rng.gen_range(0..=output.len());
+    output[dropped..]
+        .iter()
+        .map(|&dim| if rng.gen_bool(0.3) { 1 } else { dim })
+        .collect()
+}
@@ -0,0 +1,319 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::backend::parser::OnnxParser;
+use crate::onnx_rustime::backend::run::run_with_intermediates;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{ModelProto, TensorProto};
+use crate::onnx_rustime::ops::utils::tensor_proto_to_ndarray;
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::testing::{catch_panic, fnv1a};
+use crate::onnx_rustime::testing::compare::{tensors_close, CloseOptions, CloseReport};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Environment variable that must be set to `1` to rewrite the golden statistics.
+pub const UPDATE_GOLDENS_VAR: &str = "ONNX_RUSTIME_UPDATE_GOLDENS";
+
+/// Name of the file, in the models directory, holding the golden statistics.
+pub const GOLDENS_FILE: &str = "goldens.json";
+
+/// Tolerance on the output of the models, compared with the expected output shipped with their
+/// test data. Models not listed here use `DEFAULT_OUTPUT_TOLERANCE`.
+pub const OUTPUT_TOLERANCES: [(&str, f64, f64); 2] = [
+    // The deepest networks accumulate more rounding error.
+    ("resnet152-v2-7", 1e-3, 1e-3),
+    ("resnet18-v2-7", 1e-3, 1e-3),
+];
+
+/// `(atol, rtol)` on the output of the models not listed in `OUTPUT_TOLERANCES`.
+pub const DEFAULT_OUTPUT_TOLERANCE: (f64, f64) = (1e-4, 1e-3);
+
+/// Relative tolerance on the mean and standard deviation of each layer.
+pub const STATISTICS_RTOL: f64 = 1e-4;
+
+/// Mean and standard deviation of the output of a layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerStatistics {
+    pub name: String,
+    pub op_type: String,
+    pub mean: f64,
+    pub std: f64,
+}
+
+/// Statistics of all the layers of a model, in execution order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelStatistics {
+    /// Hash of the statistics rounded to 6 significant digits: equal hashes mean that no layer
+    /// drifted, without comparing them one by one.
+    pub hash: String,
+    pub layers: Vec<LayerStatistics>,
+}
+
+/// The first layer whose statistics differ from the golden ones.
+#[derive(Debug, Clone)]
+pub struct LayerDrift {
+    /// Position of the layer in execution order.
+    pub index: usize,
+    pub golden: LayerStatistics,
+    pub actual: LayerStatistics,
+}
+
+/// Outcome of the golden check of a model.
+#[derive(Debug, Clone)]
+pub enum GoldenStatus {
+    Passed,
+    /// The golden statistics have been rewritten.
+    Updated,
+    /// The output differs from the expected one, or some layer drifted.
+    Failed,
+    /// The model has no golden statistics yet.
+    MissingGolden,
+    /// The model couldn't be loaded or run.
+    Error(String),
+}
+
+/// Result of the golden check of a model.
+#[derive(Debug, Clone)]
+pub struct GoldenResult {
+    pub model: String,
+    pub status: GoldenStatus,
+    /// Comparison of the output with the expected output of the test data.
+    pub output: Option<CloseReport>,
+    /// The first drifted layer, if any.
+    pub drift: Option<LayerDrift>,
+}
+
+/// `check_goldens` - Checks the bundled models against their expected outputs and statistics
+///
+/// Every subdirectory of `models_dir` containing a `<name>/<name>.onnx` model and a
+/// `test_data_set_0` directory is run on `input_0.pb`, and:
+///
+/// * the output must match `output_0.pb` within the tolerance of the model (see
+///   `OUTPUT_TOLERANCES`);
+/// * the mean and standard deviation of the output of every layer must match the ones recorded in
+///   `models_dir/goldens.json` (see `STATISTICS_RTOL`). The first layer that drifted is reported,
+///   to localize the change responsible for it.
+///
+/// The golden statistics are only rewritten when the `ONNX_RUSTIME_UPDATE_GOLDENS` environment
+/// variable is set to `1`, and only for the models whose output still matches the expected one.
+///
+/// # Arguments
+///
+/// * `models_dir` - The directory containing the models.
+/// * `filter` - If present, only the models whose name contains it are checked.
+///
+/// # Returns
+///
+/// * `Result<Vec<GoldenResult>, OnnxError>` - The result of every model, sorted by name, or an
+///   error (`OnnxError`) if the directory or the golden file can't be read or written.
+///
+/// # Example
+///
+/// ```rust
+/// let results = check_goldens(Path::new("models"), Some("mnist"))?;
+/// ```
+pub fn check_goldens(
+    models_dir: &Path,
+    filter: Option<&str>,
+) -> Result<Vec<GoldenResult>, OnnxError> {
+    let update = env::var(UPDATE_GOLDENS_VAR).is_ok_and(|value| value == "1");
+    let goldens_path = models_dir.join(GOLDENS_FILE);
+    let mut goldens = load_goldens(&goldens_path)?;
+
+    let mut names: Vec<String> = fs::read_dir(models_dir)
+        .map_err(|e| OnnxError::io(models_dir, e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let dir = models_dir.join(name);
+            dir.join(format!("{}.onnx", name)).is_file()
+                && dir.join("test_data_set_0").join("input_0.pb").is_file()
+                && dir.join("test_data_set_0").join("output_0.pb").is_file()
+        })
+        .filter(|name| filter.is_none_or(|filter| name.contains(filter)))
+        .collect();
+    names.sort();
+
+    let mut results = Vec::new();
+    for name in names {
+        let mut result = GoldenResult {
+            model: name.clone(),
+            status: GoldenStatus::Passed,
+            output: None,
+            drift: None,
+        };
+
+        let (output, statistics) = match run_model(&models_dir.join(&name), &name) {
+            Ok(outcome) => outcome,
+            Err(message) => {
+                result.status = GoldenStatus::Error(message);
+                results.push(result);
+                continue;
+            }
+        };
+        let output_matches = output.is_close();
+        result.output = Some(output);
+
+        result.status = match goldens.get(&name) {
+            _ if update && output_matches => {
+                goldens.insert(name.clone(), statistics);
+                GoldenStatus::Updated
+            }
+            _ if !output_matches => GoldenStatus::Failed,
+            None => GoldenStatus::MissingGolden,
+            Some(golden) if golden.hash == statistics.hash => GoldenStatus::Passed,
+            Some(golden) => {
+                result.drift = first_drift(golden, &statistics);
+                match result.drift {
+                    Some(_) => GoldenStatus::Failed,
+                    None => GoldenStatus::Passed,
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    if update {
+        let json = serde_json::to_string_pretty(&goldens).map_err(|e| {
+            OnnxError::InternalError(format!("Failed to serialize the goldens: {}", e))
+        })?;
+        fs::write(&goldens_path, json + "\n").map_err(|e| OnnxError::io(&goldens_path, e))?;
+    }
+
+    Ok(results)
+}
+
+/// Computes the statistics of the output of every node of a model, in execution order.
+///
+/// Only the floating point outputs are considered.
+pub fn layer_statistics(
+    model: &ModelProto,
+    tensors: &HashMap<String, TensorProto>,
+) -> ModelStatistics {
+    let mut layers = Vec::new();
+    for node in model.get_graph().get_node() {
+        for output in node.get_output() {
+            let values = match tensors.get(output).map(tensor_proto_to_ndarray::<f32>) {
+                Some(Ok(values)) if !values.is_empty() => values,
+                _ => continue,
+            };
+
+            let count = values.len() as f64;
+            let mean = values.iter().map(|&v| v as f64).sum::<f64>() / count;
+            let variance = values
+                .iter()
+                .map(|&v| (v as f64 - mean).powi(2))
+                .sum::<f64>()
+                / count;
+
+            layers.push(LayerStatistics {
+                name: output.clone(),
+                op_type: node.get_op_type().to_string(),
+                mean,
+                std: variance.sqrt(),
+            });
+        }
+    }
+
+    ModelStatistics {
+        hash: statistics_hash(&layers),
+        layers,
+    }
+}
+
+/// Runs a bundled model on its first test data set, returning the comparison of its output with
+/// the expected one and the statistics of its layers.
+fn run_model(dir: &Path, name: &str) -> Result<(CloseReport, ModelStatistics), String> {
+    let path = |file: &str| dir.join(file).to_string_lossy().to_string();
+
+    let model = OnnxParser::load_model(path(&format!("{}.onnx", name)))
+        .map_err(|e| format!("Failed to load the model: {}", e))?;
+    let input = OnnxParser::load_tensor_proto(path("test_data_set_0/input_0.pb"))
+        .map_err(|e| format!("Failed to load the input: {}", e))?;
+    let expected = OnnxParser::load_tensor_proto(path("test_data_set_0/output_0.pb"))
+        .map_err(|e| format!("Failed to load the expected output: {}", e))?;
+
+    let tensors =
+        catch_panic(|| run_with_intermediates(&model, vec![input], &RunOptions::default()))
+            .map_err(|message| format!("panicked: {}", message))?
+            .map_err(|e| e.to_string())?;
+
+    let output_name = model
+        .get_graph()
+        .get_output()
+        .first()
+        .map(|output| output.get_name())
+        .unwrap_or_default();
+    let output = tensors
+        .get(output_name)
+        .ok_or(format!("Output tensor '{}' not found", output_name))?;
+
+    let (atol, rtol) = OUTPUT_TOLERANCES
+        .iter()
+        .find(|(model, _, _)| *model == name)
+        .map_or(DEFAULT_OUTPUT_TOLERANCE, |&(_, atol, rtol)| (atol, rtol));
+    let to_ndarray = |tensor| tensor_proto_to_ndarray::<f32>(tensor).map_err(|e| e.to_string());
+    let report = tensors_close(
+        &to_ndarray(output)?,
+        &to_ndarray(&expected)?,
+        CloseOptions::abs_rel(atol, rtol),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok((report, layer_statistics(&model, &tensors)))
+}
+
+fn load_goldens(path: &Path) -> Result<BTreeMap<String, ModelStatistics>, OnnxError> {
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+
+    let json = fs::read_to_string(path).map_err(|e| OnnxError::io(path, e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| OnnxError::InvalidValue(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+fn first_drift(golden: &ModelStatistics, actual: &ModelStatistics) -> Option<LayerDrift> {
+    let close = |a: f64, b: f64| (a - b).abs() <= STATISTICS_RTOL * b.abs().max(1e-6);
+
+    let index = (0..golden.layers.len().max(actual.layers.len())).find(|&index| {
+        match (golden.layers.get(index), actual.layers.get(index)) {
+            (Some(golden), Some(actual)) => {
+                golden.name != actual.name
+                    || !close(actual.mean, golden.mean)
+                    || !close(actual.std, golden.std)
+            }
+            _ => true,
+        }
+    })?;
+
+    let missing = |name: &str| LayerStatistics {
+        name: name.to_string(),
+        op_type: String::new(),
+        mean: f64::NAN,
+        std: f64::NAN,
+    };
+    Some(LayerDrift {
+        index,
+        golden: golden
+            .layers
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| missing("<none>")),
+        actual: actual
+            .layers
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| missing("<none>")),
+    })
+}
+
+/// Hash of the statistics rounded to 6 significant digits.
+fn statistics_hash(layers: &[LayerStatistics]) -> String {
+    let lines: String = layers
+        .iter()
+        .map(|layer| format!("{} {:.5e} {:.5e}\n", layer.name, layer.mean, layer.std))
+        .collect();
+    format!("{:016x}", fnv1a(lines.as_bytes()))
+}
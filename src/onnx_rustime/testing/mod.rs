@@ -0,0 +1,53 @@
+pub mod compare;
+pub mod conformance;
+pub mod determinism;
+pub mod differential;
+pub mod generators;
+#[cfg(feature = "serde")]
+pub mod golden;
+pub mod op_test;
+pub mod properties;
+
+pub use compare::{
+    assert_tensors_close, tensors_close, CloseElement, CloseOptions, CloseReport, Comparison,
+};
+pub use conformance::{run_node_tests, CaseResult, CaseStatus, ConformanceReport};
+pub use determinism::{
+    verify_determinism, DeterminismReport, DeterminismRun, Divergence, DETERMINISM_SEED, RANDOM_OPS,
+};
+pub use differential::{
+    check_kernel, check_kernels, random_case, KernelCase, DIFFERENTIAL_OPS, KERNEL_TOLERANCE,
+};
+#[cfg(feature = "serde")]
+pub use golden::{
+    check_goldens, layer_statistics, GoldenResult, GoldenStatus, LayerDrift, LayerStatistics,
+    ModelStatistics,
+};
+pub use op_test::{scalar, single_node_model, OpTest, TestAttribute, TestTensor};
+pub use properties::{check_binary_op_broadcasting, check_broadcasting, reference_broadcast};
+
+use std::panic::{self, AssertUnwindSafe};
+
+/// Runs `f`, turning a panic into an error with its message. The default panic message (and
+/// backtrace) is not printed.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let outcome = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+
+    outcome.map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    })
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and compiler versions (unlike `DefaultHasher`).
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
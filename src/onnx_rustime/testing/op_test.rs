@@ -0,0 +1,387 @@
+//! Scaffold for operator tests.
+//!
+//! An operator test builds a single-node model around the operator, runs it through the executor
+//! (`run_with_inputs`, the path taken by real models, pass-through nodes included) and compares
+//! every output with the expected one. The `op_test!` macro does all of it in one statement:
+//!
+//! ```rust
+//! op_test!("Relu", inputs: [array![[-1.0f32, 2.0], [3.0, -4.0]]], attrs: {},
+//!          expect: array![[0.0f32, 2.0], [3.0, 0.0]]);
+//!
+//! op_test!("Flatten",
+//!          inputs: [array![[[1.0f32, 2.0], [3.0, 4.0]]]],
+//!          attrs: { axis: 2i64 },
+//!          expect: array![[1.0f32, 2.0], [3.0, 4.0]]);
+//!
+//! // The weights are initializers, as in the models of the zoo.
+//! op_test!("Gemm",
+//!          inputs: [array![[1.0f32, 2.0]], array![[1.0f32], [1.0]], array![0.5f32]],
+//!          attrs: { alpha: 2.0f32, beta: 1.0f32 },
+//!          expect: array![[6.5f32]]);
+//!
+//! // Bool inputs, and the `training_mode` of Dropout.
+//! op_test!("Dropout",
+//!          inputs: [array![1.0f32, 2.0], scalar(0.5f32), scalar(false)],
+//!          attrs: {},
+//!          expect: array![1.0f32, 2.0]);
+//! ```
+//!
+//! Operators with several outputs list them all: `expect: [values, indices]`. The macro panics
+//! with the differences if an output doesn't match, like `assert_eq!`. `OpTest` is the builder
+//! behind it, for tests needing custom tolerances, another operator set than the default opset 3
+//! of `make_model`, or a `Result` instead of a panic:
+//!
+//! ```rust
+//! OpTest::new("Exp")
+//!     .input(array![[0.0f32, 1.0, -1.0]])
+//!     .expect(array![[1.0f32, 2.7182817, 0.36787945]])
+//!     .tolerance(1e-6, 1e-6)
+//!     .check();
+//! ```
+//!
+//! Inputs and outputs can be f32, i64, i32, u8, i8 or bool arrays of any dimension. Float outputs are compared
+//! with the tolerances of the conformance tests (see `conformance::tolerance`), the other types
+//! exactly. As in the models of the zoo, only the first input flows through the network as data:
+//! the executor binds the others as initializers (weights). `OpTest::initializer` stores an input
+//! in the model itself, for the nodes whose data is an initializer.
+
+use crate::onnx_rustime::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor, Attribute,
+    TensorValue,
+};
+use crate::onnx_rustime::backend::run::run_with_inputs;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::testing::catch_panic;
+use crate::onnx_rustime::testing::compare::{tensors_close, CloseOptions};
+use crate::onnx_rustime::testing::conformance::{tensor_values, tolerance};
+use ndarray::{arr0, Array, ArrayD, Dimension};
+use protobuf::{ProtobufEnum, RepeatedField};
+
+/// A tensor given to, or expected from, an operator test.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestTensor {
+    Float(ArrayD<f32>),
+    Int64(ArrayD<i64>),
+    Int32(ArrayD<i32>),
+    UInt8(ArrayD<u8>),
+    Int8(ArrayD<i8>),
+    Bool(ArrayD<bool>),
+}
+
+impl TestTensor {
+    fn to_tensor_proto(&self, name: &str) -> TensorProto {
+        let dims = |shape: &[usize]| shape.iter().map(|&dim| dim as i64).collect();
+        match self {
+            TestTensor::Float(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::Float(array.iter().copied().collect()),
+            ),
+            TestTensor::Int64(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::Int64(array.iter().copied().collect()),
+            ),
+            TestTensor::Int32(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::Int32(array.iter().copied().collect()),
+            ),
+            TestTensor::UInt8(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::UInt8(array.iter().copied().collect()),
+            ),
+            TestTensor::Int8(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::Int8(array.iter().copied().collect()),
+            ),
+            TestTensor::Bool(array) => make_tensor(
+                Some(name),
+                dims(array.shape()),
+                TensorValue::Bool(array.iter().copied().collect()),
+            ),
+        }
+    }
+}
+
+macro_rules! impl_test_tensor_from {
+    ($t: ty, $variant: ident) => {
+        impl<D: Dimension> From<Array<$t, D>> for TestTensor {
+            fn from(array: Array<$t, D>) -> Self {
+                TestTensor::$variant(array.into_dyn())
+            }
+        }
+    };
+}
+
+impl_test_tensor_from!(f32, Float);
+impl_test_tensor_from!(i64, Int64);
+impl_test_tensor_from!(i32, Int32);
+impl_test_tensor_from!(u8, UInt8);
+impl_test_tensor_from!(i8, Int8);
+impl_test_tensor_from!(bool, Bool);
+
+/// A 0-dimensional tensor, e.g. the `min` and `max` inputs of `Clip`.
+pub fn scalar<T>(value: T) -> ArrayD<T> {
+    arr0(value).into_dyn()
+}
+
+/// The value of an attribute of an operator test.
+pub struct TestAttribute(Attribute<String>);
+
+macro_rules! impl_test_attribute_from {
+    ($t: ty, $variant: ident) => {
+        impl From<$t> for TestAttribute {
+            fn from(value: $t) -> Self {
+                TestAttribute(Attribute::$variant(value.into()))
+            }
+        }
+    };
+}
+
+impl_test_attribute_from!(f32, Float);
+impl_test_attribute_from!(Vec<f32>, Floats);
+impl_test_attribute_from!(i64, Int);
+impl_test_attribute_from!(Vec<i64>, Ints);
+impl_test_attribute_from!(&str, String);
+impl_test_attribute_from!(String, String);
+impl_test_attribute_from!(Vec<String>, Strings);
+impl_test_attribute_from!(TensorProto, Tensor);
+
+/// Builder of an operator test (see the module documentation).
+pub struct OpTest {
+    op_type: String,
+    inputs: Vec<TestTensor>,
+    /// The positions of the inputs stored as initializers.
+    initializers: Vec<usize>,
+    attributes: Vec<AttributeProto>,
+    expected: Vec<TestTensor>,
+    options: Option<CloseOptions>,
+    opset: Option<i64>,
+}
+
+impl OpTest {
+    pub fn new(op_type: &str) -> Self {
+        OpTest {
+            op_type: op_type.to_string(),
+            inputs: Vec::new(),
+            initializers: Vec::new(),
+            attributes: Vec::new(),
+            expected: Vec::new(),
+            options: None,
+            opset: None,
+        }
+    }
+
+    /// Adds an input, after the previous ones.
+    pub fn input<T: Into<TestTensor>>(mut self, tensor: T) -> Self {
+        self.inputs.push(tensor.into());
+        self
+    }
+
+    /// Adds an input stored as an initializer of the model, after the previous ones, instead of
+    /// being passed to the run, e.g. the first input of a node transposing a weight.
+    pub fn initializer<T: Into<TestTensor>>(mut self, tensor: T) -> Self {
+        self.initializers.push(self.inputs.len());
+        self.inputs.push(tensor.into());
+        self
+    }
+
+    pub fn attribute<T: Into<TestAttribute>>(mut self, name: &str, value: T) -> Self {
+        self.attributes.push(make_attribute(name, value.into().0));
+        self
+    }
+
+    /// Adds an expected output, after the previous ones.
+    pub fn expect<T: Into<TestTensor>>(mut self, tensor: T) -> Self {
+        self.expected.push(tensor.into());
+        self
+    }
+
+    /// Compares every output with `|actual - expected| <= atol + rtol * |expected|`, instead of
+    /// the default tolerance of its type.
+    pub fn tolerance(mut self, atol: f64, rtol: f64) -> Self {
+        self.options = Some(CloseOptions::abs_rel(atol, rtol).with_nan_equal(true));
+        self
+    }
+
+    /// Imports the given version of the default operator set in the model, for the operators
+    /// whose semantics changed between versions (e.g. `Softmax` in opset 13).
+    pub fn opset(mut self, version: i64) -> Self {
+        self.opset = Some(version);
+        self
+    }
+
+    /// The single-node model run by the test.
+    pub fn model(&self) -> ModelProto {
+        let names = |prefix: &str, count: usize| -> Vec<String> {
+            (0..count).map(|i| format!("{}{}", prefix, i)).collect()
+        };
+        let inputs = names("x", self.inputs.len());
+        let outputs = names("y", self.expected.len());
+
+        let mut model = single_node_model(
+            &self.op_type,
+            &inputs.iter().map(String::as_str).collect::<Vec<_>>(),
+            &outputs.iter().map(String::as_str).collect::<Vec<_>>(),
+            self.attributes.clone(),
+        );
+        if let Some(version) = self.opset {
+            model.set_opset_import(RepeatedField::from_vec(vec![make_opsetid(
+                None::<&str>,
+                version,
+            )]));
+        }
+        for &i in &self.initializers {
+            let name = format!("x{}", i);
+            let graph = model.mut_graph();
+            graph.mut_input().retain(|input| input.get_name() != name);
+            graph
+                .mut_initializer()
+                .push(self.inputs[i].to_tensor_proto(&name));
+        }
+        model
+    }
+
+    /// Runs the test, returning a description of the first mismatch, error or panic.
+    pub fn run(&self) -> Result<(), String> {
+        let inputs: Vec<TensorProto> = self
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.initializers.contains(i))
+            .map(|(i, input)| input.to_tensor_proto(&format!("x{}", i)))
+            .collect();
+
+        let model = self.model();
+        let outputs = catch_panic(|| run_with_inputs(&model, inputs, &RunOptions::default()))
+            .map_err(|message| format!("{} panicked: {}", self.op_type, message))?
+            .map_err(|e| format!("{} failed: {}", self.op_type, e))?;
+
+        for (i, (actual, expected)) in outputs.iter().zip(&self.expected).enumerate() {
+            let expected = expected.to_tensor_proto(&format!("y{}", i));
+            compare_output(actual, &expected, self.options)
+                .map_err(|message| format!("{} output {}: {}", self.op_type, i, message))?;
+        }
+        Ok(())
+    }
+
+    /// Runs the test, panicking with a description of the first mismatch, error or panic.
+    #[track_caller]
+    pub fn check(&self) {
+        if let Err(message) = self.run() {
+            panic!("{}", message);
+        }
+    }
+}
+
+/// `op_test!` - Runs an operator test through the executor, panicking if an output differs
+///
+/// See the module documentation for examples. `expect` takes a single tensor, or a list of
+/// tensors for operators with several outputs. Attribute names are identifiers; their values can
+/// be `f32`, `i64`, `Vec<f32>`, `Vec<i64>`, strings or `TensorProto`s.
+#[macro_export]
+macro_rules! op_test {
+    ($op_type: expr,
+     inputs: [$($input: expr),* $(,)?],
+     attrs: {$($name: ident: $value: expr),* $(,)?},
+     expect: [$($output: expr),+ $(,)?] $(,)?) => {
+        $crate::onnx_rustime::testing::OpTest::new($op_type)
+            $(.input($input))*
+            $(.attribute(stringify!($name), $value))*
+            $(.expect($output))+
+            .check()
+    };
+    ($op_type: expr,
+     inputs: [$($input: expr),* $(,)?],
+     attrs: {$($name: ident: $value: expr),* $(,)?},
+     expect: $output: expr $(,)?) => {
+        $crate::op_test!($op_type, inputs: [$($input),*], attrs: {$($name: $value),*},
+                         expect: [$output])
+    };
+}
+
+/// Builds a model made of a single node, whose inputs and outputs are the graph inputs and
+/// outputs.
+pub fn single_node_model(
+    op_type: &str,
+    inputs: &[&str],
+    outputs: &[&str],
+    attributes: Vec<AttributeProto>,
+) -> ModelProto {
+    let value_info = |name: &&str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+
+    let node = make_node(
+        op_type,
+        inputs.to_vec(),
+        outputs.to_vec(),
+        None,
+        None,
+        None,
+        attributes,
+    );
+    let graph = make_graph(
+        vec![node],
+        op_type,
+        inputs.iter().map(value_info).collect(),
+        outputs.iter().map(value_info).collect(),
+        Vec::<TensorProto>::new(),
+        None,
+    );
+    make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn compare_output(
+    actual: &TensorProto,
+    expected: &TensorProto,
+    options: Option<CloseOptions>,
+) -> Result<(), String> {
+    if actual.get_data_type() != expected.get_data_type() {
+        let name = |data_type: i32| {
+            TensorProto_DataType::from_i32(data_type).map_or(data_type.to_string(), |data_type| {
+                format!("{:?}", data_type)
+            })
+        };
+        return Err(format!(
+            "expected data type {}, got {}",
+            name(expected.get_data_type()),
+            name(actual.get_data_type())
+        ));
+    }
+
+    let to_array = |tensor: &TensorProto| {
+        let shape: Vec<usize> = tensor.get_dims().iter().map(|&dim| dim as usize).collect();
+        tensor_values(tensor)
+            .and_then(|values| ArrayD::from_shape_vec(shape, values).map_err(|e| e.to_string()))
+    };
+    let report = tensors_close(
+        &to_array(actual)?,
+        &to_array(expected)?,
+        options.unwrap_or_else(|| tolerance(expected.get_data_type())),
+    )
+    .map_err(|e| e.to_string())?;
+
+    if report.is_close() {
+        Ok(())
+    } else {
+        Err(report.to_string())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::onnx_rustime::backend::run::run_with_inputs;
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::testing::catch_panic;
+use crate::onnx_rustime::testing::compare::{tensors_close, CloseOptions};
+use crate::onnx_rustime::testing::generators::{
+    broadcastable_shapes, incompatible_shapes, random_tensor,
+};
+use crate::onnx_rustime::testing::op_test::single_node_model;
+use ndarray::{ArrayD, Dimension};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// The scalar function computed by an element-wise binary operation.
+pub type ScalarFn = fn(f32, f32) -> f32;
+
+/// Binary operations supporting multidirectional broadcasting, with the scalar function they
+/// compute, checked by `check_broadcasting`.
+pub const BROADCASTING_OPS: [(&str, ScalarFn); 4] = [
+    ("Add", |a, b| a + b),
+    ("Sub", |a, b| a - b),
+    ("Mul", |a, b| a * b),
+    ("Div", |a, b| a / b),
+];
+
+/// Slow reference implementation of a broadcasting binary operation: both operands are
+/// explicitly expanded to the output shape, element by element.
+///
+/// Returns `None` if the shapes can't be broadcast together.
+pub fn reference_broadcast(
+    a: &ArrayD<f32>,
+    b: &ArrayD<f32>,
+    f: ScalarFn,
+) -> Option<ArrayD<f32>> {
+    let rank = a.ndim().max(b.ndim());
+    // Shapes padded with leading 1s to the output rank.
+    let pad = |shape: &[usize]| {
+        let mut padded = vec![1; rank - shape.len()];
+        padded.extend_from_slice(shape);
+        padded
+    };
+    let (shape_a, shape_b) = (pad(a.shape()), pad(b.shape()));
+
+    let mut output_shape = Vec::with_capacity(rank);
+    for (&x, &y) in shape_a.iter().zip(&shape_b) {
+        if x != y && x != 1 && y != 1 {
+            return None;
+        }
+        output_shape.push(x.max(y));
+    }
+
+    // The index of an operand element: dimensions of size 1 are repeated.
+    let operand_index = |shape: &[usize], index: &[usize], operand_rank: usize| -> Vec<usize> {
+        shape[rank - operand_rank..]
+            .iter()
+            .zip(&index[rank - operand_rank..])
+            .map(|(&dim, &i)| if dim == 1 { 0 } else { i })
+            .collect()
+    };
+
+    Some(ArrayD::from_shape_fn(output_shape, |index| {
+        let index = index.slice();
+        let x = a[operand_index(&shape_a, index, a.ndim()).as_slice()];
+        let y = b[operand_index(&shape_b, index, b.ndim()).as_slice()];
+        f(x, y)
+    }))
+}
+
+/// `check_binary_op_broadcasting` - Checks the broadcasting of a binary operation on random shapes
+///
+/// Half of the cases use two shapes that can be broadcast together, and the result computed by
+/// the executor (on a single-node model) must match `reference_broadcast`. The other half use
+/// incompatible shapes, and the executor must return an error without panicking.
+///
+/// Every case is generated from `seed + case index`, so a failure can be reproduced alone.
+///
+/// # Arguments
+///
+/// * `op_type` - The operation to check.
+/// * `f` - The scalar function computed by the operation.
+/// * `cases` - The number of cases to generate.
+/// * `seed` - The seed of the first case.
+///
+/// # Returns
+///
+/// * `Result<(), String>` - Nothing, or the description of the first failing case with the
+///   shapes and the seed reproducing it.
+///
+/// # Example
+///
+/// ```rust
+/// check_binary_op_broadcasting("Add", |a, b| a + b, 1000, 42)?;
+/// ```
+pub fn check_binary_op_broadcasting(
+    op_type: &str,
+    f: ScalarFn,
+    cases: usize,
+    seed: u64,
+) -> Result<(), String> {
+    for case in 0..cases {
+        let case_seed = seed.wrapping_add(case as u64);
+        let mut rng = StdRng::seed_from_u64(case_seed);
+        let compatible = case % 2 == 0;
+
+        let (shape_a, shape_b) = if compatible {
+            broadcastable_shapes(&mut rng)
+        } else {
+            incompatible_shapes(&mut rng)
+        };
+        let a = random_tensor(&mut rng, &shape_a);
+        let b = random_tensor(&mut rng, &shape_b);
+
+        let failure = |message: String| {
+            format!(
+                "{} with shapes {:?} and {:?} (seed {}): {}",
+                op_type, shape_a, shape_b, case_seed, message
+            )
+        };
+
+        let model = single_node_model(op_type, &["a", "b"], &["y"], vec![]);
+        let inputs = vec![
+            ndarray_to_tensor_proto::<f32>(a.clone(), "a")
+                .map_err(|e| failure(format!("{:?}", e)))?,
+            ndarray_to_tensor_proto::<f32>(b.clone(), "b")
+                .map_err(|e| failure(format!("{:?}", e)))?,
+        ];
+        let outcome = catch_panic(|| run_with_inputs(&model, inputs, &RunOptions::default()))
+            .map_err(|message| failure(format!("panicked: {}", message)))?;
+
+        match (reference_broadcast(&a, &b, f), outcome) {
+            (Some(expected), Ok(outputs)) => {
+                let output = tensor_proto_to_ndarray::<f32>(&outputs[0])
+                    .map_err(|e| failure(format!("{:?}", e)))?;
+                let report = tensors_close(&output, &expected, CloseOptions::abs_rel(1e-6, 1e-6))
+                    .map_err(|e| failure(format!("{:?}", e)))?;
+                if !report.is_close() {
+                    return Err(failure(report.to_string()));
+                }
+            }
+            (Some(_), Err(e)) => return Err(failure(format!("unexpected error {:?}", e))),
+            (None, Ok(_)) => return Err(failure("incompatible shapes accepted".to_string())),
+            (None, Err(_)) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `check_binary_op_broadcasting` on every operation of `BROADCASTING_OPS`.
+pub fn check_broadcasting(cases: usize, seed: u64) -> Result<(), String> {
+    BROADCASTING_OPS
+        .iter()
+        .try_for_each(|&(op_type, f)| check_binary_op_broadcasting(op_type, f, cases, seed))
+}
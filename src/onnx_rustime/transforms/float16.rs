@@ -0,0 +1,268 @@
+use crate::onnx_rustime::backend::helper::{make_attribute, make_node, Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    NodeProto, ModelProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use crate::onnx_rustime::ops::utils::tensor_proto_to_ndarray;
+use half::f16;
+use protobuf::RepeatedField;
+use std::collections::{HashMap, HashSet};
+
+/// Operations kept in single precision by default.
+///
+/// Normalization layers divide by small statistics (`var + epsilon`), which easily under/overflow
+/// in half precision, so they are computed in f32 with `Cast` nodes inserted around them.
+pub const DEFAULT_OP_BLOCK_LIST: [&str; 4] = [
+    "BatchNormalization",
+    "InstanceNormalization",
+    "LayerNormalization",
+    "LRN",
+];
+
+/// Configuration of the float16 conversion.
+#[derive(Debug, Clone)]
+pub struct Float16Config {
+    /// Operation types executed in f32. Every input of a blocked node is cast back to FLOAT,
+    /// and every output is cast to FLOAT16 for the following nodes. Only operations whose
+    /// inputs are all floating point tensors should be listed here.
+    pub op_block_list: Vec<String>,
+}
+
+impl Default for Float16Config {
+    fn default() -> Self {
+        Float16Config {
+            op_block_list: DEFAULT_OP_BLOCK_LIST.iter().map(|op| op.to_string()).collect(),
+        }
+    }
+}
+
+/// `convert_float_to_float16` - Converts a FLOAT model to FLOAT16
+///
+/// Every FLOAT initializer, `Constant` value and `value_info` entry is converted to FLOAT16,
+/// halving the size of the model weights. The interface of the model does not change: `Cast`
+/// nodes are inserted after the graph inputs and before the graph outputs, so the model still
+/// consumes and produces f32 tensors.
+///
+/// Nodes whose operation type appears in `config.op_block_list` keep running in f32: their
+/// inputs are cast back to FLOAT and their outputs to FLOAT16. Initializers consumed only by
+/// blocked nodes are left untouched.
+///
+/// The resulting model is a valid ONNX model, which can be saved with `OnnxParser::save_model`
+/// and loaded by other runtimes as well.
+///
+/// # Arguments
+///
+/// * `model` - The FLOAT model to be converted.
+/// * `config` - The conversion options (see `Float16Config`).
+///
+/// # Returns
+///
+/// * `Result<ModelProto, OnnxError>` - The converted model, or an error (`OnnxError`) if
+///   some initializer can't be decoded.
+///
+/// # Example
+///
+/// ```rust
+/// let model_fp16 = convert_float_to_float16(&model, &Float16Config::default())?;
+/// OnnxParser::save_model(&model_fp16, "model_fp16.onnx".to_string())?;
+/// ```
+pub fn convert_float_to_float16(
+    model: &ModelProto,
+    config: &Float16Config,
+) -> Result<ModelProto, OnnxError> {
+    let mut model = model.clone();
+    let graph = model.mut_graph();
+
+    let is_blocked = |node: &NodeProto| {
+        config
+            .op_block_list
+            .iter()
+            .any(|op| op == node.get_op_type())
+    };
+
+    // Initializers consumed only by blocked nodes don't need to be converted at all.
+    let mut f32_initializers: HashSet<String> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| init.get_name().to_string())
+        .collect();
+    for node in graph.get_node().iter().filter(|node| !is_blocked(node)) {
+        for input in node.get_input() {
+            f32_initializers.remove(input);
+        }
+    }
+
+    let initializer_names: HashSet<String> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| init.get_name().to_string())
+        .collect();
+
+    for init in graph.mut_initializer().iter_mut() {
+        if init.get_data_type() == TensorProto_DataType::FLOAT as i32
+            && !f32_initializers.contains(init.get_name())
+        {
+            *init = tensor_to_float16(init)?;
+        }
+    }
+
+    // Tensor renames applied to the inputs of the nodes that follow.
+    let mut renames: HashMap<String, String> = HashMap::new();
+    let mut nodes: Vec<NodeProto> = Vec::new();
+
+    // The graph inputs stay FLOAT, and are cast to FLOAT16 before being consumed.
+    for input in graph.get_input() {
+        if is_float(input) && !initializer_names.contains(input.get_name()) {
+            let name = input.get_name().to_string();
+            let casted = format!("{}_float16", name);
+            nodes.push(make_cast(&name, &casted, TensorProto_DataType::FLOAT16));
+            renames.insert(name, casted);
+        }
+    }
+
+    // The graph outputs stay FLOAT, and are produced by a final cast to FLOAT.
+    let float_outputs: HashSet<String> = graph
+        .get_output()
+        .iter()
+        .filter(|output| is_float(output))
+        .map(|output| output.get_name().to_string())
+        .collect();
+
+    for (index, node) in graph.get_node().iter().enumerate() {
+        let mut node = node.clone();
+
+        for input in node.mut_input().iter_mut() {
+            if let Some(renamed) = renames.get(input.as_str()) {
+                *input = renamed.clone();
+            }
+        }
+
+        if is_blocked(&node) {
+            for input in node.mut_input().iter_mut() {
+                if input.is_empty() || f32_initializers.contains(input.as_str()) {
+                    continue;
+                }
+                let casted = format!("{}_float32_{}", input, index);
+                nodes.push(make_cast(input, &casted, TensorProto_DataType::FLOAT));
+                *input = casted;
+            }
+
+            let mut output_casts = Vec::new();
+            for output in node.mut_output().iter_mut() {
+                // A blocked node producing a graph output already produces a FLOAT tensor.
+                if output.is_empty() || float_outputs.contains(output.as_str()) {
+                    continue;
+                }
+                let internal = format!("{}_float32", output);
+                output_casts.push(make_cast(&internal, output, TensorProto_DataType::FLOAT16));
+                *output = internal;
+            }
+
+            nodes.push(node);
+            nodes.extend(output_casts);
+            continue;
+        }
+
+        convert_node_attributes(&mut node)?;
+
+        let mut output_casts = Vec::new();
+        for output in node.mut_output().iter_mut() {
+            if float_outputs.contains(output.as_str()) {
+                let internal = format!("{}_float16", output);
+                output_casts.push(make_cast(&internal, output, TensorProto_DataType::FLOAT));
+                renames.insert(output.clone(), internal.clone());
+                *output = internal;
+            }
+        }
+
+        nodes.push(node);
+        nodes.extend(output_casts);
+    }
+
+    graph.set_node(RepeatedField::from_vec(nodes));
+
+    // Intermediate values are now half precision.
+    for value_info in graph.mut_value_info().iter_mut() {
+        set_float16(value_info);
+    }
+
+    // Old-style models also list the initializers among the graph inputs.
+    let converted_initializers: HashSet<String> = graph
+        .get_initializer()
+        .iter()
+        .filter(|init| init.get_data_type() == TensorProto_DataType::FLOAT16 as i32)
+        .map(|init| init.get_name().to_string())
+        .collect();
+    for input in graph.mut_input().iter_mut() {
+        if converted_initializers.contains(input.get_name()) {
+            set_float16(input);
+        }
+    }
+
+    Ok(model)
+}
+
+/// Converts a FLOAT tensor to a FLOAT16 tensor, storing the values in `raw_data`.
+fn tensor_to_float16(tensor: &TensorProto) -> Result<TensorProto, OnnxError> {
+    let values = tensor_proto_to_ndarray::<f32>(tensor)?;
+
+    let mut converted = tensor.clone();
+    converted.clear_float_data();
+    converted.set_raw_data(
+        values
+            .iter()
+            .flat_map(|&value| f16::from_f32(value).to_le_bytes())
+            .collect(),
+    );
+    converted.set_data_type(TensorProto_DataType::FLOAT16 as i32);
+
+    Ok(converted)
+}
+
+/// Updates the attributes of a converted node: `Constant` values become FLOAT16 tensors and
+/// casts to FLOAT become casts to FLOAT16.
+fn convert_node_attributes(node: &mut NodeProto) -> Result<(), OnnxError> {
+    let op_type = node.get_op_type().to_string();
+
+    for attribute in node.mut_attribute().iter_mut() {
+        match (op_type.as_str(), attribute.get_name()) {
+            ("Constant", "value")
+                if attribute.get_t().get_data_type() == TensorProto_DataType::FLOAT as i32 =>
+            {
+                let converted = tensor_to_float16(attribute.get_t())?;
+                attribute.set_t(converted);
+            }
+            ("Cast", "to") if attribute.get_i() == TensorProto_DataType::FLOAT as i64 => {
+                attribute.set_i(TensorProto_DataType::FLOAT16 as i64);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn make_cast(input: &str, output: &str, to: TensorProto_DataType) -> NodeProto {
+    make_node(
+        "Cast".to_string(),
+        vec![input.to_string()],
+        vec![output.to_string()],
+        Some(format!("{}_cast", output)),
+        None,
+        None,
+        vec![make_attribute("to", Attribute::<String>::Int(to as i64))],
+    )
+}
+
+fn is_float(value_info: &ValueInfoProto) -> bool {
+    value_info.get_field_type().get_tensor_type().get_elem_type()
+        == TensorProto_DataType::FLOAT as i32
+}
+
+fn set_float16(value_info: &mut ValueInfoProto) {
+    if is_float(value_info) {
+        value_info
+            .mut_field_type()
+            .mut_tensor_type()
+            .set_elem_type(TensorProto_DataType::FLOAT16 as i32);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod float16;
+pub mod ops_report;
+pub mod optimizer;
+pub mod passes;
+pub mod quantization;
+pub mod size_report;
+
+pub use float16::{convert_float_to_float16, Float16Config};
+pub use ops_report::{ops_report, OperatorCoverage, OperatorStatus, OpsReport};
+pub use optimizer::{optimize, OptLevel, OptimizationReport, PassManager};
+pub use passes::prune_unused_initializers;
+pub use quantization::{
+    quantize_weight, quantize_weights, QuantizationConfig, QuantizedWeight, WeightGranularity,
+};
+pub use size_report::{model_size_report, InitializerSize, ModelSizeReport};
@@ -0,0 +1,211 @@
+use crate::onnx_rustime::backend::parser::{is_default_domain, OnnxParser};
+use crate::onnx_rustime::backend::run::{operation_support, LATEST_OPSET};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, ModelProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::transforms::passes::known_elem_types;
+use protobuf::ProtobufEnum;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Whether the executor can run the nodes of an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "snake_case"))]
+pub enum OperatorStatus {
+    Supported,
+    /// The executor has no kernel for the operation.
+    Unsupported,
+    /// The kernel implements other versions of the operation than the one requested by the model.
+    OpsetMismatch,
+    /// Some inputs have element types the kernel doesn't handle.
+    UnsupportedTypes,
+}
+
+/// Coverage of an operation of a model.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OperatorCoverage {
+    /// The operation type, prefixed by its domain if not the default one (e.g.
+    /// `com.microsoft.Attention`).
+    pub op_type: String,
+    /// Number of nodes of the operation in the model.
+    pub count: usize,
+    pub status: OperatorStatus,
+    /// Version of the operator set of its domain imported by the model.
+    pub requested_opset: Option<i64>,
+    /// First and last version of the default operator set implemented by the kernel.
+    pub kernel_opsets: Option<(i64, i64)>,
+    /// Element types found on the inputs of the nodes (see `known_elem_types`), sorted.
+    pub input_types: Vec<String>,
+    /// Element types handled by the kernel (empty if any type is).
+    pub kernel_types: Vec<String>,
+    /// Inputs of the nodes whose element type couldn't be inferred.
+    pub unknown_inputs: usize,
+}
+
+/// Coverage of the operations of a model by the executor.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OpsReport {
+    /// The operator sets imported by the model, as `(domain, version)`.
+    pub opsets: Vec<(String, i64)>,
+    /// The operations of the model, unsupported first, then by name.
+    pub operators: Vec<OperatorCoverage>,
+}
+
+impl OpsReport {
+    /// Whether every node of the model can be run.
+    pub fn is_fully_supported(&self) -> bool {
+        self.operators
+            .iter()
+            .all(|operator| operator.status == OperatorStatus::Supported)
+    }
+
+    /// Serializes the report as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+}
+
+/// `ops_report` - Cross-references the nodes of a model with the kernels of the executor
+///
+/// For every operation of the model, the nodes of the subgraphs (the branches of `If`, the bodies
+/// of `Loop` and `Scan`) included, the report gives the number of nodes, the opset version
+/// requested by the model against the versions implemented by the kernel (see
+/// `SUPPORTED_OPERATIONS`) and the element types of the inputs against the ones the kernel
+/// handles. The input types come from the graph inputs, the initializers and the `value_info` of
+/// the model, propagated through the nodes by a lightweight type inference (`known_elem_types`):
+/// inputs whose type can't be inferred are counted as unknown, and never flagged. The nodes of a
+/// subgraph also see the types of the tensors they capture from the enclosing graphs.
+///
+/// It's meant to be run before trying a new model: the operations not marked as supported are
+/// the ones to implement (or fix) first.
+///
+/// # Arguments
+///
+/// * `model` - The model to be inspected.
+///
+/// # Returns
+///
+/// * `OpsReport` - The coverage of every operation, unsupported ones first.
+///
+/// # Example
+///
+/// ```rust
+/// let report = ops_report(&model);
+/// for operator in report.operators.iter().filter(|op| op.status != OperatorStatus::Supported) {
+///     println!("{} ({} nodes): {:?}", operator.op_type, operator.count, operator.status);
+/// }
+/// ```
+pub fn ops_report(model: &ModelProto) -> OpsReport {
+    let opsets: Vec<(String, i64)> = model
+        .get_opset_import()
+        .iter()
+        .map(|opset| (opset.get_domain().to_string(), opset.get_version()))
+        .collect();
+
+    let mut operators: BTreeMap<String, OperatorCoverage> = BTreeMap::new();
+    count_operators(model, model.get_graph(), &HashMap::new(), &mut operators);
+
+    let mut operators: Vec<OperatorCoverage> = operators
+        .into_values()
+        .map(|mut operator| {
+            operator.input_types.sort();
+            operator.status = status(&operator);
+            operator
+        })
+        .collect();
+    operators.sort_by_key(|operator| operator.status == OperatorStatus::Supported);
+
+    OpsReport { opsets, operators }
+}
+
+/// Adds the nodes of a graph and of its subgraphs to the coverage of their operations.
+/// `outer_types` are the element types known in the enclosing graphs.
+fn count_operators(
+    model: &ModelProto,
+    graph: &GraphProto,
+    outer_types: &HashMap<String, i32>,
+    operators: &mut BTreeMap<String, OperatorCoverage>,
+) {
+    let mut elem_types = outer_types.clone();
+    elem_types.extend(known_elem_types(graph));
+
+    for node in graph.get_node() {
+        let domain = node.get_domain();
+        let op_type = if is_default_domain(domain) {
+            node.get_op_type().to_string()
+        } else {
+            format!("{}.{}", domain, node.get_op_type())
+        };
+        let support = operation_support(&op_type);
+
+        let operator = operators
+            .entry(op_type.clone())
+            .or_insert_with(|| OperatorCoverage {
+                op_type,
+                count: 0,
+                status: OperatorStatus::Supported,
+                requested_opset: OnnxParser::opset_version(model, domain),
+                kernel_opsets: support.map(|support| support.opsets),
+                input_types: Vec::new(),
+                kernel_types: support
+                    .map(|support| support.data_types.iter().map(type_name).collect())
+                    .unwrap_or_default(),
+                unknown_inputs: 0,
+            });
+        operator.count += 1;
+
+        for input in node.get_input().iter().filter(|input| !input.is_empty()) {
+            match elem_types
+                .get(input)
+                .and_then(|&t| TensorProto_DataType::from_i32(t))
+            {
+                Some(data_type) => {
+                    let name = type_name(&data_type);
+                    if !operator.input_types.contains(&name) {
+                        operator.input_types.push(name);
+                    }
+                }
+                None => operator.unknown_inputs += 1,
+            }
+        }
+
+        for attribute in node.get_attribute() {
+            if attribute.has_g() {
+                count_operators(model, attribute.get_g(), &elem_types, operators);
+            }
+            for subgraph in attribute.get_graphs() {
+                count_operators(model, subgraph, &elem_types, operators);
+            }
+        }
+    }
+}
+
+fn status(operator: &OperatorCoverage) -> OperatorStatus {
+    let (first, last) = match operator.kernel_opsets {
+        Some(opsets) => opsets,
+        None => return OperatorStatus::Unsupported,
+    };
+
+    // Models without opset import are assumed to target the latest version.
+    let requested = operator.requested_opset.unwrap_or(LATEST_OPSET);
+    if requested < first || requested > last {
+        OperatorStatus::OpsetMismatch
+    } else if !operator.kernel_types.is_empty()
+        && operator
+            .input_types
+            .iter()
+            .any(|data_type| !operator.kernel_types.contains(data_type))
+    {
+        OperatorStatus::UnsupportedTypes
+    } else {
+        OperatorStatus::Supported
+    }
+}
+
+fn type_name(data_type: &TensorProto_DataType) -> String {
+    format!("{:?}", data_type)
+}
@@ -0,0 +1,296 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, ModelProto, NodeProto};
+use crate::onnx_rustime::transforms::passes::*;
+use protobuf::Message;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Maximum number of times the whole pass list is run while looking for a fixpoint.
+const MAX_ITERATIONS: usize = 10;
+
+/// Optimization levels.
+///
+/// * `None` - The model is left untouched.
+/// * `Basic` - Semantics-preserving cleanups, which never change the computed values:
+///   `fold_constants`, `optimize_transposes`, `eliminate_pass_through_nodes`,
+///   `eliminate_common_subexpressions`, `eliminate_dead_nodes`, `prune_unused_initializers`,
+///   `deduplicate_initializers`.
+/// * `Extended` - `Basic` plus the fusions, which rewrite the weights and may change the
+///   results within floating point rounding: `fuse_conv_batch_normalization`, `fuse_matmul_add`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+    None,
+    #[default]
+    Basic,
+    Extended,
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptLevel::None => write!(f, "None"),
+            OptLevel::Basic => write!(f, "Basic"),
+            OptLevel::Extended => write!(f, "Extended"),
+        }
+    }
+}
+
+/// A graph optimization pass.
+///
+/// `apply` rewrites the graph in place and returns `true` if something changed.
+#[derive(Clone, Copy)]
+pub struct OptimizationPass {
+    pub name: &'static str,
+    pub level: OptLevel,
+    pub apply: fn(&mut GraphProto) -> Result<bool, OnnxError>,
+}
+
+/// All the available passes, in execution order.
+pub const PASSES: [OptimizationPass; 9] = [
+    OptimizationPass {
+        name: "fold_constants",
+        level: OptLevel::Basic,
+        apply: fold_constants,
+    },
+    OptimizationPass {
+        name: "optimize_transposes",
+        level: OptLevel::Basic,
+        apply: optimize_transposes,
+    },
+    OptimizationPass {
+        name: "eliminate_pass_through_nodes",
+        level: OptLevel::Basic,
+        apply: eliminate_pass_through_nodes,
+    },
+    OptimizationPass {
+        name: "eliminate_common_subexpressions",
+        level: OptLevel::Basic,
+        apply: eliminate_common_subexpressions,
+    },
+    OptimizationPass {
+        name: "fuse_conv_batch_normalization",
+        level: OptLevel::Extended,
+        apply: fuse_conv_batch_normalization,
+    },
+    OptimizationPass {
+        name: "fuse_matmul_add",
+        level: OptLevel::Extended,
+        apply: fuse_matmul_add,
+    },
+    OptimizationPass {
+        name: "eliminate_dead_nodes",
+        level: OptLevel::Basic,
+        apply: eliminate_dead_nodes,
+    },
+    OptimizationPass {
+        name: "prune_unused_initializers",
+        level: OptLevel::Basic,
+        apply: prune_unused_initializers,
+    },
+    // Runs last, so initializers are compared once the other passes have rewritten them.
+    OptimizationPass {
+        name: "deduplicate_initializers",
+        level: OptLevel::Basic,
+        apply: deduplicate_initializers,
+    },
+];
+
+/// Statistics collected for a single pass over all the iterations.
+///
+/// Nodes are compared by definition, so a node rewritten by a pass (e.g. with renamed inputs)
+/// is counted both as removed and as added.
+#[derive(Debug, Clone, Default)]
+pub struct PassStatistics {
+    pub name: String,
+    /// Number of runs that changed the graph.
+    pub changes: usize,
+    pub nodes_removed: usize,
+    pub nodes_added: usize,
+    /// Change of the total size of the initializers (negative when the pass adds data).
+    pub bytes_saved: i64,
+    pub time: Duration,
+}
+
+/// Outcome of an optimization run.
+#[derive(Debug, Clone, Default)]
+pub struct OptimizationReport {
+    pub level: OptLevel,
+    /// Number of iterations over the pass list, the last one being the one without changes.
+    pub iterations: usize,
+    pub nodes_before: usize,
+    pub nodes_after: usize,
+    pub passes: Vec<PassStatistics>,
+}
+
+/// Runs the optimization passes of a level until none of them changes the graph.
+///
+/// Every pass can be disabled (or enabled outside its level) by name, which is useful to find
+/// the pass responsible for a regression.
+///
+/// # Example
+///
+/// ```rust
+/// let mut pass_manager = PassManager::new(OptLevel::Extended);
+/// pass_manager.disable("fuse_conv_batch_normalization");
+/// let (optimized_model, report) = pass_manager.run(&model)?;
+/// ```
+#[derive(Clone)]
+pub struct PassManager {
+    level: OptLevel,
+    passes: Vec<OptimizationPass>,
+}
+
+impl PassManager {
+    /// Creates a pass manager running the passes of the given level.
+    pub fn new(level: OptLevel) -> Self {
+        let passes = PASSES
+            .iter()
+            .filter(|pass| level != OptLevel::None && pass.level <= level)
+            .copied()
+            .collect();
+
+        PassManager { level, passes }
+    }
+
+    /// Names of the passes that will be run, in execution order.
+    pub fn passes(&self) -> Vec<&'static str> {
+        self.passes.iter().map(|pass| pass.name).collect()
+    }
+
+    /// Disables a pass. Returns `false` if the pass was not enabled.
+    pub fn disable(&mut self, name: &str) -> bool {
+        let before = self.passes.len();
+        self.passes.retain(|pass| pass.name != name);
+        self.passes.len() != before
+    }
+
+    /// Enables a pass, even if it doesn't belong to the current level.
+    pub fn enable(&mut self, name: &str) -> Result<(), OnnxError> {
+        if self.passes.iter().any(|pass| pass.name == name) {
+            return Ok(());
+        }
+        PASSES
+            .iter()
+            .find(|pass| pass.name == name)
+            .ok_or(OnnxError::InvalidValue(format!(
+                "Unknown optimization pass '{}'",
+                name
+            )))?;
+
+        // Keep the execution order of `PASSES`.
+        let enabled: HashSet<&str> = self.passes().into_iter().chain([name]).collect();
+        self.passes = PASSES
+            .iter()
+            .filter(|pass| enabled.contains(pass.name))
+            .copied()
+            .collect();
+
+        Ok(())
+    }
+
+    /// Optimizes a copy of the model.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ModelProto, OptimizationReport), OnnxError>` - The optimized model with the
+    ///   statistics of every pass, or the first error (`OnnxError`) raised by a pass.
+    pub fn run(&self, model: &ModelProto) -> Result<(ModelProto, OptimizationReport), OnnxError> {
+        let mut model = model.clone();
+        let mut report = OptimizationReport {
+            level: self.level,
+            nodes_before: model.get_graph().get_node().len(),
+            passes: self
+                .passes
+                .iter()
+                .map(|pass| PassStatistics {
+                    name: pass.name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        if !self.passes.is_empty() {
+            let graph = model.mut_graph();
+
+            while report.iterations < MAX_ITERATIONS {
+                report.iterations += 1;
+                let mut changed = false;
+
+                for (pass, statistics) in self.passes.iter().zip(report.passes.iter_mut()) {
+                    let before = graph.get_node().to_vec();
+                    let bytes_before = initializer_bytes(graph);
+                    let start = Instant::now();
+                    let pass_changed = (pass.apply)(graph)?;
+                    statistics.time += start.elapsed();
+
+                    if pass_changed {
+                        let (removed, added) = diff_nodes(&before, graph.get_node());
+                        statistics.changes += 1;
+                        statistics.nodes_removed += removed;
+                        statistics.nodes_added += added;
+                        statistics.bytes_saved += bytes_before - initializer_bytes(graph);
+                        changed = true;
+                    }
+                }
+
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        report.nodes_after = model.get_graph().get_node().len();
+
+        Ok((model, report))
+    }
+}
+
+/// `optimize` - Optimizes a model with the passes of the given level
+///
+/// With `OptLevel::None` the returned model is an exact copy of the input.
+///
+/// # Arguments
+///
+/// * `model` - The model to be optimized.
+/// * `level` - The optimization level (see `OptLevel` for the passes run at each level).
+///
+/// # Returns
+///
+/// * `Result<ModelProto, OnnxError>` - The optimized model, or the first error (`OnnxError`)
+///   raised by a pass.
+///
+/// # Example
+///
+/// ```rust
+/// let optimized_model = optimize(&model, OptLevel::Extended)?;
+/// ```
+pub fn optimize(model: &ModelProto, level: OptLevel) -> Result<ModelProto, OnnxError> {
+    PassManager::new(level).run(model).map(|(model, _)| model)
+}
+
+fn initializer_bytes(graph: &GraphProto) -> i64 {
+    graph.get_initializer().iter().map(tensor_bytes).sum::<usize>() as i64
+}
+
+/// Counts the nodes removed from and added to a node list, by comparing the node definitions.
+fn diff_nodes(before: &[NodeProto], after: &[NodeProto]) -> (usize, usize) {
+    let key = |node: &NodeProto| node.write_to_bytes().unwrap_or_default();
+
+    let mut remaining: HashMap<Vec<u8>, usize> = HashMap::new();
+    for node in before {
+        *remaining.entry(key(node)).or_insert(0) += 1;
+    }
+
+    let mut added = 0;
+    for node in after {
+        match remaining.get_mut(&key(node)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => added += 1,
+        }
+    }
+
+    let removed = remaining.values().sum();
+    (removed, added)
+}
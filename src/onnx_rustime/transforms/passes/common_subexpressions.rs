@@ -0,0 +1,89 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, NodeProto};
+use crate::onnx_rustime::transforms::passes::{graph_output_names, rename_input};
+use protobuf::{Message, RepeatedField};
+use std::collections::HashMap;
+
+/// Operations that must never be merged, because two executions can give different results.
+const NON_DETERMINISTIC_OPS: [&str; 5] = [
+    "Dropout",
+    "RandomNormal",
+    "RandomNormalLike",
+    "RandomUniform",
+    "RandomUniformLike",
+];
+
+/// `eliminate_common_subexpressions` - Merges the nodes computing the same value
+///
+/// Two nodes are equivalent when they have the same operation type, domain, inputs (in the same
+/// order) and attributes. The second node is removed and its consumers are rewired to the outputs
+/// of the first one. Nodes whose outputs are graph outputs are never removed, so the graph
+/// interface is preserved.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one node has been merged, or an error
+///   (`OnnxError`) if an attribute can't be serialized.
+pub fn eliminate_common_subexpressions(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+
+    let mut seen: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut kept: Vec<NodeProto> = Vec::new();
+    let mut renames: Vec<(String, String)> = Vec::new();
+
+    let mut nodes = graph.get_node().to_vec();
+    for index in 0..nodes.len() {
+        // Apply the renames found so far, so that chains of duplicates are merged as well.
+        for (from, to) in &renames {
+            rename_input(&mut nodes[index..index + 1], from, to);
+        }
+        let node = &nodes[index];
+
+        let removable = !NON_DETERMINISTIC_OPS.contains(&node.get_op_type())
+            && !node.get_output().is_empty()
+            && node.get_output().iter().all(|output| !graph_outputs.contains(output));
+        if !removable {
+            kept.push(node.clone());
+            continue;
+        }
+
+        let key = node_key(node)?;
+        match seen.get(&key) {
+            Some(&original) if kept[original].get_output().len() == node.get_output().len() => {
+                for (from, to) in node.get_output().iter().zip(kept[original].get_output()) {
+                    if !from.is_empty() {
+                        renames.push((from.clone(), to.clone()));
+                    }
+                }
+            }
+            _ => {
+                seen.insert(key, kept.len());
+                kept.push(node.clone());
+            }
+        }
+    }
+
+    if renames.is_empty() {
+        return Ok(false);
+    }
+
+    graph.set_node(RepeatedField::from_vec(kept));
+
+    Ok(true)
+}
+
+/// Builds a key identifying the value computed by a node: everything but its name and outputs.
+fn node_key(node: &NodeProto) -> Result<Vec<u8>, OnnxError> {
+    let mut key = NodeProto::new();
+    key.set_op_type(node.get_op_type().to_string());
+    key.set_domain(node.get_domain().to_string());
+    key.set_input(node.get_input().to_vec().into());
+    key.set_attribute(node.get_attribute().to_vec().into());
+
+    key.write_to_bytes()
+        .map_err(|e| OnnxError::InternalError(format!("Failed to serialize node: {}", e)))
+}
@@ -0,0 +1,180 @@
+use crate::onnx_rustime::backend::helper::{make_graph, make_model, OnnxError};
+use crate::onnx_rustime::backend::run::run_with_inputs;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, NodeProto, TensorProto, ValueInfoProto,
+};
+use crate::onnx_rustime::shared::RunOptions;
+use crate::onnx_rustime::transforms::passes::graph_output_names;
+use protobuf::RepeatedField;
+use std::collections::{HashMap, HashSet};
+
+/// Operations computed ahead of time when all their inputs are constant.
+///
+/// They are deterministic, their kernels don't depend on the operator set of the model (which
+/// the passes don't see), and their outputs are never much larger than their inputs, so folding
+/// them doesn't grow the model.
+const FOLDABLE_OPS: [&str; 30] = [
+    "Abs",
+    "Add",
+    "Cast",
+    "Ceil",
+    "Concat",
+    "Constant",
+    "Div",
+    "Equal",
+    "Exp",
+    "Flatten",
+    "Floor",
+    "Gather",
+    "Identity",
+    "Log",
+    "Mul",
+    "Neg",
+    "Not",
+    "Pow",
+    "Reciprocal",
+    "Relu",
+    "Reshape",
+    "Shape",
+    "Sigmoid",
+    "Slice",
+    "Sqrt",
+    "Squeeze",
+    "Sub",
+    "Tanh",
+    "Transpose",
+    "Unsqueeze",
+];
+
+/// `fold_constants` - Replaces the nodes computing constants with initializers
+///
+/// A node is folded when it is one of `FOLDABLE_OPS` and all its inputs are initializers, or the
+/// outputs of nodes folded before it, so whole constant subgraphs (e.g. the computation of a
+/// target shape) are folded in a single run. The node is run with the kernels of the executor,
+/// and its outputs become initializers holding the computed values.
+///
+/// The initializers listed among the graph inputs are default values the caller can replace, so
+/// the nodes reading them are not folded, and neither are the nodes producing graph outputs. A
+/// node whose kernel fails is kept, for the error to be reported by the run with its context.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one node has been folded.
+pub fn fold_constants(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let overridable: HashSet<&str> = graph
+        .get_input()
+        .iter()
+        .map(|input| input.get_name())
+        .collect();
+    let initializers: HashMap<&str, &TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .filter(|init| !overridable.contains(init.get_name()))
+        .map(|init| (init.get_name(), init))
+        .collect();
+
+    // The folded values, in the order of the nodes computing them.
+    let mut folded: Vec<TensorProto> = Vec::new();
+    let mut folded_names: HashMap<String, usize> = HashMap::new();
+    let mut kept: Vec<NodeProto> = Vec::new();
+
+    for node in graph.get_node() {
+        let constant = |name: &str| {
+            folded_names
+                .get(name)
+                .map(|&index| &folded[index])
+                .or_else(|| initializers.get(name).copied())
+        };
+
+        let foldable = node.get_domain().is_empty()
+            && FOLDABLE_OPS.contains(&node.get_op_type())
+            && !node.get_output().is_empty()
+            && node
+                .get_output()
+                .iter()
+                .all(|output| !output.is_empty() && !graph_outputs.contains(output))
+            && node
+                .get_input()
+                .iter()
+                .all(|input| input.is_empty() || constant(input).is_some());
+        if !foldable {
+            kept.push(node.clone());
+            continue;
+        }
+
+        let inputs: Vec<TensorProto> = node
+            .get_input()
+            .iter()
+            .filter(|input| !input.is_empty())
+            .filter_map(|input| {
+                constant(input).map(|tensor| {
+                    let mut tensor = tensor.clone();
+                    tensor.set_name(input.clone());
+                    tensor
+                })
+            })
+            .collect();
+
+        match evaluate(node, inputs) {
+            Some(outputs) => {
+                for (name, mut tensor) in node.get_output().iter().zip(outputs) {
+                    tensor.set_name(name.clone());
+                    folded_names.insert(name.clone(), folded.len());
+                    folded.push(tensor);
+                }
+            }
+            None => kept.push(node.clone()),
+        }
+    }
+
+    if folded.is_empty() {
+        return Ok(false);
+    }
+
+    graph.set_node(RepeatedField::from_vec(kept));
+    for tensor in folded {
+        graph.mut_initializer().push(tensor);
+    }
+
+    Ok(true)
+}
+
+/// Runs a node on constant inputs, returning its outputs in order, or `None` if it fails.
+fn evaluate(node: &NodeProto, inputs: Vec<TensorProto>) -> Option<Vec<TensorProto>> {
+    let outputs = node
+        .get_output()
+        .iter()
+        .map(|name| {
+            let mut output = ValueInfoProto::new();
+            output.set_name(name.clone());
+            output
+        })
+        .collect();
+    let graph = make_graph(
+        vec![node.clone()],
+        "constant",
+        vec![],
+        outputs,
+        inputs,
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    run_with_inputs(&model, vec![], &RunOptions::default())
+        .ok()
+        .filter(|outputs| outputs.len() == node.get_output().len())
+}
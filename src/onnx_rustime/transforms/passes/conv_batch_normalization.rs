@@ -0,0 +1,184 @@
+use crate::onnx_rustime::backend::helper::{make_tensor, OnnxError, TensorValue};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, NodeProto, TensorProto};
+use crate::onnx_rustime::ops::utils::{extract_attributes, get_float_attribute, tensor_proto_to_ndarray};
+use crate::onnx_rustime::transforms::passes::{consumer_counts, graph_output_names};
+use protobuf::RepeatedField;
+use std::collections::{HashMap, HashSet};
+
+/// `fuse_conv_batch_normalization` - Folds a BatchNormalization into the preceding Conv
+///
+/// In inference mode a BatchNormalization is an affine transformation per output channel, so it
+/// can be merged into the weights and bias of the convolution producing its input:
+///
+/// * `W' = W * scale / sqrt(var + epsilon)`
+/// * `B' = (B - mean) * scale / sqrt(var + epsilon) + bias`
+///
+/// The fusion is applied only when the Conv weights, the Conv bias (if any) and all the
+/// BatchNormalization parameters are initializers, and the Conv output is consumed by the
+/// BatchNormalization alone. The fused weights are stored as new initializers, while the
+/// original ones are removed once no other node uses them.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one pair of nodes has been fused, or an
+///   error (`OnnxError`) if some initializer can't be decoded.
+pub fn fuse_conv_batch_normalization(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let consumers = consumer_counts(graph);
+
+    let initializers: HashMap<String, TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| (init.get_name().to_string(), init.clone()))
+        .collect();
+
+    // Index of the node producing each tensor.
+    let producers: HashMap<String, usize> = graph
+        .get_node()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, node)| node.get_output().iter().map(move |out| (out.clone(), index)))
+        .collect();
+
+    let mut nodes: Vec<Option<NodeProto>> = graph.get_node().iter().cloned().map(Some).collect();
+    let mut new_initializers: Vec<TensorProto> = Vec::new();
+    let mut replaced: HashSet<String> = HashSet::new();
+    let mut fused = false;
+
+    for bn_index in 0..nodes.len() {
+        let bn = match &nodes[bn_index] {
+            Some(node) if node.get_op_type() == "BatchNormalization" => node.clone(),
+            _ => continue,
+        };
+        if bn.get_input().len() != 5 || bn.get_output().len() != 1 {
+            continue;
+        }
+
+        let conv_output = &bn.get_input()[0];
+        let conv_index = match producers.get(conv_output) {
+            Some(&index) => index,
+            None => continue,
+        };
+        let conv = match &nodes[conv_index] {
+            Some(node) if node.get_op_type() == "Conv" => node.clone(),
+            _ => continue,
+        };
+        if consumers.get(conv_output).copied().unwrap_or(0) != 1
+            || graph_outputs.contains(conv_output)
+        {
+            continue;
+        }
+
+        if conv.get_input().len() < 2 {
+            continue;
+        }
+        let parameters: Vec<&String> = bn.get_input()[1..]
+            .iter()
+            .chain(conv.get_input()[1..].iter())
+            .collect();
+        if !parameters.iter().all(|name| initializers.contains_key(*name)) {
+            continue;
+        }
+
+        let bn_attributes = extract_attributes(bn.get_attribute())?;
+        let epsilon = get_float_attribute(&bn_attributes, "epsilon", Some(1e-05))?;
+
+        let scale = tensor_proto_to_ndarray::<f32>(&initializers[&bn.get_input()[1]])?;
+        let bias = tensor_proto_to_ndarray::<f32>(&initializers[&bn.get_input()[2]])?;
+        let mean = tensor_proto_to_ndarray::<f32>(&initializers[&bn.get_input()[3]])?;
+        let var = tensor_proto_to_ndarray::<f32>(&initializers[&bn.get_input()[4]])?;
+        let weights_proto = &initializers[&conv.get_input()[1]];
+        let weights = tensor_proto_to_ndarray::<f32>(weights_proto)?;
+
+        let out_channels = weights.shape()[0];
+        if [&scale, &bias, &mean, &var].iter().any(|t| t.len() != out_channels) {
+            continue;
+        }
+
+        let conv_bias = match conv.get_input().get(2) {
+            Some(name) => tensor_proto_to_ndarray::<f32>(&initializers[name])?.into_raw_vec(),
+            None => vec![0.0; out_channels],
+        };
+        replaced.extend(parameters.into_iter().cloned());
+
+        let factors: Vec<f32> = scale
+            .iter()
+            .zip(var.iter())
+            .map(|(s, v)| s / (v + epsilon).sqrt())
+            .collect();
+
+        let per_channel = weights.len() / out_channels;
+        let fused_weights: Vec<f32> = weights
+            .iter()
+            .enumerate()
+            .map(|(i, w)| w * factors[i / per_channel])
+            .collect();
+        let fused_bias: Vec<f32> = (0..out_channels)
+            .map(|c| (conv_bias[c] - mean[c]) * factors[c] + bias[c])
+            .collect();
+
+        let bn_output = bn.get_output()[0].clone();
+        let weights_name = format!("{}_fused_W", bn_output);
+        let bias_name = format!("{}_fused_B", bn_output);
+
+        new_initializers.push(make_tensor(
+            Some(weights_name.clone()),
+            weights_proto.get_dims().to_vec(),
+            TensorValue::Float(fused_weights),
+        ));
+        new_initializers.push(make_tensor(
+            Some(bias_name.clone()),
+            vec![out_channels as i64],
+            TensorValue::Float(fused_bias),
+        ));
+
+        let mut fused_conv = conv.clone();
+        fused_conv.set_input(RepeatedField::from_vec(vec![
+            conv.get_input()[0].clone(),
+            weights_name,
+            bias_name,
+        ]));
+        fused_conv.set_output(RepeatedField::from_vec(vec![bn_output]));
+
+        // The fused Conv replaces the BatchNormalization, so that it still follows every
+        // producer of its inputs.
+        nodes[conv_index] = None;
+        nodes[bn_index] = Some(fused_conv);
+        fused = true;
+    }
+
+    if !fused {
+        return Ok(false);
+    }
+
+    let nodes: Vec<NodeProto> = nodes.into_iter().flatten().collect();
+    graph.set_node(RepeatedField::from_vec(nodes));
+
+    // Drop the original parameters no longer referenced by any node, together with the graph
+    // inputs declaring them (old-style models).
+    let consumers = consumer_counts(graph);
+    replaced.retain(|name| !consumers.contains_key(name) && !graph_outputs.contains(name));
+
+    let mut kept: Vec<TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .filter(|init| !replaced.contains(init.get_name()))
+        .cloned()
+        .collect();
+    kept.extend(new_initializers);
+    graph.set_initializer(RepeatedField::from_vec(kept));
+
+    let inputs = graph
+        .get_input()
+        .iter()
+        .filter(|input| !replaced.contains(input.get_name()))
+        .cloned()
+        .collect();
+    graph.set_input(RepeatedField::from_vec(inputs));
+
+    Ok(true)
+}
@@ -0,0 +1,53 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::GraphProto;
+use crate::onnx_rustime::transforms::passes::{consumer_counts, graph_output_names};
+use protobuf::RepeatedField;
+
+/// `eliminate_dead_nodes` - Removes the nodes whose outputs are never used
+///
+/// A node is dead when none of its outputs is consumed by another node or is a graph output.
+/// The graph is visited backwards, so whole chains of dead nodes are removed in a single run.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one node has been removed.
+pub fn eliminate_dead_nodes(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let mut consumers = consumer_counts(graph);
+
+    let mut alive = vec![true; graph.get_node().len()];
+    for (index, node) in graph.get_node().iter().enumerate().rev() {
+        let used = node.get_output().iter().any(|output| {
+            graph_outputs.contains(output) || consumers.get(output).copied().unwrap_or(0) > 0
+        });
+
+        if !used {
+            alive[index] = false;
+            // The producers of the inputs lose a consumer.
+            for input in node.get_input() {
+                if let Some(count) = consumers.get_mut(input) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    if alive.iter().all(|&keep| keep) {
+        return Ok(false);
+    }
+
+    let nodes = graph
+        .get_node()
+        .iter()
+        .zip(alive)
+        .filter(|(_, keep)| *keep)
+        .map(|(node, _)| node.clone())
+        .collect();
+    graph.set_node(RepeatedField::from_vec(nodes));
+
+    Ok(true)
+}
@@ -0,0 +1,91 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, TensorProto, TensorProto_DataLocation,
+};
+use crate::onnx_rustime::transforms::passes::{graph_output_names, rename_input};
+use protobuf::{Message, RepeatedField};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// `deduplicate_initializers` - Keeps a single copy of the initializers with the same value
+///
+/// Initializers with the same data type, dimensions and data (e.g. tied embeddings or repeated
+/// constant masks) are merged: the first one is kept and the nodes reading the others are
+/// rewired to it. The contents are bucketed by hash and then compared byte by byte.
+///
+/// Initializers that are also graph inputs (whose value can be overridden at runtime) or graph
+/// outputs are never merged, as well as the ones whose external data hasn't been loaded (see
+/// `OnnxParser::load_model`), since their bytes are not available.
+///
+/// This pass runs after the other rewrites, so that initializers are merged only once they have
+/// their final value.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one initializer has been removed, or an
+///   error (`OnnxError`) if an initializer can't be serialized.
+pub fn deduplicate_initializers(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let protected: HashSet<String> = graph
+        .get_input()
+        .iter()
+        .map(|input| input.get_name().to_string())
+        .chain(graph_output_names(graph))
+        .collect();
+
+    // Canonical initializers, by hash of their contents.
+    let mut canonical: HashMap<u64, Vec<(String, Vec<u8>)>> = HashMap::new();
+    let mut renames: Vec<(String, String)> = Vec::new();
+
+    for init in graph.get_initializer() {
+        if protected.contains(init.get_name())
+            || init.get_data_location() == TensorProto_DataLocation::EXTERNAL
+        {
+            continue;
+        }
+
+        let contents = tensor_contents(init)?;
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+
+        let bucket = canonical.entry(hasher.finish()).or_default();
+        match bucket.iter().find(|(_, other)| *other == contents) {
+            Some((name, _)) => renames.push((init.get_name().to_string(), name.clone())),
+            None => bucket.push((init.get_name().to_string(), contents)),
+        }
+    }
+
+    if renames.is_empty() {
+        return Ok(false);
+    }
+
+    let duplicates: HashSet<&String> = renames.iter().map(|(duplicate, _)| duplicate).collect();
+    let initializers: Vec<TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .filter(|init| !duplicates.contains(&init.get_name().to_string()))
+        .cloned()
+        .collect();
+    graph.set_initializer(RepeatedField::from_vec(initializers));
+
+    for (duplicate, name) in &renames {
+        rename_input(graph.mut_node(), duplicate, name);
+    }
+
+    Ok(true)
+}
+
+/// Serializes everything describing the value of a tensor: data type, dimensions and data.
+fn tensor_contents(tensor: &TensorProto) -> Result<Vec<u8>, OnnxError> {
+    let mut contents = tensor.clone();
+    contents.clear_name();
+    contents.clear_doc_string();
+
+    contents
+        .write_to_bytes()
+        .map_err(|e| OnnxError::InternalError(format!("Failed to serialize tensor: {}", e)))
+}
@@ -0,0 +1,141 @@
+use crate::onnx_rustime::backend::helper::{make_attribute, Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, NodeProto, TensorProto};
+use crate::onnx_rustime::transforms::passes::{consumer_counts, graph_output_names, known_ranks};
+use protobuf::RepeatedField;
+use std::collections::HashMap;
+
+/// `fuse_matmul_add` - Fuses a MatMul followed by the Add of a bias into a single node
+///
+/// Linear layers exported by PyTorch become a MatMul followed by the Add of a bias initializer,
+/// which means two full passes over the output. The pair is fused when:
+///
+/// * `B` is an initializer of rank 2 or more, and the other input of the Add is a 1-D
+///   initializer as long as the last dimension of `B`;
+/// * the MatMul output is consumed by the Add alone and is not a graph output.
+///
+/// The pair becomes `Gemm(A, B, bias, alpha=1, beta=1)` when `A` is known to be a matrix and `B`
+/// is a matrix, since Gemm only works on 2-D tensors. Otherwise (e.g. the `[batch, sequence,
+/// hidden]` activations of a transformer) it becomes `MatMul(A, B, bias)` with the `fused_bias`
+/// attribute, whose kernel adds the bias to the last dimension of the product.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one pair of nodes has been fused.
+pub fn fuse_matmul_add(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let consumers = consumer_counts(graph);
+    let ranks = known_ranks(graph);
+
+    let initializers: HashMap<String, &TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| (init.get_name().to_string(), init))
+        .collect();
+
+    // Index of the node producing each tensor.
+    let producers: HashMap<String, usize> = graph
+        .get_node()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, node)| {
+            node.get_output()
+                .iter()
+                .map(move |out| (out.clone(), index))
+        })
+        .collect();
+
+    let mut nodes: Vec<Option<NodeProto>> = graph.get_node().iter().cloned().map(Some).collect();
+    let mut fused = false;
+
+    for add_index in 0..nodes.len() {
+        let add = match &nodes[add_index] {
+            Some(node) if node.get_op_type() == "Add" && node.get_input().len() == 2 => {
+                node.clone()
+            }
+            _ => continue,
+        };
+
+        // The bias can be either operand of the Add.
+        let candidates = [
+            (&add.get_input()[0], &add.get_input()[1]),
+            (&add.get_input()[1], &add.get_input()[0]),
+        ];
+        let found = candidates.iter().find_map(|(matmul_output, bias)| {
+            let matmul_index = *producers.get(*matmul_output)?;
+            match &nodes[matmul_index] {
+                Some(node) if node.get_op_type() == "MatMul" => {
+                    Some((matmul_index, node.clone(), (*bias).clone()))
+                }
+                _ => None,
+            }
+        });
+        let (matmul_index, matmul, bias) = match found {
+            Some(found) => found,
+            None => continue,
+        };
+
+        let matmul_output = &matmul.get_output()[0];
+        if consumers.get(matmul_output).copied().unwrap_or(0) != 1
+            || graph_outputs.contains(matmul_output)
+        {
+            continue;
+        }
+
+        let (a, b) = (&matmul.get_input()[0], &matmul.get_input()[1]);
+        let (b_tensor, bias_tensor) = match (initializers.get(b), initializers.get(&bias)) {
+            (Some(b_tensor), Some(bias_tensor)) => (b_tensor, bias_tensor),
+            _ => continue,
+        };
+        let b_dims = b_tensor.get_dims();
+        if b_dims.len() < 2
+            || bias_tensor.get_dims().len() != 1
+            || bias_tensor.get_dims()[0] != b_dims[b_dims.len() - 1]
+        {
+            continue;
+        }
+
+        let mut fused_node = NodeProto::new();
+        if ranks.get(a) == Some(&2) && b_dims.len() == 2 {
+            fused_node.set_op_type("Gemm".to_string());
+            fused_node.set_attribute(RepeatedField::from_vec(vec![
+                make_attribute("alpha", Attribute::<String>::Float(1.0)),
+                make_attribute("beta", Attribute::<String>::Float(1.0)),
+            ]));
+        } else {
+            fused_node.set_op_type("MatMul".to_string());
+            fused_node.set_attribute(RepeatedField::from_vec(vec![make_attribute(
+                "fused_bias",
+                Attribute::<String>::Int(1),
+            )]));
+        }
+        fused_node.set_name(if matmul.get_name().is_empty() {
+            format!(
+                "{}_{}",
+                add.get_output()[0],
+                fused_node.get_op_type().to_lowercase()
+            )
+        } else {
+            matmul.get_name().to_string()
+        });
+        fused_node.set_input(RepeatedField::from_vec(vec![a.clone(), b.clone(), bias]));
+        fused_node.set_output(add.get_output().to_vec().into());
+
+        // The fused node replaces the Add, so that it still follows the producer of the bias.
+        nodes[matmul_index] = None;
+        nodes[add_index] = Some(fused_node);
+        fused = true;
+    }
+
+    if !fused {
+        return Ok(false);
+    }
+
+    let nodes: Vec<NodeProto> = nodes.into_iter().flatten().collect();
+    graph.set_node(RepeatedField::from_vec(nodes));
+
+    Ok(true)
+}
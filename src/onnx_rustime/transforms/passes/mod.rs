@@ -0,0 +1,435 @@
+pub mod common_subexpressions;
+pub mod constant_folding;
+pub mod conv_batch_normalization;
+pub mod dead_nodes;
+pub mod duplicate_initializers;
+pub mod matmul_add;
+pub mod pass_through;
+pub mod transpose;
+pub mod unused_initializers;
+
+pub use common_subexpressions::eliminate_common_subexpressions;
+pub use constant_folding::fold_constants;
+pub use conv_batch_normalization::fuse_conv_batch_normalization;
+pub use dead_nodes::eliminate_dead_nodes;
+pub use duplicate_initializers::deduplicate_initializers;
+pub use matmul_add::fuse_matmul_add;
+pub use pass_through::eliminate_pass_through_nodes;
+pub use transpose::optimize_transposes;
+pub use unused_initializers::prune_unused_initializers;
+
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, NodeProto, TensorProto, TensorProto_DataType,
+};
+use protobuf::ProtobufEnum;
+use std::collections::{HashMap, HashSet};
+
+/// Size in bytes of the elements of a tensor, as they are stored in memory (e.g. 1 byte for each
+/// UINT8 element, even if the protobuf stores them as `int32_data`).
+pub(crate) fn tensor_bytes(tensor: &TensorProto) -> usize {
+    let elements = tensor.get_dims().iter().product::<i64>().max(0) as usize;
+
+    let element_size = match TensorProto_DataType::from_i32(tensor.get_data_type()) {
+        Some(TensorProto_DataType::BOOL)
+        | Some(TensorProto_DataType::INT8)
+        | Some(TensorProto_DataType::UINT8) => 1,
+        Some(TensorProto_DataType::INT16)
+        | Some(TensorProto_DataType::UINT16)
+        | Some(TensorProto_DataType::FLOAT16)
+        | Some(TensorProto_DataType::BFLOAT16) => 2,
+        Some(TensorProto_DataType::FLOAT)
+        | Some(TensorProto_DataType::INT32)
+        | Some(TensorProto_DataType::UINT32) => 4,
+        Some(TensorProto_DataType::DOUBLE)
+        | Some(TensorProto_DataType::INT64)
+        | Some(TensorProto_DataType::UINT64)
+        | Some(TensorProto_DataType::COMPLEX64) => 8,
+        Some(TensorProto_DataType::COMPLEX128) => 16,
+        Some(TensorProto_DataType::STRING) => {
+            return tensor.get_string_data().iter().map(|s| s.len()).sum();
+        }
+        _ => return tensor.get_raw_data().len(),
+    };
+
+    elements * element_size
+}
+
+/// Returns the names of the graph outputs.
+pub(crate) fn graph_output_names(graph: &GraphProto) -> HashSet<String> {
+    graph
+        .get_output()
+        .iter()
+        .map(|output| output.get_name().to_string())
+        .collect()
+}
+
+/// Returns the rank of every tensor whose shape is known statically, i.e. the initializers, the
+/// values described by the graph inputs, outputs and `value_info`, and the outputs of the nodes
+/// computed from them.
+pub(crate) fn known_ranks(graph: &GraphProto) -> HashMap<String, usize> {
+    let mut ranks: HashMap<String, usize> = graph
+        .get_input()
+        .iter()
+        .chain(graph.get_output())
+        .chain(graph.get_value_info())
+        .filter(|value_info| value_info.get_field_type().get_tensor_type().has_shape())
+        .map(|value_info| {
+            let shape = value_info.get_field_type().get_tensor_type().get_shape();
+            (value_info.get_name().to_string(), shape.get_dim().len())
+        })
+        .collect();
+
+    for init in graph.get_initializer() {
+        ranks.insert(init.get_name().to_string(), init.get_dims().len());
+    }
+
+    // Propagate the ranks through the operations whose output rank is known in advance.
+    for node in graph.get_node() {
+        let output = match node.get_output().first() {
+            Some(output) if !ranks.contains_key(output) => output,
+            _ => continue,
+        };
+        let input_rank = |index: usize| {
+            node.get_input()
+                .get(index)
+                .and_then(|input| ranks.get(input))
+                .copied()
+        };
+
+        let rank = match node.get_op_type() {
+            "Flatten" | "Gemm" => Some(2),
+            // The vectors lose the dimension they gain to be multiplied.
+            "MatMul" | "MatMulInteger" => match (input_rank(0), input_rank(1)) {
+                (Some(1), Some(1)) => Some(0),
+                (Some(1), Some(rank)) | (Some(rank), Some(1)) => Some(rank - 1),
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            },
+            "Transpose" => input_rank(0),
+            op if SHAPE_PRESERVING_OPS.contains(&op) => input_rank(0),
+            _ => None,
+        };
+        if let Some(rank) = rank {
+            ranks.insert(output.clone(), rank);
+        }
+    }
+
+    ranks
+}
+
+/// Returns the shape of every tensor whose dimensions are all known statically: the initializers
+/// and the values described by the graph inputs, outputs and `value_info` without symbolic
+/// dimensions.
+pub(crate) fn known_shapes(graph: &GraphProto) -> HashMap<String, Vec<i64>> {
+    let mut shapes: HashMap<String, Vec<i64>> = graph
+        .get_input()
+        .iter()
+        .chain(graph.get_output())
+        .chain(graph.get_value_info())
+        .filter(|value_info| value_info.get_field_type().get_tensor_type().has_shape())
+        .filter_map(|value_info| {
+            let dims = value_info.get_field_type().get_tensor_type().get_shape().get_dim();
+            let shape: Option<Vec<i64>> = dims
+                .iter()
+                .map(|dim| Some(dim.get_dim_value()).filter(|_| dim.has_dim_value()))
+                .collect();
+            shape.map(|shape| (value_info.get_name().to_string(), shape))
+        })
+        .collect();
+
+    for init in graph.get_initializer() {
+        shapes.insert(init.get_name().to_string(), init.get_dims().to_vec());
+    }
+
+    shapes
+}
+
+/// Returns the element type (`TensorProto_DataType` value) of every tensor whose type is known
+/// statically, propagated through the operations whose output type follows from their inputs or
+/// attributes (`Cast`, `Shape`, the comparisons, the shape and type preserving operations, ...).
+pub(crate) fn known_elem_types(graph: &GraphProto) -> HashMap<String, i32> {
+    let mut types: HashMap<String, i32> = graph
+        .get_input()
+        .iter()
+        .chain(graph.get_output())
+        .chain(graph.get_value_info())
+        .filter(|value_info| value_info.get_field_type().get_tensor_type().get_elem_type() != 0)
+        .map(|value_info| {
+            let elem_type = value_info.get_field_type().get_tensor_type().get_elem_type();
+            (value_info.get_name().to_string(), elem_type)
+        })
+        .collect();
+
+    for init in graph.get_initializer() {
+        types.insert(init.get_name().to_string(), init.get_data_type());
+    }
+
+    for node in graph.get_node() {
+        let output = match node.get_output().first() {
+            Some(output) if !types.contains_key(output) => output,
+            _ => continue,
+        };
+
+        let elem_type = match node.get_op_type() {
+            "Cast" => node
+                .get_attribute()
+                .iter()
+                .find(|attribute| attribute.get_name() == "to")
+                .map(|attribute| attribute.get_i() as i32),
+            "CastLike" => node.get_input().get(1).and_then(|input| types.get(input)).copied(),
+            "Constant" => node
+                .get_attribute()
+                .iter()
+                .find(|attribute| attribute.get_name() == "value")
+                .map(|attribute| attribute.get_t().get_data_type()),
+            // A FLOAT 0 without value.
+            "ConstantOfShape" => Some(
+                node.get_attribute()
+                    .iter()
+                    .find(|attribute| attribute.get_name() == "value")
+                    .map(|attribute| attribute.get_t().get_data_type())
+                    .unwrap_or(TensorProto_DataType::FLOAT as i32),
+            ),
+            // The type of dtype, FLOAT without it.
+            "RandomNormal" | "RandomUniform" => Some(
+                node.get_attribute()
+                    .iter()
+                    .find(|attribute| attribute.get_name() == "dtype")
+                    .map(|attribute| attribute.get_i() as i32)
+                    .filter(|&dtype| dtype != 0)
+                    .unwrap_or(TensorProto_DataType::FLOAT as i32),
+            ),
+            // The type of dtype, the one of the input without it.
+            "EyeLike" | "RandomNormalLike" | "RandomUniformLike" => node
+                .get_attribute()
+                .iter()
+                .find(|attribute| attribute.get_name() == "dtype")
+                .map(|attribute| attribute.get_i() as i32)
+                .filter(|&dtype| dtype != 0)
+                .or_else(|| node.get_input().first().and_then(|input| types.get(input)).copied()),
+            "Where" => node.get_input().get(1).and_then(|input| types.get(input)).copied(),
+            "OneHot" => node.get_input().get(2).and_then(|input| types.get(input)).copied(),
+            "DequantizeLinear" => Some(TensorProto_DataType::FLOAT as i32),
+            "DynamicQuantizeLinear" => Some(TensorProto_DataType::UINT8 as i32),
+            "MatMulInteger" => Some(TensorProto_DataType::INT32 as i32),
+            // The type of y_zero_point, UINT8 without it (or without an output_dtype).
+            "QuantizeLinear" => match node.get_input().get(2).filter(|input| !input.is_empty()) {
+                Some(input) => types.get(input).copied(),
+                None => Some(
+                    node.get_attribute()
+                        .iter()
+                        .find(|attribute| attribute.get_name() == "output_dtype")
+                        .map(|attribute| attribute.get_i() as i32)
+                        .filter(|&output_dtype| output_dtype != 0)
+                        .unwrap_or(TensorProto_DataType::UINT8 as i32),
+                ),
+            },
+            // The type of y_zero_point.
+            "QLinearConv" => node.get_input().get(7).and_then(|input| types.get(input)).copied(),
+            op if INT64_OUTPUT_OPS.contains(&op) => Some(TensorProto_DataType::INT64 as i32),
+            op if BOOL_OUTPUT_OPS.contains(&op) => Some(TensorProto_DataType::BOOL as i32),
+            op if SHAPE_PRESERVING_OPS.contains(&op) || TYPE_PRESERVING_OPS.contains(&op) => node
+                .get_input()
+                .first()
+                .and_then(|input| types.get(input))
+                .copied(),
+            _ => None,
+        };
+        if let Some(elem_type) = elem_type {
+            types.insert(output.clone(), elem_type);
+        }
+    }
+
+    types
+}
+
+/// Operations whose (first) output has the same shape of their first input.
+const SHAPE_PRESERVING_OPS: [&str; 36] = [
+    "Abs",
+    "BatchNormalization",
+    "Cast",
+    "CastLike",
+    "Ceil",
+    "Clip",
+    "CumSum",
+    "Dropout",
+    "Elu",
+    "Erf",
+    "Exp",
+    "Floor",
+    "Gelu",
+    "HardSigmoid",
+    "HardSwish",
+    "Hardmax",
+    "Identity",
+    "LRN",
+    "LeakyRelu",
+    "Log",
+    "LogSoftmax",
+    "Mish",
+    "Neg",
+    "PRelu",
+    "Reciprocal",
+    "Relu",
+    "Round",
+    "Selu",
+    "Sigmoid",
+    "Sign",
+    "Softmax",
+    "Softplus",
+    "Softsign",
+    "Sqrt",
+    "Tanh",
+    "Trilu",
+];
+
+/// Operations whose (first) output has the element type of their first input, besides the
+/// shape-preserving ones.
+const TYPE_PRESERVING_OPS: [&str; 57] = [
+    "Add",
+    "AveragePool",
+    "Compress",
+    "Concat",
+    "Conv",
+    "ConvTranspose",
+    "DepthToSpace",
+    "Div",
+    "Einsum",
+    "Expand",
+    "Flatten",
+    "GRU",
+    "Gather",
+    "GatherElements",
+    "GatherND",
+    "Gemm",
+    "GlobalAveragePool",
+    "GlobalLpPool",
+    "GlobalMaxPool",
+    "LSTM",
+    "LayerNormalization",
+    "LpPool",
+    "MatMul",
+    "Max",
+    "MaxPool",
+    "Mean",
+    "Min",
+    "Mod",
+    "Mul",
+    "Pad",
+    "Pow",
+    "RNN",
+    "ReduceL1",
+    "ReduceL2",
+    "ReduceLogSum",
+    "ReduceLogSumExp",
+    "ReduceMax",
+    "ReduceMean",
+    "ReduceMin",
+    "ReduceProd",
+    "ReduceSum",
+    "ReduceSumSquare",
+    "Reshape",
+    "Resize",
+    "RoiAlign",
+    "ScatterElements",
+    "ScatterND",
+    "Slice",
+    "SpaceToDepth",
+    "Split",
+    "Squeeze",
+    "Sub",
+    "Sum",
+    "Tile",
+    "Transpose",
+    "Unsqueeze",
+    "Upsample",
+];
+
+/// Operations whose (first) output is always INT64.
+const INT64_OUTPUT_OPS: [&str; 6] = [
+    "ArgMax",
+    "ArgMin",
+    "NonMaxSuppression",
+    "NonZero",
+    "Shape",
+    "Size",
+];
+
+/// Operations whose output is always BOOL.
+const BOOL_OUTPUT_OPS: [&str; 11] = [
+    "And",
+    "Equal",
+    "Greater",
+    "GreaterOrEqual",
+    "IsInf",
+    "IsNaN",
+    "Less",
+    "LessOrEqual",
+    "Not",
+    "Or",
+    "Xor",
+];
+
+/// Counts, for every tensor name, how many node inputs reference it.
+///
+/// Nodes of the subgraphs (e.g. the branches of an `If`) are visited as well, since they can
+/// capture tensors of the enclosing graph by name. The outputs of a subgraph count as consumers
+/// too: a branch may output a tensor of the enclosing graph without any node reading it.
+pub(crate) fn consumer_counts(graph: &GraphProto) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    count_consumers(graph.get_node(), &mut counts);
+    counts
+}
+
+fn count_consumers(nodes: &[NodeProto], counts: &mut HashMap<String, usize>) {
+    for node in nodes {
+        for input in node.get_input().iter().filter(|input| !input.is_empty()) {
+            *counts.entry(input.clone()).or_insert(0) += 1;
+        }
+        for attribute in node.get_attribute() {
+            if attribute.has_g() {
+                count_subgraph_consumers(attribute.get_g(), counts);
+            }
+            for subgraph in attribute.get_graphs() {
+                count_subgraph_consumers(subgraph, counts);
+            }
+        }
+    }
+}
+
+fn count_subgraph_consumers(subgraph: &GraphProto, counts: &mut HashMap<String, usize>) {
+    count_consumers(subgraph.get_node(), counts);
+    for output in subgraph.get_output() {
+        *counts.entry(output.get_name().to_string()).or_insert(0) += 1;
+    }
+}
+
+/// Replaces every reference to the tensor `from` with `to`, subgraphs included.
+///
+/// The outputs of the subgraphs are references as well, since they are matched by position.
+pub(crate) fn rename_input(nodes: &mut [NodeProto], from: &str, to: &str) {
+    for node in nodes.iter_mut() {
+        for input in node.mut_input().iter_mut() {
+            if input == from {
+                *input = to.to_string();
+            }
+        }
+        for attribute in node.mut_attribute().iter_mut() {
+            if attribute.has_g() {
+                rename_subgraph_input(attribute.mut_g(), from, to);
+            }
+            for subgraph in attribute.mut_graphs().iter_mut() {
+                rename_subgraph_input(subgraph, from, to);
+            }
+        }
+    }
+}
+
+fn rename_subgraph_input(subgraph: &mut GraphProto, from: &str, to: &str) {
+    rename_input(subgraph.mut_node(), from, to);
+    for output in subgraph.mut_output().iter_mut() {
+        if output.get_name() == from {
+            output.set_name(to.to_string());
+        }
+    }
+}
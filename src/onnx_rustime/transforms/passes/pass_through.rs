@@ -0,0 +1,141 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, NodeProto, TensorProto};
+use crate::onnx_rustime::ops::dropout::is_training_mode;
+use crate::onnx_rustime::transforms::passes::{
+    consumer_counts, graph_output_names, known_elem_types, rename_input,
+};
+use protobuf::RepeatedField;
+use std::collections::{HashMap, HashSet};
+
+/// `eliminate_pass_through_nodes` - Removes the nodes copying their input to their output
+///
+/// The following nodes are removed, and their consumers are rewired to read the input directly:
+///
+/// * `Identity`;
+/// * `Dropout` in inference mode (`training_mode` absent or a false initializer), when its mask
+///   output is not used;
+/// * `Cast` to the type the input already has, and `CastLike` of an input of the type of its
+///   target.
+///
+/// When the output of the removed node is a graph output, the producer of the input is renamed
+/// instead, so the graph interface doesn't change. If that's not possible (the input is a graph
+/// input, an initializer or another graph output) the node is kept.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one node has been removed.
+pub fn eliminate_pass_through_nodes(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let graph_inputs: HashSet<String> = graph
+        .get_input()
+        .iter()
+        .map(|input| input.get_name().to_string())
+        .collect();
+    let initializers: HashMap<String, TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| (init.get_name().to_string(), init.clone()))
+        .collect();
+    let consumers = consumer_counts(graph);
+    let elem_types = known_elem_types(graph);
+
+    let mut nodes: Vec<NodeProto> = graph.get_node().to_vec();
+    let mut removed = false;
+
+    let mut index = 0;
+    while index < nodes.len() {
+        let forwarded = forwarded_input(
+            &nodes[index],
+            &initializers,
+            &consumers,
+            &graph_outputs,
+            &elem_types,
+        );
+        let source = match forwarded {
+            Some(source) => source,
+            None => {
+                index += 1;
+                continue;
+            }
+        };
+        let output = nodes[index].get_output()[0].clone();
+
+        if !graph_outputs.contains(&output) {
+            nodes.remove(index);
+            rename_input(&mut nodes, &output, &source);
+        } else if graph_inputs.contains(&source)
+            || initializers.contains_key(&source)
+            || graph_outputs.contains(&source)
+        {
+            index += 1;
+            continue;
+        } else {
+            // The output name is part of the graph interface: the producer writes it directly.
+            nodes.remove(index);
+            for node in nodes.iter_mut() {
+                for node_output in node.mut_output().iter_mut() {
+                    if *node_output == source {
+                        *node_output = output.clone();
+                    }
+                }
+            }
+            rename_input(&mut nodes, &source, &output);
+        }
+
+        removed = true;
+    }
+
+    if removed {
+        graph.set_node(RepeatedField::from_vec(nodes));
+    }
+
+    Ok(removed)
+}
+
+/// Returns the input forwarded unchanged by a pass-through node, or `None` for any other node.
+fn forwarded_input(
+    node: &NodeProto,
+    initializers: &HashMap<String, TensorProto>,
+    consumers: &HashMap<String, usize>,
+    graph_outputs: &HashSet<String>,
+    elem_types: &HashMap<String, i32>,
+) -> Option<String> {
+    let input = node.get_input().first().filter(|input| !input.is_empty())?;
+    if node.get_output().is_empty() || node.get_output()[0].is_empty() {
+        return None;
+    }
+
+    match node.get_op_type() {
+        "Identity" => Some(input.clone()),
+        "Dropout" => {
+            let mask_used = node.get_output().get(1).is_some_and(|mask| {
+                graph_outputs.contains(mask) || consumers.get(mask).copied().unwrap_or(0) > 0
+            });
+            let inference = match node.get_input().get(2) {
+                None => true,
+                Some(name) if name.is_empty() => true,
+                Some(name) => initializers
+                    .get(name)
+                    .is_some_and(|tensor| !is_training_mode(tensor)),
+            };
+            (!mask_used && inference).then(|| input.clone())
+        }
+        "Cast" => {
+            let to = node
+                .get_attribute()
+                .iter()
+                .find(|attribute| attribute.get_name() == "to")?
+                .get_i() as i32;
+            (elem_types.get(input) == Some(&to)).then(|| input.clone())
+        }
+        "CastLike" => {
+            let to = elem_types.get(node.get_input().get(1)?)?;
+            (elem_types.get(input) == Some(to)).then(|| input.clone())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,202 @@
+use crate::onnx_rustime::backend::helper::{make_attribute, Attribute, OnnxError};
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, NodeProto};
+use crate::onnx_rustime::transforms::passes::{
+    consumer_counts, graph_output_names, known_ranks, known_shapes,
+};
+use protobuf::RepeatedField;
+use std::collections::{HashMap, HashSet};
+
+/// `optimize_transposes` - Removes and folds the Transpose nodes
+///
+/// Models converted from TensorFlow are full of NHWC ↔ NCHW Transposes. This pass applies three
+/// rewrite rules, each one only when the output of the Transpose feeds a single node and is not a
+/// graph output (otherwise the Transpose has to be computed anyway):
+///
+/// * Two consecutive Transposes are merged into one with the composed permutation. When the two
+///   permutations are inverse the result is an `Identity`, removed by
+///   `eliminate_pass_through_nodes`.
+/// * A Transpose swapping the two dimensions of a matrix, followed by a MatMul, becomes the
+///   `transA`/`transB` attribute of an equivalent Gemm (when the other operand is a matrix too).
+/// * A Transpose followed by a Reshape is dropped when it only moves dimensions of size 1, i.e.
+///   when it doesn't change the order of the elements in memory.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one Transpose has been removed or folded.
+pub fn optimize_transposes(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let graph_outputs = graph_output_names(graph);
+    let consumers = consumer_counts(graph);
+    let ranks = known_ranks(graph);
+    let shapes = known_shapes(graph);
+
+    // Index of the node producing each tensor.
+    let producers: HashMap<String, usize> = graph
+        .get_node()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, node)| node.get_output().iter().map(move |out| (out.clone(), index)))
+        .collect();
+
+    let mut nodes: Vec<Option<NodeProto>> = graph.get_node().iter().cloned().map(Some).collect();
+    let mut changed = false;
+
+    // Returns the index and the permutation of the Transpose producing `name`, if it can be
+    // removed once its only consumer is rewritten.
+    let removable_transpose = |nodes: &Vec<Option<NodeProto>>, name: &String| {
+        if consumers.get(name).copied().unwrap_or(0) != 1 || graph_outputs.contains(name) {
+            return None;
+        }
+        let index = *producers.get(name)?;
+        let transpose = nodes[index].as_ref()?;
+        if transpose.get_op_type() != "Transpose" {
+            return None;
+        }
+        let rank = ranks.get(&transpose.get_input()[0]).copied();
+        permutation(transpose, rank).map(|perm| (index, perm))
+    };
+
+    for index in 0..nodes.len() {
+        let node = match &nodes[index] {
+            Some(node) => node.clone(),
+            None => continue,
+        };
+
+        match node.get_op_type() {
+            "Transpose" => {
+                let input = &node.get_input()[0];
+                let (first_index, first_perm) = match removable_transpose(&nodes, input) {
+                    Some(found) => found,
+                    None => continue,
+                };
+                let second_perm = match permutation(&node, Some(first_perm.len())) {
+                    Some(perm) if perm.len() == first_perm.len() => perm,
+                    _ => continue,
+                };
+                let source = nodes[first_index].as_ref().unwrap().get_input()[0].clone();
+
+                // Transpose(Transpose(X, p1), p2) = Transpose(X, q) with q[i] = p1[p2[i]].
+                let composed: Vec<i64> = second_perm
+                    .iter()
+                    .map(|&axis| first_perm[axis as usize])
+                    .collect();
+
+                let mut merged = node.clone();
+                merged.set_input(RepeatedField::from_vec(vec![source]));
+                if is_identity(&composed) {
+                    merged.set_op_type("Identity".to_string());
+                    merged.clear_attribute();
+                } else {
+                    merged.set_attribute(RepeatedField::from_vec(vec![make_attribute(
+                        "perm",
+                        Attribute::<String>::Ints(composed),
+                    )]));
+                }
+
+                nodes[first_index] = None;
+                nodes[index] = Some(merged);
+                changed = true;
+            }
+            "MatMul" => {
+                let operands = [&node.get_input()[0], &node.get_input()[1]];
+                let mut inputs: Vec<String> = operands.iter().map(|name| name.to_string()).collect();
+                let mut transposed = [false, false];
+                let mut removed = Vec::new();
+
+                for (k, operand) in operands.iter().enumerate() {
+                    if let Some((transpose_index, perm)) = removable_transpose(&nodes, operand) {
+                        let other = operands[1 - k];
+                        if perm == [1, 0] && ranks.get(other) == Some(&2) {
+                            inputs[k] = nodes[transpose_index].as_ref().unwrap().get_input()[0]
+                                .clone();
+                            transposed[k] = true;
+                            removed.push(transpose_index);
+                        }
+                    }
+                }
+                if removed.is_empty() {
+                    continue;
+                }
+
+                let mut gemm = node.clone();
+                gemm.set_op_type("Gemm".to_string());
+                gemm.set_input(RepeatedField::from_vec(inputs));
+                gemm.set_attribute(RepeatedField::from_vec(vec![
+                    make_attribute("transA", Attribute::<String>::Int(transposed[0] as i64)),
+                    make_attribute("transB", Attribute::<String>::Int(transposed[1] as i64)),
+                ]));
+
+                for transpose_index in removed {
+                    nodes[transpose_index] = None;
+                }
+                nodes[index] = Some(gemm);
+                changed = true;
+            }
+            "Reshape" => {
+                let input = &node.get_input()[0];
+                let (transpose_index, perm) = match removable_transpose(&nodes, input) {
+                    Some(found) => found,
+                    None => continue,
+                };
+                let source = nodes[transpose_index].as_ref().unwrap().get_input()[0].clone();
+                let shape = match shapes.get(&source) {
+                    Some(shape) if shape.len() == perm.len() => shape,
+                    _ => continue,
+                };
+
+                // Reshape scales the first dimension by the batch size of its input, so the
+                // first dimension has to be unchanged as well.
+                if !preserves_memory_order(shape, &perm) || shape[perm[0] as usize] != shape[0] {
+                    continue;
+                }
+
+                let mut reshape = node.clone();
+                reshape.mut_input()[0] = source;
+                nodes[transpose_index] = None;
+                nodes[index] = Some(reshape);
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+
+    if changed {
+        let nodes: Vec<NodeProto> = nodes.into_iter().flatten().collect();
+        graph.set_node(RepeatedField::from_vec(nodes));
+    }
+
+    Ok(changed)
+}
+
+/// Reads the `perm` attribute of a Transpose, which defaults to reversing the dimensions.
+fn permutation(node: &NodeProto, rank: Option<usize>) -> Option<Vec<i64>> {
+    let perm: Vec<i64> =
+        match node.get_attribute().iter().find(|attribute| attribute.get_name() == "perm") {
+            Some(attribute) => attribute.get_ints().to_vec(),
+            None => (0..rank? as i64).rev().collect(),
+        };
+
+    // Invalid permutations are left to the Transpose kernel to report.
+    let distinct: HashSet<i64> = perm.iter().copied().collect();
+    let valid = distinct.len() == perm.len()
+        && perm.iter().all(|&axis| axis >= 0 && (axis as usize) < perm.len());
+    valid.then_some(perm)
+}
+
+fn is_identity(perm: &[i64]) -> bool {
+    perm.iter().enumerate().all(|(i, &axis)| axis == i as i64)
+}
+
+/// Checks whether a permutation only moves dimensions of size 1, keeping the relative order of
+/// the others.
+fn preserves_memory_order(shape: &[i64], perm: &[i64]) -> bool {
+    let moved: Vec<i64> = perm
+        .iter()
+        .copied()
+        .filter(|&axis| shape[axis as usize] != 1)
+        .collect();
+    moved.windows(2).all(|pair| pair[0] < pair[1])
+}
@@ -0,0 +1,76 @@
+use crate::onnx_rustime::backend::helper::OnnxError;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{GraphProto, TensorProto, ValueInfoProto};
+use crate::onnx_rustime::transforms::passes::{consumer_counts, graph_output_names};
+use protobuf::RepeatedField;
+use std::collections::HashSet;
+
+/// `prune_unused_initializers` - Removes the initializers and `value_info` entries no node uses
+///
+/// After an extraction or a fusion, the graph may keep weights that no node reads anymore. An
+/// initializer is removed when it is neither consumed by a node (subgraphs included) nor a graph
+/// output.
+///
+/// Models following the old convention (IR version < 4) also declare every initializer as a
+/// graph input, whose value can be overridden at runtime. The graph input of a removed
+/// initializer is removed with it, since it only declared the weight. The graph inputs that
+/// don't shadow an initializer are part of the model interface and are never removed, even if
+/// unused.
+///
+/// The `value_info` entries describing tensors that are neither read nor written by a node are
+/// removed as well.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to be optimized in place.
+///
+/// # Returns
+///
+/// * `Result<bool, OnnxError>` - `true` if at least one initializer or `value_info` entry has
+///   been removed.
+pub fn prune_unused_initializers(graph: &mut GraphProto) -> Result<bool, OnnxError> {
+    let consumers = consumer_counts(graph);
+    let graph_outputs = graph_output_names(graph);
+    let is_used = |name: &str| consumers.contains_key(name) || graph_outputs.contains(name);
+
+    let (initializers, unused): (Vec<TensorProto>, Vec<TensorProto>) = graph
+        .get_initializer()
+        .iter()
+        .cloned()
+        .partition(|init| is_used(init.get_name()));
+    let unused: HashSet<String> = unused
+        .iter()
+        .map(|init| init.get_name().to_string())
+        .collect();
+
+    let node_outputs: HashSet<&String> = graph
+        .get_node()
+        .iter()
+        .flat_map(|node| node.get_output())
+        .collect();
+    let value_info: Vec<ValueInfoProto> = graph
+        .get_value_info()
+        .iter()
+        .filter(|info| {
+            is_used(info.get_name()) || node_outputs.contains(&info.get_name().to_string())
+        })
+        .cloned()
+        .collect();
+
+    let removed_value_info = value_info.len() != graph.get_value_info().len();
+    if unused.is_empty() && !removed_value_info {
+        return Ok(false);
+    }
+
+    let inputs: Vec<ValueInfoProto> = graph
+        .get_input()
+        .iter()
+        .filter(|input| !unused.contains(input.get_name()))
+        .cloned()
+        .collect();
+
+    graph.set_initializer(RepeatedField::from_vec(initializers));
+    graph.set_input(RepeatedField::from_vec(inputs));
+    graph.set_value_info(RepeatedField::from_vec(value_info));
+
+    Ok(true)
+}
@@ -0,0 +1,265 @@
+use crate::onnx_rustime::backend::helper::{make_attribute, make_node, Attribute, OnnxError};
+use crate::onnx_rustime::backend::parser::OnnxParser;
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType,
+};
+use crate::onnx_rustime::ops::quantize_linear::quantize;
+use crate::onnx_rustime::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use ndarray::prelude::*;
+use ndarray::Zip;
+use protobuf::RepeatedField;
+use std::collections::HashSet;
+
+/// The range of the quantized weights. -128 is left out, so that the range is symmetric around
+/// the zero point of 0.
+const WEIGHT_RANGE: (i32, i32) = (-(i8::MAX as i32), i8::MAX as i32);
+
+/// How many scales a quantized weight has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightGranularity {
+    /// A single scale for the whole weight.
+    PerTensor,
+    /// A scale per index of the first axis, i.e. per output channel of a Conv kernel.
+    PerChannel,
+}
+
+/// Configuration of the weight quantization.
+#[derive(Debug, Clone)]
+pub struct QuantizationConfig {
+    /// The granularity of the scales of the Conv kernels, per channel by default: the output
+    /// channels of a convolution (a depthwise one in particular) can have very different ranges,
+    /// and a single scale wastes most of the quantized values of the small ones.
+    pub conv_weights: WeightGranularity,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        QuantizationConfig {
+            conv_weights: WeightGranularity::PerChannel,
+        }
+    }
+}
+
+/// A weight quantized symmetrically to INT8: its zero points are all 0, and its values stand for
+/// `values * scales`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedWeight {
+    pub values: ArrayD<i8>,
+    /// A single scale, or one per index of the first axis.
+    pub scales: Array1<f32>,
+}
+
+impl QuantizedWeight {
+    /// The granularity of the scales. A weight of a single channel has a single scale either way.
+    pub fn granularity(&self) -> WeightGranularity {
+        if self.scales.len() > 1 {
+            WeightGranularity::PerChannel
+        } else {
+            WeightGranularity::PerTensor
+        }
+    }
+
+    /// The FLOAT values the quantized weight stands for, as computed by `DequantizeLinear`.
+    pub fn dequantize(&self) -> ArrayD<f32> {
+        Zip::from(&self.values)
+            .and_broadcast(&broadcast_scales(&self.scales, self.values.ndim()))
+            .map_collect(|&value, &scale| value as f32 * scale)
+    }
+}
+
+/// `quantize_weight` - Quantizes a FLOAT weight symmetrically to INT8
+///
+/// The scale of every channel (or of the whole weight) maps its largest absolute value to 127,
+/// and the values are rounded half to even. A channel of zeros gets a scale of 1.
+///
+/// # Arguments
+///
+/// * `weight` - The weight to be quantized, with the channels along its first axis.
+/// * `granularity` - One scale for the whole weight or one per channel.
+///
+/// # Returns
+///
+/// * `QuantizedWeight` - The quantized values and their scales.
+///
+/// # Example
+///
+/// ```rust
+/// let quantized = quantize_weight(&weight, WeightGranularity::PerChannel);
+/// let error = (&quantized.dequantize() - &weight).mapv(f32::abs);
+/// ```
+pub fn quantize_weight(weight: &ArrayD<f32>, granularity: WeightGranularity) -> QuantizedWeight {
+    let scale = |values: ArrayViewD<f32>| {
+        let max = values
+            .iter()
+            .fold(0.0f32, |max, &value| max.max(value.abs()));
+        if max > 0.0 {
+            max / WEIGHT_RANGE.1 as f32
+        } else {
+            1.0
+        }
+    };
+
+    let scales = match granularity {
+        WeightGranularity::PerChannel if weight.ndim() > 0 => {
+            weight.axis_iter(Axis(0)).map(scale).collect()
+        }
+        _ => arr1(&[scale(weight.view())]),
+    };
+
+    let mut quantized = QuantizedWeight {
+        values: ArrayD::zeros(weight.raw_dim()),
+        scales,
+    };
+    let scales = broadcast_scales(&quantized.scales, weight.ndim());
+    Zip::from(&mut quantized.values)
+        .and(weight)
+        .and_broadcast(&scales)
+        .for_each(|quantized, &value, &scale| {
+            *quantized = quantize(value / scale, 0, WEIGHT_RANGE) as i8
+        });
+    quantized
+}
+
+/// `quantize_weights` - Quantizes the Conv kernels of a FLOAT model to INT8
+///
+/// Every FLOAT initializer used as the kernel of a `Conv` is replaced by its INT8 values
+/// (`<name>_quantized`), its scales (`<name>_scale`) and zero points (`<name>_zero_point`), and
+/// a `DequantizeLinear` node computing the FLOAT kernel back from them is inserted at the start
+/// of the graph. The kernels are quantized per output channel by default (see
+/// `QuantizationConfig`), the scales being along axis 0 of the `DequantizeLinear` nodes.
+///
+/// The rest of the model is unchanged, so the model computes the same outputs up to the
+/// rounding of the kernels, and stores them in a quarter of the size.
+///
+/// # Arguments
+///
+/// * `model` - The FLOAT model to be quantized.
+/// * `config` - The quantization options (see `QuantizationConfig`).
+///
+/// # Returns
+///
+/// * `Result<ModelProto, OnnxError>` - The quantized model, or an error (`OnnxError`) if some
+///   kernel can't be decoded.
+///
+/// # Errors
+///
+/// Possible errors include:
+/// * A model importing an opset without `DequantizeLinear` (before opset 10), or without its
+///   `axis` attribute (before opset 13) for per-channel kernels.
+///
+/// # Example
+///
+/// ```rust
+/// let quantized = quantize_weights(&model, &QuantizationConfig::default())?;
+/// OnnxParser::save_model(&quantized, "model_quantized.onnx".to_string())?;
+/// ```
+pub fn quantize_weights(
+    model: &ModelProto,
+    config: &QuantizationConfig,
+) -> Result<ModelProto, OnnxError> {
+    let mut model = model.clone();
+    let opset = OnnxParser::opset_version(&model, "").unwrap_or(13);
+    let required = match config.conv_weights {
+        WeightGranularity::PerTensor => 10,
+        WeightGranularity::PerChannel => 13,
+    };
+
+    let graph = model.mut_graph();
+    let kernels: HashSet<String> = graph
+        .get_node()
+        .iter()
+        .filter(|node| node.get_op_type() == "Conv")
+        .filter_map(|node| node.get_input().get(1).cloned())
+        .collect();
+
+    let mut initializers = Vec::new();
+    let mut nodes = Vec::new();
+    let mut quantized_names = HashSet::new();
+    for init in graph.get_initializer() {
+        if !kernels.contains(init.get_name())
+            || init.get_data_type() != TensorProto_DataType::FLOAT as i32
+        {
+            initializers.push(init.clone());
+            continue;
+        }
+        if opset < required {
+            return Err(OnnxError::UnsupportedOperation(format!(
+                "Quantizing the Conv kernels {:?} requires opset {}, the model imports opset {}",
+                config.conv_weights, required, opset
+            )));
+        }
+
+        let name = init.get_name();
+        let quantized =
+            quantize_weight(&tensor_proto_to_ndarray::<f32>(init)?, config.conv_weights);
+        let (tensors, node) = dequantized_kernel(name, &quantized)?;
+        initializers.extend(tensors);
+        nodes.push(node);
+        quantized_names.insert(name.to_string());
+    }
+
+    nodes.extend(graph.get_node().iter().cloned());
+    graph.set_node(RepeatedField::from_vec(nodes));
+    graph.set_initializer(RepeatedField::from_vec(initializers));
+
+    // Old-style models also list the initializers among the graph inputs.
+    graph
+        .mut_input()
+        .retain(|input| !quantized_names.contains(input.get_name()));
+
+    Ok(model)
+}
+
+/// Reshapes the scales of a weight of the given rank to broadcast them along its first axis.
+fn broadcast_scales(scales: &Array1<f32>, ndim: usize) -> ArrayViewD<'_, f32> {
+    let mut shape = vec![1; ndim];
+    if scales.len() > 1 {
+        shape[0] = scales.len();
+    }
+    scales
+        .view()
+        .into_shape(shape)
+        .expect("a single scale or one per channel")
+}
+
+/// The initializers of a quantized kernel and the `DequantizeLinear` node computing the kernel.
+fn dequantized_kernel(
+    name: &str,
+    quantized: &QuantizedWeight,
+) -> Result<(Vec<TensorProto>, NodeProto), OnnxError> {
+    let values_name = format!("{}_quantized", name);
+    let scale_name = format!("{}_scale", name);
+    let zero_point_name = format!("{}_zero_point", name);
+
+    let per_channel = quantized.granularity() == WeightGranularity::PerChannel;
+    let (scales, zero_points) = if per_channel {
+        (
+            quantized.scales.clone().into_dyn(),
+            ArrayD::zeros(IxDyn(&[quantized.scales.len()])),
+        )
+    } else {
+        (arr0(quantized.scales[0]).into_dyn(), arr0(0i8).into_dyn())
+    };
+    let tensors = vec![
+        ndarray_to_tensor_proto::<i8>(quantized.values.clone(), &values_name)?,
+        ndarray_to_tensor_proto::<f32>(scales, &scale_name)?,
+        ndarray_to_tensor_proto::<i8>(zero_points, &zero_point_name)?,
+    ];
+
+    let attributes = if per_channel {
+        vec![make_attribute("axis", Attribute::<String>::Int(0))]
+    } else {
+        vec![]
+    };
+    let node = make_node(
+        "DequantizeLinear".to_string(),
+        vec![values_name, scale_name, zero_point_name],
+        vec![name.to_string()],
+        Some(format!("{}_dequantize", name)),
+        None,
+        None,
+        attributes,
+    );
+
+    Ok((tensors, node))
+}
@@ -0,0 +1,70 @@
+use crate::onnx_rustime::onnx_proto::onnx_ml_proto3::{ModelProto, TensorProto_DataType};
+use crate::onnx_rustime::transforms::passes::tensor_bytes;
+use protobuf::{Message, ProtobufEnum};
+
+/// Size of a single initializer.
+#[derive(Debug, Clone)]
+pub struct InitializerSize {
+    pub name: String,
+    /// Name of the element type (e.g. `FLOAT`).
+    pub data_type: String,
+    pub shape: Vec<i64>,
+    /// Size of the elements in memory (see `ModelSizeReport::initializer_bytes`).
+    pub bytes: usize,
+}
+
+/// Breakdown of the size of a model.
+#[derive(Debug, Clone, Default)]
+pub struct ModelSizeReport {
+    /// The initializers, from the largest to the smallest.
+    pub initializers: Vec<InitializerSize>,
+    /// Total size of the initializers, computed from their element type and shape (the protobuf
+    /// encoding of the data may be larger, e.g. for varint-encoded integers).
+    pub initializer_bytes: usize,
+    /// Size of the serialized model.
+    pub model_bytes: usize,
+}
+
+/// `model_size_report` - Computes where the bytes of a model go
+///
+/// Initializers whose data is stored in an external file count for their full size as well.
+///
+/// # Arguments
+///
+/// * `model` - The model to be inspected.
+///
+/// # Returns
+///
+/// * `ModelSizeReport` - The size of every initializer, sorted in descending order of size
+///   (and then by name), with the totals.
+///
+/// # Example
+///
+/// ```rust
+/// let report = model_size_report(&model);
+/// for init in report.initializers.iter().take(5) {
+///     println!("{} {:?}: {} bytes", init.name, init.shape, init.bytes);
+/// }
+/// ```
+pub fn model_size_report(model: &ModelProto) -> ModelSizeReport {
+    let mut initializers: Vec<InitializerSize> = model
+        .get_graph()
+        .get_initializer()
+        .iter()
+        .map(|init| InitializerSize {
+            name: init.get_name().to_string(),
+            data_type: TensorProto_DataType::from_i32(init.get_data_type())
+                .map(|data_type| format!("{:?}", data_type))
+                .unwrap_or_else(|| format!("UNKNOWN({})", init.get_data_type())),
+            shape: init.get_dims().to_vec(),
+            bytes: tensor_bytes(init),
+        })
+        .collect();
+    initializers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+    ModelSizeReport {
+        initializer_bytes: initializers.iter().map(|init| init.bytes).sum(),
+        model_bytes: model.compute_size() as usize,
+        initializers,
+    }
+}
@@ -0,0 +1,115 @@
+//! Runs the ReLU variants through the executor, with their default attributes and with
+//! explicit ones, and PRelu with the slope shapes found in the exported models:
+//!
+//! ```text
+//! cargo test --test activations
+//! ```
+
+use ndarray::{arr1, arr2, arr3, Array, Array1};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+/// Negative values, 0 and positive values.
+fn x() -> Array1<f32> {
+    arr1(&[-3.0, -1.0, -0.5, 0.0, 0.5, 2.0])
+}
+
+#[test]
+fn relu() {
+    op_test!("Relu",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[0.0f32, 0.0, 0.0, 0.0, 0.5, 2.0]));
+}
+
+#[test]
+fn leaky_relu() {
+    // alpha is 0.01 by default.
+    op_test!("LeakyRelu",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[-0.03f32, -0.01, -0.005, 0.0, 0.5, 2.0]));
+    op_test!("LeakyRelu",
+             inputs: [x()],
+             attrs: { alpha: 0.2f32 },
+             expect: arr1(&[-0.6f32, -0.2, -0.1, 0.0, 0.5, 2.0]));
+}
+
+#[test]
+fn elu() {
+    // alpha is 1.0 by default.
+    op_test!("Elu",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[-0.95021293f32, -0.63212056, -0.39346934, 0.0, 0.5, 2.0]));
+    op_test!("Elu",
+             inputs: [x()],
+             attrs: { alpha: 2.0f32 },
+             expect: arr1(&[-1.9004259f32, -1.2642411, -0.78693868, 0.0, 0.5, 2.0]));
+}
+
+#[test]
+fn selu() {
+    // alpha is 1.6732632 and gamma 1.050701 by default.
+    op_test!("Selu",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[-1.6705687f32, -1.1113307, -0.6917582, 0.0, 0.5253505, 2.101402]));
+    op_test!("Selu",
+             inputs: [x()],
+             attrs: { alpha: 2.0f32, gamma: 3.0f32 },
+             expect: arr1(&[-5.7012776f32, -3.7927234, -2.360816, 0.0, 1.5, 6.0]));
+    // Only gamma is given: alpha keeps its default.
+    op_test!("Selu",
+             inputs: [x()],
+             attrs: { gamma: 1.0f32 },
+             expect: arr1(&[-1.5899563f32, -1.0577041, -0.65837776, 0.0, 0.5, 2.0]));
+}
+
+#[test]
+fn prelu_scalar_slope() {
+    op_test!("PRelu",
+             inputs: [x(), arr1(&[0.5f32])],
+             attrs: {},
+             expect: arr1(&[-1.5f32, -0.5, -0.25, 0.0, 0.5, 2.0]));
+}
+
+#[test]
+fn prelu_per_channel_slope() {
+    // A slope of shape [C, 1, 1] against [N, C, H, W], as exported from per-channel PReLU layers.
+    let input = Array::from_shape_fn((2, 3, 2, 2), |(n, c, h, w)| {
+        (n * 12 + c * 4 + h * 2 + w) as f32 - 12.0
+    });
+    let slope = arr3(&[[[0.0f32]], [[0.5]], [[2.0]]]);
+    let expected = Array::from_shape_fn((2, 3, 2, 2), |index| {
+        let value = input[index];
+        if value < 0.0 {
+            value * slope[[index.1, 0, 0]]
+        } else {
+            value
+        }
+    });
+    op_test!("PRelu", inputs: [input, slope], attrs: {}, expect: expected);
+}
+
+#[test]
+fn prelu_full_shape_slope() {
+    op_test!("PRelu",
+             inputs: [
+                 arr2(&[[-1.0f32, -2.0], [3.0, -4.0]]),
+                 arr2(&[[1.0f32, 0.5], [9.0, -1.0]]),
+             ],
+             attrs: {},
+             expect: arr2(&[[-1.0f32, -1.0], [3.0, 4.0]]));
+}
+
+#[test]
+fn prelu_slope_not_broadcastable_to_the_input() {
+    // Broadcasting together would give [2, 2]: only the slope may be stretched.
+    let result = OpTest::new("PRelu")
+        .input(arr2(&[[-1.0f32, 2.0]]))
+        .input(arr2(&[[1.0f32], [2.0]]))
+        .expect(arr2(&[[-1.0f32, 2.0]]))
+        .run();
+    assert!(result.unwrap_err().contains("broadcastable"));
+}
@@ -0,0 +1,256 @@
+//! Counts the memory allocated by the runs of a `Session`:
+//!
+//! ```text
+//! cargo test --test allocations
+//! ```
+//!
+//! The global allocator of the test counts the allocations and the bytes allocated, by every
+//! thread: the tests hold a lock while measuring, so that they don't count each other. A run
+//! after the first one must not copy the weights of the model (decoded once by the plan of the
+//! session), nor the tensors flowing through the forwarding nodes and the kernels overwriting
+//! their operand.
+
+use ndarray::arr1;
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::generators::random_tensor;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// Counts the allocations and the bytes allocated; a reallocation counts as an allocation of the
+/// bytes it adds.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Held by the tests while they measure.
+static MEASURING: Mutex<()> = Mutex::new(());
+
+/// The number of allocations and the bytes allocated by `f`.
+fn allocated(f: impl FnOnce()) -> (usize, usize) {
+    let (allocations, bytes) = (
+        ALLOCATIONS.load(Ordering::SeqCst),
+        BYTES.load(Ordering::SeqCst),
+    );
+    f();
+    (
+        ALLOCATIONS.load(Ordering::SeqCst) - allocations,
+        BYTES.load(Ordering::SeqCst) - bytes,
+    )
+}
+
+fn value_info(name: &str, dims: &[usize]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        dims.iter()
+            .map(|&dim| Dimension::Value(dim as i64))
+            .collect(),
+        None,
+    )
+}
+
+fn node(op_type: &str, inputs: Vec<&str>, output: &str) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        vec![],
+    )
+}
+
+fn model(
+    nodes: Vec<NodeProto>,
+    input: (&str, &[usize]),
+    output: (&str, &[usize]),
+    initializers: Vec<TensorProto>,
+) -> ModelProto {
+    let graph = make_graph(
+        nodes,
+        "allocations",
+        vec![value_info(input.0, input.1)],
+        vec![value_info(output.0, output.1)],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn random_input(shape: &[usize]) -> Tensor {
+    let mut rng = StdRng::seed_from_u64(0);
+    Tensor::from_ndarray(random_tensor(&mut rng, shape)).with_name("x")
+}
+
+/// The allocations and bytes of a run of the session, after a first run.
+fn allocated_by_run(session: &Session, input: &Tensor) -> (usize, usize) {
+    session.run(input.clone()).unwrap();
+    allocated(|| {
+        session.run(input.clone()).unwrap();
+    })
+}
+
+#[test]
+fn weights_are_decoded_once() {
+    let _lock = MEASURING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (inputs, features) = (4096, 256);
+    let mut rng = StdRng::seed_from_u64(242);
+    let weight = random_tensor(&mut rng, &[inputs, features]);
+    let bias = random_tensor(&mut rng, &[features]);
+    let model = model(
+        vec![
+            node("MatMul", vec!["x", "w"], "hidden"),
+            node("Gemm", vec!["hidden", "v", "c"], "y"),
+        ],
+        ("x", &[1, inputs]),
+        ("y", &[1, features]),
+        vec![
+            ndarray_to_tensor_proto::<f32>(weight, "w").unwrap(),
+            ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[features, features]), "v")
+                .unwrap(),
+            ndarray_to_tensor_proto::<f32>(bias, "c").unwrap(),
+        ],
+    );
+    let session = Session::from_model(model).unwrap();
+
+    let weight_bytes = (inputs + features) * features * std::mem::size_of::<f32>();
+    let (allocations, bytes) = allocated_by_run(&session, &random_input(&[1, inputs]));
+    assert!(
+        bytes < weight_bytes / 4,
+        "{} allocations of {} bytes, the weights take {} bytes",
+        allocations,
+        bytes,
+        weight_bytes
+    );
+}
+
+#[test]
+fn forwarded_tensors_are_not_copied() {
+    let _lock = MEASURING.lock().unwrap_or_else(PoisonError::into_inner);
+    let shape = [64, 64, 64];
+    let flat = [64, 4096];
+    let model = model(
+        vec![
+            node("Relu", vec!["x"], "rectified"),
+            node("Identity", vec!["rectified"], "identity"),
+            node("Reshape", vec!["identity", "shape"], "reshaped"),
+            make_node(
+                "Flatten",
+                vec!["reshaped"],
+                vec!["flat"],
+                Some("flat"),
+                None,
+                None,
+                vec![make_attribute("axis", Attribute::<String>::Int(1))],
+            ),
+            node("Relu", vec!["flat"], "y"),
+        ],
+        ("x", &shape),
+        ("y", &flat),
+        vec![ndarray_to_tensor_proto::<i64>(arr1(&[64, 4096]).into_dyn(), "shape").unwrap()],
+    );
+    let session = Session::from_model(model).unwrap();
+
+    // The input is converted to a `TensorProto` and decoded, and the output encoded and
+    // converted back: the tensor itself is never copied by the nodes.
+    let tensor_bytes = shape.iter().product::<usize>() * std::mem::size_of::<f32>();
+    let (allocations, bytes) = allocated_by_run(&session, &random_input(&shape));
+    assert!(
+        bytes < 4 * tensor_bytes,
+        "{} allocations of {} bytes, the tensor takes {} bytes",
+        allocations,
+        bytes,
+        tensor_bytes
+    );
+}
+
+#[test]
+fn kernels_do_not_allocate_per_pixel() {
+    let _lock = MEASURING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (channels, side) = (16, 64);
+    let mut rng = StdRng::seed_from_u64(242);
+    let kernel = random_tensor(&mut rng, &[channels, channels, 3, 3]);
+    let ints = |name: &str, values: &[i64]| {
+        make_attribute(name, Attribute::<String>::Ints(values.to_vec()))
+    };
+    let model = model(
+        vec![
+            make_node(
+                "Conv",
+                vec!["x", "w"],
+                vec!["features"],
+                Some("features"),
+                None,
+                None,
+                vec![ints("kernel_shape", &[3, 3]), ints("pads", &[1, 1, 1, 1])],
+            ),
+            make_node(
+                "MaxPool",
+                vec!["features"],
+                vec!["y"],
+                Some("y"),
+                None,
+                None,
+                vec![ints("kernel_shape", &[2, 2]), ints("strides", &[2, 2])],
+            ),
+        ],
+        ("x", &[1, channels, side, side]),
+        ("y", &[1, channels, side / 2, side / 2]),
+        vec![ndarray_to_tensor_proto::<f32>(kernel, "w").unwrap()],
+    );
+    let session = Session::from_model(model).unwrap();
+
+    // The im2col rows of the convolution and the windows of the pooling are thousands.
+    let (allocations, bytes) =
+        allocated_by_run(&session, &random_input(&[1, channels, side, side]));
+    assert!(
+        allocations < 1000,
+        "{} allocations of {} bytes",
+        allocations,
+        bytes
+    );
+}
@@ -0,0 +1,171 @@
+//! Runs ArgMax and ArgMin through the executor:
+//!
+//! ```text
+//! cargo test --test argmax
+//! ```
+//!
+//! The indices are INT64, whatever the type of the input.
+
+use ndarray::{arr1, arr2, arr3, Array2};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+/// The input of the ONNX backend tests with ties: the first row has two maxima and two minima.
+fn ties() -> Array2<f32> {
+    arr2(&[[2.0, 2.0], [3.0, 10.0]])
+}
+
+#[test]
+fn select_last_index() {
+    for (op_type, select_last_index, expected) in [
+        ("ArgMax", 0i64, [[0i64], [1]]),
+        ("ArgMax", 1, [[1], [1]]),
+        ("ArgMin", 0, [[0], [0]]),
+        ("ArgMin", 1, [[1], [0]]),
+    ] {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(ties())
+            .attribute("axis", 1i64)
+            .attribute("select_last_index", select_last_index)
+            .expect(arr2(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn negative_axis_and_keepdims() {
+    let x = arr3(&[
+        [[1.0f32, 7.0, 3.0], [4.0, 2.0, 6.0]],
+        [[9.0, 0.0, 5.0], [8.0, 8.5, -1.0]],
+    ]);
+    // The default axis is 0.
+    OpTest::new("ArgMax")
+        .opset(13)
+        .input(x.clone())
+        .expect(arr3(&[[[1i64, 0, 1], [1, 1, 0]]]))
+        .check();
+    OpTest::new("ArgMax")
+        .opset(13)
+        .input(x.clone())
+        .attribute("axis", -1i64)
+        .attribute("keepdims", 0i64)
+        .expect(arr2(&[[1i64, 2], [0, 1]]))
+        .check();
+    OpTest::new("ArgMin")
+        .opset(13)
+        .input(x)
+        .attribute("axis", -2i64)
+        .attribute("keepdims", 0i64)
+        .expect(arr2(&[[0i64, 1, 0], [1, 0, 1]]))
+        .check();
+}
+
+#[test]
+fn int64_input() {
+    OpTest::new("ArgMin")
+        .opset(13)
+        .input(arr1(&[5i64, -3, 8, -3]))
+        .attribute("keepdims", 0i64)
+        .attribute("select_last_index", 1i64)
+        .expect(arr1(&[3i64]).into_shape(()).unwrap())
+        .check();
+}
+
+#[test]
+fn indices_feed_a_gather() {
+    // The label of the best score of every row.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "ArgMax",
+            vec!["scores"],
+            vec!["classes"],
+            None,
+            None,
+            None,
+            vec![
+                make_attribute("axis", Attribute::<String>::Int(1)),
+                make_attribute("keepdims", Attribute::<String>::Int(0)),
+            ],
+        ),
+        make_node(
+            "Gather",
+            vec!["labels", "classes"],
+            vec!["predictions"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+    ];
+    let labels = ndarray_to_tensor_proto::<f32>(arr1(&[10.0, 20.0, 30.0]).into_dyn(), "labels");
+    let graph = make_graph(
+        nodes,
+        "argmax_and_gather",
+        vec![value_info("scores")],
+        vec![value_info("predictions"), value_info("classes")],
+        vec![labels.unwrap()],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let scores = arr2(&[[0.1f32, 0.7, 0.2], [0.5, 0.1, 0.4], [0.0, 0.3, 0.9]]);
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(scores.into_dyn(), "scores").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = |name: &str| {
+        outputs
+            .iter()
+            .find(|output| output.get_name() == name)
+            .unwrap()
+            .clone()
+    };
+
+    let classes = output("classes");
+    assert_eq!(classes.get_data_type(), TensorProto_DataType::INT64 as i32);
+    assert_eq!(
+        tensor_proto_to_ndarray::<i64>(&classes).unwrap(),
+        arr1(&[1i64, 0, 2]).into_dyn()
+    );
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output("predictions")).unwrap(),
+        arr1(&[20.0f32, 10.0, 30.0]).into_dyn()
+    );
+}
+
+#[test]
+fn invalid_axis() {
+    let error = OpTest::new("ArgMax")
+        .opset(13)
+        .input(ties())
+        .attribute("axis", 2i64)
+        .expect(arr2(&[[0i64], [1]]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Axis 2 is out of range for a tensor of rank 2"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,194 @@
+//! Runs Add, Sub, Mul, Div and Mod through the executor:
+//!
+//! ```text
+//! cargo test --test arithmetic
+//! ```
+//!
+//! The operands are broadcast together as in NumPy, from the last dimension on.
+
+use ndarray::{arr1, arr2, arr3, Array, Array3, Array4};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn scalar_operands() {
+    let x = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let two = || arr1(&[2.0f32]).into_shape(()).unwrap();
+    for (op_type, expected) in [
+        ("Add", [[3.0f32, 4.0], [5.0, 6.0]]),
+        ("Sub", [[-1.0, 0.0], [1.0, 2.0]]),
+        ("Mul", [[2.0, 4.0], [6.0, 8.0]]),
+        ("Div", [[0.5, 1.0], [1.5, 2.0]]),
+    ] {
+        OpTest::new(op_type)
+            .opset(14)
+            .input(x.clone())
+            .input(two())
+            .expect(arr2(&expected))
+            .check();
+    }
+    // The scalar can come first.
+    OpTest::new("Sub")
+        .opset(14)
+        .input(two())
+        .input(x)
+        .expect(arr2(&[[1.0f32, 0.0], [-1.0, -2.0]]))
+        .check();
+}
+
+#[test]
+fn prepended_dimensions() {
+    // A positional embedding of shape [1, S, D] added to a batch of shape [B, S, D].
+    let batch: Array3<f32> =
+        Array::from_shape_fn((2, 3, 2), |(b, s, d)| (b * 100 + s * 10 + d) as f32);
+    let embedding = Array::from_shape_fn((1, 3, 2), |(_, s, d)| (s * 2 + d) as f32 / 10.0);
+    let expected = Array::from_shape_fn((2, 3, 2), |(b, s, d)| {
+        (b * 100 + s * 10 + d) as f32 + (s * 2 + d) as f32 / 10.0
+    });
+    OpTest::new("Add")
+        .opset(14)
+        .input(batch)
+        .input(embedding)
+        .expect(expected)
+        .check();
+
+    // A bias of shape [C, 1, 1] subtracted from every pixel of an image of shape [N, C, H, W].
+    let image: Array4<f32> = Array::from_elem((1, 2, 2, 2), 10.0);
+    let expected = Array::from_shape_fn((1, 2, 2, 2), |(_, c, _, _)| 10.0 - (c + 1) as f32);
+    OpTest::new("Sub")
+        .opset(14)
+        .input(image)
+        .input(arr3(&[[[1.0f32]], [[2.0]]]))
+        .expect(expected)
+        .check();
+
+    // A row times a column.
+    OpTest::new("Mul")
+        .opset(14)
+        .input(arr1(&[1.0f32, 2.0, 3.0]))
+        .input(arr2(&[[1.0f32], [10.0]]))
+        .expect(arr2(&[[1.0f32, 2.0, 3.0], [10.0, 20.0, 30.0]]))
+        .check();
+}
+
+#[test]
+fn int64_operands() {
+    // Shape arithmetic, e.g. the number of heads times the head size.
+    OpTest::new("Mul")
+        .opset(14)
+        .input(arr1(&[2i64, 64]))
+        .input(arr1(&[12i64]))
+        .expect(arr1(&[24i64, 768]))
+        .check();
+    // The quotient is truncated toward zero.
+    OpTest::new("Div")
+        .opset(14)
+        .input(arr1(&[7i64, -7, 768]))
+        .input(arr1(&[2i64, 2, 12]))
+        .expect(arr1(&[3i64, -3, 64]))
+        .check();
+    let error = OpTest::new("Div")
+        .opset(14)
+        .input(arr1(&[7i64]))
+        .input(arr1(&[0i64]))
+        .expect(arr1(&[0i64]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Integer division by zero"), "{}", error);
+}
+
+#[test]
+fn incompatible_shapes() {
+    for op_type in ["Add", "Sub", "Mul", "Div"] {
+        let error = OpTest::new(op_type)
+            .opset(14)
+            .input(arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]))
+            .input(arr1(&[1.0f32, 2.0]))
+            .expect(arr2(&[[0.0f32; 3]; 2]))
+            .run()
+            .unwrap_err();
+        assert!(
+            error.contains("Shapes [2, 3] and [2] can't be broadcast together"),
+            "{}: {}",
+            op_type,
+            error
+        );
+    }
+}
+
+#[test]
+fn mixed_types() {
+    let error = OpTest::new("Mul")
+        .opset(14)
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[2i64]))
+        .expect(arr1(&[2.0f32, 4.0]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The inputs of a Mul must have the same data type"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn modulo_with_mixed_signs() {
+    let a = || arr1(&[7i64, -7, 7, -7, 6]);
+    let b = || arr1(&[3i64, 3, -3, -3, 3]);
+    // The sign of the divisor, as in Python.
+    OpTest::new("Mod")
+        .opset(13)
+        .input(a())
+        .input(b())
+        .expect(arr1(&[1i64, 2, -2, -1, 0]))
+        .check();
+    // The sign of the dividend, as in C.
+    OpTest::new("Mod")
+        .opset(13)
+        .input(a())
+        .input(b())
+        .attribute("fmod", 1i64)
+        .expect(arr1(&[1i64, -1, 1, -1, 0]))
+        .check();
+
+    let a = || arr1(&[5.5f32, -5.5, 5.5, -5.5]);
+    let b = || arr1(&[2.0f32, 2.0, -2.0, -2.0]);
+    OpTest::new("Mod")
+        .opset(13)
+        .input(a())
+        .input(b())
+        .attribute("fmod", 1i64)
+        .expect(arr1(&[1.5f32, -1.5, 1.5, -1.5]))
+        .check();
+    OpTest::new("Mod")
+        .opset(13)
+        .input(a())
+        .input(b())
+        .expect(arr1(&[1.5f32, 0.5, -0.5, -1.5]))
+        .check();
+}
+
+#[test]
+fn modulo_by_zero() {
+    for fmod in [0i64, 1] {
+        OpTest::new("Mod")
+            .opset(13)
+            .input(arr1(&[3.0f32, -1.0]))
+            .input(arr1(&[0.0f32]))
+            .attribute("fmod", fmod)
+            .expect(arr1(&[f32::NAN, f32::NAN]))
+            .check();
+    }
+    let error = OpTest::new("Mod")
+        .opset(13)
+        .input(arr1(&[3i64]))
+        .input(arr1(&[0i64]))
+        .expect(arr1(&[0i64]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Integer division by zero in a Mod"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,132 @@
+//! Runs a shared session from concurrent tasks:
+//!
+//! ```text
+//! cargo test --features tokio --test async_run
+//! ```
+#![cfg(feature = "tokio")]
+
+use ndarray::{arr1, ArrayD};
+use onnx_rustime_lib::backend::helper::{make_graph, make_model, make_node, OnnxError};
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, ValueInfoProto,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Number of nodes of the chains run by the tests.
+const CHAIN_LENGTH: usize = 50;
+
+/// Time spent by every `Slow` node.
+const SLOW_NODE_TIME: Duration = Duration::from_millis(20);
+
+/// Number of `Slow` nodes run so far.
+static SLOW_NODES_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// Kernel of the `Slow` custom operation: the identity, after a pause.
+fn slow(operands: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    std::thread::sleep(SLOW_NODE_TIME);
+    SLOW_NODES_RUN.fetch_add(1, Ordering::SeqCst);
+
+    let mut output = operands[0].clone();
+    output.set_name(node.get_output()[0].clone());
+    Ok(output)
+}
+
+/// A model made of a chain of `CHAIN_LENGTH` nodes of the given type, from `x` to `y`.
+fn chain_model(op_type: &str) -> ModelProto {
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let name = |index: usize| match index {
+        0 => "x".to_string(),
+        CHAIN_LENGTH => "y".to_string(),
+        _ => format!("t{}", index),
+    };
+
+    let nodes = (0..CHAIN_LENGTH)
+        .map(|index| {
+            make_node(
+                op_type.to_string(),
+                vec![name(index)],
+                vec![name(index + 1)],
+                None,
+                None,
+                None,
+                vec![],
+            )
+        })
+        .collect();
+    let graph = make_graph(
+        nodes,
+        "chain",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn input(values: Vec<f32>) -> Tensor {
+    Tensor::from_ndarray(arr1(&values).into_dyn()).with_name("x")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_runs_share_a_session() {
+    let session = Arc::new(Session::from_model(chain_model("Relu")).unwrap());
+
+    let first = tokio::spawn({
+        let session = Arc::clone(&session);
+        async move { session.run_async(vec![input(vec![-1.0, 2.0])]).await }
+    });
+    let second = session.run_async(vec![input(vec![3.0, -4.0, 5.0])]);
+    let (first, second) = tokio::join!(first, second);
+
+    let output = |outputs: Vec<Tensor>| outputs[0].to_ndarray::<f32>().unwrap();
+    assert_eq!(
+        output(first.unwrap().unwrap()),
+        arr1(&[0.0, 2.0]).into_dyn()
+    );
+    assert_eq!(output(second.unwrap()), arr1(&[3.0, 0.0, 5.0]).into_dyn());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropping_the_future_cancels_the_run() {
+    let session = Session::builder()
+        .model(chain_model("Slow"))
+        .register_op("Slow", slow)
+        .build()
+        .unwrap();
+
+    let run = session.run_async(vec![input(vec![1.0])]);
+    let timeout = tokio::time::timeout(SLOW_NODE_TIME * 5, run).await;
+    assert!(timeout.is_err(), "the run should still be pending");
+
+    // The node running when the future was dropped completes, then the run stops.
+    tokio::time::sleep(SLOW_NODE_TIME * 10).await;
+    let nodes_run = SLOW_NODES_RUN.load(Ordering::SeqCst);
+    tokio::time::sleep(SLOW_NODE_TIME * 10).await;
+    assert_eq!(SLOW_NODES_RUN.load(Ordering::SeqCst), nodes_run);
+    assert!(nodes_run < CHAIN_LENGTH, "{} nodes were run", nodes_run);
+
+    // The session is still usable.
+    let outputs = session.run_async(vec![input(vec![1.0])]).await.unwrap();
+    assert_eq!(
+        outputs[0].to_ndarray::<f32>().unwrap(),
+        ArrayD::from_elem(vec![1], 1.0)
+    );
+}
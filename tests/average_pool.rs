@@ -0,0 +1,88 @@
+//! Runs padded average poolings through the executor:
+//!
+//! ```text
+//! cargo test --test average_pool
+//! ```
+//!
+//! The input is a 1x1x5x5 tensor holding 0, 1, ..., 24.
+
+use ndarray::{Array, Array4};
+use onnx_rustime_lib::op_test;
+
+fn input() -> Array4<f32> {
+    Array::range(0.0, 25.0, 1.0)
+        .into_shape((1, 1, 5, 5))
+        .unwrap()
+}
+
+#[test]
+fn padding_excluded_from_the_average() {
+    // The corner windows average 4 input values, the edge ones 6.
+    let expected = Array::from_shape_vec(
+        (1, 1, 5, 5),
+        vec![
+            3.0, 3.5, 4.5, 5.5, 6.0, //
+            5.5, 6.0, 7.0, 8.0, 8.5, //
+            10.5, 11.0, 12.0, 13.0, 13.5, //
+            15.5, 16.0, 17.0, 18.0, 18.5, //
+            18.0, 18.5, 19.5, 20.5, 21.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("AveragePool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 pads: vec![1i64, 1, 1, 1],
+                 count_include_pad: 0i64,
+             },
+             expect: expected);
+}
+
+#[test]
+fn padding_included_in_the_average() {
+    // Every window sums its input values and divides by 9.
+    let sums = vec![
+        12.0, 21.0, 27.0, 33.0, 24.0, //
+        33.0, 54.0, 63.0, 72.0, 51.0, //
+        63.0, 99.0, 108.0, 117.0, 81.0, //
+        93.0, 144.0, 153.0, 162.0, 111.0, //
+        72.0, 111.0, 117.0, 123.0, 84.0,
+    ];
+    let expected = Array::from_shape_vec((1, 1, 5, 5), sums)
+        .unwrap()
+        .mapv(|sum: f32| sum / 9.0);
+
+    op_test!("AveragePool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 pads: vec![1i64, 1, 1, 1],
+                 count_include_pad: 1i64,
+             },
+             expect: expected);
+}
+
+#[test]
+fn ceil_mode_clips_the_last_windows() {
+    // 2x2 windows with strides 2: the third window of each axis only covers the last pixel.
+    let expected = Array::from_shape_vec(
+        (1, 1, 3, 3),
+        vec![
+            3.0, 5.0, 6.5, //
+            13.0, 15.0, 16.5, //
+            20.5, 22.5, 24.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("AveragePool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![2i64, 2],
+                 strides: vec![2i64, 2],
+                 ceil_mode: 1i64,
+             },
+             expect: expected);
+}
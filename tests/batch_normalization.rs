@@ -0,0 +1,73 @@
+//! Runs BatchNormalization on inputs of other ranks than 4 through the executor:
+//!
+//! ```text
+//! cargo test --test batch_normalization
+//! ```
+//!
+//! Every channel c is normalized with (x - mean[c]) / sqrt(var[c] + epsilon) * scale[c] + B[c].
+
+use ndarray::{arr1, arr2, Array, Array1, Array2, IxDyn};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn batch_of_vectors() {
+    // The default epsilon of 1e-5 barely moves the standard deviations of 2, 1 and 0.5.
+    let expected = arr2(&[
+        [-0.4999994f32, 0.999990, 4.99992],
+        [0.9999988, -2.99999, 1.0],
+    ]);
+
+    op_test!("BatchNormalization",
+             inputs: [
+                 arr2(&[[0.0f32, 1.0, 2.0], [3.0, -1.0, -2.0]]),
+                 arr1(&[1.0f32, 2.0, 0.5]),
+                 arr1(&[0.0f32, -1.0, 1.0]),
+                 arr1(&[1.0f32, 0.0, -2.0]),
+                 arr1(&[4.0f32, 1.0, 0.25]),
+             ],
+             attrs: {},
+             expect: expected);
+}
+
+#[test]
+fn volumes_with_epsilon() {
+    // With an epsilon of 0.01, the standard deviations are 2 and 0.5: the first channel
+    // (0, ..., 7) becomes x - 2, the second one (8, ..., 15) x + 1.
+    let input = Array::range(0.0f32, 16.0, 1.0)
+        .into_shape(IxDyn(&[1, 2, 2, 2, 2]))
+        .unwrap();
+    let expected = Array::range(-2.0f32, 6.0, 1.0)
+        .into_iter()
+        .chain(Array::range(9.0f32, 17.0, 1.0))
+        .collect::<Array1<f32>>()
+        .into_shape(IxDyn(&[1, 2, 2, 2, 2]))
+        .unwrap();
+
+    op_test!("BatchNormalization",
+             inputs: [
+                 input,
+                 arr1(&[2.0f32, 0.5]),
+                 arr1(&[1.0f32, 0.0]),
+                 arr1(&[3.0f32, -1.0]),
+                 arr1(&[3.99f32, 0.24]),
+             ],
+             attrs: { epsilon: 0.01f32 },
+             expect: expected);
+}
+
+#[test]
+fn parameters_not_matching_the_channels() {
+    // 3 channels, but 2 means.
+    let result = OpTest::new("BatchNormalization")
+        .input(Array2::<f32>::zeros((2, 3)))
+        .input(arr1(&[1.0f32, 1.0, 1.0]))
+        .input(arr1(&[0.0f32, 0.0, 0.0]))
+        .input(arr1(&[0.0f32, 0.0]))
+        .input(arr1(&[1.0f32, 1.0, 1.0]))
+        .expect(Array2::<f32>::zeros((2, 3)))
+        .run();
+
+    let message = result.unwrap_err();
+    assert!(message.contains("one value per channel"), "{}", message);
+}
@@ -0,0 +1,134 @@
+//! Runs Cast through the executor, between the numeric element types and BOOL:
+//!
+//! ```text
+//! cargo test --test cast
+//! ```
+//!
+//! The `to` attribute is the ONNX data type of the output.
+
+use ndarray::arr1;
+use onnx_rustime_lib::backend::helper::{make_attribute, make_tensor, Attribute, TensorValue};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::testing::{single_node_model, OpTest, TestTensor};
+
+fn cast(input: impl Into<TestTensor>, to: TensorProto_DataType) -> OpTest {
+    OpTest::new("Cast")
+        .opset(13)
+        .input(input)
+        .attribute("to", to as i64)
+}
+
+#[test]
+fn float_to_integers_truncates_toward_zero() {
+    let x = arr1(&[-2.7f32, -0.5, 0.5, 2.9, 7.0]);
+    cast(x.clone(), TensorProto_DataType::INT64)
+        .expect(arr1(&[-2i64, 0, 0, 2, 7]))
+        .check();
+    cast(x, TensorProto_DataType::INT32)
+        .expect(arr1(&[-2i32, 0, 0, 2, 7]))
+        .check();
+}
+
+#[test]
+fn float_to_integers_saturates() {
+    cast(arr1(&[-5.0f32, 300.0, 1e20]), TensorProto_DataType::UINT8)
+        .expect(arr1(&[0u8, 255, 255]))
+        .check();
+    cast(arr1(&[-1e20f32, 1e20]), TensorProto_DataType::INT8)
+        .expect(arr1(&[-128i8, 127]))
+        .check();
+}
+
+#[test]
+fn integers_to_narrower_integers_wrap() {
+    cast(arr1(&[300i64, -129, 5]), TensorProto_DataType::INT8)
+        .expect(arr1(&[44i8, 127, 5]))
+        .check();
+    cast(arr1(&[-1i32, 256]), TensorProto_DataType::UINT8)
+        .expect(arr1(&[255u8, 0]))
+        .check();
+}
+
+#[test]
+fn int64_to_float_precision() {
+    // FLOAT has 24 bits of mantissa: 2^24 + 1 is rounded to 2^24.
+    let x = arr1(&[16_777_217i64, -3, 1 << 40]);
+    cast(x, TensorProto_DataType::FLOAT)
+        .expect(arr1(&[16_777_216.0f32, -3.0, 1_099_511_627_776.0]))
+        .check();
+}
+
+#[test]
+fn doubles() {
+    // DOUBLE tensors are bound directly, the operator tests building FLOAT tensors from floats.
+    let run = |input: TensorProto, to: TensorProto_DataType| {
+        let model = single_node_model(
+            "Cast",
+            &["x"],
+            &["y"],
+            vec![make_attribute("to", Attribute::<String>::Int(to as i64))],
+        );
+        let mut outputs = run_with_inputs(&model, vec![input], &RunOptions::default()).unwrap();
+        outputs.remove(0)
+    };
+
+    let x = make_tensor(
+        Some("x"),
+        vec![3],
+        TensorValue::Double(vec![0.1, -2.5, 1e300]),
+    );
+    let y = run(x, TensorProto_DataType::FLOAT);
+    assert_eq!(y.get_data_type(), TensorProto_DataType::FLOAT as i32);
+    assert_eq!(y.get_float_data(), &[0.1f32, -2.5, f32::INFINITY]);
+
+    let x = make_tensor(Some("x"), vec![2], TensorValue::Float(vec![1.5, -7.25]));
+    let y = run(x, TensorProto_DataType::DOUBLE);
+    assert_eq!(y.get_data_type(), TensorProto_DataType::DOUBLE as i32);
+    assert_eq!(y.get_double_data(), &[1.5, -7.25]);
+
+    let x = make_tensor(Some("x"), vec![2], TensorValue::Int64(vec![16_777_217, -3]));
+    let y = run(x, TensorProto_DataType::DOUBLE);
+    assert_eq!(y.get_double_data(), &[16_777_217.0, -3.0]);
+}
+
+#[test]
+fn bool_round_trips() {
+    let flags = arr1(&[true, false, true]);
+    cast(flags.clone(), TensorProto_DataType::FLOAT)
+        .expect(arr1(&[1.0f32, 0.0, 1.0]))
+        .check();
+    cast(flags.clone(), TensorProto_DataType::INT64)
+        .expect(arr1(&[1i64, 0, 1]))
+        .check();
+    cast(flags.clone(), TensorProto_DataType::BOOL)
+        .expect(flags)
+        .check();
+
+    // Every value other than 0 is true, NaN included.
+    cast(
+        arr1(&[0.0f32, -0.0, 0.25, f32::NAN]),
+        TensorProto_DataType::BOOL,
+    )
+    .expect(arr1(&[false, false, true, true]))
+    .check();
+    cast(arr1(&[0i64, -4, 1]), TensorProto_DataType::BOOL)
+        .expect(arr1(&[false, true, true]))
+        .check();
+}
+
+#[test]
+fn unsupported_type() {
+    let error = cast(arr1(&[1.0f32]), TensorProto_DataType::STRING)
+        .expect(arr1(&[1.0f32]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Cast to data type 8 is not supported"),
+        "{}",
+        error
+    );
+}
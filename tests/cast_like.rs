@@ -0,0 +1,61 @@
+//! Runs CastLike through the executor:
+//!
+//! ```text
+//! cargo test --test cast_like
+//! ```
+//!
+//! The first input is converted like a Cast to the element type of the second input, whose values
+//! are ignored.
+
+use ndarray::{arr0, arr1, arr2};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn float_like_int64() {
+    OpTest::new("CastLike")
+        .opset(15)
+        .input(arr2(&[[-2.7f32, -0.5, 0.5], [2.9, 7.0, 300.0]]))
+        .input(arr1(&[0i64]))
+        .expect(arr2(&[[-2i64, 0, 0], [2, 7, 300]]))
+        .check();
+}
+
+#[test]
+fn int64_like_float() {
+    OpTest::new("CastLike")
+        .opset(15)
+        .input(arr1(&[-3i64, 0, 16_777_217]))
+        .input(arr0(1.5f32))
+        .expect(arr1(&[-3.0f32, 0.0, 16_777_216.0]))
+        .check();
+}
+
+#[test]
+fn same_type_is_identity() {
+    let x = arr2(&[[1.5f32, -0.25], [f32::MAX, f32::MIN_POSITIVE]]);
+    OpTest::new("CastLike")
+        .opset(15)
+        .input(x.clone())
+        .input(arr2(&[[0.0f32; 3]; 4]))
+        .expect(x)
+        .check();
+
+    let flags = arr1(&[true, false, true]);
+    OpTest::new("CastLike")
+        .opset(19)
+        .input(flags.clone())
+        .input(arr0(false))
+        .expect(flags)
+        .check();
+}
+
+#[test]
+fn missing_target_type() {
+    let error = OpTest::new("CastLike")
+        .opset(15)
+        .input(arr1(&[1.0f32]))
+        .expect(arr1(&[1.0f32]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("target_type"), "{}", error);
+}
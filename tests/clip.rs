@@ -0,0 +1,115 @@
+//! Runs Clip through the executor, with the bounds of both operator sets:
+//!
+//! ```text
+//! cargo test --test clip
+//! ```
+//!
+//! Before opset 11 the bounds are the `min` and `max` attributes, since opset 11 optional scalar
+//! inputs, given by initializers as in the ReLU6 of MobileNet. A missing bound is unbounded.
+
+use ndarray::{arr0, arr1, s, Array1};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+fn x() -> Array1<f32> {
+    arr1(&[-3.0, -0.5, 0.0, 2.5, 6.0, 7.5, f32::NAN])
+}
+
+#[test]
+fn attribute_bounds() {
+    OpTest::new("Clip")
+        .opset(6)
+        .input(x())
+        .attribute("min", 0.0f32)
+        .attribute("max", 6.0f32)
+        .expect(arr1(&[0.0f32, 0.0, 0.0, 2.5, 6.0, 6.0, f32::NAN]))
+        .check();
+    OpTest::new("Clip")
+        .opset(6)
+        .input(x())
+        .attribute("max", 1.0f32)
+        .expect(arr1(&[-3.0f32, -0.5, 0.0, 1.0, 1.0, 1.0, f32::NAN]))
+        .check();
+}
+
+#[test]
+fn input_bounds() {
+    OpTest::new("Clip")
+        .opset(11)
+        .input(x())
+        .input(arr0(-1.0f32))
+        .input(arr0(2.0f32))
+        .expect(arr1(&[-1.0f32, -0.5, 0.0, 2.0, 2.0, 2.0, f32::NAN]))
+        .check();
+    // A min greater than max gives max.
+    OpTest::new("Clip")
+        .opset(11)
+        .input(x())
+        .input(arr0(3.0f32))
+        .input(arr0(1.0f32))
+        .expect(arr1(&[1.0f32, 1.0, 1.0, 1.0, 1.0, 1.0, f32::NAN]))
+        .check();
+}
+
+#[test]
+fn relu6_with_initializer_bounds() {
+    let mut model = single_node_model("Clip", &["x", "min", "max"], &["y"], vec![]);
+    let graph = model.mut_graph();
+    graph.mut_input().retain(|input| input.get_name() == "x");
+    graph
+        .mut_initializer()
+        .push(ndarray_to_tensor_proto::<f32>(arr0(0.0f32).into_dyn(), "min").unwrap());
+    graph
+        .mut_initializer()
+        .push(ndarray_to_tensor_proto::<f32>(arr0(6.0f32).into_dyn(), "max").unwrap());
+
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap();
+    assert_eq!(
+        output.slice(s![..6]),
+        arr1(&[0.0f32, 0.0, 0.0, 2.5, 6.0, 6.0])
+    );
+    assert!(output[6].is_nan());
+}
+
+#[test]
+fn only_one_bound() {
+    // Only min: the inputs are x and min.
+    OpTest::new("Clip")
+        .opset(13)
+        .input(x())
+        .input(arr0(0.0f32))
+        .expect(arr1(&[0.0f32, 0.0, 0.0, 2.5, 6.0, 7.5, f32::NAN]))
+        .check();
+
+    // Only max: the inputs are x, "" and max.
+    let mut model = single_node_model("Clip", &["x", "", "max"], &["y"], vec![]);
+    model
+        .mut_graph()
+        .mut_input()
+        .retain(|input| !input.get_name().is_empty());
+    let inputs = vec![
+        ndarray_to_tensor_proto::<f32>(x().into_dyn(), "x").unwrap(),
+        ndarray_to_tensor_proto::<f32>(arr0(1.0f32).into_dyn(), "max").unwrap(),
+    ];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap();
+    assert_eq!(
+        output.slice(s![..6]),
+        arr1(&[-3.0f32, -0.5, 0.0, 1.0, 1.0, 1.0])
+    );
+}
+
+#[test]
+fn bound_not_a_single_value() {
+    let result = OpTest::new("Clip")
+        .opset(11)
+        .input(x())
+        .input(arr1(&[0.0f32, 1.0]))
+        .expect(x())
+        .run();
+    assert!(result.unwrap_err().contains("single value"));
+}
@@ -0,0 +1,153 @@
+//! Helpers shared by the integration tests:
+//!
+//! - the proptest strategies of the property tests: shapes, shapes that can (or can't) be
+//!   broadcast together, and tensors of a given shape. Proptest shrinks a failing case to the
+//!   smallest shapes and values still failing;
+//! - the check of the bundled models. The tests reading them are marked
+//!   `#[ignore = "needs the bundled models (git lfs pull)"]`, and run with
+//!   `cargo test -- --ignored` once the models are pulled.
+//!
+//! A test file uses them with `mod common;`; each one uses only some of them.
+#![allow(dead_code)]
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::testing::generators::{MAX_DIM, MAX_RANK};
+use proptest::prelude::*;
+use std::fs;
+use std::path::Path;
+
+/// Whether the file is a Git LFS pointer, left in place of the model when LFS isn't installed.
+pub fn is_lfs_pointer(path: impl AsRef<Path>) -> bool {
+    fs::read(path)
+        .map(|bytes| bytes.starts_with(b"version https://git-lfs"))
+        .unwrap_or(false)
+}
+
+/// Panics if a bundled model hasn't been pulled, so that an ignored test run without the models
+/// fails instead of passing without checking anything.
+#[track_caller]
+pub fn require_model(path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    assert!(
+        !is_lfs_pointer(path),
+        "{} is a Git LFS pointer: run `git lfs pull` first",
+        path.display()
+    );
+}
+
+/// A shape with rank in `min_rank..=max_rank` and dimensions in `1..=max_dim`.
+pub fn shape(
+    min_rank: usize,
+    max_rank: usize,
+    max_dim: usize,
+) -> impl Strategy<Value = Vec<usize>> {
+    prop::collection::vec(1..=max_dim, min_rank..=max_rank)
+}
+
+/// `count` shapes that can be broadcast together.
+///
+/// Every shape is derived from a common output shape, dropping some leading dimensions and
+/// replacing others with 1, so that every broadcasting case (different ranks, 1 against a
+/// dimension, scalars) shows up.
+pub fn broadcastable_shapes(count: usize) -> impl Strategy<Value = Vec<Vec<usize>>> {
+    shape(0, MAX_RANK, MAX_DIM).prop_flat_map(move |output| {
+        let rank = output.len();
+        prop::collection::vec(derived_shape(output, 0..=rank), count)
+    })
+}
+
+/// Two shapes that can't be broadcast together: two aligned dimensions differ and are both
+/// greater than 1.
+pub fn incompatible_shapes() -> impl Strategy<Value = (Vec<usize>, Vec<usize>)> {
+    shape(1, MAX_RANK, MAX_DIM)
+        .prop_flat_map(|output| {
+            let rank = output.len();
+            // Both shapes keep the last dimension at least.
+            let a = derived_shape(output.clone(), 0..=rank - 1);
+            let b = derived_shape(output, 0..=rank - 1);
+            (a, b)
+        })
+        .prop_flat_map(|(a, b)| {
+            let common_rank = a.len().min(b.len());
+            (
+                Just(a),
+                Just(b),
+                // Dimensions are aligned starting from the last one.
+                0..common_rank,
+                2..=MAX_DIM,
+                1..MAX_DIM - 1,
+            )
+        })
+        .prop_map(|(mut a, mut b, k, x, offset)| {
+            let (index_a, index_b) = (a.len() - 1 - k, b.len() - 1 - k);
+            a[index_a] = x;
+            b[index_b] = (x - 2 + offset) % (MAX_DIM - 1) + 2;
+            (a, b)
+        })
+}
+
+/// A tensor of the given shape with values in `[-10, 10)`.
+pub fn tensor(shape: Vec<usize>) -> impl Strategy<Value = ArrayD<f32>> {
+    let elements = shape.iter().product::<usize>();
+    prop::collection::vec(-10.0f32..10.0, elements)
+        .prop_map(move |values| ArrayD::from_shape_vec(IxDyn(&shape), values).unwrap())
+}
+
+/// A tensor of booleans of the given shape, e.g. the condition of Where.
+pub fn bool_tensor(shape: Vec<usize>) -> impl Strategy<Value = ArrayD<bool>> {
+    let elements = shape.iter().product::<usize>();
+    prop::collection::vec(any::<bool>(), elements)
+        .prop_map(move |values| ArrayD::from_shape_vec(IxDyn(&shape), values).unwrap())
+}
+
+/// The output shape of the broadcast of the given shapes, or `None` if they can't be broadcast
+/// together.
+pub fn broadcast_shape(shapes: &[&[usize]]) -> Option<Vec<usize>> {
+    let rank = shapes.iter().map(|shape| shape.len()).max().unwrap_or(0);
+    let mut output = vec![1; rank];
+    for shape in shapes {
+        for (dim, &size) in output[rank - shape.len()..].iter_mut().zip(shape.iter()) {
+            match (*dim, size) {
+                (x, y) if x == y || y == 1 => {}
+                (1, y) => *dim = y,
+                _ => return None,
+            }
+        }
+    }
+    Some(output)
+}
+
+/// Slow reference of broadcasting: copies every element of `tensor` to the positions of the
+/// output shape it is broadcast to, repeating the dimensions of size 1.
+pub fn materialize<T: Clone>(tensor: &ArrayD<T>, shape: &[usize]) -> ArrayD<T> {
+    let offset = shape.len() - tensor.ndim();
+    ArrayD::from_shape_fn(IxDyn(shape), |index| {
+        let source: Vec<usize> = tensor
+            .shape()
+            .iter()
+            .enumerate()
+            .map(|(axis, &dim)| if dim == 1 { 0 } else { index[offset + axis] })
+            .collect();
+        tensor[source.as_slice()].clone()
+    })
+}
+
+/// A shape derived from `output`, dropping a number of leading dimensions in `dropped` and
+/// replacing others with 1.
+fn derived_shape(
+    output: Vec<usize>,
+    dropped: impl Strategy<Value = usize>,
+) -> impl Strategy<Value = Vec<usize>> {
+    let rank = output.len();
+    (
+        dropped,
+        prop::collection::vec(prop::bool::weighted(0.3), rank),
+    )
+        .prop_map(move |(dropped, ones)| {
+            output[dropped..]
+                .iter()
+                .zip(&ones[dropped..])
+                .map(|(&dim, &one)| if one { 1 } else { dim })
+                .collect()
+        })
+}
@@ -0,0 +1,180 @@
+//! Runs the comparison operations through the executor:
+//!
+//! ```text
+//! cargo test --test compare
+//! ```
+//!
+//! The outputs are BOOL tensors, broadcast like the operands of Add.
+
+use ndarray::{arr1, arr2};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn float_against_a_scalar() {
+    let x = arr2(&[[0.5f32, 1.0], [1.5, f32::NAN]]);
+    for (op_type, expected) in [
+        ("Equal", [[false, true], [false, false]]),
+        ("Greater", [[false, false], [true, false]]),
+        ("GreaterOrEqual", [[false, true], [true, false]]),
+        ("Less", [[true, false], [false, false]]),
+        ("LessOrEqual", [[true, true], [false, false]]),
+    ] {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(x.clone())
+            .input(arr1(&[1.0f32]).into_shape(()).unwrap())
+            .expect(arr2(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn int64_shape_against_a_constant() {
+    OpTest::new("Equal")
+        .opset(13)
+        .input(arr1(&[1i64, 3, 224, 224]))
+        .input(arr1(&[1i64, 3, 224, 112]))
+        .expect(arr1(&[true, true, true, false]))
+        .check();
+    // A row against a column.
+    OpTest::new("Less")
+        .opset(13)
+        .input(arr1(&[0i64, 1, 2]))
+        .input(arr2(&[[1i64], [2]]))
+        .expect(arr2(&[[true, false, false], [true, true, false]]))
+        .check();
+}
+
+#[test]
+fn bool_inputs() {
+    OpTest::new("Equal")
+        .opset(13)
+        .input(arr1(&[true, false, true]))
+        .input(arr1(&[true]))
+        .expect(arr1(&[true, false, true]))
+        .check();
+}
+
+#[test]
+fn mismatched_inputs() {
+    let error = OpTest::new("Greater")
+        .opset(13)
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[1i64, 2]))
+        .expect(arr1(&[false, false]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The inputs of a Greater must have the same data type"),
+        "{}",
+        error
+    );
+    let error = OpTest::new("Less")
+        .opset(13)
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[1.0f32, 2.0, 3.0]))
+        .expect(arr1(&[false, false]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("can't be broadcast together"), "{}", error);
+}
+
+#[test]
+fn comparisons_feed_where_and_cast() {
+    // Clamps the negative values to 0 with Where, and counts the positive ones with a Cast.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "Greater",
+            vec!["x", "zero"],
+            vec!["positive"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "Where",
+            vec!["positive", "x", "zero"],
+            vec!["clamped"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "Cast",
+            vec!["positive"],
+            vec!["indicator"],
+            None,
+            None,
+            None,
+            vec![make_attribute(
+                "to",
+                Attribute::<String>::Int(TensorProto_DataType::FLOAT as i64),
+            )],
+        ),
+    ];
+    let zero = ndarray_to_tensor_proto::<f32>(arr1(&[0.0]).into_dyn(), "zero");
+    let graph = make_graph(
+        nodes,
+        "compare_where_cast",
+        vec![value_info("x")],
+        vec![
+            value_info("positive"),
+            value_info("clamped"),
+            value_info("indicator"),
+        ],
+        vec![zero.unwrap()],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let x = arr1(&[-1.5f32, 2.0, 0.0, 3.5]);
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x.into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = |name: &str| {
+        outputs
+            .iter()
+            .find(|output| output.get_name() == name)
+            .unwrap()
+            .clone()
+    };
+
+    let positive = output("positive");
+    assert_eq!(positive.get_data_type(), TensorProto_DataType::BOOL as i32);
+    assert_eq!(
+        tensor_proto_to_ndarray::<bool>(&positive).unwrap(),
+        arr1(&[false, true, false, true]).into_dyn()
+    );
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output("clamped")).unwrap(),
+        arr1(&[0.0f32, 2.0, 0.0, 3.5]).into_dyn()
+    );
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output("indicator")).unwrap(),
+        arr1(&[0.0f32, 1.0, 0.0, 1.0]).into_dyn()
+    );
+}
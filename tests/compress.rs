@@ -0,0 +1,85 @@
+//! Runs Compress through the executor:
+//!
+//! ```text
+//! cargo test --test compress
+//! ```
+//!
+//! The cases are the examples of the ONNX operator documentation.
+
+use ndarray::{arr1, arr2, Array2};
+use onnx_rustime_lib::testing::OpTest;
+
+fn input() -> Array2<f32> {
+    arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]])
+}
+
+#[test]
+fn rows() {
+    OpTest::new("Compress")
+        .opset(11)
+        .input(input())
+        .input(arr1(&[false, true, true]))
+        .attribute("axis", 0i64)
+        .expect(arr2(&[[3.0f32, 4.0], [5.0, 6.0]]))
+        .check();
+}
+
+#[test]
+fn columns() {
+    for axis in [1i64, -1] {
+        OpTest::new("Compress")
+            .opset(11)
+            .input(input())
+            .input(arr1(&[false, true]))
+            .attribute("axis", axis)
+            .expect(arr2(&[[2.0f32], [4.0], [6.0]]))
+            .check();
+    }
+}
+
+#[test]
+fn without_axis() {
+    // The flattened input, with a condition shorter than its 6 elements.
+    OpTest::new("Compress")
+        .opset(11)
+        .input(input())
+        .input(arr1(&[false, true, false, false, true]))
+        .expect(arr1(&[2.0f32, 5.0]))
+        .check();
+}
+
+#[test]
+fn shorter_condition() {
+    // The last row is past the end of the condition.
+    OpTest::new("Compress")
+        .opset(11)
+        .input(arr2(&[[1i64, 2], [3, 4], [5, 6]]))
+        .input(arr1(&[true, true]))
+        .attribute("axis", 0i64)
+        .expect(arr2(&[[1i64, 2], [3, 4]]))
+        .check();
+}
+
+#[test]
+fn all_false() {
+    OpTest::new("Compress")
+        .opset(11)
+        .input(input())
+        .input(arr1(&[false, false, false]))
+        .attribute("axis", 0i64)
+        .expect(Array2::<f32>::zeros((0, 2)))
+        .check();
+}
+
+#[test]
+fn longer_condition() {
+    let error = OpTest::new("Compress")
+        .opset(11)
+        .input(input())
+        .input(arr1(&[true, false, true]))
+        .attribute("axis", 1i64)
+        .expect(Array2::<f32>::zeros((3, 2)))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("not longer than the 2 slices"), "{}", error);
+}
@@ -0,0 +1,123 @@
+//! Runs Concat through the executor, with any number of inputs and negative axes:
+//!
+//! ```text
+//! cargo test --test concat
+//! ```
+//!
+//! The inputs after the first one are bound as initializers by `op_test!`, so every test also
+//! concatenates constants of the graph with the tensor it computes.
+
+use ndarray::{arr2, arr3, concatenate, Array, Array2, ArrayD, Axis, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+fn a() -> Array2<f32> {
+    arr2(&[[1.0, 2.0], [3.0, 4.0]])
+}
+
+fn b() -> Array2<f32> {
+    arr2(&[[5.0], [6.0]])
+}
+
+#[test]
+fn two_inputs() {
+    op_test!("Concat",
+             inputs: [a(), b()],
+             attrs: { axis: 1i64 },
+             expect: arr2(&[[1.0f32, 2.0, 5.0], [3.0, 4.0, 6.0]]));
+    op_test!("Concat",
+             inputs: [a(), arr2(&[[7.0f32, 8.0]])],
+             attrs: { axis: 0i64 },
+             expect: arr2(&[[1.0f32, 2.0], [3.0, 4.0], [7.0, 8.0]]));
+}
+
+#[test]
+fn three_inputs_along_the_last_axis() {
+    let x = arr3(&[[[1.0f32], [2.0]]]);
+    let y = arr3(&[[[3.0f32, 4.0], [5.0, 6.0]]]);
+    let z = arr3(&[[[7.0f32, 8.0, 9.0], [10.0, 11.0, 12.0]]]);
+    let expected = arr3(&[[
+        [1.0f32, 3.0, 4.0, 7.0, 8.0, 9.0],
+        [2.0, 5.0, 6.0, 10.0, 11.0, 12.0],
+    ]]);
+    op_test!("Concat", inputs: [x.clone(), y.clone(), z.clone()], attrs: { axis: -1i64 },
+             expect: expected.clone());
+    op_test!("Concat", inputs: [x, y, z], attrs: { axis: 2i64 }, expect: expected);
+}
+
+#[test]
+fn five_inputs() {
+    let inputs: Vec<ArrayD<f32>> = (0..5)
+        .map(|i| Array::from_elem(IxDyn(&[2, i + 1, 3]), i as f32))
+        .collect();
+    let views: Vec<_> = inputs.iter().map(|input| input.view()).collect();
+    let expected = concatenate(Axis(1), &views).unwrap();
+    assert_eq!(expected.shape(), &[2, 15, 3]);
+
+    let test = inputs
+        .into_iter()
+        .fold(OpTest::new("Concat"), |test, input| test.input(input));
+    test.attribute("axis", -2i64).expect(expected).check();
+}
+
+#[test]
+fn mismatched_dimensions() {
+    let result = OpTest::new("Concat")
+        .input(a())
+        .input(b())
+        .input(arr2(&[[1.0f32, 2.0, 3.0]]))
+        .attribute("axis", -1i64)
+        .expect(a())
+        .run();
+    let error = result.unwrap_err();
+    assert!(
+        error.contains("input 2 ('x2') of shape [1, 3]"),
+        "{}",
+        error
+    );
+
+    let result = OpTest::new("Concat")
+        .input(a())
+        .input(arr3(&[[[1.0f32]]]))
+        .attribute("axis", 0i64)
+        .expect(a())
+        .run();
+    assert!(result
+        .unwrap_err()
+        .contains("input 1 ('x1') of shape [1, 1, 1]"));
+
+    let result = OpTest::new("Concat")
+        .input(a())
+        .input(b())
+        .attribute("axis", -3i64)
+        .expect(a())
+        .run();
+    assert!(result.unwrap_err().contains("out of range"));
+}
+
+#[test]
+fn initializers_between_computed_tensors() {
+    // x is computed by the graph, c is a constant of the graph.
+    let mut model = single_node_model(
+        "Concat",
+        &["c", "x"],
+        &["y"],
+        vec![make_attribute("axis", Attribute::<String>::Int(-1))],
+    );
+    let graph = model.mut_graph();
+    graph.mut_input().retain(|input| input.get_name() == "x");
+    graph
+        .mut_initializer()
+        .push(ndarray_to_tensor_proto::<f32>(b().into_dyn(), "c").unwrap());
+
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(a().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap(),
+        arr2(&[[5.0f32, 1.0, 2.0], [6.0, 3.0, 4.0]]).into_dyn()
+    );
+}
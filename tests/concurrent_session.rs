@@ -0,0 +1,239 @@
+//! Runs MNIST from several threads on one shared session, and two sessions with different
+//! options side by side:
+//!
+//! ```text
+//! cargo test --test concurrent_session
+//! ```
+//!
+//! The MNIST test reads the bundled model: it is ignored until the models are pulled, then run
+//! with `cargo test --test concurrent_session -- --ignored`.
+
+mod common;
+
+use ndarray::{arr1, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::{Model, NonFiniteCheck};
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const MODEL: &str = "models/mnist-8/mnist-8.onnx";
+const INPUT: &str = "models/mnist-8/test_data_set_0/input_0.pb";
+
+const THREADS: usize = 8;
+const RUNS_PER_THREAD: usize = 20;
+
+/// Time after which a missing result is taken for a deadlock.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn concurrent_runs_give_identical_results() {
+    common::require_model(MODEL);
+
+    // The runs also share the thread pool of the session.
+    let session = Arc::new(
+        Session::builder()
+            .model_path(MODEL)
+            .threads(2)
+            .build()
+            .unwrap(),
+    );
+    let input = OnnxParser::load_data(INPUT.to_string()).unwrap();
+    let expected = session.run_with_inputs(vec![input.clone()]).unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    for thread_index in 0..THREADS {
+        let session = Arc::clone(&session);
+        let input = input.clone();
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for _ in 0..RUNS_PER_THREAD {
+                let outputs = session.run_with_inputs(vec![input.clone()]);
+                sender.send((thread_index, outputs)).unwrap();
+            }
+        });
+    }
+    drop(sender);
+
+    for _ in 0..THREADS * RUNS_PER_THREAD {
+        let (thread_index, outputs) = receiver
+            .recv_timeout(TIMEOUT)
+            .expect("every run should complete (deadlock, or a thread panicked)");
+        let outputs = outputs.unwrap();
+        assert_eq!(outputs.len(), expected.len());
+        for (output, expected) in outputs.iter().zip(&expected) {
+            assert_eq!(
+                output.to_ndarray::<f32>().unwrap(),
+                expected.to_ndarray::<f32>().unwrap(),
+                "thread {} computed a different output",
+                thread_index
+            );
+        }
+    }
+}
+
+/// `Scale(Log(x)) + offset`, where `Scale` is a custom operation registered by the sessions.
+fn log_model() -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let value_info = |name: &str| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            vec![Dimension::Value(3)],
+            None,
+        )
+    };
+    let graph = make_graph(
+        vec![
+            node("Log", vec!["x"], "logarithm"),
+            node("Scale", vec!["logarithm"], "scaled"),
+            node("Add", vec!["scaled", "offset"], "y"),
+        ],
+        "scaled_log",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![offset(0.0)],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn offset(value: f32) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(arr1(&[value; 3]).into_dyn(), "offset").unwrap()
+}
+
+fn scale(operand: &TensorProto, node: &NodeProto, factor: f32) -> Result<TensorProto, OnnxError> {
+    let scaled = tensor_proto_to_ndarray::<f32>(operand)?.mapv(|value| value * factor);
+    ndarray_to_tensor_proto::<f32>(scaled, &node.get_output()[0])
+}
+
+fn double(operands: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    scale(operands[0], node, 2.0)
+}
+
+fn triple(operands: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    scale(operands[0], node, 3.0)
+}
+
+#[test]
+fn sessions_with_different_options() {
+    // Nothing is shared but the model: the kernels, the checks, the initializers, the thread
+    // pools and the labels differ.
+    let strict = Arc::new(
+        Session::builder()
+            .model(log_model())
+            .register_op("Scale", double)
+            .non_finite_check(NonFiniteCheck::Abort)
+            .threads(1)
+            .labels(Model::Mnist)
+            .build()
+            .unwrap(),
+    );
+    let lenient = Arc::new(
+        Session::builder()
+            .model(log_model())
+            .register_op("Scale", triple)
+            .override_initializer(offset(10.0))
+            .threads(3)
+            .labels(Model::ResNet)
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(strict.options().model, Model::Mnist);
+    assert_eq!(lenient.options().model, Model::ResNet);
+
+    let e = std::f32::consts::E;
+    let finite = Tensor::from_ndarray(arr1(&[1.0, e, e * e]).into_dyn()).with_name("x");
+    let zero = Tensor::from_ndarray(arr1(&[1.0, 0.0, e]).into_dyn()).with_name("x");
+
+    // Every thread alternates the sessions and the inputs, so that the runs interleave.
+    let (sender, receiver) = mpsc::channel();
+    for thread_index in 0..THREADS {
+        let sessions = [Arc::clone(&strict), Arc::clone(&lenient)];
+        let inputs = [finite.clone(), zero.clone()];
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for run in 0..RUNS_PER_THREAD {
+                let (session, input) = ((thread_index + run) % 2, run / 2 % 2);
+                let outputs = sessions[session].run_with_inputs(vec![inputs[input].clone()]);
+                sender.send((session, input, outputs)).unwrap();
+            }
+        });
+    }
+    drop(sender);
+
+    // The infinities are only close to themselves.
+    let close = |output: &ArrayD<f32>, expected: &[f32]| {
+        output
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| actual == expected || (actual - expected).abs() < 1e-5)
+    };
+    for _ in 0..THREADS * RUNS_PER_THREAD {
+        let (session, input, outputs) = receiver
+            .recv_timeout(TIMEOUT)
+            .expect("every run should complete (deadlock, or a thread panicked)");
+        match (session, input, outputs) {
+            (0, 0, Ok(outputs)) => {
+                let output = outputs[0].to_ndarray::<f32>().unwrap();
+                assert!(close(&output, &[0.0, 2.0, 4.0]), "strict: {}", output);
+            }
+            (0, 1, Err(error)) => match error.root_cause() {
+                OnnxError::NonFiniteOutput {
+                    node_name,
+                    first_bad_index,
+                    ..
+                } => {
+                    assert_eq!(node_name, "logarithm");
+                    assert_eq!(first_bad_index, &[1]);
+                }
+                other => panic!("strict: expected a non-finite output, got {:?}", other),
+            },
+            (1, input, Ok(outputs)) => {
+                let output = outputs[0].to_ndarray::<f32>().unwrap();
+                let expected = if input == 0 {
+                    [10.0, 13.0, 16.0]
+                } else {
+                    [10.0, f32::NEG_INFINITY, 13.0]
+                };
+                assert!(close(&output, &expected), "lenient: {}", output);
+            }
+            (session, input, outputs) => panic!(
+                "session {} on input {}: unexpected result {:?}",
+                session,
+                input,
+                outputs.map(|_| ())
+            ),
+        }
+    }
+}
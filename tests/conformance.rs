@@ -0,0 +1,234 @@
+//! Runs test cases in the layout of the ONNX backend node tests:
+//!
+//! ```text
+//! cargo test --test conformance
+//! ```
+//!
+//! A few cases are written to a temporary directory, one for every outcome, to check the
+//! bookkeeping of `run_node_tests` and its JSON report. The node tests of the ONNX repository are
+//! run by an ignored test, given their directory in `ONNX_RUSTIME_NODE_TESTS`:
+//!
+//! ```text
+//! ONNX_RUSTIME_NODE_TESTS=onnx/onnx/backend/test/data/node \
+//!     cargo test --test conformance -- --ignored
+//! ```
+
+use ndarray::{arr1, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::{run_node_tests, CaseStatus, ConformanceReport};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory of the ONNX backend node tests (`onnx/backend/test/data/node`).
+const NODE_TESTS_VAR: &str = "ONNX_RUSTIME_NODE_TESTS";
+
+/// Cases of the ONNX node tests every build must pass.
+const REQUIRED_CASES: [&str; 4] = [
+    "test_add",
+    "test_relu",
+    "test_matmul_2d",
+    "test_softmax_axis_0",
+];
+
+fn value_info(name: &str) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        vec![Dimension::Value(3)],
+        None,
+    )
+}
+
+/// `y = op_type(x)`, on 3 values.
+fn model(op_type: &str) -> ModelProto {
+    let node = make_node(op_type, vec!["x"], vec!["y"], None, None, None, vec![]);
+    let graph = make_graph(
+        vec![node],
+        "case",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn save_tensor(path: PathBuf, values: ArrayD<f32>) {
+    let tensor = ndarray_to_tensor_proto::<f32>(values, "").unwrap();
+    OnnxParser::save_tensor_proto(&tensor, path.to_string_lossy().into_owned()).unwrap();
+}
+
+/// Writes a case directory, with a data set if `expected` is given.
+fn write_case(dir: &Path, name: &str, op_type: &str, expected: Option<[f32; 3]>) {
+    let case_dir = dir.join(name);
+    fs::create_dir_all(&case_dir).unwrap();
+    let path = case_dir.join("model.onnx");
+    OnnxParser::save_model(&model(op_type), path.to_string_lossy().into_owned()).unwrap();
+
+    if let Some(expected) = expected {
+        let data_set = case_dir.join("test_data_set_0");
+        fs::create_dir_all(&data_set).unwrap();
+        save_tensor(
+            data_set.join("input_0.pb"),
+            arr1(&[-1.0, 0.0, 2.0]).into_dyn(),
+        );
+        save_tensor(data_set.join("output_0.pb"), arr1(&expected).into_dyn());
+    }
+}
+
+/// One case of every outcome, and a directory without model that isn't a case.
+fn case_dir(test: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "onnx_rustime_conformance_{}_{}",
+        test,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("not_a_case")).unwrap();
+
+    write_case(&dir, "test_relu", "Relu", Some([0.0, 0.0, 2.0]));
+    write_case(&dir, "test_relu_wrong", "Relu", Some([0.0, 0.0, 3.0]));
+    write_case(&dir, "test_relu_no_data", "Relu", None);
+    write_case(&dir, "test_frobnicate", "Frobnicate", Some([0.0; 3]));
+    dir
+}
+
+fn statuses(report: &ConformanceReport) -> Vec<(&str, CaseStatus)> {
+    report
+        .cases
+        .iter()
+        .map(|case| (case.name.as_str(), case.status))
+        .collect()
+}
+
+#[test]
+fn bookkeeping() {
+    let dir = case_dir("bookkeeping");
+    let report = run_node_tests(&dir, None).unwrap();
+
+    // Sorted by name.
+    assert_eq!(
+        statuses(&report),
+        [
+            ("test_frobnicate", CaseStatus::Skipped),
+            ("test_relu", CaseStatus::Passed),
+            ("test_relu_no_data", CaseStatus::Failed),
+            ("test_relu_wrong", CaseStatus::Failed),
+        ]
+    );
+    let messages: Vec<&str> = report
+        .cases
+        .iter()
+        .map(|case| case.message.as_str())
+        .collect();
+    assert_eq!(messages[0], "Unsupported operations: Frobnicate");
+    assert_eq!(messages[1], "");
+    assert_eq!(messages[2], "No test data set found");
+    assert!(
+        messages[3].starts_with("test_data_set_0: output 0: "),
+        "{}",
+        messages[3]
+    );
+
+    assert_eq!(report.count(CaseStatus::Passed), 1);
+    assert_eq!(report.count(CaseStatus::Failed), 2);
+    assert_eq!(report.count(CaseStatus::Skipped), 1);
+    assert_eq!(
+        report.by_op_type(),
+        [
+            ("Frobnicate".to_string(), [0, 0, 1]),
+            ("Relu".to_string(), [1, 2, 0]),
+        ]
+    );
+
+    let filtered = run_node_tests(&dir, Some("relu_")).unwrap();
+    assert_eq!(
+        statuses(&filtered),
+        [
+            ("test_relu_no_data", CaseStatus::Failed),
+            ("test_relu_wrong", CaseStatus::Failed),
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(run_node_tests(&dir, None).is_err());
+}
+
+#[test]
+fn json_report() {
+    let dir = case_dir("json_report");
+    let json = run_node_tests(&dir, Some("frobnicate")).unwrap().to_json();
+    assert_eq!(
+        json,
+        "{\n  \"passed\": 0,\n  \"failed\": 0,\n  \"skipped\": 1,\n  \"cases\": [\n    \
+         {\"name\": \"test_frobnicate\", \"op_types\": [\"Frobnicate\"], \"status\": \"skipped\", \
+         \"message\": \"Unsupported operations: Frobnicate\"}\n  ]\n}\n"
+    );
+
+    // The messages are escaped.
+    let mut report = run_node_tests(&dir, None).unwrap();
+    report.cases[1].message = "a \"quoted\"\nmessage".to_string();
+    let json = report.to_json();
+    assert!(
+        json.starts_with("{\n  \"passed\": 1,\n  \"failed\": 2,\n  \"skipped\": 1,\n"),
+        "{}",
+        json
+    );
+    assert!(
+        json.contains("\"message\": \"a \\\"quoted\\\"\\nmessage\""),
+        "{}",
+        json
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[ignore = "needs the ONNX node tests, in ONNX_RUSTIME_NODE_TESTS"]
+fn onnx_node_tests() {
+    let dir = env::var(NODE_TESTS_VAR).unwrap_or_else(|_| {
+        panic!(
+            "{} must be set to the onnx/backend/test/data/node directory",
+            NODE_TESTS_VAR
+        )
+    });
+    let report = run_node_tests(Path::new(&dir), None).unwrap();
+    println!(
+        "{} passed, {} failed, {} skipped",
+        report.count(CaseStatus::Passed),
+        report.count(CaseStatus::Failed),
+        report.count(CaseStatus::Skipped)
+    );
+
+    for name in REQUIRED_CASES {
+        let case = report
+            .cases
+            .iter()
+            .find(|case| case.name == name)
+            .unwrap_or_else(|| panic!("{} isn't in {}", name, dir));
+        assert_eq!(
+            case.status,
+            CaseStatus::Passed,
+            "{}: {}",
+            name,
+            case.message
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Runs Constant and ConstantOfShape through the executor:
+//!
+//! ```text
+//! cargo test --test constant
+//! ```
+
+use ndarray::{arr1, arr2, Array1, Array2, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{TensorProto, ValueInfoProto};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+fn int64_tensor(values: ArrayD<i64>) -> TensorProto {
+    ndarray_to_tensor_proto::<i64>(values, "value").unwrap()
+}
+
+fn float_tensor(values: ArrayD<f32>) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(values, "value").unwrap()
+}
+
+#[test]
+fn constant_values() {
+    OpTest::new("Constant")
+        .opset(13)
+        .attribute("value", int64_tensor(arr1(&[2i64, -1]).into_dyn()))
+        .expect(arr1(&[2i64, -1]))
+        .check();
+    OpTest::new("Constant")
+        .opset(13)
+        .attribute("value", float_tensor(arr2(&[[0.5f32, 1.5]]).into_dyn()))
+        .expect(arr2(&[[0.5f32, 1.5]]))
+        .check();
+    OpTest::new("Constant")
+        .opset(13)
+        .attribute("value_ints", vec![1i64, 2, 3])
+        .expect(arr1(&[1i64, 2, 3]))
+        .check();
+    OpTest::new("Constant")
+        .opset(13)
+        .attribute("value_float", 0.25f32)
+        .expect(scalar(0.25f32))
+        .check();
+}
+
+#[test]
+fn constant_of_shape_values() {
+    OpTest::new("ConstantOfShape")
+        .opset(13)
+        .input(arr1(&[2i64, 3]))
+        .attribute("value", int64_tensor(arr1(&[7i64]).into_dyn()))
+        .expect(Array2::from_elem((2, 3), 7i64))
+        .check();
+    OpTest::new("ConstantOfShape")
+        .opset(13)
+        .input(arr1(&[3i64]))
+        .attribute("value", float_tensor(arr1(&[-1.5f32]).into_dyn()))
+        .expect(Array1::from_elem(3, -1.5f32))
+        .check();
+}
+
+#[test]
+fn constant_of_shape_default_value() {
+    OpTest::new("ConstantOfShape")
+        .opset(13)
+        .input(arr1(&[2i64, 2]))
+        .expect(Array2::<f32>::zeros((2, 2)))
+        .check();
+}
+
+#[test]
+fn constant_of_shape_scalar() {
+    // An empty shape gives a scalar.
+    OpTest::new("ConstantOfShape")
+        .opset(13)
+        .input(Array1::<i64>::zeros(0))
+        .attribute("value", int64_tensor(arr1(&[4i64]).into_dyn()))
+        .expect(scalar(4i64))
+        .check();
+    OpTest::new("ConstantOfShape")
+        .opset(13)
+        .input(Array1::<i64>::zeros(0))
+        .expect(scalar(0.0f32))
+        .check();
+}
+
+#[test]
+fn constants_feed_the_graph() {
+    // y = x + ConstantOfShape(Constant([2, 3]), value = 1.5), without initializers.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "Constant",
+            vec![],
+            vec!["shape"],
+            None,
+            None,
+            None,
+            vec![make_attribute(
+                "value",
+                Attribute::<String>::Tensor(int64_tensor(arr1(&[2i64, 3]).into_dyn())),
+            )],
+        ),
+        make_node(
+            "ConstantOfShape",
+            vec!["shape"],
+            vec!["ones"],
+            None,
+            None,
+            None,
+            vec![make_attribute(
+                "value",
+                Attribute::<String>::Tensor(float_tensor(arr1(&[1.5f32]).into_dyn())),
+            )],
+        ),
+        make_node(
+            "Add",
+            vec!["x", "ones"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+    ];
+    let graph = make_graph(
+        nodes,
+        "constants",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let x = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x.clone().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let y = outputs
+        .iter()
+        .find(|output| output.get_name() == "y")
+        .unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(y).unwrap(),
+        (x + 1.5).into_dyn()
+    );
+}
@@ -0,0 +1,78 @@
+//! Runs dilated convolutions through the executor:
+//!
+//! ```text
+//! cargo test --test conv_dilations
+//! ```
+//!
+//! The expected outputs follow the definition of Conv: output[i][j] is the sum of
+//! kernel[a][b] * padded_input[i * stride + a * dilation][j * stride + b * dilation].
+
+use ndarray::{Array, Array4};
+use onnx_rustime_lib::op_test;
+
+/// A 1x1x7x7 input holding 0, 1, ..., 48.
+fn input() -> Array4<f32> {
+    Array::range(0.0, 49.0, 1.0)
+        .into_shape((1, 1, 7, 7))
+        .unwrap()
+}
+
+/// A 1x1x3x3 kernel.
+fn kernel() -> Array4<f32> {
+    Array::from_shape_vec(
+        (1, 1, 3, 3),
+        vec![1.0, 0.0, -1.0, 2.0, 1.0, 0.0, 0.0, -1.0, 1.0],
+    )
+    .unwrap()
+}
+
+#[test]
+fn dilated_kernel_with_explicit_pads() {
+    // The kernel covers 5x5 pixels of the 9x9 padded input.
+    let expected = Array::from_shape_vec(
+        (1, 1, 5, 5),
+        vec![
+            10.0, 25.0, 28.0, 31.0, 6.0, //
+            14.0, 42.0, 45.0, 48.0, 23.0, //
+            14.0, 63.0, 66.0, 69.0, 44.0, //
+            14.0, 84.0, 87.0, 90.0, 65.0, //
+            12.0, 103.0, 106.0, 109.0, 140.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [input(), kernel()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 dilations: vec![2i64, 2],
+                 pads: vec![1i64, 1, 1, 1],
+                 strides: vec![1i64, 1],
+             },
+             expect: expected);
+}
+
+#[test]
+fn dilated_kernel_with_same_padding() {
+    // ceil(7 / 2) = 4 outputs per axis, from 2 pixels of padding on every side.
+    let expected = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            2.5, 4.5, 10.5, -5.5, //
+            14.5, 42.5, 48.5, 26.5, //
+            14.5, 84.5, 90.5, 68.5, //
+            12.5, 124.5, 130.5, 172.5,
+        ],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [input(), kernel(), Array::from_elem(1, 0.5f32)],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 dilations: vec![2i64, 2],
+                 auto_pad: "SAME_UPPER",
+                 strides: vec![2i64, 2],
+             },
+             expect: expected);
+}
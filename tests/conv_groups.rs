@@ -0,0 +1,98 @@
+//! Runs grouped and depthwise convolutions through the executor:
+//!
+//! ```text
+//! cargo test --test conv_groups
+//! ```
+//!
+//! The outputs are compared shape included, so an `[N, M, H_out, W_out]` output of the wrong
+//! shape fails as well.
+
+use ndarray::{Array, Array1, Array4};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+/// An input of the given shape holding 0, 1, 2...
+fn range_input(shape: (usize, usize, usize, usize)) -> Array4<f32> {
+    let (n, c, h, w) = shape;
+    Array::range(0.0, (n * c * h * w) as f32, 1.0)
+        .into_shape(shape)
+        .unwrap()
+}
+
+#[test]
+fn two_groups() {
+    // Kernels 0 and 1 convolve the input channels 0-1 and 2-3 respectively.
+    let kernel = Array::from_shape_vec(
+        (2, 2, 2, 2),
+        vec![
+            1.0, 0.0, 0.0, -1.0, 2.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0,
+        ],
+    )
+    .unwrap();
+    let expected = Array::from_shape_vec(
+        (1, 2, 2, 2),
+        vec![15.0, 17.0, 21.0, 23.0, 8.0, 9.0, 11.0, 12.0],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [range_input((1, 4, 3, 3)), kernel, Array1::from(vec![1.0f32, -1.0])],
+             attrs: { kernel_shape: vec![2i64, 2], strides: vec![1i64, 1], group: 2i64 },
+             expect: expected);
+}
+
+#[test]
+fn depthwise() {
+    // One kernel per channel: the identity, a box sum and a Laplacian.
+    let kernel = Array::from_shape_vec(
+        (3, 1, 3, 3),
+        vec![
+            0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, //
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, //
+            0.0, -1.0, 0.0, -1.0, 4.0, -1.0, 0.0, -1.0, 0.0,
+        ],
+    )
+    .unwrap();
+    let expected = Array::from_shape_vec(
+        (1, 3, 4, 4),
+        vec![
+            0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, //
+            8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, //
+            74.0, 114.0, 120.0, 82.0, 123.0, 189.0, 198.0, 135.0, //
+            147.0, 225.0, 234.0, 159.0, 106.0, 162.0, 168.0, 114.0, //
+            59.0, 29.0, 30.0, 67.0, 35.0, 0.0, 0.0, 40.0, //
+            39.0, 0.0, 0.0, 44.0, 91.0, 49.0, 50.0, 99.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [range_input((1, 3, 4, 4)), kernel],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 pads: vec![1i64, 1, 1, 1],
+                 strides: vec![1i64, 1],
+                 group: 3i64,
+             },
+             expect: expected);
+}
+
+#[test]
+fn kernels_not_divisible_by_group() {
+    // 4 input channels in 2 groups, but 3 kernels.
+    let result = OpTest::new("Conv")
+        .input(range_input((1, 4, 3, 3)))
+        .input(Array4::<f32>::zeros((3, 2, 2, 2)))
+        .attribute("strides", vec![1i64, 1])
+        .attribute("group", 2i64)
+        .expect(Array4::<f32>::zeros((1, 3, 2, 2)))
+        .run();
+
+    let message = result.unwrap_err();
+    assert!(
+        message.contains("can't be split into 2 groups"),
+        "{}",
+        message
+    );
+}
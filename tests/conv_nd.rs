@@ -0,0 +1,68 @@
+//! Runs 1D and 3D convolutions through the executor:
+//!
+//! ```text
+//! cargo test --test conv_nd
+//! ```
+//!
+//! The expected outputs follow the definition of Conv, computed pixel by pixel: output[m][o] is
+//! the sum of kernel[m][c][k] * padded_input[c][o * stride + k] along every spatial axis.
+
+use ndarray::{Array, Array1, ArrayD, IxDyn};
+use onnx_rustime_lib::op_test;
+
+/// An input of the given shape holding 0, 1, 2...
+fn range_input(shape: &[usize]) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::range(0.0, len as f32, 1.0)
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+/// A kernel of the given shape cycling through -2, -1, 0, 1, 2.
+fn kernel(shape: &[usize]) -> ArrayD<f32> {
+    range_input(shape).mapv(|value| value % 5.0 - 2.0)
+}
+
+#[test]
+fn conv1d_with_stride_2() {
+    // (9 - 3) / 2 + 1 = 4 outputs per kernel.
+    let expected = Array::from_shape_vec(
+        (1, 3, 4),
+        vec![
+            6.5, 2.5, -1.5, -5.5, //
+            -11.5, -13.5, -15.5, -17.5, //
+            -22.0, -22.0, -22.0, -22.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [range_input(&[1, 2, 9]), kernel(&[3, 2, 3]), Array1::from(vec![0.5f32, -0.5, 1.0])],
+             attrs: { kernel_shape: vec![3i64], strides: vec![2i64] },
+             expect: expected);
+}
+
+#[test]
+fn conv3d_with_padding_1() {
+    // The padding keeps the 3x3x3 volume.
+    let expected = Array::from_shape_vec(
+        (1, 2, 3, 3, 3),
+        vec![
+            -28.0, -19.0, -23.0, -31.0, -19.0, -2.0, 24.0, 28.0, 31.0, //
+            -26.0, -28.0, -49.0, -53.0, -26.0, 1.0, 28.0, 49.0, 68.0, //
+            46.0, 4.0, -28.0, -9.0, -43.0, -61.0, -4.0, 28.0, 34.0, //
+            21.0, 19.0, 22.0, 16.0, 2.0, -1.0, -29.0, -32.0, 4.0, //
+            28.0, 46.0, 59.0, 22.0, 1.0, 2.0, -51.0, -64.0, -22.0, //
+            -1.0, 28.0, 31.0, 44.0, 62.0, 26.0, -28.0, -31.0, -59.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("Conv",
+             inputs: [range_input(&[1, 1, 3, 3, 3]), kernel(&[2, 1, 3, 3, 3])],
+             attrs: {
+                 kernel_shape: vec![3i64, 3, 3],
+                 pads: vec![1i64, 1, 1, 1, 1, 1],
+             },
+             expect: expected);
+}
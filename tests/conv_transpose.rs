@@ -0,0 +1,134 @@
+//! Runs transposed convolutions through the executor:
+//!
+//! ```text
+//! cargo test --test conv_transpose
+//! ```
+//!
+//! The expected outputs follow the definition of ConvTranspose, computed pixel by pixel:
+//! output[m][y][x] is the sum of input[c][i][j] * kernel[c][m][a][b] over the taps with
+//! i * stride + a * dilation = y + pad_top and j * stride + b * dilation = x + pad_left.
+
+use ndarray::{Array, Array1, Array4};
+use onnx_rustime_lib::op_test;
+
+/// An input of the given shape holding 0, 1, 2...
+fn range_input(shape: (usize, usize, usize, usize)) -> Array4<f32> {
+    let (n, c, h, w) = shape;
+    Array::range(0.0, (n * c * h * w) as f32, 1.0)
+        .into_shape(shape)
+        .unwrap()
+}
+
+/// A kernel of the given shape cycling through -2, -1, 0, 1, 2.
+fn kernel(shape: (usize, usize, usize, usize)) -> Array4<f32> {
+    let (_, m, h, w) = shape;
+    Array::from_shape_fn(shape, |(i, j, y, x)| {
+        ((((i * m + j) * h + y) * w + x) % 5) as f32 - 2.0
+    })
+}
+
+#[test]
+fn upsampling_decoder_block() {
+    // The usual 2x upsampling of decoders: a 4x4 kernel with strides 2 and pads 1.
+    let expected = Array::from_shape_vec(
+        (1, 1, 6, 6),
+        vec![
+            -8.5, -17.5, -2.5, -18.5, -4.5, -1.5, //
+            -20.5, 3.5, 5.5, 1.5, 6.5, -0.5, //
+            0.5, -28.5, 3.5, -28.5, 1.5, -22.5, //
+            -23.5, -2.5, 8.5, -4.5, 9.5, -6.5, //
+            0.5, -28.5, -2.5, -28.5, -4.5, -25.5, //
+            -17.5, 12.5, -23.5, 12.5, -24.5, -32.5,
+        ],
+    )
+    .unwrap();
+
+    op_test!("ConvTranspose",
+             inputs: [range_input((1, 2, 3, 3)), kernel((2, 1, 4, 4)), Array1::from(vec![0.5f32])],
+             attrs: {
+                 kernel_shape: vec![4i64, 4],
+                 strides: vec![2i64, 2],
+                 pads: vec![1i64, 1, 1, 1],
+             },
+             expect: expected);
+}
+
+#[test]
+fn output_shape_wins_over_pads() {
+    // The full output is 8x8: 2 rows are cropped (1 at the top) and no column, whatever the pads.
+    let expected = Array::from_shape_vec(
+        (1, 1, 6, 8),
+        vec![
+            -17.5, -8.5, -17.5, -2.5, -18.5, -4.5, -1.5, 11.5, //
+            0.5, -20.5, 3.5, 5.5, 1.5, 6.5, -0.5, 31.5, //
+            -8.5, 0.5, -28.5, 3.5, -28.5, 1.5, -22.5, -0.5, //
+            0.5, -23.5, -2.5, 8.5, -4.5, 9.5, -6.5, 37.5, //
+            -5.5, 0.5, -28.5, -2.5, -28.5, -4.5, -25.5, -6.5, //
+            36.5, -17.5, 12.5, -23.5, 12.5, -24.5, -32.5, -7.5,
+        ],
+    )
+    .unwrap();
+
+    op_test!("ConvTranspose",
+             inputs: [range_input((1, 2, 3, 3)), kernel((2, 1, 4, 4)), Array1::from(vec![0.5f32])],
+             attrs: {
+                 strides: vec![2i64, 2],
+                 pads: vec![1i64, 1, 1, 1],
+                 output_shape: vec![6i64, 8],
+             },
+             expect: expected);
+}
+
+#[test]
+fn asymmetric_pads_in_two_groups() {
+    // 0 rows cropped at the top and 2 at the bottom, 1 column on the left and 0 on the right.
+    let expected = Array::from_shape_vec(
+        (1, 2, 5, 6),
+        vec![
+            1.0, -1.0, 0.0, -3.0, -1.0, 1.0, //
+            1.0, 2.0, 3.0, 1.0, 5.0, -3.0, //
+            -2.0, -8.0, -3.0, -10.0, -4.0, 3.0, //
+            7.0, -1.0, 9.0, -2.0, 11.0, -9.0, //
+            -5.0, -14.0, -6.0, -16.0, -7.0, 6.0, //
+            -19.0, 10.0, -21.0, 11.0, -23.0, -12.0, //
+            8.0, 17.0, 9.0, 19.0, 10.0, 21.0, //
+            -34.0, -7.0, -37.0, -8.0, -40.0, -15.0, //
+            11.0, 23.0, 12.0, 25.0, 13.0, 27.0, //
+            -43.0, -10.0, -46.0, -11.0, -49.0, -18.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("ConvTranspose",
+             inputs: [range_input((1, 2, 3, 3)), kernel((2, 1, 3, 3)), Array1::from(vec![1.0f32, -1.0])],
+             attrs: {
+                 strides: vec![2i64, 2],
+                 pads: vec![0i64, 1, 2, 0],
+                 group: 2i64,
+             },
+             expect: expected);
+}
+
+#[test]
+fn output_padding_extends_the_end() {
+    // Strides 2 and pads 1 give 3x3 pixels, output_padding adds a row and a column.
+    let expected = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            0.0, 1.0, 2.0, -2.0, //
+            -2.0, -7.0, -3.0, 1.0, //
+            4.0, -1.0, 6.0, -6.0, //
+            0.0, -1.0, 0.0, 3.0,
+        ],
+    )
+    .unwrap();
+
+    op_test!("ConvTranspose",
+             inputs: [range_input((1, 1, 2, 2)), kernel((1, 1, 3, 3))],
+             attrs: {
+                 strides: vec![2i64, 2],
+                 pads: vec![1i64, 1, 1, 1],
+                 output_padding: vec![1i64, 1],
+             },
+             expect: expected);
+}
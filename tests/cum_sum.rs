@@ -0,0 +1,127 @@
+//! Runs CumSum through the executor:
+//!
+//! ```text
+//! cargo test --test cum_sum
+//! ```
+//!
+//! Every combination of `exclusive` and `reverse` is checked along both axes of a [2, 5] tensor.
+
+use ndarray::{arr1, arr2, Array2};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+fn x() -> Array2<f32> {
+    arr2(&[[1.0f32, 2.0, 3.0, 4.0, 5.0], [6.0, 7.0, 8.0, 9.0, 10.0]])
+}
+
+fn cum_sum(axis: i64, exclusive: i64, reverse: i64) -> OpTest {
+    OpTest::new("CumSum")
+        .opset(14)
+        .input(x())
+        .input(scalar(axis))
+        .attribute("exclusive", exclusive)
+        .attribute("reverse", reverse)
+}
+
+#[test]
+fn along_the_last_axis() {
+    for (exclusive, reverse, expected) in [
+        (
+            0,
+            0,
+            [
+                [1.0f32, 3.0, 6.0, 10.0, 15.0],
+                [6.0, 13.0, 21.0, 30.0, 40.0],
+            ],
+        ),
+        (
+            1,
+            0,
+            [[0.0, 1.0, 3.0, 6.0, 10.0], [0.0, 6.0, 13.0, 21.0, 30.0]],
+        ),
+        (
+            0,
+            1,
+            [[15.0, 14.0, 12.0, 9.0, 5.0], [40.0, 34.0, 27.0, 19.0, 10.0]],
+        ),
+        (
+            1,
+            1,
+            [[14.0, 12.0, 9.0, 5.0, 0.0], [34.0, 27.0, 19.0, 10.0, 0.0]],
+        ),
+    ] {
+        cum_sum(1, exclusive, reverse)
+            .expect(arr2(&expected))
+            .check();
+        // The same axis, counted from the end.
+        cum_sum(-1, exclusive, reverse)
+            .expect(arr2(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn along_the_first_axis() {
+    for (exclusive, reverse, expected) in [
+        (
+            0,
+            0,
+            [[1.0f32, 2.0, 3.0, 4.0, 5.0], [7.0, 9.0, 11.0, 13.0, 15.0]],
+        ),
+        (1, 0, [[0.0; 5], [1.0, 2.0, 3.0, 4.0, 5.0]]),
+        (
+            0,
+            1,
+            [[7.0, 9.0, 11.0, 13.0, 15.0], [6.0, 7.0, 8.0, 9.0, 10.0]],
+        ),
+        (1, 1, [[6.0, 7.0, 8.0, 9.0, 10.0], [0.0; 5]]),
+    ] {
+        cum_sum(0, exclusive, reverse)
+            .expect(arr2(&expected))
+            .check();
+        cum_sum(-2, exclusive, reverse)
+            .expect(arr2(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn default_attributes_and_int32_axis() {
+    OpTest::new("CumSum")
+        .opset(11)
+        .input(x())
+        .input(scalar(1i32))
+        .expect(arr2(&[
+            [1.0f32, 3.0, 6.0, 10.0, 15.0],
+            [6.0, 13.0, 21.0, 30.0, 40.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn int64_positions_from_an_attention_mask() {
+    OpTest::new("CumSum")
+        .opset(14)
+        .input(arr2(&[[1i64, 1, 1, 0, 0], [1, 1, 1, 1, 1]]))
+        .input(scalar(1i64))
+        .expect(arr2(&[[1i64, 2, 3, 3, 3], [1, 2, 3, 4, 5]]))
+        .check();
+}
+
+#[test]
+fn invalid_axis() {
+    let error = cum_sum(2, 0, 0).expect(x()).run().unwrap_err();
+    assert!(
+        error.contains("Axis 2 is out of range for a tensor of rank 2"),
+        "{}",
+        error
+    );
+
+    let error = OpTest::new("CumSum")
+        .opset(14)
+        .input(x())
+        .input(arr1(&[0i64, 1]))
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("must be a single value"), "{}", error);
+}
@@ -0,0 +1,227 @@
+//! Merges the initializers holding the same value, and compares the runs of the models before
+//! and after:
+//!
+//! ```text
+//! cargo test --test deduplicate_initializers
+//! ```
+//!
+//! The test model is a small language model whose encoder, decoder and output layer tie their
+//! embeddings, exported as three identical 1 MB initializers.
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, StringStringEntryProto, TensorProto, TensorProto_DataLocation,
+    TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::passes::deduplicate_initializers;
+use onnx_rustime_lib::transforms::{OptLevel, PassManager};
+use protobuf::RepeatedField;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+
+const VOCABULARY: usize = 1024;
+const HIDDEN: usize = 256;
+const TOKENS: usize = 6;
+
+/// Size of each embedding: 1 MB of f32.
+const EMBEDDING_BYTES: usize = VOCABULARY * HIDDEN * 4;
+
+const EMBEDDINGS: [&str; 3] = ["encoder_embedding", "decoder_embedding", "output_embedding"];
+
+/// The same embedding stored as three initializers, in the raw format of the exporters.
+fn embeddings() -> Vec<TensorProto> {
+    let mut rng = StdRng::seed_from_u64(224);
+    let embedding = random_tensor(&mut rng, &[VOCABULARY, HIDDEN]);
+    let raw_data: Vec<u8> = embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    EMBEDDINGS
+        .iter()
+        .map(|name| {
+            let mut tensor = TensorProto::new();
+            tensor.set_name(name.to_string());
+            tensor.set_data_type(TensorProto_DataType::FLOAT as i32);
+            tensor.set_dims(vec![VOCABULARY as i64, HIDDEN as i64]);
+            tensor.set_raw_data(raw_data.clone());
+            tensor
+        })
+        .collect()
+}
+
+/// Embeds the source and target tokens, sums them, and projects the sum back on the vocabulary.
+fn language_model(initializers: Vec<TensorProto>) -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let mut projection = node("Gemm", vec!["hidden", "output_embedding"], "logits");
+    projection
+        .mut_attribute()
+        .push(make_attribute("transB", Attribute::<String>::Int(1)));
+    let nodes = vec![
+        node("Gather", vec!["encoder_embedding", "source"], "encoded"),
+        node("Gather", vec!["decoder_embedding", "target"], "decoded"),
+        node("Add", vec!["encoded", "decoded"], "hidden"),
+        projection,
+    ];
+
+    let tokens = |name: &str| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::INT64,
+            vec![Dimension::Value(TOKENS as i64)],
+            None,
+        )
+    };
+    let mut logits = ValueInfoProto::new();
+    logits.set_name("logits".to_string());
+    let graph = make_graph(
+        nodes,
+        "language_model",
+        vec![tokens("source"), tokens("target")],
+        vec![logits],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(model: &ModelProto) -> ArrayD<f32> {
+    let tokens = |name: &str, first: i64| {
+        let ids = ArrayD::from_shape_fn(IxDyn(&[TOKENS]), |i| (first + 97 * i[0] as i64) % 1024);
+        ndarray_to_tensor_proto::<i64>(ids, name).unwrap()
+    };
+    let outputs = run_with_inputs(
+        model,
+        vec![tokens("source", 3), tokens("target", 500)],
+        &RunOptions::default(),
+    )
+    .unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+fn initializer_names(model: &ModelProto) -> Vec<&str> {
+    model
+        .get_graph()
+        .get_initializer()
+        .iter()
+        .map(|init| init.get_name())
+        .collect()
+}
+
+/// Runs the deduplication alone, as the last pass of the levels does.
+fn deduplicate(model: &ModelProto) -> (ModelProto, i64) {
+    let mut pass_manager = PassManager::new(OptLevel::None);
+    pass_manager.enable("deduplicate_initializers").unwrap();
+    let (model, report) = pass_manager.run(model).unwrap();
+    (model, report.passes[0].bytes_saved)
+}
+
+#[test]
+fn tied_embeddings() {
+    let model = language_model(embeddings());
+    let (deduplicated, bytes_saved) = deduplicate(&model);
+
+    assert_eq!(bytes_saved, 2 * EMBEDDING_BYTES as i64);
+    assert_eq!(initializer_names(&deduplicated), ["encoder_embedding"]);
+    let inputs: Vec<&str> = deduplicated
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_input()[0].as_str())
+        .collect();
+    assert_eq!(inputs[..2], ["encoder_embedding", "encoder_embedding"]);
+    assert_eq!(
+        deduplicated.get_graph().get_node()[3].get_input(),
+        ["hidden", "encoder_embedding"]
+    );
+    assert_eq!(run(&deduplicated), run(&model));
+}
+
+#[test]
+fn same_data_with_other_dimensions() {
+    // The output layer stores the transposed shape: the bytes are the same, the value isn't.
+    let mut initializers = embeddings();
+    initializers[2].set_dims(vec![HIDDEN as i64, VOCABULARY as i64]);
+    // The decoder embedding can be replaced by the caller.
+    let mut model = language_model(initializers);
+    let mut overridable = ValueInfoProto::new();
+    overridable.set_name("decoder_embedding".to_string());
+    model.mut_graph().mut_input().push(overridable);
+
+    let mut deduplicated = model.clone();
+    assert!(!deduplicate_initializers(deduplicated.mut_graph()).unwrap());
+    assert_eq!(deduplicated, model);
+}
+
+#[test]
+fn external_data() {
+    let directory =
+        std::env::temp_dir().join(format!("onnx_rustime_deduplicate_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+
+    // The three embeddings are written one after the other in the same file.
+    let mut initializers = embeddings();
+    let mut weights = Vec::new();
+    for tensor in initializers.iter_mut() {
+        let entry = |key: &str, value: String| {
+            let mut entry = StringStringEntryProto::new();
+            entry.set_key(key.to_string());
+            entry.set_value(value);
+            entry
+        };
+        tensor.set_external_data(RepeatedField::from_vec(vec![
+            entry("location", "weights.bin".to_string()),
+            entry("offset", weights.len().to_string()),
+            entry("length", EMBEDDING_BYTES.to_string()),
+        ]));
+        tensor.set_data_location(TensorProto_DataLocation::EXTERNAL);
+        weights.extend(tensor.take_raw_data());
+    }
+    fs::write(directory.join("weights.bin"), weights).unwrap();
+    let model = language_model(initializers);
+
+    // Without their bytes, the initializers can't be compared.
+    let mut unresolved = model.clone();
+    assert!(!deduplicate_initializers(unresolved.mut_graph()).unwrap());
+    assert_eq!(unresolved, model);
+
+    // The loaded model holds the bytes read from the file.
+    let path = directory.join("model.onnx").to_string_lossy().into_owned();
+    OnnxParser::save_model(&model, path.clone()).unwrap();
+    let loaded = OnnxParser::load_model(path).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    let (deduplicated, bytes_saved) = deduplicate(&loaded);
+    assert_eq!(bytes_saved, 2 * EMBEDDING_BYTES as i64);
+    assert_eq!(initializer_names(&deduplicated), ["encoder_embedding"]);
+    assert_eq!(run(&deduplicated), run(&language_model(embeddings())));
+}
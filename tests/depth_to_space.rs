@@ -0,0 +1,129 @@
+//! Runs DepthToSpace and SpaceToDepth through the executor:
+//!
+//! ```text
+//! cargo test --test depth_to_space
+//! ```
+//!
+//! The pixel shuffles are the examples of the ONNX operator documentation.
+
+use ndarray::{arr3, Array, Array4, ArrayD, Axis};
+use onnx_rustime_lib::backend::helper::{make_attribute, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+/// 8 channels of 2x3 pixels: channel `c` holds `9 * c + 3 * y + x`.
+fn x() -> Array4<f32> {
+    Array4::from_shape_fn((1, 8, 2, 3), |(_, c, y, x)| (9 * c + 3 * y + x) as f32)
+}
+
+/// Runs a DepthToSpace or SpaceToDepth node of blocksize 2 on `x`.
+fn rearrange(op_type: &str, x: ArrayD<f32>) -> ArrayD<f32> {
+    let blocksize = make_attribute("blocksize", Attribute::<String>::Int(2));
+    let model = single_node_model(op_type, &["x"], &["y"], vec![blocksize]);
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x, "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+#[test]
+fn depth_column_row() {
+    OpTest::new("DepthToSpace")
+        .opset(13)
+        .input(x())
+        .attribute("blocksize", 2i64)
+        .expect(
+            arr3(&[
+                [
+                    [0.0f32, 18.0, 1.0, 19.0, 2.0, 20.0],
+                    [36.0, 54.0, 37.0, 55.0, 38.0, 56.0],
+                    [3.0, 21.0, 4.0, 22.0, 5.0, 23.0],
+                    [39.0, 57.0, 40.0, 58.0, 41.0, 59.0],
+                ],
+                [
+                    [9.0, 27.0, 10.0, 28.0, 11.0, 29.0],
+                    [45.0, 63.0, 46.0, 64.0, 47.0, 65.0],
+                    [12.0, 30.0, 13.0, 31.0, 14.0, 32.0],
+                    [48.0, 66.0, 49.0, 67.0, 50.0, 68.0],
+                ],
+            ])
+            .insert_axis(Axis(0)),
+        )
+        .check();
+}
+
+#[test]
+fn column_row_depth() {
+    // PyTorch's PixelShuffle: the channels 0 to 3 make the first output channel.
+    OpTest::new("DepthToSpace")
+        .opset(13)
+        .input(x())
+        .attribute("blocksize", 2i64)
+        .attribute("mode", "CRD")
+        .expect(
+            arr3(&[
+                [
+                    [0.0f32, 9.0, 1.0, 10.0, 2.0, 11.0],
+                    [18.0, 27.0, 19.0, 28.0, 20.0, 29.0],
+                    [3.0, 12.0, 4.0, 13.0, 5.0, 14.0],
+                    [21.0, 30.0, 22.0, 31.0, 23.0, 32.0],
+                ],
+                [
+                    [36.0, 45.0, 37.0, 46.0, 38.0, 47.0],
+                    [54.0, 63.0, 55.0, 64.0, 56.0, 65.0],
+                    [39.0, 48.0, 40.0, 49.0, 41.0, 50.0],
+                    [57.0, 66.0, 58.0, 67.0, 59.0, 68.0],
+                ],
+            ])
+            .insert_axis(Axis(0)),
+        )
+        .check();
+}
+
+#[test]
+fn round_trips() {
+    let x = x().into_dyn();
+    let space = rearrange("DepthToSpace", x.clone());
+    assert_eq!(rearrange("SpaceToDepth", space), x);
+
+    let image = Array::from_shape_fn((2, 3, 4, 6), |(n, c, y, x)| {
+        (n * 1000 + c * 100 + y * 10 + x) as f32
+    })
+    .into_dyn();
+    let depth = rearrange("SpaceToDepth", image.clone());
+    assert_eq!(depth.shape(), &[2, 12, 2, 3]);
+    assert_eq!(rearrange("DepthToSpace", depth), image);
+}
+
+#[test]
+fn channels_not_divisible() {
+    let error = OpTest::new("DepthToSpace")
+        .opset(13)
+        .input(Array4::<f32>::zeros((1, 6, 2, 2)))
+        .attribute("blocksize", 2i64)
+        .expect(Array4::<f32>::zeros((1, 1, 4, 4)))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The 6 channels of the input of DepthToSpace aren't a multiple of the 4"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn pixels_not_divisible() {
+    let error = OpTest::new("SpaceToDepth")
+        .opset(13)
+        .input(Array4::<f32>::zeros((1, 1, 3, 4)))
+        .attribute("blocksize", 2i64)
+        .expect(Array4::<f32>::zeros((1, 4, 1, 2)))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("aren't multiples of the blocksize 2"),
+        "{}",
+        error
+    );
+}
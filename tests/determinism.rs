@@ -0,0 +1,193 @@
+//! Checks that the outputs are bit-identical across runs and thread counts:
+//!
+//! ```text
+//! cargo test --test determinism
+//! ```
+//!
+//! MNIST is run three times single-threaded, like the `verify-determinism` command does, by an
+//! ignored test run once the models are pulled; a synthetic convolutional network with random
+//! weights is checked the same way on every run. The random nodes without a seed are seeded, so they don't count as
+//! nondeterminism.
+
+mod common;
+
+use ndarray::{arr0, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::testing::{verify_determinism, DeterminismReport};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const MODEL: &str = "models/mnist-8/mnist-8.onnx";
+const INPUT: &str = "models/mnist-8/test_data_set_0/input_0.pb";
+
+fn value_info(name: &str, shape: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        shape.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn node(
+    op_type: &str,
+    inputs: Vec<&str>,
+    output: &str,
+    attributes: Vec<AttributeProto>,
+) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        attributes,
+    )
+}
+
+fn model(
+    nodes: Vec<NodeProto>,
+    input: &[i64],
+    output: &[i64],
+    inits: Vec<TensorProto>,
+) -> ModelProto {
+    let graph = make_graph(
+        nodes,
+        "determinism",
+        vec![value_info("x", input)],
+        vec![value_info("y", output)],
+        inits,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn random(rng: &mut StdRng, name: &str, shape: &[usize]) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(random_tensor(rng, shape), name).unwrap()
+}
+
+/// `Gemm(Flatten(Relu(Conv(x))))` on a 1x1x8x8 image, with random weights.
+fn convolutional() -> (ModelProto, TensorProto) {
+    let mut rng = StdRng::seed_from_u64(234);
+    let axis = make_attribute("axis", Attribute::<String>::Int(1));
+    let nodes = vec![
+        node("Conv", vec!["x", "conv_w", "conv_b"], "conv", vec![]),
+        node("Relu", vec!["conv"], "relu", vec![]),
+        node("Flatten", vec!["relu"], "flat", vec![axis]),
+        node("Gemm", vec!["flat", "gemm_w"], "y", vec![]),
+    ];
+    let inits = vec![
+        random(&mut rng, "conv_w", &[4, 1, 3, 3]),
+        random(&mut rng, "conv_b", &[4]),
+        random(&mut rng, "gemm_w", &[144, 10]),
+    ];
+    let model = model(nodes, &[1, 1, 8, 8], &[1, 10], inits);
+    (model, random(&mut rng, "x", &[1, 1, 8, 8]))
+}
+
+/// Checks that all the runs have the same hashes, one per output.
+fn assert_identical_runs(report: &DeterminismReport, runs: usize) {
+    assert!(report.is_deterministic(), "{:?}", report.divergences);
+    assert_eq!(report.runs.len(), runs);
+    let first = &report.runs[0].hashes;
+    assert_eq!(first.len(), report.output_names.len());
+    for run in &report.runs {
+        assert_eq!(
+            &run.hashes, first,
+            "{} threads, run {}",
+            run.threads, run.run
+        );
+    }
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn mnist_single_threaded() {
+    common::require_model(MODEL);
+    let model = OnnxParser::load_model(MODEL.to_string()).unwrap();
+    let input = OnnxParser::load_tensor_proto(INPUT.to_string()).unwrap();
+
+    let report = verify_determinism(&model, vec![input], 3, &[1]).unwrap();
+    assert_identical_runs(&report, 3);
+    assert!(report.seeded_nodes.is_empty());
+}
+
+#[test]
+fn synthetic_single_threaded() {
+    let (model, input) = convolutional();
+    let report = verify_determinism(&model, vec![input], 3, &[1]).unwrap();
+    assert_identical_runs(&report, 3);
+    assert_eq!(report.output_names, ["y"]);
+    assert!(report.runs.iter().all(|run| run.threads == 1));
+    assert_eq!(
+        report.runs.iter().map(|run| run.run).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn synthetic_across_thread_counts() {
+    let (model, input) = convolutional();
+    let report = verify_determinism(&model, vec![input], 2, &[1, 2, 4]).unwrap();
+    assert_identical_runs(&report, 6);
+    assert_eq!(
+        report
+            .runs
+            .iter()
+            .map(|run| run.threads)
+            .collect::<Vec<_>>(),
+        [1, 1, 2, 2, 4, 4]
+    );
+}
+
+#[test]
+fn random_nodes_are_seeded() {
+    let seed = make_attribute("seed", Attribute::<String>::Float(3.0));
+    let nodes = vec![
+        node("RandomUniformLike", vec!["x"], "noise", vec![]),
+        node("RandomNormalLike", vec!["x"], "seeded_noise", vec![seed]),
+        node("Add", vec!["x", "noise"], "noisy", vec![]),
+        node("Add", vec!["noisy", "seeded_noise"], "noisier", vec![]),
+        node("Dropout", vec!["noisier", "ratio", "training"], "y", vec![]),
+    ];
+    let inits = vec![
+        ndarray_to_tensor_proto::<f32>(arr0(0.5f32).into_dyn(), "ratio").unwrap(),
+        ndarray_to_tensor_proto::<bool>(arr0(true).into_dyn(), "training").unwrap(),
+    ];
+    let mut model = model(nodes, &[4, 4], &[4, 4], inits);
+    let input = ndarray_to_tensor_proto::<f32>(ArrayD::ones(vec![4, 4]), "x").unwrap();
+
+    let report = verify_determinism(&model, vec![input], 3, &[1, 2]).unwrap();
+    assert_identical_runs(&report, 6);
+    // The node with a seed keeps it.
+    assert_eq!(
+        report.seeded_nodes,
+        ["noise (RandomUniformLike)", "y (Dropout)"]
+    );
+
+    // An inference Dropout isn't random.
+    model.mut_graph().mut_initializer()[1] =
+        ndarray_to_tensor_proto::<bool>(arr0(false).into_dyn(), "training").unwrap();
+    let input = ndarray_to_tensor_proto::<f32>(ArrayD::ones(vec![4, 4]), "x").unwrap();
+    let report = verify_determinism(&model, vec![input], 1, &[1]).unwrap();
+    assert_eq!(report.seeded_nodes, ["noise (RandomUniformLike)"]);
+}
@@ -0,0 +1,188 @@
+//! Fuzzes the optimized kernels against the reference implementations of `ops::reference`:
+//!
+//! ```text
+//! cargo test --test differential
+//! ```
+//!
+//! Every operation of `DIFFERENTIAL_OPS` is checked on random shapes, strides, pads, dilations and
+//! groups; a failure prints the parameters of the case. The `check-kernels` command runs many
+//! more cases. The reference kernels are also run in place of the optimized ones on a small
+//! network, like `RunOptions::reference_kernels` does when debugging a model.
+
+use ndarray::ArrayD;
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::testing::{
+    assert_tensors_close, check_kernel, random_case, KernelCase, DIFFERENTIAL_OPS, KERNEL_TOLERANCE,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Random cases of every operation.
+const CASES: usize = 200;
+
+const SEED: u64 = 237;
+
+fn ints(name: &str, values: &[i64]) -> AttributeProto {
+    make_attribute(name, Attribute::<String>::Ints(values.to_vec()))
+}
+
+#[test]
+fn kernels_match_the_reference() {
+    for op_type in DIFFERENTIAL_OPS {
+        if let Err(case) = check_kernel(op_type, CASES, SEED) {
+            panic!("{}", case);
+        }
+    }
+}
+
+#[test]
+fn cases_are_reproducible() {
+    for op_type in DIFFERENTIAL_OPS {
+        let case = |seed| random_case(&mut StdRng::seed_from_u64(seed), op_type);
+        let (first, second) = (case(SEED), case(SEED));
+        assert_eq!(first.to_string(), second.to_string());
+        assert_eq!(first.inputs(), second.inputs());
+    }
+}
+
+#[test]
+fn failures_print_a_reproducer() {
+    let case = KernelCase {
+        op_type: "Conv",
+        shapes: vec![vec![1, 4, 7, 6], vec![6, 2, 3, 2], vec![6]],
+        attributes: vec![
+            ("kernel_shape", vec![3, 2]),
+            ("strides", vec![2, 1]),
+            ("pads", vec![1, 0, 2, 1]),
+            ("dilations", vec![2, 3]),
+            ("group", vec![2]),
+        ],
+        seed: 11,
+    };
+    case.check().unwrap();
+    assert_eq!(
+        case.to_string(),
+        "Conv with inputs of shapes [[1, 4, 7, 6], [6, 2, 3, 2], [6]], kernel_shape [3, 2], \
+         strides [2, 1], pads [1, 0, 2, 1], dilations [2, 3], group [2] (input seed 11)"
+    );
+
+    // A case the kernels can't run fails with its parameters rather than panicking.
+    let mut invalid = case.clone();
+    invalid.shapes[1] = vec![6, 3, 3, 2];
+    let message = invalid.check().unwrap_err();
+    assert!(
+        message.starts_with("Conv with inputs of shapes [[1, 4, 7, 6], [6, 3, 3, 2], [6]]"),
+        "{}",
+        message
+    );
+}
+
+/// `Softmax(MatMul(Flatten(GlobalAveragePool(MaxPool(Relu(Conv(x)))))))`: every node but the
+/// Relu and the Flatten has a reference implementation.
+fn network() -> (ModelProto, TensorProto) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str, attributes| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            attributes,
+        )
+    };
+    let tensor = |values: ArrayD<f32>, name: &str| ndarray_to_tensor_proto::<f32>(values, name);
+
+    let nodes = vec![
+        node(
+            "Conv",
+            vec!["x", "conv_w", "conv_b"],
+            "conv",
+            vec![
+                ints("kernel_shape", &[3, 3]),
+                ints("pads", &[1, 1, 1, 1]),
+                ints("dilations", &[2, 1]),
+            ],
+        ),
+        node("Relu", vec!["conv"], "relu", vec![]),
+        node(
+            "MaxPool",
+            vec!["relu"],
+            "pool",
+            vec![ints("kernel_shape", &[2, 2]), ints("strides", &[2, 2])],
+        ),
+        node("GlobalAveragePool", vec!["pool"], "gap", vec![]),
+        node(
+            "Flatten",
+            vec!["gap"],
+            "flat",
+            vec![make_attribute("axis", Attribute::<String>::Int(1))],
+        ),
+        node("MatMul", vec!["flat", "fc_w"], "logits", vec![]),
+        node("Softmax", vec!["logits"], "y", vec![]),
+    ];
+    let initializers = vec![
+        tensor(random_tensor(&mut rng, &[8, 3, 3, 3]), "conv_w").unwrap(),
+        tensor(random_tensor(&mut rng, &[8]), "conv_b").unwrap(),
+        tensor(random_tensor(&mut rng, &[8, 5]), "fc_w").unwrap(),
+    ];
+    let value_info = |name: &str, shape: &[i64]| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            shape.iter().map(|&dim| Dimension::Value(dim)).collect(),
+            None,
+        )
+    };
+    let graph = make_graph(
+        nodes,
+        "network",
+        vec![value_info("x", &[2, 3, 12, 12])],
+        vec![value_info("y", &[2, 5])],
+        initializers,
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+    // Small inputs, so that the Softmax isn't saturated.
+    let x = random_tensor(&mut rng, &[2, 3, 12, 12]).mapv(|value| value / 100.0);
+    (model, tensor(x, "x").unwrap())
+}
+
+#[test]
+fn reference_kernels_at_run_time() {
+    let (model, x) = network();
+    let run = |reference_kernels| {
+        let options = RunOptions {
+            reference_kernels,
+            ..RunOptions::default()
+        };
+        let outputs = run_with_inputs(&model, vec![x.clone()], &options).unwrap();
+        tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+    };
+
+    let optimized = run(false);
+    let reference = run(true);
+    assert_eq!(reference.shape(), [2, 5]);
+    let (atol, rtol) = KERNEL_TOLERANCE;
+    assert_tensors_close(&optimized, &reference, atol, rtol);
+}
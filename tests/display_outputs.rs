@@ -0,0 +1,73 @@
+//! Checks that displaying an output that doesn't hold class scores fails instead of panicking:
+//!
+//! ```text
+//! cargo test --features cli --test display_outputs
+//! ```
+#![cfg(feature = "cli")]
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::OnnxError;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::display::display_outputs;
+use onnx_rustime_lib::onnx_rustime::shared::{Model, RunOptions};
+
+fn options(model: Model) -> RunOptions {
+    RunOptions {
+        model,
+        ..RunOptions::default()
+    }
+}
+
+fn scores(shape: &[usize]) -> Tensor {
+    let values = ArrayD::from_shape_fn(IxDyn(shape), |index| index[shape.len() - 1] as f32);
+    Tensor::from_ndarray(values)
+}
+
+fn invalid_value(result: Result<(), OnnxError>) -> String {
+    match result {
+        Err(OnnxError::InvalidValue(message)) => message,
+        other => panic!("Expected an invalid value, got {:?}", other),
+    }
+}
+
+#[test]
+fn class_scores() {
+    display_outputs(
+        &scores(&[2, 10]),
+        Some(scores(&[2, 10])),
+        &options(Model::Mnist),
+    )
+    .unwrap();
+    display_outputs(&scores(&[1, 1000]), None, &options(Model::ResNet)).unwrap();
+}
+
+#[test]
+fn more_classes_than_labels() {
+    let message = invalid_value(display_outputs(
+        &scores(&[1, 1000]),
+        None,
+        &options(Model::Mnist),
+    ));
+    assert!(message.contains("[1, 1000]"), "{}", message);
+
+    // The expected output is checked too.
+    let message = invalid_value(display_outputs(
+        &scores(&[1, 10]),
+        Some(scores(&[1, 12])),
+        &options(Model::Mnist),
+    ));
+    assert!(message.contains("[1, 12]"), "{}", message);
+}
+
+#[test]
+fn empty_or_integer_outputs() {
+    let message = invalid_value(display_outputs(
+        &scores(&[0, 10]),
+        None,
+        &options(Model::Mnist),
+    ));
+    assert!(message.contains("[0, 10]"), "{}", message);
+
+    let integers = Tensor::from_ndarray(ArrayD::<i64>::zeros(IxDyn(&[1, 10])));
+    assert!(display_outputs(&integers, None, &options(Model::Mnist)).is_err());
+}
@@ -0,0 +1,164 @@
+//! Runs Dropout in inference mode through the executor:
+//!
+//! ```text
+//! cargo test --test dropout
+//! ```
+//!
+//! The input is passed through unchanged, whether the ratio is an attribute (before opset 12) or
+//! an input, and the optional mask output keeps every unit.
+
+use ndarray::{arr0, arr2, Array2, ArrayD};
+use onnx_rustime_lib::backend::helper::{make_graph, make_model, make_node};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+fn x() -> Array2<f32> {
+    arr2(&[[1.0, -2.0, 3.0], [-0.5, 0.25, 4.0]])
+}
+
+#[test]
+fn ratio_attribute() {
+    OpTest::new("Dropout")
+        .opset(10)
+        .input(x())
+        .attribute("ratio", 0.2f32)
+        .expect(x())
+        .check();
+}
+
+#[test]
+fn ratio_and_training_mode_inputs() {
+    op_test!("Dropout",
+             inputs: [x(), arr0(0.3f32), arr0(false)],
+             attrs: {},
+             expect: x());
+    op_test!("Dropout",
+             inputs: [x(), arr0(0.3f32)],
+             attrs: {},
+             expect: x());
+}
+
+#[test]
+fn mask_output() {
+    let mask = Array2::from_elem((2, 3), true);
+    OpTest::new("Dropout")
+        .opset(13)
+        .input(x())
+        .input(arr0(0.5f32))
+        .input(arr0(false))
+        .expect(x())
+        .expect(mask.clone())
+        .check();
+    OpTest::new("Dropout")
+        .opset(10)
+        .input(x())
+        .attribute("ratio", 0.5f32)
+        .expect(x())
+        .expect(mask)
+        .check();
+}
+
+fn value_info(name: &str) -> ValueInfoProto {
+    let mut value_info = ValueInfoProto::new();
+    value_info.set_name(name.to_string());
+    value_info
+}
+
+fn gemm(inputs: Vec<&str>, output: &str) -> NodeProto {
+    make_node("Gemm", inputs, vec![output], None, None, None, vec![])
+}
+
+/// `Gemm(Gemm(x, w1, b1), w2)`, with the nodes in `between` after the first Gemm, which
+/// computes `h`.
+fn two_gemms(between: Vec<NodeProto>, second_input: &str, outputs: &[&str]) -> ModelProto {
+    let mut nodes = vec![gemm(vec!["x", "w1", "b1"], "h")];
+    nodes.extend(between);
+    nodes.push(gemm(vec![second_input, "w2"], "y"));
+
+    let initializers = vec![
+        ndarray_to_tensor_proto::<f32>(
+            arr2(&[
+                [0.5f32, -1.0, 2.0, 0.0],
+                [1.5, 0.5, -0.5, 1.0],
+                [-1.0, 2.0, 0.25, -0.75],
+            ])
+            .into_dyn(),
+            "w1",
+        )
+        .unwrap(),
+        ndarray_to_tensor_proto::<f32>(arr2(&[[0.1f32, -0.2, 0.3, 0.4]]).into_dyn(), "b1").unwrap(),
+        ndarray_to_tensor_proto::<f32>(
+            arr2(&[[1.0f32, -1.0], [0.5, 2.0], [-2.0, 0.5], [0.75, 1.5]]).into_dyn(),
+            "w2",
+        )
+        .unwrap(),
+        ndarray_to_tensor_proto::<f32>(arr0(0.5f32).into_dyn(), "ratio").unwrap(),
+    ];
+    let graph = make_graph(
+        nodes,
+        "two_gemms",
+        vec![value_info("x")],
+        outputs.iter().map(|name| value_info(name)).collect(),
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(model: &ModelProto) -> Vec<ArrayD<f32>> {
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x().into_dyn(), "x").unwrap()];
+    run_with_inputs(model, inputs, &RunOptions::default())
+        .unwrap()
+        .iter()
+        .filter(|output| output.get_name() == "y")
+        .map(|output| tensor_proto_to_ndarray::<f32>(output).unwrap())
+        .collect()
+}
+
+#[test]
+fn between_two_gemms() {
+    let expected = run(&two_gemms(vec![], "h", &["y"]));
+    assert_eq!(expected.len(), 1);
+
+    let dropout = |outputs: Vec<&str>| {
+        make_node(
+            "Dropout",
+            vec!["h", "ratio"],
+            outputs,
+            None,
+            None,
+            None,
+            vec![],
+        )
+    };
+    // Without the mask, the output of the first Gemm is forwarded to the second one.
+    let without_mask = two_gemms(vec![dropout(vec!["d"])], "d", &["y"]);
+    assert_eq!(run(&without_mask), expected);
+
+    // With the mask, the kernel runs.
+    let with_mask = two_gemms(vec![dropout(vec!["d", "mask"])], "d", &["y", "mask"]);
+    assert_eq!(run(&with_mask), expected);
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&with_mask, inputs, &RunOptions::default()).unwrap();
+    let mask = outputs
+        .iter()
+        .find(|output| output.get_name() == "mask")
+        .unwrap();
+    assert_eq!(mask.get_dims(), &[2, 4]);
+    assert!(mask.get_int32_data().iter().all(|&value| value == 1));
+}
@@ -0,0 +1,120 @@
+//! Runs DynamicQuantizeLinear through the executor, alone and feeding a MatMulInteger:
+//!
+//! ```text
+//! cargo test --test dynamic_quantize_linear
+//! ```
+//!
+//! The range of the input is extended to include 0: an all-positive input gets a zero point of 0
+//! and an all-negative one a zero point of 255, both with the scale of their extent / 255.
+
+use ndarray::{arr0, arr1, arr2};
+use onnx_rustime_lib::backend::helper::{make_graph, make_model, make_node};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_proto::onnx_ml_proto3::{TensorProto, ValueInfoProto};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+
+#[test]
+fn mixed_signs() {
+    // The example of the specification: a range of [-3, 2].
+    op_test!("DynamicQuantizeLinear",
+             inputs: [arr1(&[0.0f32, 2.0, -3.0, -2.5, 1.34, 0.5])],
+             attrs: {},
+             expect: [
+                 arr1(&[153u8, 255, 0, 26, 221, 179]),
+                 arr0(0.019607844f32),
+                 arr0(153u8),
+             ]);
+}
+
+#[test]
+fn all_positive() {
+    op_test!("DynamicQuantizeLinear",
+             inputs: [arr1(&[1.0f32, 2.55, 0.5, 2.0])],
+             attrs: {},
+             expect: [arr1(&[100u8, 255, 50, 200]), arr0(0.01f32), arr0(0u8)]);
+}
+
+#[test]
+fn all_negative() {
+    op_test!("DynamicQuantizeLinear",
+             inputs: [arr1(&[-1.0f32, -2.55, -0.5])],
+             attrs: {},
+             expect: [arr1(&[155u8, 0, 205]), arr0(0.01f32), arr0(255u8)]);
+}
+
+#[test]
+fn zeros() {
+    // A scale of 0 doesn't divide the zeros by 0.
+    op_test!("DynamicQuantizeLinear",
+             inputs: [arr1(&[0.0f32, 0.0])],
+             attrs: {},
+             expect: [arr1(&[0u8, 0]), arr0(0.0f32), arr0(0u8)]);
+}
+
+#[test]
+fn outputs_feed_the_next_node() {
+    // x -> DynamicQuantizeLinear -> (q, scale, zero_point) -> MatMulInteger(q, identity,
+    // zero_point) -> y: every output is bound for the following nodes, or the graph outputs.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "DynamicQuantizeLinear",
+            vec!["x"],
+            vec!["q", "scale", "zero_point"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "MatMulInteger",
+            vec!["q", "w", "zero_point"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+    ];
+    let graph = make_graph(
+        nodes,
+        "dynamic_quantization",
+        vec![value_info("x"), value_info("w")],
+        vec![value_info("y"), value_info("scale")],
+        Vec::<TensorProto>::new(),
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let inputs = vec![
+        ndarray_to_tensor_proto::<f32>(arr2(&[[-1.0f32, -2.55], [-0.5, 0.0]]).into_dyn(), "x")
+            .unwrap(),
+        ndarray_to_tensor_proto::<u8>(arr2(&[[1u8, 0], [0, 1]]).into_dyn(), "w").unwrap(),
+    ];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+
+    // q = [[155, 0], [205, 255]] with a zero point of 255.
+    assert_eq!(
+        tensor_proto_to_ndarray::<i32>(&outputs[0]).unwrap(),
+        arr2(&[[-100, -255], [-50, 0]]).into_dyn()
+    );
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&outputs[1]).unwrap(),
+        arr0(2.55f32 / 255.0).into_dyn()
+    );
+}
@@ -0,0 +1,142 @@
+//! Runs Einsum through the executor:
+//!
+//! ```text
+//! cargo test --test einsum
+//! ```
+//!
+//! The expected values are computed with explicit loops.
+
+use ndarray::{arr1, arr2, Array, Array4, ArrayD, IxDyn};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+fn einsum(equation: &str) -> OpTest {
+    OpTest::new("Einsum")
+        .opset(12)
+        .attribute("equation", equation)
+}
+
+/// A tensor of the given shape with distinct values.
+fn values(shape: &[usize], offset: f32) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::from_iter((0..len).map(|i| (i as f32 * 0.37 + offset).sin()))
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+#[test]
+fn matrix_multiplication() {
+    let a = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let b = arr2(&[[1.0f32, 0.0], [0.0, 1.0], [2.0, -1.0]]);
+    einsum("ij,jk->ik")
+        .input(a.clone())
+        .input(b.clone())
+        .expect(arr2(&[[7.0f32, -1.0], [16.0, -1.0]]))
+        .check();
+    // The same product without the output, and with the transpose of the result.
+    einsum("ij,jk")
+        .input(a.clone())
+        .input(b.clone())
+        .expect(arr2(&[[7.0f32, -1.0], [16.0, -1.0]]))
+        .check();
+    einsum("ij,jk->ki")
+        .input(a)
+        .input(b)
+        .expect(arr2(&[[7.0f32, 16.0], [-1.0, -1.0]]))
+        .check();
+}
+
+#[test]
+fn batched_attention_scores() {
+    let (batch, heads, queries, keys, depth) = (2, 3, 4, 5, 6);
+    let q = values(&[batch, heads, queries, depth], 0.0);
+    let k = values(&[batch, heads, keys, depth], 1.0);
+    let expected = Array4::from_shape_fn((batch, heads, queries, keys), |(b, h, i, j)| {
+        (0..depth)
+            .map(|d| q[[b, h, i, d]] * k[[b, h, j, d]])
+            .sum::<f32>()
+    });
+    einsum("bhqd,bhkd->bhqk")
+        .input(q)
+        .input(k)
+        .expect(expected)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn trace_and_diagonal() {
+    let x = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    einsum("ii->")
+        .input(x.clone())
+        .expect(scalar(15.0f32))
+        .check();
+    einsum("ii->i")
+        .input(x)
+        .expect(arr1(&[1.0f32, 5.0, 9.0]))
+        .check();
+}
+
+#[test]
+fn outer_product_and_reductions() {
+    let a = arr1(&[1.0f32, 2.0, 3.0]);
+    let b = arr1(&[1.0f32, -1.0]);
+    einsum("i,j->ij")
+        .input(a.clone())
+        .input(b)
+        .expect(arr2(&[[1.0f32, -1.0], [2.0, -2.0], [3.0, -3.0]]))
+        .check();
+    // A dot product and the sums of the rows.
+    einsum("i,i->")
+        .input(a.clone())
+        .input(a)
+        .expect(scalar(14.0f32))
+        .check();
+    einsum("ij->i")
+        .input(arr2(&[[1.0f32, 2.0], [3.0, 4.0]]))
+        .expect(arr1(&[3.0f32, 7.0]))
+        .check();
+}
+
+#[test]
+fn three_operands() {
+    let a = values(&[2, 3], 0.0);
+    let b = values(&[3, 4], 1.0);
+    let c = values(&[4, 5], 2.0);
+    let expected = Array::from_shape_fn((2, 5), |(i, l)| {
+        let mut sum = 0.0f32;
+        for j in 0..3 {
+            for k in 0..4 {
+                sum += a[[i, j]] * b[[j, k]] * c[[k, l]];
+            }
+        }
+        sum
+    });
+    einsum("ij,jk,kl->il")
+        .input(a)
+        .input(b)
+        .input(c)
+        .expect(expected)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn unsupported_and_invalid_equations() {
+    let x = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    for (equation, message) in [
+        (
+            "...ij->...ji",
+            "ellipsis of the Einsum equation \"...ij->...ji\"",
+        ),
+        ("ij->ik", "the output label 'k' is not an input label"),
+        ("ijk->i", "doesn't match an input of shape [2, 2]"),
+        ("ij,jk->ik", "has 2 terms for 1 inputs"),
+    ] {
+        let error = einsum(equation)
+            .input(x.clone())
+            .expect(x.clone())
+            .run()
+            .unwrap_err();
+        assert!(error.contains(message), "{}: {}", equation, error);
+    }
+}
@@ -0,0 +1,147 @@
+//! Checks that invalid files and graphs give an `OnnxError`, rather than a panic:
+//!
+//! ```text
+//! cargo test --test errors
+//! ```
+//!
+//! The nodes whose only operands are initializers (folded constants, weights transposed by an
+//! adapter, ...) are run like the ones reading tensors computed by the graph.
+
+use ndarray::{arr1, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, OnnxError,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use std::path::PathBuf;
+
+/// A path in the temporary directory, unique to the test.
+fn temporary_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "onnx_rustime_errors_{}_{}",
+        std::process::id(),
+        name
+    ))
+}
+
+fn value_info(name: &str) -> ValueInfoProto {
+    let mut value_info = ValueInfoProto::new();
+    value_info.set_name(name.to_string());
+    value_info
+}
+
+/// A model running `node`, whose inputs are the `initializers`, to the output `y`.
+fn model_of_initializers(node: NodeProto, initializers: Vec<TensorProto>) -> ModelProto {
+    let graph = make_graph(
+        vec![node],
+        "initializers",
+        vec![],
+        vec![value_info("y")],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn node(op_type: &str, inputs: Vec<&str>) -> NodeProto {
+    make_node(op_type, inputs, vec!["y"], None, None, None, vec![])
+}
+
+fn run(model: &ModelProto) -> Result<Vec<TensorProto>, OnnxError> {
+    run_with_inputs(model, vec![], &RunOptions::default())
+}
+
+#[test]
+fn missing_file() {
+    let path = temporary_path("missing.onnx");
+    match OnnxParser::load_model(path.to_string_lossy().into_owned()) {
+        Err(OnnxError::Io {
+            path: error_path, ..
+        }) => {
+            assert_eq!(error_path, path.to_string_lossy())
+        }
+        result => panic!("Expected an Io error, got {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn corrupt_tensor() {
+    let path = temporary_path("corrupt.pb");
+    std::fs::write(&path, [0xff, 0xff, 0xff, 0xff, 0x0f, 0x42]).unwrap();
+    let result = OnnxParser::load_data(path.to_string_lossy().into_owned());
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        matches!(result, Err(OnnxError::ProtobufDecode { .. })),
+        "Expected a ProtobufDecode error, got {:?}",
+        result.map(|_| ())
+    );
+}
+
+#[test]
+fn unknown_operation() {
+    let x = ndarray_to_tensor_proto::<f32>(arr1(&[1.0f32]).into_dyn(), "x").unwrap();
+    let error = run(&model_of_initializers(
+        node("Frobnicate", vec!["x"]),
+        vec![x],
+    ))
+    .unwrap_err();
+    assert!(
+        matches!(error.root_cause(), OnnxError::UnsupportedOperation(_)),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn initializer_as_the_only_operand() {
+    let x = ndarray_to_tensor_proto::<f32>(arr1(&[-1.0f32, 0.5, 2.0]).into_dyn(), "x").unwrap();
+    let outputs = run(&model_of_initializers(node("Relu", vec!["x"]), vec![x])).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap(),
+        arr1(&[0.0f32, 0.5, 2.0]).into_dyn()
+    );
+}
+
+#[test]
+fn convolution_of_initializers() {
+    // A 1x1x3x3 input of ones convolved with a 1x1x2x2 kernel of ones.
+    let x = Array::from_elem(IxDyn(&[1, 1, 3, 3]), 1.0f32);
+    let w = Array::from_elem(IxDyn(&[1, 1, 2, 2]), 1.0f32);
+    let initializers = vec![
+        ndarray_to_tensor_proto::<f32>(x, "x").unwrap(),
+        ndarray_to_tensor_proto::<f32>(w, "w").unwrap(),
+    ];
+    let outputs = run(&model_of_initializers(
+        node("Conv", vec!["x", "w"]),
+        initializers,
+    ))
+    .unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap(),
+        ArrayD::from_elem(IxDyn(&[1, 1, 2, 2]), 4.0f32)
+    );
+}
+
+#[test]
+fn missing_data() {
+    let error = run(&model_of_initializers(node("Relu", vec![""]), vec![])).unwrap_err();
+    assert!(
+        matches!(error.root_cause(), OnnxError::MissingInput(_)),
+        "{}",
+        error
+    );
+}
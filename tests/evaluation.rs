@@ -0,0 +1,246 @@
+//! Evaluates a synthetic classifier on five generated images and a fake ground truth:
+//!
+//! ```text
+//! cargo test --features image --test evaluation
+//! ```
+//!
+//! The classifier averages the channels of the image and scores ten classes: classes 0, 1 and 2
+//! are the red, green and blue channels, the other classes score 0. On a plain color image the
+//! ranking is the channel of the color, then classes 3 to 9, so the expected accuracies are known
+//! in advance.
+#![cfg(feature = "image")]
+
+use image::{Rgb, RgbImage};
+use ndarray::Array2;
+use onnx_rustime_lib::backend::evaluation::{evaluate, EvaluationOptions};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::TensorProto_DataType;
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CLASSES: usize = 10;
+
+const RED: Rgb<u8> = Rgb([255, 0, 0]);
+const GREEN: Rgb<u8> = Rgb([0, 255, 0]);
+const BLUE: Rgb<u8> = Rgb([0, 0, 255]);
+
+/// The images and their classes, in the format of `val_map.txt`: empty lines, comments and comma
+/// separated pairs included.
+const GROUND_TRUTH: &str = "\
+# file class
+red_0.png 0
+green_0.png 1
+
+blue_0.png 0
+red_1.png,4
+green_1.png 1
+";
+
+fn classifier() -> Session {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str, attributes| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            attributes,
+        )
+    };
+    let weights = Array2::from_shape_fn((3, CLASSES), |(channel, class)| {
+        (channel == class) as u8 as f32
+    });
+
+    let graph = make_graph(
+        vec![
+            node("GlobalAveragePool", vec!["data"], "pooled", vec![]),
+            node(
+                "Flatten",
+                vec!["pooled"],
+                "channels",
+                vec![make_attribute("axis", Attribute::<String>::Int(1))],
+            ),
+            node("MatMul", vec!["channels", "weights"], "scores", vec![]),
+        ],
+        "colors",
+        vec![make_tensor_value_info(
+            "data",
+            TensorProto_DataType::FLOAT,
+            vec![
+                Dimension::Param("N".to_string()),
+                Dimension::Value(3),
+                Dimension::Value(224),
+                Dimension::Value(224),
+            ],
+            None,
+        )],
+        vec![make_tensor_value_info(
+            "scores",
+            TensorProto_DataType::FLOAT,
+            vec![
+                Dimension::Param("N".to_string()),
+                Dimension::Value(CLASSES as i64),
+            ],
+            None,
+        )],
+        vec![ndarray_to_tensor_proto::<f32>(weights.into_dyn(), "weights").unwrap()],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+    Session::from_model(model).unwrap()
+}
+
+/// Writes the images and the ground truth to a new directory.
+fn fixture(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "onnx_rustime_evaluation_{}_{}",
+        test,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let images = [
+        ("red_0.png", RED),
+        ("green_0.png", GREEN),
+        ("blue_0.png", BLUE),
+        ("red_1.png", RED),
+        ("green_1.png", GREEN),
+    ];
+    for (file, color) in images {
+        // Not square, so that the images are resized and cropped.
+        RgbImage::from_pixel(12, 8, color)
+            .save(dir.join(file))
+            .unwrap();
+    }
+    fs::write(dir.join("val_map.txt"), GROUND_TRUTH).unwrap();
+    dir
+}
+
+fn options(batch_size: usize, limit: Option<usize>) -> EvaluationOptions {
+    EvaluationOptions { batch_size, limit }
+}
+
+#[test]
+fn accuracies() {
+    let dir = fixture("accuracies");
+    let session = classifier();
+
+    // The batches don't change the predictions, the last one is incomplete.
+    for batch_size in [1, 2, 5, 8] {
+        let report = evaluate(
+            &session,
+            &dir,
+            &dir.join("val_map.txt"),
+            options(batch_size, None),
+        )
+        .unwrap();
+
+        // blue_0 is predicted as 2, outside the top 5 [2, 3, 4, 5, 6] of 0; red_1 is predicted as
+        // 0, but 4 is in the top 5 [0, 3, 4, 5, 6].
+        assert_eq!(report.images, 5);
+        assert_eq!((report.top1_correct, report.top5_correct), (3, 4));
+        assert!((report.top1_accuracy() - 0.6).abs() < 1e-12);
+        assert!((report.top5_accuracy() - 0.8).abs() < 1e-12);
+
+        let per_class: Vec<(usize, usize, usize, usize)> = report
+            .per_class
+            .iter()
+            .map(|(&class, accuracy)| {
+                (
+                    class,
+                    accuracy.images,
+                    accuracy.top1_correct,
+                    accuracy.top5_correct,
+                )
+            })
+            .collect();
+        assert_eq!(per_class, [(0, 2, 1, 1), (1, 2, 2, 2), (4, 1, 0, 1)]);
+
+        assert_eq!(
+            report.misclassified_csv(),
+            "file,expected,predicted\nblue_0.png,0,2\nred_1.png,4,0\n"
+        );
+        assert_eq!(report.latency.batches, 5usize.div_ceil(batch_size));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn limit() {
+    let dir = fixture("limit");
+    let report = evaluate(
+        &classifier(),
+        &dir,
+        &dir.join("val_map.txt"),
+        options(2, Some(3)),
+    )
+    .unwrap();
+
+    // The comments and the empty line don't count.
+    assert_eq!(report.images, 3);
+    assert_eq!((report.top1_correct, report.top5_correct), (2, 2));
+    assert_eq!(report.misclassified.len(), 1);
+    assert_eq!(report.misclassified[0].file, "blue_0.png");
+    assert_eq!(report.latency.batches, 2);
+
+    let report = evaluate(
+        &classifier(),
+        &dir,
+        &dir.join("val_map.txt"),
+        options(2, Some(0)),
+    )
+    .unwrap();
+    assert_eq!(report.images, 0);
+    assert_eq!(report.top1_accuracy(), 0.0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_evaluations() {
+    let dir = fixture("invalid");
+    let session = classifier();
+    let error = |ground_truth: &Path, batch_size: usize| {
+        evaluate(&session, &dir, ground_truth, options(batch_size, None))
+            .unwrap_err()
+            .to_string()
+    };
+
+    let message = error(&dir.join("val_map.txt"), 0);
+    assert!(message.contains("batch size"), "{}", message);
+
+    fs::write(dir.join("bad_class.txt"), "red_0.png 0\nred_1.png red\n").unwrap();
+    let message = error(&dir.join("bad_class.txt"), 1);
+    assert!(
+        message.contains("'red'") && message.contains("line 2"),
+        "{}",
+        message
+    );
+
+    fs::write(dir.join("missing_class.txt"), "red_0.png\n").unwrap();
+    let message = error(&dir.join("missing_class.txt"), 1);
+    assert!(message.contains("line 1"), "{}", message);
+
+    fs::write(dir.join("missing_image.txt"), "purple.png 3\n").unwrap();
+    let message = error(&dir.join("missing_image.txt"), 1);
+    assert!(message.contains("purple.png"), "{}", message);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
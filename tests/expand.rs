@@ -0,0 +1,84 @@
+//! Runs Expand through the executor:
+//!
+//! ```text
+//! cargo test --test expand
+//! ```
+//!
+//! The broadcast goes both ways, as in NumPy: a dimension of 1 of the target shape keeps the
+//! dimension of the input.
+
+use ndarray::{arr1, arr2, arr3, Array, Array3};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn stretches_the_dimensions_of_1() {
+    OpTest::new("Expand")
+        .opset(13)
+        .input(arr2(&[[1.0f32], [2.0], [3.0]]))
+        .input(arr1(&[3i64, 4]))
+        .expect(arr2(&[
+            [1.0f32, 1.0, 1.0, 1.0],
+            [2.0, 2.0, 2.0, 2.0],
+            [3.0, 3.0, 3.0, 3.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn prepends_the_missing_dimensions() {
+    let expected: Array3<f32> = Array::from_shape_fn((2, 3, 4), |(_, i, _)| i as f32 + 1.0);
+    OpTest::new("Expand")
+        .opset(13)
+        .input(arr2(&[[1.0f32], [2.0], [3.0]]))
+        .input(arr1(&[2i64, 3, 4]))
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn scalar_to_any_shape() {
+    OpTest::new("Expand")
+        .opset(13)
+        .input(arr1(&[7i64]).into_shape(()).unwrap())
+        .input(arr1(&[2i64, 1, 3]))
+        .expect(arr3(&[[[7i64, 7, 7]], [[7, 7, 7]]]))
+        .check();
+    // An empty shape leaves the scalar as is.
+    OpTest::new("Expand")
+        .opset(13)
+        .input(arr1(&[7.0f32]).into_shape(()).unwrap())
+        .input(arr1::<i64>(&[]))
+        .expect(arr1(&[7.0f32]).into_shape(()).unwrap())
+        .check();
+}
+
+#[test]
+fn broadcasts_both_ways() {
+    // The output is [3, 4], not the target [1, 4].
+    OpTest::new("Expand")
+        .opset(13)
+        .input(arr2(&[[true], [false], [true]]))
+        .input(arr1(&[1i64, 4]))
+        .expect(arr2(&[
+            [true, true, true, true],
+            [false, false, false, false],
+            [true, true, true, true],
+        ]))
+        .check();
+}
+
+#[test]
+fn mismatched_dimensions() {
+    let error = OpTest::new("Expand")
+        .opset(13)
+        .input(arr2(&[[1.0f32], [2.0], [3.0]]))
+        .input(arr1(&[2i64, 4]))
+        .expect(arr2(&[[0.0f32; 4]; 2]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Cannot expand a tensor of shape [3, 1] to the shape [2, 4]"),
+        "{}",
+        error
+    );
+}
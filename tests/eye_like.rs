@@ -0,0 +1,75 @@
+//! Runs EyeLike through the executor:
+//!
+//! ```text
+//! cargo test --test eye_like
+//! ```
+//!
+//! The expected matrices are the ones of `numpy.eye`, with the same `k`.
+
+use ndarray::{arr2, Array2};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn above_the_diagonal() {
+    // A wide matrix with k = 1: the ones stop at the last column.
+    OpTest::new("EyeLike")
+        .opset(9)
+        .input(Array2::<f32>::from_elem((3, 5), 7.0))
+        .attribute("k", 1i64)
+        .expect(arr2(&[
+            [0.0f32, 1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn below_the_diagonal() {
+    // A tall matrix with k = -2: the ones start at the third row.
+    OpTest::new("EyeLike")
+        .opset(9)
+        .input(Array2::<f32>::zeros((5, 3)))
+        .attribute("k", -2i64)
+        .expect(arr2(&[
+            [0.0f32, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn dtype_override() {
+    // An INT64 identity (dtype 7) from a FLOAT input.
+    OpTest::new("EyeLike")
+        .opset(9)
+        .input(Array2::<f32>::zeros((3, 3)))
+        .attribute("dtype", 7i64)
+        .expect(Array2::<i64>::eye(3))
+        .check();
+}
+
+#[test]
+fn type_of_the_input() {
+    // Without dtype, the type of the input; a diagonal out of the matrix is all zeros.
+    OpTest::new("EyeLike")
+        .opset(9)
+        .input(Array2::<i32>::ones((2, 3)))
+        .attribute("k", 3i64)
+        .expect(Array2::<i32>::zeros((2, 3)))
+        .check();
+}
+
+#[test]
+fn not_a_matrix() {
+    let error = OpTest::new("EyeLike")
+        .opset(9)
+        .input(Array2::<f32>::zeros((2, 2)).into_shape(4).unwrap())
+        .expect(Array2::<f32>::zeros((2, 2)))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("2-D"), "{}", error);
+}
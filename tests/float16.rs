@@ -0,0 +1,319 @@
+//! Converts FLOAT models to FLOAT16 and runs them against the original ones:
+//!
+//! ```text
+//! cargo test --test float16
+//! ```
+//!
+//! The test model is a small classifier (Conv, BatchNormalization, Relu, GlobalAveragePool,
+//! Flatten and Gemm) with random weights. ResNet is also checked, by an ignored test run once the
+//! models are pulled.
+
+mod common;
+
+use half::f16;
+use ndarray::ArrayD;
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::{convert_float_to_float16, Float16Config};
+use protobuf::Message;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+
+const RESNET: &str = "models/resnet18-v2-7/resnet18-v2-7.onnx";
+const RESNET_INPUT: &str = "models/resnet18-v2-7/test_data_set_0/input_0.pb";
+
+const CHANNELS: usize = 16;
+const CLASSES: usize = 1000;
+
+fn initializer(rng: &mut StdRng, name: &str, shape: &[usize], scale: f32) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(random_tensor(rng, shape).mapv(|x| x * scale), name).unwrap()
+}
+
+/// A classifier of [1, 3, 8, 8] images into `CLASSES` classes.
+fn classifier() -> ModelProto {
+    let mut rng = StdRng::seed_from_u64(219);
+    let mut variance = initializer(&mut rng, "variance", &[CHANNELS], 1.0);
+    variance = ndarray_to_tensor_proto::<f32>(
+        tensor_proto_to_ndarray::<f32>(&variance)
+            .unwrap()
+            .mapv(|x| x.abs() + 0.5),
+        "variance",
+    )
+    .unwrap();
+    let initializers = vec![
+        initializer(&mut rng, "w", &[CHANNELS, 3, 3, 3], 0.5),
+        initializer(&mut rng, "b", &[CHANNELS], 0.1),
+        initializer(&mut rng, "scale", &[CHANNELS], 1.0),
+        initializer(&mut rng, "bias", &[CHANNELS], 0.1),
+        initializer(&mut rng, "mean", &[CHANNELS], 0.1),
+        variance,
+        initializer(&mut rng, "fc_w", &[CHANNELS, CLASSES], 0.5),
+        initializer(&mut rng, "fc_b", &[CLASSES], 0.1),
+    ];
+
+    let nodes = vec![
+        make_node(
+            "Conv",
+            vec!["x", "w", "b"],
+            vec!["conv"],
+            Some("conv"),
+            None,
+            None,
+            vec![make_attribute(
+                "pads",
+                Attribute::<String>::Ints(vec![1; 4]),
+            )],
+        ),
+        make_node(
+            "BatchNormalization",
+            vec!["conv", "scale", "bias", "mean", "variance"],
+            vec!["normalized"],
+            Some("normalization"),
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "Relu",
+            vec!["normalized"],
+            vec!["relu"],
+            Some("relu"),
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "GlobalAveragePool",
+            vec!["relu"],
+            vec!["pooled"],
+            Some("pool"),
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "Flatten",
+            vec!["pooled"],
+            vec!["features"],
+            Some("flatten"),
+            None,
+            None,
+            vec![],
+        ),
+        make_node(
+            "Gemm",
+            vec!["features", "fc_w", "fc_b"],
+            vec!["y"],
+            Some("fc"),
+            None,
+            None,
+            vec![],
+        ),
+    ];
+    let dims = |dims: &[i64]| dims.iter().map(|&dim| Dimension::Value(dim)).collect();
+    let graph = make_graph(
+        nodes,
+        "classifier",
+        vec![make_tensor_value_info(
+            "x",
+            TensorProto_DataType::FLOAT,
+            dims(&[1, 3, 8, 8]),
+            None,
+        )],
+        vec![make_tensor_value_info(
+            "y",
+            TensorProto_DataType::FLOAT,
+            dims(&[1, CLASSES as i64]),
+            None,
+        )],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn image() -> TensorProto {
+    let mut rng = StdRng::seed_from_u64(0);
+    ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[1, 3, 8, 8]), "x").unwrap()
+}
+
+fn run(model: &ModelProto, input: TensorProto) -> ArrayD<f32> {
+    let outputs = run_with_inputs(model, vec![input], &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+/// The indices of the 5 largest values of a [1, classes] output, the largest first.
+fn top_5(output: &ArrayD<f32>) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..output.len()).collect();
+    let values: Vec<f32> = output.iter().copied().collect();
+    indices.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    indices.truncate(5);
+    indices
+}
+
+fn find_initializer<'a>(model: &'a ModelProto, name: &str) -> &'a TensorProto {
+    model
+        .get_graph()
+        .get_initializer()
+        .iter()
+        .find(|initializer| initializer.get_name() == name)
+        .unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let model = classifier();
+    let converted = convert_float_to_float16(&model, &Float16Config::default()).unwrap();
+
+    let path =
+        std::env::temp_dir().join(format!("onnx_rustime_float16_{}.onnx", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    OnnxParser::save_model(&converted, path.clone()).unwrap();
+    let loaded = OnnxParser::load_model(path.clone()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, converted);
+
+    // The weights are the f32 values rounded to the nearest f16.
+    for name in ["w", "b", "fc_w", "fc_b"] {
+        assert_eq!(
+            find_initializer(&loaded, name).get_data_type(),
+            TensorProto_DataType::FLOAT16 as i32,
+            "{}",
+            name
+        );
+        let original = find_initializer(&model, name);
+        let converted = find_initializer(&loaded, name);
+        assert_eq!(converted.get_dims(), original.get_dims());
+        let expected = tensor_proto_to_ndarray::<f32>(original)
+            .unwrap()
+            .mapv(|x| f32::from(f16::from_f32(x)));
+        assert_eq!(tensor_proto_to_ndarray::<f32>(converted).unwrap(), expected);
+    }
+
+    // The parameters of BatchNormalization are only read by the blocked node.
+    for name in ["scale", "bias", "mean", "variance"] {
+        assert_eq!(
+            find_initializer(&loaded, name).get_data_type(),
+            TensorProto_DataType::FLOAT as i32,
+            "{}",
+            name
+        );
+    }
+
+    // The interface of the model is unchanged.
+    let graph = loaded.get_graph();
+    for value_info in graph.get_input().iter().chain(graph.get_output()) {
+        assert_eq!(
+            value_info
+                .get_field_type()
+                .get_tensor_type()
+                .get_elem_type(),
+            TensorProto_DataType::FLOAT as i32
+        );
+    }
+
+    // About half the size: the weights dominate.
+    let size = model.write_to_bytes().unwrap().len();
+    let converted_size = loaded.write_to_bytes().unwrap().len();
+    assert!(
+        converted_size * 10 < size * 6,
+        "{} bytes converted to {} bytes",
+        size,
+        converted_size
+    );
+}
+
+#[test]
+fn blocked_operations_run_in_single_precision() {
+    let converted = convert_float_to_float16(&classifier(), &Float16Config::default()).unwrap();
+    let nodes = converted.get_graph().get_node();
+    let position = |name: &str| {
+        nodes
+            .iter()
+            .position(|node| node.get_name() == name)
+            .unwrap()
+    };
+
+    let normalization = &nodes[position("normalization")];
+    let cast_to = |node_output: &str| {
+        let cast = nodes
+            .iter()
+            .find(|node| node.get_op_type() == "Cast" && node.get_output()[0] == node_output)
+            .unwrap();
+        cast.get_attribute()[0].get_i()
+    };
+    assert_eq!(
+        cast_to(&normalization.get_input()[0]),
+        TensorProto_DataType::FLOAT as i64
+    );
+    let output = &normalization.get_output()[0];
+    let cast = nodes
+        .iter()
+        .find(|node| node.get_op_type() == "Cast" && &node.get_input()[0] == output)
+        .unwrap();
+    assert_eq!(
+        cast.get_attribute()[0].get_i(),
+        TensorProto_DataType::FLOAT16 as i64
+    );
+    assert_eq!(cast.get_output()[0], "normalized");
+}
+
+#[test]
+fn accuracy_against_the_float_model() {
+    let model = classifier();
+    let converted = convert_float_to_float16(&model, &Float16Config::default()).unwrap();
+
+    let expected = run(&model, image());
+    let output = run(&converted, image());
+    assert_eq!(output.shape(), &[1, CLASSES]);
+
+    let scale = expected.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+    for (index, (x, y)) in output.iter().zip(expected.iter()).enumerate() {
+        assert!(
+            (x - y).abs() <= 1e-2 * scale,
+            "class {}: {} in half precision, {} in single precision",
+            index,
+            x,
+            y
+        );
+    }
+    assert_eq!(top_5(&output), top_5(&expected));
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn resnet_top_5() {
+    common::require_model(RESNET);
+
+    let model = OnnxParser::load_model(RESNET.to_string()).unwrap();
+    let converted = convert_float_to_float16(&model, &Float16Config::default()).unwrap();
+    let input = OnnxParser::load_tensor_proto(RESNET_INPUT.to_string()).unwrap();
+
+    assert_eq!(
+        top_5(&run(&converted, input.clone())),
+        top_5(&run(&model, input))
+    );
+    let size = model.write_to_bytes().unwrap().len();
+    let converted_size = converted.write_to_bytes().unwrap().len();
+    assert!(converted_size * 10 < size * 6);
+}
@@ -0,0 +1,156 @@
+//! Checks the numpy-style formatting of the tensors:
+//!
+//! ```text
+//! cargo test --test format_tensor
+//! ```
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::format::{format_tensor, tensor_stats, FormatOptions};
+use onnx_rustime_lib::backend::tensor::Tensor;
+
+fn array<T>(shape: &[usize], values: Vec<T>) -> ArrayD<T> {
+    ArrayD::from_shape_vec(IxDyn(shape), values).unwrap()
+}
+
+#[test]
+fn summarizes_1d_tensors() {
+    let values = array(&[2000], (0..2000i64).collect());
+
+    assert_eq!(
+        format_tensor(&values, &FormatOptions::default()),
+        "[   0,    1,    2, ..., 1997, 1998, 1999]"
+    );
+}
+
+#[test]
+fn wraps_1d_tensors_at_the_line_width() {
+    let values = array(&[20], (0..20).map(|value| value as f32 * 0.5).collect());
+    let options = FormatOptions {
+        line_width: 40,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        format_tensor(&values, &options),
+        "[0.0000, 0.5000, 1.0000, 1.5000, 2.0000,\n \
+         2.5000, 3.0000, 3.5000, 4.0000, 4.5000,\n \
+         5.0000, 5.5000, 6.0000, 6.5000, 7.0000,\n \
+         7.5000, 8.0000, 8.5000, 9.0000, 9.5000]"
+    );
+}
+
+#[test]
+fn summarizes_2d_tensors() {
+    let values = array(&[100, 100], (0..10000i32).collect());
+
+    assert_eq!(
+        format_tensor(&values, &FormatOptions::default()),
+        "[[   0,    1,    2, ...,   97,   98,   99],\n \
+         [ 100,  101,  102, ...,  197,  198,  199],\n \
+         [ 200,  201,  202, ...,  297,  298,  299],\n \
+         ...,\n \
+         [9700, 9701, 9702, ..., 9797, 9798, 9799],\n \
+         [9800, 9801, 9802, ..., 9897, 9898, 9899],\n \
+         [9900, 9901, 9902, ..., 9997, 9998, 9999]]"
+    );
+}
+
+#[test]
+fn summarizes_4d_tensors() {
+    // Only the axes longer than twice the edge items are summarized.
+    let values = array(
+        &[1, 2, 10, 100],
+        (0..2000).map(|value| value as f32).collect(),
+    );
+    let options = FormatOptions {
+        precision: 0,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        format_tensor(&values, &options),
+        "[[[[   0,    1,    2, ...,   97,   98,   99],\n   \
+         [ 100,  101,  102, ...,  197,  198,  199],\n   \
+         [ 200,  201,  202, ...,  297,  298,  299],\n   \
+         ...,\n   \
+         [ 700,  701,  702, ...,  797,  798,  799],\n   \
+         [ 800,  801,  802, ...,  897,  898,  899],\n   \
+         [ 900,  901,  902, ...,  997,  998,  999]],\n\n  \
+         [[1000, 1001, 1002, ..., 1097, 1098, 1099],\n   \
+         [1100, 1101, 1102, ..., 1197, 1198, 1199],\n   \
+         [1200, 1201, 1202, ..., 1297, 1298, 1299],\n   \
+         ...,\n   \
+         [1700, 1701, 1702, ..., 1797, 1798, 1799],\n   \
+         [1800, 1801, 1802, ..., 1897, 1898, 1899],\n   \
+         [1900, 1901, 1902, ..., 1997, 1998, 1999]]]]"
+    );
+}
+
+#[test]
+fn full_prints_every_value() {
+    let values = array(&[2000], (0..2000i64).collect());
+    let options = FormatOptions {
+        full: true,
+        ..Default::default()
+    };
+
+    let formatted = format_tensor(&values, &options);
+    assert!(!formatted.contains("..."));
+    assert_eq!(formatted.matches(',').count(), 1999);
+}
+
+#[test]
+fn switches_to_scientific_notation() {
+    let values = array(&[2, 3], vec![1.0f32, f32::NAN, -3.0, 4.0, 5.0e9, 6.0]);
+
+    assert_eq!(
+        format_tensor(&values, &FormatOptions::default()),
+        "[[ 1.0000e+00,         NaN, -3.0000e+00],\n \
+         [ 4.0000e+00,  5.0000e+09,  6.0000e+00]]"
+    );
+}
+
+#[test]
+fn stats_skip_nan_values() {
+    let values = array(&[1, 5], vec![1.0f32, f32::NAN, 2.0, 3.0, f32::NAN]);
+
+    assert_eq!(
+        tensor_stats(&values, 4),
+        "float32[1, 5] min 1.0000 max 3.0000 mean 2.0000 std 0.8165 NaN 2"
+    );
+}
+
+#[test]
+fn tensors_format_with_their_stats() {
+    let tensor = Tensor::from_ndarray(array(&[2, 2], vec![1i64, -2, 3, 4]));
+    let options = FormatOptions {
+        stats: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        tensor.format(&options),
+        "int64[2, 2] min -2.0000 max 4.0000 mean 1.5000 std 2.2913 NaN 0\n\
+         [[ 1, -2],\n \
+         [ 3,  4]]"
+    );
+}
+
+#[test]
+fn booleans_and_bytes() {
+    let mask = Tensor::from_ndarray(array(&[3], vec![true, false, true]));
+    let options = FormatOptions {
+        stats: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        mask.format(&options),
+        "bool[3] min 0.0000 max 1.0000 mean 0.6667 std 0.4714 NaN 0\n[ True, False,  True]"
+    );
+
+    let bytes = Tensor::from_ndarray(array(&[2, 2], vec![0u8, 255, 128, 7]));
+    assert_eq!(
+        bytes.format(&FormatOptions::default()),
+        "[[  0, 255],\n [128,   7]]"
+    );
+}
@@ -0,0 +1,121 @@
+//! Runs Gather through the executor:
+//!
+//! ```text
+//! cargo test --test gather
+//! ```
+//!
+//! The data is a [3, 4] matrix holding `4 * row + column`, so that every value tells its position
+//! in the data.
+
+use ndarray::{arr0, arr1, arr2, arr3, Array, Array2};
+use onnx_rustime_lib::backend::helper::{make_attribute, make_node, Attribute, OnnxError};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::gather;
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::OpTest;
+
+fn data() -> Array2<f32> {
+    Array::range(0.0, 12.0, 1.0).into_shape((3, 4)).unwrap()
+}
+
+#[test]
+fn rows() {
+    op_test!("Gather",
+             inputs: [data(), arr1(&[2i64, 0])],
+             attrs: {},
+             expect: arr2(&[[8.0f32, 9.0, 10.0, 11.0], [0.0, 1.0, 2.0, 3.0]]));
+    // Negative indices count from the end, and the same row can be taken twice.
+    op_test!("Gather",
+             inputs: [data(), arr1(&[-1i64, 1, -1])],
+             attrs: { axis: 0i64 },
+             expect: arr2(&[[8.0f32, 9.0, 10.0, 11.0],
+                            [4.0, 5.0, 6.0, 7.0],
+                            [8.0, 9.0, 10.0, 11.0]]));
+}
+
+#[test]
+fn columns() {
+    op_test!("Gather",
+             inputs: [data(), arr1(&[3i64, 1])],
+             attrs: { axis: 1i64 },
+             expect: arr2(&[[3.0f32, 1.0], [7.0, 5.0], [11.0, 9.0]]));
+    op_test!("Gather",
+             inputs: [data(), arr1(&[0i64])],
+             attrs: { axis: -1i64 },
+             expect: arr2(&[[0.0f32], [4.0], [8.0]]));
+}
+
+#[test]
+fn scalar_index_removes_the_axis() {
+    op_test!("Gather",
+             inputs: [data(), arr0(1i64)],
+             attrs: {},
+             expect: arr1(&[4.0f32, 5.0, 6.0, 7.0]));
+    op_test!("Gather",
+             inputs: [data(), arr0(-2i64)],
+             attrs: { axis: 1i64 },
+             expect: arr1(&[2.0f32, 6.0, 10.0]));
+    // The batch size out of the INT64 output of a Shape.
+    op_test!("Gather",
+             inputs: [arr1(&[8i64, 3, 224, 224]), arr0(0i64)],
+             attrs: {},
+             expect: arr0(8i64));
+}
+
+#[test]
+fn index_tensor_of_rank_2() {
+    // An embedding lookup: a [2, 2] batch of token ids gives a [2, 2, 4] tensor.
+    op_test!("Gather",
+             inputs: [data(), arr2(&[[0i64, 2], [1, 1]])],
+             attrs: {},
+             expect: arr3(&[[[0.0f32, 1.0, 2.0, 3.0], [8.0, 9.0, 10.0, 11.0]],
+                            [[4.0, 5.0, 6.0, 7.0], [4.0, 5.0, 6.0, 7.0]]]));
+    // Along axis 1, the [3] rows become [3, 2, 2].
+    op_test!("Gather",
+             inputs: [data(), arr2(&[[0i64, 3], [1, 2]])],
+             attrs: { axis: 1i64 },
+             expect: arr3(&[[[0.0f32, 3.0], [1.0, 2.0]],
+                            [[4.0, 7.0], [5.0, 6.0]],
+                            [[8.0, 11.0], [9.0, 10.0]]]));
+}
+
+#[test]
+fn int32_indices() {
+    OpTest::new("Gather")
+        .input(data())
+        .input(arr1(&[1i32]))
+        .expect(arr2(&[[4.0f32, 5.0, 6.0, 7.0]]))
+        .check();
+}
+
+#[test]
+fn out_of_range() {
+    let tensor = ndarray_to_tensor_proto::<f32>(data().into_dyn(), "data").unwrap();
+    for (index, axis) in [(3i64, 0i64), (-4, 0), (4, 1), (-5, -1)] {
+        let indices = ndarray_to_tensor_proto::<i64>(arr1(&[0, index]).into_dyn(), "i").unwrap();
+        let node = make_node(
+            "Gather",
+            vec!["data", "i"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![make_attribute("axis", Attribute::<String>::Int(axis))],
+        );
+        match gather(&[&tensor, &indices], &node) {
+            Err(OnnxError::InvalidValue(message)) => {
+                assert!(message.contains("out of range"), "{}", message)
+            }
+            other => panic!("index {}: expected an error, got {:?}", index, other),
+        }
+    }
+
+    let error = OpTest::new("Gather")
+        .input(data())
+        .input(arr1(&[0i64]))
+        .attribute("axis", 2i64)
+        .expect(data())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Axis 2 is out of range"), "{}", error);
+}
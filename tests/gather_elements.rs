@@ -0,0 +1,63 @@
+//! Runs GatherElements through the executor, on the examples of the ONNX operator documentation:
+//!
+//! ```text
+//! cargo test --test gather_elements
+//! ```
+
+use ndarray::{arr2, arr3};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn along_axis_1() {
+    op_test!("GatherElements",
+             inputs: [arr2(&[[1.0f32, 2.0], [3.0, 4.0]]), arr2(&[[0i64, 0], [1, 0]])],
+             attrs: { axis: 1i64 },
+             expect: arr2(&[[1.0f32, 1.0], [4.0, 3.0]]));
+}
+
+#[test]
+fn along_axis_0() {
+    op_test!("GatherElements",
+             inputs: [arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]),
+                      arr2(&[[1i64, 2, 0], [2, 0, 0]])],
+             attrs: { axis: 0i64 },
+             expect: arr2(&[[4.0f32, 8.0, 3.0], [7.0, 2.0, 3.0]]));
+}
+
+#[test]
+fn negative_indices() {
+    op_test!("GatherElements",
+             inputs: [arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]),
+                      arr2(&[[-1i64, -2, 0], [-2, 0, 0]])],
+             attrs: { axis: 0i64 },
+             expect: arr2(&[[7.0f32, 5.0, 3.0], [4.0, 2.0, 3.0]]));
+    // The negative axis of the last dimension, on INT64 data.
+    op_test!("GatherElements",
+             inputs: [arr3(&[[[1i64, 2], [3, 4]], [[5, 6], [7, 8]]]),
+                      arr3(&[[[1i64, -2, 1]], [[0, 0, -1]]])],
+             attrs: { axis: -1i64 },
+             expect: arr3(&[[[2i64, 1, 2]], [[5, 5, 6]]]));
+}
+
+#[test]
+fn invalid_indices() {
+    let data = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let error = OpTest::new("GatherElements")
+        .input(data.clone())
+        .input(arr2(&[[0i64, 2]]))
+        .attribute("axis", 1i64)
+        .expect(arr2(&[[1.0f32, 1.0]]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Index 2 is out of range"), "{}", error);
+
+    // Indices longer than the data along another axis than `axis`.
+    let error = OpTest::new("GatherElements")
+        .input(data)
+        .input(arr2(&[[0i64, 0, 0]]))
+        .expect(arr2(&[[1.0f32, 1.0, 1.0]]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("must have the rank of the data"), "{}", error);
+}
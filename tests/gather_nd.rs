@@ -0,0 +1,98 @@
+//! Runs GatherND through the executor, on the examples of the ONNX operator documentation:
+//!
+//! ```text
+//! cargo test --test gather_nd
+//! ```
+
+use ndarray::{arr1, arr2, arr3, Array, Array3};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+/// `[[[0, 1], [2, 3]], [[4, 5], [6, 7]]]`
+fn cube() -> Array3<f32> {
+    Array::range(0.0, 8.0, 1.0).into_shape((2, 2, 2)).unwrap()
+}
+
+#[test]
+fn entries_of_a_matrix() {
+    op_test!("GatherND",
+             inputs: [arr2(&[[0i64, 1], [2, 3]]), arr2(&[[0i64, 0], [1, 1]])],
+             attrs: {},
+             expect: arr1(&[0i64, 3]));
+}
+
+#[test]
+fn rows_of_a_matrix() {
+    op_test!("GatherND",
+             inputs: [arr2(&[[0.0f32, 1.0], [2.0, 3.0]]), arr2(&[[1i64], [0]])],
+             attrs: {},
+             expect: arr2(&[[2.0f32, 3.0], [0.0, 1.0]]));
+}
+
+#[test]
+fn rows_of_a_cube() {
+    op_test!("GatherND",
+             inputs: [cube(), arr2(&[[0i64, 1], [1, 0]])],
+             attrs: {},
+             expect: arr2(&[[2.0f32, 3.0], [4.0, 5.0]]));
+    op_test!("GatherND",
+             inputs: [cube(), arr3(&[[[0i64, 1]], [[1, 0]]])],
+             attrs: {},
+             expect: arr3(&[[[2.0f32, 3.0]], [[4.0, 5.0]]]));
+}
+
+#[test]
+fn batch_dims() {
+    // The first index addresses the first matrix, the second one the second matrix.
+    op_test!("GatherND",
+             inputs: [cube(), arr2(&[[1i64], [0]])],
+             attrs: { batch_dims: 1i64 },
+             expect: arr2(&[[2.0f32, 3.0], [4.0, 5.0]]));
+    // Two index tuples per batch, of the full rank of the matrices.
+    op_test!("GatherND",
+             inputs: [cube(), arr3(&[[[0i64, 1], [1, 1]], [[1, 0], [0, 0]]])],
+             attrs: { batch_dims: 1i64 },
+             expect: arr2(&[[1.0f32, 3.0], [6.0, 4.0]]));
+}
+
+#[test]
+fn negative_indices() {
+    op_test!("GatherND",
+             inputs: [cube(), arr2(&[[-1i64, 0], [0, -1]])],
+             attrs: {},
+             expect: arr2(&[[4.0f32, 5.0], [2.0, 3.0]]));
+    op_test!("GatherND",
+             inputs: [cube(), arr2(&[[-1i64], [-2]])],
+             attrs: { batch_dims: 1i64 },
+             expect: arr2(&[[2.0f32, 3.0], [4.0, 5.0]]));
+}
+
+#[test]
+fn invalid_indices() {
+    let error = OpTest::new("GatherND")
+        .input(cube())
+        .input(arr2(&[[2i64]]))
+        .expect(arr3(&[[[0.0f32, 1.0], [2.0, 3.0]]]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Index 2 is out of range"), "{}", error);
+
+    // Different batch dimensions.
+    let error = OpTest::new("GatherND")
+        .input(cube())
+        .input(arr2(&[[0i64], [0], [0]]))
+        .attribute("batch_dims", 1i64)
+        .expect(arr2(&[[0.0f32, 1.0]]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Cannot index the data"), "{}", error);
+
+    let error = OpTest::new("GatherND")
+        .input(cube())
+        .input(arr2(&[[0i64], [0]]))
+        .attribute("batch_dims", 2i64)
+        .expect(arr2(&[[0.0f32, 1.0]]))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("must be less than the ranks"), "{}", error);
+}
@@ -0,0 +1,124 @@
+//! Runs Erf and Gelu through the executor:
+//!
+//! ```text
+//! cargo test --test gelu
+//! ```
+//!
+//! The exact Gelu is built on the error function of Erf; its `tanh` approximation differs from it
+//! by less than 5e-4 on [-5, 5], which is enough to tell which of the two a node computed.
+
+use ndarray::{arr1, Array, Array1, ArrayD};
+use onnx_rustime_lib::backend::helper::{make_attribute, make_opsetid, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+use protobuf::RepeatedField;
+
+fn x() -> Array1<f32> {
+    arr1(&[-3.0, -1.0, -0.5, 0.0, 0.5, 1.0, 2.0, 3.0])
+}
+
+/// Runs Gelu on `input`, with the given `approximate` attribute if any.
+fn gelu(input: ArrayD<f32>, approximate: Option<&str>) -> ArrayD<f32> {
+    let attributes = approximate
+        .map(|value| {
+            vec![make_attribute(
+                "approximate",
+                Attribute::String(value.to_string()),
+            )]
+        })
+        .unwrap_or_default();
+    let mut model = single_node_model("Gelu", &["x"], &["y"], attributes);
+    model.set_opset_import(RepeatedField::from_vec(vec![make_opsetid(
+        None::<&str>,
+        20,
+    )]));
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(input, "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+#[test]
+fn erf() {
+    op_test!("Erf",
+             inputs: [arr1(&[-3.0f32, -1.5, -0.5, -0.1, 0.0, 0.1, 0.5, 1.0, 2.0, 3.5])],
+             attrs: {},
+             expect: arr1(&[-0.9999779f32, -0.96610515, -0.5204999, -0.11246292, 0.0,
+                            0.11246292, 0.5204999, 0.8427008, 0.9953223, 0.9999993]));
+}
+
+#[test]
+fn exact() {
+    let expected = arr1(&[
+        -0.004049694f32,
+        -0.15865525,
+        -0.15426877,
+        0.0,
+        0.34573123,
+        0.8413448,
+        1.9544997,
+        2.9959503,
+    ]);
+    OpTest::new("Gelu")
+        .opset(20)
+        .input(x())
+        .expect(expected.clone())
+        .check();
+    OpTest::new("Gelu")
+        .opset(20)
+        .input(x())
+        .attribute("approximate", "none")
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn tanh_approximation() {
+    OpTest::new("Gelu")
+        .opset(20)
+        .input(x())
+        .attribute("approximate", "tanh")
+        .expect(arr1(&[
+            -0.003637392f32,
+            -0.15880801,
+            -0.154286,
+            0.0,
+            0.345714,
+            0.841192,
+            1.9545977,
+            2.9963626,
+        ]))
+        .check();
+}
+
+#[test]
+fn approximation_close_to_exact() {
+    let input = Array::range(-5.0f32, 5.0, 0.125).into_dyn();
+    let exact = gelu(input.clone(), None);
+    let approximated = gelu(input, Some("tanh"));
+    let difference = (&exact - &approximated).mapv(f32::abs);
+    assert!(difference.iter().all(|&difference| difference < 5e-4));
+
+    // The attribute is respected: the variants differ beyond the float rounding error.
+    let exact = gelu(arr1(&[1.0f32]).into_dyn(), Some("none"));
+    let approximated = gelu(arr1(&[1.0f32]).into_dyn(), Some("tanh"));
+    assert!((exact[0] - 0.8413448).abs() < 1e-6, "{}", exact);
+    assert!(
+        (approximated[0] - 0.841192).abs() < 1e-6,
+        "{}",
+        approximated
+    );
+}
+
+#[test]
+fn invalid_approximation() {
+    let result = OpTest::new("Gelu")
+        .opset(20)
+        .input(x())
+        .attribute("approximate", "sigmoid")
+        .expect(x())
+        .run();
+    assert!(result.unwrap_err().contains("approximate"));
+}
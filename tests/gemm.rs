@@ -0,0 +1,102 @@
+//! Runs Gemm through the executor, with every combination of transA and transB and every shape of
+//! C:
+//!
+//! ```text
+//! cargo test --test gemm
+//! ```
+//!
+//! A is 2x3 and B is 3x4, so that A * B = [[5, 1, -8, -2], [-4, -26, 7, 10]]. With alpha 0.5
+//! and beta 2, every test expects 0.5 * A * B + 2 * C.
+
+use ndarray::{arr0, arr1, arr2, Array2, ArrayD};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+fn a() -> Array2<f32> {
+    arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, -6.0]])
+}
+
+fn b() -> Array2<f32> {
+    arr2(&[
+        [-2.0, -1.0, 0.0, 1.0],
+        [2.0, -2.0, -1.0, 0.0],
+        [1.0, 2.0, -2.0, -1.0],
+    ])
+}
+
+/// 0.5 * A * B.
+fn half_product() -> Array2<f32> {
+    arr2(&[[2.5, 0.5, -4.0, -1.0], [-2.0, -13.0, 3.5, 5.0]])
+}
+
+/// A Gemm of A and B, transposed beforehand if the attributes transpose them back.
+fn gemm(trans_a: bool, trans_b: bool) -> OpTest {
+    let a = if trans_a { a().reversed_axes() } else { a() };
+    let b = if trans_b { b().reversed_axes() } else { b() };
+    OpTest::new("Gemm")
+        .input(a)
+        .input(b)
+        .attribute("transA", trans_a as i64)
+        .attribute("transB", trans_b as i64)
+        .attribute("alpha", 0.5f32)
+        .attribute("beta", 2.0f32)
+}
+
+#[test]
+fn every_combination_of_transpositions() {
+    let c = arr1(&[1.0f32, 2.0, 3.0, 4.0]);
+    let expected = half_product() + arr1(&[2.0f32, 4.0, 6.0, 8.0]);
+
+    for trans_a in [false, true] {
+        for trans_b in [false, true] {
+            gemm(trans_a, trans_b)
+                .input(c.clone())
+                .expect(expected.clone())
+                .check();
+        }
+    }
+}
+
+#[test]
+fn every_shape_of_c() {
+    let cases: [(ArrayD<f32>, Array2<f32>); 4] = [
+        // A scalar.
+        (arr0(1.0).into_dyn(), arr2(&[[2.0; 4]; 2])),
+        // A vector of N values, like the bias of a linear layer.
+        (
+            arr1(&[1.0, 2.0, 3.0, 4.0]).into_dyn(),
+            arr2(&[[2.0, 4.0, 6.0, 8.0]; 2]),
+        ),
+        // A row.
+        (
+            arr2(&[[1.0, 2.0, 3.0, 4.0]]).into_dyn(),
+            arr2(&[[2.0, 4.0, 6.0, 8.0]; 2]),
+        ),
+        // A full matrix.
+        (
+            arr2(&[[1.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]]).into_dyn(),
+            arr2(&[[2.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 2.0]]),
+        ),
+    ];
+
+    for (c, scaled_c) in cases {
+        gemm(false, true)
+            .input(c)
+            .expect(half_product() + scaled_c)
+            .check();
+    }
+}
+
+#[test]
+fn without_c() {
+    gemm(true, false).expect(half_product()).check();
+}
+
+#[test]
+fn default_alpha_and_beta() {
+    // Both default to 1.
+    op_test!("Gemm",
+             inputs: [a(), b(), arr1(&[1.0f32, 2.0, 3.0, 4.0])],
+             attrs: {},
+             expect: arr2(&[[6.0f32, 3.0, -5.0, 2.0], [-3.0, -24.0, 10.0, 14.0]]));
+}
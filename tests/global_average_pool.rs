@@ -0,0 +1,79 @@
+//! Runs GlobalAveragePool through the executor on inputs of several ranks:
+//!
+//! ```text
+//! cargo test --test global_average_pool
+//! ```
+//!
+//! Every channel is averaged over all its spatial axes, which are kept as 1s in the output.
+
+use ndarray::{arr3, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn signals() {
+    // [N, C, L] = [2, 2, 4].
+    let x = arr3(&[
+        [[1.0f32, 2.0, 3.0, 4.0], [-1.0, 1.0, -3.0, 3.0]],
+        [[0.5, 0.5, 0.5, 0.5], [10.0, 0.0, 0.0, -2.0]],
+    ]);
+    OpTest::new("GlobalAveragePool")
+        .input(x)
+        .expect(arr3(&[[[2.5f32], [0.0]], [[0.5], [2.0]]]))
+        .check();
+}
+
+#[test]
+fn volumes() {
+    // [N, C, D, H, W] = [1, 2, 2, 3, 4], the channels holding 0, 1, ..., 23 and their negatives
+    // times 2.
+    let x = Array::from_shape_fn(IxDyn(&[1, 2, 2, 3, 4]), |index| {
+        let value = (index[2] * 12 + index[3] * 4 + index[4]) as f32;
+        match index[1] {
+            0 => value,
+            _ => -2.0 * value,
+        }
+    });
+    let expected = ArrayD::from_shape_vec(IxDyn(&[1, 2, 1, 1, 1]), vec![11.5f32, -23.0]).unwrap();
+    OpTest::new("GlobalAveragePool")
+        .input(x)
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn images() {
+    let x = Array::from_shape_fn(IxDyn(&[2, 3, 5, 5]), |index| {
+        (index[0] * 100 + index[1] * 10) as f32 + (index[2] * 5 + index[3]) as f32
+    });
+    let expected = Array::from_shape_fn(IxDyn(&[2, 3, 1, 1]), |index| {
+        (index[0] * 100 + index[1] * 10) as f32 + 12.0
+    });
+    OpTest::new("GlobalAveragePool")
+        .input(x)
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn precision_of_a_million_values() {
+    // 2^24 followed by 999,999 ones: a f32 running sum stays at 2^24, where adding 1 rounds back
+    // to the same value, and the average would be 16.777216 instead of 17.777215.
+    let mut x = Array::from_elem(IxDyn(&[1, 1, 1000, 1000]), 1.0f32);
+    x[[0, 0, 0, 0]] = 16_777_216.0;
+    let expected = ArrayD::from_elem(IxDyn(&[1, 1, 1, 1]), (17_777_215.0f64 / 1e6) as f32);
+    OpTest::new("GlobalAveragePool")
+        .input(x)
+        .expect(expected)
+        .tolerance(0.0, 1e-7)
+        .check();
+}
+
+#[test]
+fn input_without_spatial_axes() {
+    let error = OpTest::new("GlobalAveragePool")
+        .input(Array::from_elem(IxDyn(&[2, 3]), 1.0f32))
+        .expect(Array::from_elem(IxDyn(&[2, 3]), 1.0f32))
+        .run()
+        .unwrap_err();
+    assert!(error.contains("spatial axes"), "{}", error);
+}
@@ -0,0 +1,277 @@
+//! Runs the golden harness on a directory of synthetic models, and on the bundled models:
+//!
+//! ```text
+//! cargo test --features serde --test golden
+//! ```
+//!
+//! The test of the bundled models is ignored until they are pulled from Git LFS, then run with
+//! `-- --ignored`.
+//!
+//! The synthetic model computes `Relu(x + bias) * scale` on a positive input, with the last four
+//! elements of the bias far below zero: changing them moves the statistics of the Add, but not
+//! the output of the model, which is the kind of drift only the layer statistics catch.
+#![cfg(feature = "serde")]
+
+mod common;
+
+use ndarray::{arr1, arr2, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::golden::{GOLDENS_FILE, UPDATE_GOLDENS_VAR};
+use onnx_rustime_lib::testing::{check_goldens, GoldenResult, GoldenStatus, ModelStatistics};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const MODELS: &str = "models";
+
+/// The update variable is read by every check, so the tests setting it don't run alongside the
+/// others.
+static ENVIRONMENT: Mutex<()> = Mutex::new(());
+
+fn model(low_bias: f32) -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let tensor =
+        |values: ArrayD<f32>, name: &str| ndarray_to_tensor_proto::<f32>(values, name).unwrap();
+    let bias = arr1(&[
+        0.0f32, 0.5, 1.0, 1.5, low_bias, low_bias, low_bias, low_bias,
+    ])
+    .into_dyn();
+    let scale = arr1(&[2.0f32; 8]).into_dyn();
+
+    let value_info = |name: &str| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            vec![Dimension::Value(1), Dimension::Value(8)],
+            None,
+        )
+    };
+    let graph = make_graph(
+        vec![
+            node("Add", vec!["x", "bias"], "shifted"),
+            node("Relu", vec!["shifted"], "activation"),
+            node("Mul", vec!["activation", "scale"], "y"),
+        ],
+        "shifted_relu",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![tensor(bias, "bias"), tensor(scale, "scale")],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn input() -> TensorProto {
+    let x = arr2(&[[0.1f32, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8]]).into_dyn();
+    ndarray_to_tensor_proto::<f32>(x, "x").unwrap()
+}
+
+/// Writes a model in the layout of the bundled ones: `<name>/<name>.onnx`, with its input and
+/// expected output in `<name>/test_data_set_0`.
+fn write_model(models_dir: &Path, name: &str, model: &ModelProto, expected: &TensorProto) {
+    let dir = models_dir.join(name);
+    fs::create_dir_all(dir.join("test_data_set_0")).unwrap();
+    let path = |file: &str| dir.join(file).to_string_lossy().into_owned();
+    OnnxParser::save_model(model, path(&format!("{}.onnx", name))).unwrap();
+    OnnxParser::save_tensor_proto(&input(), path("test_data_set_0/input_0.pb")).unwrap();
+    OnnxParser::save_tensor_proto(expected, path("test_data_set_0/output_0.pb")).unwrap();
+}
+
+fn expected_output(model: &ModelProto) -> TensorProto {
+    run_with_inputs(model, vec![input()], &RunOptions::default())
+        .unwrap()
+        .remove(0)
+}
+
+/// Checks the models, with the goldens updated if `update` is set.
+fn check(models_dir: &Path, update: bool) -> Vec<GoldenResult> {
+    if update {
+        std::env::set_var(UPDATE_GOLDENS_VAR, "1");
+    }
+    let results = check_goldens(models_dir, None);
+    std::env::remove_var(UPDATE_GOLDENS_VAR);
+    results.unwrap()
+}
+
+fn statuses(results: &[GoldenResult]) -> Vec<(&str, String)> {
+    results
+        .iter()
+        .map(|result| (result.model.as_str(), format!("{:?}", result.status)))
+        .collect()
+}
+
+fn goldens(models_dir: &Path) -> BTreeMap<String, ModelStatistics> {
+    serde_json::from_str(&fs::read_to_string(models_dir.join(GOLDENS_FILE)).unwrap()).unwrap()
+}
+
+fn temp_models_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "onnx_rustime_golden_{}_{}",
+        test,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn golden_statistics_localize_the_drift() {
+    let _environment = ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_models_dir("drift");
+    let original = model(-5.0);
+    write_model(&dir, "shifted_relu", &original, &expected_output(&original));
+    // A directory without test data isn't a model.
+    fs::create_dir_all(dir.join("notes")).unwrap();
+
+    // Nothing is written without the update variable.
+    let results = check(&dir, false);
+    assert_eq!(
+        statuses(&results),
+        [("shifted_relu", "MissingGolden".into())]
+    );
+    assert!(results[0].output.as_ref().unwrap().is_close());
+    assert!(!dir.join(GOLDENS_FILE).exists());
+
+    assert_eq!(
+        statuses(&check(&dir, true)),
+        [("shifted_relu", "Updated".into())]
+    );
+    let recorded = goldens(&dir);
+    let layers: Vec<(&str, &str)> = recorded["shifted_relu"]
+        .layers
+        .iter()
+        .map(|layer| (layer.name.as_str(), layer.op_type.as_str()))
+        .collect();
+    assert_eq!(
+        layers,
+        [("shifted", "Add"), ("activation", "Relu"), ("y", "Mul")]
+    );
+    assert_eq!(
+        statuses(&check(&dir, false)),
+        [("shifted_relu", "Passed".into())]
+    );
+
+    // The output is the same, but the Add drifted.
+    let drifted = model(-6.0);
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&expected_output(&drifted)).unwrap(),
+        tensor_proto_to_ndarray::<f32>(&expected_output(&original)).unwrap()
+    );
+    write_model(&dir, "shifted_relu", &drifted, &expected_output(&original));
+    let results = check(&dir, false);
+    assert_eq!(statuses(&results), [("shifted_relu", "Failed".into())]);
+    assert!(results[0].output.as_ref().unwrap().is_close());
+    let drift = results[0].drift.as_ref().unwrap();
+    assert_eq!(drift.index, 0);
+    assert_eq!(drift.golden.name, "shifted");
+    assert!(drift.actual.mean < drift.golden.mean);
+    assert_eq!(goldens(&dir), recorded);
+
+    // Accepting the change.
+    assert_eq!(
+        statuses(&check(&dir, true)),
+        [("shifted_relu", "Updated".into())]
+    );
+    assert_eq!(
+        statuses(&check(&dir, false)),
+        [("shifted_relu", "Passed".into())]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn wrong_outputs_are_never_recorded() {
+    let _environment = ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_models_dir("wrong_outputs");
+    let model = model(-5.0);
+    write_model(&dir, "correct", &model, &expected_output(&model));
+    let mut wrong = tensor_proto_to_ndarray::<f32>(&expected_output(&model)).unwrap();
+    wrong[[0, 3]] += 0.1;
+    write_model(
+        &dir,
+        "wrong",
+        &model,
+        &ndarray_to_tensor_proto::<f32>(wrong, "y").unwrap(),
+    );
+
+    let results = check(&dir, true);
+    assert_eq!(
+        statuses(&results),
+        [("correct", "Updated".into()), ("wrong", "Failed".into())]
+    );
+    let output = results[1].output.as_ref().unwrap();
+    assert_eq!(output.violations, 1);
+    assert_eq!(output.worst_index, Some(vec![0, 3]));
+    assert_eq!(goldens(&dir).keys().collect::<Vec<_>>(), ["correct"]);
+
+    // Only the matching models are checked with a filter.
+    let results = check_goldens(&dir, Some("corr")).unwrap();
+    assert_eq!(statuses(&results), [("correct", "Passed".into())]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn bundled_models() {
+    let _environment = ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names: Vec<String> = fs::read_dir(MODELS)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| Path::new(MODELS).join(name).is_dir())
+        .collect();
+    names.sort();
+
+    for name in names {
+        common::require_model(Path::new(MODELS).join(&name).join(format!("{}.onnx", name)));
+
+        for result in check_goldens(Path::new(MODELS), Some(&name)).unwrap() {
+            match result.status {
+                GoldenStatus::Passed => {}
+                // The statistics are recorded by running the update once the models are pulled.
+                GoldenStatus::MissingGolden => eprintln!(
+                    "{}: no golden statistics (set {}=1 to record them)",
+                    result.model, UPDATE_GOLDENS_VAR
+                ),
+                status => panic!(
+                    "{}: {:?}, output {:?}, drift {:?}",
+                    result.model,
+                    status,
+                    result.output.map(|output| output.to_string()),
+                    result.drift
+                ),
+            }
+        }
+    }
+}
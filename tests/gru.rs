@@ -0,0 +1,246 @@
+//! Runs GRU through the executor:
+//!
+//! ```text
+//! cargo test --test gru
+//! ```
+//!
+//! The expected outputs come from `reference_gru`, the ONNX equations written out one sequence
+//! and one hidden unit at a time, and from a single unit worked out by hand.
+
+use ndarray::{arr1, arr2, arr3, Array, Array3, Array4, ArrayD, Ix3, IxDyn};
+use onnx_rustime_lib::testing::OpTest;
+
+const SEQ_LENGTH: usize = 5;
+const BATCH_SIZE: usize = 3;
+const INPUT_SIZE: usize = 3;
+const HIDDEN_SIZE: usize = 4;
+
+/// A tensor of the given shape with distinct values in [-scale, scale].
+fn values(shape: &[usize], seed: f32, scale: f32) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::from_iter((0..len).map(|i| scale * (i as f32 * 0.731 + seed).sin()))
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+fn x() -> Array3<f32> {
+    values(&[SEQ_LENGTH, BATCH_SIZE, INPUT_SIZE], 0.3, 1.0)
+        .into_dimensionality::<Ix3>()
+        .unwrap()
+}
+
+/// The weights of a GRU layer: `W`, `R` and `B`.
+struct Weights {
+    w: ArrayD<f32>,
+    r: ArrayD<f32>,
+    b: ArrayD<f32>,
+}
+
+impl Weights {
+    fn new(directions: usize, seed: f32) -> Self {
+        let gates = 3 * HIDDEN_SIZE;
+        Weights {
+            w: values(&[directions, gates, INPUT_SIZE], seed, 0.5),
+            r: values(&[directions, gates, HIDDEN_SIZE], seed + 1.0, 0.5),
+            b: values(&[directions, 2 * gates], seed + 2.0, 0.2),
+        }
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// `Y` and `Y_h` of a GRU with the default activations. `reverse` tells whether each direction
+/// runs from the end of the sequences.
+fn reference_gru(
+    x: &Array3<f32>,
+    weights: &Weights,
+    reverse: &[bool],
+    linear_before_reset: bool,
+    sequence_lens: &[usize],
+    initial_h: &Array3<f32>,
+) -> (Array4<f32>, Array3<f32>) {
+    let (seq_length, batch_size, input_size) = x.dim();
+    let directions = reverse.len();
+    let hidden = HIDDEN_SIZE;
+    let mut y = Array4::zeros((seq_length, directions, batch_size, hidden));
+    let mut y_h = initial_h.clone();
+
+    for direction in 0..directions {
+        let (w, r, b) = (&weights.w, &weights.r, &weights.b);
+        for batch in 0..batch_size {
+            let len = sequence_lens[batch];
+            let time_steps: Vec<usize> = match reverse[direction] {
+                false => (0..len).collect(),
+                true => (0..len).rev().collect(),
+            };
+            let mut h: Vec<f32> = (0..hidden)
+                .map(|j| initial_h[[direction, batch, j]])
+                .collect();
+            for time in time_steps {
+                // Xt*(W^T) + Wb, and (state)*(R^T) + Rb for a row of a gate.
+                let input_part = |row: usize| {
+                    let mut sum = b[[direction, row]];
+                    for k in 0..input_size {
+                        sum += x[[time, batch, k]] * w[[direction, row, k]];
+                    }
+                    sum
+                };
+                let recurrent_part = |row: usize, state: &[f32]| {
+                    let mut sum = b[[direction, 3 * hidden + row]];
+                    for k in 0..hidden {
+                        sum += state[k] * r[[direction, row, k]];
+                    }
+                    sum
+                };
+                let z: Vec<f32> = (0..hidden)
+                    .map(|j| sigmoid(input_part(j) + recurrent_part(j, &h)))
+                    .collect();
+                let reset: Vec<f32> = (0..hidden)
+                    .map(|j| sigmoid(input_part(hidden + j) + recurrent_part(hidden + j, &h)))
+                    .collect();
+                let reset_h: Vec<f32> = (0..hidden).map(|k| reset[k] * h[k]).collect();
+                let next_h: Vec<f32> = (0..hidden)
+                    .map(|j| {
+                        let row = 2 * hidden + j;
+                        let candidate = match linear_before_reset {
+                            true => (input_part(row) + reset[j] * recurrent_part(row, &h)).tanh(),
+                            false => (input_part(row) + recurrent_part(row, &reset_h)).tanh(),
+                        };
+                        (1.0 - z[j]) * candidate + z[j] * h[j]
+                    })
+                    .collect();
+                h = next_h;
+                for j in 0..hidden {
+                    y[[time, direction, batch, j]] = h[j];
+                }
+            }
+            for j in 0..hidden {
+                y_h[[direction, batch, j]] = h[j];
+            }
+        }
+    }
+    (y, y_h)
+}
+
+fn gru(weights: &Weights, direction: &str, linear_before_reset: i64) -> OpTest {
+    OpTest::new("GRU")
+        .opset(14)
+        .input(x())
+        .input(weights.w.clone())
+        .input(weights.r.clone())
+        .input(weights.b.clone())
+        .attribute("hidden_size", HIDDEN_SIZE as i64)
+        .attribute("direction", direction)
+        .attribute("linear_before_reset", linear_before_reset)
+}
+
+#[test]
+fn single_unit_by_hand() {
+    // The gates z, r and h of a single unit, over the sequence [1, 2].
+    let x = arr3(&[[[1.0f32]], [[2.0]]]);
+    let w = arr3(&[[[0.5f32], [-0.5], [1.0]]]);
+    let r = arr3(&[[[0.5f32], [0.25], [-1.0]]]);
+    let b = arr2(&[[0.0f32, 0.0, 0.0, 0.0, 0.0, 0.5]]);
+    for (linear_before_reset, expected) in
+        [(1, [0.3134332f32, 0.4699504]), (0, [0.3417303, 0.4936948])]
+    {
+        OpTest::new("GRU")
+            .opset(14)
+            .input(x.clone())
+            .input(w.clone())
+            .input(r.clone())
+            .input(b.clone())
+            .attribute("hidden_size", 1i64)
+            .attribute("linear_before_reset", linear_before_reset)
+            .expect(arr1(&expected).into_shape((2, 1, 1, 1)).unwrap())
+            .expect(arr1(&expected[1..]).into_shape((1, 1, 1)).unwrap())
+            .tolerance(1e-6, 1e-6)
+            .check();
+    }
+}
+
+#[test]
+fn forward_linear_before_reset() {
+    let weights = Weights::new(1, 1.0);
+    let zeros = Array3::zeros((1, BATCH_SIZE, HIDDEN_SIZE));
+    let lens = [SEQ_LENGTH; BATCH_SIZE];
+    let (y, y_h) = reference_gru(&x(), &weights, &[false], true, &lens, &zeros);
+    gru(&weights, "forward", 1)
+        .expect(y)
+        .expect(y_h)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn forward_reset_before_linear() {
+    let weights = Weights::new(1, 2.0);
+    let zeros = Array3::zeros((1, BATCH_SIZE, HIDDEN_SIZE));
+    let lens = [SEQ_LENGTH; BATCH_SIZE];
+    let (y, y_h) = reference_gru(&x(), &weights, &[false], false, &lens, &zeros);
+    gru(&weights, "forward", 0)
+        .expect(y)
+        .expect(y_h)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn reverse_with_initial_h() {
+    let weights = Weights::new(1, 3.0);
+    let initial_h = values(&[1, BATCH_SIZE, HIDDEN_SIZE], 4.0, 0.5)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let lens = [SEQ_LENGTH; BATCH_SIZE];
+    let (y, y_h) = reference_gru(&x(), &weights, &[true], true, &lens, &initial_h);
+    gru(&weights, "reverse", 1)
+        .input(Array::from_elem(BATCH_SIZE, SEQ_LENGTH as i32))
+        .input(initial_h)
+        .expect(y)
+        .expect(y_h)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn bidirectional_with_sequence_lengths() {
+    let weights = Weights::new(2, 5.0);
+    let zeros = Array3::zeros((2, BATCH_SIZE, HIDDEN_SIZE));
+    for linear_before_reset in [0, 1] {
+        let (y, y_h) = reference_gru(
+            &x(),
+            &weights,
+            &[false, true],
+            linear_before_reset != 0,
+            &[5, 3, 1],
+            &zeros,
+        );
+        gru(&weights, "bidirectional", linear_before_reset)
+            .input(arr1(&[5i32, 3, 1]))
+            .expect(y)
+            .expect(y_h)
+            .tolerance(1e-5, 1e-5)
+            .check();
+    }
+}
+
+#[test]
+fn mismatching_bias() {
+    let weights = Weights::new(1, 1.0);
+    let error = OpTest::new("GRU")
+        .opset(14)
+        .input(x())
+        .input(weights.w)
+        .input(weights.r)
+        .input(values(&[1, 8 * HIDDEN_SIZE], 0.0, 1.0))
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The B input of GRU must be of shape [1, 24]"),
+        "{}",
+        error
+    );
+}
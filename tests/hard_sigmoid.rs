@@ -0,0 +1,39 @@
+//! Runs HardSigmoid and HardSwish through the executor, around the boundaries where they start
+//! and stop clamping:
+//!
+//! ```text
+//! cargo test --test hard_sigmoid
+//! ```
+//!
+//! HardSigmoid is 0 up to `-beta / alpha` and 1 from `(1 - beta) / alpha`.
+
+use ndarray::arr1;
+use onnx_rustime_lib::op_test;
+
+#[test]
+fn hard_sigmoid_default_attributes() {
+    // alpha = 0.2 and beta = 0.5: the boundaries are -2.5 and 2.5.
+    op_test!("HardSigmoid",
+             inputs: [arr1(&[-3.0f32, -2.5, -1.0, 0.0, 1.0, 2.5, 3.0])],
+             attrs: {},
+             expect: arr1(&[0.0f32, 0.0, 0.3, 0.5, 0.7, 1.0, 1.0]));
+}
+
+#[test]
+fn hard_sigmoid_clamps_at_the_boundaries_of_its_attributes() {
+    // alpha = 0.5 and beta = 0.25: the boundaries are -0.5 and 1.5, where the defaults would
+    // give 0.4 and 0.8.
+    op_test!("HardSigmoid",
+             inputs: [arr1(&[-1.0f32, -0.5, -0.25, 0.0, 1.0, 1.5, 2.0])],
+             attrs: { alpha: 0.5f32, beta: 0.25f32 },
+             expect: arr1(&[0.0f32, 0.0, 0.125, 0.25, 0.75, 1.0, 1.0]));
+}
+
+#[test]
+fn hard_swish() {
+    // alpha = 1/6 and beta = 0.5: the boundaries are -3 and 3.
+    op_test!("HardSwish",
+             inputs: [arr1(&[-4.0f32, -3.0, -1.0, 0.0, 1.0, 3.0, 4.0])],
+             attrs: {},
+             expect: arr1(&[0.0f32, 0.0, -0.33333334, 0.0, 0.6666667, 3.0, 4.0]));
+}
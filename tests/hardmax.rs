@@ -0,0 +1,77 @@
+//! Runs Hardmax through the executor:
+//!
+//! ```text
+//! cargo test --test hardmax
+//! ```
+//!
+//! Only the first occurrence of a maximum is set to 1.
+
+use ndarray::{arr2, Array2};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+/// A [3, 5] input whose rows and columns have duplicate maxima.
+fn x() -> Array2<f32> {
+    arr2(&[
+        [1.0f32, 3.0, 3.0, -2.0, 3.0],
+        [4.0, 0.0, 4.0, 4.0, -1.0],
+        [4.0, 3.0, -5.0, 0.5, 3.0],
+    ])
+}
+
+#[test]
+fn first_maximum_of_every_row() {
+    let expected = arr2(&[
+        [0.0f32, 1.0, 0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0, 0.0],
+    ]);
+
+    for opset in [11, 13] {
+        for axis in [1i64, -1] {
+            OpTest::new("Hardmax")
+                .opset(opset)
+                .input(x())
+                .attribute("axis", axis)
+                .expect(expected.clone())
+                .check();
+        }
+    }
+    // The default axis: 1 before opset 13, -1 since, the same on a 2D input.
+    op_test!("Hardmax", inputs: [x()], attrs: {}, expect: expected.clone());
+    OpTest::new("Hardmax")
+        .opset(13)
+        .input(x())
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn first_maximum_of_every_column() {
+    OpTest::new("Hardmax")
+        .opset(13)
+        .input(x())
+        .attribute("axis", -2i64)
+        .expect(arr2(&[
+            [0.0f32, 1.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn first_maximum_of_the_coerced_input() {
+    // Before opset 13, axis 0 flattens the whole input into a single row: the 4.0 of the second
+    // row comes first.
+    OpTest::new("Hardmax")
+        .opset(11)
+        .input(x())
+        .attribute("axis", 0i64)
+        .expect(arr2(&[
+            [0.0f32, 0.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0],
+        ]))
+        .check();
+}
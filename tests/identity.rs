@@ -0,0 +1,152 @@
+//! Runs chains of pass-through nodes through the executor:
+//!
+//! ```text
+//! cargo test --test identity
+//! ```
+//!
+//! The Identity nodes, the Dropout nodes in inference mode and the Cast nodes to the type of their
+//! input are forwarded: the output is the input, whose tensor is never copied. The bytes allocated
+//! by a run are counted by the global allocator of the test, and stay far below the size of the
+//! input however long the chain.
+
+use ndarray::{Array, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// The system allocator, counting the bytes allocated by every thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // The counter can't be reached while the thread is torn down.
+        let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + layout.size()));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + new_size));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// 16 MiB of FLOAT values.
+fn x() -> ArrayD<f32> {
+    Array::from_shape_fn(IxDyn(&[1024, 4096]), |index| {
+        (index[0] * 4096 + index[1]) as f32 * 0.5
+    })
+}
+
+fn value_info(name: &str) -> ValueInfoProto {
+    let mut value_info = ValueInfoProto::new();
+    value_info.set_name(name.to_string());
+    value_info
+}
+
+/// The nodes of `op_types` chained from `x` to `y`, with the attributes of a Cast to FLOAT.
+fn chain(op_types: &[&str]) -> ModelProto {
+    let names: Vec<String> = (0..op_types.len())
+        .map(|index| match index {
+            0 => "x".to_string(),
+            index => format!("t{}", index),
+        })
+        .chain(std::iter::once("y".to_string()))
+        .collect();
+    let nodes: Vec<NodeProto> = op_types
+        .iter()
+        .enumerate()
+        .map(|(index, &op_type)| {
+            let attributes = match op_type {
+                "Cast" => vec![make_attribute("to", Attribute::<String>::Int(1))],
+                _ => vec![],
+            };
+            make_node(
+                op_type,
+                vec![names[index].as_str()],
+                vec![names[index + 1].as_str()],
+                None,
+                None,
+                None,
+                attributes,
+            )
+        })
+        .collect();
+    let graph = make_graph(
+        nodes,
+        "chain",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Runs the chain on `x`, checking that the output is the input and that the run allocated less
+/// than a tenth of its size.
+fn check_without_copies(model: &ModelProto) {
+    let x = x();
+    let size = x.len() * std::mem::size_of::<f32>();
+    let input = ndarray_to_tensor_proto::<f32>(x.clone(), "x").unwrap();
+
+    let before = ALLOCATED.with(Cell::get);
+    let outputs = run_with_inputs(model, vec![input], &RunOptions::default()).unwrap();
+    let allocated = ALLOCATED.with(Cell::get) - before;
+
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].get_name(), "y");
+    assert_eq!(tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap(), x);
+    assert!(
+        allocated < size / 10,
+        "The run allocated {} bytes for an input of {} bytes",
+        allocated,
+        size
+    );
+}
+
+#[test]
+fn chain_of_identities() {
+    check_without_copies(&chain(&["Identity"; 5]));
+}
+
+#[test]
+fn chain_of_pass_through_nodes() {
+    check_without_copies(&chain(&[
+        "Identity", "Dropout", "Cast", "Identity", "Dropout",
+    ]));
+}
+
+#[test]
+fn integers() {
+    let x = ndarray::arr2(&[[1i64, -2, 3], [4, 5, -6]]);
+    OpTest::new("Identity").input(x.clone()).expect(x).check();
+}
@@ -0,0 +1,266 @@
+//! Runs If nodes and their branches through the executor:
+//!
+//! ```text
+//! cargo test --test if_op
+//! ```
+
+use ndarray::{arr1, arr2, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, ModelProto, NodeProto, TensorProto, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::transforms::{optimize, prune_unused_initializers, OptLevel};
+
+fn value_info(name: &str) -> ValueInfoProto {
+    let mut value_info = ValueInfoProto::new();
+    value_info.set_name(name.to_string());
+    value_info
+}
+
+fn float_tensor(values: ArrayD<f32>, name: &str) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(values, name).unwrap()
+}
+
+fn condition(value: bool) -> TensorProto {
+    ndarray_to_tensor_proto::<bool>(arr1(&[value]).into_shape(vec![]).unwrap(), "cond").unwrap()
+}
+
+fn node(op_type: &str, inputs: Vec<&str>, outputs: Vec<&str>) -> NodeProto {
+    make_node(op_type, inputs, outputs, None, None, None, vec![])
+}
+
+fn constant(output: &str, value: ArrayD<f32>) -> NodeProto {
+    make_node(
+        "Constant",
+        vec![],
+        vec![output],
+        None,
+        None,
+        None,
+        vec![make_attribute(
+            "value",
+            Attribute::<String>::Tensor(float_tensor(value, "value")),
+        )],
+    )
+}
+
+/// A branch without inputs, computing `output` with its nodes and initializers.
+fn branch(
+    name: &str,
+    nodes: Vec<NodeProto>,
+    initializers: Vec<TensorProto>,
+    output: &str,
+) -> GraphProto {
+    make_graph(
+        nodes,
+        name,
+        vec![],
+        vec![value_info(output)],
+        initializers,
+        None,
+    )
+}
+
+/// A model whose output `y` is computed by an If node on the input `cond`, after the given
+/// nodes.
+fn if_model(
+    inputs: &[&str],
+    mut nodes: Vec<NodeProto>,
+    then_branch: GraphProto,
+    else_branch: GraphProto,
+) -> ModelProto {
+    nodes.push(make_node(
+        "If",
+        vec!["cond"],
+        vec!["y"],
+        None,
+        None,
+        None,
+        vec![
+            make_attribute("then_branch", Attribute::<String>::Graph(then_branch)),
+            make_attribute("else_branch", Attribute::<String>::Graph(else_branch)),
+        ],
+    ));
+    let graph = make_graph(
+        nodes,
+        "if",
+        inputs.iter().map(|&name| value_info(name)).collect(),
+        vec![value_info("y")],
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(model: &ModelProto, inputs: Vec<TensorProto>) -> ArrayD<f32> {
+    let outputs = run_with_inputs(model, inputs, &RunOptions::default()).unwrap();
+    assert_eq!(outputs[0].get_name(), "y");
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+#[test]
+fn scalar_condition() {
+    // The branches give constants of different shapes.
+    let then_values = arr1(&[1.0f32, 2.0, 3.0]).into_dyn();
+    let else_values = arr2(&[[4.0f32, 5.0]]).into_dyn();
+    let model = if_model(
+        &["cond"],
+        vec![],
+        branch(
+            "then",
+            vec![constant("then_out", then_values.clone())],
+            vec![],
+            "then_out",
+        ),
+        branch(
+            "else",
+            vec![constant("else_out", else_values.clone())],
+            vec![],
+            "else_out",
+        ),
+    );
+
+    assert_eq!(run(&model, vec![condition(true)]), then_values);
+    assert_eq!(run(&model, vec![condition(false)]), else_values);
+}
+
+#[test]
+fn branches_read_the_enclosing_graph() {
+    // The then branch adds an initializer of its own to the input x of the model, read after its
+    // last use by the main graph; the else branch outputs x as is.
+    let one = float_tensor(arr1(&[1.0f32]).into_dyn(), "one");
+    let model = if_model(
+        &["x", "cond"],
+        vec![node("Identity", vec!["x"], vec!["x_copy"])],
+        branch(
+            "then",
+            vec![node("Add", vec!["x", "one"], vec!["sum"])],
+            vec![one],
+            "sum",
+        ),
+        branch("else", vec![], vec![], "x"),
+    );
+
+    let x = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]).into_dyn();
+    let inputs = |value| vec![float_tensor(x.clone(), "x"), condition(value)];
+    assert_eq!(run(&model, inputs(true)), &x + 1.0);
+    assert_eq!(run(&model, inputs(false)), x);
+}
+
+#[test]
+fn optimized_branches_keep_the_tensors_they_output() {
+    // No node reads `doubled` and `copy`: the branches output them as they are.
+    let mut model = if_model(
+        &["x", "cond"],
+        vec![
+            node("Mul", vec!["x", "two"], vec!["doubled"]),
+            node("Identity", vec!["x"], vec!["copy"]),
+        ],
+        branch("then", vec![], vec![], "doubled"),
+        branch("else", vec![], vec![], "copy"),
+    );
+    let two = float_tensor(arr1(&[2.0f32]).into_dyn(), "two");
+    model.mut_graph().mut_initializer().push(two);
+
+    let optimized = optimize(&model, OptLevel::Extended).unwrap();
+    let op_types: Vec<&str> = optimized
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_op_type())
+        .collect();
+    assert_eq!(op_types, ["Mul", "If"]);
+
+    let x = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]).into_dyn();
+    let inputs = |value| vec![float_tensor(x.clone(), "x"), condition(value)];
+    assert_eq!(run(&optimized, inputs(true)), &x * 2.0);
+    assert_eq!(run(&optimized, inputs(false)), x);
+}
+
+#[test]
+fn branches_keep_the_initializers_they_output() {
+    let mut model = if_model(
+        &["x", "cond"],
+        vec![],
+        branch("then", vec![], vec![], "offset"),
+        branch("else", vec![], vec![], "x"),
+    );
+    let offset = float_tensor(arr1(&[5.0f32]).into_dyn(), "offset");
+    model.mut_graph().mut_initializer().push(offset);
+
+    assert!(!prune_unused_initializers(model.mut_graph()).unwrap());
+    let x = float_tensor(arr1(&[1.0f32]).into_dyn(), "x");
+    assert_eq!(
+        run(&model, vec![x, condition(true)]),
+        arr1(&[5.0f32]).into_dyn()
+    );
+}
+
+#[test]
+fn nested_if() {
+    // The inner If reads the condition of the main graph.
+    let inner = if_model(
+        &["cond"],
+        vec![],
+        branch(
+            "inner_then",
+            vec![constant("a", arr1(&[1.0f32]).into_dyn())],
+            vec![],
+            "a",
+        ),
+        branch(
+            "inner_else",
+            vec![constant("b", arr1(&[2.0f32]).into_dyn())],
+            vec![],
+            "b",
+        ),
+    );
+    let inner_if = inner.get_graph().get_node()[0].clone();
+    let model = if_model(
+        &["cond"],
+        vec![],
+        branch("then", vec![inner_if], vec![], "y"),
+        branch(
+            "else",
+            vec![constant("c", arr1(&[3.0f32]).into_dyn())],
+            vec![],
+            "c",
+        ),
+    );
+
+    assert_eq!(
+        run(&model, vec![condition(true)]),
+        arr1(&[1.0f32]).into_dyn()
+    );
+    assert_eq!(
+        run(&model, vec![condition(false)]),
+        arr1(&[3.0f32]).into_dyn()
+    );
+}
+
+#[test]
+fn condition_of_several_values() {
+    let model = if_model(
+        &["cond"],
+        vec![],
+        branch("then", vec![], vec![], "cond"),
+        branch("else", vec![], vec![], "cond"),
+    );
+    let cond = ndarray_to_tensor_proto::<bool>(arr1(&[true, false]).into_dyn(), "cond").unwrap();
+    let error = run_with_inputs(&model, vec![cond], &RunOptions::default()).unwrap_err();
+    assert!(error.to_string().contains("single value"), "{}", error);
+}
@@ -0,0 +1,79 @@
+//! Runs LayerNormalization through the executor:
+//!
+//! ```text
+//! cargo test --test layer_normalization
+//! ```
+//!
+//! The expected outputs follow the definition of the operator, group by group: the values
+//! sharing the indices before `axis` are shifted by their mean, divided by
+//! sqrt(variance + 1e-5), then scaled and shifted.
+
+use ndarray::{arr1, Array, Array3};
+use onnx_rustime_lib::op_test;
+
+#[test]
+fn last_axis_of_a_3d_tensor() {
+    // The input holds (3i^2 + i) % 17 - 8 at the flat index i.
+    let input = Array::from_shape_fn(64, |i| ((3 * i * i + i) % 17) as f32 - 8.0)
+        .into_shape((2, 4, 8))
+        .unwrap();
+    let scale = arr1(&[1.0f32, 0.5, 2.0, -1.0, 1.0, 1.5, 0.25, 1.0]);
+    let bias = arr1(&[0.0f32, 0.5, -0.5, 1.0, 0.0, 0.0, 2.0, -1.0]);
+    let expected: Array3<f32> = Array::from_shape_vec(
+        (2, 4, 8),
+        vec![
+            -1.238253, 0.228453, 1.889611, -0.021015, //
+            -1.064463, 1.270838, 2.211806, -2.064463, //
+            1.146829, 1.177672, -1.959601, 2.563858, //
+            -1.146829, 0.781929, 1.973936, -0.478714, //
+            -0.932643, -0.143968, -1.6547, -0.199112, //
+            1.021466, -1.665433, 2.210955, -0.156181, //
+            -1.049405, 1.146726, 2.477381, 1.46369, //
+            -1.244643, -1.28125, 2.176935, -0.877976, //
+            0.550019, 0.041651, -3.066755, 1.550019, //
+            1.283378, 1.650057, 1.72499, -0.083302, //
+            0.916698, -0.050019, 1.700076, -0.283378, //
+            -0.550019, -1.925067, 1.770825, -0.449981, //
+            0.122024, 0.853869, -2.208333, 2.244643, //
+            -0.463690, 2.233036, 2.323363, -2.049405, //
+            0.843819, 0.921910, -2.720578, -0.021466, //
+            1.199112, -0.866025, 1.678016, -1.932643,
+        ],
+    )
+    .unwrap();
+
+    op_test!("LayerNormalization",
+             inputs: [input, scale, bias],
+             attrs: { axis: -1i64 },
+             expect: expected);
+}
+
+#[test]
+fn mean_and_inv_std_dev_outputs() {
+    // Normalized over the last two axes, the Scale of shape [2] being broadcast to [3, 2]. There
+    // is no B.
+    let input: Array3<f32> = Array::from_shape_vec(
+        (2, 3, 2),
+        vec![
+            0.0, 1.0, 4.0, 2.0, 2.0, 4.0, //
+            1.0, 0.0, 1.0, 4.0, 2.0, 2.0,
+        ],
+    )
+    .unwrap();
+    let expected: Array3<f32> = Array::from_shape_vec(
+        (2, 3, 2),
+        vec![
+            -1.481484, -1.595444, 1.253563, -0.227921, -0.113960, 2.507127, //
+            -0.534521, -2.672604, -0.534521, 3.741645, 0.267260, 0.534521,
+        ],
+    )
+    .unwrap();
+    // The means are 13 / 6 and 10 / 6.
+    let mean = Array::from_shape_vec((2, 1, 1), vec![13.0f32 / 6.0, 10.0 / 6.0]).unwrap();
+    let inv_std_dev = Array::from_shape_vec((2, 1, 1), vec![0.683762f32, 0.801781]).unwrap();
+
+    op_test!("LayerNormalization",
+             inputs: [input, arr1(&[1.0f32, 2.0])],
+             attrs: { axis: 1i64 },
+             expect: [expected, mean, inv_std_dev]);
+}
@@ -0,0 +1,104 @@
+//! Runs LogSoftmax through the executor:
+//!
+//! ```text
+//! cargo test --test log_softmax
+//! ```
+//!
+//! LogSoftmax shares the `axis` handling of Softmax, and is computed as
+//! `x - max - log(sum(exp(x - max)))`, so that large negative logits don't become -inf.
+
+use ndarray::{arr1, arr2, Array, ArrayD, Axis, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, make_opsetid, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+use protobuf::RepeatedField;
+
+/// Runs LogSoftmax on `input` with the given opset and axis.
+fn log_softmax(input: ArrayD<f32>, opset: i64, axis: i64) -> ArrayD<f32> {
+    let mut model = single_node_model(
+        "LogSoftmax",
+        &["x"],
+        &["y"],
+        vec![make_attribute("axis", Attribute::<String>::Int(axis))],
+    );
+    model.set_opset_import(RepeatedField::from_vec(vec![make_opsetid(
+        None::<&str>,
+        opset,
+    )]));
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(input, "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+#[test]
+fn values() {
+    op_test!("LogSoftmax",
+             inputs: [arr2(&[[1.0f32, 2.0, 3.0]])],
+             attrs: {},
+             expect: arr2(&[[-2.407606f32, -1.4076059, -0.40760596]]));
+
+    // Per column since opset 13, over the whole tensor coerced to 2D before.
+    let x = arr2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    OpTest::new("LogSoftmax")
+        .opset(13)
+        .input(x.clone())
+        .attribute("axis", 0i64)
+        .expect(arr2(&[
+            [-2.126928f32, -2.126928],
+            [-0.12692801, -0.12692801],
+        ]))
+        .check();
+    OpTest::new("LogSoftmax")
+        .opset(11)
+        .input(x)
+        .attribute("axis", 0i64)
+        .expect(arr2(&[
+            [-3.4401897f32, -2.4401897],
+            [-1.4401897, -0.4401897],
+        ]))
+        .check();
+}
+
+#[test]
+fn exp_sums_to_one() {
+    let input = Array::range(-12.0f32, 12.0, 1.0)
+        .mapv(|value| value * 1.5)
+        .into_shape(IxDyn(&[2, 3, 4]))
+        .unwrap();
+
+    for (opset, axis) in [(13, 0), (13, 1), (13, -1), (11, 1), (11, -1)] {
+        let output = log_softmax(input.clone(), opset, axis);
+        let exp = output.mapv(f32::exp);
+        let normalized_axis = if axis < 0 { axis + 3 } else { axis } as usize;
+        let sums = if opset >= 13 {
+            exp.sum_axis(Axis(normalized_axis))
+        } else {
+            // The rows of the input coerced to 2D around axis.
+            let rows = input.shape()[..normalized_axis].iter().product::<usize>();
+            exp.into_shape((rows, 24 / rows))
+                .unwrap()
+                .sum_axis(Axis(1))
+                .into_dyn()
+        };
+        for sum in sums {
+            assert!(
+                (sum - 1.0).abs() < 1e-5,
+                "opset {}, axis {}: sum of {}",
+                opset,
+                axis,
+                sum
+            );
+        }
+    }
+}
+
+#[test]
+fn large_negative_logits() {
+    // log(softmax(x)) would give log(0) = -inf for the last two values.
+    let output = log_softmax(arr1(&[0.0f32, -1000.0, -2000.0]).into_dyn(), 13, -1);
+    assert!(output.iter().all(|value| value.is_finite()), "{}", output);
+    assert_eq!(output, arr1(&[0.0f32, -1000.0, -2000.0]).into_dyn());
+}
@@ -0,0 +1,128 @@
+//! Runs the logical operations through the executor:
+//!
+//! ```text
+//! cargo test --test logical
+//! ```
+//!
+//! The inputs and the outputs are BOOL tensors, the two inputs of And, Or and Xor being broadcast
+//! together.
+
+use ndarray::{arr1, arr2, Array2};
+use onnx_rustime_lib::backend::helper::{make_graph, make_model, make_node};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::ValueInfoProto;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn truth_tables() {
+    let a = arr1(&[false, false, true, true]);
+    let b = arr1(&[false, true, false, true]);
+    for (op_type, expected) in [
+        ("And", [false, false, false, true]),
+        ("Or", [false, true, true, true]),
+        ("Xor", [false, true, true, false]),
+    ] {
+        OpTest::new(op_type)
+            .opset(7)
+            .input(a.clone())
+            .input(b.clone())
+            .expect(arr1(&expected))
+            .check();
+    }
+    OpTest::new("Not")
+        .opset(1)
+        .input(a)
+        .expect(arr1(&[true, true, false, false]))
+        .check();
+}
+
+#[test]
+fn row_mask_and_column_mask() {
+    // A [1, S] mask of the valid keys and a [B, 1] mask of the valid queries.
+    OpTest::new("And")
+        .opset(7)
+        .input(arr2(&[[true, true, false]]))
+        .input(arr2(&[[true], [false]]))
+        .expect(arr2(&[[true, true, false], [false, false, false]]))
+        .check();
+    OpTest::new("Or")
+        .opset(7)
+        .input(arr2(&[[true, true, false]]))
+        .input(arr2(&[[true], [false]]))
+        .expect(arr2(&[[true, true, true], [true, true, false]]))
+        .check();
+}
+
+#[test]
+fn non_bool_inputs() {
+    let error = OpTest::new("And")
+        .opset(7)
+        .input(arr1(&[1.0f32, 0.0]))
+        .input(arr1(&[true, true]))
+        .expect(arr1(&[true, false]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The inputs of And must be BOOL"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn greater_and_where() {
+    // Keeps the scores above a threshold in the valid positions, and sets the others to -1.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(op_type, inputs, vec![output], None, None, None, vec![])
+    };
+    let nodes = vec![
+        node("Greater", vec!["scores", "threshold"], "confident"),
+        node("And", vec!["confident", "valid"], "keep"),
+        node("Where", vec!["keep", "scores", "fill"], "masked"),
+    ];
+    let initializers = vec![
+        ndarray_to_tensor_proto::<f32>(arr1(&[0.5]).into_dyn(), "threshold").unwrap(),
+        ndarray_to_tensor_proto::<f32>(arr1(&[-1.0]).into_dyn(), "fill").unwrap(),
+    ];
+    let graph = make_graph(
+        nodes,
+        "greater_and_where",
+        vec![value_info("scores"), value_info("valid")],
+        vec![value_info("masked")],
+        initializers,
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let scores: Array2<f32> = arr2(&[[0.9, 0.2, 0.7], [0.6, 0.8, 0.1]]);
+    let valid = arr2(&[[true, true, false]]);
+    let inputs = vec![
+        ndarray_to_tensor_proto::<f32>(scores.into_dyn(), "scores").unwrap(),
+        ndarray_to_tensor_proto::<bool>(valid.into_dyn(), "valid").unwrap(),
+    ];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let masked = outputs
+        .iter()
+        .find(|output| output.get_name() == "masked")
+        .unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(masked).unwrap(),
+        arr2(&[[0.9f32, -1.0, -1.0], [0.6, 0.8, -1.0]]).into_dyn()
+    );
+}
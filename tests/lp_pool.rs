@@ -0,0 +1,92 @@
+//! Runs LpPool and GlobalLpPool through the executor:
+//!
+//! ```text
+//! cargo test --test lp_pool
+//! ```
+
+use ndarray::{Array, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::single_node_model;
+
+/// A tensor of the given shape holding start, start + 1, start + 2...
+fn range_input(shape: &[usize], start: f32) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::range(start, start + len as f32, 1.0)
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+#[test]
+fn p1_is_the_average_pool_times_the_window_size() {
+    // On non-negative values, the L1 norm of a window is its sum, the average including the
+    // padding times the 9 positions of the window.
+    let input = range_input(&[1, 2, 5, 5], 0.0);
+    let model = single_node_model(
+        "AveragePool",
+        &["x"],
+        &["y"],
+        vec![
+            make_attribute("kernel_shape", Attribute::<String>::Ints(vec![3, 3])),
+            make_attribute("pads", Attribute::<String>::Ints(vec![1, 1, 1, 1])),
+            make_attribute("count_include_pad", Attribute::<String>::Int(1)),
+        ],
+    );
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(input.clone(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let sums = tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap() * 9.0;
+
+    op_test!("LpPool",
+             inputs: [input],
+             attrs: {
+                 p: 1i64,
+                 kernel_shape: vec![3i64, 3],
+                 pads: vec![1i64, 1, 1, 1],
+             },
+             expect: sums);
+}
+
+#[test]
+fn p2_as_an_int_or_a_float() {
+    // 2x2 windows with strides 2 over -8, -7, ..., 7.
+    let input = range_input(&[1, 1, 4, 4], -8.0);
+    let expected = Array::from_shape_vec(
+        (1, 1, 2, 2),
+        vec![
+            138.0f32.sqrt(),
+            66.0f32.sqrt(),
+            42.0f32.sqrt(),
+            98.0f32.sqrt(),
+        ],
+    )
+    .unwrap();
+
+    op_test!("LpPool",
+             inputs: [input.clone()],
+             attrs: { p: 2i64, kernel_shape: vec![2i64, 2], strides: vec![2i64, 2] },
+             expect: expected.clone());
+    op_test!("LpPool",
+             inputs: [input],
+             attrs: { p: 2.0f32, kernel_shape: vec![2i64, 2], strides: vec![2i64, 2] },
+             expect: expected);
+}
+
+#[test]
+fn global_lp_pool_of_every_channel() {
+    // The channels hold -6, ..., -1 and 0, ..., 5.
+    let input = range_input(&[1, 2, 2, 3], -6.0);
+
+    op_test!("GlobalLpPool",
+             inputs: [input.clone()],
+             attrs: {},
+             expect: Array::from_shape_vec((1, 2, 1, 1), vec![91.0f32.sqrt(), 55.0f32.sqrt()])
+                 .unwrap());
+    op_test!("GlobalLpPool",
+             inputs: [input],
+             attrs: { p: 3i64 },
+             expect: Array::from_shape_vec((1, 2, 1, 1), vec![441.0f32.cbrt(), 225.0f32.cbrt()])
+                 .unwrap());
+}
@@ -0,0 +1,37 @@
+//! Runs LRN through the executor:
+//!
+//! ```text
+//! cargo test --test lrn
+//! ```
+//!
+//! The expected outputs follow the definition of LRN, element by element:
+//! x / (bias + alpha / size * square_sum) ^ beta, where square_sum adds the squares of the
+//! channels c - 1, c and c + 1 that exist.
+
+use ndarray::{Array, Array4};
+use onnx_rustime_lib::op_test;
+
+#[test]
+fn truncated_windows_at_the_edge_channels() {
+    // A 1x5x2x2 input holding (5i % 9 - 4) / 2 at the flat index i. The first and the last
+    // channels only sum 2 channels, but still divide by a size of 3.
+    let input = Array::from_shape_fn(20, |i| ((5 * i) % 9) as f32 / 2.0 - 2.0)
+        .into_shape((1, 5, 2, 2))
+        .unwrap();
+    let expected: Array4<f32> = Array::from_shape_vec(
+        (1, 5, 2, 2),
+        vec![
+            -0.915813, 0.257963, -0.773889, 0.457906, //
+            -0.457906, 0.644653, -0.254677, 0.834296, //
+            0.0, -0.834296, 0.254677, -0.644653, //
+            0.457906, -0.457906, 0.644653, -0.254677, //
+            0.915813, 0.0, -0.868353, 0.288337,
+        ],
+    )
+    .unwrap();
+
+    op_test!("LRN",
+             inputs: [input],
+             attrs: { size: 3i64, alpha: 0.5f32, beta: 0.75f32, bias: 2.0f32 },
+             expect: expected);
+}
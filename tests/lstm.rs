@@ -0,0 +1,399 @@
+//! Runs LSTM through the executor:
+//!
+//! ```text
+//! cargo test --test lstm
+//! ```
+//!
+//! The expected outputs come from `reference_lstm`, the ONNX equations written out one sequence
+//! and one hidden unit at a time, which walks each sequence of the batch on its own (backwards
+//! for the reverse direction) instead of masking the batch.
+
+use ndarray::{arr1, Array, Array2, Array3, Array4, ArrayD, Axis, Ix3, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{TensorProto, ValueInfoProto};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+const SEQ_LENGTH: usize = 5;
+const BATCH_SIZE: usize = 3;
+const HIDDEN_SIZE: usize = 4;
+
+/// A tensor of the given shape with distinct values in [-scale, scale].
+fn values(shape: &[usize], seed: f32, scale: f32) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::from_iter((0..len).map(|i| scale * (i as f32 * 0.731 + seed).sin()))
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+/// The weights of an LSTM layer: `W`, `R`, `B` and `P`.
+struct Weights {
+    w: ArrayD<f32>,
+    r: ArrayD<f32>,
+    b: ArrayD<f32>,
+    p: ArrayD<f32>,
+}
+
+impl Weights {
+    fn new(directions: usize, input_size: usize, seed: f32) -> Self {
+        let gates = 4 * HIDDEN_SIZE;
+        Weights {
+            w: values(&[directions, gates, input_size], seed, 0.5),
+            r: values(&[directions, gates, HIDDEN_SIZE], seed + 1.0, 0.5),
+            b: values(&[directions, 2 * gates], seed + 2.0, 0.2),
+            p: ArrayD::zeros(IxDyn(&[directions, 3 * HIDDEN_SIZE])),
+        }
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// `Y`, `Y_h` and `Y_c` of an LSTM with the default activations, without `clip`. `reverse`
+/// tells whether each direction runs from the end of the sequences.
+fn reference_lstm(
+    x: &Array3<f32>,
+    weights: &Weights,
+    reverse: &[bool],
+    sequence_lens: &[usize],
+    initial_h: &Array3<f32>,
+    initial_c: &Array3<f32>,
+) -> (Array4<f32>, Array3<f32>, Array3<f32>) {
+    let (seq_length, batch_size, input_size) = x.dim();
+    let directions = reverse.len();
+    let hidden = HIDDEN_SIZE;
+    let mut y = Array4::zeros((seq_length, directions, batch_size, hidden));
+    let mut y_h = initial_h.clone();
+    let mut y_c = initial_c.clone();
+
+    for direction in 0..directions {
+        for batch in 0..batch_size {
+            let len = sequence_lens[batch];
+            let time_steps: Vec<usize> = match reverse[direction] {
+                false => (0..len).collect(),
+                true => (0..len).rev().collect(),
+            };
+            let mut h: Vec<f32> = (0..hidden)
+                .map(|j| initial_h[[direction, batch, j]])
+                .collect();
+            let mut c: Vec<f32> = (0..hidden)
+                .map(|j| initial_c[[direction, batch, j]])
+                .collect();
+            for time in time_steps {
+                let pre = |gate: usize, j: usize| {
+                    let row = gate * hidden + j;
+                    let mut sum =
+                        weights.b[[direction, row]] + weights.b[[direction, 4 * hidden + row]];
+                    for k in 0..input_size {
+                        sum += x[[time, batch, k]] * weights.w[[direction, row, k]];
+                    }
+                    for (k, h) in h.iter().enumerate() {
+                        sum += h * weights.r[[direction, row, k]];
+                    }
+                    sum
+                };
+                let peephole = |gate: usize, j: usize| weights.p[[direction, gate * hidden + j]];
+                let mut next_h = vec![0.0; hidden];
+                let mut next_c = vec![0.0; hidden];
+                for j in 0..hidden {
+                    let i = sigmoid(pre(0, j) + peephole(0, j) * c[j]);
+                    let f = sigmoid(pre(2, j) + peephole(2, j) * c[j]);
+                    let candidate = pre(3, j).tanh();
+                    next_c[j] = f * c[j] + i * candidate;
+                    let o = sigmoid(pre(1, j) + peephole(1, j) * next_c[j]);
+                    next_h[j] = o * next_c[j].tanh();
+                }
+                h = next_h;
+                c = next_c;
+                for j in 0..hidden {
+                    y[[time, direction, batch, j]] = h[j];
+                }
+            }
+            for j in 0..hidden {
+                y_h[[direction, batch, j]] = h[j];
+                y_c[[direction, batch, j]] = c[j];
+            }
+        }
+    }
+    (y, y_h, y_c)
+}
+
+/// The reference outputs with zero initial states, forward or bidirectional.
+fn reference_from_zero(
+    x: &Array3<f32>,
+    weights: &Weights,
+    sequence_lens: &[usize],
+) -> (Array4<f32>, Array3<f32>, Array3<f32>) {
+    let directions = weights.w.shape()[0];
+    let zeros = Array3::zeros((directions, x.shape()[1], HIDDEN_SIZE));
+    let reverse = [false, true];
+    reference_lstm(
+        x,
+        weights,
+        &reverse[..directions],
+        sequence_lens,
+        &zeros,
+        &zeros,
+    )
+}
+
+/// `Y` with the directions concatenated on its last axis, the input of the next layer.
+fn next_layer_input(y: &Array4<f32>) -> Array3<f32> {
+    let (seq_length, directions, batch_size, hidden) = y.dim();
+    y.view()
+        .permuted_axes([0, 2, 1, 3])
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((seq_length, batch_size, directions * hidden))
+        .unwrap()
+}
+
+/// Runs two stacked LSTM layers (the first with biases and the sequence lengths, the second
+/// without biases), joined by a Transpose and a Reshape as in the exported models, and compares
+/// every output with the reference.
+fn check_two_layers(direction: &str, sequence_lens: [usize; BATCH_SIZE]) {
+    let directions = if direction == "bidirectional" { 2 } else { 1 };
+    let input_size = 3;
+    let x = values(&[SEQ_LENGTH, BATCH_SIZE, input_size], 0.3, 1.0)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let first = Weights::new(directions, input_size, 1.0);
+    let mut second = Weights::new(directions, directions * HIDDEN_SIZE, 5.0);
+    second.b.fill(0.0);
+
+    let (y1, y_h1, y_c1) = reference_from_zero(&x, &first, &sequence_lens);
+    let (y2, y_h2, y_c2) = reference_from_zero(&next_layer_input(&y1), &second, &sequence_lens);
+
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let lstm = |inputs: Vec<&str>, outputs: Vec<&str>| {
+        make_node(
+            "LSTM",
+            inputs,
+            outputs,
+            None,
+            None,
+            None,
+            vec![
+                make_attribute("hidden_size", Attribute::<String>::Int(HIDDEN_SIZE as i64)),
+                make_attribute(
+                    "direction",
+                    Attribute::<String>::String(direction.to_string()),
+                ),
+            ],
+        )
+    };
+    let nodes = vec![
+        lstm(
+            vec!["x", "w1", "r1", "b1", "lens"],
+            vec!["y1", "y_h1", "y_c1"],
+        ),
+        make_node(
+            "Transpose",
+            vec!["y1"],
+            vec!["y1_transposed"],
+            None,
+            None,
+            None,
+            vec![make_attribute(
+                "perm",
+                Attribute::<String>::Ints(vec![0, 2, 1, 3]),
+            )],
+        ),
+        make_node(
+            "Reshape",
+            vec!["y1_transposed", "shape"],
+            vec!["y1_reshaped"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+        // The omitted B is zero.
+        lstm(
+            vec!["y1_reshaped", "w2", "r2", "", "lens"],
+            vec!["y2", "y_h2", "y_c2"],
+        ),
+    ];
+    let tensor = |array: &ArrayD<f32>, name: &str| {
+        ndarray_to_tensor_proto::<f32>(array.clone(), name).unwrap()
+    };
+    let lens = sequence_lens
+        .iter()
+        .map(|&len| len as i32)
+        .collect::<Vec<_>>();
+    let shape = [0i64, 0, -1];
+    let initializers = vec![
+        tensor(&first.w, "w1"),
+        tensor(&first.r, "r1"),
+        tensor(&first.b, "b1"),
+        tensor(&second.w, "w2"),
+        tensor(&second.r, "r2"),
+        ndarray_to_tensor_proto::<i32>(arr1(&lens).into_dyn(), "lens").unwrap(),
+        ndarray_to_tensor_proto::<i64>(arr1(&shape).into_dyn(), "shape").unwrap(),
+    ];
+    let output_names = ["y1", "y_h1", "y_c1", "y2", "y_h2", "y_c2"];
+    let graph = make_graph(
+        nodes,
+        "two_lstm_layers",
+        vec![value_info("x")],
+        output_names.iter().map(|name| value_info(name)).collect(),
+        initializers,
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 14)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let inputs = vec![tensor(&x.into_dyn(), "x")];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let expected = [
+        y1.into_dyn(),
+        y_h1.into_dyn(),
+        y_c1.into_dyn(),
+        y2.into_dyn(),
+        y_h2.into_dyn(),
+        y_c2.into_dyn(),
+    ];
+    for (name, expected) in output_names.iter().zip(expected) {
+        let output: &TensorProto = outputs
+            .iter()
+            .find(|output| output.get_name() == *name)
+            .unwrap();
+        let actual = tensor_proto_to_ndarray::<f32>(output).unwrap();
+        assert_eq!(actual.shape(), expected.shape(), "{}", name);
+        for (actual, expected) in actual.iter().zip(&expected) {
+            assert!(
+                (actual - expected).abs() <= 1e-5,
+                "{} ({}): {} != {}",
+                name,
+                direction,
+                actual,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn two_forward_layers() {
+    check_two_layers("forward", [SEQ_LENGTH; BATCH_SIZE]);
+}
+
+#[test]
+fn two_bidirectional_layers() {
+    check_two_layers("bidirectional", [SEQ_LENGTH; BATCH_SIZE]);
+}
+
+#[test]
+fn sequence_lengths() {
+    // The reverse direction of the shorter sequences starts from their last element, and their
+    // outputs past it are zero.
+    check_two_layers("forward", [5, 3, 1]);
+    check_two_layers("bidirectional", [5, 3, 1]);
+    check_two_layers("bidirectional", [2, 5, 0]);
+}
+
+#[test]
+fn reverse_with_initial_states_and_peepholes() {
+    let x = values(&[SEQ_LENGTH, 2, 3], 0.7, 1.0)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let mut weights = Weights::new(1, 3, 2.0);
+    weights.p = values(&[1, 3 * HIDDEN_SIZE], 4.0, 0.3);
+    let initial_h = values(&[1, 2, HIDDEN_SIZE], 6.0, 0.5)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let initial_c = values(&[1, 2, HIDDEN_SIZE], 7.0, 0.5)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let (y, y_h, y_c) = reference_lstm(&x, &weights, &[true], &[5, 4], &initial_h, &initial_c);
+
+    OpTest::new("LSTM")
+        .opset(14)
+        .input(x)
+        .input(weights.w.clone())
+        .input(weights.r.clone())
+        .input(weights.b.clone())
+        .input(arr1(&[5i32, 4]))
+        .input(initial_h)
+        .input(initial_c)
+        .input(weights.p.clone())
+        .attribute("hidden_size", HIDDEN_SIZE as i64)
+        .attribute("direction", "reverse")
+        .expect(y)
+        .expect(y_h)
+        .expect(y_c)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn batch_first_layout() {
+    let x = values(&[SEQ_LENGTH, BATCH_SIZE, 3], 0.1, 1.0)
+        .into_dimensionality::<Ix3>()
+        .unwrap();
+    let weights = Weights::new(2, 3, 3.0);
+    let (y, y_h, _) = reference_from_zero(&x, &weights, &[SEQ_LENGTH; BATCH_SIZE]);
+    let batch_first = |array: ArrayD<f32>, axes: &[usize]| {
+        array
+            .permuted_axes(IxDyn(axes))
+            .as_standard_layout()
+            .into_owned()
+    };
+
+    OpTest::new("LSTM")
+        .opset(14)
+        .input(batch_first(x.into_dyn(), &[1, 0, 2]))
+        .input(weights.w.clone())
+        .input(weights.r.clone())
+        .input(weights.b.clone())
+        .attribute("hidden_size", HIDDEN_SIZE as i64)
+        .attribute("direction", "bidirectional")
+        .attribute("layout", 1i64)
+        .expect(batch_first(y.into_dyn(), &[2, 0, 1, 3]))
+        .expect(batch_first(y_h.into_dyn(), &[1, 0, 2]))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn mismatching_weights() {
+    let weights = Weights::new(2, 3, 1.0);
+    let error = OpTest::new("LSTM")
+        .opset(14)
+        .input(values(&[SEQ_LENGTH, BATCH_SIZE, 3], 0.0, 1.0))
+        .input(
+            weights
+                .w
+                .index_axis(Axis(0), 0)
+                .insert_axis(Axis(0))
+                .to_owned(),
+        )
+        .input(weights.r)
+        .attribute("hidden_size", HIDDEN_SIZE as i64)
+        .attribute("direction", "bidirectional")
+        .expect(Array2::<f32>::zeros((1, 1)))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The W input of LSTM must be of shape [2, 16, 3]"),
+        "{}",
+        error
+    );
+}
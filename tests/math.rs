@@ -0,0 +1,149 @@
+//! Runs the element-wise math operations through the executor:
+//!
+//! ```text
+//! cargo test --test math
+//! ```
+//!
+//! The values undefined in the reals follow IEEE 754: `Log(0)` is -inf and `Sqrt(-1)` is NaN.
+
+use ndarray::{arr1, arr2, Array1};
+use onnx_rustime_lib::testing::OpTest;
+
+/// The input of every unary operation.
+fn x() -> Array1<f32> {
+    arr1(&[-2.0, -0.5, 0.0, 0.25, 1.0, 4.0])
+}
+
+#[test]
+fn unary_operations() {
+    let nan = f32::NAN;
+    let inf = f32::INFINITY;
+    for (op_type, opset, expected) in [
+        ("Abs", 13, [2.0, 0.5, 0.0, 0.25, 1.0, 4.0]),
+        ("Neg", 13, [2.0, 0.5, -0.0, -0.25, -1.0, -4.0]),
+        ("Sign", 13, [-1.0, -1.0, 0.0, 1.0, 1.0, 1.0]),
+        ("Sqrt", 13, [nan, nan, 0.0, 0.5, 1.0, 2.0]),
+        ("Reciprocal", 13, [-0.5, -2.0, inf, 4.0, 1.0, 0.25]),
+        (
+            "Log",
+            13,
+            [nan, nan, -inf, -(4.0f32.ln()), 0.0, 4.0f32.ln()],
+        ),
+        (
+            "Exp",
+            13,
+            [
+                (-2.0f32).exp(),
+                (-0.5f32).exp(),
+                1.0,
+                0.25f32.exp(),
+                1.0f32.exp(),
+                4.0f32.exp(),
+            ],
+        ),
+    ] {
+        OpTest::new(op_type)
+            .opset(opset)
+            .input(x())
+            .expect(arr1(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn int64_operations() {
+    let x = arr1(&[-3i64, 0, 7]);
+    for (op_type, expected) in [
+        ("Abs", [3i64, 0, 7]),
+        ("Neg", [3, 0, -7]),
+        ("Sign", [-1, 0, 1]),
+    ] {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(x.clone())
+            .expect(arr1(&expected))
+            .check();
+    }
+    let error = OpTest::new("Sqrt")
+        .opset(13)
+        .input(x)
+        .expect(arr1(&[0i64, 0, 0]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Sqrt is not defined on INT64 tensors"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn scalar_input() {
+    OpTest::new("Exp")
+        .opset(13)
+        .input(arr1(&[0.0f32]).into_shape(()).unwrap())
+        .expect(arr1(&[1.0f32]).into_shape(()).unwrap())
+        .check();
+}
+
+#[test]
+fn pow() {
+    // The cube of the tanh approximation of GELU, with a scalar exponent.
+    OpTest::new("Pow")
+        .opset(15)
+        .input(x())
+        .input(arr1(&[3.0f32]).into_shape(()).unwrap())
+        .expect(arr1(&[-8.0f32, -0.125, 0.0, 0.015625, 1.0, 64.0]))
+        .check();
+    // A column of bases with a row of exponents.
+    OpTest::new("Pow")
+        .opset(15)
+        .input(arr2(&[[2.0f32], [9.0]]))
+        .input(arr1(&[0.5f32, 2.0]))
+        .expect(arr2(&[[2.0f32.sqrt(), 4.0], [3.0, 81.0]]))
+        .check();
+}
+
+#[test]
+fn pow_with_int64_exponents() {
+    OpTest::new("Pow")
+        .opset(15)
+        .input(arr1(&[-2.0f32, 0.5, 10.0]))
+        .input(arr1(&[3i64, -1, 2]))
+        .expect(arr1(&[-8.0f32, 2.0, 100.0]))
+        .check();
+    OpTest::new("Pow")
+        .opset(15)
+        .input(arr1(&[2i64, -3, 5]))
+        .input(arr1(&[10i64, 3, 0]))
+        .expect(arr1(&[1024i64, -27, 1]))
+        .check();
+}
+
+#[test]
+fn floor_and_ceil() {
+    let x = arr1(&[-1.5f32, -0.5, 0.0, 0.5, 1.2]);
+    OpTest::new("Floor")
+        .opset(13)
+        .input(x.clone())
+        .expect(arr1(&[-2.0f32, -1.0, 0.0, 0.0, 1.0]))
+        .check();
+    OpTest::new("Ceil")
+        .opset(13)
+        .input(x)
+        .expect(arr1(&[-1.0f32, -0.0, 0.0, 1.0, 2.0]))
+        .check();
+}
+
+#[test]
+fn round_halves_to_even() {
+    OpTest::new("Round")
+        .opset(11)
+        .input(arr1(&[
+            0.5f32, 1.5, 2.5, 3.5, -0.5, -1.5, -2.5, 2.4, 2.6, -2.6,
+        ]))
+        .expect(arr1(&[
+            0.0f32, 2.0, 2.0, 4.0, -0.0, -2.0, -2.0, 2.0, 3.0, -3.0,
+        ]))
+        .check();
+}
@@ -0,0 +1,82 @@
+//! Runs MatMul with batch dimensions and vectors through the executor:
+//!
+//! ```text
+//! cargo test --test matmul
+//! ```
+//!
+//! The stacks of matrices are checked against the reference implementation of MatMul, which
+//! multiplies every pair of matrices of the broadcast batch element by element; the vectors
+//! against products computed by hand.
+
+use ndarray::{arr0, arr1, arr2, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::reference;
+
+/// A tensor of the given shape cycling through -3, -2, ..., 3.
+fn cycle(shape: &[usize]) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::from_shape_fn(len, |i| (i % 7) as f32 - 3.0)
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+fn check_stacks(a_shape: &[usize], b_shape: &[usize], output_shape: &[usize]) {
+    let (a, b) = (cycle(a_shape), cycle(b_shape));
+    let expected = reference::matmul(&a, &b).unwrap();
+    assert_eq!(expected.shape(), output_shape);
+
+    op_test!("MatMul", inputs: [a, b], attrs: {}, expect: expected);
+}
+
+#[test]
+fn stack_times_matrix() {
+    check_stacks(&[2, 3, 4], &[4, 5], &[2, 3, 5]);
+}
+
+#[test]
+fn batch_dimensions_broadcast() {
+    check_stacks(&[1, 3, 4], &[7, 4, 5], &[7, 3, 5]);
+}
+
+#[test]
+fn attention_scores() {
+    // [B, H, S, D] x [B, H, D, S], like the queries by the transposed keys.
+    check_stacks(&[2, 3, 5, 4], &[2, 3, 4, 5], &[2, 3, 5, 5]);
+}
+
+#[test]
+fn vector_times_matrix() {
+    let b = arr2(&[
+        [-3.0f32, -2.0, -1.0, 0.0, 1.0],
+        [2.0, 3.0, -3.0, -2.0, -1.0],
+        [0.0, 1.0, 2.0, 3.0, -3.0],
+        [-2.0, -1.0, 0.0, 1.0, 2.0],
+    ]);
+
+    op_test!("MatMul",
+             inputs: [arr1(&[1.0f32, 2.0, 3.0, 4.0]), b],
+             attrs: {},
+             expect: arr1(&[-7.0f32, 3.0, -1.0, 9.0, -2.0]));
+}
+
+#[test]
+fn matrix_times_vector() {
+    let a = arr2(&[
+        [-2.0f32, -1.0, 0.0, 1.0],
+        [2.0, -2.0, -1.0, 0.0],
+        [1.0, 2.0, -2.0, -1.0],
+    ]);
+
+    op_test!("MatMul",
+             inputs: [a, arr1(&[1.0f32, 2.0, 3.0, 4.0])],
+             attrs: {},
+             expect: arr1(&[0.0f32, -5.0, -5.0]));
+}
+
+#[test]
+fn vector_times_vector() {
+    op_test!("MatMul",
+             inputs: [arr1(&[1.0f32, 2.0, 3.0, 4.0]), arr1(&[1.0f32, 2.0, 3.0, 4.0])],
+             attrs: {},
+             expect: arr0(30.0f32));
+}
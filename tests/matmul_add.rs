@@ -0,0 +1,227 @@
+//! Fuses the MatMul and Add pairs of linear layers, and compares the runs of the fused models
+//! with the ones of the original models:
+//!
+//! ```text
+//! cargo test --test matmul_add
+//! ```
+//!
+//! The feed-forward block of BERT multiplies [batch, sequence, hidden] activations, which Gemm
+//! can't take: its MatMuls keep the bias (the `fused_bias` attribute), and compute the same values
+//! as the MatMul and Add pairs. The layers of matrices become Gemms.
+
+use ndarray::ArrayD;
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::assert_tensors_close;
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::{optimize, OptLevel, PassManager};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const BATCH: usize = 2;
+const SEQUENCE: usize = 5;
+const HIDDEN: usize = 16;
+const INTERMEDIATE: usize = 64;
+
+fn node(op_type: &str, inputs: Vec<&str>, output: &str) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        vec![],
+    )
+}
+
+fn value_info(name: &str, dims: &[usize]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        dims.iter()
+            .map(|&dim| Dimension::Value(dim as i64))
+            .collect(),
+        None,
+    )
+}
+
+fn initializer(rng: &mut StdRng, name: &str, shape: &[usize]) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(random_tensor(rng, shape), name).unwrap()
+}
+
+fn model(
+    nodes: Vec<NodeProto>,
+    input: ValueInfoProto,
+    initializers: Vec<TensorProto>,
+) -> ModelProto {
+    let mut output = ValueInfoProto::new();
+    output.set_name("y".to_string());
+    let graph = make_graph(
+        nodes,
+        "linear",
+        vec![input],
+        vec![output],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 20)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// The feed-forward block of a BERT layer: two linear layers with a Gelu between them, then the
+/// residual connection and the normalization.
+fn bert_feed_forward() -> ModelProto {
+    let mut rng = StdRng::seed_from_u64(221);
+    let initializers = vec![
+        initializer(&mut rng, "w1", &[HIDDEN, INTERMEDIATE]),
+        initializer(&mut rng, "b1", &[INTERMEDIATE]),
+        initializer(&mut rng, "w2", &[INTERMEDIATE, HIDDEN]),
+        initializer(&mut rng, "b2", &[HIDDEN]),
+        initializer(&mut rng, "gamma", &[HIDDEN]),
+        initializer(&mut rng, "beta", &[HIDDEN]),
+    ];
+    let nodes = vec![
+        node("MatMul", vec!["x", "w1"], "intermediate_product"),
+        // The bias first, as some exporters write it.
+        node("Add", vec!["b1", "intermediate_product"], "intermediate"),
+        node("Gelu", vec!["intermediate"], "activation"),
+        node("MatMul", vec!["activation", "w2"], "output_product"),
+        node("Add", vec!["output_product", "b2"], "output"),
+        node("Add", vec!["output", "x"], "residual"),
+        node("LayerNormalization", vec!["residual", "gamma", "beta"], "y"),
+    ];
+    model(
+        nodes,
+        value_info("x", &[BATCH, SEQUENCE, HIDDEN]),
+        initializers,
+    )
+}
+
+fn run(model: &ModelProto, input: &ArrayD<f32>, options: &RunOptions) -> ArrayD<f32> {
+    let input = ndarray_to_tensor_proto::<f32>(input.clone(), "x").unwrap();
+    let outputs = run_with_inputs(model, vec![input], options).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+fn fuse(model: &ModelProto) -> ModelProto {
+    let mut pass_manager = PassManager::new(OptLevel::None);
+    pass_manager.enable("fuse_matmul_add").unwrap();
+    pass_manager.run(model).unwrap().0
+}
+
+#[test]
+fn bert_feed_forward_block() {
+    let model = bert_feed_forward();
+    let fused = fuse(&model);
+
+    let nodes = fused.get_graph().get_node();
+    assert_eq!(nodes.len(), 5);
+    let op_types: Vec<&str> = nodes.iter().map(|node| node.get_op_type()).collect();
+    assert_eq!(
+        op_types,
+        ["MatMul", "Gelu", "MatMul", "Add", "LayerNormalization"]
+    );
+    for (matmul, inputs, output) in [
+        (&nodes[0], ["x", "w1", "b1"], "intermediate"),
+        (&nodes[2], ["activation", "w2", "b2"], "output"),
+    ] {
+        assert_eq!(matmul.get_input(), inputs);
+        assert_eq!(matmul.get_output(), [output]);
+        let attribute = &matmul.get_attribute()[0];
+        assert_eq!((attribute.get_name(), attribute.get_i()), ("fused_bias", 1));
+    }
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let x = random_tensor(&mut rng, &[BATCH, SEQUENCE, HIDDEN]);
+    let expected = run(&model, &x, &RunOptions::default());
+    assert_eq!(run(&fused, &x, &RunOptions::default()), expected);
+
+    // The reference kernel of MatMul adds the bias as well.
+    let reference = RunOptions {
+        reference_kernels: true,
+        ..RunOptions::default()
+    };
+    assert_eq!(run(&fused, &x, &reference), run(&model, &x, &reference));
+
+    // The Extended level fuses the block in the same way.
+    assert_eq!(
+        optimize(&model, OptLevel::Extended)
+            .unwrap()
+            .get_graph()
+            .get_node()
+            .len(),
+        5
+    );
+}
+
+#[test]
+fn linear_layer_of_matrices() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let initializers = vec![
+        initializer(&mut rng, "w", &[HIDDEN, INTERMEDIATE]),
+        initializer(&mut rng, "b", &[INTERMEDIATE]),
+    ];
+    let model = model(
+        vec![
+            node("MatMul", vec!["x", "w"], "product"),
+            node("Add", vec!["product", "b"], "y"),
+        ],
+        value_info("x", &[SEQUENCE, HIDDEN]),
+        initializers,
+    );
+    let fused = fuse(&model);
+
+    let nodes = fused.get_graph().get_node();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].get_op_type(), "Gemm");
+    assert_eq!(nodes[0].get_input(), ["x", "w", "b"]);
+
+    // Gemm sums the products in another order than MatMul.
+    let x = random_tensor(&mut rng, &[SEQUENCE, HIDDEN]);
+    assert_tensors_close(
+        &run(&fused, &x, &RunOptions::default()),
+        &run(&model, &x, &RunOptions::default()),
+        1e-4,
+        1e-5,
+    );
+}
+
+#[test]
+fn shared_product() {
+    // The product is also read by the Relu, so the Add can't take it over.
+    let mut rng = StdRng::seed_from_u64(2);
+    let initializers = vec![
+        initializer(&mut rng, "w", &[HIDDEN, HIDDEN]),
+        initializer(&mut rng, "b", &[HIDDEN]),
+    ];
+    let model = model(
+        vec![
+            node("MatMul", vec!["x", "w"], "product"),
+            node("Add", vec!["product", "b"], "biased"),
+            node("Relu", vec!["product"], "activation"),
+            node("Add", vec!["biased", "activation"], "y"),
+        ],
+        value_info("x", &[BATCH, SEQUENCE, HIDDEN]),
+        initializers,
+    );
+    assert_eq!(
+        fuse(&model).get_graph().get_node(),
+        model.get_graph().get_node()
+    );
+}
@@ -0,0 +1,81 @@
+//! Runs MatMulInteger through the executor, with zero points per tensor, per row of A and per
+//! column of B:
+//!
+//! ```text
+//! cargo test --test matmul_integer
+//! ```
+//!
+//! The UINT8 operands hold values near 255 around a zero point near 128, so their products
+//! only fit once widened: 250 * 255 overflows 8 bits, and so would (0 - 128) in unsigned
+//! arithmetic. The expected products were computed on the differences, element by element.
+
+use ndarray::{arr0, arr1, arr2, Array2};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::single_node_model;
+
+fn a() -> Array2<u8> {
+    arr2(&[[250, 128, 3], [0, 255, 200]])
+}
+
+fn b() -> Array2<u8> {
+    arr2(&[[255, 130, 1], [127, 64, 240], [200, 128, 255]])
+}
+
+#[test]
+fn zero_points_per_tensor() {
+    op_test!("MatMulInteger",
+             inputs: [a(), b(), arr0(128u8), arr0(128u8)],
+             attrs: {},
+             expect: arr2(&[[6494, 244, -31369], [-11199, -8384, 39624]]));
+}
+
+#[test]
+fn zero_points_per_row_and_per_column() {
+    op_test!("MatMulInteger",
+             inputs: [a(), b(), arr1(&[128u8, 100]), arr1(&[127u8, 128, 129])],
+             attrs: {},
+             expect: arr2(&[[6491, 244, -31366], [-5500, -10120, 42605]]));
+}
+
+#[test]
+fn omitted_zero_point_of_a() {
+    // The inputs are A, B, "" and b_zero_point: A keeps its values.
+    let mut model = single_node_model(
+        "MatMulInteger",
+        &["a", "b", "", "b_zero_point"],
+        &["y"],
+        vec![],
+    );
+    model
+        .mut_graph()
+        .mut_input()
+        .retain(|input| !input.get_name().is_empty());
+
+    let inputs = vec![
+        ndarray_to_tensor_proto::<u8>(a().into_dyn(), "a").unwrap(),
+        ndarray_to_tensor_proto::<u8>(b().into_dyn(), "b").unwrap(),
+        ndarray_to_tensor_proto::<u8>(arr1(&[128u8, 128, 128]).into_dyn(), "b_zero_point").unwrap(),
+    ];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+
+    assert_eq!(
+        tensor_proto_to_ndarray::<i32>(&outputs[0]).unwrap(),
+        arr2(&[[31838, -7692, -17033], [14145, -16320, 53960]]).into_dyn()
+    );
+}
+
+#[test]
+fn signed_operands() {
+    op_test!("MatMulInteger",
+             inputs: [
+                 arr2(&[[-128i8, 127, 5], [-1, 0, -100]]),
+                 arr2(&[[127i8, -128], [-50, 3], [100, -7]]),
+                 arr0(-3i8),
+                 arr0(5i8),
+             ],
+             attrs: {},
+             expect: arr2(&[[-21640, 16269], [-9136, 892]]));
+}
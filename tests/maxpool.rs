@@ -0,0 +1,121 @@
+//! Runs max poolings of a 7x7 input with 3x3 windows and strides 2 through the executor:
+//!
+//! ```text
+//! cargo test --test maxpool
+//! ```
+//!
+//! The input is a 1x1x7x7 tensor holding a permutation of 0, 1, ..., 48 (the pixel at row r and
+//! column c holds (7 * r + c) * 17 % 49), so that every maximum has a distinct position. The end
+//! of both spatial axes is padded by 1: (7 + 1 - 3) / 2 + 1 gives 3 windows, rounded down, or 4,
+//! rounded up.
+
+use ndarray::{Array, Array4};
+use onnx_rustime_lib::op_test;
+
+fn input() -> Array4<f32> {
+    Array::from_shape_fn((1, 1, 7, 7), |(_, _, row, column)| {
+        ((7 * row + column) * 17 % 49) as f32
+    })
+}
+
+#[test]
+fn floor_mode_drops_the_partial_windows() {
+    let expected = Array::from_shape_vec(
+        (1, 1, 3, 3),
+        vec![
+            42.0, 44.0, 46.0, //
+            48.0, 48.0, 46.0, //
+            45.0, 47.0, 47.0,
+        ],
+    )
+    .unwrap();
+    let indices = Array::from_shape_vec(
+        (1, 1, 3, 3),
+        vec![
+            14i64, 17, 20, //
+            23, 23, 20, //
+            43, 46, 46,
+        ],
+    )
+    .unwrap();
+
+    op_test!("MaxPool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 strides: vec![2i64, 2],
+                 pads: vec![0i64, 0, 1, 1],
+             },
+             expect: [expected, indices]);
+}
+
+#[test]
+fn ceil_mode_clips_the_last_windows() {
+    // The fourth window of each axis starts on the last pixel and only covers it.
+    let expected = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            42.0, 44.0, 46.0, 46.0, //
+            48.0, 48.0, 46.0, 46.0, //
+            45.0, 47.0, 47.0, 39.0, //
+            45.0, 47.0, 47.0, 32.0,
+        ],
+    )
+    .unwrap();
+    let indices = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            14i64, 17, 20, 20, //
+            23, 23, 20, 20, //
+            43, 46, 46, 34, //
+            43, 46, 46, 48,
+        ],
+    )
+    .unwrap();
+
+    op_test!("MaxPool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 strides: vec![2i64, 2],
+                 pads: vec![0i64, 0, 1, 1],
+                 ceil_mode: 1i64,
+             },
+             expect: [expected, indices]);
+}
+
+#[test]
+fn column_major_indices() {
+    // The pixel at row r and column c is at 7 * c + r.
+    let expected = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            42.0, 44.0, 46.0, 46.0, //
+            48.0, 48.0, 46.0, 46.0, //
+            45.0, 47.0, 47.0, 39.0, //
+            45.0, 47.0, 47.0, 32.0,
+        ],
+    )
+    .unwrap();
+    let indices = Array::from_shape_vec(
+        (1, 1, 4, 4),
+        vec![
+            2i64, 23, 44, 44, //
+            17, 17, 44, 44, //
+            13, 34, 34, 46, //
+            13, 34, 34, 48,
+        ],
+    )
+    .unwrap();
+
+    op_test!("MaxPool",
+             inputs: [input()],
+             attrs: {
+                 kernel_shape: vec![3i64, 3],
+                 strides: vec![2i64, 2],
+                 pads: vec![0i64, 0, 1, 1],
+                 ceil_mode: 1i64,
+                 storage_order: 1i64,
+             },
+             expect: [expected, indices]);
+}
@@ -0,0 +1,147 @@
+//! Runs a network through the library alone, so that it passes with the minimal features:
+//!
+//! ```text
+//! cargo test --no-default-features --test minimal_features
+//! ```
+//!
+//! A small convolutional classifier built in the test is run on every build; MNIST is run against
+//! its test data by an ignored test, once the models are pulled.
+
+mod common;
+
+use ndarray::{arr1, arr2, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::assert_tensors_close;
+
+const MODEL: &str = "models/mnist-8/mnist-8.onnx";
+const INPUT: &str = "models/mnist-8/test_data_set_0/input_0.pb";
+const OUTPUT: &str = "models/mnist-8/test_data_set_0/output_0.pb";
+
+fn value_info(name: &str, shape: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        shape.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn ints(name: &str, values: &[i64]) -> AttributeProto {
+    make_attribute(name, Attribute::<String>::Ints(values.to_vec()))
+}
+
+/// `Add(MatMul(Flatten(MaxPool(Relu(Conv(x))))))` on a 1x1x4x4 image, the layers of MNIST: the
+/// 3x3 kernel of ones sums its window, and the bias of -60 leaves two positive sums.
+fn classifier() -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str, attributes| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            attributes,
+        )
+    };
+    let nodes = vec![
+        node(
+            "Conv",
+            vec!["x", "conv_w", "conv_b"],
+            "conv",
+            vec![ints("kernel_shape", &[3, 3])],
+        ),
+        node("Relu", vec!["conv"], "relu", vec![]),
+        node(
+            "MaxPool",
+            vec!["relu"],
+            "pool",
+            vec![ints("kernel_shape", &[2, 2])],
+        ),
+        node(
+            "Flatten",
+            vec!["pool"],
+            "flat",
+            vec![make_attribute("axis", Attribute::<String>::Int(1))],
+        ),
+        node("MatMul", vec!["flat", "fc_w"], "logits", vec![]),
+        node("Add", vec!["logits", "fc_b"], "y", vec![]),
+    ];
+    let tensor = |values: ArrayD<f32>, name: &str| ndarray_to_tensor_proto::<f32>(values, name);
+    let initializers = vec![
+        tensor(ArrayD::ones(IxDyn(&[1, 1, 3, 3])), "conv_w").unwrap(),
+        tensor(arr1(&[-60.0]).into_dyn(), "conv_b").unwrap(),
+        tensor(arr2(&[[1.0, -1.0, 0.5]]).into_dyn(), "fc_w").unwrap(),
+        tensor(arr1(&[0.0, 1.0, 2.0]).into_dyn(), "fc_b").unwrap(),
+    ];
+    let graph = make_graph(
+        nodes,
+        "classifier",
+        vec![value_info("x", &[1, 1, 4, 4])],
+        vec![value_info("y", &[1, 3])],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn synthetic_classifier() {
+    let session = Session::builder()
+        .model(classifier())
+        .build()
+        .expect("the model should load");
+    let x = Array::range(0.0f32, 16.0, 1.0)
+        .into_shape(vec![1, 1, 4, 4])
+        .unwrap();
+    let outputs = session
+        .run_with_inputs(vec![Tensor::from_ndarray(x).with_name("x")])
+        .expect("the model should run");
+
+    // The window sums are [[45, 54], [81, 90]], so the pooled feature is 90 - 60 = 30.
+    assert_eq!(
+        outputs[0].to_ndarray::<f32>().unwrap(),
+        arr2(&[[30.0, -29.0, 17.0]]).into_dyn()
+    );
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn mnist_matches_its_test_data() {
+    common::require_model(MODEL);
+
+    let session = Session::builder()
+        .model_path(MODEL)
+        .build()
+        .expect("the model should load");
+    let input = OnnxParser::load_data(INPUT.to_string()).expect("the input should load");
+    let expected = OnnxParser::load_data(OUTPUT.to_string()).expect("the output should load");
+
+    let output = session.run(input).expect("the model should run");
+
+    assert_tensors_close(
+        &output.to_ndarray::<f32>().unwrap(),
+        &expected.to_ndarray::<f32>().unwrap(),
+        1e-4,
+        1e-3,
+    );
+}
@@ -0,0 +1,153 @@
+//! Detects the NaN and infinite values produced by the nodes of a graph:
+//!
+//! ```text
+//! cargo test --test non_finite
+//! ```
+//!
+//! The graph takes the logarithm of a rectified input, so a negative element becomes Log(0) =
+//! -inf, and the following nodes carry the infinity to the output: the check must blame the Log,
+//! not the last node.
+
+use ndarray::{arr2, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::{NonFiniteCheck, RunOptions};
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+
+/// `Abs(Log(Relu(x)) * 2)`, or `Abs(Log(x) * 2)` without the Relu.
+fn model(rectified: bool) -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let mut nodes = vec![
+        node("Log", vec!["rectified"], "logarithm"),
+        node("Mul", vec!["logarithm", "two"], "scaled"),
+        node("Abs", vec!["scaled"], "y"),
+    ];
+    if rectified {
+        nodes.insert(0, node("Relu", vec!["x"], "rectified"));
+    } else {
+        nodes[0].mut_input()[0] = "x".to_string();
+    }
+    let two = ndarray_to_tensor_proto::<f32>(ArrayD::from_elem(vec![], 2.0), "two").unwrap();
+
+    let value_info = |name: &str| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            vec![Dimension::Value(2), Dimension::Value(3)],
+            None,
+        )
+    };
+    let graph = make_graph(
+        nodes,
+        "log",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![two],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Positive except for the element at [1, 2].
+fn input() -> TensorProto {
+    let x = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, -6.0]]).into_dyn();
+    ndarray_to_tensor_proto::<f32>(x, "x").unwrap()
+}
+
+fn options(check: NonFiniteCheck) -> RunOptions {
+    RunOptions {
+        non_finite_check: check,
+        ..RunOptions::default()
+    }
+}
+
+fn run(model: &ModelProto, check: NonFiniteCheck) -> Result<ArrayD<f32>, OnnxError> {
+    let mut outputs = run_with_inputs(model, vec![input()], &options(check))?;
+    Ok(tensor_proto_to_ndarray::<f32>(&outputs.remove(0)).unwrap())
+}
+
+#[test]
+fn not_checked_by_default() {
+    assert_eq!(RunOptions::default().non_finite_check, NonFiniteCheck::Off);
+
+    let output = run(&model(true), NonFiniteCheck::Off).unwrap();
+    assert_eq!(output[[1, 2]], f32::INFINITY);
+    assert!(output.iter().take(5).all(|value| value.is_finite()));
+}
+
+#[test]
+fn abort_points_at_the_log() {
+    let error = run(&model(true), NonFiniteCheck::Abort).unwrap_err();
+    match error.root_cause() {
+        OnnxError::NonFiniteOutput {
+            node_name,
+            op_type,
+            first_bad_index,
+        } => {
+            assert_eq!(node_name, "logarithm");
+            assert_eq!(op_type, "Log");
+            assert_eq!(first_bad_index, &[1, 2]);
+        }
+        other => panic!("Expected a non-finite output, got {:?}", other),
+    }
+    let message = error.to_string();
+    assert!(
+        message.contains("logarithm") && message.contains("[1, 2]"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn abort_on_nan() {
+    // Without the Relu, the Log of -6 is NaN.
+    let error = run(&model(false), NonFiniteCheck::Abort).unwrap_err();
+    match error.root_cause() {
+        OnnxError::NonFiniteOutput {
+            node_name,
+            first_bad_index,
+            ..
+        } => {
+            assert_eq!(node_name, "logarithm");
+            assert_eq!(first_bad_index, &[1, 2]);
+        }
+        other => panic!("Expected a non-finite output, got {:?}", other),
+    }
+}
+
+#[test]
+fn warn_continues() {
+    let model = model(true);
+    let warned = run(&model, NonFiniteCheck::Warn).unwrap();
+    let unchecked = run(&model, NonFiniteCheck::Off).unwrap();
+    assert_eq!(warned, unchecked);
+
+    // A finite run passes the strictest check.
+    let x = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn();
+    let x = ndarray_to_tensor_proto::<f32>(x, "x").unwrap();
+    assert!(run_with_inputs(&model, vec![x], &options(NonFiniteCheck::Abort)).is_ok());
+}
@@ -0,0 +1,161 @@
+//! Runs NonMaxSuppression through the executor:
+//!
+//! ```text
+//! cargo test --test non_max_suppression
+//! ```
+//!
+//! The boxes and the expected selections are the ones of the ONNX backend tests: three
+//! overlapping boxes around `[0, 0, 1, 1]`, two around `[0, 10, 1, 11]` and one at
+//! `[0, 100, 1, 101]`.
+
+use ndarray::{arr2, arr3, concatenate, Array2, Array3, Axis};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+/// The boxes as two opposite corners `[y1, x1, y2, x2]`.
+fn boxes() -> Array3<f32> {
+    arr3(&[[
+        [0.0, 0.0, 1.0, 1.0],
+        [0.0, 0.1, 1.0, 1.1],
+        [0.0, -0.1, 1.0, 0.9],
+        [0.0, 10.0, 1.0, 11.0],
+        [0.0, 10.1, 1.0, 11.1],
+        [0.0, 100.0, 1.0, 101.0],
+    ]])
+}
+
+fn scores() -> Array3<f32> {
+    arr3(&[[[0.9, 0.75, 0.6, 0.95, 0.5, 0.3]]])
+}
+
+fn non_max_suppression(boxes: Array3<f32>, scores: Array3<f32>, max_boxes: i64) -> OpTest {
+    OpTest::new("NonMaxSuppression")
+        .opset(14)
+        .input(boxes)
+        .input(scores)
+        .input(scalar(max_boxes))
+        .input(scalar(0.5f32))
+}
+
+#[test]
+fn suppress_by_iou() {
+    // The best box of each cluster, from the best score on.
+    non_max_suppression(boxes(), scores(), 3)
+        .input(scalar(0.0f32))
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]))
+        .check();
+}
+
+#[test]
+fn center_point_box() {
+    let centers = arr3(&[[
+        [0.5, 0.5, 1.0, 1.0],
+        [0.5, 0.6, 1.0, 1.0],
+        [0.5, 0.4, 1.0, 1.0],
+        [0.5, 10.5, 1.0, 1.0],
+        [0.5, 10.6, 1.0, 1.0],
+        [0.5, 100.5, 1.0, 1.0],
+    ]]);
+    non_max_suppression(centers, scores(), 3)
+        .input(scalar(0.0f32))
+        .attribute("center_point_box", 1i64)
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]))
+        .check();
+}
+
+#[test]
+fn flipped_coordinates() {
+    let flipped = arr3(&[[
+        [1.0, 1.0, 0.0, 0.0],
+        [0.0, 0.1, 1.0, 1.1],
+        [0.0, 0.9, 1.0, -0.1],
+        [0.0, 10.0, 1.0, 11.0],
+        [1.0, 10.1, 0.0, 11.1],
+        [1.0, 101.0, 0.0, 100.0],
+    ]]);
+    non_max_suppression(flipped, scores(), 3)
+        .input(scalar(0.0f32))
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]))
+        .check();
+}
+
+#[test]
+fn identical_boxes() {
+    // Of equal scores, the first box is taken.
+    let boxes = Array3::from_shape_fn((1, 10, 4), |(_, _, i)| [0.0, 0.0, 1.0, 1.0][i]);
+    non_max_suppression(boxes, Array3::from_elem((1, 1, 10), 0.9), 3)
+        .input(scalar(0.0f32))
+        .expect(arr2(&[[0i64, 0, 0]]))
+        .check();
+}
+
+#[test]
+fn limit_output_size() {
+    non_max_suppression(boxes(), scores(), 2)
+        .input(scalar(0.0f32))
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0]]))
+        .check();
+}
+
+#[test]
+fn score_threshold() {
+    // The box at [0, 100, 1, 101] scores 0.3.
+    non_max_suppression(boxes(), scores(), 3)
+        .input(scalar(0.4f32))
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0]]))
+        .check();
+}
+
+#[test]
+fn no_score_threshold() {
+    non_max_suppression(boxes(), scores(), 3)
+        .expect(arr2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]))
+        .check();
+}
+
+#[test]
+fn two_batches_and_two_classes() {
+    let boxes = concatenate![Axis(0), boxes(), boxes()];
+    let scores = concatenate![Axis(1), scores(), scores()];
+    let scores = concatenate![Axis(0), scores, scores];
+    non_max_suppression(boxes, scores, 2)
+        .input(scalar(0.0f32))
+        .expect(arr2(&[
+            [0i64, 0, 3],
+            [0, 0, 0],
+            [0, 1, 3],
+            [0, 1, 0],
+            [1, 0, 3],
+            [1, 0, 0],
+            [1, 1, 3],
+            [1, 1, 0],
+        ]))
+        .check();
+}
+
+#[test]
+fn no_box_by_default() {
+    OpTest::new("NonMaxSuppression")
+        .opset(14)
+        .input(boxes())
+        .input(scores())
+        .expect(Array2::<i64>::zeros((0, 3)))
+        .check();
+}
+
+#[test]
+fn iou_threshold_out_of_range() {
+    let error = OpTest::new("NonMaxSuppression")
+        .opset(14)
+        .input(boxes())
+        .input(scores())
+        .input(scalar(3i64))
+        .input(scalar(1.5f32))
+        .expect(Array2::<i64>::zeros((0, 3)))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The iou_threshold of NonMaxSuppression must be between 0 and 1, got 1.5"),
+        "{}",
+        error
+    );
+}
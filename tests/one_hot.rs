@@ -0,0 +1,97 @@
+//! Runs OneHot through the executor:
+//!
+//! ```text
+//! cargo test --test one_hot
+//! ```
+//!
+//! The cases are the examples of the ONNX operator documentation.
+
+use ndarray::{arr1, arr2, Array2, Array3};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+/// The rows of `depth` values, `on` at the given columns and `off` elsewhere.
+fn rows<T: Copy>(hot: &[Option<usize>], depth: usize, off: T, on: T) -> Array2<T> {
+    let mut rows = Array2::from_elem((hot.len(), depth), off);
+    for (row, &column) in hot.iter().enumerate() {
+        if let Some(column) = column {
+            rows[[row, column]] = on;
+        }
+    }
+    rows
+}
+
+#[test]
+fn without_axis() {
+    // The last axis by default, with INT32 values and a FLOAT depth.
+    OpTest::new("OneHot")
+        .opset(11)
+        .input(arr1(&[0i64, 7, 8]))
+        .input(scalar(12.0f32))
+        .input(arr1(&[2i32, 5]))
+        .expect(rows(&[Some(0), Some(7), Some(8)], 12, 2i32, 5))
+        .check();
+}
+
+#[test]
+fn with_axis() {
+    // The indices [[1, 9], [2, 4]] along the axis 1 of a [2, 10, 2] output.
+    let mut expected = Array3::from_elem((2, 10, 2), 1.0f32);
+    for (i, j, index) in [(0, 0, 1), (0, 1, 9), (1, 0, 2), (1, 1, 4)] {
+        expected[[i, index, j]] = 3.0;
+    }
+    OpTest::new("OneHot")
+        .opset(11)
+        .input(arr2(&[[1.0f32, 9.0], [2.0, 4.0]]))
+        .input(scalar(10.0f32))
+        .input(arr1(&[1.0f32, 3.0]))
+        .attribute("axis", 1i64)
+        .expect(expected)
+        .check();
+}
+
+#[test]
+fn negative_axis() {
+    // -2 is the first of the two axes of the output.
+    OpTest::new("OneHot")
+        .opset(11)
+        .input(arr1(&[1i64, 0]))
+        .input(scalar(3i64))
+        .input(arr1(&[0i64, 1]))
+        .attribute("axis", -2i64)
+        .expect(arr2(&[[0i64, 1], [1, 0], [0, 0]]))
+        .check();
+}
+
+#[test]
+fn negative_indices() {
+    // -7 and -8 count from the end; 10 and -11 are out of range, all off.
+    OpTest::new("OneHot")
+        .opset(11)
+        .input(arr1(&[0i64, -7, -8, 10, -11]))
+        .input(scalar(10.0f32))
+        .input(arr1(&[1.0f32, 3.0]))
+        .expect(rows(
+            &[Some(0), Some(3), Some(2), None, None],
+            10,
+            1.0f32,
+            3.0,
+        ))
+        .check();
+}
+
+#[test]
+fn invalid_values() {
+    let error = OpTest::new("OneHot")
+        .opset(11)
+        .input(arr1(&[0i64]))
+        .input(scalar(2i64))
+        .input(arr1(&[0.0f32, 1.0, 2.0]))
+        .expect(arr2(&[[1.0f32, 0.0]]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The values of a OneHot must be the two values [off, on]"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,212 @@
+//! Cross-references the nodes of a model with the kernels of the executor:
+//!
+//! ```text
+//! cargo test --test ops_report
+//! ```
+//!
+//! The test model imports opset 13 and has one operation of every status: an unknown operation
+//! inside a branch of an If, a Gelu (implemented since opset 20), and a Sqrt of INT64 values.
+//! The JSON report is checked with the `serde` feature.
+
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    GraphProto, ModelProto, NodeProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::transforms::{ops_report, OperatorCoverage, OperatorStatus, OpsReport};
+
+fn value_info(name: &str, data_type: TensorProto_DataType, dims: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        data_type,
+        dims.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn node(op_type: &str, inputs: Vec<&str>, output: &str) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        vec![],
+    )
+}
+
+/// A branch without inputs or type information: the types of the captured tensors come from the
+/// enclosing graph.
+fn branch(name: &str, nodes: Vec<NodeProto>, output: &str) -> GraphProto {
+    let mut output_info = ValueInfoProto::new();
+    output_info.set_name(output.to_string());
+    make_graph(nodes, name, vec![], vec![output_info], vec![], None)
+}
+
+/// `If(cond, Relu(Frobnicate(Relu(x))), Sqrt(x))`, next to `Gelu(x)` and `Sqrt(n)`.
+fn model() -> ModelProto {
+    let float = TensorProto_DataType::FLOAT;
+    let then_branch = branch(
+        "then",
+        vec![
+            node("Frobnicate", vec!["rectified"], "frobnicated"),
+            node("Relu", vec!["frobnicated"], "then_out"),
+        ],
+        "then_out",
+    );
+    let else_branch = branch(
+        "else",
+        vec![node("Sqrt", vec!["x"], "else_out")],
+        "else_out",
+    );
+    let nodes = vec![
+        node("Relu", vec!["x"], "rectified"),
+        node("Gelu", vec!["x"], "gelu"),
+        node("Sqrt", vec!["n"], "root"),
+        make_node(
+            "If",
+            vec!["cond"],
+            vec!["y"],
+            Some("if"),
+            None,
+            None,
+            vec![
+                make_attribute("then_branch", Attribute::<String>::Graph(then_branch)),
+                make_attribute("else_branch", Attribute::<String>::Graph(else_branch)),
+            ],
+        ),
+    ];
+    let graph = make_graph(
+        nodes,
+        "coverage",
+        vec![
+            value_info("x", float, &[2]),
+            value_info("n", TensorProto_DataType::INT64, &[2]),
+            value_info("cond", TensorProto_DataType::BOOL, &[]),
+        ],
+        vec![
+            value_info("y", float, &[2]),
+            value_info("gelu", float, &[2]),
+            value_info("root", TensorProto_DataType::INT64, &[2]),
+        ],
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn operator<'a>(report: &'a OpsReport, op_type: &str) -> &'a OperatorCoverage {
+    report
+        .operators
+        .iter()
+        .find(|operator| operator.op_type == op_type)
+        .unwrap_or_else(|| panic!("{} isn't in the report", op_type))
+}
+
+#[test]
+fn statuses() {
+    let report = ops_report(&model());
+    assert!(!report.is_fully_supported());
+    assert_eq!(report.opsets, [(String::new(), 13)]);
+
+    // Unsupported operations first, then by name.
+    let statuses: Vec<(&str, OperatorStatus)> = report
+        .operators
+        .iter()
+        .map(|operator| (operator.op_type.as_str(), operator.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            ("Frobnicate", OperatorStatus::Unsupported),
+            ("Gelu", OperatorStatus::OpsetMismatch),
+            ("Sqrt", OperatorStatus::UnsupportedTypes),
+            ("If", OperatorStatus::Supported),
+            ("Relu", OperatorStatus::Supported),
+        ]
+    );
+
+    let frobnicate = operator(&report, "Frobnicate");
+    assert_eq!(frobnicate.kernel_opsets, None);
+    assert!(frobnicate.kernel_types.is_empty());
+
+    let gelu = operator(&report, "Gelu");
+    assert_eq!(gelu.requested_opset, Some(13));
+    assert_eq!(gelu.kernel_opsets.map(|(first, _)| first), Some(20));
+}
+
+#[test]
+fn subgraph_nodes() {
+    let report = ops_report(&model());
+
+    // The Relu of the then branch reads the output of the unknown operation.
+    let relu = operator(&report, "Relu");
+    assert_eq!((relu.count, relu.unknown_inputs), (2, 1));
+    assert_eq!(relu.input_types, ["FLOAT"]);
+
+    // The Sqrt of the else branch reads the input x of the model.
+    let sqrt = operator(&report, "Sqrt");
+    assert_eq!((sqrt.count, sqrt.unknown_inputs), (2, 0));
+    assert_eq!(sqrt.input_types, ["FLOAT", "INT64"]);
+    assert!(!sqrt.kernel_types.contains(&"INT64".to_string()));
+
+    assert_eq!(operator(&report, "Frobnicate").input_types, ["FLOAT"]);
+    assert_eq!(operator(&report, "If").input_types, ["BOOL"]);
+}
+
+#[test]
+fn fully_supported() {
+    let mut model = model();
+    let graph = model.mut_graph();
+    graph.mut_node().remove(2);
+    graph.mut_node().remove(1);
+    graph.mut_node()[1].mut_attribute()[0].mut_g().mut_node()[0].set_op_type("Neg".to_string());
+
+    let report = ops_report(&model);
+    assert!(report.is_fully_supported(), "{:?}", report.operators);
+    assert_eq!(report.operators.len(), 4);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json() {
+    let json: serde_json::Value = serde_json::from_str(&ops_report(&model()).to_json()).unwrap();
+    assert_eq!(json["opsets"], serde_json::json!([["", 13]]));
+
+    let operators = json["operators"].as_array().unwrap();
+    assert_eq!(operators.len(), 5);
+    let statuses: Vec<&str> = operators
+        .iter()
+        .map(|operator| operator["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            "unsupported",
+            "opset_mismatch",
+            "unsupported_types",
+            "supported",
+            "supported"
+        ]
+    );
+
+    let gelu = &operators[1];
+    assert_eq!(gelu["op_type"], "Gelu");
+    assert_eq!(gelu["count"], 1);
+    assert_eq!(gelu["requested_opset"], 13);
+    assert_eq!(gelu["kernel_opsets"][0], 20);
+    assert_eq!(gelu["input_types"], serde_json::json!(["FLOAT"]));
+    assert_eq!(operators[0]["kernel_opsets"], serde_json::Value::Null);
+}
@@ -0,0 +1,210 @@
+//! Removes and merges the Transpose nodes of graphs, and compares the runs of the graphs before
+//! and after:
+//!
+//! ```text
+//! cargo test --test optimize_transposes
+//! ```
+//!
+//! The graphs move between the NCHW and NHWC layouts, as the ones converted from TensorFlow do.
+
+use ndarray::ArrayD;
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::passes::{eliminate_pass_through_nodes, optimize_transposes};
+use onnx_rustime_lib::transforms::{self, OptLevel};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const SHAPE: [usize; 4] = [2, 3, 4, 5];
+const TO_NHWC: [i64; 4] = [0, 2, 3, 1];
+const TO_NCHW: [i64; 4] = [0, 3, 1, 2];
+
+fn node(op_type: &str, input: &str, output: &str) -> NodeProto {
+    make_node(
+        op_type,
+        vec![input],
+        vec![output],
+        Some(output),
+        None,
+        None,
+        vec![],
+    )
+}
+
+fn transpose(input: &str, output: &str, perm: &[i64]) -> NodeProto {
+    let mut node = node("Transpose", input, output);
+    node.mut_attribute().push(make_attribute(
+        "perm",
+        Attribute::<String>::Ints(perm.to_vec()),
+    ));
+    node
+}
+
+fn value_info(name: &str) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        SHAPE
+            .iter()
+            .map(|&dim| Dimension::Value(dim as i64))
+            .collect(),
+        None,
+    )
+}
+
+fn model(nodes: Vec<NodeProto>, outputs: Vec<&str>) -> ModelProto {
+    let outputs = outputs
+        .into_iter()
+        .map(|name| {
+            let mut output = ValueInfoProto::new();
+            output.set_name(name.to_string());
+            output
+        })
+        .collect();
+    let graph = make_graph(
+        nodes,
+        "transpose",
+        vec![value_info("x")],
+        outputs,
+        vec![],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Applies the transpose pass to a copy of the model, returning whether something changed.
+fn optimize(model: &ModelProto) -> (ModelProto, bool) {
+    let mut optimized = model.clone();
+    let changed = optimize_transposes(optimized.mut_graph()).unwrap();
+    (optimized, changed)
+}
+
+fn run(model: &ModelProto) -> Vec<ArrayD<f32>> {
+    let mut rng = StdRng::seed_from_u64(223);
+    let x = ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &SHAPE), "x").unwrap();
+    run_with_inputs(model, vec![x], &RunOptions::default())
+        .unwrap()
+        .iter()
+        .map(|output| tensor_proto_to_ndarray::<f32>(output).unwrap())
+        .collect()
+}
+
+fn op_types(model: &ModelProto) -> Vec<&str> {
+    model
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_op_type())
+        .collect()
+}
+
+#[test]
+fn inverse_pair_cancels() {
+    let model = model(
+        vec![
+            transpose("x", "nhwc", &TO_NHWC),
+            transpose("nhwc", "nchw", &TO_NCHW),
+            node("Relu", "nchw", "y"),
+        ],
+        vec!["y"],
+    );
+    let (mut optimized, changed) = optimize(&model);
+
+    assert!(changed);
+    assert_eq!(op_types(&optimized), ["Identity", "Relu"]);
+    assert_eq!(optimized.get_graph().get_node()[0].get_input(), ["x"]);
+    assert!(optimized.get_graph().get_node()[0]
+        .get_attribute()
+        .is_empty());
+    assert_eq!(run(&optimized), run(&model));
+
+    // The Identity is left to the pass-through elimination.
+    assert!(eliminate_pass_through_nodes(optimized.mut_graph()).unwrap());
+    assert_eq!(op_types(&optimized), ["Relu"]);
+    assert_eq!(optimized.get_graph().get_node()[0].get_input(), ["x"]);
+    assert_eq!(run(&optimized), run(&model));
+}
+
+#[test]
+fn permutations_are_merged() {
+    let model = model(
+        vec![
+            transpose("x", "first", &[1, 0, 2, 3]),
+            transpose("first", "second", &[0, 2, 3, 1]),
+            node("Relu", "second", "y"),
+        ],
+        vec!["y"],
+    );
+    let (optimized, changed) = optimize(&model);
+
+    assert!(changed);
+    assert_eq!(op_types(&optimized), ["Transpose", "Relu"]);
+    let merged = &optimized.get_graph().get_node()[0];
+    assert_eq!(merged.get_input(), ["x"]);
+    assert_eq!(merged.get_output(), ["second"]);
+    assert_eq!(merged.get_attribute()[0].get_ints(), [1, 2, 3, 0]);
+    assert_eq!(run(&optimized), run(&model));
+
+    // The Basic level runs the passes until nothing changes, merging a chain of three into one.
+    let model = self::model(
+        vec![
+            transpose("x", "nhwc", &TO_NHWC),
+            transpose("nhwc", "nchw", &TO_NCHW),
+            transpose("nchw", "y", &TO_NHWC),
+        ],
+        vec!["y"],
+    );
+    let optimized = transforms::optimize(&model, OptLevel::Basic).unwrap();
+    assert_eq!(op_types(&optimized), ["Transpose"]);
+    assert_eq!(
+        optimized.get_graph().get_node()[0].get_attribute()[0].get_ints(),
+        TO_NHWC
+    );
+    assert_eq!(run(&optimized), run(&model));
+}
+
+#[test]
+fn transposes_with_other_consumers_are_kept() {
+    // The NHWC tensor is also read by the Relu, so the first Transpose has to be computed.
+    let shared = model(
+        vec![
+            transpose("x", "nhwc", &TO_NHWC),
+            transpose("nhwc", "y", &TO_NCHW),
+            node("Relu", "nhwc", "z"),
+        ],
+        vec!["y", "z"],
+    );
+    let (optimized, changed) = optimize(&shared);
+    assert!(!changed);
+    assert_eq!(optimized, shared);
+
+    // The same when the NHWC tensor is a graph output.
+    let output = model(
+        vec![
+            transpose("x", "nhwc", &TO_NHWC),
+            transpose("nhwc", "y", &TO_NCHW),
+        ],
+        vec!["nhwc", "y"],
+    );
+    let (optimized, changed) = optimize(&output);
+    assert!(!changed);
+    assert_eq!(optimized, output);
+}
@@ -0,0 +1,338 @@
+//! Optimizes models at every level and compares the runs of the optimized models with the ones of
+//! the original models:
+//!
+//! ```text
+//! cargo test --test optimizer
+//! ```
+//!
+//! The residual network is a small ResNet (two convolution blocks, each followed by
+//! BatchNormalization, with a skip connection, then pooling and a classifier) whose final Reshape
+//! reads a target shape computed by constant nodes. ResNet itself is also checked, by an ignored
+//! test run once the models are pulled.
+
+mod common;
+
+use ndarray::arr1;
+use ndarray::ArrayD;
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::assert_tensors_close;
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::{optimize, OptLevel, PassManager};
+use protobuf::Message;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const RESNET: &str = "models/resnet18-v2-7/resnet18-v2-7.onnx";
+const RESNET_INPUT: &str = "models/resnet18-v2-7/test_data_set_0/input_0.pb";
+
+const CHANNELS: usize = 8;
+const CLASSES: usize = 10;
+
+fn node(op_type: &str, inputs: Vec<&str>, output: &str, attributes: Vec<(&str, i64)>) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        attributes
+            .into_iter()
+            .map(|(name, value)| make_attribute(name, Attribute::<String>::Int(value)))
+            .collect(),
+    )
+}
+
+fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        dims.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+struct Initializers(StdRng, Vec<TensorProto>);
+
+impl Initializers {
+    fn add(&mut self, name: &str, shape: &[usize], f: impl Fn(f32) -> f32) {
+        let values = random_tensor(&mut self.0, shape).mapv(f);
+        self.1
+            .push(ndarray_to_tensor_proto::<f32>(values, name).unwrap());
+    }
+
+    /// The weights of a 3x3 convolution and of the BatchNormalization following it.
+    fn add_block(&mut self, name: &str) {
+        self.add(&format!("{}_w", name), &[CHANNELS, CHANNELS, 3, 3], |x| {
+            x * 0.3
+        });
+        self.add(&format!("{}_scale", name), &[CHANNELS], |x| 1.0 + 0.2 * x);
+        self.add(&format!("{}_bias", name), &[CHANNELS], |x| 0.1 * x);
+        self.add(&format!("{}_mean", name), &[CHANNELS], |x| 0.1 * x);
+        self.add(&format!("{}_var", name), &[CHANNELS], |x| 0.5 + x.abs());
+    }
+}
+
+/// Conv (3x3, same padding), then BatchNormalization, from `input` to `output`.
+fn block(name: &str, input: &str, output: &str) -> Vec<NodeProto> {
+    let w = format!("{}_w", name);
+    let conv = format!("{}_conv", name);
+    let parameters: Vec<String> = ["scale", "bias", "mean", "var"]
+        .iter()
+        .map(|parameter| format!("{}_{}", name, parameter))
+        .collect();
+    let mut batch_normalization_inputs = vec![conv.as_str()];
+    batch_normalization_inputs.extend(parameters.iter().map(String::as_str));
+
+    let mut conv_node = node("Conv", vec![input, &w], &conv, vec![]);
+    conv_node.mut_attribute().push(make_attribute(
+        "pads",
+        Attribute::<String>::Ints(vec![1; 4]),
+    ));
+    vec![
+        conv_node,
+        node(
+            "BatchNormalization",
+            batch_normalization_inputs,
+            output,
+            vec![],
+        ),
+    ]
+}
+
+fn residual_network() -> ModelProto {
+    let mut initializers = Initializers(StdRng::seed_from_u64(220), vec![]);
+    initializers.add_block("block1");
+    initializers.add_block("block2");
+    initializers.add("fc_w", &[CHANNELS, CLASSES], |x| x * 0.5);
+    initializers.add("fc_b", &[CLASSES], |x| x * 0.1);
+
+    let mut nodes = vec![];
+    nodes.extend(block("block1", "x", "block1_out"));
+    nodes.push(node("Relu", vec!["block1_out"], "relu1", vec![]));
+    nodes.extend(block("block2", "relu1", "block2_out"));
+    nodes.push(node("Add", vec!["block2_out", "x"], "residual", vec![]));
+    nodes.push(node("Relu", vec!["residual"], "relu2", vec![]));
+    nodes.push(node("GlobalAveragePool", vec!["relu2"], "pooled", vec![]));
+    // The target shape [1, -1], computed by the graph as exporters do.
+    let mut batch = node("Constant", vec![], "batch", vec![]);
+    batch.mut_attribute().push(make_attribute(
+        "value",
+        Attribute::<String>::Tensor(
+            ndarray_to_tensor_proto::<i64>(arr1(&[1i64]).into_dyn(), "batch").unwrap(),
+        ),
+    ));
+    let mut rest = node("Constant", vec![], "rest", vec![]);
+    rest.mut_attribute().push(make_attribute(
+        "value",
+        Attribute::<String>::Tensor(
+            ndarray_to_tensor_proto::<i64>(arr1(&[-1i64]).into_dyn(), "rest").unwrap(),
+        ),
+    ));
+    nodes.push(batch);
+    nodes.push(rest);
+    nodes.push(node(
+        "Concat",
+        vec!["batch", "rest"],
+        "shape",
+        vec![("axis", 0)],
+    ));
+    nodes.push(node("Reshape", vec!["pooled", "shape"], "features", vec![]));
+    nodes.push(node("Identity", vec!["features"], "features_copy", vec![]));
+    nodes.push(node(
+        "Gemm",
+        vec!["features_copy", "fc_w", "fc_b"],
+        "y",
+        vec![],
+    ));
+
+    let graph = make_graph(
+        nodes,
+        "residual_network",
+        vec![value_info("x", &[1, CHANNELS as i64, 6, 6])],
+        vec![value_info("y", &[1, CLASSES as i64])],
+        initializers.1,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn image() -> TensorProto {
+    let mut rng = StdRng::seed_from_u64(0);
+    ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[1, CHANNELS, 6, 6]), "x").unwrap()
+}
+
+fn run(model: &ModelProto, input: TensorProto) -> ArrayD<f32> {
+    let outputs = run_with_inputs(model, vec![input], &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+fn count(model: &ModelProto, op_type: &str) -> usize {
+    model
+        .get_graph()
+        .get_node()
+        .iter()
+        .filter(|node| node.get_op_type() == op_type)
+        .count()
+}
+
+#[test]
+fn level_none_is_byte_identical() {
+    let model = residual_network();
+    let optimized = optimize(&model, OptLevel::None).unwrap();
+    assert_eq!(
+        optimized.write_to_bytes().unwrap(),
+        model.write_to_bytes().unwrap()
+    );
+}
+
+#[test]
+fn extended_preserves_the_outputs() {
+    let model = residual_network();
+    let optimized = optimize(&model, OptLevel::Extended).unwrap();
+
+    let nodes = |model: &ModelProto| model.get_graph().get_node().len();
+    assert!(
+        nodes(&optimized) < nodes(&model),
+        "{} nodes optimized to {}",
+        nodes(&model),
+        nodes(&optimized)
+    );
+    for op_type in ["BatchNormalization", "Constant", "Concat", "Identity"] {
+        assert_eq!(count(&optimized, op_type), 0, "{}", op_type);
+    }
+    assert_eq!(count(&optimized, "Conv"), 2);
+
+    assert_tensors_close(&run(&optimized, image()), &run(&model, image()), 1e-4, 1e-4);
+}
+
+#[test]
+fn basic_folds_the_constant_subgraph() {
+    let model = residual_network();
+    let (optimized, report) = PassManager::new(OptLevel::Basic).run(&model).unwrap();
+
+    // The target shape is an initializer, the intermediate constants have been pruned.
+    assert_eq!(count(&optimized, "Concat"), 0);
+    assert_eq!(count(&optimized, "Constant"), 0);
+    let shape = optimized
+        .get_graph()
+        .get_initializer()
+        .iter()
+        .find(|init| init.get_name() == "shape")
+        .unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<i64>(shape).unwrap(),
+        arr1(&[1i64, -1]).into_dyn()
+    );
+    for name in ["batch", "rest"] {
+        assert!(optimized
+            .get_graph()
+            .get_initializer()
+            .iter()
+            .all(|init| init.get_name() != name));
+    }
+    let folding = report
+        .passes
+        .iter()
+        .find(|pass| pass.name == "fold_constants")
+        .unwrap();
+    assert_eq!(folding.nodes_removed, 3);
+
+    // Nothing else than the constants and the Identity is removed at this level.
+    assert_eq!(count(&optimized, "BatchNormalization"), 2);
+    assert_eq!(run(&optimized, image()), run(&model, image()));
+}
+
+#[test]
+fn folding_can_be_disabled() {
+    let mut pass_manager = PassManager::new(OptLevel::Basic);
+    assert!(pass_manager.disable("fold_constants"));
+    let (optimized, _) = pass_manager.run(&residual_network()).unwrap();
+    assert_eq!(count(&optimized, "Concat"), 1);
+    assert_eq!(count(&optimized, "Constant"), 2);
+}
+
+#[test]
+fn overridable_initializers_are_not_folded() {
+    let mut model = residual_network();
+    let graph = model.mut_graph();
+    // The initializers declared among the graph inputs can be replaced by the caller.
+    graph
+        .mut_input()
+        .push(value_info("fc_b", &[CLASSES as i64]));
+    let nodes = [
+        node("Neg", vec!["fc_b"], "negated_bias", vec![]),
+        node("Add", vec!["negated_bias", "fc_b"], "bias_sum", vec![]),
+        node("Neg", vec!["fc_w"], "negated_weights", vec![]),
+        node(
+            "Add",
+            vec!["negated_weights", "fc_w"],
+            "weights_sum",
+            vec![],
+        ),
+    ];
+    graph.mut_node().extend(nodes);
+    graph
+        .mut_output()
+        .push(value_info("bias_sum", &[CLASSES as i64]));
+    graph.mut_output().push(value_info(
+        "weights_sum",
+        &[CHANNELS as i64, CLASSES as i64],
+    ));
+
+    let optimized = optimize(&model, OptLevel::Basic).unwrap();
+    let computed: Vec<&str> = optimized
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_output()[0].as_str())
+        .collect();
+    // Reads an overridable initializer.
+    assert!(computed.contains(&"negated_bias"));
+    // Reads constants only, but computes a graph output.
+    assert!(computed.contains(&"weights_sum"));
+    assert!(!computed.contains(&"negated_weights"));
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn resnet() {
+    common::require_model(RESNET);
+
+    let model = OnnxParser::load_model(RESNET.to_string()).unwrap();
+    let input = OnnxParser::load_tensor_proto(RESNET_INPUT.to_string()).unwrap();
+
+    let unchanged = optimize(&model, OptLevel::None).unwrap();
+    assert_eq!(
+        unchanged.write_to_bytes().unwrap(),
+        model.write_to_bytes().unwrap()
+    );
+
+    let optimized = optimize(&model, OptLevel::Extended).unwrap();
+    assert!(optimized.get_graph().get_node().len() < model.get_graph().get_node().len());
+    assert_tensors_close(
+        &run(&optimized, input.clone()),
+        &run(&model, input),
+        1e-4,
+        1e-4,
+    );
+}
@@ -0,0 +1,199 @@
+//! Runs Pad through the executor, in every mode and with the pads as inputs (opset 11 and later)
+//! or as attributes (before opset 11):
+//!
+//! ```text
+//! cargo test --test pad
+//! ```
+
+use ndarray::{arr1, arr2, arr3, Array2};
+use onnx_rustime_lib::backend::helper::{make_attribute, make_node, Attribute};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::pad;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+fn x() -> Array2<f32> {
+    arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]])
+}
+
+/// A Pad of `x()` by `pads` in `mode` (opset 19).
+fn padded(pads: [i64; 4], mode: &str) -> OpTest {
+    OpTest::new("Pad")
+        .opset(19)
+        .input(x())
+        .input(arr1(&pads))
+        .attribute("mode", mode)
+}
+
+#[test]
+fn constant() {
+    op_test!("Pad",
+             inputs: [arr2(&[[1.0f32, 1.2], [2.3, 3.4], [4.5, 5.7]]), arr1(&[0i64, 2, 0, 0])],
+             attrs: {},
+             expect: arr2(&[[0.0f32, 0.0, 1.0, 1.2], [0.0, 0.0, 2.3, 3.4], [0.0, 0.0, 4.5, 5.7]]));
+    // With a constant value, one row before and two columns after.
+    op_test!("Pad",
+             inputs: [x(), arr1(&[1i64, 0, 0, 2]), arr1(&[9.0f32])],
+             attrs: { mode: "constant" },
+             expect: arr2(&[[9.0f32, 9.0, 9.0, 9.0, 9.0],
+                            [1.0, 2.0, 3.0, 9.0, 9.0],
+                            [4.0, 5.0, 6.0, 9.0, 9.0]]));
+}
+
+#[test]
+fn reflect() {
+    padded([0, 2, 0, 2], "reflect")
+        .expect(arr2(&[
+            [3.0f32, 2.0, 1.0, 2.0, 3.0, 2.0, 1.0],
+            [6.0, 5.0, 4.0, 5.0, 6.0, 5.0, 4.0],
+        ]))
+        .check();
+    // A row before and after a single row reflects it, and beyond the length the mirror repeats.
+    padded([1, 0, 1, 4], "reflect")
+        .expect(arr2(&[
+            [4.0f32, 5.0, 6.0, 5.0, 4.0, 5.0, 6.0],
+            [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0, 5.0, 4.0, 5.0, 6.0],
+            [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn edge() {
+    padded([1, 2, 0, 1], "edge")
+        .expect(arr2(&[
+            [1.0f32, 1.0, 1.0, 2.0, 3.0, 3.0],
+            [1.0, 1.0, 1.0, 2.0, 3.0, 3.0],
+            [4.0, 4.0, 4.0, 5.0, 6.0, 6.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn wrap() {
+    padded([0, 1, 1, 2], "wrap")
+        .expect(arr2(&[
+            [3.0f32, 1.0, 2.0, 3.0, 1.0, 2.0],
+            [6.0, 4.0, 5.0, 6.0, 4.0, 5.0],
+            [3.0, 1.0, 2.0, 3.0, 1.0, 2.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn negative_pads_crop() {
+    padded([0, 0, -1, 0], "constant")
+        .expect(arr2(&[[1.0f32, 2.0, 3.0]]))
+        .check();
+    // The first column is cropped before the last one is repeated.
+    padded([0, -1, 0, 1], "edge")
+        .expect(arr2(&[[2.0f32, 3.0, 3.0], [5.0, 6.0, 6.0]]))
+        .check();
+    padded([0, -1, 0, 2], "reflect")
+        .expect(arr2(&[[2.0f32, 3.0, 2.0, 3.0], [5.0, 6.0, 5.0, 6.0]]))
+        .check();
+}
+
+#[test]
+fn axes() {
+    // Reflect-pads the time axis of a [batch, channels, time] signal, as before a Conv1d.
+    OpTest::new("Pad")
+        .opset(18)
+        .input(arr3(&[[[1.0f32, 2.0, 3.0, 4.0]]]))
+        .input(arr1(&[2i64, 1]))
+        .input(arr1(&[0.0f32]))
+        .input(arr1(&[-1i64]))
+        .attribute("mode", "reflect")
+        .expect(arr3(&[[[3.0f32, 2.0, 1.0, 2.0, 3.0, 4.0, 3.0]]]))
+        .check();
+
+    // The constant value can be omitted before the axes.
+    let tensor = |values: &[i64], name: &str| {
+        ndarray_to_tensor_proto::<i64>(arr1(values).into_dyn(), name).unwrap()
+    };
+    let data = ndarray_to_tensor_proto::<f32>(x().into_dyn(), "data").unwrap();
+    let (pads, axes) = (tensor(&[1, 0], "pads"), tensor(&[0], "axes"));
+    let node = make_node(
+        "Pad",
+        vec!["data", "pads", "", "axes"],
+        vec!["y"],
+        None,
+        None,
+        None,
+        vec![],
+    );
+    let output = pad(&[Some(&data), Some(&pads), None, Some(&axes)], &node).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output).unwrap(),
+        arr2(&[[0.0f32, 0.0, 0.0], [1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn()
+    );
+}
+
+#[test]
+fn attributes_before_opset_11() {
+    OpTest::new("Pad")
+        .opset(2)
+        .input(x())
+        .attribute("pads", vec![1i64, 0, 0, 1])
+        .attribute("value", 7.0f32)
+        .expect(arr2(&[
+            [7.0f32, 7.0, 7.0, 7.0],
+            [1.0, 2.0, 3.0, 7.0],
+            [4.0, 5.0, 6.0, 7.0],
+        ]))
+        .check();
+
+    let data = ndarray_to_tensor_proto::<f32>(x().into_dyn(), "data").unwrap();
+    let node = make_node(
+        "Pad",
+        vec!["data"],
+        vec!["y"],
+        None,
+        None,
+        None,
+        vec![
+            make_attribute("pads", Attribute::<String>::Ints(vec![0, 1, 0, 0])),
+            make_attribute("mode", Attribute::<String>::String("edge".to_string())),
+        ],
+    );
+    let output = pad(&[Some(&data)], &node).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output).unwrap(),
+        arr2(&[[1.0f32, 1.0, 2.0, 3.0], [4.0, 4.0, 5.0, 6.0]]).into_dyn()
+    );
+}
+
+#[test]
+fn invalid_pads() {
+    let error = padded([0, 0, -3, 0], "constant")
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Cannot crop"), "{}", error);
+
+    let error = padded([0, 1, 0, 1], "mirror")
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Invalid attribute 'mode'"), "{}", error);
+
+    let error = OpTest::new("Pad")
+        .input(x())
+        .input(arr1(&[1i64, 1]))
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("2 values for each of the 2 padded axes"),
+        "{}",
+        error
+    );
+
+    // Nothing to reflect once the columns are cropped.
+    let error = padded([0, -3, 0, 1], "reflect")
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Cannot pad the empty axis"), "{}", error);
+}
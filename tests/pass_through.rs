@@ -0,0 +1,201 @@
+//! Removes the pass-through nodes (Identity, Dropout in inference mode, Cast to the type of the
+//! input) from graphs, and compares the runs of the graphs before and after:
+//!
+//! ```text
+//! cargo test --test pass_through
+//! ```
+
+use ndarray::{arr0, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::passes::eliminate_pass_through_nodes;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn node(op_type: &str, inputs: Vec<&str>, outputs: Vec<&str>) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        outputs.clone(),
+        Some(outputs[0]),
+        None,
+        None,
+        vec![],
+    )
+}
+
+fn cast(input: &str, output: &str, to: TensorProto_DataType) -> NodeProto {
+    let mut node = node("Cast", vec![input], vec![output]);
+    node.mut_attribute()
+        .push(make_attribute("to", Attribute::<String>::Int(to as i64)));
+    node
+}
+
+fn value_info(name: &str) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        vec![Dimension::Value(2), Dimension::Value(3)],
+        None,
+    )
+}
+
+fn model(nodes: Vec<NodeProto>, outputs: Vec<&str>, initializers: Vec<TensorProto>) -> ModelProto {
+    let graph = make_graph(
+        nodes,
+        "pass_through",
+        vec![value_info("x")],
+        outputs.into_iter().map(value_info).collect(),
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Removes the pass-through nodes from a copy of the model, returning whether something changed.
+fn eliminate(model: &ModelProto) -> (ModelProto, bool) {
+    let mut optimized = model.clone();
+    let changed = eliminate_pass_through_nodes(optimized.mut_graph()).unwrap();
+    (optimized, changed)
+}
+
+fn run(model: &ModelProto) -> Vec<ArrayD<f32>> {
+    let mut rng = StdRng::seed_from_u64(222);
+    let x = ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[2, 3]), "x").unwrap();
+    run_with_inputs(model, vec![x], &RunOptions::default())
+        .unwrap()
+        .iter()
+        .map(|output| tensor_proto_to_ndarray::<f32>(output).unwrap())
+        .collect()
+}
+
+fn op_types(model: &ModelProto) -> Vec<&str> {
+    model
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_op_type())
+        .collect()
+}
+
+#[test]
+fn identity_dropout_and_cast() {
+    let model = model(
+        vec![
+            node("Identity", vec!["x"], vec!["copy"]),
+            node("Relu", vec!["copy"], vec!["relu"]),
+            node("Dropout", vec!["relu"], vec!["dropped"]),
+            cast("dropped", "float", TensorProto_DataType::FLOAT),
+            node("Exp", vec!["float"], vec!["y"]),
+        ],
+        vec!["y"],
+        vec![],
+    );
+    let (optimized, changed) = eliminate(&model);
+
+    assert!(changed);
+    assert_eq!(op_types(&optimized), ["Relu", "Exp"]);
+    let nodes = optimized.get_graph().get_node();
+    assert_eq!(nodes[0].get_input(), ["x"]);
+    assert_eq!(nodes[1].get_input(), ["relu"]);
+    assert_eq!(run(&optimized), run(&model));
+
+    // Nothing is left to remove.
+    let mut again = optimized.clone();
+    assert!(!eliminate_pass_through_nodes(again.mut_graph()).unwrap());
+    assert_eq!(again, optimized);
+}
+
+#[test]
+fn graph_output_of_a_pass_through_node() {
+    // The Relu writes y itself, so the output keeps its name.
+    let model = model(
+        vec![
+            node("Relu", vec!["x"], vec!["relu"]),
+            node("Identity", vec!["relu"], vec!["y"]),
+            node("Neg", vec!["relu"], vec!["z"]),
+        ],
+        vec!["y", "z"],
+        vec![],
+    );
+    let (optimized, changed) = eliminate(&model);
+
+    assert!(changed);
+    assert_eq!(op_types(&optimized), ["Relu", "Neg"]);
+    let nodes = optimized.get_graph().get_node();
+    assert_eq!(nodes[0].get_output(), ["y"]);
+    assert_eq!(nodes[1].get_input(), ["y"]);
+    assert_eq!(run(&optimized), run(&model));
+}
+
+#[test]
+fn graph_output_copying_a_graph_input() {
+    // Neither name can disappear from the interface of the graph.
+    let model = model(
+        vec![node("Identity", vec!["x"], vec!["y"])],
+        vec!["y"],
+        vec![],
+    );
+    let (optimized, changed) = eliminate(&model);
+    assert!(!changed);
+    assert_eq!(optimized, model);
+}
+
+#[test]
+fn nodes_changing_their_input_are_kept() {
+    let training_mode = ndarray_to_tensor_proto::<bool>(arr0(true).into_dyn(), "training").unwrap();
+    let model = model(
+        vec![
+            // Training mode.
+            node("Dropout", vec!["x", "", "training"], vec!["dropped"]),
+            // The mask is read.
+            node("Dropout", vec!["dropped"], vec!["masked", "mask"]),
+            cast("mask", "mask_float", TensorProto_DataType::FLOAT),
+            // To another type.
+            cast("masked", "integers", TensorProto_DataType::INT64),
+            cast("integers", "y", TensorProto_DataType::FLOAT),
+        ],
+        vec!["y", "mask_float"],
+        vec![training_mode],
+    );
+    let (optimized, changed) = eliminate(&model);
+    assert!(!changed);
+    assert_eq!(optimized, model);
+}
+
+#[test]
+fn cast_like_of_the_same_type() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let like = ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[1]), "like").unwrap();
+    let model = model(
+        vec![
+            node("CastLike", vec!["x", "like"], vec!["cast"]),
+            node("Abs", vec!["cast"], vec!["y"]),
+        ],
+        vec!["y"],
+        vec![like],
+    );
+    let (optimized, changed) = eliminate(&model);
+
+    assert!(changed);
+    assert_eq!(op_types(&optimized), ["Abs"]);
+    assert_eq!(optimized.get_graph().get_node()[0].get_input(), ["x"]);
+}
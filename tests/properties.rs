@@ -0,0 +1,240 @@
+//! Checks the broadcasting and shape rules of the executor on shapes generated by proptest:
+//!
+//! ```text
+//! cargo test --test properties
+//! ```
+//!
+//! Add, Sub, Mul, Div and Where are compared with a slow reference that copies the operands to
+//! the output shape explicitly, incompatible shapes must give an error (a panic fails the case),
+//! and Reshape and Slice are compared with the shape inference and the clamping rules of the
+//! specification. A failing case is shrunk to the smallest one, and printed with the seed
+//! reproducing it.
+
+mod common;
+
+use common::{
+    bool_tensor, broadcast_shape, broadcastable_shapes, incompatible_shapes, materialize, shape,
+    tensor,
+};
+use ndarray::{arr0, arr1, ArrayD, IxDyn, Zip};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::generators::{MAX_DIM, MAX_RANK};
+use onnx_rustime_lib::testing::properties::BROADCASTING_OPS;
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+use proptest::prelude::*;
+
+/// A tensor holding the index of each element, so that every value tells its position.
+fn positions(shape: &[usize]) -> ArrayD<f32> {
+    let elements = shape.iter().product::<usize>();
+    ArrayD::from_shape_vec(IxDyn(shape), (0..elements).map(|i| i as f32).collect()).unwrap()
+}
+
+/// A Slice index: small values, out of range on both sides, and the extreme values exporters
+/// write for "to the end".
+fn slice_index() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        8 => -12i64..12,
+        1 => Just(i64::MIN),
+        1 => Just(i64::MAX),
+    ]
+}
+
+fn slice_step() -> impl Strategy<Value = i64> {
+    prop_oneof![-3i64..=-1, 1i64..=3]
+}
+
+/// The indices selected by a Slice on a dimension, after the clamping of the specification.
+fn slice_indices(dim: usize, start: i64, end: i64, step: i64) -> Vec<usize> {
+    let dim = dim as i64;
+    let resolve = |index: i64| if index < 0 { index + dim } else { index };
+    let (start, end) = if step > 0 {
+        (resolve(start).clamp(0, dim), resolve(end).clamp(0, dim))
+    } else {
+        (
+            resolve(start).clamp(0, dim - 1),
+            resolve(end).clamp(-1, dim - 1),
+        )
+    };
+
+    let mut indices = Vec::new();
+    let mut index = start;
+    while (step > 0 && index < end) || (step < 0 && index > end) {
+        indices.push(index as usize);
+        index += step;
+    }
+    indices
+}
+
+proptest! {
+    #[test]
+    fn binary_operations_match_the_reference(
+        (a, b) in broadcastable_shapes(2)
+            .prop_flat_map(|shapes| (tensor(shapes[0].clone()), tensor(shapes[1].clone())))
+    ) {
+        let output_shape = broadcast_shape(&[a.shape(), b.shape()]).unwrap();
+        let (full_a, full_b) = (materialize(&a, &output_shape), materialize(&b, &output_shape));
+
+        for (op_type, f) in BROADCASTING_OPS {
+            let expected = Zip::from(&full_a).and(&full_b).map_collect(|&x, &y| f(x, y));
+            OpTest::new(op_type)
+                .opset(14)
+                .input(a.clone())
+                .input(b.clone())
+                .expect(expected)
+                .run()
+                .map_err(TestCaseError::fail)?;
+        }
+    }
+
+    #[test]
+    fn where_matches_the_reference(
+        (condition, x, y) in broadcastable_shapes(3).prop_flat_map(|shapes| {
+            (
+                bool_tensor(shapes[0].clone()),
+                tensor(shapes[1].clone()),
+                tensor(shapes[2].clone()),
+            )
+        })
+    ) {
+        let output_shape = broadcast_shape(&[condition.shape(), x.shape(), y.shape()]).unwrap();
+        let expected = Zip::from(&materialize(&condition, &output_shape))
+            .and(&materialize(&x, &output_shape))
+            .and(&materialize(&y, &output_shape))
+            .map_collect(|&c, &x, &y| if c { x } else { y });
+
+        OpTest::new("Where")
+            .opset(16)
+            .input(condition)
+            .input(x)
+            .input(y)
+            .expect(expected)
+            .run()
+            .map_err(TestCaseError::fail)?;
+    }
+
+    #[test]
+    fn incompatible_shapes_are_errors((shape_a, shape_b) in incompatible_shapes()) {
+        prop_assert!(broadcast_shape(&[&shape_a, &shape_b]).is_none());
+        let a = ndarray_to_tensor_proto::<f32>(ArrayD::zeros(IxDyn(&shape_a)), "a").unwrap();
+        let b = ndarray_to_tensor_proto::<f32>(ArrayD::zeros(IxDyn(&shape_b)), "b").unwrap();
+        let condition = ndarray_to_tensor_proto::<bool>(arr0(true).into_dyn(), "c").unwrap();
+
+        let cases = BROADCASTING_OPS
+            .iter()
+            .map(|&(op_type, _)| (op_type, vec!["a", "b"], vec![a.clone(), b.clone()]))
+            .chain([("Where", vec!["c", "a", "b"], vec![condition, a.clone(), b.clone()])]);
+        for (op_type, names, inputs) in cases {
+            let model = single_node_model(op_type, &names, &["y"], vec![]);
+            match run_with_inputs(&model, inputs, &RunOptions::default()) {
+                Ok(_) => prop_assert!(false, "{} accepted incompatible shapes", op_type),
+                Err(error) => prop_assert!(
+                    error.to_string().contains("can't be broadcast together"),
+                    "{}: {}",
+                    op_type,
+                    error
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn reshape_infers_the_dimensions(
+        (input, target, copied, inferred) in shape(0, MAX_RANK, MAX_DIM).prop_flat_map(|input| {
+            let rank = input.len();
+            (
+                Just(input),
+                // Whether each dimension is merged with the previous one.
+                prop::collection::vec(any::<bool>(), rank.saturating_sub(1)),
+                prop::collection::vec(any::<bool>(), rank),
+                prop::option::of(any::<prop::sample::Index>()),
+            )
+        })
+        .prop_map(|(input, merged, copied, inferred)| {
+            let mut target: Vec<usize> = Vec::new();
+            for (axis, &dim) in input.iter().enumerate() {
+                match target.last_mut() {
+                    Some(last) if merged[axis - 1] => *last *= dim,
+                    _ => target.push(dim),
+                }
+            }
+            (input, target, copied, inferred)
+        })
+    ) {
+        // 0 copies the dimension of the input at the same position, -1 is inferred.
+        let mut shape: Vec<i64> = target.iter().map(|&dim| dim as i64).collect();
+        for (axis, dim) in shape.iter_mut().enumerate() {
+            if copied[axis] && input.get(axis) == Some(&target[axis]) {
+                *dim = 0;
+            }
+        }
+        if let Some(index) = inferred.filter(|_| !shape.is_empty()) {
+            let axis = index.index(shape.len());
+            shape[axis] = -1;
+        }
+
+        let x = positions(&input);
+        let expected = x.clone().into_shape(IxDyn(&target)).unwrap();
+        OpTest::new("Reshape")
+            .opset(14)
+            .input(x)
+            .input(arr1(&shape))
+            .expect(expected)
+            .run()
+            .map_err(|message| TestCaseError::fail(format!("shape {:?}: {}", shape, message)))?;
+    }
+
+    #[test]
+    fn slice_clamps_the_indices(
+        (input, axes, bounds) in shape(1, 3, 5).prop_flat_map(|input| {
+            let rank = input.len();
+            // Whether the axis is negative, start, end and step of each axis.
+            let bounds = (any::<bool>(), slice_index(), slice_index(), slice_step());
+            let all_axes: Vec<usize> = (0..rank).collect();
+            (
+                Just(input),
+                prop::sample::subsequence(all_axes, 1..=rank).prop_shuffle(),
+                prop::collection::vec(bounds, rank),
+            )
+        })
+    ) {
+        let x = positions(&input);
+        let rank = input.len() as i64;
+        let mut selected: Vec<Vec<usize>> = input.iter().map(|&dim| (0..dim).collect()).collect();
+        let (mut starts, mut ends, mut axis_inputs, mut steps) = (vec![], vec![], vec![], vec![]);
+        for &axis in &axes {
+            let (negative_axis, start, end, step) = bounds[axis];
+            selected[axis] = slice_indices(input[axis], start, end, step);
+            starts.push(start);
+            ends.push(end);
+            axis_inputs.push(if negative_axis { axis as i64 - rank } else { axis as i64 });
+            steps.push(step);
+        }
+
+        let output_shape: Vec<usize> = selected.iter().map(|indices| indices.len()).collect();
+        let expected = ArrayD::from_shape_fn(IxDyn(&output_shape), |index| {
+            let source: Vec<usize> = selected
+                .iter()
+                .enumerate()
+                .map(|(axis, indices)| indices[index[axis]])
+                .collect();
+            x[source.as_slice()]
+        });
+        OpTest::new("Slice")
+            .opset(13)
+            .input(x.clone())
+            .input(arr1(&starts))
+            .input(arr1(&ends))
+            .input(arr1(&axis_inputs))
+            .input(arr1(&steps))
+            .expect(expected)
+            .run()
+            .map_err(|message| {
+                TestCaseError::fail(format!(
+                    "starts {:?}, ends {:?}, axes {:?}, steps {:?}: {}",
+                    starts, ends, axis_inputs, steps, message
+                ))
+            })?;
+    }
+}
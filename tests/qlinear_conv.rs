@@ -0,0 +1,245 @@
+//! Runs QLinearConv through the executor, against the float Conv:
+//!
+//! ```text
+//! cargo test --test qlinear_conv
+//! ```
+//!
+//! The inputs are quantized, then the output of QLinearConv is dequantized and compared with the
+//! float Conv of the dequantized inputs: as the accumulation is exact, the only error left is the
+//! rounding of the output to its scale, half a step at most.
+
+use ndarray::{arr0, arr1, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_proto::onnx_ml_proto3::{AttributeProto, TensorProto};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::single_node_model;
+
+const QLINEAR_CONV_INPUTS: [&str; 9] = [
+    "x",
+    "x_scale",
+    "x_zero_point",
+    "w",
+    "w_scale",
+    "w_zero_point",
+    "y_scale",
+    "y_zero_point",
+    "B",
+];
+
+/// A tensor of the given shape with values spread over [-1, 1).
+fn spread(shape: &[usize], seed: usize) -> ArrayD<f32> {
+    let len = shape.iter().product::<usize>();
+    Array::from_shape_fn(len, |i| ((i * 37 + seed) % 101) as f32 / 50.5 - 1.0)
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+/// Quantizes the values of every output channel (the first axis) with its scale and zero point.
+fn quantize(values: &ArrayD<f32>, scales: &[f32], zero_points: &[i32]) -> ArrayD<i32> {
+    let mut quantized = values.mapv(|_| 0);
+    for (channel, (mut quantized, values)) in quantized
+        .outer_iter_mut()
+        .zip(values.outer_iter())
+        .enumerate()
+    {
+        let (scale, zero_point) = (scales[channel], zero_points[channel]);
+        quantized.assign(&values.mapv(|value| (value / scale).round() as i32 + zero_point));
+    }
+    quantized
+}
+
+fn dequantize(values: &ArrayD<i32>, scales: &[f32], zero_points: &[i32]) -> ArrayD<f32> {
+    let mut dequantized = values.mapv(|_| 0.0);
+    for (channel, (mut dequantized, values)) in dequantized
+        .outer_iter_mut()
+        .zip(values.outer_iter())
+        .enumerate()
+    {
+        let (scale, zero_point) = (scales[channel], zero_points[channel]);
+        dequantized.assign(&values.mapv(|value| (value - zero_point) as f32 * scale));
+    }
+    dequantized
+}
+
+fn run(
+    op_type: &str,
+    names: &[&str],
+    inputs: Vec<TensorProto>,
+    attributes: &[AttributeProto],
+) -> TensorProto {
+    let model = single_node_model(op_type, names, &["y"], attributes.to_vec());
+    let mut outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    outputs.remove(0)
+}
+
+fn f32_tensor(values: ArrayD<f32>, name: &str) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(values, name).unwrap()
+}
+
+/// Quantized tensors, whose values must fit in their type.
+fn u8_tensor(values: ArrayD<i32>, name: &str) -> TensorProto {
+    assert!(values.iter().all(|value| (0..=255).contains(value)));
+    ndarray_to_tensor_proto::<u8>(values.mapv(|value| value as u8), name).unwrap()
+}
+
+fn i8_tensor(values: ArrayD<i32>, name: &str) -> TensorProto {
+    assert!(values.iter().all(|value| (-128..=127).contains(value)));
+    ndarray_to_tensor_proto::<i8>(values.mapv(|value| value as i8), name).unwrap()
+}
+
+/// Checks the dequantized output of QLinearConv against the float Conv, within half a step of
+/// `y_scale`.
+fn assert_within_half_a_step(quantized: &ArrayD<f32>, float: &ArrayD<f32>, y_scale: f32) {
+    assert_eq!(quantized.shape(), float.shape());
+    for (quantized, float) in quantized.iter().zip(float) {
+        assert!(
+            (quantized - float).abs() <= y_scale / 2.0 + 1e-5,
+            "{} is more than half a step of {} away from {}",
+            quantized,
+            y_scale,
+            float
+        );
+    }
+}
+
+#[test]
+fn per_channel_weights_with_bias() {
+    // UINT8 activations, INT8 kernels with a scale per output channel, as in the zoo.
+    let attributes = [
+        make_attribute("pads", Attribute::<String>::Ints(vec![1, 1, 1, 1])),
+        make_attribute("strides", Attribute::<String>::Ints(vec![2, 2])),
+    ];
+    let (x_scale, x_zero_point) = (0.01, 100);
+    let w_scales = [0.01, 0.02, 0.008, 0.015];
+    let (y_scale, y_zero_point) = (0.03, 128);
+
+    let x = quantize(
+        &spread(&[2, 3, 7, 7], 0).mapv(|x| x * 0.7 + 0.6),
+        &[x_scale; 2],
+        &[x_zero_point; 2],
+    );
+    let w = quantize(&spread(&[4, 3, 3, 3], 5), &w_scales, &[0; 4]);
+    let bias_scales: Vec<f32> = w_scales.iter().map(|w_scale| x_scale * w_scale).collect();
+    let bias = quantize(
+        &arr1(&[0.5, -0.25, 0.0, 1.0]).into_dyn(),
+        &bias_scales,
+        &[0; 4],
+    );
+
+    let quantized = run(
+        "QLinearConv",
+        &QLINEAR_CONV_INPUTS,
+        vec![
+            u8_tensor(x.clone(), "x"),
+            f32_tensor(arr0(x_scale).into_dyn(), "x_scale"),
+            u8_tensor(arr0(x_zero_point).into_dyn(), "x_zero_point"),
+            i8_tensor(w.clone(), "w"),
+            f32_tensor(arr1(&w_scales).into_dyn(), "w_scale"),
+            i8_tensor(arr0(0).into_dyn(), "w_zero_point"),
+            f32_tensor(arr0(y_scale).into_dyn(), "y_scale"),
+            u8_tensor(arr0(y_zero_point).into_dyn(), "y_zero_point"),
+            ndarray_to_tensor_proto::<i32>(bias.clone(), "B").unwrap(),
+        ],
+        &attributes,
+    );
+    let quantized = tensor_proto_to_ndarray::<u8>(&quantized)
+        .unwrap()
+        .mapv(|value| (value as i32 - y_zero_point) as f32 * y_scale);
+
+    let float = run(
+        "Conv",
+        &["x", "w", "B"],
+        vec![
+            f32_tensor(dequantize(&x, &[x_scale; 2], &[x_zero_point; 2]), "x"),
+            f32_tensor(dequantize(&w, &w_scales, &[0; 4]), "w"),
+            f32_tensor(dequantize(&bias, &bias_scales, &[0; 4]), "B"),
+        ],
+        &attributes,
+    );
+    let float = tensor_proto_to_ndarray::<f32>(&float).unwrap();
+
+    // The output range of UINT8 covers every value, none saturates.
+    assert!(float.iter().all(|value| value.abs() < 3.5));
+    assert_within_half_a_step(&quantized, &float, y_scale);
+}
+
+#[test]
+fn grouped_1d_convolution_with_zero_points_per_channel() {
+    // INT8 activations and outputs, UINT8 kernels with a zero point per output channel.
+    let attributes = [
+        make_attribute("group", Attribute::<String>::Int(2)),
+        make_attribute("dilations", Attribute::<String>::Ints(vec![2])),
+        make_attribute(
+            "auto_pad",
+            Attribute::<String>::String("SAME_UPPER".to_string()),
+        ),
+    ];
+    let (x_scale, x_zero_point) = (0.01, -10);
+    let w_scales = [0.005; 4];
+    let w_zero_points = [128, 110, 150, 105];
+    let (y_scale, y_zero_point) = (0.01, 5);
+
+    let x = quantize(&spread(&[1, 4, 9], 3), &[x_scale], &[x_zero_point]);
+    let w = quantize(
+        &spread(&[4, 2, 3], 8).mapv(|w| w / 2.0),
+        &w_scales,
+        &w_zero_points,
+    );
+
+    let quantized = run(
+        "QLinearConv",
+        &QLINEAR_CONV_INPUTS[..8],
+        vec![
+            i8_tensor(x.clone(), "x"),
+            f32_tensor(arr0(x_scale).into_dyn(), "x_scale"),
+            i8_tensor(arr0(x_zero_point).into_dyn(), "x_zero_point"),
+            u8_tensor(w.clone(), "w"),
+            f32_tensor(arr0(w_scales[0]).into_dyn(), "w_scale"),
+            u8_tensor(arr1(&w_zero_points).into_dyn(), "w_zero_point"),
+            f32_tensor(arr0(y_scale).into_dyn(), "y_scale"),
+            i8_tensor(arr0(y_zero_point).into_dyn(), "y_zero_point"),
+        ],
+        &attributes,
+    );
+    let quantized = tensor_proto_to_ndarray::<i8>(&quantized)
+        .unwrap()
+        .mapv(|value| (value as i32 - y_zero_point) as f32 * y_scale);
+
+    let float = run(
+        "Conv",
+        &["x", "w"],
+        vec![
+            f32_tensor(dequantize(&x, &[x_scale], &[x_zero_point]), "x"),
+            f32_tensor(dequantize(&w, &w_scales, &w_zero_points), "w"),
+        ],
+        &attributes,
+    );
+    let float = tensor_proto_to_ndarray::<f32>(&float).unwrap();
+
+    assert_eq!(float.shape(), &[1, 4, 9]);
+    assert!(float.iter().all(|value| value.abs() < 1.2));
+    assert_within_half_a_step(&quantized, &float, y_scale);
+}
+
+#[test]
+fn saturates_to_the_output_type() {
+    // 255 * 127 = 32385 doesn't fit in UINT8, nor -32385 in INT8.
+    for (w, expected) in [(127i8, 255u8), (-127, 0)] {
+        op_test!("QLinearConv",
+                 inputs: [
+                     Array::from_elem((1, 1, 1, 1), 255u8),
+                     arr0(1.0f32),
+                     arr0(0u8),
+                     Array::from_elem((1, 1, 1, 1), w),
+                     arr0(1.0f32),
+                     arr0(0i8),
+                     arr0(1.0f32),
+                     arr0(0u8),
+                 ],
+                 attrs: {},
+                 expect: Array::from_elem((1, 1, 1, 1), expected));
+    }
+}
@@ -0,0 +1,264 @@
+//! Quantizes the Conv kernels of FLOAT models to INT8, per output channel or per tensor:
+//!
+//! ```text
+//! cargo test --test quantization
+//! ```
+//!
+//! The test kernel is a depthwise convolution with random weights, whose channels have ranges
+//! from 0.01 to 100: the per-channel scales must reconstruct it (and the outputs of the model)
+//! strictly better than a single scale. The first kernel of MNIST is checked the same way by an
+//! ignored test run once the models are pulled.
+
+mod common;
+
+use ndarray::{ArrayD, Axis};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use onnx_rustime_lib::transforms::{
+    quantize_weight, quantize_weights, QuantizationConfig, WeightGranularity,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const MNIST: &str = "models/mnist-8/mnist-8.onnx";
+
+const CHANNEL_RANGES: [f32; 4] = [100.0, 1.0, 0.01, 0.1];
+
+/// A depthwise kernel of shape [4, 1, 3, 3], with the ranges of `CHANNEL_RANGES`.
+fn depthwise_kernel() -> ArrayD<f32> {
+    let mut rng = StdRng::seed_from_u64(225);
+    let mut kernel = random_tensor(&mut rng, &[CHANNEL_RANGES.len(), 1, 3, 3]);
+    for (mut channel, range) in kernel.axis_iter_mut(Axis(0)).zip(CHANNEL_RANGES) {
+        channel.mapv_inplace(|value| value * range);
+    }
+    kernel
+}
+
+/// The mean squared error of the reconstruction of a quantized weight.
+fn reconstruction_error(weight: &ArrayD<f32>, granularity: WeightGranularity) -> f32 {
+    let quantized = quantize_weight(weight, granularity);
+    assert_eq!(quantized.granularity(), granularity);
+    let error = &quantized.dequantize() - weight;
+    error.mapv(|value| value * value).mean().unwrap()
+}
+
+/// A depthwise Conv of a [1, 4, 6, 6] image.
+fn depthwise_model(opset: i64) -> ModelProto {
+    let channels = CHANNEL_RANGES.len() as i64;
+    let value_info = |name: &str, dims: [i64; 4]| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            dims.iter().map(|&dim| Dimension::Value(dim)).collect(),
+            None,
+        )
+    };
+    let node = make_node(
+        "Conv",
+        vec!["x", "w"],
+        vec!["y"],
+        Some("conv"),
+        None,
+        None,
+        vec![make_attribute("group", Attribute::<String>::Int(channels))],
+    );
+    let graph = make_graph(
+        vec![node],
+        "depthwise",
+        vec![value_info("x", [1, channels, 6, 6])],
+        vec![value_info("y", [1, channels, 4, 4])],
+        vec![ndarray_to_tensor_proto::<f32>(depthwise_kernel(), "w").unwrap()],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, opset)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(model: &ModelProto) -> ArrayD<f32> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let x = random_tensor(&mut rng, &[1, CHANNEL_RANGES.len(), 6, 6]);
+    let input = ndarray_to_tensor_proto::<f32>(x, "x").unwrap();
+    let outputs = run_with_inputs(model, vec![input], &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+fn find_initializer<'a>(model: &'a ModelProto, name: &str) -> &'a TensorProto {
+    model
+        .get_graph()
+        .get_initializer()
+        .iter()
+        .find(|initializer| initializer.get_name() == name)
+        .unwrap_or_else(|| panic!("{} isn't an initializer", name))
+}
+
+#[test]
+fn per_channel_reconstruction() {
+    let kernel = depthwise_kernel();
+    let per_channel = reconstruction_error(&kernel, WeightGranularity::PerChannel);
+    let per_tensor = reconstruction_error(&kernel, WeightGranularity::PerTensor);
+    assert!(
+        per_channel < per_tensor,
+        "per channel: {}, per tensor: {}",
+        per_channel,
+        per_tensor
+    );
+
+    // A single scale rounds the small channels to 0.
+    let quantized = quantize_weight(&kernel, WeightGranularity::PerTensor);
+    assert!(quantized
+        .values
+        .index_axis(Axis(0), 2)
+        .iter()
+        .all(|&value| value == 0));
+    let quantized = quantize_weight(&kernel, WeightGranularity::PerChannel);
+    assert_eq!(quantized.scales.len(), CHANNEL_RANGES.len());
+    for channel in quantized.values.axis_iter(Axis(0)) {
+        let max = channel.iter().map(|value| value.abs()).max().unwrap();
+        assert_eq!(max, 127);
+    }
+}
+
+#[test]
+fn zeros_and_scalars() {
+    let zeros = ArrayD::<f32>::zeros(vec![2, 3]);
+    let quantized = quantize_weight(&zeros, WeightGranularity::PerChannel);
+    assert_eq!(quantized.scales.to_vec(), [1.0, 1.0]);
+    assert_eq!(quantized.dequantize(), zeros);
+
+    let scalar = ArrayD::from_elem(vec![], -2.0f32);
+    let quantized = quantize_weight(&scalar, WeightGranularity::PerChannel);
+    assert_eq!(quantized.granularity(), WeightGranularity::PerTensor);
+    assert_eq!(quantized.values[[]], -127);
+    assert_eq!(quantized.dequantize(), scalar);
+}
+
+#[test]
+fn per_channel_by_default() {
+    let model = depthwise_model(13);
+    let quantized = quantize_weights(&model, &QuantizationConfig::default()).unwrap();
+    let graph = quantized.get_graph();
+
+    let dequantize = &graph.get_node()[0];
+    assert_eq!(dequantize.get_op_type(), "DequantizeLinear");
+    assert_eq!(
+        dequantize.get_input(),
+        ["w_quantized", "w_scale", "w_zero_point"]
+    );
+    assert_eq!(dequantize.get_output(), ["w"]);
+    assert_eq!(dequantize.get_attribute()[0].get_name(), "axis");
+    assert_eq!(dequantize.get_attribute()[0].get_i(), 0);
+    assert_eq!(graph.get_node()[1].get_op_type(), "Conv");
+
+    assert_eq!(
+        find_initializer(&quantized, "w_quantized").get_data_type(),
+        TensorProto_DataType::INT8 as i32
+    );
+    assert_eq!(find_initializer(&quantized, "w_scale").get_dims(), [4]);
+    assert_eq!(find_initializer(&quantized, "w_zero_point").get_dims(), [4]);
+    assert!(graph
+        .get_initializer()
+        .iter()
+        .all(|init| init.get_name() != "w"));
+
+    // The kernel computed by the model is the dequantized one.
+    let kernel = depthwise_kernel();
+    let expected = quantize_weight(&kernel, WeightGranularity::PerChannel).dequantize();
+    let mut reference = model.clone();
+    reference.mut_graph().mut_initializer()[0] =
+        ndarray_to_tensor_proto::<f32>(expected, "w").unwrap();
+    assert_eq!(run(&quantized), run(&reference));
+}
+
+#[test]
+fn per_channel_outputs() {
+    let model = depthwise_model(13);
+    let per_tensor = QuantizationConfig {
+        conv_weights: WeightGranularity::PerTensor,
+    };
+    let per_tensor_model = quantize_weights(&model, &per_tensor).unwrap();
+    let dequantize = &per_tensor_model.get_graph().get_node()[0];
+    assert!(dequantize.get_attribute().is_empty());
+    assert!(find_initializer(&per_tensor_model, "w_scale")
+        .get_dims()
+        .is_empty());
+
+    let per_channel_model = quantize_weights(&model, &QuantizationConfig::default()).unwrap();
+    let expected = run(&model);
+    let error = |model: &ModelProto| {
+        let error = &run(model) - &expected;
+        error.mapv(|value| value * value).mean().unwrap()
+    };
+    let per_channel = error(&per_channel_model);
+    let per_tensor = error(&per_tensor_model);
+    assert!(
+        per_channel < per_tensor,
+        "per channel: {}, per tensor: {}",
+        per_channel,
+        per_tensor
+    );
+}
+
+#[test]
+fn old_opsets() {
+    // DequantizeLinear has an axis since opset 13, and exists since opset 10.
+    let model = depthwise_model(11);
+    match quantize_weights(&model, &QuantizationConfig::default()) {
+        Err(OnnxError::UnsupportedOperation(message)) => {
+            assert!(message.contains("opset 13"), "{}", message)
+        }
+        other => panic!("Expected an unsupported operation, got {:?}", other),
+    }
+    let per_tensor = QuantizationConfig {
+        conv_weights: WeightGranularity::PerTensor,
+    };
+    assert!(quantize_weights(&model, &per_tensor).is_ok());
+    assert!(quantize_weights(&depthwise_model(8), &per_tensor).is_err());
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn mnist_kernel() {
+    common::require_model(MNIST);
+    let model = OnnxParser::load_model(MNIST.to_string()).unwrap();
+    let graph = model.get_graph();
+    let conv = graph
+        .get_node()
+        .iter()
+        .find(|node| node.get_op_type() == "Conv")
+        .unwrap();
+    let kernel = tensor_proto_to_ndarray::<f32>(
+        graph
+            .get_initializer()
+            .iter()
+            .find(|init| init.get_name() == conv.get_input()[1])
+            .unwrap(),
+    )
+    .unwrap();
+
+    let per_channel = reconstruction_error(&kernel, WeightGranularity::PerChannel);
+    let per_tensor = reconstruction_error(&kernel, WeightGranularity::PerTensor);
+    assert!(
+        per_channel < per_tensor,
+        "per channel: {}, per tensor: {}",
+        per_channel,
+        per_tensor
+    );
+}
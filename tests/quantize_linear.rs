@@ -0,0 +1,126 @@
+//! Runs QuantizeLinear and DequantizeLinear through the executor, per tensor and per axis:
+//!
+//! ```text
+//! cargo test --test quantize_linear
+//! ```
+//!
+//! QuantizeLinear rounds x / scale half to even before adding the zero point and saturating,
+//! so 0.5 and 2.5 steps round down, 1.5 steps up.
+
+use ndarray::{arr0, arr1, arr2, Array1};
+use onnx_rustime_lib::onnx_proto::onnx_ml_proto3::{TensorProto, TensorProto_DataType};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+
+fn x() -> Array1<f32> {
+    arr1(&[-1.0, 0.0, 0.25, 0.75, 1.25, 2.0, 300.0, -300.0])
+}
+
+#[test]
+fn uint8_per_tensor() {
+    // x / 0.5 = -2, 0, 0.5, 1.5, 2.5, 4, 600, -600: the last two saturate.
+    op_test!("QuantizeLinear",
+             inputs: [x(), arr0(0.5f32), arr0(128u8)],
+             attrs: {},
+             expect: arr1(&[126u8, 128, 128, 130, 130, 132, 255, 0]));
+}
+
+#[test]
+fn int8_per_tensor() {
+    op_test!("QuantizeLinear",
+             inputs: [x(), arr0(0.5f32), arr0(-1i8)],
+             attrs: {},
+             expect: arr1(&[-3i8, -1, -1, 1, 1, 3, 127, -128]));
+}
+
+#[test]
+fn output_type_without_zero_point() {
+    // UINT8 by default, or the output_dtype.
+    op_test!("QuantizeLinear",
+             inputs: [arr1(&[-1.0f32, 0.5, 1.5, 2.5, 1000.0]), arr0(1.0f32)],
+             attrs: {},
+             expect: arr1(&[0u8, 0, 2, 2, 255]));
+    op_test!("QuantizeLinear",
+             inputs: [arr1(&[-200.0f32, -1.5, 1.5, 200.0]), arr0(1.0f32)],
+             attrs: { output_dtype: TensorProto_DataType::INT8 as i64 },
+             expect: arr1(&[-128i8, -2, 2, 127]));
+}
+
+#[test]
+fn per_axis() {
+    // One scale and zero point per column (axis 1, the default).
+    op_test!("QuantizeLinear",
+             inputs: [
+                 arr2(&[[1.0f32, 1.0, 1.0], [-1.0, 2.5, -30.0]]),
+                 arr1(&[1.0f32, 0.5, 0.25]),
+                 arr1(&[0u8, 10, 100]),
+             ],
+             attrs: {},
+             expect: arr2(&[[1u8, 12, 104], [0, 15, 0]]));
+
+    // One per row, with a negative axis.
+    op_test!("QuantizeLinear",
+             inputs: [
+                 arr2(&[[1.5f32, 2.5, -3.0], [3.0, 5.0, -300.0]]),
+                 arr1(&[1.0f32, 2.0]),
+                 arr1(&[0i8, -5]),
+             ],
+             attrs: { axis: -2i64 },
+             expect: arr2(&[[2i8, 2, -3], [-3, -3, -128]]));
+}
+
+#[test]
+fn dequantize_per_tensor() {
+    op_test!("DequantizeLinear",
+             inputs: [arr1(&[0u8, 128, 255]), arr0(0.5f32), arr0(128u8)],
+             attrs: {},
+             expect: arr1(&[-64.0f32, 0.0, 63.5]));
+    op_test!("DequantizeLinear",
+             inputs: [arr1(&[-128i8, 127]), arr0(0.25f32)],
+             attrs: {},
+             expect: arr1(&[-32.0f32, 31.75]));
+    // A bias quantized to INT32.
+    op_test!("DequantizeLinear",
+             inputs: [arr1(&[-1000i32, 5]), arr0(0.001f32)],
+             attrs: {},
+             expect: arr1(&[-1.0f32, 0.005]));
+}
+
+#[test]
+fn dequantize_per_axis() {
+    op_test!("DequantizeLinear",
+             inputs: [
+                 arr2(&[[1u8, 12, 104], [0, 15, 0]]),
+                 arr1(&[1.0f32, 0.5, 0.25]),
+                 arr1(&[0u8, 10, 100]),
+             ],
+             attrs: {},
+             expect: arr2(&[[1.0f32, 1.0, 1.0], [0.0, 2.5, -25.0]]));
+}
+
+#[test]
+fn quantized_tensors_round_trip() {
+    let tensor = ndarray_to_tensor_proto::<u8>(arr1(&[0u8, 7, 255]).into_dyn(), "x").unwrap();
+    assert_eq!(tensor.get_data_type(), TensorProto_DataType::UINT8 as i32);
+    assert_eq!(
+        tensor_proto_to_ndarray::<u8>(&tensor).unwrap(),
+        arr1(&[0u8, 7, 255]).into_dyn()
+    );
+
+    let tensor = ndarray_to_tensor_proto::<i8>(arr1(&[-128i8, 0, 127]).into_dyn(), "x").unwrap();
+    assert_eq!(tensor.get_data_type(), TensorProto_DataType::INT8 as i32);
+    assert_eq!(
+        tensor_proto_to_ndarray::<i8>(&tensor).unwrap(),
+        arr1(&[-128i8, 0, 127]).into_dyn()
+    );
+
+    // The initializers of exported models hold their bytes in raw_data.
+    let mut tensor = TensorProto::new();
+    tensor.set_data_type(TensorProto_DataType::INT8 as i32);
+    tensor.set_dims(vec![3]);
+    tensor.set_raw_data(vec![0x80, 0x00, 0x7f]);
+    assert_eq!(
+        tensor_proto_to_ndarray::<i8>(&tensor).unwrap(),
+        arr1(&[-128i8, 0, 127]).into_dyn()
+    );
+}
@@ -0,0 +1,126 @@
+//! Runs RandomNormal, RandomUniform and their Like variants through the executor:
+//!
+//! ```text
+//! cargo test --test random
+//! ```
+//!
+//! The samples can't be compared with fixed values: the tests check the reproducibility of the
+//! seeds, the shapes and types of the outputs, and the statistics of large samples.
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, Attribute};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::single_node_model;
+
+fn float(name: &str, value: f32) -> AttributeProto {
+    make_attribute(name, Attribute::<String>::Float(value))
+}
+
+fn int(name: &str, value: i64) -> AttributeProto {
+    make_attribute(name, Attribute::<String>::Int(value))
+}
+
+fn shape(dims: &[i64]) -> AttributeProto {
+    make_attribute("shape", Attribute::<String>::Ints(dims.to_vec()))
+}
+
+/// A RandomNormal or RandomUniform model, with the given attributes.
+fn model(op_type: &str, attributes: Vec<AttributeProto>) -> ModelProto {
+    single_node_model(op_type, &[], &["y"], attributes)
+}
+
+fn run(model: &ModelProto, inputs: Vec<TensorProto>) -> TensorProto {
+    run_with_inputs(model, inputs, &RunOptions::default())
+        .unwrap()
+        .remove(0)
+}
+
+fn samples(model: &ModelProto) -> ArrayD<f32> {
+    tensor_proto_to_ndarray::<f32>(&run(model, vec![])).unwrap()
+}
+
+/// The mean and the standard deviation of the samples.
+fn statistics(samples: &ArrayD<f32>) -> (f32, f32) {
+    let mean = samples.mean().unwrap();
+    let variance = samples.mapv(|value| (value - mean).powi(2)).mean().unwrap();
+    (mean, variance.sqrt())
+}
+
+#[test]
+fn seeds_are_reproducible() {
+    for op_type in ["RandomNormal", "RandomUniform"] {
+        let seeded = model(op_type, vec![shape(&[4, 5]), float("seed", 7.0)]);
+        let first = samples(&seeded);
+        assert_eq!(first.shape(), [4, 5]);
+        assert_eq!(first, samples(&seeded), "{}", op_type);
+
+        let other_seed = model(op_type, vec![shape(&[4, 5]), float("seed", 8.0)]);
+        assert_ne!(first, samples(&other_seed), "{}", op_type);
+        let unseeded = model(op_type, vec![shape(&[4, 5])]);
+        assert_ne!(samples(&unseeded), samples(&unseeded), "{}", op_type);
+    }
+}
+
+#[test]
+fn normal_statistics() {
+    let normal = model(
+        "RandomNormal",
+        vec![shape(&[100, 100]), float("mean", 3.0), float("scale", 0.5)],
+    );
+    let samples = samples(&normal);
+    let (mean, std) = statistics(&samples);
+    // The standard error of the mean of 10000 samples is 0.005.
+    assert!((mean - 3.0).abs() < 0.03, "mean {}", mean);
+    assert!((std - 0.5).abs() < 0.02, "std {}", std);
+}
+
+#[test]
+fn uniform_statistics() {
+    let uniform = model(
+        "RandomUniform",
+        vec![shape(&[100, 100]), float("low", -2.0), float("high", 4.0)],
+    );
+    let samples = samples(&uniform);
+    assert!(samples.iter().all(|&value| (-2.0..4.0).contains(&value)));
+    // The mean and standard deviation of U(-2, 4) are 1 and 6 / sqrt(12).
+    let (mean, std) = statistics(&samples);
+    assert!((mean - 1.0).abs() < 0.1, "mean {}", mean);
+    assert!((std - 6.0 / 12f32.sqrt()).abs() < 0.05, "std {}", std);
+}
+
+#[test]
+fn dtype() {
+    let double = model("RandomUniform", vec![shape(&[3]), int("dtype", 11)]);
+    let output = run(&double, vec![]);
+    assert_eq!(output.get_data_type(), TensorProto_DataType::DOUBLE as i32);
+    assert_eq!(tensor_proto_to_ndarray::<f64>(&output).unwrap().len(), 3);
+
+    let int64 = model("RandomNormal", vec![shape(&[3]), int("dtype", 7)]);
+    let error = run_with_inputs(&int64, vec![], &RunOptions::default()).unwrap_err();
+    assert!(error.to_string().contains("data type 7"), "{}", error);
+}
+
+#[test]
+fn like_variants() {
+    let input = ArrayD::<f64>::zeros(IxDyn(&[2, 3, 4]));
+    let inputs = || vec![ndarray_to_tensor_proto::<f64>(input.clone(), "x").unwrap()];
+    for op_type in ["RandomNormalLike", "RandomUniformLike"] {
+        // The shape and the type of the input.
+        let like = single_node_model(op_type, &["x"], &["y"], vec![float("seed", 1.0)]);
+        let output = run(&like, inputs());
+        assert_eq!(output.get_dims(), [2, 3, 4], "{}", op_type);
+        assert_eq!(output.get_data_type(), TensorProto_DataType::DOUBLE as i32);
+        assert_eq!(output, run(&like, inputs()), "{}", op_type);
+
+        // The type of dtype.
+        let float = single_node_model(op_type, &["x"], &["y"], vec![int("dtype", 1)]);
+        let output = run(&float, inputs());
+        assert_eq!(output.get_data_type(), TensorProto_DataType::FLOAT as i32);
+        assert_eq!(output.get_dims(), [2, 3, 4], "{}", op_type);
+    }
+}
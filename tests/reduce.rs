@@ -0,0 +1,146 @@
+//! Runs the Reduce operations through the executor, on the data of the ONNX backend tests:
+//!
+//! ```text
+//! cargo test --test reduce
+//! ```
+
+use ndarray::{arr1, arr2, arr3, Array3};
+use onnx_rustime_lib::testing::OpTest;
+
+fn data() -> Array3<f32> {
+    arr3(&[
+        [[5.0, 1.0], [20.0, 2.0]],
+        [[30.0, 1.0], [40.0, 2.0]],
+        [[55.0, 1.0], [60.0, 2.0]],
+    ])
+}
+
+/// A reduction of `data()` along the axes input (opset 18).
+fn reduced(op_type: &str, axes: &[i64]) -> OpTest {
+    OpTest::new(op_type)
+        .opset(18)
+        .input(data())
+        .input(arr1(axes))
+}
+
+#[test]
+fn keepdims() {
+    reduced("ReduceSum", &[1])
+        .expect(arr3(&[[[25.0, 3.0]], [[70.0, 3.0]], [[115.0, 3.0]]]))
+        .check();
+    reduced("ReduceSum", &[1])
+        .attribute("keepdims", 0)
+        .expect(arr2(&[[25.0, 3.0], [70.0, 3.0], [115.0, 3.0]]))
+        .check();
+}
+
+#[test]
+fn multiple_and_negative_axes() {
+    reduced("ReduceMean", &[0, -1])
+        .attribute("keepdims", 0)
+        .expect(arr1(&[15.5, 21.0]))
+        .check();
+    reduced("ReduceMax", &[-2, 2])
+        .expect(arr3(&[[[20.0]], [[40.0]], [[60.0]]]))
+        .check();
+}
+
+#[test]
+fn empty_axes_reduce_all() {
+    // Without the axes input, and with an empty one.
+    OpTest::new("ReduceSum")
+        .opset(13)
+        .input(data())
+        .attribute("keepdims", 0)
+        .expect(arr1(&[219.0]).into_shape(()).unwrap())
+        .check();
+    reduced("ReduceMin", &[]).expect(arr3(&[[[1.0]]])).check();
+}
+
+#[test]
+fn noop_with_empty_axes() {
+    reduced("ReduceSum", &[])
+        .attribute("noop_with_empty_axes", 1)
+        .expect(data())
+        .check();
+    // The axes still apply when they are given.
+    reduced("ReduceProd", &[2])
+        .attribute("noop_with_empty_axes", 1)
+        .attribute("keepdims", 0)
+        .expect(arr2(&[[5.0, 40.0], [30.0, 80.0], [55.0, 120.0]]))
+        .check();
+}
+
+#[test]
+fn axes_attribute() {
+    // Before opset 18 (13 for ReduceSum), the axes are an attribute.
+    OpTest::new("ReduceMean")
+        .opset(13)
+        .input(data())
+        .attribute("axes", vec![1i64])
+        .expect(arr3(&[[[12.5, 1.5]], [[35.0, 1.5]], [[57.5, 1.5]]]))
+        .check();
+    OpTest::new("ReduceSum")
+        .opset(11)
+        .input(data())
+        .attribute("axes", vec![0i64, 1])
+        .attribute("keepdims", 0)
+        .expect(arr1(&[210.0, 9.0]))
+        .check();
+}
+
+#[test]
+fn norms_and_logarithms() {
+    let x = arr2(&[[3.0f32, -4.0], [1.0, 0.0]]);
+    let reduce = |op_type: &str| {
+        OpTest::new(op_type)
+            .opset(18)
+            .input(x.clone())
+            .input(arr1(&[1i64]))
+            .attribute("keepdims", 0)
+    };
+    reduce("ReduceL1").expect(arr1(&[7.0, 1.0])).check();
+    reduce("ReduceL2").expect(arr1(&[5.0, 1.0])).check();
+    reduce("ReduceSumSquare").expect(arr1(&[25.0, 1.0])).check();
+    OpTest::new("ReduceLogSum")
+        .opset(18)
+        .input(arr2(&[[1.0f32, 2.0], [0.5, 0.5]]))
+        .input(arr1(&[1i64]))
+        .attribute("keepdims", 0)
+        .expect(arr1(&[3.0f32.ln(), 0.0]))
+        .check();
+    reduce("ReduceLogSumExp")
+        .expect(arr1(&[
+            (3.0f32.exp() + (-4.0f32).exp()).ln(),
+            (1.0f32.exp() + 1.0).ln(),
+        ]))
+        .check();
+}
+
+#[test]
+fn log_sum_exp_is_stable() {
+    // exp(1000) overflows, the max subtraction doesn't.
+    OpTest::new("ReduceLogSumExp")
+        .opset(18)
+        .input(arr1(&[1000.0f32, 1000.0]))
+        .attribute("keepdims", 0)
+        .expect(arr1(&[1000.0 + 2.0f32.ln()]).into_shape(()).unwrap())
+        .check();
+    OpTest::new("ReduceLogSumExp")
+        .opset(18)
+        .input(arr1(&[f32::NEG_INFINITY, f32::NEG_INFINITY]))
+        .expect(arr1(&[f32::NEG_INFINITY]))
+        .check();
+}
+
+#[test]
+fn invalid_axes() {
+    let error = reduced("ReduceSum", &[3]).expect(data()).run().unwrap_err();
+    assert!(error.contains("out of range"), "{}", error);
+
+    let error = reduced("ReduceMax", &[1, -2])
+        .expect(data())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("repeated"), "{}", error);
+}
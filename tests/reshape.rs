@@ -0,0 +1,154 @@
+//! Runs Reshape through the executor, with the special values of the target shape:
+//!
+//! ```text
+//! cargo test --test reshape
+//! ```
+//!
+//! A -1 is inferred from the number of values and a 0 copies the dimension of the input, unless
+//! `allowzero` is set. The other dimensions are the ones of the output, whether the shape is
+//! computed by the graph or an initializer.
+
+use ndarray::{arr1, arr2, Array, Array3, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+/// A [2, 3, 4] tensor of the values from 0 to 23.
+fn x() -> Array3<f32> {
+    Array::range(0.0, 24.0, 1.0).into_shape((2, 3, 4)).unwrap()
+}
+
+fn reshaped(shape: &[usize]) -> ArrayD<f32> {
+    x().into_shape(IxDyn(shape)).unwrap()
+}
+
+#[test]
+fn explicit_shape() {
+    op_test!("Reshape", inputs: [x(), arr1(&[2i64, 12])], attrs: {}, expect: reshaped(&[2, 12]));
+    op_test!("Reshape", inputs: [x(), arr1(&[4i64, 6])], attrs: {}, expect: reshaped(&[4, 6]));
+    // The first dimension is the one of the output, not the one of an element of a batch.
+    let matrix = Array::range(0.0f32, 12.0, 1.0).into_shape((3, 4)).unwrap();
+    op_test!("Reshape",
+             inputs: [matrix.clone(), arr1(&[2i64, 6])],
+             attrs: {},
+             expect: matrix.into_shape((2, 6)).unwrap());
+}
+
+#[test]
+fn flattened_matrix() {
+    op_test!("Reshape",
+             inputs: [arr2(&[[1i64, 2], [3, 4]]), arr1(&[4i64])],
+             attrs: {},
+             expect: arr1(&[1i64, 2, 3, 4]));
+    op_test!("Reshape",
+             inputs: [arr2(&[[1.5f32, -2.0], [3.0, 0.25]]), arr1(&[4i64])],
+             attrs: {},
+             expect: arr1(&[1.5f32, -2.0, 3.0, 0.25]));
+}
+
+#[test]
+fn inferred_dimension() {
+    op_test!("Reshape", inputs: [x(), arr1(&[-1i64])], attrs: {}, expect: reshaped(&[24]));
+    op_test!("Reshape",
+             inputs: [x(), arr1(&[-1i64, 3, 2])],
+             attrs: {},
+             expect: reshaped(&[4, 3, 2]));
+}
+
+#[test]
+fn copied_dimension() {
+    op_test!("Reshape", inputs: [x(), arr1(&[0i64, 12])], attrs: {}, expect: reshaped(&[2, 12]));
+    // Flattens every element of the batch.
+    op_test!("Reshape", inputs: [x(), arr1(&[0i64, -1])], attrs: {}, expect: reshaped(&[2, 12]));
+    op_test!("Reshape",
+             inputs: [x(), arr1(&[-1i64, 0, 2, 2])],
+             attrs: {},
+             expect: reshaped(&[2, 3, 2, 2]));
+}
+
+#[test]
+fn allowzero() {
+    // A 0 is an explicit 0 with allowzero, so the input must be empty.
+    let empty = Array3::<f32>::zeros((2, 0, 3));
+    OpTest::new("Reshape")
+        .opset(14)
+        .input(empty.clone())
+        .input(arr1(&[1i64, 3, 0]))
+        .attribute("allowzero", 1i64)
+        .expect(Array3::<f32>::zeros((1, 3, 0)))
+        .check();
+    // Without it, the 0 copies the 3 of the input.
+    let result = OpTest::new("Reshape")
+        .opset(14)
+        .input(empty)
+        .input(arr1(&[1i64, 3, 0]))
+        .expect(Array3::<f32>::zeros((1, 3, 0)))
+        .run();
+    assert!(result.unwrap_err().contains("Cannot reshape"));
+
+    // Without a 0, allowzero changes nothing.
+    OpTest::new("Reshape")
+        .opset(14)
+        .input(x())
+        .input(arr1(&[-1i64, 6]))
+        .attribute("allowzero", 1i64)
+        .expect(reshaped(&[4, 6]))
+        .check();
+}
+
+#[test]
+fn shape_attribute_before_opset_5() {
+    OpTest::new("Reshape")
+        .opset(4)
+        .input(x())
+        .attribute("shape", vec![0i64, -1])
+        .expect(reshaped(&[2, 12]))
+        .check();
+}
+
+#[test]
+fn invalid_shapes() {
+    let error = |shape: &[i64], allowzero: i64| {
+        OpTest::new("Reshape")
+            .opset(14)
+            .input(x())
+            .input(arr1(shape))
+            .attribute("allowzero", allowzero)
+            .expect(x())
+            .run()
+            .unwrap_err()
+    };
+
+    assert!(error(&[-1, 2, -1], 0).contains("At most one dimension"));
+    // 24 values can't be split into rows of 5.
+    assert!(error(&[-1, 5], 0).contains("Cannot infer the -1"));
+    assert!(error(&[5, 5], 0).contains("Cannot reshape"));
+    assert!(error(&[0, -1], 1).contains("both 0 and -1"));
+    assert!(error(&[2, -3, 4], 0).contains("Invalid dimension"));
+}
+
+/// Runs a Reshape of `x()`, computed by the graph, to a shape stored in an initializer.
+fn reshape_with_initializer_shape(shape: &[i64]) -> ArrayD<f32> {
+    let mut model = single_node_model("Reshape", &["x", "shape"], &["y"], vec![]);
+    let graph = model.mut_graph();
+    graph.mut_input().retain(|input| input.get_name() == "x");
+    graph
+        .mut_initializer()
+        .push(ndarray_to_tensor_proto::<i64>(arr1(shape).into_dyn(), "shape").unwrap());
+
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap()
+}
+
+#[test]
+fn constant_shape() {
+    // The shape is applied as is to the data computed by the graph, whose reshape is forwarded.
+    assert_eq!(reshape_with_initializer_shape(&[2, 12]), reshaped(&[2, 12]));
+    assert_eq!(reshape_with_initializer_shape(&[4, 6]), reshaped(&[4, 6]));
+    assert_eq!(reshape_with_initializer_shape(&[24]), reshaped(&[24]));
+    assert_eq!(reshape_with_initializer_shape(&[0, -1]), reshaped(&[2, 12]));
+    assert_eq!(reshape_with_initializer_shape(&[-1, 4]), reshaped(&[6, 4]));
+}
@@ -0,0 +1,204 @@
+//! Runs Resize through the executor, on the cases of the ONNX backend tests (whose outputs are
+//! the ones of onnxruntime):
+//!
+//! ```text
+//! cargo test --test resize
+//! ```
+//!
+//! The scales and the sizes are bound as initializers, after an empty `roi`.
+
+use ndarray::{arr1, arr2, Array, Array1, Array2, Array4, Axis};
+use onnx_rustime_lib::testing::OpTest;
+
+/// An NCHW tensor of one image of one channel.
+fn image(pixels: Array2<f32>) -> Array4<f32> {
+    pixels.insert_axis(Axis(0)).insert_axis(Axis(0))
+}
+
+fn two_by_two() -> Array4<f32> {
+    image(arr2(&[[1.0, 2.0], [3.0, 4.0]]))
+}
+
+fn two_by_four() -> Array4<f32> {
+    image(arr2(&[[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]))
+}
+
+/// A Resize of `x` by `scales` (opset 13).
+fn scaled(x: Array4<f32>, scales: [f32; 4]) -> OpTest {
+    OpTest::new("Resize")
+        .opset(13)
+        .input(x)
+        .input(Array1::<f32>::zeros(0))
+        .input(arr1(&scales))
+}
+
+/// A Resize of `x` to `sizes` (opset 13).
+fn sized(x: Array4<f32>, sizes: [i64; 4]) -> OpTest {
+    OpTest::new("Resize")
+        .opset(13)
+        .input(x)
+        .input(Array1::<f32>::zeros(0))
+        .input(Array1::<f32>::zeros(0))
+        .input(arr1(&sizes))
+}
+
+#[test]
+fn nearest_2x_upscale() {
+    scaled(two_by_two(), [1.0, 1.0, 2.0, 2.0])
+        .attribute("mode", "nearest")
+        .expect(image(arr2(&[
+            [1.0, 1.0, 2.0, 2.0],
+            [1.0, 1.0, 2.0, 2.0],
+            [3.0, 3.0, 4.0, 4.0],
+            [3.0, 3.0, 4.0, 4.0],
+        ])))
+        .check();
+    // resize_upsample_scales_nearest
+    scaled(two_by_two(), [1.0, 1.0, 2.0, 3.0])
+        .expect(image(arr2(&[
+            [1.0, 1.0, 1.0, 2.0, 2.0, 2.0],
+            [1.0, 1.0, 1.0, 2.0, 2.0, 2.0],
+            [3.0, 3.0, 3.0, 4.0, 4.0, 4.0],
+            [3.0, 3.0, 3.0, 4.0, 4.0, 4.0],
+        ])))
+        .check();
+}
+
+#[test]
+fn nearest_sizes() {
+    // resize_upsample_sizes_nearest: 3.5 times more rows, the row 3 falling halfway between the
+    // two of the input.
+    sized(two_by_two(), [1, 1, 7, 8])
+        .expect(Array::from_shape_fn((1, 1, 7, 8), |(_, _, row, column)| {
+            1.0 + 2.0 * (row >= 4) as u8 as f32 + (column >= 4) as u8 as f32
+        }))
+        .check();
+    // resize_downsample_sizes_nearest
+    sized(two_by_four(), [1, 1, 1, 3])
+        .expect(image(arr2(&[[1.0, 2.0, 4.0]])))
+        .check();
+    // resize_downsample_scales_nearest
+    scaled(two_by_four(), [1.0, 1.0, 0.6, 0.6])
+        .expect(image(arr2(&[[1.0, 3.0]])))
+        .check();
+}
+
+#[test]
+fn nearest_modes() {
+    // The columns 0.5 and 1.5 of the asymmetric coordinates, rounded down or up.
+    let x = image(arr2(&[[1.0, 2.0, 3.0]]));
+    scaled(x.clone(), [1.0, 1.0, 1.0, 2.0])
+        .attribute("coordinate_transformation_mode", "asymmetric")
+        .attribute("nearest_mode", "floor")
+        .expect(image(arr2(&[[1.0, 1.0, 2.0, 2.0, 3.0, 3.0]])))
+        .check();
+    scaled(x.clone(), [1.0, 1.0, 1.0, 2.0])
+        .attribute("coordinate_transformation_mode", "asymmetric")
+        .attribute("nearest_mode", "round_prefer_ceil")
+        .expect(image(arr2(&[[1.0, 2.0, 2.0, 3.0, 3.0, 3.0]])))
+        .check();
+    // Opset 10 takes the scales as second input and rounds down when upsampling.
+    OpTest::new("Resize")
+        .opset(10)
+        .input(x)
+        .input(arr1(&[1.0f32, 1.0, 1.0, 2.0]))
+        .expect(image(arr2(&[[1.0, 1.0, 2.0, 2.0, 3.0, 3.0]])))
+        .check();
+}
+
+#[test]
+fn bilinear_half_pixel_downscale() {
+    // Every output pixel is the mean of a 2x2 block.
+    let x = Array::range(1.0f32, 17.0, 1.0)
+        .into_shape((1, 1, 4, 4))
+        .unwrap();
+    scaled(x, [1.0, 1.0, 0.5, 0.5])
+        .attribute("mode", "linear")
+        .expect(image(arr2(&[[3.5, 5.5], [11.5, 13.5]])))
+        .check();
+    // resize_downsample_scales_linear
+    scaled(two_by_four(), [1.0, 1.0, 0.6, 0.6])
+        .attribute("mode", "linear")
+        .expect(image(arr2(&[[2.6666665, 4.333333]])))
+        .check();
+}
+
+#[test]
+fn bilinear_upscale() {
+    // resize_upsample_scales_linear
+    scaled(two_by_two(), [1.0, 1.0, 2.0, 2.0])
+        .attribute("mode", "linear")
+        .expect(image(arr2(&[
+            [1.0, 1.25, 1.75, 2.0],
+            [1.5, 1.75, 2.25, 2.5],
+            [2.5, 2.75, 3.25, 3.5],
+            [3.0, 3.25, 3.75, 4.0],
+        ])))
+        .check();
+    // resize_upsample_scales_linear_align_corners: the corners stay, the values in between are a
+    // third apart.
+    scaled(two_by_two(), [1.0, 1.0, 2.0, 2.0])
+        .attribute("mode", "linear")
+        .attribute("coordinate_transformation_mode", "align_corners")
+        .expect(Array::from_shape_fn((1, 1, 4, 4), |(_, _, row, column)| {
+            1.0 + (2 * row + column) as f32 / 3.0
+        }))
+        .check();
+    // Asymmetric coordinates, clamped to the last column.
+    sized(image(arr2(&[[1.0, 2.0]])), [1, 1, 1, 4])
+        .attribute("mode", "linear")
+        .attribute("coordinate_transformation_mode", "asymmetric")
+        .expect(image(arr2(&[[1.0, 1.5, 2.0, 2.0]])))
+        .check();
+}
+
+#[test]
+fn invalid_resizes() {
+    let error = scaled(two_by_two(), [1.0, 1.0, 2.0, 2.0])
+        .attribute("mode", "cubic")
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("cubic mode of Resize is not supported"),
+        "{}",
+        error
+    );
+
+    let error = scaled(two_by_two(), [1.0, 1.0, 2.0, 2.0])
+        .attribute("coordinate_transformation_mode", "tf_crop_and_resize")
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("coordinate_transformation_mode"),
+        "{}",
+        error
+    );
+
+    let error = OpTest::new("Resize")
+        .opset(13)
+        .input(two_by_two())
+        .input(Array1::<f32>::zeros(0))
+        .input(arr1(&[1.0f32, 2.0]))
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("must be 4 positive values"), "{}", error);
+
+    // Both the scales and the sizes.
+    let error = OpTest::new("Resize")
+        .opset(13)
+        .input(two_by_two())
+        .input(Array1::<f32>::zeros(0))
+        .input(arr1(&[1.0f32, 1.0, 2.0, 2.0]))
+        .input(arr1(&[1i64, 1, 4, 4]))
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Only one of the scales and the sizes"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,103 @@
+//! Runs RNN through the executor:
+//!
+//! ```text
+//! cargo test --test rnn
+//! ```
+//!
+//! The expected outputs are worked out by hand, on a single hidden unit.
+
+use ndarray::{arr1, arr2, arr3};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn default_tanh() {
+    // Ht = tanh(Xt + 0.5 * Ht-1 + 0.1 + 0.2)
+    OpTest::new("RNN")
+        .opset(14)
+        .input(arr3(&[[[0.5f32]], [[-0.25]]]))
+        .input(arr3(&[[[1.0f32]]]))
+        .input(arr3(&[[[0.5f32]]]))
+        .input(arr2(&[[0.1f32, 0.2]]))
+        .attribute("hidden_size", 1i64)
+        .expect(
+            arr1(&[0.66403677f32, 0.36445904])
+                .into_shape((2, 1, 1, 1))
+                .unwrap(),
+        )
+        .expect(arr3(&[[[0.36445904f32]]]))
+        .tolerance(1e-6, 1e-6)
+        .check();
+}
+
+#[test]
+fn relu_with_sequences_shorter_than_the_input() {
+    // Two sequences of lengths 2 and 3: the third step of the first one is padding, with a
+    // value that would show in the outputs if it were read.
+    let x = arr3(&[[[1.0f32], [1.0]], [[2.0], [-4.0]], [[100.0], [3.0]]]);
+    // Ht = relu(Xt + 0.5 * Ht-1) forward, and relu(2 * Xt - Ht-1) in reverse, from the last
+    // element of each sequence.
+    let w = arr3(&[[[1.0f32]], [[2.0]]]);
+    let r = arr3(&[[[0.5f32]], [[-1.0]]]);
+    OpTest::new("RNN")
+        .opset(14)
+        .input(x)
+        .input(w)
+        .input(r)
+        .input(arr2(&[[0.0f32, 0.0], [0.0, 0.0]]))
+        .input(arr1(&[2i32, 3]))
+        .attribute("hidden_size", 1i64)
+        .attribute("direction", "bidirectional")
+        .attribute("activations", vec!["Relu".to_string(), "Relu".to_string()])
+        .expect(
+            arr1(&[
+                1.0f32, 1.0, 0.0, 2.0, // t = 0
+                2.5, 0.0, 4.0, 0.0, // t = 1
+                // The first sequence is over: its outputs are zero.
+                0.0, 3.0, 0.0, 6.0, // t = 2
+            ])
+            .into_shape((3, 2, 2, 1))
+            .unwrap(),
+        )
+        // Its final states are the ones of its last element.
+        .expect(arr3(&[[[2.5f32], [3.0]], [[0.0], [2.0]]]))
+        .check();
+}
+
+#[test]
+fn leaky_relu_with_alpha() {
+    // Ht = leaky_relu(Xt + 0.5 * Ht-1), with a slope of 0.1 for the negative values.
+    OpTest::new("RNN")
+        .opset(14)
+        .input(arr3(&[[[1.0f32]], [[-3.0]], [[2.0]]]))
+        .input(arr3(&[[[1.0f32]]]))
+        .input(arr3(&[[[0.5f32]]]))
+        .attribute("activations", vec!["LeakyRelu".to_string()])
+        .attribute("activation_alpha", vec![0.1f32])
+        .expect(
+            arr1(&[1.0f32, -0.25, 1.875])
+                .into_shape((3, 1, 1, 1))
+                .unwrap(),
+        )
+        .expect(arr3(&[[[1.875f32]]]))
+        .tolerance(1e-6, 1e-6)
+        .check();
+}
+
+#[test]
+fn invalid_activations() {
+    let error = OpTest::new("RNN")
+        .opset(14)
+        .input(arr3(&[[[1.0f32]]]))
+        .input(arr3(&[[[1.0f32]], [[1.0]]]))
+        .input(arr3(&[[[1.0f32]], [[1.0]]]))
+        .attribute("direction", "bidirectional")
+        .attribute("activations", vec!["Relu".to_string()])
+        .expect(arr3(&[[[0.0f32]]]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("expected 2 functions for 2 direction(s)"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,153 @@
+//! Runs RoiAlign through the executor:
+//!
+//! ```text
+//! cargo test --test roi_align
+//! ```
+//!
+//! The feature maps are linear, `4 * y + x` on a 4x4 map: the bilinear interpolation is exact on
+//! them, so the average of a bin inside the map is the value at its center, the same in
+//! torchvision and onnxruntime. The bins crossing the border and the maximum mode are worked out
+//! by hand.
+
+use ndarray::{arr1, arr2, Array, Array4};
+use onnx_rustime_lib::testing::OpTest;
+
+/// The feature maps `100 * batch ± (4 * y + x)`, the sign being the one of the channel.
+fn features(batches: usize, channels: usize) -> Array4<f32> {
+    Array4::from_shape_fn((batches, channels, 4, 4), |(b, c, y, x)| {
+        let sign = if c == 0 { 1.0 } else { -1.0 };
+        100.0 * b as f32 + sign * (4 * y + x) as f32
+    })
+}
+
+fn roi_align(opset: i64, roi: [f32; 4]) -> OpTest {
+    OpTest::new("RoiAlign")
+        .opset(opset)
+        .input(features(1, 1))
+        .input(arr2(&[roi]))
+        .input(arr1(&[0i64]))
+}
+
+fn pooled(values: &[f32], height: usize, width: usize) -> Array4<f32> {
+    Array::from_iter(values.iter().copied())
+        .into_shape((values.len() / (height * width), 1, height, width))
+        .unwrap()
+}
+
+#[test]
+fn half_pixel() {
+    // The region [0.5, 2.5] along both axes: the centers of the bins are at 1 and 2.
+    roi_align(16, [1.0, 1.0, 3.0, 3.0])
+        .attribute("output_height", 2i64)
+        .attribute("output_width", 2i64)
+        .attribute("sampling_ratio", 2i64)
+        .expect(pooled(&[5.0, 6.0, 9.0, 10.0], 2, 2))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn output_half_pixel() {
+    // The region [1, 3] along both axes: the centers of the bins are at 1.5 and 2.5. It is the
+    // only mode before opset 16.
+    let expected = pooled(&[7.5, 8.5, 11.5, 12.5], 2, 2);
+    roi_align(14, [1.0, 1.0, 3.0, 3.0])
+        .attribute("output_height", 2i64)
+        .attribute("output_width", 2i64)
+        .attribute("sampling_ratio", 2i64)
+        .expect(expected.clone())
+        .tolerance(1e-5, 1e-5)
+        .check();
+    roi_align(16, [1.0, 1.0, 3.0, 3.0])
+        .attribute("output_height", 2i64)
+        .attribute("output_width", 2i64)
+        .attribute("sampling_ratio", 2i64)
+        .attribute("coordinate_transformation_mode", "output_half_pixel")
+        .expect(expected)
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn spatial_scale() {
+    roi_align(16, [2.0, 2.0, 6.0, 6.0])
+        .attribute("output_height", 2i64)
+        .attribute("output_width", 2i64)
+        .attribute("sampling_ratio", 2i64)
+        .attribute("spatial_scale", 0.5f32)
+        .expect(pooled(&[5.0, 6.0, 9.0, 10.0], 2, 2))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn points_outside_the_feature_map() {
+    // The points at 3 and 5 along both axes: only the one at (3, 3) is in the map, its value 15
+    // being averaged with three zeros.
+    roi_align(14, [2.0, 2.0, 6.0, 6.0])
+        .attribute("sampling_ratio", 2i64)
+        .expect(pooled(&[3.75], 1, 1))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn adaptive_sampling_ratio() {
+    // Four points per axis, at 2.5, 3.5, 4.5 and 5.5: 3.5 is clamped to the last pixel, 3, and
+    // the last two are outside, so (4 * (2.5 + 3) * 2 + (2.5 + 3) * 2) / 16.
+    roi_align(14, [2.0, 2.0, 6.0, 6.0])
+        .expect(pooled(&[3.4375], 1, 1))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn max_mode() {
+    // A single point at (0.5, 0.5): the largest of the weighted pixels is 0.25 * 5, where the
+    // average is 2.5.
+    roi_align(14, [0.0, 0.0, 1.0, 1.0])
+        .attribute("sampling_ratio", 1i64)
+        .attribute("mode", "max")
+        .expect(pooled(&[1.25], 1, 1))
+        .tolerance(1e-5, 1e-5)
+        .check();
+}
+
+#[test]
+fn batch_indices_and_channels() {
+    OpTest::new("RoiAlign")
+        .opset(16)
+        .input(features(2, 2))
+        .input(arr2(&[[1.0f32, 1.0, 3.0, 3.0], [1.0, 1.0, 3.0, 3.0]]))
+        .input(arr1(&[1i64, 0]))
+        .attribute("output_height", 2i64)
+        .attribute("output_width", 2i64)
+        .attribute("sampling_ratio", 2i64)
+        .expect(
+            Array::from_iter([
+                105.0f32, 106.0, 109.0, 110.0, 95.0, 94.0, 91.0, 90.0, // batch 1
+                5.0, 6.0, 9.0, 10.0, -5.0, -6.0, -9.0, -10.0, // batch 0
+            ])
+            .into_shape((2, 2, 2, 2))
+            .unwrap(),
+        )
+        .tolerance(1e-4, 1e-5)
+        .check();
+}
+
+#[test]
+fn batch_index_out_of_range() {
+    let error = OpTest::new("RoiAlign")
+        .opset(16)
+        .input(features(1, 1))
+        .input(arr2(&[[1.0f32, 1.0, 3.0, 3.0]]))
+        .input(arr1(&[1i64]))
+        .expect(pooled(&[0.0], 1, 1))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The batch index 1 of region 0 is out of range for a batch of 1"),
+        "{}",
+        error
+    );
+}
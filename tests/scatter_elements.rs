@@ -0,0 +1,97 @@
+//! Runs ScatterElements through the executor, on the examples of the ONNX operator
+//! documentation:
+//!
+//! ```text
+//! cargo test --test scatter_elements
+//! ```
+
+use ndarray::{arr2, Array2};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+fn row() -> Array2<f32> {
+    arr2(&[[1.0f32, 2.0, 3.0, 4.0, 5.0]])
+}
+
+/// A ScatterElements of `[[1.1, 2.1]]` into `row()` at the columns `indices`.
+fn scattered(indices: [i64; 2], reduction: &str) -> OpTest {
+    OpTest::new("ScatterElements")
+        .opset(18)
+        .input(row())
+        .input(arr2(&[indices]))
+        .input(arr2(&[[1.1f32, 2.1]]))
+        .attribute("axis", 1i64)
+        .attribute("reduction", reduction)
+}
+
+#[test]
+fn along_axis_0() {
+    op_test!("ScatterElements",
+             inputs: [Array2::<f32>::zeros((3, 3)),
+                      arr2(&[[1i64, 0, 2], [0, 2, 1]]),
+                      arr2(&[[1.0f32, 1.1, 1.2], [2.0, 2.1, 2.2]])],
+             attrs: {},
+             expect: arr2(&[[2.0f32, 1.1, 0.0], [1.0, 0.0, 2.2], [0.0, 2.1, 1.2]]));
+}
+
+#[test]
+fn along_axis_1() {
+    op_test!("ScatterElements",
+             inputs: [row(), arr2(&[[1i64, 3]]), arr2(&[[1.1f32, 2.1]])],
+             attrs: { axis: 1i64 },
+             expect: arr2(&[[1.0f32, 1.1, 3.0, 2.1, 5.0]]));
+    // On INT64 data.
+    op_test!("ScatterElements",
+             inputs: [arr2(&[[1i64, 2, 3]]), arr2(&[[2i64, 0]]), arr2(&[[7i64, 8]])],
+             attrs: { axis: -1i64 },
+             expect: arr2(&[[8i64, 2, 7]]));
+}
+
+#[test]
+fn negative_indices() {
+    scattered([1, -3], "none")
+        .expect(arr2(&[[1.0f32, 1.1, 2.1, 4.0, 5.0]]))
+        .check();
+}
+
+#[test]
+fn duplicate_indices() {
+    // Without reduction, the last update wins.
+    scattered([1, 1], "none")
+        .expect(arr2(&[[1.0f32, 2.1, 3.0, 4.0, 5.0]]))
+        .check();
+    // 2 + 1.1 + 2.1
+    scattered([1, 1], "add")
+        .expect(arr2(&[[1.0f32, 5.2, 3.0, 4.0, 5.0]]))
+        .check();
+    // 2 * 1.1 * 2.1
+    scattered([1, 1], "mul")
+        .expect(arr2(&[[1.0f32, 4.62, 3.0, 4.0, 5.0]]))
+        .check();
+    scattered([1, 1], "min")
+        .expect(arr2(&[[1.0f32, 1.1, 3.0, 4.0, 5.0]]))
+        .check();
+    scattered([1, 1], "max")
+        .expect(arr2(&[[1.0f32, 2.1, 3.0, 4.0, 5.0]]))
+        .check();
+}
+
+#[test]
+fn invalid_scatters() {
+    let error = scattered([1, 5], "none").expect(row()).run().unwrap_err();
+    assert!(error.contains("Index 5 is out of range"), "{}", error);
+
+    let error = scattered([1, 3], "sum").expect(row()).run().unwrap_err();
+    assert!(error.contains("Invalid attribute 'reduction'"), "{}", error);
+
+    // Updates of another shape than the indices.
+    let error = OpTest::new("ScatterElements")
+        .input(row())
+        .input(arr2(&[[1i64, 3]]))
+        .input(arr2(&[[1.1f32]]))
+        .attribute("axis", 1i64)
+        .expect(row())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("must have the same shape"), "{}", error);
+}
@@ -0,0 +1,122 @@
+//! Runs ScatterND through the executor, on the examples of the ONNX operator documentation:
+//!
+//! ```text
+//! cargo test --test scatter_nd
+//! ```
+
+use ndarray::{arr1, arr2, arr3, stack, Array1, Array2, Array3, Axis};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+fn vector() -> Array1<f32> {
+    arr1(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0])
+}
+
+/// A ScatterND of `[10, 20]` into `vector()` at the positions `indices`.
+fn scattered(indices: [i64; 2], reduction: &str) -> OpTest {
+    OpTest::new("ScatterND")
+        .opset(18)
+        .input(vector())
+        .input(arr2(&[[indices[0]], [indices[1]]]))
+        .input(arr1(&[10.0f32, 20.0]))
+        .attribute("reduction", reduction)
+}
+
+#[test]
+fn entries_of_a_vector() {
+    op_test!("ScatterND",
+             inputs: [vector(), arr2(&[[4i64], [3], [1], [7]]), arr1(&[9.0f32, 10.0, 11.0, 12.0])],
+             attrs: {},
+             expect: arr1(&[1.0f32, 11.0, 3.0, 10.0, 9.0, 6.0, 7.0, 12.0]));
+}
+
+#[test]
+fn slices_of_a_cube() {
+    let ascending = arr2(&[
+        [1.0f32, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [8.0, 7.0, 6.0, 5.0],
+        [4.0, 3.0, 2.0, 1.0],
+    ]);
+    let descending = arr2(&[
+        [8.0f32, 7.0, 6.0, 5.0],
+        [4.0, 3.0, 2.0, 1.0],
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+    ]);
+    // Rows of the same value.
+    let rows = |values: [f32; 4]| Array2::from_shape_fn((4, 4), |(row, _)| values[row]);
+    let cube = |matrices: [&Array2<f32>; 4]| -> Array3<f32> {
+        stack(Axis(0), &matrices.map(|matrix| matrix.view())).unwrap()
+    };
+    let (fives, ones) = (rows([5.0, 6.0, 7.0, 8.0]), rows([1.0, 2.0, 3.0, 4.0]));
+
+    op_test!("ScatterND",
+             inputs: [cube([&ascending, &ascending, &descending, &descending]),
+                      arr2(&[[0i64], [2]]),
+                      stack(Axis(0), &[fives.view(), ones.view()]).unwrap()],
+             attrs: {},
+             expect: cube([&fives, &ascending, &ones, &descending]));
+}
+
+#[test]
+fn rows_of_a_buffer() {
+    // Two detections written into the rows 0 and 2 of a buffer of 4, the last one from the end.
+    op_test!("ScatterND",
+             inputs: [Array2::<f32>::zeros((4, 3)),
+                      arr2(&[[0i64], [-2]]),
+                      arr2(&[[0.9f32, 10.0, 20.0], [0.8, 30.0, 40.0]])],
+             attrs: {},
+             expect: arr2(&[[0.9f32, 10.0, 20.0],
+                            [0.0, 0.0, 0.0],
+                            [0.8, 30.0, 40.0],
+                            [0.0, 0.0, 0.0]]));
+    // Single entries of INT64 data.
+    op_test!("ScatterND",
+             inputs: [arr3(&[[[0i64, 0], [0, 0]]]), arr2(&[[0i64, 1, 0]]), arr1(&[5i64])],
+             attrs: {},
+             expect: arr3(&[[[0i64, 0], [5, 0]]]));
+}
+
+#[test]
+fn duplicate_indices() {
+    // Without reduction, the last update wins.
+    scattered([1, 1], "none")
+        .expect(arr1(&[1.0f32, 20.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]))
+        .check();
+    scattered([1, 1], "add")
+        .expect(arr1(&[1.0f32, 32.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]))
+        .check();
+    scattered([1, 1], "mul")
+        .expect(arr1(&[1.0f32, 400.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]))
+        .check();
+    scattered([1, -1], "min")
+        .expect(arr1(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]))
+        .check();
+    scattered([1, -1], "max")
+        .expect(arr1(&[1.0f32, 10.0, 3.0, 4.0, 5.0, 6.0, 7.0, 20.0]))
+        .check();
+}
+
+#[test]
+fn invalid_scatters() {
+    let error = scattered([1, 8], "none")
+        .expect(vector())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("Index 8 is out of range"), "{}", error);
+
+    // One update for two indices.
+    let error = OpTest::new("ScatterND")
+        .input(vector())
+        .input(arr2(&[[0i64], [1]]))
+        .input(arr1(&[10.0f32]))
+        .expect(vector())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Cannot scatter updates of shape [1]"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,322 @@
+//! Configures sessions with `SessionBuilder`, one option at a time:
+//!
+//! ```text
+//! cargo test --test session_builder
+//! ```
+//!
+//! The test model computes `Relu(Identity(x)) * scale`, with `scale` an initializer of ones: the
+//! Identity shows the optimization level, the initializer the overrides and the external data.
+
+use ndarray::{arr1, ArrayD};
+use onnx_rustime_lib::backend::format::FormatOptions;
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::session::{Session, SessionBuilder};
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, NodeProto, StringStringEntryProto, TensorProto, TensorProto_DataLocation,
+    TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::{
+    CancellationToken, Model, NonFiniteCheck, RunOptions,
+};
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::transforms::OptLevel;
+use protobuf::RepeatedField;
+use std::fs;
+use std::path::PathBuf;
+
+fn scale(value: f32) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(arr1(&[value; 4]).into_dyn(), "scale").unwrap()
+}
+
+fn model() -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let value_info = |name: &str| {
+        make_tensor_value_info(
+            name,
+            TensorProto_DataType::FLOAT,
+            vec![Dimension::Value(4)],
+            None,
+        )
+    };
+    let graph = make_graph(
+        vec![
+            node("Identity", vec!["x"], "copy"),
+            node("Relu", vec!["copy"], "rectified"),
+            node("Mul", vec!["rectified", "scale"], "y"),
+        ],
+        "scaled_relu",
+        vec![value_info("x")],
+        vec![value_info("y")],
+        vec![scale(1.0)],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn builder() -> SessionBuilder {
+    Session::builder().model(model())
+}
+
+fn run(session: &Session) -> Result<ArrayD<f32>, OnnxError> {
+    let x = Tensor::from_ndarray(arr1(&[-2.0f32, -1.0, 1.0, 2.0]).into_dyn()).with_name("x");
+    let outputs = session.run_with_inputs(vec![x])?;
+    outputs[0].to_ndarray::<f32>()
+}
+
+fn negate(operands: &[&TensorProto], node: &NodeProto) -> Result<TensorProto, OnnxError> {
+    let negated = tensor_proto_to_ndarray::<f32>(operands[0])?.mapv(|value| -value);
+    ndarray_to_tensor_proto::<f32>(negated, &node.get_output()[0])
+}
+
+fn invalid_value(builder: SessionBuilder) -> String {
+    match builder.build() {
+        Err(OnnxError::InvalidValue(message)) => message,
+        other => panic!("Expected an invalid value, got {:?}", other.map(|_| ())),
+    }
+}
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "onnx_rustime_session_builder_{}_{}",
+        test,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn default_construction() {
+    let session = Session::from_model(model()).unwrap();
+
+    let options = session.options();
+    let defaults = RunOptions::default();
+    assert_eq!(options.threads, None);
+    assert!(!options.verbose && !options.profiling && !options.reference_kernels);
+    assert_eq!(options.model, defaults.model);
+    assert_eq!(options.non_finite_check, NonFiniteCheck::Off);
+    assert_eq!(options.tensor_format, FormatOptions::default());
+    assert!(options.custom_ops.is_empty() && options.cancellation.is_none());
+
+    // Optimized at the Basic level: the Identity is gone.
+    let report = session.optimization_report();
+    assert_eq!(report.level, OptLevel::Basic);
+    assert_eq!((report.nodes_before, report.nodes_after), (3, 2));
+    assert_eq!(session.model().get_graph().get_node().len(), 2);
+
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[0.0, 0.0, 1.0, 2.0]).into_dyn()
+    );
+}
+
+#[test]
+fn run_options_take_effect() {
+    let format = FormatOptions {
+        precision: 2,
+        ..FormatOptions::default()
+    };
+    let token = CancellationToken::new();
+    let session = builder()
+        .threads(2)
+        .enable_profiling()
+        .verbose(true)
+        .labels(Model::Mnist)
+        .non_finite_check(NonFiniteCheck::Warn)
+        .reference_kernels(true)
+        .tensor_format(format.clone())
+        .cancellation_token(token.clone())
+        .build()
+        .unwrap();
+
+    let options = session.options();
+    assert_eq!(options.threads, Some(2));
+    assert!(options.profiling && options.verbose && options.reference_kernels);
+    assert_eq!(options.model, Model::Mnist);
+    assert_eq!(options.non_finite_check, NonFiniteCheck::Warn);
+    assert_eq!(options.tensor_format, format);
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[0.0, 0.0, 1.0, 2.0]).into_dyn()
+    );
+
+    // The session holds a clone of the token.
+    token.cancel();
+    assert!(matches!(run(&session), Err(OnnxError::Cancelled)));
+}
+
+#[test]
+fn verbosity_of_a_copy() {
+    let session = builder().labels(Model::Mnist).build().unwrap();
+    let verbose = session.with_verbose(true);
+    assert!(verbose.options().verbose && !session.options().verbose);
+    assert_eq!(verbose.options().model, Model::Mnist);
+    assert_eq!(verbose.model(), session.model());
+    assert_eq!(run(&verbose).unwrap(), run(&session).unwrap());
+}
+
+#[test]
+fn optimization_level() {
+    let session = builder().opt_level(OptLevel::None).build().unwrap();
+    assert_eq!(session.optimization_report().level, OptLevel::None);
+    assert_eq!(session.model().get_graph().get_node().len(), 3);
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[0.0, 0.0, 1.0, 2.0]).into_dyn()
+    );
+}
+
+#[test]
+fn custom_operations() {
+    // The custom kernel replaces the built-in Relu, and survives the replaced run options.
+    let session = builder()
+        .enable_profiling()
+        .register_op("Relu", negate)
+        .run_options(RunOptions {
+            threads: Some(3),
+            ..RunOptions::default()
+        })
+        .build()
+        .unwrap();
+    assert_eq!(session.options().threads, Some(3));
+    assert!(!session.options().profiling);
+    assert!(session.options().custom_ops.contains_key("Relu"));
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[2.0, 1.0, -1.0, -2.0]).into_dyn()
+    );
+}
+
+#[test]
+fn initializer_overrides() {
+    let session = builder().override_initializer(scale(3.0)).build().unwrap();
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[0.0, 0.0, 3.0, 6.0]).into_dyn()
+    );
+
+    // The non-finite check sees the overridden value.
+    let session = builder()
+        .override_initializer(scale(f32::INFINITY))
+        .non_finite_check(NonFiniteCheck::Abort)
+        .build()
+        .unwrap();
+    match run(&session).unwrap_err().root_cause() {
+        OnnxError::NonFiniteOutput { node_name, .. } => assert_eq!(node_name, "y"),
+        other => panic!("Expected a non-finite output, got {:?}", other),
+    }
+
+    let mut unknown = scale(3.0);
+    unknown.set_name("bias".to_string());
+    let message = invalid_value(builder().override_initializer(unknown));
+    assert!(message.contains("'bias'"), "{}", message);
+
+    let wrong_shape = ndarray_to_tensor_proto::<f32>(arr1(&[3.0f32; 2]).into_dyn(), "scale");
+    let message = invalid_value(builder().override_initializer(wrong_shape.unwrap()));
+    assert!(message.contains("shape [4]"), "{}", message);
+}
+
+#[test]
+fn model_path_and_external_data_dir() {
+    let dir = temp_dir("external_data");
+    let weights_dir = dir.join("weights");
+    fs::create_dir_all(&weights_dir).unwrap();
+
+    // The scale is stored in weights/scale.bin, and set to 2.
+    let mut model = model();
+    let initializer = &mut model.mut_graph().mut_initializer()[0];
+    *initializer = scale(2.0);
+    let entry = |key: &str, value: &str| {
+        let mut entry = StringStringEntryProto::new();
+        entry.set_key(key.to_string());
+        entry.set_value(value.to_string());
+        entry
+    };
+    initializer.set_external_data(RepeatedField::from_vec(vec![entry(
+        "location",
+        "scale.bin",
+    )]));
+    initializer.set_data_location(TensorProto_DataLocation::EXTERNAL);
+    fs::write(weights_dir.join("scale.bin"), initializer.take_raw_data()).unwrap();
+    let path = dir.join("model.onnx");
+    OnnxParser::save_model(&model, path.to_string_lossy().into_owned()).unwrap();
+
+    // The data isn't next to the model.
+    assert!(Session::builder().model_path(&path).build().is_err());
+
+    let session = Session::builder()
+        .model_path(&path)
+        .external_data_dir(&weights_dir)
+        .build()
+        .unwrap();
+    assert_eq!(
+        run(&session).unwrap(),
+        arr1(&[0.0, 0.0, 2.0, 4.0]).into_dyn()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn validation_failures() {
+    let message = invalid_value(Session::builder());
+    assert!(message.starts_with("No model given"), "{}", message);
+
+    let message = invalid_value(builder().model_path("model.onnx"));
+    assert!(message.contains("'model.onnx'"), "{}", message);
+
+    let message = invalid_value(builder().external_data_dir(std::env::temp_dir()));
+    assert!(message.contains("model_path"), "{}", message);
+
+    let missing = temp_dir("validation").join("missing");
+    let message = invalid_value(
+        Session::builder()
+            .model_path("model.onnx")
+            .external_data_dir(&missing),
+    );
+    assert!(message.contains("doesn't exist"), "{}", message);
+
+    let message = invalid_value(builder().threads(0));
+    assert!(message.contains("threads"), "{}", message);
+
+    let message = invalid_value(builder().register_op("", negate));
+    assert!(message.contains("must have a type"), "{}", message);
+
+    let message = invalid_value(
+        builder()
+            .register_op("Relu", negate)
+            .register_op("Relu", negate),
+    );
+    assert!(message.contains("'Relu' registered twice"), "{}", message);
+
+    // The model is only loaded once the options are valid.
+    match Session::builder().model_path(&missing).build() {
+        Err(OnnxError::Io { path, .. }) => assert_eq!(path, missing.to_string_lossy()),
+        other => panic!("Expected an Io error, got {:?}", other.map(|_| ())),
+    }
+    fs::remove_dir_all(missing.parent().unwrap()).unwrap();
+}
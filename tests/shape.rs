@@ -0,0 +1,160 @@
+//! Runs Shape and Size through the executor:
+//!
+//! ```text
+//! cargo test --test shape
+//! ```
+//!
+//! Both outputs are INT64, whatever the type of the input, so that they can feed a Reshape.
+
+use ndarray::{arr1, arr3, Array1, Array3};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+/// A [2, 3, 4] tensor of the values 0 to 23.
+fn input() -> Array3<f32> {
+    Array1::range(0.0, 24.0, 1.0).into_shape((2, 3, 4)).unwrap()
+}
+
+#[test]
+fn whole_shape() {
+    OpTest::new("Shape")
+        .opset(13)
+        .input(input())
+        .expect(arr1(&[2i64, 3, 4]))
+        .check();
+    OpTest::new("Shape")
+        .opset(15)
+        .input(arr1(&[1i64, 2, 3, 4, 5]))
+        .expect(arr1(&[5i64]))
+        .check();
+}
+
+#[test]
+fn start_and_end() {
+    for (start, end, expected) in [
+        (Some(1i64), None, vec![3i64, 4]),
+        (None, Some(1i64), vec![2]),
+        (Some(-2), Some(-1), vec![3]),
+        // Out of range values are clamped.
+        (Some(-10), Some(10), vec![2, 3, 4]),
+        (Some(2), Some(1), vec![]),
+    ] {
+        let mut test = OpTest::new("Shape").opset(15).input(input());
+        if let Some(start) = start {
+            test = test.attribute("start", start);
+        }
+        if let Some(end) = end {
+            test = test.attribute("end", end);
+        }
+        test.expect(Array1::from(expected)).check();
+    }
+}
+
+#[test]
+fn size() {
+    OpTest::new("Size")
+        .opset(13)
+        .input(input())
+        .expect(arr1(&[24i64]).into_shape(()).unwrap())
+        .check();
+    OpTest::new("Size")
+        .opset(13)
+        .input(arr3(&[[[true], [false]]]))
+        .expect(arr1(&[2i64]).into_shape(()).unwrap())
+        .check();
+}
+
+#[test]
+fn shape_feeds_a_reshape() {
+    // Flattens every item of the batch: Reshape(x, Concat(Shape(x)[:1], [-1])).
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "Shape",
+            vec!["x"],
+            vec!["batch"],
+            None,
+            None,
+            None,
+            vec![make_attribute("end", Attribute::<String>::Int(1))],
+        ),
+        make_node(
+            "Concat",
+            vec!["batch", "rest"],
+            vec!["target_shape"],
+            None,
+            None,
+            None,
+            vec![make_attribute("axis", Attribute::<String>::Int(0))],
+        ),
+        make_node(
+            "Reshape",
+            vec!["x", "target_shape"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![],
+        ),
+    ];
+    let rest = ndarray_to_tensor_proto::<i64>(arr1(&[-1]).into_dyn(), "rest");
+    let graph = make_graph(
+        nodes,
+        "shape_and_reshape",
+        vec![value_info("x")],
+        vec![value_info("y"), value_info("target_shape")],
+        vec![rest.unwrap()],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(input().into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = |name: &str| {
+        outputs
+            .iter()
+            .find(|output| output.get_name() == name)
+            .unwrap()
+            .clone()
+    };
+
+    let target_shape = output("target_shape");
+    assert_eq!(
+        target_shape.get_data_type(),
+        TensorProto_DataType::INT64 as i32
+    );
+    assert_eq!(
+        tensor_proto_to_ndarray::<i64>(&target_shape).unwrap(),
+        arr1(&[2i64, -1]).into_dyn()
+    );
+    let y = output("y");
+    assert_eq!(y.get_dims(), &[2, 12]);
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&y).unwrap(),
+        Array1::range(0.0, 24.0, 1.0)
+            .into_shape((2, 12))
+            .unwrap()
+            .into_dyn()
+    );
+}
@@ -0,0 +1,169 @@
+//! Checks that shape mismatches in Conv, Gemm and Concat name the node and its inputs:
+//!
+//! ```text
+//! cargo test --test shape_mismatch
+//! ```
+//!
+//! The test graph is a small classifier, `Concat(Gemm(Flatten(GlobalAveragePool(Conv(x)))),
+//! extra)`, and every test breaks the shape of one weight or input of a different node.
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+
+fn zeros(name: &str, shape: &[usize]) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(ArrayD::zeros(IxDyn(shape)), name).unwrap()
+}
+
+fn value_info(name: &str, shape: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        shape.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+/// The classifier, with the given shapes of the convolution and Gemm weights.
+fn model(conv_weight: &[usize], gemm_weight: &[usize]) -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str, name: &str, attributes| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(name),
+            None,
+            None,
+            attributes,
+        )
+    };
+    let axis = |axis| vec![make_attribute("axis", Attribute::<String>::Int(axis))];
+
+    let graph = make_graph(
+        vec![
+            node("Conv", vec!["x", "stem_w"], "features", "stem", vec![]),
+            node(
+                "GlobalAveragePool",
+                vec!["features"],
+                "pooled",
+                "pool",
+                vec![],
+            ),
+            node("Flatten", vec!["pooled"], "flat", "flatten", axis(1)),
+            node(
+                "Gemm",
+                vec!["flat", "classifier_w"],
+                "logits",
+                "classifier",
+                vec![],
+            ),
+            node("Concat", vec!["logits", "extra"], "y", "merge", axis(1)),
+        ],
+        "classifier",
+        vec![value_info("x", &[1, 3, 8, 8]), value_info("extra", &[1, 2])],
+        vec![value_info("y", &[1, 12])],
+        vec![
+            zeros("stem_w", conv_weight),
+            zeros("classifier_w", gemm_weight),
+        ],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(model: &ModelProto, extra: &[usize]) -> Result<Vec<TensorProto>, OnnxError> {
+    let inputs = vec![zeros("x", &[1, 3, 8, 8]), zeros("extra", extra)];
+    run_with_inputs(model, inputs, &RunOptions::default())
+}
+
+/// Runs the model expecting a shape mismatch, and checks the node it names, its inputs and the
+/// position of the node in the message.
+fn assert_mismatch(
+    result: Result<Vec<TensorProto>, OnnxError>,
+    node: (&str, &str),
+    inputs: &[(&str, &[i64])],
+    position: &str,
+) {
+    let error = result.unwrap_err();
+    let message = error.to_string();
+    match error.root_cause() {
+        OnnxError::ShapeMismatch {
+            node_name,
+            op_type,
+            input_names,
+            input_shapes,
+            ..
+        } => {
+            assert_eq!((node_name.as_str(), op_type.as_str()), node);
+            let actual: Vec<(&str, &[i64])> = input_names
+                .iter()
+                .map(|name| name.as_str())
+                .zip(input_shapes.iter().map(|shape| shape.as_slice()))
+                .collect();
+            assert_eq!(actual, inputs);
+        }
+        other => panic!("Expected a shape mismatch, got {:?}", other),
+    }
+
+    assert!(message.contains(position), "{}", message);
+    assert!(message.contains(&format!("'{}'", node.0)), "{}", message);
+    for (name, _) in inputs {
+        assert!(message.contains(name), "{}", message);
+    }
+}
+
+#[test]
+fn valid_model() {
+    let outputs = run(&model(&[4, 3, 3, 3], &[4, 10]), &[1, 2]).unwrap();
+    assert_eq!(outputs[0].get_dims(), [1, 12]);
+}
+
+#[test]
+fn conv_channels() {
+    // The weight expects 2 input channels, the image has 3.
+    assert_mismatch(
+        run(&model(&[4, 2, 3, 3], &[4, 10]), &[1, 2]),
+        ("stem", "Conv"),
+        &[("x", &[1, 3, 8, 8]), ("stem_w", &[4, 2, 3, 3])],
+        "Node 1/5",
+    );
+}
+
+#[test]
+fn gemm_inner_dimensions() {
+    // 4 features against a weight for 5.
+    assert_mismatch(
+        run(&model(&[4, 3, 3, 3], &[5, 10]), &[1, 2]),
+        ("classifier", "Gemm"),
+        &[("flat", &[1, 4]), ("classifier_w", &[5, 10])],
+        "Node 4/5",
+    );
+}
+
+#[test]
+fn concat_other_dimensions() {
+    // The batch dimensions differ, out of the concatenation axis.
+    assert_mismatch(
+        run(&model(&[4, 3, 3, 3], &[4, 10]), &[2, 2]),
+        ("merge", "Concat"),
+        &[("logits", &[1, 10]), ("extra", &[2, 2])],
+        "Node 5/5",
+    );
+}
@@ -0,0 +1,345 @@
+//! Runs models on the arrays shared between the nodes, and compares them with the kernels taking
+//! `TensorProto`s:
+//!
+//! ```text
+//! cargo test --test shared_tensors
+//! ```
+//!
+//! The FLOAT tensors flowing between the kernels that run on arrays are neither decoded nor
+//! encoded, the initializers are decoded once by the plan of the session, and the kernels
+//! overwrite the operands no other node reads. The outputs, and every intermediate tensor, must
+//! be bit-identical to the ones of the kernels called node by node on `TensorProto`s. The test
+//! model is a small residual network; ResNet-18 is checked the same way by an ignored test run
+//! once the models are pulled.
+
+mod common;
+
+use ndarray::{arr1, ArrayD};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::run::run_with_intermediates;
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::Tensor;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    AttributeProto, ModelProto, NodeProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::add::add;
+use onnx_rustime_lib::ops::batch_normalization::batch_normalization;
+use onnx_rustime_lib::ops::conv::conv;
+use onnx_rustime_lib::ops::flatten::flatten;
+use onnx_rustime_lib::ops::gemm::gemm;
+use onnx_rustime_lib::ops::global_average_pool::global_average_pool;
+use onnx_rustime_lib::ops::identity::identity;
+use onnx_rustime_lib::ops::matmul::matmul;
+use onnx_rustime_lib::ops::maxpool::maxpool;
+use onnx_rustime_lib::ops::relu::relu;
+use onnx_rustime_lib::ops::reshape::reshape;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::generators::random_tensor;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+
+const RESNET: &str = "models/resnet18-v2-7/resnet18-v2-7.onnx";
+const RESNET_INPUT: &str = "models/resnet18-v2-7/test_data_set_0/input_0.pb";
+
+const CHANNELS: usize = 8;
+
+fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        dims.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn ints(name: &str, values: &[i64]) -> AttributeProto {
+    make_attribute(name, Attribute::<String>::Ints(values.to_vec()))
+}
+
+fn node(
+    op_type: &str,
+    inputs: Vec<&str>,
+    output: &str,
+    attributes: Vec<AttributeProto>,
+) -> NodeProto {
+    make_node(
+        op_type,
+        inputs,
+        vec![output],
+        Some(output),
+        None,
+        None,
+        attributes,
+    )
+}
+
+/// A Conv of 3x3 kernels keeping the spatial dimensions.
+fn conv3x3(input: &str, kernel: &str, output: &str) -> NodeProto {
+    node(
+        "Conv",
+        vec![input, kernel],
+        output,
+        vec![ints("kernel_shape", &[3, 3]), ints("pads", &[1, 1, 1, 1])],
+    )
+}
+
+fn batch_norm(input: &str, prefix: &str, output: &str) -> NodeProto {
+    let names: Vec<String> = ["scale", "bias", "mean", "var"]
+        .iter()
+        .map(|parameter| format!("{}_{}", prefix, parameter))
+        .collect();
+    let mut inputs = vec![input];
+    inputs.extend(names.iter().map(String::as_str));
+    node("BatchNormalization", inputs, output, vec![])
+}
+
+/// A residual network on [1, 3, 16, 16] images: a stem, a basic block, a bias shared through an
+/// Identity, global pooling and two fully connected layers, the second one after a Reshape.
+///
+/// The input of the block is read by its first Conv and by the Add of the residual, and the
+/// logits are both an output and the input of the Reshape, so that the kernels overwriting their
+/// operand must copy them.
+fn residual_model() -> ModelProto {
+    let mut rng = StdRng::seed_from_u64(242);
+    let mut initializer = |name: &str, shape: &[usize]| {
+        let mut values = random_tensor(&mut rng, shape);
+        if name.ends_with("_var") {
+            values.mapv_inplace(|value| value.abs() + 0.1);
+        }
+        ndarray_to_tensor_proto::<f32>(values, name).unwrap()
+    };
+
+    let mut initializers = vec![
+        initializer("stem_w", &[CHANNELS, 3, 3, 3]),
+        initializer("stem_b", &[CHANNELS]),
+        initializer("block_w1", &[CHANNELS, CHANNELS, 3, 3]),
+        initializer("block_w2", &[CHANNELS, CHANNELS, 3, 3]),
+        initializer("shift", &[CHANNELS, 1, 1]),
+        initializer("fc_w", &[10, CHANNELS]),
+        initializer("fc_b", &[10]),
+        initializer("head_w", &[5, 4]),
+    ];
+    for prefix in ["stem_bn", "block_bn1", "block_bn2"] {
+        for parameter in ["scale", "bias", "mean", "var"] {
+            initializers.push(initializer(
+                &format!("{}_{}", prefix, parameter),
+                &[CHANNELS],
+            ));
+        }
+    }
+    initializers
+        .push(ndarray_to_tensor_proto::<i64>(arr1(&[2, 5]).into_dyn(), "head_shape").unwrap());
+
+    let nodes = vec![
+        node(
+            "Conv",
+            vec!["x", "stem_w", "stem_b"],
+            "stem_conv",
+            vec![ints("kernel_shape", &[3, 3]), ints("pads", &[1, 1, 1, 1])],
+        ),
+        batch_norm("stem_conv", "stem_bn", "stem_norm"),
+        node("Relu", vec!["stem_norm"], "stem_relu", vec![]),
+        node(
+            "MaxPool",
+            vec!["stem_relu"],
+            "pooled",
+            vec![ints("kernel_shape", &[2, 2]), ints("strides", &[2, 2])],
+        ),
+        conv3x3("pooled", "block_w1", "block_conv1"),
+        batch_norm("block_conv1", "block_bn1", "block_norm1"),
+        node("Relu", vec!["block_norm1"], "block_relu1", vec![]),
+        node("Identity", vec!["block_relu1"], "block_identity", vec![]),
+        conv3x3("block_identity", "block_w2", "block_conv2"),
+        batch_norm("block_conv2", "block_bn2", "block_norm2"),
+        node("Add", vec!["block_norm2", "pooled"], "residual", vec![]),
+        node("Relu", vec!["residual"], "block_out", vec![]),
+        node("Identity", vec!["shift"], "shift_identity", vec![]),
+        node("Relu", vec!["shift_identity"], "shift_relu", vec![]),
+        node("Add", vec!["shift", "block_out"], "shifted", vec![]),
+        node("Add", vec!["shifted", "shift_relu"], "features", vec![]),
+        node("GlobalAveragePool", vec!["features"], "gap", vec![]),
+        node(
+            "Flatten",
+            vec!["gap"],
+            "flat",
+            vec![make_attribute("axis", Attribute::<String>::Int(1))],
+        ),
+        node(
+            "Gemm",
+            vec!["flat", "fc_w", "fc_b"],
+            "logits",
+            vec![make_attribute("transB", Attribute::<String>::Int(1))],
+        ),
+        node("Reshape", vec!["logits", "head_shape"], "head_in", vec![]),
+        node("MatMul", vec!["head_in", "head_w"], "head", vec![]),
+        node("Relu", vec!["head"], "y", vec![]),
+    ];
+    let graph = make_graph(
+        nodes,
+        "residual",
+        vec![value_info("x", &[1, 3, 16, 16])],
+        vec![value_info("y", &[2, 4]), value_info("logits", &[1, 10])],
+        initializers,
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Runs the nodes of a model one by one with the kernels taking `TensorProto`s, and returns
+/// every tensor computed, by name.
+fn run_protos(model: &ModelProto, input: TensorProto) -> HashMap<String, TensorProto> {
+    let graph = model.get_graph();
+    let initializers: HashMap<&str, &TensorProto> = graph
+        .get_initializer()
+        .iter()
+        .map(|tensor| (tensor.get_name(), tensor))
+        .collect();
+    let mut tensors = HashMap::new();
+    tensors.insert(graph.get_input()[0].get_name().to_string(), input);
+
+    for node in graph.get_node() {
+        let computed: HashSet<&str> = node
+            .get_input()
+            .iter()
+            .map(String::as_str)
+            .filter(|name| tensors.contains_key(*name))
+            .collect();
+        let operands: Vec<&TensorProto> = node
+            .get_input()
+            .iter()
+            .map(|name| {
+                tensors
+                    .get(name)
+                    .or_else(|| initializers.get(name.as_str()).copied())
+                    .unwrap_or_else(|| panic!("{} isn't computed", name))
+            })
+            .collect();
+        let (data, parameters) = (operands[0], operands[1..].to_vec());
+
+        let output = match node.get_op_type() {
+            "Add" => {
+                let (inputs, initializers): (Vec<_>, Vec<_>) = node
+                    .get_input()
+                    .iter()
+                    .zip(&operands)
+                    .partition(|(name, _)| computed.contains(name.as_str()));
+                let inputs = inputs.into_iter().map(|(_, &tensor)| tensor).collect();
+                let initializers = initializers
+                    .into_iter()
+                    .map(|(_, &tensor)| tensor)
+                    .collect();
+                add(&inputs, Some(&initializers), node)
+            }
+            "BatchNormalization" => batch_normalization(data, &parameters, node),
+            "Conv" => conv(data, &parameters, node),
+            "Flatten" => flatten(data, node),
+            "Gemm" => gemm(&operands, None, node),
+            "GlobalAveragePool" => global_average_pool(data, node),
+            "Identity" => identity(&operands, node),
+            "MatMul" => matmul(&operands, None, node),
+            "MaxPool" => maxpool(data, node).map(|mut outputs| outputs.remove(0)),
+            "Relu" => relu(data, node),
+            "Reshape" => reshape(&operands, node),
+            op_type => panic!("{} isn't supported by the test", op_type),
+        }
+        .unwrap();
+        tensors.insert(node.get_output()[0].clone(), output);
+    }
+    tensors
+}
+
+fn bits(tensor: &TensorProto) -> (Vec<usize>, Vec<u32>) {
+    let values = tensor_proto_to_ndarray::<f32>(tensor).unwrap();
+    let bits = values.iter().map(|value| value.to_bits()).collect();
+    (values.shape().to_vec(), bits)
+}
+
+fn tensor_bits(tensor: &Tensor) -> (Vec<usize>, Vec<u32>) {
+    let values: ArrayD<f32> = tensor.to_ndarray::<f32>().unwrap();
+    let bits = values.iter().map(|value| value.to_bits()).collect();
+    (values.shape().to_vec(), bits)
+}
+
+fn random_input(name: &str) -> TensorProto {
+    let mut rng = StdRng::seed_from_u64(0);
+    ndarray_to_tensor_proto::<f32>(random_tensor(&mut rng, &[1, 3, 16, 16]), name).unwrap()
+}
+
+#[test]
+fn outputs_are_bit_identical() {
+    let model = residual_model();
+    let input = random_input("x");
+    let expected = run_protos(&model, input.clone());
+
+    // The second run checks that the first one overwrote no initializer.
+    let session = Session::from_model(model).unwrap();
+    for _ in 0..2 {
+        let outputs = session
+            .run_with_inputs(vec![Tensor::try_from(&input).unwrap()])
+            .unwrap();
+        assert_eq!(outputs[0].name(), "y");
+        assert_eq!(tensor_bits(&outputs[0]), bits(&expected["y"]));
+        assert_eq!(outputs[1].name(), "logits");
+        assert_eq!(tensor_bits(&outputs[1]), bits(&expected["logits"]));
+    }
+}
+
+#[test]
+fn intermediates_are_not_overwritten() {
+    let model = residual_model();
+    let input = random_input("x");
+    let expected = run_protos(&model, input.clone());
+
+    let tensors = run_with_intermediates(&model, vec![input], &RunOptions::default()).unwrap();
+    for (name, tensor) in &tensors {
+        assert_eq!(tensor.get_name(), name);
+        assert_eq!(bits(tensor), bits(&expected[name]), "{}", name);
+    }
+
+    // The operands of the Relu, BatchNormalization and Add nodes, which overwrite them in a
+    // `Session`. The inputs of the forwarding nodes are moved to their outputs.
+    let overwritten = [
+        "stem_conv",
+        "stem_norm",
+        "block_conv1",
+        "block_norm1",
+        "block_conv2",
+        "block_norm2",
+        "residual",
+        "block_out",
+        "shifted",
+        "head",
+    ];
+    for name in overwritten {
+        assert!(tensors.contains_key(name), "{} isn't kept", name);
+    }
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn resnet_is_bit_identical() {
+    common::require_model(RESNET);
+    let model = OnnxParser::load_model(RESNET.to_string()).unwrap();
+    let input = OnnxParser::load_tensor_proto(RESNET_INPUT.to_string()).unwrap();
+    let output_name = model.get_graph().get_output()[0].get_name().to_string();
+    let expected = run_protos(&model, input.clone());
+
+    let session = Session::from_model(model).unwrap();
+    let output = session.run(Tensor::try_from(&input).unwrap()).unwrap();
+    assert_eq!(tensor_bits(&output), bits(&expected[&output_name]));
+}
@@ -0,0 +1,179 @@
+//! Runs Slice through the executor, with the indices as inputs (opset 10 and later) and as
+//! attributes (before opset 10):
+//!
+//! ```text
+//! cargo test --test slice
+//! ```
+//!
+//! The input is a [3, 4] matrix holding `4 * row + column`, so that every value tells its
+//! position in the input.
+
+use ndarray::{arr1, arr2, Array, Array2};
+use onnx_rustime_lib::backend::helper::{make_node, OnnxError};
+use onnx_rustime_lib::ops::slice;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+fn x() -> Array2<f32> {
+    Array::range(0.0, 12.0, 1.0).into_shape((3, 4)).unwrap()
+}
+
+/// A Slice of `x()` with the given inputs after `data` (opset 13).
+fn sliced(starts: &[i64], ends: &[i64], axes: &[i64], steps: &[i64]) -> OpTest {
+    let mut test = OpTest::new("Slice")
+        .opset(13)
+        .input(x())
+        .input(arr1(starts))
+        .input(arr1(ends));
+    if !axes.is_empty() {
+        test = test.input(arr1(axes));
+    }
+    if !steps.is_empty() {
+        test = test.input(arr1(steps));
+    }
+    test
+}
+
+#[test]
+fn positive_indices() {
+    sliced(&[1], &[3], &[1], &[])
+        .expect(arr2(&[[1.0f32, 2.0], [5.0, 6.0], [9.0, 10.0]]))
+        .check();
+    sliced(&[0, 1], &[2, 3], &[0, 1], &[1, 1])
+        .expect(arr2(&[[1.0f32, 2.0], [5.0, 6.0]]))
+        .check();
+}
+
+#[test]
+fn negative_indices() {
+    // The last two columns of the last row, and the negative axis of the columns.
+    sliced(&[-1, -2], &[3, 4], &[0, -1], &[])
+        .expect(arr2(&[[10.0f32, 11.0]]))
+        .check();
+    sliced(&[0], &[-1], &[1], &[])
+        .expect(arr2(&[
+            [0.0f32, 1.0, 2.0],
+            [4.0, 5.0, 6.0],
+            [8.0, 9.0, 10.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn steps() {
+    sliced(&[0], &[4], &[1], &[2])
+        .expect(arr2(&[[0.0f32, 2.0], [4.0, 6.0], [8.0, 10.0]]))
+        .check();
+    // Reverses the rows.
+    sliced(&[-1], &[i64::MIN], &[0], &[-1])
+        .expect(arr2(&[
+            [8.0f32, 9.0, 10.0, 11.0],
+            [4.0, 5.0, 6.0, 7.0],
+            [0.0, 1.0, 2.0, 3.0],
+        ]))
+        .check();
+    // Backwards from the column 3 down to the column 0, excluded, every 2.
+    sliced(&[3], &[0], &[1], &[-2])
+        .expect(arr2(&[[3.0f32, 1.0], [7.0, 5.0], [11.0, 9.0]]))
+        .check();
+}
+
+#[test]
+fn axes_omitted() {
+    // The starts and ends apply to the first axes.
+    sliced(&[1, 2], &[2, 4], &[], &[])
+        .expect(arr2(&[[6.0f32, 7.0]]))
+        .check();
+
+    // The steps can be given without the axes.
+    let tensor = |values: &[i64], name: &str| {
+        ndarray_to_tensor_proto::<i64>(arr1(values).into_dyn(), name).unwrap()
+    };
+    let data = ndarray_to_tensor_proto::<f32>(x().into_dyn(), "data").unwrap();
+    let (starts, ends, steps) = (
+        tensor(&[0, 3], "starts"),
+        tensor(&[3, 0], "ends"),
+        tensor(&[2, -1], "steps"),
+    );
+    let node = make_node(
+        "Slice",
+        vec!["data", "starts", "ends", "", "steps"],
+        vec!["y"],
+        None,
+        None,
+        None,
+        vec![],
+    );
+    let output = slice(
+        &[Some(&data), Some(&starts), Some(&ends), None, Some(&steps)],
+        &node,
+    )
+    .unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&output).unwrap(),
+        arr2(&[[3.0f32, 2.0, 1.0], [11.0, 10.0, 9.0]]).into_dyn()
+    );
+}
+
+#[test]
+fn indices_beyond_the_axis_are_clamped() {
+    sliced(&[1], &[i64::MAX], &[1], &[])
+        .expect(arr2(&[
+            [1.0f32, 2.0, 3.0],
+            [5.0, 6.0, 7.0],
+            [9.0, 10.0, 11.0],
+        ]))
+        .check();
+    sliced(&[-100], &[100], &[0], &[]).expect(x()).check();
+    // A start past the end gives an empty slice.
+    sliced(&[10], &[20], &[1], &[])
+        .expect(Array2::<f32>::zeros((3, 0)))
+        .check();
+    // Walking backwards, a start before the first element is clamped to it.
+    sliced(&[-100], &[-5], &[0], &[-1])
+        .expect(arr2(&[[0.0f32, 1.0, 2.0, 3.0]]))
+        .check();
+    sliced(&[i64::MIN], &[i64::MIN], &[1], &[-2])
+        .expect(arr2(&[[0.0f32], [4.0], [8.0]]))
+        .check();
+}
+
+#[test]
+fn attributes_before_opset_10() {
+    OpTest::new("Slice")
+        .opset(9)
+        .input(x())
+        .attribute("starts", vec![1i64, -3])
+        .attribute("ends", vec![i64::MAX, 3])
+        .attribute("axes", vec![0i64, 1])
+        .expect(arr2(&[[5.0f32, 6.0], [9.0, 10.0]]))
+        .check();
+}
+
+#[test]
+fn invalid_indices() {
+    let error = sliced(&[0], &[2], &[1], &[0])
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("can't be 0"), "{}", error);
+    let error = sliced(&[0, 0], &[2, 2], &[1, -1], &[])
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("repeated"), "{}", error);
+    let error = sliced(&[0], &[2], &[2], &[]).expect(x()).run().unwrap_err();
+    assert!(error.contains("out of range"), "{}", error);
+    let error = sliced(&[0, 0], &[2], &[], &[])
+        .expect(x())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("same length"), "{}", error);
+
+    let data = ndarray_to_tensor_proto::<f32>(x().into_dyn(), "data").unwrap();
+    let node = make_node("Slice", vec!["data"], vec!["y"], None, None, None, vec![]);
+    assert!(matches!(
+        slice(&[Some(&data)], &node),
+        Err(OnnxError::AttributeNotFound(_))
+    ));
+}
@@ -0,0 +1,156 @@
+//! Runs Softmax through the executor with the semantics of both operator sets:
+//!
+//! ```text
+//! cargo test --test softmax
+//! ```
+//!
+//! Before opset 13 the input is coerced to 2D around `axis` (1 by default) and every row is
+//! normalized; since opset 13 only the values along `axis` (-1 by default) are. Both agree on the
+//! last axis.
+
+use ndarray::{arr2, arr3, Array3};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+fn x() -> Array3<f32> {
+    arr3(&[
+        [
+            [-2.5f32, 1.0, -1.0, 2.5],
+            [0.5, -1.5, 2.0, 0.0],
+            [-2.0, 1.5, -0.5, -2.5],
+        ],
+        [
+            [1.0, -1.0, 2.5, 0.5],
+            [-1.5, 2.0, 0.0, -2.0],
+            [1.5, -0.5, -2.5, 1.0],
+        ],
+    ])
+}
+
+/// Normalized along the last axis, or over the last axis coerced to 2D.
+fn last_axis() -> Array3<f32> {
+    arr3(&[
+        [
+            [0.005347299f32, 0.17707823, 0.023964932, 0.79360956],
+            [0.16067986, 0.021745656, 0.7201172, 0.09745727],
+            [0.025507815, 0.84470284, 0.1143181, 0.015471272],
+        ],
+        [
+            [0.16067986, 0.021745656, 0.7201172, 0.09745727],
+            [0.025507815, 0.84470284, 0.1143181, 0.015471272],
+            [0.5681232, 0.076887116, 0.01040554, 0.34458414],
+        ],
+    ])
+}
+
+fn softmax(opset: i64, axis: Option<i64>, expected: Array3<f32>) {
+    let test = OpTest::new("Softmax").opset(opset).input(x());
+    match axis {
+        Some(axis) => test.attribute("axis", axis),
+        None => test,
+    }
+    .expect(expected)
+    .check();
+}
+
+#[test]
+fn coerced_to_2d_before_opset_13() {
+    // The whole tensor is a single row.
+    let axis_0 = arr3(&[
+        [
+            [0.0012732126f32, 0.04216301, 0.005706143, 0.1889615],
+            [0.025573159, 0.0034609507, 0.11461095, 0.015510905],
+            [0.0020991727, 0.06951505, 0.009407839, 0.0012732126],
+        ],
+        [
+            [0.04216301, 0.005706143, 0.1889615, 0.025573159],
+            [0.0034609507, 0.11461095, 0.015510905, 0.0020991727],
+            [0.06951505, 0.009407839, 0.0012732126, 0.04216301],
+        ],
+    ]);
+    // Every [3, 4] matrix is a row.
+    let axis_1 = arr3(&[
+        [
+            [0.0026549872f32, 0.0879211, 0.011898827, 0.394035],
+            [0.05332684, 0.007217003, 0.23899432, 0.032344364],
+            [0.0043773334, 0.14495738, 0.019617848, 0.0026549872],
+        ],
+        [
+            [0.0810134, 0.010963972, 0.36307687, 0.04913711],
+            [0.0066499845, 0.22021724, 0.029803164, 0.00403342],
+            [0.13356851, 0.018076533, 0.0024463926, 0.0810134],
+        ],
+    ]);
+
+    softmax(11, Some(0), axis_0);
+    softmax(11, Some(1), axis_1.clone());
+    softmax(11, Some(-1), last_axis());
+    softmax(11, None, axis_1.clone());
+    // The models built by the tests import opset 3.
+    op_test!("Softmax", inputs: [x()], attrs: {}, expect: axis_1);
+}
+
+#[test]
+fn along_a_single_axis_since_opset_13() {
+    let axis_0 = arr3(&[
+        [
+            [0.02931223f32, 0.8807971, 0.02931223, 0.8807971],
+            [0.8807971, 0.02931223, 0.8807971, 0.8807971],
+            [0.02931223, 0.8807971, 0.8807971, 0.02931223],
+        ],
+        [
+            [0.97068775, 0.11920292, 0.97068775, 0.11920292],
+            [0.11920292, 0.97068775, 0.11920292, 0.11920292],
+            [0.97068775, 0.11920292, 0.11920292, 0.97068775],
+        ],
+    ]);
+    let axis_1 = arr3(&[
+        [
+            [0.04398648f32, 0.36619222, 0.04398648, 0.91842294],
+            [0.88349205, 0.030058887, 0.88349205, 0.075388744],
+            [0.07252145, 0.6037489, 0.07252145, 0.006188285],
+        ],
+        [
+            [0.36619222, 0.04398648, 0.91842294, 0.36619222],
+            [0.030058887, 0.88349205, 0.075388744, 0.030058887],
+            [0.6037489, 0.07252145, 0.006188285, 0.6037489],
+        ],
+    ]);
+
+    softmax(13, Some(0), axis_0);
+    softmax(13, Some(1), axis_1.clone());
+    softmax(13, Some(-2), axis_1);
+    softmax(13, Some(-1), last_axis());
+    softmax(13, None, last_axis());
+}
+
+#[test]
+fn large_logits() {
+    // exp(1000) overflows: the maximum of every row is subtracted first.
+    for opset in [11, 13] {
+        OpTest::new("Softmax")
+            .opset(opset)
+            .input(arr2(&[
+                [1000.0f32, 1001.0, 1002.0],
+                [-1002.0, -1001.0, -1000.0],
+            ]))
+            .expect(arr2(&[
+                [0.09003057f32, 0.24472847, 0.66524096],
+                [0.09003057, 0.24472847, 0.66524096],
+            ]))
+            .check();
+    }
+}
+
+#[test]
+fn axis_out_of_range() {
+    for (opset, axis) in [(11, 3i64), (13, 3), (13, -4)] {
+        let result = OpTest::new("Softmax")
+            .opset(opset)
+            .input(x())
+            .attribute("axis", axis)
+            .expect(x())
+            .run();
+        assert!(result.unwrap_err().contains("out of range"));
+    }
+}
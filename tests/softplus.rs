@@ -0,0 +1,66 @@
+//! Runs Softplus, Softsign and Mish through the executor:
+//!
+//! ```text
+//! cargo test --test softplus
+//! ```
+//!
+//! Softplus (and Mish, built on it) must stay finite on large inputs, where `exp(x)` overflows.
+
+use ndarray::{arr1, Array1};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::single_node_model;
+use std::f32::consts::LN_2;
+
+fn x() -> Array1<f32> {
+    arr1(&[-2.0, -0.5, 0.0, 0.5, 2.0])
+}
+
+#[test]
+fn small_values() {
+    op_test!("Softplus",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[0.12692801f32, 0.474077, LN_2, 0.974077, 2.126928]));
+    op_test!("Softsign",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[-2.0f32 / 3.0, -1.0 / 3.0, 0.0, 1.0 / 3.0, 2.0 / 3.0]));
+    op_test!("Mish",
+             inputs: [x()],
+             attrs: {},
+             expect: arr1(&[-0.2525015f32, -0.22074377, 0.0, 0.3752452, 1.943959]));
+}
+
+#[test]
+fn large_values_stay_finite() {
+    for op_type in ["Softplus", "Softsign", "Mish"] {
+        let model = single_node_model(op_type, &["x"], &["y"], vec![]);
+        let input = arr1(&[-100.0f32, 100.0]).into_dyn();
+        let inputs = vec![ndarray_to_tensor_proto::<f32>(input, "x").unwrap()];
+        let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+        let output = tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap();
+        assert!(
+            output.iter().all(|value| value.is_finite()),
+            "{}: {}",
+            op_type,
+            output
+        );
+    }
+
+    // log(1 + exp(100)) is 100 in f32, and log(1 + exp(-100)) about 3.7e-44.
+    op_test!("Softplus",
+             inputs: [arr1(&[-100.0f32, 100.0])],
+             attrs: {},
+             expect: arr1(&[0.0f32, 100.0]));
+    op_test!("Mish",
+             inputs: [arr1(&[-100.0f32, 100.0])],
+             attrs: {},
+             expect: arr1(&[0.0f32, 100.0]));
+    op_test!("Softsign",
+             inputs: [arr1(&[-100.0f32, 100.0])],
+             attrs: {},
+             expect: arr1(&[-100.0f32 / 101.0, 100.0 / 101.0]));
+}
@@ -0,0 +1,194 @@
+//! Runs Split through the executor, with the sizes of the parts given in every way the operator
+//! sets allow:
+//!
+//! ```text
+//! cargo test --test split
+//! ```
+//!
+//! The input is a [2, 6] (or [2, 5]) matrix whose values tell their column, so that every part
+//! shows which columns it holds.
+
+use ndarray::{arr1, arr2, Array, Array2};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, Attribute,
+};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{TensorProto, ValueInfoProto};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+/// A [2, columns] matrix of the values `10 * row + column`.
+fn x(columns: usize) -> Array2<f32> {
+    Array::from_shape_fn((2, columns), |(row, column)| (10 * row + column) as f32)
+}
+
+/// The columns from `start` to `end` of `x(columns)`.
+fn columns(columns: usize, start: usize, end: usize) -> Array2<f32> {
+    x(columns).slice_move(ndarray::s![.., start..end])
+}
+
+#[test]
+fn equal_parts() {
+    op_test!("Split",
+             inputs: [x(6)],
+             attrs: { axis: 1i64 },
+             expect: [columns(6, 0, 2), columns(6, 2, 4), columns(6, 4, 6)]);
+    // Along the first axis by default.
+    op_test!("Split",
+             inputs: [x(6)],
+             attrs: {},
+             expect: [arr2(&[[0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0]]),
+                      arr2(&[[10.0f32, 11.0, 12.0, 13.0, 14.0, 15.0]])]);
+}
+
+#[test]
+fn split_attribute() {
+    OpTest::new("Split")
+        .opset(11)
+        .input(x(6))
+        .attribute("axis", -1i64)
+        .attribute("split", vec![1i64, 2, 3])
+        .expect(columns(6, 0, 1))
+        .expect(columns(6, 1, 3))
+        .expect(columns(6, 3, 6))
+        .check();
+}
+
+#[test]
+fn split_input() {
+    OpTest::new("Split")
+        .opset(13)
+        .input(x(6))
+        .input(arr1(&[4i64, 2]))
+        .attribute("axis", 1i64)
+        .expect(columns(6, 0, 4))
+        .expect(columns(6, 4, 6))
+        .check();
+}
+
+#[test]
+fn num_outputs_with_a_smaller_last_part() {
+    // ceil(5 / 3) = 2 columns in every part but the last one.
+    OpTest::new("Split")
+        .opset(18)
+        .input(x(5))
+        .attribute("axis", -1i64)
+        .attribute("num_outputs", 3i64)
+        .expect(columns(5, 0, 2))
+        .expect(columns(5, 2, 4))
+        .expect(columns(5, 4, 5))
+        .check();
+    // Uneven sizes can be given as well.
+    OpTest::new("Split")
+        .opset(13)
+        .input(x(5))
+        .input(arr1(&[3i64, 2]))
+        .attribute("axis", 1i64)
+        .expect(columns(5, 0, 3))
+        .expect(columns(5, 3, 5))
+        .check();
+}
+
+#[test]
+fn invalid_sizes() {
+    let result = OpTest::new("Split")
+        .opset(13)
+        .input(x(6))
+        .input(arr1(&[2i64, 2]))
+        .attribute("axis", 1i64)
+        .expect(columns(6, 0, 2))
+        .expect(columns(6, 2, 4))
+        .run();
+    assert!(result.unwrap_err().contains("don't sum to the length 6"));
+
+    // Three sizes for two outputs.
+    let result = OpTest::new("Split")
+        .opset(13)
+        .input(x(6))
+        .input(arr1(&[2i64, 2, 2]))
+        .attribute("axis", 1i64)
+        .expect(columns(6, 0, 2))
+        .expect(columns(6, 2, 4))
+        .run();
+    assert!(result
+        .unwrap_err()
+        .contains("3 parts, but the node has 2 outputs"));
+
+    // 5 columns can't be split equally in 2.
+    let result = OpTest::new("Split")
+        .input(x(5))
+        .attribute("axis", 1i64)
+        .expect(columns(5, 0, 2))
+        .expect(columns(5, 2, 5))
+        .run();
+    assert!(result.unwrap_err().contains("equally into 2 outputs"));
+}
+
+#[test]
+fn every_output_is_bound() {
+    // Split into three parts, concatenated back in the reverse order.
+    let value_info = |name: &str| {
+        let mut value_info = ValueInfoProto::new();
+        value_info.set_name(name.to_string());
+        value_info
+    };
+    let nodes = vec![
+        make_node(
+            "Split",
+            vec!["x"],
+            vec!["a", "b", "c"],
+            None,
+            None,
+            None,
+            vec![make_attribute("axis", Attribute::<String>::Int(1))],
+        ),
+        make_node(
+            "Concat",
+            vec!["c", "b", "a"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![make_attribute("axis", Attribute::<String>::Int(1))],
+        ),
+    ];
+    let graph = make_graph(
+        nodes,
+        "split_and_concat",
+        vec![value_info("x")],
+        vec![value_info("y"), value_info("b")],
+        Vec::<TensorProto>::new(),
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let inputs = vec![ndarray_to_tensor_proto::<f32>(x(6).into_dyn(), "x").unwrap()];
+    let outputs = run_with_inputs(&model, inputs, &RunOptions::default()).unwrap();
+    let output = |name: &str| {
+        let tensor = outputs
+            .iter()
+            .find(|output| output.get_name() == name)
+            .unwrap();
+        tensor_proto_to_ndarray::<f32>(tensor).unwrap()
+    };
+    assert_eq!(
+        output("y"),
+        arr2(&[
+            [4.0f32, 5.0, 2.0, 3.0, 0.0, 1.0],
+            [14.0, 15.0, 12.0, 13.0, 10.0, 11.0]
+        ])
+        .into_dyn()
+    );
+    assert_eq!(output("b"), columns(6, 2, 4).into_dyn());
+}
@@ -0,0 +1,91 @@
+//! Runs Squeeze and Unsqueeze through the executor, with the axes of both operator sets:
+//!
+//! ```text
+//! cargo test --test squeeze
+//! ```
+//!
+//! The axes are an attribute before opset 13 and an input since. Only the dimensions change, so
+//! tensors of any element type are accepted.
+
+use ndarray::{arr1, arr2, Array, ArrayD, IxDyn};
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::testing::OpTest;
+
+/// A [1, 3, 1, 2] tensor of the values from 0 to 5.
+fn x() -> ArrayD<f32> {
+    values(&[1, 3, 1, 2])
+}
+
+fn values(shape: &[usize]) -> ArrayD<f32> {
+    Array::range(0.0, 6.0, 1.0)
+        .into_shape(IxDyn(shape))
+        .unwrap()
+}
+
+#[test]
+fn attribute_axes() {
+    OpTest::new("Squeeze")
+        .opset(11)
+        .input(x())
+        .attribute("axes", vec![0i64, 2])
+        .expect(values(&[3, 2]))
+        .check();
+    OpTest::new("Unsqueeze")
+        .opset(11)
+        .input(values(&[3, 2]))
+        .attribute("axes", vec![0i64, 2])
+        .expect(x())
+        .check();
+}
+
+#[test]
+fn input_axes() {
+    op_test!("Squeeze", inputs: [x(), arr1(&[2i64])], attrs: {}, expect: values(&[1, 3, 2]));
+    op_test!("Unsqueeze", inputs: [values(&[3, 2]), arr1(&[1i64, 3])], attrs: {},
+             expect: values(&[3, 1, 2, 1]));
+}
+
+#[test]
+fn negative_axes() {
+    // -2 is the third dimension of the input of Squeeze, and of the output of Unsqueeze.
+    op_test!("Squeeze", inputs: [x(), arr1(&[-2i64, -4])], attrs: {}, expect: values(&[3, 2]));
+    op_test!("Unsqueeze", inputs: [values(&[3, 2]), arr1(&[-2i64, 0])], attrs: {}, expect: x());
+}
+
+#[test]
+fn squeeze_every_dimension_of_size_1() {
+    OpTest::new("Squeeze")
+        .opset(13)
+        .input(x())
+        .expect(values(&[3, 2]))
+        .check();
+    OpTest::new("Squeeze")
+        .opset(11)
+        .input(values(&[1, 6, 1]))
+        .expect(values(&[6]))
+        .check();
+}
+
+#[test]
+fn any_element_type() {
+    op_test!("Unsqueeze", inputs: [arr1(&[3i64, 4]), arr1(&[0i64])], attrs: {},
+             expect: arr2(&[[3i64, 4]]));
+}
+
+#[test]
+fn invalid_axes() {
+    let error = |op_type: &str, axes: &[i64]| {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(x())
+            .input(arr1(axes))
+            .expect(x())
+            .run()
+            .unwrap_err()
+    };
+
+    assert!(error("Squeeze", &[1]).contains("Cannot squeeze axis 1 of size 3"));
+    assert!(error("Squeeze", &[4]).contains("out of range"));
+    assert!(error("Squeeze", &[0, -4]).contains("repeated"));
+    assert!(error("Unsqueeze", &[-6]).contains("out of range"));
+}
@@ -0,0 +1,259 @@
+//! Converts `Tensor`s from and to arrays and `TensorProto`s, and previews their values:
+//!
+//! ```text
+//! cargo test --test tensor
+//! ```
+//!
+//! Every element type goes through a round-trip; the JSON round-trips are checked with the
+//! `serde` feature.
+
+use half::f16;
+use ndarray::{arr1, arr2, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension, OnnxError,
+};
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::backend::tensor::{DType, Tensor, TensorElement};
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use protobuf::ProtobufEnum;
+
+fn array<T>(shape: &[usize], values: Vec<T>) -> ArrayD<T> {
+    ArrayD::from_shape_vec(IxDyn(shape), values).unwrap()
+}
+
+/// Converts the values to a tensor, a `TensorProto` and back, checking the type on the way.
+fn round_trip<T: TensorElement + PartialEq>(values: ArrayD<T>, dtype: DType, display: &str) {
+    let tensor = Tensor::from_ndarray(values.clone()).with_name("t");
+    assert_eq!(tensor.dtype(), dtype);
+    assert_eq!(tensor.dtype().to_string(), display);
+    assert_eq!(tensor.shape(), values.shape());
+    assert_eq!(tensor.as_slice::<T>(), values.as_slice());
+
+    let proto = tensor.to_proto().unwrap();
+    assert_eq!(proto.get_name(), "t");
+    assert_eq!(proto.get_data_type(), dtype.to_proto().value());
+
+    let back = Tensor::from_proto(&proto).unwrap();
+    assert_eq!(back, tensor);
+    assert_eq!(back.to_ndarray::<T>().unwrap(), values);
+    assert_eq!(back.into_ndarray::<T>().unwrap(), values);
+}
+
+#[test]
+fn round_trips() {
+    let shape = [2, 3];
+    round_trip(
+        array(&shape, vec![0.5f32, -1.0, 2.0, 3.5, 0.0, 1e-8]),
+        DType::Float,
+        "float32",
+    );
+    let halves = [0.5f32, -1.0, 2.0, 3.5, 0.0, 65504.0].map(f16::from_f32);
+    round_trip(array(&shape, halves.to_vec()), DType::Float16, "float16");
+    round_trip(
+        array(&shape, vec![0.1f64, -1e300, 2.0, 3.5, 0.0, 1e-300]),
+        DType::Double,
+        "float64",
+    );
+    round_trip(
+        array(&shape, vec![-128i8, -1, 0, 1, 2, 127]),
+        DType::Int8,
+        "int8",
+    );
+    round_trip(
+        array(&shape, vec![0u8, 1, 2, 127, 128, 255]),
+        DType::UInt8,
+        "uint8",
+    );
+    round_trip(
+        array(&shape, vec![i32::MIN, -1, 0, 1, 2, i32::MAX]),
+        DType::Int32,
+        "int32",
+    );
+    round_trip(
+        array(&shape, vec![i64::MIN, -1, 0, 1, 2, i64::MAX]),
+        DType::Int64,
+        "int64",
+    );
+    round_trip(
+        array(&shape, vec![true, false, false, true, true, false]),
+        DType::Bool,
+        "bool",
+    );
+    let strings = vec!["a", "", "bc", "d e", "f", "ü"];
+    round_trip(
+        array(&shape, strings.into_iter().map(String::from).collect()),
+        DType::String,
+        "string",
+    );
+    // Scalars and empty tensors.
+    round_trip(array(&[], vec![1.5f64]), DType::Double, "float64");
+    round_trip(array(&[0, 3], Vec::<bool>::new()), DType::Bool, "bool");
+}
+
+#[test]
+fn protos_of_the_runtime() {
+    // The tensors read the protos written by the kernels and by the parser.
+    let values = arr2(&[[1u8, 2], [3, 4]]).into_dyn();
+    let proto = ndarray_to_tensor_proto::<u8>(values.clone(), "bytes").unwrap();
+    let tensor = Tensor::try_from(&proto).unwrap();
+    assert_eq!(tensor.name(), "bytes");
+    assert_eq!(tensor.to_ndarray::<u8>().unwrap(), values);
+
+    let flags = Tensor::from_ndarray(arr1(&[true, false]).into_dyn());
+    let proto = TensorProto::try_from(flags).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<bool>(&proto).unwrap(),
+        arr1(&[true, false]).into_dyn()
+    );
+
+    let mut proto = ndarray_to_tensor_proto::<u8>(values, "shorts").unwrap();
+    proto.set_data_type(TensorProto_DataType::UINT16.value());
+    match Tensor::from_proto(&proto) {
+        Err(OnnxError::ConversionError(message)) => {
+            assert_eq!(message, "Tensor 'shorts' has unsupported data type UINT16")
+        }
+        other => panic!("Expected a conversion error, got {:?}", other),
+    }
+}
+
+#[test]
+fn conversions() {
+    // FLOAT16 values can be read as f32, the other types only as themselves.
+    let halves = Tensor::from_ndarray(arr1(&[f16::from_f32(0.5), f16::from_f32(-2.0)]).into_dyn());
+    assert_eq!(
+        halves.to_ndarray::<f32>().unwrap(),
+        arr1(&[0.5f32, -2.0]).into_dyn()
+    );
+
+    let doubles = Tensor::from_ndarray(arr1(&[0.5f64]).into_dyn()).with_name("d");
+    match doubles.to_ndarray::<f32>() {
+        Err(OnnxError::ConversionError(message)) => {
+            assert_eq!(message, "Tensor 'd' holds float64 values, not float32")
+        }
+        other => panic!("Expected a conversion error, got {:?}", other),
+    }
+    assert!(doubles.as_slice::<f32>().is_none());
+    assert!(doubles.into_ndarray::<i8>().is_err());
+
+    // The values are stored in row-major order.
+    let transposed = arr2(&[[1i8, 2, 3], [4, 5, 6]]).reversed_axes().into_dyn();
+    let tensor = Tensor::from_ndarray(transposed.clone());
+    assert_eq!(tensor.as_slice::<i8>(), Some(&[1i8, 4, 2, 5, 3, 6][..]));
+    assert_eq!(tensor.into_ndarray::<i8>().unwrap(), transposed);
+}
+
+#[test]
+fn display_and_debug() {
+    let short = Tensor::from_ndarray(arr1(&[true, false, true]).into_dyn()).with_name("mask");
+    assert_eq!(short.to_string(), "bool[3] 'mask': [true, false, true]");
+    assert_eq!(
+        format!("{:?}", short),
+        "Tensor { name: \"mask\", dtype: Bool, shape: [3], values: [true, false, true] }"
+    );
+
+    // The first 8 values, then the number of the others; no name if unnamed.
+    let long = Tensor::from_ndarray(ArrayD::from_shape_fn(IxDyn(&[2, 5]), |index| {
+        (index[0] * 5 + index[1]) as u8
+    }));
+    assert_eq!(
+        long.to_string(),
+        "uint8[2, 5]: [0, 1, 2, 3, 4, 5, 6, 7, ... 2 more]"
+    );
+    assert_eq!(
+        format!("{:?}", long),
+        "Tensor { name: \"\", dtype: UInt8, shape: [2, 5], \
+         values: [0, 1, 2, 3, 4, 5, 6, 7, ... 2 more] }"
+    );
+
+    let doubles = Tensor::from_ndarray(arr1(&[0.1f64, -2.0]).into_dyn()).with_name("x");
+    assert_eq!(doubles.to_string(), "float64[2] 'x': [0.1, -2.0]");
+    let bytes = Tensor::from_ndarray(arr1(&[-1i8]).into_dyn());
+    assert_eq!(bytes.to_string(), "int8[1]: [-1]");
+    let empty = Tensor::from_ndarray(ArrayD::<f32>::zeros(IxDyn(&[0])));
+    assert_eq!(empty.to_string(), "float32[0]: []");
+}
+
+#[test]
+fn session_outputs() {
+    // `x > 0` gives a BOOL output.
+    let value_info = |name: &str, data_type| {
+        make_tensor_value_info(name, data_type, vec![Dimension::Value(3)], None)
+    };
+    let node = make_node(
+        "Greater",
+        vec!["x", "zero"],
+        vec!["y"],
+        None,
+        None,
+        None,
+        vec![],
+    );
+    let zero = ndarray_to_tensor_proto::<f32>(arr1(&[0.0]).into_dyn(), "zero").unwrap();
+    let graph = make_graph(
+        vec![node],
+        "positive",
+        vec![value_info("x", TensorProto_DataType::FLOAT)],
+        vec![value_info("y", TensorProto_DataType::BOOL)],
+        vec![zero],
+        None,
+    );
+    let model = make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    );
+    let session = Session::from_model(model).unwrap();
+
+    let x = Tensor::from_ndarray(arr1(&[-1.0f32, 0.0, 2.0]).into_dyn()).with_name("x");
+    let outputs = session.run_with_inputs(vec![x.clone()]).unwrap();
+    assert_eq!(outputs[0].name(), "y");
+    assert_eq!(outputs[0].dtype(), DType::Bool);
+    assert_eq!(
+        outputs[0].to_ndarray::<bool>().unwrap(),
+        arr1(&[false, false, true]).into_dyn()
+    );
+    assert_eq!(session.run(x).unwrap(), outputs[0]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_round_trips() {
+    let tensors = [
+        Tensor::from_ndarray(arr1(&[0.1f64, -1e300]).into_dyn()),
+        Tensor::from_ndarray(arr1(&[0.1f32, 2.0]).into_dyn()),
+        Tensor::from_ndarray(arr1(&[-128i8, 127]).into_dyn()),
+        Tensor::from_ndarray(arr1(&[0u8, 255]).into_dyn()),
+        Tensor::from_ndarray(arr1(&[true, false]).into_dyn()),
+    ];
+    for tensor in tensors {
+        let tensor = tensor.with_name("t");
+        let json = tensor.to_json().unwrap();
+        assert_eq!(Tensor::from_json(&json).unwrap(), tensor, "{}", json);
+    }
+
+    let doubles = Tensor::from_ndarray(arr1(&[0.1f64]).into_dyn()).with_name("d");
+    assert_eq!(
+        doubles.to_json().unwrap(),
+        r#"{"name":"d","dtype":"float64","shape":[1],"data":[0.1]}"#
+    );
+    let floats = Tensor::from_ndarray(arr1(&[0.1f32]).into_dyn()).with_name("f");
+    assert_eq!(
+        floats.to_json().unwrap(),
+        r#"{"name":"f","dtype":"float32","shape":[1],"data":[0.1]}"#
+    );
+    let infinite = Tensor::from_ndarray(arr1(&[f64::INFINITY]).into_dyn());
+    assert!(infinite.to_json().is_err());
+
+    let out_of_range = r#"{"dtype":"uint8","shape":[1],"data":[256]}"#;
+    assert!(Tensor::from_json(out_of_range).is_err());
+    let not_a_bool = r#"{"dtype":"bool","shape":[1],"data":[1]}"#;
+    assert!(Tensor::from_json(not_a_bool).is_err());
+}
@@ -0,0 +1,191 @@
+//! Compares tensors with `tensors_close`, in both comparison modes:
+//!
+//! ```text
+//! cargo test --test tensors_close
+//! ```
+
+use half::f16;
+use ndarray::{arr1, arr2, ArrayD};
+use onnx_rustime_lib::backend::helper::OnnxError;
+use onnx_rustime_lib::testing::{
+    assert_tensors_close, tensors_close, CloseElement, CloseOptions, CloseReport,
+};
+
+fn tensor(values: &[f32]) -> ArrayD<f32> {
+    arr1(values).into_dyn()
+}
+
+fn compare(actual: &[f32], expected: &[f32], options: CloseOptions) -> CloseReport {
+    tensors_close(&tensor(actual), &tensor(expected), options).unwrap()
+}
+
+/// The representable f32 `steps` values after `value`.
+fn next_f32(value: f32, steps: u32) -> f32 {
+    f32::from_bits(value.to_bits() + steps)
+}
+
+#[test]
+fn mismatched_shapes() {
+    let actual = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn();
+    let expected = arr2(&[[1.0f32, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn();
+
+    // The same number of elements isn't enough.
+    match tensors_close(&actual, &expected, CloseOptions::abs_rel(1.0, 1.0)) {
+        Err(OnnxError::ShapeMismatch { detail, .. }) => {
+            assert!(
+                detail.contains("[2, 3]") && detail.contains("[3, 2]"),
+                "{}",
+                detail
+            )
+        }
+        other => panic!("Expected a shape mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+#[should_panic(expected = "Tensors are not close")]
+fn assert_mismatched_shapes() {
+    assert_tensors_close(&tensor(&[1.0]), &tensor(&[1.0, 1.0]), 1.0, 1.0);
+}
+
+#[test]
+fn absolute_and_relative_tolerance() {
+    let expected = [1.0, 100.0, -2.0, 0.0];
+    let actual = [1.05, 100.5, -2.0, 0.01];
+
+    // 0.05 <= 0.01 + 0.01 * 100 for the second element, but not for the first.
+    let report = compare(&actual, &expected, CloseOptions::abs_rel(0.01, 0.01));
+    assert_eq!((report.elements, report.violations), (4, 1));
+    assert!(!report.is_close());
+    assert!((report.max_abs_diff - 0.5).abs() < 1e-6);
+    assert_eq!(report.max_rel_diff, f64::INFINITY);
+    assert_eq!(report.worst_index, Some(vec![0]));
+    let (worst_actual, worst_expected) = report.worst_values.unwrap();
+    assert_eq!((worst_actual as f32, worst_expected as f32), (1.05, 1.0));
+
+    let report = compare(&actual, &expected, CloseOptions::abs_rel(0.1, 0.01));
+    assert!(report.is_close());
+    // Without violations, the worst element is the one with the largest difference.
+    assert_eq!(report.worst_index, Some(vec![1]));
+
+    let message = compare(&actual, &expected, CloseOptions::abs_rel(0.01, 0.01)).to_string();
+    assert!(
+        message.starts_with("1 of 4 elements differ (atol 0.01, rtol 0.01)"),
+        "{}",
+        message
+    );
+    assert!(message.contains("worst at [0]"), "{}", message);
+}
+
+#[test]
+fn infinities() {
+    let options = CloseOptions::abs_rel(1e-6, 1e-6);
+    let report = compare(
+        &[f32::INFINITY, f32::NEG_INFINITY],
+        &[f32::INFINITY, f32::NEG_INFINITY],
+        options,
+    );
+    assert!(report.is_close());
+    assert_eq!(report.max_abs_diff, 0.0);
+
+    // An infinity is close to nothing else, however large the tolerance.
+    let report = compare(
+        &[f32::INFINITY, f32::MAX, 1.0],
+        &[f32::NEG_INFINITY, f32::INFINITY, 1.0],
+        CloseOptions::abs_rel(f64::MAX, 1.0),
+    );
+    assert_eq!(report.violations, 2);
+    // The differences with infinities aren't counted in the statistics.
+    assert_eq!(report.max_abs_diff, 0.0);
+    assert_eq!(report.max_ulp_diff, 0);
+}
+
+#[test]
+fn nan_mismatches() {
+    let actual = [1.0, f32::NAN, f32::NAN, 2.5];
+    let expected = [1.0, f32::NAN, 3.0, 2.0];
+
+    let report = compare(&actual, &expected, CloseOptions::abs_rel(1.0, 0.0));
+    assert_eq!(report.violations, 2);
+    // The NaN mismatches come before the numbers, however far apart.
+    assert_eq!(report.worst_index, Some(vec![1]));
+
+    let report = compare(
+        &actual,
+        &expected,
+        CloseOptions::abs_rel(1.0, 0.0).with_nan_equal(true),
+    );
+    assert_eq!(report.violations, 1);
+    assert_eq!(report.worst_index, Some(vec![2]));
+    let (worst_actual, worst_expected) = report.worst_values.unwrap();
+    assert!(worst_actual.is_nan());
+    assert_eq!(worst_expected, 3.0);
+
+    // Matching NaNs don't count as a difference either.
+    let report = compare(
+        &[f32::NAN, 1.0],
+        &[f32::NAN, 1.5],
+        CloseOptions::abs_rel(1.0, 0.0).with_nan_equal(true),
+    );
+    assert!(report.is_close());
+    assert_eq!(report.worst_index, Some(vec![1]));
+}
+
+#[test]
+fn ulp_distance() {
+    assert_eq!(1.0f32.ulp_distance(next_f32(1.0, 3)), 3);
+    assert_eq!(next_f32(1.0, 3).ulp_distance(1.0), 3);
+    assert_eq!(0.0f32.ulp_distance(-0.0), 0);
+    // The smallest subnormals are 1 ulp away from zero, on both sides.
+    let tiny = f32::from_bits(1);
+    assert_eq!(tiny.ulp_distance(-tiny), 2);
+    assert_eq!(f32::MAX.ulp_distance(f32::INFINITY), 1);
+
+    assert_eq!(1.0f64.ulp_distance(f64::from_bits(1.0f64.to_bits() + 7)), 7);
+    let one = f16::from_f32(1.0);
+    assert_eq!(one.ulp_distance(f16::from_bits(one.to_bits() + 2)), 2);
+    assert_eq!(
+        f16::MIN_POSITIVE_SUBNORMAL.ulp_distance(-f16::MIN_POSITIVE_SUBNORMAL),
+        2
+    );
+}
+
+#[test]
+fn ulp_mode() {
+    let expected = [1.0, 1e-30, -4.0];
+    let actual = [next_f32(1.0, 2), next_f32(1e-30, 4), -4.0];
+
+    let report = compare(&actual, &expected, CloseOptions::ulps(4));
+    assert!(report.is_close());
+    assert_eq!(report.max_ulp_diff, 4);
+    assert_eq!(report.worst_index, Some(vec![0]));
+
+    // Tiny values are far apart in ulps, though an absolute tolerance would accept them.
+    let report = compare(&actual, &expected, CloseOptions::ulps(2));
+    assert_eq!(report.violations, 1);
+    assert_eq!(report.worst_index, Some(vec![1]));
+    assert!(compare(&actual, &expected, CloseOptions::abs_rel(1e-6, 0.0)).is_close());
+    assert!(report
+        .to_string()
+        .starts_with("1 of 3 elements differ (max 2 ulps)"));
+
+    // Without nan_equal, NaN is not 0 ulps away from itself.
+    assert_eq!(
+        compare(&[f32::NAN], &[f32::NAN], CloseOptions::ulps(u64::MAX)).violations,
+        1
+    );
+    assert!(compare(
+        &[f32::NAN],
+        &[f32::NAN],
+        CloseOptions::ulps(0).with_nan_equal(true)
+    )
+    .is_close());
+}
+
+#[test]
+fn empty_tensors() {
+    let report = compare(&[], &[], CloseOptions::ulps(0));
+    assert!(report.is_close());
+    assert_eq!(report.elements, 0);
+    assert_eq!(report.worst_index, None);
+}
@@ -0,0 +1,81 @@
+//! Runs Tile through the executor:
+//!
+//! ```text
+//! cargo test --test tile
+//! ```
+//!
+//! A repeat of 0 gives a tensor without elements, which must survive the conversions to and from
+//! `TensorProto`.
+
+use ndarray::{arr1, arr2, Array2};
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::OpTest;
+
+fn matrix() -> Array2<f32> {
+    arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+}
+
+#[test]
+fn two_by_two() {
+    OpTest::new("Tile")
+        .opset(13)
+        .input(matrix())
+        .input(arr1(&[2i64, 2]))
+        .expect(arr2(&[
+            [1.0f32, 2.0, 3.0, 1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0, 4.0, 5.0, 6.0],
+            [1.0, 2.0, 3.0, 1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0, 4.0, 5.0, 6.0],
+        ]))
+        .check();
+}
+
+#[test]
+fn repeats_of_1() {
+    OpTest::new("Tile")
+        .opset(13)
+        .input(matrix())
+        .input(arr1(&[1i64, 1]))
+        .expect(matrix())
+        .check();
+    OpTest::new("Tile")
+        .opset(13)
+        .input(arr2(&[[7i64], [8]]))
+        .input(arr1(&[1i64, 3]))
+        .expect(arr2(&[[7i64, 7, 7], [8, 8, 8]]))
+        .check();
+}
+
+#[test]
+fn repeats_of_0() {
+    OpTest::new("Tile")
+        .opset(13)
+        .input(matrix())
+        .input(arr1(&[0i64, 2]))
+        .expect(Array2::<f32>::zeros((0, 6)))
+        .check();
+}
+
+#[test]
+fn empty_tensors_round_trip() {
+    let empty = Array2::<f32>::zeros((0, 3)).into_dyn();
+    let tensor = ndarray_to_tensor_proto::<f32>(empty.clone(), "empty").unwrap();
+    assert_eq!(tensor.get_dims(), &[0, 3]);
+    assert_eq!(tensor_proto_to_ndarray::<f32>(&tensor).unwrap(), empty);
+}
+
+#[test]
+fn one_repeat_per_axis() {
+    let error = OpTest::new("Tile")
+        .opset(13)
+        .input(matrix())
+        .input(arr1(&[2i64]))
+        .expect(matrix())
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Expected one repeat per axis of a tensor of shape [2, 3], got [2]"),
+        "{}",
+        error
+    );
+}
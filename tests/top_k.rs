@@ -0,0 +1,108 @@
+//! Runs TopK through the executor, on the cases of the ONNX backend tests:
+//!
+//! ```text
+//! cargo test --test top_k
+//! ```
+//!
+//! Both outputs are bound: the values, of the type of the input, and the INT64 indices.
+
+use ndarray::{arr1, arr2, arr3, Array2};
+use onnx_rustime_lib::testing::OpTest;
+
+fn x() -> Array2<f32> {
+    arr2(&[
+        [0.0, 1.0, 2.0, 3.0],
+        [4.0, 5.0, 6.0, 7.0],
+        [11.0, 10.0, 9.0, 8.0],
+    ])
+}
+
+fn top_k(x: Array2<f32>, k: i64) -> OpTest {
+    OpTest::new("TopK").opset(11).input(x).input(arr1(&[k]))
+}
+
+#[test]
+fn largest() {
+    top_k(x(), 3)
+        .expect(arr2(&[
+            [3.0f32, 2.0, 1.0],
+            [7.0, 6.0, 5.0],
+            [11.0, 10.0, 9.0],
+        ]))
+        .expect(arr2(&[[3i64, 2, 1], [3, 2, 1], [0, 1, 2]]))
+        .check();
+    // k is an attribute in opset 1.
+    OpTest::new("TopK")
+        .opset(1)
+        .input(x())
+        .attribute("k", 1i64)
+        .expect(arr2(&[[3.0f32], [7.0], [11.0]]))
+        .expect(arr2(&[[3i64], [3], [0]]))
+        .check();
+}
+
+#[test]
+fn smallest() {
+    top_k(x(), 2)
+        .attribute("largest", 0i64)
+        .attribute("sorted", 1i64)
+        .expect(arr2(&[[0.0f32, 1.0], [4.0, 5.0], [8.0, 9.0]]))
+        .expect(arr2(&[[0i64, 1], [0, 1], [3, 2]]))
+        .check();
+}
+
+#[test]
+fn axis_other_than_last() {
+    top_k(x(), 2)
+        .attribute("axis", 0i64)
+        .expect(arr2(&[[11.0f32, 10.0, 9.0, 8.0], [4.0, 5.0, 6.0, 7.0]]))
+        .expect(arr2(&[[2i64, 2, 2, 2], [1, 1, 1, 1]]))
+        .check();
+    let x = arr3(&[[[1.0f32, 9.0], [5.0, 3.0], [7.0, 7.0]]]);
+    OpTest::new("TopK")
+        .opset(11)
+        .input(x)
+        .input(arr1(&[2i64]))
+        .attribute("axis", -2i64)
+        .expect(arr3(&[[[7.0f32, 9.0], [5.0, 7.0]]]))
+        .expect(arr3(&[[[2i64, 0], [1, 2]]]))
+        .check();
+}
+
+#[test]
+fn equal_values_keep_the_order_of_their_indices() {
+    let x = arr2(&[[3.0f32, 1.0, 3.0, 2.0, 3.0, 1.0]]);
+    top_k(x.clone(), 4)
+        .expect(arr2(&[[3.0f32, 3.0, 3.0, 2.0]]))
+        .expect(arr2(&[[0i64, 2, 4, 3]]))
+        .check();
+    top_k(x, 3)
+        .attribute("largest", 0i64)
+        .expect(arr2(&[[1.0f32, 1.0, 2.0]]))
+        .expect(arr2(&[[1i64, 5, 3]]))
+        .check();
+}
+
+#[test]
+fn int64_values() {
+    OpTest::new("TopK")
+        .opset(11)
+        .input(arr1(&[5i64, -3, 8, 0]))
+        .input(arr1(&[2i64]))
+        .expect(arr1(&[8i64, 5]))
+        .expect(arr1(&[2i64, 0]))
+        .check();
+}
+
+#[test]
+fn k_larger_than_the_axis() {
+    let error = top_k(x(), 5).expect(x()).expect(x()).run().unwrap_err();
+    assert!(
+        error.contains("between 0 and the length 4 of axis 1"),
+        "{}",
+        error
+    );
+
+    let error = top_k(x(), -1).expect(x()).expect(x()).run().unwrap_err();
+    assert!(error.contains("The k -1 of a TopK"), "{}", error);
+}
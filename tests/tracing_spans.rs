@@ -0,0 +1,202 @@
+//! Checks the spans recorded while loading and running a model:
+//!
+//! ```text
+//! cargo test --features tracing --test tracing_spans
+//! ```
+//!
+//! A small network built in the test is saved to a temporary directory and loaded back, so that
+//! the parser spans are recorded too; MNIST is checked the same way by an ignored test.
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_attribute, make_graph, make_model, make_node, make_opsetid, make_tensor_value_info,
+    Attribute, Dimension,
+};
+use onnx_rustime_lib::backend::parser::OnnxParser;
+use onnx_rustime_lib::backend::session::Session;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+const MODEL: &str = "models/mnist-8/mnist-8.onnx";
+const INPUT: &str = "models/mnist-8/test_data_set_0/input_0.pb";
+
+/// A span, as created.
+#[derive(Debug, Clone)]
+struct SpanRecord {
+    name: &'static str,
+    parent: Option<&'static str>,
+    /// The `op_type` field of the node spans.
+    op_type: Option<String>,
+}
+
+/// Collects every span created.
+#[derive(Clone, Default)]
+struct Collector {
+    spans: Arc<Mutex<Vec<SpanRecord>>>,
+}
+
+struct OpTypeVisitor<'a>(&'a mut Option<String>);
+
+impl Visit for OpTypeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "op_type" {
+            *self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Collector {
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("the span was just created");
+        let mut op_type = None;
+        attributes.record(&mut OpTypeVisitor(&mut op_type));
+
+        self.spans.lock().unwrap().push(SpanRecord {
+            name: span.name(),
+            parent: span.parent().map(|parent| parent.name()),
+            op_type,
+        });
+    }
+}
+
+fn value_info(name: &str, shape: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        TensorProto_DataType::FLOAT,
+        shape.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn ones(name: &str, shape: &[usize]) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(ArrayD::ones(IxDyn(shape)), name).unwrap()
+}
+
+/// `Gemm(Flatten(Relu(Conv(x))))` on a 1x1x6x6 image.
+fn network() -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str, attributes| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            attributes,
+        )
+    };
+    let axis = make_attribute("axis", Attribute::<String>::Int(1));
+    let graph = make_graph(
+        vec![
+            node("Conv", vec!["x", "conv_w", "conv_b"], "conv", vec![]),
+            node("Relu", vec!["conv"], "relu", vec![]),
+            node("Flatten", vec!["relu"], "flat", vec![axis]),
+            node("Gemm", vec!["flat", "gemm_w"], "y", vec![]),
+        ],
+        "network",
+        vec![value_info("x", &[1, 1, 6, 6])],
+        vec![value_info("y", &[1, 10])],
+        vec![
+            ones("conv_w", &[2, 1, 3, 3]),
+            ones("conv_b", &[2]),
+            ones("gemm_w", &[32, 10]),
+        ],
+        None,
+    );
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Loads the model and its input, runs them in a session, and checks the spans recorded.
+fn assert_spans(model: &Path, input: &Path) {
+    let collector = Collector::default();
+    let subscriber = Registry::default().with(collector.clone());
+    let session = tracing::subscriber::with_default(subscriber, || {
+        let session = Session::builder()
+            .model_path(model)
+            .threads(2)
+            .build()
+            .unwrap();
+        let input = OnnxParser::load_data(input.to_string_lossy().into_owned()).unwrap();
+        session.run(input).unwrap();
+        session
+    });
+
+    let spans = collector.spans.lock().unwrap().clone();
+    let count = |name: &str| spans.iter().filter(|span| span.name == name).count();
+    assert_eq!(count("parser.load_model"), 1);
+    assert_eq!(count("parser.load_data"), 1);
+    assert_eq!(count("session.run"), 1);
+
+    // Every node of the optimized graph runs in its own span, in order, under the run.
+    let nodes: Vec<&SpanRecord> = spans.iter().filter(|span| span.name == "node").collect();
+    let graph_nodes = session.model().get_graph().get_node();
+    assert!(!graph_nodes.is_empty());
+    assert_eq!(nodes.len(), graph_nodes.len());
+    for (span, node) in nodes.iter().zip(graph_nodes) {
+        assert_eq!(span.parent, Some("session.run"));
+        assert_eq!(span.op_type.as_deref(), Some(node.get_op_type()));
+    }
+
+    let roots: Vec<&str> = spans
+        .iter()
+        .filter(|span| span.parent.is_none())
+        .map(|span| span.name)
+        .collect();
+    assert_eq!(
+        roots,
+        ["parser.load_model", "parser.load_data", "session.run"]
+    );
+}
+
+#[test]
+fn synthetic_run_has_a_span_per_node() {
+    let dir = std::env::temp_dir().join(format!(
+        "onnx_rustime_tracing_spans_synthetic_{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let (model, input) = (dir.join("network.onnx"), dir.join("input_0.pb"));
+    OnnxParser::save_model(&network(), model.to_string_lossy().into_owned()).unwrap();
+    OnnxParser::save_tensor_proto(
+        &ones("x", &[1, 1, 6, 6]),
+        input.to_string_lossy().into_owned(),
+    )
+    .unwrap();
+
+    assert_spans(&model, &input);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[ignore = "needs the bundled models (git lfs pull)"]
+fn mnist_run_has_a_span_per_node() {
+    common::require_model(MODEL);
+    assert_spans(Path::new(MODEL), Path::new(INPUT));
+}
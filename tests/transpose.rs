@@ -0,0 +1,131 @@
+//! Runs Transpose through the executor:
+//!
+//! ```text
+//! cargo test --test transpose
+//! ```
+//!
+//! The input is an NCHW tensor of shape [1, 2, 2, 3] holding `6 * c + 3 * h + w`, so that every
+//! value tells its position in the input.
+
+use ndarray::{Array, Array4, ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{make_attribute, make_node, Attribute, OnnxError};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::op_test;
+use onnx_rustime_lib::ops::transpose;
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+fn nchw() -> Array4<f32> {
+    Array::range(0.0, 12.0, 1.0)
+        .into_shape((1, 2, 2, 3))
+        .unwrap()
+}
+
+fn values(shape: &[usize], values: &[f32]) -> ArrayD<f32> {
+    ArrayD::from_shape_vec(IxDyn(shape), values.to_vec()).unwrap()
+}
+
+#[test]
+fn default_perm_reverses_the_axes() {
+    op_test!("Transpose",
+             inputs: [nchw()],
+             attrs: {},
+             expect: values(&[3, 2, 2, 1],
+                            &[0.0, 6.0, 3.0, 9.0, 1.0, 7.0, 4.0, 10.0, 2.0, 8.0, 5.0, 11.0]));
+}
+
+#[test]
+fn nchw_to_nhwc_and_back() {
+    let nhwc = values(
+        &[1, 2, 3, 2],
+        &[0.0, 6.0, 1.0, 7.0, 2.0, 8.0, 3.0, 9.0, 4.0, 10.0, 5.0, 11.0],
+    );
+    op_test!("Transpose",
+             inputs: [nchw()],
+             attrs: { perm: vec![0i64, 2, 3, 1] },
+             expect: nhwc.clone());
+    op_test!("Transpose",
+             inputs: [nhwc],
+             attrs: { perm: vec![0i64, 3, 1, 2] },
+             expect: nchw());
+}
+
+#[test]
+fn other_ranks() {
+    op_test!("Transpose",
+             inputs: [values(&[2, 3], &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])],
+             attrs: {},
+             expect: values(&[3, 2], &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]));
+    // A permutation of 5 axes.
+    let x = Array::range(0.0f32, 120.0, 1.0)
+        .into_shape(IxDyn(&[1, 2, 3, 4, 5]))
+        .unwrap();
+    let expected = x.clone().permuted_axes(IxDyn(&[4, 0, 3, 1, 2]));
+    op_test!("Transpose",
+             inputs: [x],
+             attrs: { perm: vec![4i64, 0, 3, 1, 2] },
+             expect: expected);
+}
+
+#[test]
+fn initializer() {
+    // The HWIO weights of a TF convolution, turned into the OIHW ones of ONNX.
+    let hwio = Array::range(0.0f32, 24.0, 1.0)
+        .into_shape(IxDyn(&[2, 2, 3, 2]))
+        .unwrap();
+    let oihw = hwio.clone().permuted_axes(IxDyn(&[3, 2, 0, 1]));
+    OpTest::new("Transpose")
+        .initializer(hwio)
+        .attribute("perm", vec![3i64, 2, 0, 1])
+        .expect(oihw)
+        .check();
+}
+
+#[test]
+fn invalid_perm() {
+    let input = ndarray_to_tensor_proto::<f32>(nchw().into_dyn(), "x").unwrap();
+    for perm in [
+        vec![0i64, 2, 3],
+        vec![0, 1, 1, 2],
+        vec![0, 1, 2, 4],
+        vec![0, -1, 2, 3],
+    ] {
+        let node = make_node(
+            "Transpose",
+            vec!["x"],
+            vec!["y"],
+            None,
+            None,
+            None,
+            vec![make_attribute(
+                "perm",
+                Attribute::<String>::Ints(perm.clone()),
+            )],
+        );
+        match transpose(&[&input], &node) {
+            Err(OnnxError::InvalidAttribute { name, .. }) => assert_eq!(name, "perm"),
+            other => panic!(
+                "perm {:?}: expected an invalid attribute, got {:?}",
+                perm, other
+            ),
+        }
+    }
+
+    // Through the executor, the error is reported with the node.
+    let model = single_node_model(
+        "Transpose",
+        &["x"],
+        &["y"],
+        vec![make_attribute(
+            "perm",
+            Attribute::<String>::Ints(vec![0, 0, 1, 2]),
+        )],
+    );
+    let error = run_with_inputs(&model, vec![input], &RunOptions::default()).unwrap_err();
+    assert!(
+        error.to_string().contains("Invalid attribute 'perm'"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,124 @@
+//! Runs Trilu through the executor:
+//!
+//! ```text
+//! cargo test --test trilu
+//! ```
+//!
+//! The 4x5 cases are the examples of the ONNX backend tests.
+
+use ndarray::{arr2, arr3, Array2, Array3};
+use onnx_rustime_lib::testing::{scalar, OpTest};
+
+fn x() -> Array2<i64> {
+    arr2(&[
+        [4, 7, 3, 7, 9],
+        [1, 2, 8, 6, 9],
+        [9, 4, 0, 8, 7],
+        [4, 3, 4, 2, 4],
+    ])
+}
+
+#[test]
+fn upper() {
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(x())
+        .expect(arr2(&[
+            [4i64, 7, 3, 7, 9],
+            [0, 2, 8, 6, 9],
+            [0, 0, 0, 8, 7],
+            [0, 0, 0, 2, 4],
+        ]))
+        .check();
+}
+
+#[test]
+fn lower() {
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(x())
+        .attribute("upper", 0i64)
+        .expect(arr2(&[
+            [4i64, 0, 0, 0, 0],
+            [1, 2, 0, 0, 0],
+            [9, 4, 0, 0, 0],
+            [4, 3, 4, 2, 0],
+        ]))
+        .check();
+}
+
+#[test]
+fn negative_k() {
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(x())
+        .input(scalar(-1i64))
+        .expect(arr2(&[
+            [4i64, 7, 3, 7, 9],
+            [1, 2, 8, 6, 9],
+            [0, 4, 0, 8, 7],
+            [0, 0, 4, 2, 4],
+        ]))
+        .check();
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(x())
+        .input(scalar(-1i64))
+        .attribute("upper", 0i64)
+        .expect(arr2(&[
+            [0i64, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0],
+            [9, 4, 0, 0, 0],
+            [4, 3, 4, 0, 0],
+        ]))
+        .check();
+}
+
+#[test]
+fn batched() {
+    // Every matrix of the last two axes, with k = 1.
+    let x = Array3::from_shape_fn((2, 3, 3), |(b, i, j)| (b * 9 + i * 3 + j + 1) as f32);
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(x)
+        .input(scalar(1i64))
+        .expect(arr3(&[
+            [[0.0f32, 2.0, 3.0], [0.0, 0.0, 6.0], [0.0, 0.0, 0.0]],
+            [[0.0, 11.0, 12.0], [0.0, 0.0, 15.0], [0.0, 0.0, 0.0]],
+        ]))
+        .check();
+}
+
+#[test]
+fn k_beyond_the_matrix() {
+    // Above the last column the upper part is empty and the lower part full; below the last row,
+    // the opposite.
+    for (k, upper, expected) in [
+        (5i64, 1i64, Array2::zeros((4, 5))),
+        (5, 0, x()),
+        (-4, 1, x()),
+        (-4, 0, Array2::zeros((4, 5))),
+    ] {
+        OpTest::new("Trilu")
+            .opset(14)
+            .input(x())
+            .input(scalar(k))
+            .attribute("upper", upper)
+            .expect(expected)
+            .check();
+    }
+}
+
+#[test]
+fn causal_mask() {
+    OpTest::new("Trilu")
+        .opset(14)
+        .input(Array2::from_elem((3, 3), true))
+        .attribute("upper", 0i64)
+        .expect(arr2(&[
+            [true, false, false],
+            [true, true, false],
+            [true, true, true],
+        ]))
+        .check();
+}
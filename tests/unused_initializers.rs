@@ -0,0 +1,210 @@
+//! Prunes the initializers no node reads, and reports where the bytes of the models go:
+//!
+//! ```text
+//! cargo test --test unused_initializers
+//! ```
+//!
+//! The test model follows the old convention (IR version < 4), declaring its initializers as
+//! graph inputs, and keeps two orphaned initializers from a previous fusion.
+
+use ndarray::{ArrayD, IxDyn};
+use onnx_rustime_lib::backend::helper::{
+    make_graph, make_model, make_node, make_opsetid, make_tensor_value_info, Dimension,
+};
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    ModelProto, TensorProto, TensorProto_DataLocation, TensorProto_DataType, ValueInfoProto,
+};
+use onnx_rustime_lib::ops::utils::ndarray_to_tensor_proto;
+use onnx_rustime_lib::transforms::{model_size_report, prune_unused_initializers};
+use protobuf::Message;
+
+fn value_info(name: &str, data_type: TensorProto_DataType, dims: &[i64]) -> ValueInfoProto {
+    make_tensor_value_info(
+        name,
+        data_type,
+        dims.iter().map(|&dim| Dimension::Value(dim)).collect(),
+        None,
+    )
+}
+
+fn zeros(name: &str, shape: &[usize]) -> TensorProto {
+    ndarray_to_tensor_proto::<f32>(ArrayD::zeros(IxDyn(shape)), name).unwrap()
+}
+
+/// Computes `x * scale + bias`, with the weights of a removed layer still in the graph.
+fn model() -> ModelProto {
+    let node = |op_type: &str, inputs: Vec<&str>, output: &str| {
+        make_node(
+            op_type,
+            inputs,
+            vec![output],
+            Some(output),
+            None,
+            None,
+            vec![],
+        )
+    };
+    let nodes = vec![
+        node("Mul", vec!["x", "scale"], "product"),
+        node("Add", vec!["product", "bias"], "y"),
+    ];
+    let initializers = vec![
+        zeros("scale", &[4]),
+        zeros("bias", &[4]),
+        zeros("fused_weight", &[16, 16]),
+        ndarray_to_tensor_proto::<i64>(ArrayD::from_elem(IxDyn(&[2]), 16), "fused_shape").unwrap(),
+    ];
+    let float = TensorProto_DataType::FLOAT;
+    let inputs = vec![
+        value_info("x", float, &[1, 4]),
+        // Unused, but part of the interface of the model.
+        value_info("mask", float, &[1, 4]),
+        // The old convention.
+        value_info("scale", float, &[4]),
+        value_info("bias", float, &[4]),
+        value_info("fused_weight", float, &[16, 16]),
+    ];
+
+    let mut graph = make_graph(
+        nodes,
+        "affine",
+        inputs,
+        vec![value_info("y", float, &[1, 4])],
+        initializers,
+        None,
+    );
+    graph
+        .mut_value_info()
+        .push(value_info("product", float, &[1, 4]));
+    graph
+        .mut_value_info()
+        .push(value_info("fused", float, &[1, 16]));
+    make_model(
+        graph,
+        vec![make_opsetid(None::<&str>, 13)],
+        None::<&str>,
+        None::<i64>,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    names.collect()
+}
+
+#[test]
+fn orphaned_initializers_are_pruned() {
+    let mut model = model();
+    assert!(prune_unused_initializers(model.mut_graph()).unwrap());
+
+    let graph = model.get_graph();
+    assert_eq!(
+        names(graph.get_initializer().iter().map(|init| init.get_name())),
+        ["scale", "bias"]
+    );
+    // The graph input declaring the removed weight goes with it, the unused mask stays.
+    assert_eq!(
+        names(graph.get_input().iter().map(|input| input.get_name())),
+        ["x", "mask", "scale", "bias"]
+    );
+    assert_eq!(
+        names(graph.get_value_info().iter().map(|info| info.get_name())),
+        ["product"]
+    );
+    assert_eq!(graph.get_node(), self::model().get_graph().get_node());
+
+    // Nothing is left to prune.
+    let pruned = model.clone();
+    assert!(!prune_unused_initializers(model.mut_graph()).unwrap());
+    assert_eq!(model, pruned);
+}
+
+#[test]
+fn initializers_read_as_graph_outputs_are_kept() {
+    let mut model = model();
+    let graph = model.mut_graph();
+    graph
+        .mut_output()
+        .push(value_info("fused_shape", TensorProto_DataType::INT64, &[2]));
+    assert!(prune_unused_initializers(graph).unwrap());
+    assert_eq!(
+        names(graph.get_initializer().iter().map(|init| init.get_name())),
+        ["scale", "bias", "fused_shape"]
+    );
+}
+
+#[test]
+fn size_report() {
+    let mut model = model();
+    let report = model_size_report(&model);
+
+    // From the largest to the smallest, the ties by name.
+    let sizes: Vec<(&str, &str, &[i64], usize)> = report
+        .initializers
+        .iter()
+        .map(|init| {
+            (
+                init.name.as_str(),
+                init.data_type.as_str(),
+                init.shape.as_slice(),
+                init.bytes,
+            )
+        })
+        .collect();
+    assert_eq!(
+        sizes,
+        [
+            ("fused_weight", "FLOAT", &[16, 16][..], 1024),
+            ("bias", "FLOAT", &[4][..], 16),
+            ("fused_shape", "INT64", &[2][..], 16),
+            ("scale", "FLOAT", &[4][..], 16),
+        ]
+    );
+    assert_eq!(report.initializer_bytes, 1072);
+    assert_eq!(report.model_bytes, model.write_to_bytes().unwrap().len());
+
+    // The pruned weights are no longer counted.
+    prune_unused_initializers(model.mut_graph()).unwrap();
+    let report = model_size_report(&model);
+    assert_eq!(report.initializer_bytes, 32);
+    assert_eq!(report.model_bytes, model.write_to_bytes().unwrap().len());
+}
+
+#[test]
+fn size_of_external_and_narrow_initializers() {
+    let mut model = model();
+    let graph = model.mut_graph();
+
+    // The data of an external initializer counts for its full size, though it isn't in the model.
+    let mut external = TensorProto::new();
+    external.set_name("external".to_string());
+    external.set_data_type(TensorProto_DataType::FLOAT as i32);
+    external.set_dims(vec![1000]);
+    external.set_data_location(TensorProto_DataLocation::EXTERNAL);
+    graph.mut_initializer().push(external);
+
+    // UINT8 elements are stored as int32_data, but take a byte each.
+    let mut narrow = TensorProto::new();
+    narrow.set_name("narrow".to_string());
+    narrow.set_data_type(TensorProto_DataType::UINT8 as i32);
+    narrow.set_dims(vec![3]);
+    narrow.set_int32_data(vec![1, 2, 255]);
+    graph.mut_initializer().push(narrow);
+
+    let report = model_size_report(&model);
+    let bytes = |name: &str| {
+        report
+            .initializers
+            .iter()
+            .find(|init| init.name == name)
+            .unwrap()
+            .bytes
+    };
+    assert_eq!(bytes("external"), 4000);
+    assert_eq!(bytes("narrow"), 3);
+    assert_eq!(report.initializers[0].name, "external");
+    assert_eq!(report.initializer_bytes, 1072 + 4000 + 3);
+}
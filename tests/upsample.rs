@@ -0,0 +1,77 @@
+//! Runs Upsample through the executor, with the scales of opset 7 and opset 9:
+//!
+//! ```text
+//! cargo test --test upsample
+//! ```
+
+use ndarray::{arr1, arr2, Array2, Array4, Axis};
+use onnx_rustime_lib::testing::OpTest;
+
+/// An NCHW tensor of one image of one channel.
+fn image(pixels: Array2<f32>) -> Array4<f32> {
+    pixels.insert_axis(Axis(0)).insert_axis(Axis(0))
+}
+
+fn two_by_two() -> Array4<f32> {
+    image(arr2(&[[1.0, 2.0], [3.0, 4.0]]))
+}
+
+fn doubled() -> Array4<f32> {
+    image(arr2(&[
+        [1.0, 1.0, 2.0, 2.0],
+        [1.0, 1.0, 2.0, 2.0],
+        [3.0, 3.0, 4.0, 4.0],
+        [3.0, 3.0, 4.0, 4.0],
+    ]))
+}
+
+#[test]
+fn nearest_2x_upscale() {
+    // The scales are an attribute in opset 7...
+    OpTest::new("Upsample")
+        .opset(7)
+        .input(two_by_two())
+        .attribute("mode", "nearest")
+        .attribute("scales", vec![1.0f32, 1.0, 2.0, 2.0])
+        .expect(doubled())
+        .check();
+    // ...and the second input in opset 9.
+    OpTest::new("Upsample")
+        .opset(9)
+        .input(two_by_two())
+        .input(arr1(&[1.0f32, 1.0, 2.0, 2.0]))
+        .expect(doubled())
+        .check();
+}
+
+#[test]
+fn linear_upscale() {
+    // The asymmetric coordinates, clamped to the last column.
+    OpTest::new("Upsample")
+        .opset(9)
+        .input(image(arr2(&[[1.0, 2.0]])))
+        .input(arr1(&[1.0f32, 1.0, 1.0, 2.0]))
+        .attribute("mode", "linear")
+        .expect(image(arr2(&[[1.0, 1.5, 2.0, 2.0]])))
+        .check();
+}
+
+#[test]
+fn invalid_scales() {
+    let error = OpTest::new("Upsample")
+        .opset(9)
+        .input(two_by_two())
+        .input(arr1(&[1.0f32, 1.0, 0.5, 0.5]))
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("4 values of at least 1"), "{}", error);
+
+    let error = OpTest::new("Upsample")
+        .opset(7)
+        .input(two_by_two())
+        .expect(two_by_two())
+        .run()
+        .unwrap_err();
+    assert!(error.contains("scales"), "{}", error);
+}
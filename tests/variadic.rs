@@ -0,0 +1,112 @@
+//! Runs the variadic operations Max, Mean, Min and Sum through the executor:
+//!
+//! ```text
+//! cargo test --test variadic
+//! ```
+//!
+//! Any number of inputs is broadcast together, as the operands of Add.
+
+use ndarray::{arr1, arr2, arr3, Array1};
+use onnx_rustime_lib::testing::OpTest;
+
+#[test]
+fn single_input() {
+    let x = arr2(&[[1.0f32, -2.0], [3.0, 4.5]]);
+    for op_type in ["Max", "Mean", "Min", "Sum"] {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(x.clone())
+            .expect(x.clone())
+            .check();
+    }
+}
+
+#[test]
+fn two_inputs() {
+    let a = arr2(&[[1.0f32, 5.0, 3.0], [-4.0, 0.0, 2.0]]);
+    let b = arr1(&[2.0f32, 2.0, 6.0]);
+    for (op_type, expected) in [
+        ("Max", [[2.0f32, 5.0, 6.0], [2.0, 2.0, 6.0]]),
+        ("Mean", [[1.5, 3.5, 4.5], [-1.0, 1.0, 4.0]]),
+        ("Min", [[1.0, 2.0, 3.0], [-4.0, 0.0, 2.0]]),
+        ("Sum", [[3.0, 7.0, 9.0], [-2.0, 2.0, 8.0]]),
+    ] {
+        OpTest::new(op_type)
+            .opset(13)
+            .input(a.clone())
+            .input(b.clone())
+            .expect(arr2(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn four_inputs() {
+    // [2, 1, 1], [3, 1], [4] and a scalar broadcast to [2, 3, 4].
+    let expected = Array1::from_iter(
+        (0..24).map(|i| (100 * (i / 12 + 1) + 10 * (i / 4 % 3 + 1) + (i % 4 + 1)) as f32 + 0.5),
+    )
+    .into_shape((2, 3, 4))
+    .unwrap();
+    OpTest::new("Sum")
+        .opset(13)
+        .input(arr3(&[[[100.0f32]], [[200.0]]]))
+        .input(arr2(&[[10.0f32], [20.0], [30.0]]))
+        .input(arr1(&[1.0f32, 2.0, 3.0, 4.0]))
+        .input(arr1(&[0.5f32]).into_shape(()).unwrap())
+        .expect(expected)
+        .check();
+
+    OpTest::new("Mean")
+        .opset(13)
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[3.0f32, 4.0]))
+        .input(arr2(&[[5.0f32], [7.0]]))
+        .input(arr1(&[7.0f32]).into_shape(()).unwrap())
+        .expect(arr2(&[[4.0f32, 4.5], [4.5, 5.0]]))
+        .check();
+}
+
+#[test]
+fn int64_inputs() {
+    OpTest::new("Max")
+        .opset(13)
+        .input(arr1(&[3i64, -1, 8]))
+        .input(arr1(&[5i64]))
+        .input(arr1(&[0i64, 9, 1]))
+        .expect(arr1(&[5i64, 9, 8]))
+        .check();
+    OpTest::new("Min")
+        .opset(13)
+        .input(arr1(&[3i64, -1, 8]))
+        .input(arr1(&[5i64]))
+        .expect(arr1(&[3i64, -1, 5]))
+        .check();
+}
+
+#[test]
+fn nan_wins() {
+    OpTest::new("Max")
+        .opset(13)
+        .input(arr1(&[1.0f32, f32::NAN]))
+        .input(arr1(&[f32::NAN, 2.0]))
+        .expect(arr1(&[f32::NAN, f32::NAN]))
+        .check();
+}
+
+#[test]
+fn incompatible_shapes() {
+    let error = OpTest::new("Sum")
+        .opset(13)
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[1.0f32]))
+        .input(arr1(&[1.0f32, 2.0, 3.0]))
+        .expect(arr1(&[0.0f32; 3]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Shapes [2] and [3] can't be broadcast together"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,113 @@
+//! Runs Where through the executor:
+//!
+//! ```text
+//! cargo test --test where_op
+//! ```
+//!
+//! The condition and the two branches are broadcast together, like the operands of Add.
+
+use ndarray::{arr1, arr2};
+use onnx_rustime_lib::backend::run::run_with_inputs;
+use onnx_rustime_lib::onnx_rustime::onnx_proto::onnx_ml_proto3::{
+    TensorProto, TensorProto_DataType,
+};
+use onnx_rustime_lib::onnx_rustime::shared::RunOptions;
+use onnx_rustime_lib::ops::utils::{ndarray_to_tensor_proto, tensor_proto_to_ndarray};
+use onnx_rustime_lib::testing::{single_node_model, OpTest};
+
+#[test]
+fn same_shapes() {
+    OpTest::new("Where")
+        .opset(16)
+        .input(arr2(&[[true, false], [true, true]]))
+        .input(arr2(&[[1.0f32, 2.0], [3.0, 4.0]]))
+        .input(arr2(&[[9.0f32, 8.0], [7.0, 6.0]]))
+        .expect(arr2(&[[1.0f32, 8.0], [3.0, 4.0]]))
+        .check();
+}
+
+#[test]
+fn scalar_condition() {
+    for (condition, expected) in [(true, [1i64, 2, 3]), (false, [0, 0, 0])] {
+        OpTest::new("Where")
+            .opset(16)
+            .input(arr1(&[condition]).into_shape(()).unwrap())
+            .input(arr1(&[1i64, 2, 3]))
+            .input(arr1(&[0i64]))
+            .expect(arr1(&expected))
+            .check();
+    }
+}
+
+#[test]
+fn broadcast_y() {
+    // Masks the scores with a scalar, the mask being a column stretched over the rows.
+    OpTest::new("Where")
+        .opset(16)
+        .input(arr1(&[true, false, true]))
+        .input(arr2(&[[0.5f32, 0.25, 0.125], [1.0, 2.0, 3.0]]))
+        .input(arr1(&[-1e9f32]).into_shape(()).unwrap())
+        .expect(arr2(&[[0.5f32, -1e9, 0.125], [1.0, -1e9, 3.0]]))
+        .check();
+    // The output can be larger than every input.
+    OpTest::new("Where")
+        .opset(16)
+        .input(arr2(&[[true], [false]]))
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr2(&[[5.0f32], [6.0]]))
+        .expect(arr2(&[[1.0f32, 2.0], [6.0, 6.0]]))
+        .check();
+}
+
+#[test]
+fn mismatched_shapes() {
+    let error = OpTest::new("Where")
+        .opset(16)
+        .input(arr1(&[true, false, true]))
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[0.0f32]))
+        .expect(arr1(&[0.0f32; 3]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("Shapes [[3], [2], [1]] can't be broadcast together"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn raw_bool_condition() {
+    // Exporters store BOOL tensors as one byte per value in raw_data.
+    let mut condition = TensorProto::new();
+    condition.set_name("condition".to_string());
+    condition.set_data_type(TensorProto_DataType::BOOL as i32);
+    condition.set_dims(vec![4]);
+    condition.set_raw_data(vec![1, 0, 0, 1]);
+    let x = ndarray_to_tensor_proto::<f32>(arr1(&[1.0, 2.0, 3.0, 4.0]).into_dyn(), "x").unwrap();
+    let y = ndarray_to_tensor_proto::<f32>(arr1(&[0.0]).into_dyn(), "y").unwrap();
+
+    let model = single_node_model("Where", &["condition", "x", "y"], &["output"], vec![]);
+    let outputs = run_with_inputs(&model, vec![condition, x, y], &RunOptions::default()).unwrap();
+    assert_eq!(
+        tensor_proto_to_ndarray::<f32>(&outputs[0]).unwrap(),
+        arr1(&[1.0f32, 0.0, 0.0, 4.0]).into_dyn()
+    );
+}
+
+#[test]
+fn condition_must_be_bool() {
+    let error = OpTest::new("Where")
+        .opset(16)
+        .input(arr1(&[1i64, 0]))
+        .input(arr1(&[1.0f32, 2.0]))
+        .input(arr1(&[0.0f32, 0.0]))
+        .expect(arr1(&[1.0f32, 0.0]))
+        .run()
+        .unwrap_err();
+    assert!(
+        error.contains("The condition of a Where must be BOOL"),
+        "{}",
+        error
+    );
+}